@@ -211,6 +211,21 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     .unwrap(),
                 cs_dir.join("api").join("relay.proto").to_str().unwrap(),
                 cs_dir.join("api").join("fuota.proto").to_str().unwrap(),
+                cs_dir
+                    .join("api")
+                    .join("roaming_agreement.proto")
+                    .to_str()
+                    .unwrap(),
+                cs_dir
+                    .join("api")
+                    .join("join_server.proto")
+                    .to_str()
+                    .unwrap(),
+                cs_dir
+                    .join("api")
+                    .join("backend_interfaces_log.proto")
+                    .to_str()
+                    .unwrap(),
             ],
             &[
                 proto_dir.join("chirpstack").to_str().unwrap(),
@@ -33,6 +33,8 @@ impl Into<String> for LogCode {
             LogCode::RelayNewEndDevice => "RELAY_NEW_END_DEVICE",
             LogCode::FCntDown => "F_CNT_DOWN",
             LogCode::Expired => "EXPIRED",
+            LogCode::DeviceClassChange => "DEVICE_CLASS_CHANGE",
+            LogCode::Certification => "CERTIFICATION",
         }
         .to_string()
     }
@@ -3,7 +3,7 @@ extern crate anyhow;
 
 use std::fs::File;
 use std::io::Read;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use aes_kw::{KeyInit, KwAes128};
 use anyhow::{Context, Result};
@@ -93,17 +93,10 @@ impl Client {
             .use_rustls_tls(); // this is important as else the client-certificate does not work!
 
         if !c.tls_cert.is_empty() && !c.tls_key.is_empty() {
-            trace!(tls_cert = %c.tls_cert, tls_key = %c.tls_key, "Reading client certificate");
-
-            let mut b: Vec<u8> = Vec::new();
-            File::open(&c.tls_key)
-                .context("Open tls_key")?
-                .read_to_end(&mut b)
-                .context("Read tls_key")?;
-            File::open(&c.tls_cert)
-                .context("Open tls_cert")?
-                .read_to_end(&mut b)
-                .context("Read tls_cert")?;
+            trace!("Reading client certificate");
+
+            let mut b = read_pem(&c.tls_key).context("Read tls_key")?;
+            b.extend(read_pem(&c.tls_cert).context("Read tls_cert")?);
 
             trace!("Parsing client certificate");
             let id = Identity::from_pem(&b).context("Parse tls_cert and tls_key")?;
@@ -115,12 +108,8 @@ impl Client {
         }
 
         if !c.ca_cert.is_empty() {
-            trace!(ca_cert = %c.ca_cert, "Reading CA certificate");
-            let mut b: Vec<u8> = Vec::new();
-            File::open(&c.ca_cert)
-                .context("Open ca_cert")?
-                .read_to_end(&mut b)
-                .context("Read ca_cert")?;
+            trace!("Reading CA certificate");
+            let b = read_pem(&c.ca_cert).context("Read ca_cert")?;
 
             trace!("Parsing CA certificate");
             let cert = Certificate::from_pem(&b).context("Parse ca_cert")?;
@@ -142,6 +131,14 @@ impl Client {
         self.config.sender_id.clone()
     }
 
+    // Sets the configured SenderID, unless the SenderID has already been set by the
+    // caller (e.g. in case of a multi-NetID setup).
+    fn set_sender_id(&self, bp: &mut BasePayload) {
+        if bp.sender_id.is_empty() {
+            bp.sender_id.clone_from(&self.config.sender_id);
+        }
+    }
+
     pub fn get_receiver_id(&self) -> Vec<u8> {
         self.config.receiver_id.clone()
     }
@@ -160,7 +157,7 @@ impl Client {
         pl: &mut JoinReqPayload,
         async_resp: Option<Receiver<Vec<u8>>>,
     ) -> Result<JoinAnsPayload> {
-        pl.base.sender_id.clone_from(&self.config.sender_id);
+        self.set_sender_id(&mut pl.base);
         pl.base.receiver_id = receiver_id;
         pl.base.message_type = MessageType::JoinReq;
 
@@ -169,12 +166,16 @@ impl Client {
         Ok(ans)
     }
 
+    pub async fn join_ans(&self, pl: &JoinAnsPayload) -> Result<()> {
+        self.response_request(None, pl).await
+    }
+
     pub async fn rejoin_req(
         &self,
         pl: &mut RejoinReqPayload,
         async_resp: Option<Receiver<Vec<u8>>>,
     ) -> Result<RejoinAnsPayload> {
-        pl.base.sender_id.clone_from(&self.config.sender_id);
+        self.set_sender_id(&mut pl.base);
         pl.base.receiver_id.clone_from(&self.config.receiver_id);
         pl.base.message_type = MessageType::RejoinReq;
 
@@ -183,12 +184,16 @@ impl Client {
         Ok(ans)
     }
 
+    pub async fn rejoin_ans(&self, pl: &RejoinAnsPayload) -> Result<()> {
+        self.response_request(None, pl).await
+    }
+
     pub async fn app_s_key_req(
         &self,
         pl: &mut AppSKeyReqPayload,
         async_resp: Option<Receiver<Vec<u8>>>,
     ) -> Result<AppSKeyAnsPayload> {
-        pl.base.sender_id.clone_from(&self.config.sender_id);
+        self.set_sender_id(&mut pl.base);
         pl.base.receiver_id.clone_from(&self.config.receiver_id);
         pl.base.message_type = MessageType::AppSKeyReq;
 
@@ -197,13 +202,17 @@ impl Client {
         Ok(ans)
     }
 
+    pub async fn app_s_key_ans(&self, pl: &AppSKeyAnsPayload) -> Result<()> {
+        self.response_request(None, pl).await
+    }
+
     pub async fn pr_start_req(
         &self,
         target_role: Role,
         pl: &mut PRStartReqPayload,
         async_resp: Option<Receiver<Vec<u8>>>,
     ) -> Result<PRStartAnsPayload> {
-        pl.base.sender_id.clone_from(&self.config.sender_id);
+        self.set_sender_id(&mut pl.base);
         pl.base.receiver_id.clone_from(&self.config.receiver_id);
         pl.base.message_type = MessageType::PRStartReq;
 
@@ -223,7 +232,7 @@ impl Client {
         pl: &mut PRStopReqPayload,
         async_resp: Option<Receiver<Vec<u8>>>,
     ) -> Result<PRStopAnsPayload> {
-        pl.base.sender_id.clone_from(&self.config.sender_id);
+        self.set_sender_id(&mut pl.base);
         pl.base.receiver_id.clone_from(&self.config.receiver_id);
         pl.base.message_type = MessageType::PRStopReq;
 
@@ -243,7 +252,7 @@ impl Client {
         pl: &mut HomeNSReqPayload,
         async_resp: Option<Receiver<Vec<u8>>>,
     ) -> Result<HomeNSAnsPayload> {
-        pl.base.sender_id.clone_from(&self.config.sender_id);
+        self.set_sender_id(&mut pl.base);
         pl.base.receiver_id = receiver_id;
         pl.base.message_type = MessageType::HomeNSReq;
 
@@ -256,13 +265,73 @@ impl Client {
         self.response_request(Some(target_role), pl).await
     }
 
+    pub async fn profile_req(
+        &self,
+        target_role: Role,
+        pl: &mut ProfileReqPayload,
+        async_resp: Option<Receiver<Vec<u8>>>,
+    ) -> Result<ProfileAnsPayload> {
+        self.set_sender_id(&mut pl.base);
+        pl.base.receiver_id.clone_from(&self.config.receiver_id);
+        pl.base.message_type = MessageType::ProfileReq;
+
+        let mut ans: ProfileAnsPayload = Default::default();
+        self.request(Some(target_role), &pl, &mut ans, async_resp)
+            .await?;
+        Ok(ans)
+    }
+
+    pub async fn profile_ans(&self, target_role: Role, pl: &ProfileAnsPayload) -> Result<()> {
+        self.response_request(Some(target_role), pl).await
+    }
+
+    pub async fn hr_start_req(
+        &self,
+        target_role: Role,
+        pl: &mut HRStartReqPayload,
+        async_resp: Option<Receiver<Vec<u8>>>,
+    ) -> Result<HRStartAnsPayload> {
+        self.set_sender_id(&mut pl.base);
+        pl.base.receiver_id.clone_from(&self.config.receiver_id);
+        pl.base.message_type = MessageType::HRStartReq;
+
+        let mut ans: HRStartAnsPayload = Default::default();
+        self.request(Some(target_role), &pl, &mut ans, async_resp)
+            .await?;
+        Ok(ans)
+    }
+
+    pub async fn hr_start_ans(&self, target_role: Role, pl: &HRStartAnsPayload) -> Result<()> {
+        self.response_request(Some(target_role), pl).await
+    }
+
+    pub async fn hr_stop_req(
+        &self,
+        target_role: Role,
+        pl: &mut HRStopReqPayload,
+        async_resp: Option<Receiver<Vec<u8>>>,
+    ) -> Result<HRStopAnsPayload> {
+        self.set_sender_id(&mut pl.base);
+        pl.base.receiver_id.clone_from(&self.config.receiver_id);
+        pl.base.message_type = MessageType::HRStopReq;
+
+        let mut ans: HRStopAnsPayload = Default::default();
+        self.request(Some(target_role), &pl, &mut ans, async_resp)
+            .await?;
+        Ok(ans)
+    }
+
+    pub async fn hr_stop_ans(&self, target_role: Role, pl: &HRStopAnsPayload) -> Result<()> {
+        self.response_request(Some(target_role), pl).await
+    }
+
     pub async fn xmit_data_req(
         &self,
         target_role: Role,
         pl: &mut XmitDataReqPayload,
         async_resp: Option<Receiver<Vec<u8>>>,
     ) -> Result<XmitDataAnsPayload> {
-        pl.base.sender_id.clone_from(&self.config.sender_id);
+        self.set_sender_id(&mut pl.base);
         pl.base.receiver_id.clone_from(&self.config.receiver_id);
         pl.base.message_type = MessageType::XmitDataReq;
 
@@ -326,15 +395,18 @@ impl Client {
             receiver_id: hex::encode(&bp.receiver_id),
             transaction_id: bp.transaction_id,
             message_type: format!("{:?}", bp.message_type),
+            target_role: target_role.map(|v| format!("{:?}", v)).unwrap_or_default(),
             ..Default::default()
         };
 
         let span = span!(Level::INFO, "request", message_type = ?bp.message_type, sender_id = %be_req_log.sender_id, receiver_id = %be_req_log.receiver_id, transaction_id = bp.transaction_id);
 
+        let start = Instant::now();
         let res = self
             ._request(target_role, pl, ans, async_resp, &mut be_req_log)
             .instrument(span)
             .await;
+        be_req_log.latency = start.elapsed().as_millis() as u32;
 
         if let Err(e) = &res {
             be_req_log.request_error = format!("{:#}", e);
@@ -427,6 +499,21 @@ impl Client {
     }
 }
 
+// Returns the PEM encoded content. The given value can either be the PEM content
+// itself or the path to the PEM file.
+fn read_pem(v: &str) -> Result<Vec<u8>> {
+    if v.trim_start().starts_with("-----BEGIN") {
+        return Ok(v.as_bytes().to_vec());
+    }
+
+    let mut b: Vec<u8> = Vec::new();
+    File::open(v)
+        .context("Open file")?
+        .read_to_end(&mut b)
+        .context("Read file")?;
+    Ok(b)
+}
+
 #[derive(Default, Serialize, Deserialize, PartialEq, Eq, Debug, Copy, Clone)]
 pub enum MessageType {
     #[default]
@@ -444,6 +531,12 @@ pub enum MessageType {
     HomeNSAns,
     XmitDataReq,
     XmitDataAns,
+    ProfileReq,
+    ProfileAns,
+    HRStartReq,
+    HRStartAns,
+    HRStopReq,
+    HRStopAns,
 }
 
 #[derive(Default, Serialize, Deserialize, PartialEq, Eq, Debug, Copy, Clone)]
@@ -477,6 +570,12 @@ pub enum RatePolicy {
     Mark,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Copy, Clone)]
+pub enum RoamingActivationType {
+    Passive,
+    Handover,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 #[serde(default)]
 pub struct BasePayload {
@@ -523,6 +622,12 @@ impl BasePayload {
                     MessageType::PRStopReq => MessageType::PRStopAns,
                     MessageType::XmitDataReq => MessageType::XmitDataAns,
                     MessageType::HomeNSReq => MessageType::HomeNSAns,
+                    MessageType::JoinReq => MessageType::JoinAns,
+                    MessageType::RejoinReq => MessageType::RejoinAns,
+                    MessageType::AppSKeyReq => MessageType::AppSKeyAns,
+                    MessageType::ProfileReq => MessageType::ProfileAns,
+                    MessageType::HRStartReq => MessageType::HRStartAns,
+                    MessageType::HRStopReq => MessageType::HRStopAns,
                     _ => self.message_type,
                 },
                 sender_token: self.receiver_token.clone(),
@@ -543,7 +648,10 @@ impl BasePayload {
             | MessageType::PRStartAns
             | MessageType::PRStopAns
             | MessageType::HomeNSAns
-            | MessageType::XmitDataAns => true,
+            | MessageType::XmitDataAns
+            | MessageType::ProfileAns
+            | MessageType::HRStartAns
+            | MessageType::HRStopAns => true,
 
             MessageType::JoinReq
             | MessageType::RejoinReq
@@ -551,7 +659,10 @@ impl BasePayload {
             | MessageType::PRStartReq
             | MessageType::PRStopReq
             | MessageType::HomeNSReq
-            | MessageType::XmitDataReq => false,
+            | MessageType::XmitDataReq
+            | MessageType::ProfileReq
+            | MessageType::HRStartReq
+            | MessageType::HRStopReq => false,
         }
     }
 }
@@ -962,6 +1073,145 @@ impl BasePayloadResultProvider for HomeNSAnsPayload {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+pub struct ProfileReqPayload {
+    #[serde(flatten)]
+    pub base: BasePayload,
+    #[serde(rename = "DevEUI", with = "hex_encode")]
+    pub dev_eui: Vec<u8>,
+}
+
+impl BasePayloadProvider for &mut ProfileReqPayload {
+    fn base_payload(&self) -> &BasePayload {
+        &self.base
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+pub struct ProfileAnsPayload {
+    #[serde(flatten)]
+    pub base: BasePayloadResult,
+    #[serde(rename = "DeviceProfile", skip_serializing_if = "Option::is_none")]
+    pub device_profile: Option<DeviceProfile>,
+    #[serde(
+        rename = "DeviceProfileTimestamp",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub device_profile_timestamp: Option<DateTime<Utc>>,
+    #[serde(
+        rename = "RoamingActivationType",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub roaming_activation_type: Option<RoamingActivationType>,
+}
+
+impl BasePayloadResultProvider for ProfileAnsPayload {
+    fn base_payload(&self) -> &BasePayloadResult {
+        &self.base
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+pub struct HRStartReqPayload {
+    #[serde(flatten)]
+    pub base: BasePayload,
+    #[serde(rename = "PHYPayload", with = "hex_encode")]
+    pub phy_payload: Vec<u8>,
+    #[serde(rename = "ULMetaData")]
+    pub ul_meta_data: ULMetaData,
+    #[serde(
+        rename = "DeviceProfileTimestamp",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub device_profile_timestamp: Option<DateTime<Utc>>,
+}
+
+impl BasePayloadProvider for &mut HRStartReqPayload {
+    fn base_payload(&self) -> &BasePayload {
+        &self.base
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+pub struct HRStartAnsPayload {
+    #[serde(flatten)]
+    pub base: BasePayloadResult,
+    #[serde(
+        default,
+        rename = "PHYPayload",
+        with = "hex_encode",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub phy_payload: Vec<u8>,
+    #[serde(
+        default,
+        rename = "DevEUI",
+        with = "hex_encode",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub dev_eui: Vec<u8>,
+    #[serde(
+        default,
+        rename = "DevAddr",
+        with = "hex_encode",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub dev_addr: Vec<u8>,
+    #[serde(rename = "Lifetime", skip_serializing_if = "Option::is_none")]
+    pub lifetime: Option<usize>,
+    #[serde(rename = "SNwkSIntKey", skip_serializing_if = "Option::is_none")]
+    pub s_nwk_s_int_key: Option<KeyEnvelope>,
+    #[serde(rename = "FNwkSIntKey", skip_serializing_if = "Option::is_none")]
+    pub f_nwk_s_int_key: Option<KeyEnvelope>,
+    #[serde(rename = "NwkSEncKey", skip_serializing_if = "Option::is_none")]
+    pub nwk_s_enc_key: Option<KeyEnvelope>,
+    #[serde(rename = "NwkSKey", skip_serializing_if = "Option::is_none")]
+    pub nwk_s_key: Option<KeyEnvelope>,
+    #[serde(rename = "DeviceProfile", skip_serializing_if = "Option::is_none")]
+    pub device_profile: Option<DeviceProfile>,
+    #[serde(
+        rename = "DeviceProfileTimestamp",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub device_profile_timestamp: Option<DateTime<Utc>>,
+    #[serde(rename = "ServiceProfile", skip_serializing_if = "Option::is_none")]
+    pub service_profile: Option<ServiceProfile>,
+    #[serde(rename = "DLMetaData", skip_serializing_if = "Option::is_none")]
+    pub dl_meta_data: Option<DLMetaData>,
+}
+
+impl BasePayloadResultProvider for HRStartAnsPayload {
+    fn base_payload(&self) -> &BasePayloadResult {
+        &self.base
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+pub struct HRStopReqPayload {
+    #[serde(flatten)]
+    pub base: BasePayload,
+    #[serde(rename = "DevEUI", with = "hex_encode")]
+    pub dev_eui: Vec<u8>,
+}
+
+impl BasePayloadProvider for &mut HRStopReqPayload {
+    fn base_payload(&self) -> &BasePayload {
+        &self.base
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+pub struct HRStopAnsPayload {
+    #[serde(flatten)]
+    pub base: BasePayloadResult,
+}
+
+impl BasePayloadResultProvider for HRStopAnsPayload {
+    fn base_payload(&self) -> &BasePayloadResult {
+        &self.base
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct ULMetaData {
     #[serde(
@@ -1119,6 +1369,51 @@ pub struct ServiceProfile {
     pub min_gw_diversity: usize,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[serde(default)]
+pub struct DeviceProfile {
+    #[serde(rename = "DeviceProfileID")]
+    pub device_profile_id: String,
+    #[serde(rename = "SupportsClassB")]
+    pub supports_class_b: bool,
+    #[serde(rename = "ClassBTimeout")]
+    pub class_b_timeout: usize,
+    #[serde(rename = "PingSlotPeriod")]
+    pub ping_slot_period: usize,
+    #[serde(rename = "PingSlotDR")]
+    pub ping_slot_dr: usize,
+    #[serde(rename = "PingSlotFreq")]
+    pub ping_slot_freq: f64,
+    #[serde(rename = "SupportsClassC")]
+    pub supports_class_c: bool,
+    #[serde(rename = "ClassCTimeout")]
+    pub class_c_timeout: usize,
+    #[serde(rename = "MACVersion")]
+    pub mac_version: String,
+    #[serde(rename = "RegParamsRevision")]
+    pub reg_params_revision: String,
+    #[serde(rename = "RXDelay1")]
+    pub rx_delay_1: usize,
+    #[serde(rename = "RXDROffset1")]
+    pub rx_dr_offset_1: usize,
+    #[serde(rename = "RXDataRate2")]
+    pub rx_data_rate_2: usize,
+    #[serde(rename = "RXFreq2")]
+    pub rx_freq_2: f64,
+    #[serde(rename = "FactoryPresetFreqs")]
+    pub factory_preset_freqs: Vec<f64>,
+    #[serde(rename = "MaxEIRP")]
+    pub max_eirp: usize,
+    #[serde(rename = "MaxDutyCycle")]
+    pub max_duty_cycle: f64,
+    #[serde(rename = "SupportsJoin")]
+    pub supports_join: bool,
+    #[serde(rename = "RFRegion")]
+    pub rf_region: String,
+    #[serde(rename = "Supports32bitFCnt")]
+    pub supports_32bit_f_cnt: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
 pub struct DLMetaData {
     #[serde(
@@ -1232,6 +1527,48 @@ pub mod test {
         assert_eq!(key, ke.unwrap(&kek).unwrap());
     }
 
+    #[test]
+    fn test_read_pem() {
+        let pem = "-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----\n";
+        assert_eq!(pem.as_bytes().to_vec(), read_pem(pem).unwrap());
+        assert!(read_pem("/does/not/exist.pem").is_err());
+    }
+
+    #[test]
+    fn test_profile_ans() {
+        let ans = ProfileAnsPayload {
+            base: BasePayloadResult {
+                base: BasePayload {
+                    sender_id: vec![1, 2, 3],
+                    receiver_id: vec![3, 2, 1],
+                    message_type: MessageType::ProfileAns,
+                    transaction_id: 1234,
+                    ..Default::default()
+                },
+                result: ResultPayload {
+                    result_code: ResultCode::Success,
+                    description: "".into(),
+                },
+            },
+            device_profile: Some(DeviceProfile {
+                mac_version: "1.0.3".into(),
+                supports_join: true,
+                rf_region: "EU868".into(),
+                ..Default::default()
+            }),
+            device_profile_timestamp: None,
+            roaming_activation_type: Some(RoamingActivationType::Handover),
+        };
+
+        let b = serde_json::to_string(&ans).unwrap();
+        assert!(b.contains(r#""MessageType":"ProfileAns""#));
+        assert!(b.contains(r#""RoamingActivationType":"Handover""#));
+        assert!(b.contains(r#""MACVersion":"1.0.3""#));
+
+        let ans_decoded: ProfileAnsPayload = serde_json::from_str(&b).unwrap();
+        assert_eq!(ans, ans_decoded);
+    }
+
     #[tokio::test]
     async fn test_async_request() {
         let server = MockServer::start();
@@ -1431,6 +1768,23 @@ pub mod test {
         mock.assert();
         mock.delete();
         assert!(resp.is_err());
+
+        // SenderID set by caller
+        req.base.sender_id = vec![3, 2, 1];
+        let mut mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .body(serde_json::to_string(&req).unwrap());
+            then.body(serde_json::to_vec(&ans).unwrap()).status(200);
+        });
+        let resp = c
+            .home_ns_req(vec![1, 2, 3, 4, 5, 6, 7, 8], &mut req, None)
+            .await
+            .unwrap();
+        mock.assert();
+        mock.delete();
+        assert_eq!(resp, ans);
+        assert_eq!(vec![3, 2, 1], req.base.sender_id);
     }
 
     #[tokio::test]
@@ -1492,6 +1846,7 @@ pub mod test {
         assert_eq!("010203", be_req_log.sender_id);
         assert_eq!("0102030405060708", be_req_log.receiver_id);
         assert_eq!(1234, be_req_log.transaction_id);
+        assert!(be_req_log.target_role.is_empty());
         assert!(be_req_log.request_error.is_empty());
     }
 
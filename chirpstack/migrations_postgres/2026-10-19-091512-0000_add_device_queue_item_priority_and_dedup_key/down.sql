@@ -0,0 +1,5 @@
+drop index idx_device_queue_item_dedup_key;
+
+alter table device_queue_item
+  drop column dedup_key,
+  drop column priority;
@@ -0,0 +1,8 @@
+alter table device_queue_item
+  add column priority smallint not null default 0,
+  add column dedup_key varchar(100) null;
+
+alter table device_queue_item
+  alter column priority drop default;
+
+create index idx_device_queue_item_dedup_key on device_queue_item (dev_eui, dedup_key);
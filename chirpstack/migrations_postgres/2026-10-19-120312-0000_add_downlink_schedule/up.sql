@@ -0,0 +1,23 @@
+alter table device_queue_item
+  add column not_before timestamp with time zone null;
+
+create table downlink_schedule (
+  id uuid primary key,
+  created_at timestamp with time zone not null,
+  updated_at timestamp with time zone not null,
+  application_id uuid not null references application on delete cascade,
+  name varchar(100) not null,
+  dev_eui bytea null references device on delete cascade,
+  multicast_group_id uuid null references multicast_group on delete cascade,
+  tags jsonb not null,
+  cron_expression varchar(100) not null,
+  f_port smallint not null,
+  confirmed boolean not null,
+  data bytea not null,
+  is_disabled boolean not null,
+  last_run_at timestamp with time zone null,
+  next_run_at timestamp with time zone not null
+);
+
+create index idx_downlink_schedule_application_id on downlink_schedule (application_id);
+create index idx_downlink_schedule_next_run_at on downlink_schedule (next_run_at);
@@ -0,0 +1,3 @@
+alter table fuota_deployment_device
+  drop column reboot_completed_at,
+  drop column upgrade_image_completed_at;
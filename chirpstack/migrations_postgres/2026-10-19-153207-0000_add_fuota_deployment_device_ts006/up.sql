@@ -0,0 +1,3 @@
+alter table fuota_deployment_device
+  add column upgrade_image_completed_at timestamp with time zone null,
+  add column reboot_completed_at timestamp with time zone null;
@@ -0,0 +1,5 @@
+alter table device_profile
+  add column allow_handover_roaming boolean not null default false;
+
+alter table device_profile
+  alter column allow_handover_roaming drop default;
@@ -0,0 +1,7 @@
+create table data_encryption_key (
+    id integer not null primary key,
+    created_at timestamp with time zone not null,
+    kek_label varchar(100) not null,
+    wrapped_key bytea not null,
+    is_active boolean not null
+);
@@ -0,0 +1 @@
+drop table backend_interfaces_log;
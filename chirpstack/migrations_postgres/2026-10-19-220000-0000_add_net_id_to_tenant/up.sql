@@ -0,0 +1,2 @@
+alter table tenant
+  add column net_id bytea null;
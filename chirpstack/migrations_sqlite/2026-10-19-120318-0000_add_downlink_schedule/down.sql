@@ -0,0 +1,5 @@
+drop index idx_downlink_schedule_next_run_at;
+drop index idx_downlink_schedule_application_id;
+drop table downlink_schedule;
+
+alter table device_queue_item drop column not_before;
@@ -0,0 +1,22 @@
+alter table device_queue_item add column not_before datetime null;
+
+create table downlink_schedule (
+    id text not null primary key,
+    created_at datetime not null,
+    updated_at datetime not null,
+    application_id text not null references application on delete cascade,
+    name varchar(100) not null,
+    dev_eui blob null references device on delete cascade,
+    multicast_group_id text null references multicast_group on delete cascade,
+    tags text not null,
+    cron_expression varchar(100) not null,
+    f_port smallint not null,
+    confirmed boolean not null,
+    data blob not null,
+    is_disabled boolean not null,
+    last_run_at datetime null,
+    next_run_at datetime not null
+);
+
+create index idx_downlink_schedule_application_id on downlink_schedule (application_id);
+create index idx_downlink_schedule_next_run_at on downlink_schedule (next_run_at);
@@ -0,0 +1,5 @@
+alter table fuota_deployment_device
+  drop column reboot_completed_at;
+
+alter table fuota_deployment_device
+  drop column upgrade_image_completed_at;
@@ -0,0 +1,5 @@
+alter table fuota_deployment_device
+  add column upgrade_image_completed_at datetime null;
+
+alter table fuota_deployment_device
+  add column reboot_completed_at datetime null;
@@ -0,0 +1,2 @@
+alter table device_profile
+  add column allow_handover_roaming boolean not null default FALSE;
@@ -0,0 +1,5 @@
+drop index idx_join_server_name;
+drop table join_server;
+
+drop index idx_roaming_agreement_name;
+drop table roaming_agreement;
@@ -0,0 +1,39 @@
+create table roaming_agreement (
+    net_id blob not null primary key,
+    created_at datetime not null,
+    updated_at datetime not null,
+    name varchar(100) not null,
+    secondary_net_ids text not null,
+    server varchar(255) not null,
+    use_target_role_suffix boolean not null,
+    async_timeout integer not null,
+    passive_roaming_lifetime integer not null,
+    passive_roaming_kek_label varchar(100) not null,
+    passive_roaming_validate_mic boolean not null,
+    handover_roaming boolean not null,
+    handover_roaming_lifetime integer not null,
+    handover_roaming_kek_label varchar(100) not null,
+    ca_cert text not null,
+    tls_cert text not null,
+    tls_key text not null,
+    authorization_header text not null,
+    allowed_device_profile_ids text not null
+);
+
+create index idx_roaming_agreement_name on roaming_agreement (name);
+
+create table join_server (
+    id text not null primary key,
+    created_at datetime not null,
+    updated_at datetime not null,
+    name varchar(100) not null,
+    join_eui_prefix varchar(20) not null,
+    server varchar(255) not null,
+    async_timeout integer not null,
+    ca_cert text not null,
+    tls_cert text not null,
+    tls_key text not null,
+    authorization_header text not null
+);
+
+create index idx_join_server_name on join_server (name);
@@ -0,0 +1,7 @@
+create table data_encryption_key (
+    id integer not null primary key,
+    created_at datetime not null,
+    kek_label varchar(100) not null,
+    wrapped_key blob not null,
+    is_active boolean not null
+);
@@ -0,0 +1,20 @@
+create table backend_interfaces_log (
+    id text not null primary key,
+    created_at datetime not null,
+    sender_id varchar(32) not null,
+    receiver_id varchar(32) not null,
+    transaction_id bigint not null,
+    message_type varchar(50) not null,
+    result_code varchar(50) not null,
+    target_role varchar(10) not null,
+    latency integer not null,
+    dev_eui blob null,
+    request_body text not null,
+    request_error text not null,
+    response_body text not null
+);
+
+create index idx_backend_interfaces_log_created_at on backend_interfaces_log (created_at);
+create index idx_backend_interfaces_log_sender_id on backend_interfaces_log (sender_id);
+create index idx_backend_interfaces_log_receiver_id on backend_interfaces_log (receiver_id);
+create index idx_backend_interfaces_log_dev_eui on backend_interfaces_log (dev_eui);
@@ -0,0 +1,12 @@
+create table roaming_settlement (
+    net_id blob not null,
+    day date not null,
+    role varchar(3) not null,
+    direction varchar(10) not null,
+    frame_count bigint not null,
+    phy_payload_bytes bigint not null,
+    gw_count bigint not null,
+    primary key (net_id, day, role, direction)
+);
+
+create index idx_roaming_settlement_day on roaming_settlement (day);
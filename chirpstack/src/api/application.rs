@@ -1,8 +1,10 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 
 use chirpstack_api::api;
 use chirpstack_api::api::application_service_server::ApplicationService;
 use chirpstack_api::tonic::{self, Request, Response, Status};
+use tracing::info;
 use uuid::Uuid;
 
 use super::auth::validator;
@@ -10,7 +12,11 @@ use super::error::ToStatus;
 use super::helpers;
 use crate::api::auth::AuthID;
 use crate::certificate;
-use crate::storage::{application, fields};
+use crate::codec;
+use crate::storage::{
+    application, device, device_profile, device_queue, downlink_schedule, fields,
+};
+use lrwn::EUI64;
 
 pub struct Application {
     validator: validator::RequestValidator,
@@ -1889,6 +1895,443 @@ impl ApplicationService for Application {
 
         Ok(resp)
     }
+
+    async fn create_downlink_schedule(
+        &self,
+        request: Request<api::CreateDownlinkScheduleRequest>,
+    ) -> Result<Response<api::CreateDownlinkScheduleResponse>, Status> {
+        let req_ds = match &request.get_ref().downlink_schedule {
+            Some(v) => v,
+            None => {
+                return Err(Status::invalid_argument("downlink_schedule is missing"));
+            }
+        };
+        let app_id = Uuid::from_str(&req_ds.application_id).map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateApplicationAccess::new(validator::Flag::Update, app_id),
+            )
+            .await?;
+
+        let ds = downlink_schedule::create(downlink_schedule::DownlinkSchedule {
+            application_id: app_id.into(),
+            ..downlink_schedule_from_proto(req_ds)?
+        })
+        .await
+        .map_err(|e| e.status())?;
+
+        let mut resp = Response::new(api::CreateDownlinkScheduleResponse {
+            id: ds.id.to_string(),
+        });
+        resp.metadata_mut().insert(
+            "x-log-application_id",
+            req_ds.application_id.parse().unwrap(),
+        );
+        resp.metadata_mut().insert(
+            "x-log-downlink_schedule_id",
+            ds.id.to_string().parse().unwrap(),
+        );
+
+        Ok(resp)
+    }
+
+    async fn get_downlink_schedule(
+        &self,
+        request: Request<api::GetDownlinkScheduleRequest>,
+    ) -> Result<Response<api::GetDownlinkScheduleResponse>, Status> {
+        let req = request.get_ref();
+        let id = Uuid::from_str(&req.id).map_err(|e| e.status())?;
+        let ds = downlink_schedule::get(&id).await.map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateApplicationAccess::new(
+                    validator::Flag::Read,
+                    ds.application_id.into(),
+                ),
+            )
+            .await?;
+
+        let mut resp = Response::new(api::GetDownlinkScheduleResponse {
+            downlink_schedule: Some(api::DownlinkSchedule {
+                id: ds.id.to_string(),
+                application_id: ds.application_id.to_string(),
+                name: ds.name.clone(),
+                dev_eui: ds.dev_eui.map(|v| v.to_string()).unwrap_or_default(),
+                multicast_group_id: ds
+                    .multicast_group_id
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+                tags: ds.tags.into_hashmap(),
+                cron_expression: ds.cron_expression.clone(),
+                f_port: ds.f_port as u32,
+                confirmed: ds.confirmed,
+                data: ds.data.clone(),
+                is_disabled: ds.is_disabled,
+            }),
+            created_at: Some(helpers::datetime_to_prost_timestamp(&ds.created_at)),
+            updated_at: Some(helpers::datetime_to_prost_timestamp(&ds.updated_at)),
+            last_run_at: ds
+                .last_run_at
+                .as_ref()
+                .map(helpers::datetime_to_prost_timestamp),
+            next_run_at: Some(helpers::datetime_to_prost_timestamp(&ds.next_run_at)),
+        });
+        resp.metadata_mut().insert(
+            "x-log-application_id",
+            ds.application_id.to_string().parse().unwrap(),
+        );
+        resp.metadata_mut()
+            .insert("x-log-downlink_schedule_id", req.id.parse().unwrap());
+
+        Ok(resp)
+    }
+
+    async fn update_downlink_schedule(
+        &self,
+        request: Request<api::UpdateDownlinkScheduleRequest>,
+    ) -> Result<Response<()>, Status> {
+        let req_ds = match &request.get_ref().downlink_schedule {
+            Some(v) => v,
+            None => {
+                return Err(Status::invalid_argument("downlink_schedule is missing"));
+            }
+        };
+        let id = Uuid::from_str(&req_ds.id).map_err(|e| e.status())?;
+        let ds = downlink_schedule::get(&id).await.map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateApplicationAccess::new(
+                    validator::Flag::Update,
+                    ds.application_id.into(),
+                ),
+            )
+            .await?;
+
+        // The application_id can not be changed.
+        let _ = downlink_schedule::update(downlink_schedule::DownlinkSchedule {
+            id: ds.id,
+            created_at: ds.created_at,
+            application_id: ds.application_id,
+            last_run_at: ds.last_run_at,
+            ..downlink_schedule_from_proto(req_ds)?
+        })
+        .await
+        .map_err(|e| e.status())?;
+
+        let mut resp = Response::new(());
+        resp.metadata_mut().insert(
+            "x-log-application_id",
+            ds.application_id.to_string().parse().unwrap(),
+        );
+        resp.metadata_mut()
+            .insert("x-log-downlink_schedule_id", req_ds.id.parse().unwrap());
+
+        Ok(resp)
+    }
+
+    async fn delete_downlink_schedule(
+        &self,
+        request: Request<api::DeleteDownlinkScheduleRequest>,
+    ) -> Result<Response<()>, Status> {
+        let req = request.get_ref();
+        let id = Uuid::from_str(&req.id).map_err(|e| e.status())?;
+        let ds = downlink_schedule::get(&id).await.map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateApplicationAccess::new(
+                    validator::Flag::Update,
+                    ds.application_id.into(),
+                ),
+            )
+            .await?;
+
+        downlink_schedule::delete(&id)
+            .await
+            .map_err(|e| e.status())?;
+
+        let mut resp = Response::new(());
+        resp.metadata_mut().insert(
+            "x-log-application_id",
+            ds.application_id.to_string().parse().unwrap(),
+        );
+        resp.metadata_mut()
+            .insert("x-log-downlink_schedule_id", req.id.parse().unwrap());
+
+        Ok(resp)
+    }
+
+    async fn list_downlink_schedules(
+        &self,
+        request: Request<api::ListDownlinkSchedulesRequest>,
+    ) -> Result<Response<api::ListDownlinkSchedulesResponse>, Status> {
+        let req = request.get_ref();
+        let app_id = Uuid::from_str(&req.application_id).map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateApplicationAccess::new(validator::Flag::Read, app_id),
+            )
+            .await?;
+
+        let count = downlink_schedule::get_count(&app_id)
+            .await
+            .map_err(|e| e.status())?;
+        let items = downlink_schedule::list(req.limit as i64, req.offset as i64, &app_id)
+            .await
+            .map_err(|e| e.status())?;
+
+        let mut resp = Response::new(api::ListDownlinkSchedulesResponse {
+            total_count: count as u32,
+            result: items
+                .iter()
+                .map(|ds| api::DownlinkScheduleListItem {
+                    id: ds.id.to_string(),
+                    name: ds.name.clone(),
+                    cron_expression: ds.cron_expression.clone(),
+                    is_disabled: ds.is_disabled,
+                    last_run_at: ds
+                        .last_run_at
+                        .as_ref()
+                        .map(helpers::datetime_to_prost_timestamp),
+                    next_run_at: Some(helpers::datetime_to_prost_timestamp(&ds.next_run_at)),
+                })
+                .collect(),
+        });
+        resp.metadata_mut()
+            .insert("x-log-application_id", req.application_id.parse().unwrap());
+
+        Ok(resp)
+    }
+
+    async fn bulk_enqueue(
+        &self,
+        request: Request<api::BulkEnqueueDeviceQueueItemsRequest>,
+    ) -> Result<Response<api::BulkEnqueueDeviceQueueItemsResponse>, Status> {
+        let req = request.get_ref();
+        let app_id = Uuid::from_str(&req.application_id).map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateDevicesAccess::new(validator::Flag::Create, app_id),
+            )
+            .await?;
+
+        let filter_selector = !req.device_profile_id.is_empty() || !req.tags.is_empty();
+        if filter_selector == !req.dev_euis.is_empty() {
+            return Err(Status::invalid_argument(
+                "Either device_profile_id and / or tags, or dev_euis must be set",
+            ));
+        }
+
+        let priority = i16::try_from(req.priority)
+            .map_err(|_| Status::invalid_argument("priority must be <= 32767"))?;
+        let expires_at = match req.expires_at {
+            Some(v) => {
+                let v: std::time::SystemTime = v
+                    .try_into()
+                    .map_err(|e: prost_types::TimestampError| e.status())?;
+                Some(v.into())
+            }
+            None => None,
+        };
+        let not_before = match req.not_before {
+            Some(v) => {
+                let v: std::time::SystemTime = v
+                    .try_into()
+                    .map_err(|e: prost_types::TimestampError| e.status())?;
+                Some(v.into())
+            }
+            None => None,
+        };
+
+        let job_id = Uuid::new_v4();
+        let mut result: Vec<api::BulkEnqueueDeviceResult> = Vec::new();
+
+        let dev_euis: Vec<EUI64> = if filter_selector {
+            device::get_dev_euis(&device::Filters {
+                application_id: Some(app_id),
+                device_profile_id: if req.device_profile_id.is_empty() {
+                    None
+                } else {
+                    Some(Uuid::from_str(&req.device_profile_id).map_err(|e| e.status())?)
+                },
+                tags: req.tags.clone(),
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| e.status())?
+        } else {
+            let mut dev_euis = Vec::with_capacity(req.dev_euis.len());
+            for dev_eui in &req.dev_euis {
+                match EUI64::from_str(dev_eui) {
+                    Ok(v) => dev_euis.push(v),
+                    Err(e) => result.push(api::BulkEnqueueDeviceResult {
+                        dev_eui: dev_eui.clone(),
+                        error: e.to_string(),
+                        ..Default::default()
+                    }),
+                }
+            }
+            dev_euis
+        };
+
+        let devices: HashMap<EUI64, device::Device> = device::get_many(&dev_euis)
+            .await
+            .map_err(|e| e.status())?
+            .into_iter()
+            .map(|d| (d.dev_eui, d))
+            .collect();
+
+        // The payload is encoded once per device-profile and set of device variables.
+        let mut device_profiles: HashMap<Uuid, device_profile::DeviceProfile> = HashMap::new();
+        let mut encoded: HashMap<(Uuid, Vec<(String, String)>), (u8, Vec<u8>)> = HashMap::new();
+        let mut items: Vec<device_queue::DeviceQueueItem> = Vec::with_capacity(dev_euis.len());
+
+        for dev_eui in &dev_euis {
+            let d = match devices.get(dev_eui) {
+                Some(v) if Uuid::from(v.application_id) == app_id => v,
+                _ => {
+                    result.push(api::BulkEnqueueDeviceResult {
+                        dev_eui: dev_eui.to_string(),
+                        error: "Device does not exist within the application".into(),
+                        ..Default::default()
+                    });
+                    continue;
+                }
+            };
+
+            let (f_port, data) = match &req.object {
+                Some(obj) => {
+                    let dp_id: Uuid = d.device_profile_id.into();
+                    let mut variables: Vec<(String, String)> =
+                        d.variables.into_hashmap().into_iter().collect();
+                    variables.sort();
+                    let key = (dp_id, variables);
+
+                    if let Some(v) = encoded.get(&key) {
+                        v.clone()
+                    } else {
+                        if !device_profiles.contains_key(&dp_id) {
+                            let dp = device_profile::get(&dp_id).await.map_err(|e| e.status())?;
+                            device_profiles.insert(dp_id, dp);
+                        }
+                        let dp = &device_profiles[&dp_id];
+
+                        match codec::struct_to_binary(
+                            dp.payload_codec_runtime,
+                            req.f_port as u8,
+                            &d.variables,
+                            &dp.payload_codec_script,
+                            obj,
+                        )
+                        .await
+                        {
+                            Ok(v) => {
+                                encoded.insert(key, v.clone());
+                                v
+                            }
+                            Err(e) => {
+                                result.push(api::BulkEnqueueDeviceResult {
+                                    dev_eui: dev_eui.to_string(),
+                                    error: format!("Encode payload error: {:#}", e),
+                                    ..Default::default()
+                                });
+                                continue;
+                            }
+                        }
+                    }
+                }
+                None => (req.f_port as u8, req.data.clone()),
+            };
+
+            let qi = device_queue::DeviceQueueItem {
+                id: Uuid::new_v4().into(),
+                dev_eui: *dev_eui,
+                f_port: f_port as i16,
+                confirmed: req.confirmed,
+                data,
+                expires_at,
+                priority,
+                dedup_key: if req.dedup_key.is_empty() {
+                    None
+                } else {
+                    Some(req.dedup_key.clone())
+                },
+                not_before,
+                ..Default::default()
+            };
+
+            result.push(api::BulkEnqueueDeviceResult {
+                dev_eui: dev_eui.to_string(),
+                queue_item_id: qi.id.to_string(),
+                ..Default::default()
+            });
+            items.push(qi);
+        }
+
+        let success_count = items.len() as u32;
+        let failure_count = result.len() as u32 - success_count;
+
+        if !items.is_empty() {
+            device_queue::enqueue_items(items)
+                .await
+                .map_err(|e| e.status())?;
+        }
+
+        info!(job_id = %job_id, application_id = %app_id, success_count = success_count, failure_count = failure_count, "Bulk enqueue completed");
+
+        let mut resp = Response::new(api::BulkEnqueueDeviceQueueItemsResponse {
+            job_id: job_id.to_string(),
+            success_count,
+            failure_count,
+            result,
+        });
+        resp.metadata_mut()
+            .insert("x-log-application_id", req.application_id.parse().unwrap());
+        resp.metadata_mut()
+            .insert("x-log-job_id", job_id.to_string().parse().unwrap());
+
+        Ok(resp)
+    }
+}
+
+fn downlink_schedule_from_proto(
+    ds: &api::DownlinkSchedule,
+) -> Result<downlink_schedule::DownlinkSchedule, Status> {
+    Ok(downlink_schedule::DownlinkSchedule {
+        name: ds.name.clone(),
+        dev_eui: if ds.dev_eui.is_empty() {
+            None
+        } else {
+            Some(EUI64::from_str(&ds.dev_eui).map_err(|e| e.status())?)
+        },
+        multicast_group_id: if ds.multicast_group_id.is_empty() {
+            None
+        } else {
+            Some(
+                Uuid::from_str(&ds.multicast_group_id)
+                    .map_err(|e| e.status())?
+                    .into(),
+            )
+        },
+        tags: fields::KeyValue::new(ds.tags.clone()),
+        cron_expression: ds.cron_expression.clone(),
+        f_port: ds.f_port as i16,
+        confirmed: ds.confirmed,
+        data: ds.data.clone(),
+        is_disabled: ds.is_disabled,
+        ..Default::default()
+    })
 }
 
 #[cfg(test)]
@@ -2065,6 +2508,250 @@ pub mod test {
         req
     }
 
+    #[tokio::test]
+    async fn test_bulk_enqueue() {
+        let _guard = test::prepare().await;
+        let app = get_application().await;
+        let u = get_user().await;
+        let service = Application::new(RequestValidator::new());
+        let dp = device_profile::test::create_device_profile(Some(app.tenant_id.into())).await;
+
+        for (i, site) in ["a", "a", "b"].iter().enumerate() {
+            device::create(device::Device {
+                application_id: app.id,
+                device_profile_id: dp.id,
+                name: format!("dev-{}", i),
+                dev_eui: EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, i as u8]),
+                tags: fields::KeyValue::new(
+                    [("site".to_string(), site.to_string())]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                ),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        }
+
+        // no selector
+        let req = get_request(
+            &u.id,
+            api::BulkEnqueueDeviceQueueItemsRequest {
+                application_id: app.id.to_string(),
+                f_port: 10,
+                data: vec![1, 2, 3],
+                ..Default::default()
+            },
+        );
+        let resp = service.bulk_enqueue(req).await;
+        assert_eq!(tonic::Code::InvalidArgument, resp.err().unwrap().code());
+
+        // by tags
+        let req = get_request(
+            &u.id,
+            api::BulkEnqueueDeviceQueueItemsRequest {
+                application_id: app.id.to_string(),
+                tags: [("site".to_string(), "a".to_string())]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                f_port: 10,
+                data: vec![1, 2, 3],
+                ..Default::default()
+            },
+        );
+        let resp = service.bulk_enqueue(req).await.unwrap();
+        let resp = resp.get_ref();
+        assert_eq!(2, resp.success_count);
+        assert_eq!(0, resp.failure_count);
+        assert_eq!("0102030405060700", resp.result[0].dev_eui);
+        assert_eq!("0102030405060701", resp.result[1].dev_eui);
+
+        let queue = device_queue::get_for_dev_eui(&EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 0]))
+            .await
+            .unwrap();
+        assert_eq!(1, queue.len());
+        assert_eq!(resp.result[0].queue_item_id, queue[0].id.to_string());
+        assert!(
+            device_queue::get_for_dev_eui(&EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 2]))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // by dev_euis
+        let req = get_request(
+            &u.id,
+            api::BulkEnqueueDeviceQueueItemsRequest {
+                application_id: app.id.to_string(),
+                dev_euis: vec![
+                    "0102030405060702".into(),
+                    "0102030405060709".into(),
+                    "invalid".into(),
+                ],
+                f_port: 10,
+                data: vec![1, 2, 3],
+                ..Default::default()
+            },
+        );
+        let resp = service.bulk_enqueue(req).await.unwrap();
+        let resp = resp.get_ref();
+        assert_eq!(1, resp.success_count);
+        assert_eq!(2, resp.failure_count);
+        assert!(!resp.job_id.is_empty());
+
+        let res: HashMap<String, api::BulkEnqueueDeviceResult> = resp
+            .result
+            .iter()
+            .map(|r| (r.dev_eui.clone(), r.clone()))
+            .collect();
+        assert!(res["0102030405060702"].error.is_empty());
+        assert!(!res["0102030405060702"].queue_item_id.is_empty());
+        assert_eq!(
+            "Device does not exist within the application",
+            res["0102030405060709"].error
+        );
+        assert!(!res["invalid"].error.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_downlink_schedule() {
+        let _guard = test::prepare().await;
+        let app = get_application().await;
+        let u = get_user().await;
+        let service = Application::new(RequestValidator::new());
+
+        // create with invalid cron expression
+        let create_req = get_request(
+            &u.id,
+            api::CreateDownlinkScheduleRequest {
+                downlink_schedule: Some(api::DownlinkSchedule {
+                    application_id: app.id.to_string(),
+                    name: "time-sync".into(),
+                    tags: [("site".to_string(), "a".to_string())]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    cron_expression: "nightly".into(),
+                    f_port: 10,
+                    data: vec![1, 2, 3],
+                    ..Default::default()
+                }),
+            },
+        );
+        let create_resp = service.create_downlink_schedule(create_req).await;
+        assert_eq!(
+            tonic::Code::InvalidArgument,
+            create_resp.err().unwrap().code()
+        );
+
+        // create
+        let create_req = get_request(
+            &u.id,
+            api::CreateDownlinkScheduleRequest {
+                downlink_schedule: Some(api::DownlinkSchedule {
+                    application_id: app.id.to_string(),
+                    name: "time-sync".into(),
+                    tags: [("site".to_string(), "a".to_string())]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    cron_expression: "0 0 3 * * *".into(),
+                    f_port: 10,
+                    data: vec![1, 2, 3],
+                    ..Default::default()
+                }),
+            },
+        );
+        let create_resp = service.create_downlink_schedule(create_req).await.unwrap();
+        let create_resp = create_resp.get_ref();
+
+        // get
+        let get_req = get_request(
+            &u.id,
+            api::GetDownlinkScheduleRequest {
+                id: create_resp.id.clone(),
+            },
+        );
+        let get_resp = service.get_downlink_schedule(get_req).await.unwrap();
+        let get_resp = get_resp.get_ref();
+        assert_eq!(
+            Some(api::DownlinkSchedule {
+                id: create_resp.id.clone(),
+                application_id: app.id.to_string(),
+                name: "time-sync".into(),
+                tags: [("site".to_string(), "a".to_string())]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                cron_expression: "0 0 3 * * *".into(),
+                f_port: 10,
+                data: vec![1, 2, 3],
+                ..Default::default()
+            }),
+            get_resp.downlink_schedule
+        );
+        assert!(get_resp.last_run_at.is_none());
+        assert!(get_resp.next_run_at.is_some());
+
+        // update
+        let update_req = get_request(
+            &u.id,
+            api::UpdateDownlinkScheduleRequest {
+                downlink_schedule: Some(api::DownlinkSchedule {
+                    id: create_resp.id.clone(),
+                    name: "config-refresh".into(),
+                    tags: [("site".to_string(), "b".to_string())]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    cron_expression: "0 30 4 * * Sun".into(),
+                    f_port: 20,
+                    data: vec![3, 2, 1],
+                    is_disabled: true,
+                    ..Default::default()
+                }),
+            },
+        );
+        let _ = service.update_downlink_schedule(update_req).await.unwrap();
+
+        // list
+        let list_req = get_request(
+            &u.id,
+            api::ListDownlinkSchedulesRequest {
+                application_id: app.id.to_string(),
+                limit: 10,
+                offset: 0,
+            },
+        );
+        let list_resp = service.list_downlink_schedules(list_req).await.unwrap();
+        let list_resp = list_resp.get_ref();
+        assert_eq!(1, list_resp.total_count);
+        assert_eq!(1, list_resp.result.len());
+        assert_eq!("config-refresh", list_resp.result[0].name);
+        assert_eq!("0 30 4 * * Sun", list_resp.result[0].cron_expression);
+        assert!(list_resp.result[0].is_disabled);
+
+        // delete
+        let del_req = get_request(
+            &u.id,
+            api::DeleteDownlinkScheduleRequest {
+                id: create_resp.id.clone(),
+            },
+        );
+        let _ = service.delete_downlink_schedule(del_req).await.unwrap();
+
+        let del_req = get_request(
+            &u.id,
+            api::DeleteDownlinkScheduleRequest {
+                id: create_resp.id.clone(),
+            },
+        );
+        let del_resp = service.delete_downlink_schedule(del_req).await;
+        assert!(del_resp.is_err());
+    }
+
     #[tokio::test]
     async fn test_http_integration() {
         let _guard = test::prepare().await;
@@ -2882,6 +2882,55 @@ impl Validator for ValidateFuotaDeploymentAccess {
     }
 }
 
+pub struct ValidateBackendInterfacesAccess {
+    flag: Flag,
+}
+
+impl ValidateBackendInterfacesAccess {
+    pub fn new(flag: Flag) -> Self {
+        ValidateBackendInterfacesAccess { flag }
+    }
+}
+
+// Roaming agreements and Join Servers contain credentials (TLS material and
+// authorization headers) and therefore are restricted to global admins.
+#[async_trait]
+impl Validator for ValidateBackendInterfacesAccess {
+    async fn validate_user(&self, id: &Uuid) -> Result<i64, Error> {
+        let q = user::table.select(dsl::count_star()).filter(
+            user::id
+                .eq(fields::Uuid::from(id))
+                .and(user::is_active.eq(true))
+                .and(user::is_admin.eq(true)),
+        );
+
+        Ok(q.first(&mut get_async_db_conn().await?).await?)
+    }
+
+    async fn validate_key(&self, id: &Uuid) -> Result<i64, Error> {
+        let mut q = api_key::table
+            .select(dsl::count_star())
+            .filter(
+                api_key::id
+                    .eq(fields::Uuid::from(id))
+                    .and(api_key::is_admin.eq(true)),
+            )
+            .into_boxed();
+
+        match self.flag {
+            // admin api key
+            Flag::Read | Flag::List => {}
+            // admin api key (not RO)
+            Flag::Create | Flag::Update | Flag::Delete => {
+                q = q.filter(api_key::is_read_only.eq(false));
+            }
+            _ => return Ok(0),
+        }
+
+        Ok(q.first(&mut get_async_db_conn().await?).await?)
+    }
+}
+
 #[cfg(test)]
 pub mod test {
     use super::*;
@@ -7026,4 +7075,102 @@ pub mod test {
         ];
         run_tests(tests).await;
     }
+
+    #[tokio::test]
+    async fn backend_interfaces() {
+        let _guard = test::prepare().await;
+
+        let user_admin = user::create(user::User {
+            email: "admin@user".into(),
+            is_active: true,
+            is_admin: true,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let user_active = user::create(user::User {
+            email: "active@user".into(),
+            is_active: true,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let api_key_admin = api_key::test::create_api_key(true, false).await;
+        let api_key_admin_ro = api_key::create(api_key::ApiKey {
+            name: api_key_admin.name.clone(),
+            is_admin: api_key_admin.is_admin,
+            tenant_id: api_key_admin.tenant_id,
+            is_read_only: true,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let api_key_tenant = api_key::test::create_api_key(false, true).await;
+
+        let tests = vec![
+            // admin user can create, read, update, delete and list
+            ValidatorTest {
+                validators: vec![
+                    ValidateBackendInterfacesAccess::new(Flag::Create),
+                    ValidateBackendInterfacesAccess::new(Flag::Read),
+                    ValidateBackendInterfacesAccess::new(Flag::Update),
+                    ValidateBackendInterfacesAccess::new(Flag::Delete),
+                    ValidateBackendInterfacesAccess::new(Flag::List),
+                ],
+                id: AuthID::User(user_admin.id.into()),
+                ok: true,
+            },
+            // non-admin user can not read or list
+            ValidatorTest {
+                validators: vec![
+                    ValidateBackendInterfacesAccess::new(Flag::Read),
+                    ValidateBackendInterfacesAccess::new(Flag::List),
+                ],
+                id: AuthID::User(user_active.id.into()),
+                ok: false,
+            },
+            // admin api key can create, read, update, delete and list
+            ValidatorTest {
+                validators: vec![
+                    ValidateBackendInterfacesAccess::new(Flag::Create),
+                    ValidateBackendInterfacesAccess::new(Flag::Read),
+                    ValidateBackendInterfacesAccess::new(Flag::Update),
+                    ValidateBackendInterfacesAccess::new(Flag::Delete),
+                    ValidateBackendInterfacesAccess::new(Flag::List),
+                ],
+                id: AuthID::Key(api_key_admin.id.into()),
+                ok: true,
+            },
+            // RO admin api key can read and list
+            ValidatorTest {
+                validators: vec![
+                    ValidateBackendInterfacesAccess::new(Flag::Read),
+                    ValidateBackendInterfacesAccess::new(Flag::List),
+                ],
+                id: AuthID::Key(api_key_admin_ro.id.into()),
+                ok: true,
+            },
+            // RO admin api key can not create, update or delete
+            ValidatorTest {
+                validators: vec![
+                    ValidateBackendInterfacesAccess::new(Flag::Create),
+                    ValidateBackendInterfacesAccess::new(Flag::Update),
+                    ValidateBackendInterfacesAccess::new(Flag::Delete),
+                ],
+                id: AuthID::Key(api_key_admin_ro.id.into()),
+                ok: false,
+            },
+            // tenant api key can not read or list
+            ValidatorTest {
+                validators: vec![
+                    ValidateBackendInterfacesAccess::new(Flag::Read),
+                    ValidateBackendInterfacesAccess::new(Flag::List),
+                ],
+                id: AuthID::Key(api_key_tenant.id.into()),
+                ok: false,
+            },
+        ];
+        run_tests(tests).await;
+    }
 }
@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::response::{IntoResponse, Json, Response};
+use http::StatusCode;
+use tokio::task;
+use tracing::error;
+use uuid::Uuid;
+
+use super::{err_to_response, err_to_result_code, log_request_response};
+use crate::backend::roaming;
+use crate::helpers::errors::PrintFullError;
+use crate::region;
+use crate::storage::{
+    error::Error as StorageError, handover_roaming, helpers::get_all_device_data,
+};
+use crate::uplink::{
+    RoamingMetaData, UplinkFrameSet, data_hr, error::Error as UplinkError, helpers, join_sns,
+};
+use lrwn::region::CommonName;
+use lrwn::{EUI64, NetID};
+
+pub async fn handle_profile_req(
+    sender_client: Arc<backend::Client>,
+    bp: backend::BasePayload,
+    b: &[u8],
+) -> Response {
+    let pl: backend::ProfileReqPayload = match serde_json::from_slice(b) {
+        Ok(v) => v,
+        Err(e) => {
+            let ans = err_to_response(anyhow::Error::new(e), &bp);
+            log_request_response(&bp, b, &ans).await;
+            return Json(&ans).into_response();
+        }
+    };
+
+    if sender_client.is_async() {
+        let b = b.to_vec();
+        task::spawn(async move {
+            let ans = match _handle_profile_req(pl).await {
+                Ok(v) => v,
+                Err(e) => {
+                    let msg = e.to_string();
+                    backend::ProfileAnsPayload {
+                        base: bp.to_base_payload_result(err_to_result_code(e), &msg),
+                        ..Default::default()
+                    }
+                }
+            };
+
+            log_request_response(&bp, &b, &ans).await;
+
+            if let Err(e) = sender_client.profile_ans(backend::Role::SNS, &ans).await {
+                error!(error = %e.full(), "Send async ProfileAns error");
+            }
+        });
+
+        (StatusCode::OK, "").into_response()
+    } else {
+        match _handle_profile_req(pl).await {
+            Ok(ans) => {
+                log_request_response(&bp, b, &ans).await;
+                Json(&ans).into_response()
+            }
+            Err(e) => {
+                let ans = err_to_response(e, &bp);
+                log_request_response(&bp, b, &ans).await;
+                Json(&ans).into_response()
+            }
+        }
+    }
+}
+
+async fn _handle_profile_req(pl: backend::ProfileReqPayload) -> Result<backend::ProfileAnsPayload> {
+    let sender_id = NetID::from_slice(&pl.base.sender_id)?;
+    let dev_eui = EUI64::from_slice(&pl.dev_eui)?;
+
+    let dp = match get_all_device_data(dev_eui).await {
+        Ok((_, _, _, dp)) => dp,
+        Err(StorageError::NotFound(_)) => {
+            return Ok(backend::ProfileAnsPayload {
+                base: pl
+                    .base
+                    .to_base_payload_result(backend::ResultCode::UnknownDevEUI, ""),
+                ..Default::default()
+            });
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if !dp.allow_roaming || !roaming::is_device_profile_allowed(sender_id, &dp.id.into()) {
+        return Err(UplinkError::RoamingIsNotAllowed.into());
+    }
+
+    // Handover-roaming must be allowed both by the device-profile and by the
+    // roaming agreement with the requesting NetID. Else we fall back to passive-roaming.
+    let roaming_activation_type =
+        if dp.allow_handover_roaming && roaming::get_handover_roaming(sender_id).unwrap_or(false) {
+            backend::RoamingActivationType::Handover
+        } else {
+            backend::RoamingActivationType::Passive
+        };
+
+    Ok(backend::ProfileAnsPayload {
+        base: pl
+            .base
+            .to_base_payload_result(backend::ResultCode::Success, ""),
+        device_profile: Some(roaming::device_profile_to_bi(&dp)),
+        device_profile_timestamp: Some(dp.updated_at),
+        roaming_activation_type: Some(roaming_activation_type),
+    })
+}
+
+pub async fn handle_hr_start_req(
+    sender_client: Arc<backend::Client>,
+    bp: backend::BasePayload,
+    b: &[u8],
+) -> Response {
+    if sender_client.is_async() {
+        let b = b.to_vec();
+        task::spawn(async move {
+            let ans = match _handle_hr_start_req(&b).await {
+                Ok(v) => v,
+                Err(e) => {
+                    let msg = e.to_string();
+                    backend::HRStartAnsPayload {
+                        base: bp.to_base_payload_result(err_to_result_code(e), &msg),
+                        ..Default::default()
+                    }
+                }
+            };
+
+            log_request_response(&bp, &b, &ans).await;
+
+            if let Err(e) = sender_client.hr_start_ans(backend::Role::SNS, &ans).await {
+                error!(error = %e.full(), transaction_id = bp.transaction_id, "Send async HRStartAns error");
+            }
+        });
+        (StatusCode::OK, "").into_response()
+    } else {
+        match _handle_hr_start_req(b).await {
+            Ok(ans) => {
+                log_request_response(&bp, b, &ans).await;
+                Json(&ans).into_response()
+            }
+            Err(e) => {
+                let ans = err_to_response(e, &bp);
+                log_request_response(&bp, b, &ans).await;
+                Json(&ans).into_response()
+            }
+        }
+    }
+}
+
+async fn _handle_hr_start_req(b: &[u8]) -> Result<backend::HRStartAnsPayload> {
+    let pl: backend::HRStartReqPayload = serde_json::from_slice(b)?;
+    let sender_id = NetID::from_slice(&pl.base.sender_id)?;
+    let phy = lrwn::PhyPayload::from_slice(&pl.phy_payload)?;
+
+    if phy.mhdr.f_type != lrwn::FType::JoinRequest {
+        return Err(anyhow!("PHYPayload does not contain a join-request"));
+    }
+
+    if !roaming::get_handover_roaming(sender_id)? {
+        return Err(UplinkError::RoamingIsNotAllowed.into());
+    }
+
+    let rx_info = roaming::ul_meta_data_to_rx_info(&pl.ul_meta_data)?;
+    let tx_info = roaming::ul_meta_data_to_tx_info(&pl.ul_meta_data)?;
+    let region_common_name = CommonName::from_str(&pl.ul_meta_data.rf_region)?;
+    let region_config_id = region::get_region_config_id(region_common_name)?;
+    let dr = pl.ul_meta_data.data_rate.unwrap_or_default();
+
+    let ufs = UplinkFrameSet {
+        uplink_set_id: Uuid::new_v4(),
+        dr,
+        ch: helpers::get_uplink_ch(&region_config_id, tx_info.frequency, dr)?,
+        phy_payload: phy,
+        phy_payload_b: pl.phy_payload.clone(),
+        tx_info,
+        rx_info_set: rx_info,
+        gateway_private_up_map: HashMap::new(),
+        gateway_private_down_map: HashMap::new(),
+        gateway_tenant_id_map: HashMap::new(),
+        gateway_downlink_priority_map: HashMap::new(),
+        region_common_name,
+        region_config_id,
+        roaming_meta_data: Some(RoamingMetaData {
+            base_payload: pl.base.clone(),
+            ul_meta_data: pl.ul_meta_data.clone(),
+        }),
+    };
+
+    // This flow will return RoamingIsNotAllowed in case allow_roaming or
+    // allow_handover_roaming is not enabled in the device-profile.
+    join_sns::JoinRequest::start_hr(ufs, pl).await
+}
+
+pub async fn handle_hr_stop_req(
+    sender_client: Arc<backend::Client>,
+    bp: backend::BasePayload,
+    b: &[u8],
+) -> Response {
+    if sender_client.is_async() {
+        let b = b.to_vec();
+        task::spawn(async move {
+            let ans = match _handle_hr_stop_req(&b).await {
+                Ok(v) => v,
+                Err(e) => {
+                    let msg = e.to_string();
+                    backend::HRStopAnsPayload {
+                        base: bp.to_base_payload_result(err_to_result_code(e), &msg),
+                    }
+                }
+            };
+
+            log_request_response(&bp, &b, &ans).await;
+
+            if let Err(e) = sender_client.hr_stop_ans(backend::Role::HNS, &ans).await {
+                error!(error = %e.full(), "Send async HRStopAns error");
+            }
+        });
+        (StatusCode::OK, "").into_response()
+    } else {
+        match _handle_hr_stop_req(b).await {
+            Ok(ans) => {
+                log_request_response(&bp, b, &ans).await;
+                Json(&ans).into_response()
+            }
+            Err(e) => {
+                let ans = err_to_response(e, &bp);
+                log_request_response(&bp, b, &ans).await;
+                Json(&ans).into_response()
+            }
+        }
+    }
+}
+
+async fn _handle_hr_stop_req(b: &[u8]) -> Result<backend::HRStopAnsPayload> {
+    let pl: backend::HRStopReqPayload = serde_json::from_slice(b)?;
+    let sender_id = NetID::from_slice(&pl.base.sender_id)?;
+    let dev_eui = EUI64::from_slice(&pl.dev_eui)?;
+
+    // Only the NetID with which the session was established can stop it.
+    match handover_roaming::get(&dev_eui).await {
+        Ok(ds) if ds.net_id == sender_id.to_vec() => {}
+        Ok(_) | Err(StorageError::NotFound(_)) => {
+            return Ok(backend::HRStopAnsPayload {
+                base: pl
+                    .base
+                    .to_base_payload_result(backend::ResultCode::UnknownDevEUI, ""),
+            });
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    handover_roaming::delete(&dev_eui).await?;
+
+    Ok(backend::HRStopAnsPayload {
+        base: pl
+            .base
+            .to_base_payload_result(backend::ResultCode::Success, ""),
+    })
+}
+
+// Returns true in case the XmitDataReq contains the uplink of a device that has been
+// handed over to the sender (the serving Network Server). This is keyed on the stored
+// handover-roaming device-session, as the FRMPayload might be empty.
+pub async fn is_xmit_data_req_uplink(pl: &backend::XmitDataReqPayload) -> Result<bool> {
+    let ul_meta_data = match &pl.ul_meta_data {
+        Some(v) if pl.phy_payload.is_empty() && !v.dev_eui.is_empty() => v,
+        _ => return Ok(false),
+    };
+
+    let sender_id = NetID::from_slice(&pl.base.sender_id)?;
+    let dev_eui = EUI64::from_slice(&ul_meta_data.dev_eui)?;
+
+    match handover_roaming::get(&dev_eui).await {
+        Ok(ds) => Ok(!ds.serving && ds.net_id == sender_id.to_vec()),
+        Err(StorageError::NotFound(_)) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// Handles the XmitDataReq containing the FRMPayload of a handed-over device, which is
+// sent by the serving Network Server.
+pub async fn handle_xmit_data_req_uplink(
+    pl: &backend::XmitDataReqPayload,
+    ul_meta_data: &backend::ULMetaData,
+) -> Result<()> {
+    let sender_id = NetID::from_slice(&pl.base.sender_id)?;
+    data_hr::Data::handle(sender_id, ul_meta_data.clone(), pl.frm_payload.clone()).await
+}
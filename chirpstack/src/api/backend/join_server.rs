@@ -0,0 +1,798 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use axum::response::{IntoResponse, Json, Response};
+use http::StatusCode;
+use thiserror::Error;
+use tokio::task;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use super::{err_to_response, err_to_result_code, log_request_response};
+use crate::backend::keywrap;
+use crate::config;
+use crate::helpers::errors::PrintFullError;
+use crate::storage::{device, device_keys, error::Error as StorageError, join_server_session};
+use chirpstack_api::internal;
+use lrwn::{
+    AES128Key, CFList, DLSettings, DevAddr, EUI64, FType, JoinAcceptPayload, JoinType, MHDR, Major,
+    NetID, Payload, PhyPayload, keys,
+};
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Join Server role is disabled")]
+    Disabled,
+
+    #[error("Activation of JoinEUI {1} is not allowed for NetID {0}")]
+    ActivationDisallowed(NetID, EUI64),
+
+    #[error("Unknown DevEUI: {0}")]
+    UnknownDevEUI(EUI64),
+
+    #[error("Invalid MIC")]
+    InvalidMIC,
+
+    #[error("DevNonce has already been used")]
+    InvalidDevNonce,
+
+    #[error("Join-nonce overflow")]
+    JoinNonceOverflow,
+
+    #[error("Unknown SessionKeyID: {0}")]
+    UnknownSessionKeyID(String),
+}
+
+impl Error {
+    pub fn result_code(&self) -> backend::ResultCode {
+        match self {
+            Error::Disabled | Error::ActivationDisallowed(_, _) => {
+                backend::ResultCode::ActivationDisallowed
+            }
+            Error::UnknownDevEUI(_) | Error::UnknownSessionKeyID(_) => {
+                backend::ResultCode::UnknownDevEUI
+            }
+            Error::InvalidMIC => backend::ResultCode::MICFailed,
+            Error::InvalidDevNonce | Error::JoinNonceOverflow => backend::ResultCode::JoinReqFailed,
+        }
+    }
+}
+
+struct SessionKeys {
+    f_nwk_s_int_key: AES128Key,
+    s_nwk_s_int_key: AES128Key,
+    nwk_s_enc_key: AES128Key,
+    app_s_key: AES128Key,
+}
+
+pub async fn handle_join_req(
+    sender_client: Arc<backend::Client>,
+    bp: backend::BasePayload,
+    b: &[u8],
+) -> Response {
+    let pl: backend::JoinReqPayload = match serde_json::from_slice(b) {
+        Ok(v) => v,
+        Err(e) => {
+            let ans = err_to_response(anyhow::Error::new(e), &bp);
+            log_request_response(&bp, b, &ans).await;
+            return Json(&ans).into_response();
+        }
+    };
+
+    if sender_client.is_async() {
+        let b = b.to_vec();
+        task::spawn(async move {
+            let ans = match _handle_join_req(pl).await {
+                Ok(v) => v,
+                Err(e) => {
+                    let msg = e.to_string();
+                    backend::JoinAnsPayload {
+                        base: bp.to_base_payload_result(err_to_result_code(e), &msg),
+                        ..Default::default()
+                    }
+                }
+            };
+
+            log_request_response(&bp, &b, &ans).await;
+
+            if let Err(e) = sender_client.join_ans(&ans).await {
+                error!(error = %e.full(), "Send async JoinAns error");
+            }
+        });
+
+        (StatusCode::OK, "").into_response()
+    } else {
+        match _handle_join_req(pl).await {
+            Ok(ans) => {
+                log_request_response(&bp, b, &ans).await;
+                Json(&ans).into_response()
+            }
+            Err(e) => {
+                let ans = err_to_response(e, &bp);
+                log_request_response(&bp, b, &ans).await;
+                Json(&ans).into_response()
+            }
+        }
+    }
+}
+
+async fn _handle_join_req(pl: backend::JoinReqPayload) -> Result<backend::JoinAnsPayload> {
+    let sender_id = NetID::from_slice(&pl.base.sender_id)?;
+    let phy = PhyPayload::from_slice(&pl.phy_payload)?;
+    let jr = match &phy.payload {
+        Payload::JoinRequest(v) => v,
+        _ => {
+            return Err(anyhow!("PHYPayload does not contain a join-request"));
+        }
+    };
+
+    let kek_label = get_kek_label(sender_id, jr.join_eui)?;
+    let dk = get_device_keys(&jr.dev_eui).await?;
+
+    if !phy.validate_join_request_mic(&dk.nwk_key)? {
+        return Err(Error::InvalidMIC.into());
+    }
+
+    let dk = match device_keys::validate_incr_join_and_store_dev_nonce(
+        jr.join_eui,
+        jr.dev_eui,
+        jr.dev_nonce,
+    )
+    .await
+    {
+        Ok(v) => v,
+        Err(StorageError::InvalidDevNonce) => return Err(Error::InvalidDevNonce.into()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let join_nonce = get_join_nonce(&dk)?;
+    let opt_neg = !pl.mac_version.starts_with("1.0");
+
+    let join_accept = get_join_accept(
+        JoinType::Join,
+        opt_neg,
+        &jr.join_eui,
+        jr.dev_nonce,
+        join_nonce,
+        sender_id,
+        &pl.dev_addr,
+        &pl.dl_settings,
+        pl.rx_delay,
+        &pl.cf_list,
+        &dk,
+    )?;
+
+    let session_keys = get_session_keys(
+        opt_neg,
+        &dk,
+        sender_id,
+        &jr.join_eui,
+        join_nonce,
+        jr.dev_nonce,
+    )?;
+
+    let session_key_id = save_session(sender_id, jr.dev_eui, session_keys.app_s_key).await?;
+    let conf = config::get();
+
+    info!(dev_eui = %jr.dev_eui, net_id = %sender_id, "Join-request handled");
+
+    Ok(backend::JoinAnsPayload {
+        base: pl
+            .base
+            .to_base_payload_result(backend::ResultCode::Success, ""),
+        phy_payload: join_accept,
+        nwk_s_key: if opt_neg {
+            None
+        } else {
+            Some(keywrap::wrap(&kek_label, session_keys.f_nwk_s_int_key)?)
+        },
+        f_nwk_s_int_key: if opt_neg {
+            Some(keywrap::wrap(&kek_label, session_keys.f_nwk_s_int_key)?)
+        } else {
+            None
+        },
+        s_nwk_s_int_key: if opt_neg {
+            Some(keywrap::wrap(&kek_label, session_keys.s_nwk_s_int_key)?)
+        } else {
+            None
+        },
+        nwk_s_enc_key: if opt_neg {
+            Some(keywrap::wrap(&kek_label, session_keys.nwk_s_enc_key)?)
+        } else {
+            None
+        },
+        app_s_key: Some(keywrap::wrap(
+            &conf.backend_interfaces.join_server.app_s_key_kek_label,
+            session_keys.app_s_key,
+        )?),
+        session_key_id,
+        ..Default::default()
+    })
+}
+
+pub async fn handle_rejoin_req(
+    sender_client: Arc<backend::Client>,
+    bp: backend::BasePayload,
+    b: &[u8],
+) -> Response {
+    let pl: backend::RejoinReqPayload = match serde_json::from_slice(b) {
+        Ok(v) => v,
+        Err(e) => {
+            let ans = err_to_response(anyhow::Error::new(e), &bp);
+            log_request_response(&bp, b, &ans).await;
+            return Json(&ans).into_response();
+        }
+    };
+
+    if sender_client.is_async() {
+        let b = b.to_vec();
+        task::spawn(async move {
+            let ans = match _handle_rejoin_req(pl).await {
+                Ok(v) => v,
+                Err(e) => {
+                    let msg = e.to_string();
+                    backend::RejoinAnsPayload {
+                        base: bp.to_base_payload_result(err_to_result_code(e), &msg),
+                        ..Default::default()
+                    }
+                }
+            };
+
+            log_request_response(&bp, &b, &ans).await;
+
+            if let Err(e) = sender_client.rejoin_ans(&ans).await {
+                error!(error = %e.full(), "Send async RejoinAns error");
+            }
+        });
+
+        (StatusCode::OK, "").into_response()
+    } else {
+        match _handle_rejoin_req(pl).await {
+            Ok(ans) => {
+                log_request_response(&bp, b, &ans).await;
+                Json(&ans).into_response()
+            }
+            Err(e) => {
+                let ans = err_to_response(e, &bp);
+                log_request_response(&bp, b, &ans).await;
+                Json(&ans).into_response()
+            }
+        }
+    }
+}
+
+async fn _handle_rejoin_req(pl: backend::RejoinReqPayload) -> Result<backend::RejoinAnsPayload> {
+    let sender_id = NetID::from_slice(&pl.base.sender_id)?;
+    let phy = PhyPayload::from_slice(&pl.phy_payload)?;
+
+    // The rejoin-request type 0 and 2 MIC is validated by the Network Server
+    // (it uses the SNwkSIntKey), the type 1 MIC is validated using the JSIntKey.
+    let (join_type, join_eui, dev_eui, rj_count) = match &phy.payload {
+        Payload::RejoinRequestType02(v) => {
+            let d = device::get(&v.dev_eui)
+                .await
+                .map_err(|_| Error::UnknownDevEUI(v.dev_eui))?;
+            (v.rejoin_type.clone(), d.join_eui, v.dev_eui, v.rj_count_0)
+        }
+        Payload::RejoinRequestType1(v) => {
+            (v.rejoin_type.clone(), v.join_eui, v.dev_eui, v.rj_count_1)
+        }
+        _ => {
+            return Err(anyhow!("PHYPayload does not contain a rejoin-request"));
+        }
+    };
+
+    let kek_label = get_kek_label(sender_id, join_eui)?;
+    let dk = get_device_keys(&dev_eui).await?;
+
+    if join_type == JoinType::RejoinType1 {
+        let js_int_key = keys::get_js_int_key(&dev_eui, &dk.nwk_key)?;
+        if !phy.validate_join_request_mic(&js_int_key)? {
+            return Err(Error::InvalidMIC.into());
+        }
+    }
+
+    let dk = device_keys::incr_join_nonce(dev_eui).await?;
+    let join_nonce = get_join_nonce(&dk)?;
+
+    let join_accept = get_join_accept(
+        join_type.clone(),
+        true,
+        &join_eui,
+        rj_count,
+        join_nonce,
+        sender_id,
+        &pl.dev_addr,
+        &pl.dl_settings,
+        pl.rx_delay,
+        &pl.cf_list,
+        &dk,
+    )?;
+
+    let session_keys = get_session_keys(true, &dk, sender_id, &join_eui, join_nonce, rj_count)?;
+    let session_key_id = save_session(sender_id, dev_eui, session_keys.app_s_key).await?;
+    let conf = config::get();
+
+    info!(dev_eui = %dev_eui, net_id = %sender_id, rejoin_type = ?join_type, "Rejoin-request handled");
+
+    Ok(backend::RejoinAnsPayload {
+        base: pl
+            .base
+            .to_base_payload_result(backend::ResultCode::Success, ""),
+        phy_payload: join_accept,
+        f_nwk_s_int_key: Some(keywrap::wrap(&kek_label, session_keys.f_nwk_s_int_key)?),
+        s_nwk_s_int_key: Some(keywrap::wrap(&kek_label, session_keys.s_nwk_s_int_key)?),
+        nwk_s_enc_key: Some(keywrap::wrap(&kek_label, session_keys.nwk_s_enc_key)?),
+        app_s_key: Some(keywrap::wrap(
+            &conf.backend_interfaces.join_server.app_s_key_kek_label,
+            session_keys.app_s_key,
+        )?),
+        session_key_id,
+        ..Default::default()
+    })
+}
+
+pub async fn handle_app_s_key_req(
+    sender_client: Arc<backend::Client>,
+    bp: backend::BasePayload,
+    b: &[u8],
+) -> Response {
+    let pl: backend::AppSKeyReqPayload = match serde_json::from_slice(b) {
+        Ok(v) => v,
+        Err(e) => {
+            let ans = err_to_response(anyhow::Error::new(e), &bp);
+            log_request_response(&bp, b, &ans).await;
+            return Json(&ans).into_response();
+        }
+    };
+
+    if sender_client.is_async() {
+        let b = b.to_vec();
+        task::spawn(async move {
+            let ans = match _handle_app_s_key_req(pl).await {
+                Ok(v) => v,
+                Err(e) => {
+                    let msg = e.to_string();
+                    backend::AppSKeyAnsPayload {
+                        base: bp.to_base_payload_result(err_to_result_code(e), &msg),
+                        ..Default::default()
+                    }
+                }
+            };
+
+            log_request_response(&bp, &b, &ans).await;
+
+            if let Err(e) = sender_client.app_s_key_ans(&ans).await {
+                error!(error = %e.full(), "Send async AppSKeyAns error");
+            }
+        });
+
+        (StatusCode::OK, "").into_response()
+    } else {
+        match _handle_app_s_key_req(pl).await {
+            Ok(ans) => {
+                log_request_response(&bp, b, &ans).await;
+                Json(&ans).into_response()
+            }
+            Err(e) => {
+                let ans = err_to_response(e, &bp);
+                log_request_response(&bp, b, &ans).await;
+                Json(&ans).into_response()
+            }
+        }
+    }
+}
+
+async fn _handle_app_s_key_req(
+    pl: backend::AppSKeyReqPayload,
+) -> Result<backend::AppSKeyAnsPayload> {
+    let conf = config::get();
+    if !conf.backend_interfaces.join_server.enabled {
+        return Err(Error::Disabled.into());
+    }
+
+    let sender_id = NetID::from_slice(&pl.base.sender_id)?;
+    let dev_eui = EUI64::from_slice(&pl.dev_eui)?;
+
+    // The session can only be retrieved by the Network Server that performed
+    // the join, for the same device.
+    let sess = join_server_session::get(&pl.session_key_id)
+        .await
+        .map_err(|_| Error::UnknownSessionKeyID(hex::encode(&pl.session_key_id)))?;
+    if sess.net_id != sender_id.to_vec() || sess.dev_eui != dev_eui.to_vec() {
+        return Err(Error::UnknownSessionKeyID(hex::encode(&pl.session_key_id)).into());
+    }
+
+    Ok(backend::AppSKeyAnsPayload {
+        base: pl
+            .base
+            .to_base_payload_result(backend::ResultCode::Success, ""),
+        dev_eui: pl.dev_eui.clone(),
+        app_s_key: Some(keywrap::wrap(
+            &conf.backend_interfaces.join_server.app_s_key_kek_label,
+            AES128Key::from_slice(&sess.app_s_key)?,
+        )?),
+        session_key_id: pl.session_key_id.clone(),
+    })
+}
+
+// Returns the KEK label to use for wrapping the network session-keys, after
+// validating that the given NetID is allowed to join the given JoinEUI.
+fn get_kek_label(net_id: NetID, join_eui: EUI64) -> Result<String> {
+    let conf = config::get();
+    if !conf.backend_interfaces.join_server.enabled {
+        return Err(Error::Disabled.into());
+    }
+
+    for allowed in &conf.backend_interfaces.join_server.allowed {
+        if allowed.net_id == net_id
+            && allowed
+                .join_eui_prefixes
+                .iter()
+                .any(|prefix| prefix.matches(join_eui))
+        {
+            return Ok(allowed.kek_label.clone());
+        }
+    }
+
+    Err(Error::ActivationDisallowed(net_id, join_eui).into())
+}
+
+async fn get_device_keys(dev_eui: &EUI64) -> Result<device_keys::DeviceKeys> {
+    match device_keys::get(dev_eui).await {
+        Ok(v) => Ok(v),
+        Err(StorageError::NotFound(_)) => Err(Error::UnknownDevEUI(*dev_eui).into()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn get_join_nonce(dk: &device_keys::DeviceKeys) -> Result<u32> {
+    let join_nonce = dk.join_nonce - 1; // this was incremented on validation
+    if join_nonce >= (1 << 24) - 1 {
+        return Err(Error::JoinNonceOverflow.into());
+    }
+    Ok(join_nonce as u32)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn get_join_accept(
+    join_type: JoinType,
+    opt_neg: bool,
+    join_eui: &EUI64,
+    dev_nonce: u16,
+    join_nonce: u32,
+    net_id: NetID,
+    dev_addr: &[u8],
+    dl_settings: &[u8],
+    rx_delay: u8,
+    cf_list: &[u8],
+    dk: &device_keys::DeviceKeys,
+) -> Result<Vec<u8>> {
+    let mut dl_settings = DLSettings::from_le_bytes(
+        dl_settings
+            .try_into()
+            .map_err(|_| anyhow!("DLSettings must be exactly 1 byte"))?,
+    );
+    dl_settings.opt_neg = opt_neg;
+
+    let mut phy = PhyPayload {
+        mhdr: MHDR {
+            f_type: FType::JoinAccept,
+            major: Major::LoRaWANR1,
+        },
+        payload: Payload::JoinAccept(JoinAcceptPayload {
+            join_nonce,
+            home_netid: net_id,
+            devaddr: DevAddr::from_slice(dev_addr).context("Decode DevAddr")?,
+            dl_settings,
+            rx_delay,
+            cflist: if cf_list.is_empty() {
+                None
+            } else {
+                Some(CFList::from_bytes(
+                    cf_list
+                        .try_into()
+                        .map_err(|_| anyhow!("CFList must be exactly 16 bytes"))?,
+                )?)
+            },
+        }),
+        mic: None,
+    };
+
+    let dev_eui = dk.dev_eui;
+
+    if join_type == JoinType::Join {
+        if opt_neg {
+            let js_int_key = keys::get_js_int_key(&dev_eui, &dk.nwk_key)?;
+            phy.set_join_accept_mic(join_type, join_eui, dev_nonce, &js_int_key)?;
+        } else {
+            phy.set_join_accept_mic(join_type, join_eui, dev_nonce, &dk.nwk_key)?;
+        }
+        phy.encrypt_join_accept_payload(&dk.nwk_key)?;
+    } else {
+        let js_int_key = keys::get_js_int_key(&dev_eui, &dk.nwk_key)?;
+        let js_enc_key = keys::get_js_enc_key(&dev_eui, &dk.nwk_key)?;
+        phy.set_join_accept_mic(join_type, join_eui, dev_nonce, &js_int_key)?;
+        phy.encrypt_join_accept_payload(&js_enc_key)?;
+    }
+
+    phy.to_vec()
+}
+
+fn get_session_keys(
+    opt_neg: bool,
+    dk: &device_keys::DeviceKeys,
+    net_id: NetID,
+    join_eui: &EUI64,
+    join_nonce: u32,
+    dev_nonce: u16,
+) -> Result<SessionKeys> {
+    let f_nwk_s_int_key = keys::get_f_nwk_s_int_key(
+        opt_neg,
+        &dk.nwk_key,
+        &net_id,
+        join_eui,
+        join_nonce,
+        dev_nonce,
+    )?;
+
+    Ok(SessionKeys {
+        f_nwk_s_int_key,
+        s_nwk_s_int_key: match opt_neg {
+            true => keys::get_s_nwk_s_int_key(
+                opt_neg,
+                &dk.nwk_key,
+                &net_id,
+                join_eui,
+                join_nonce,
+                dev_nonce,
+            )?,
+            false => f_nwk_s_int_key,
+        },
+        nwk_s_enc_key: match opt_neg {
+            true => keys::get_nwk_s_enc_key(
+                opt_neg,
+                &dk.nwk_key,
+                &net_id,
+                join_eui,
+                join_nonce,
+                dev_nonce,
+            )?,
+            false => f_nwk_s_int_key,
+        },
+        app_s_key: match opt_neg {
+            true => keys::get_app_s_key(
+                opt_neg,
+                &dk.app_key,
+                &net_id,
+                join_eui,
+                join_nonce,
+                dev_nonce,
+            )?,
+            false => keys::get_app_s_key(
+                opt_neg,
+                &dk.nwk_key,
+                &net_id,
+                join_eui,
+                join_nonce,
+                dev_nonce,
+            )?,
+        },
+    })
+}
+
+async fn save_session(net_id: NetID, dev_eui: EUI64, app_s_key: AES128Key) -> Result<Vec<u8>> {
+    let conf = config::get();
+    let session_key_id = Uuid::new_v4().as_bytes().to_vec();
+
+    join_server_session::save(
+        &internal::JoinServerSession {
+            session_key_id: session_key_id.clone(),
+            net_id: net_id.to_vec(),
+            dev_eui: dev_eui.to_vec(),
+            app_s_key: app_s_key.to_vec(),
+        },
+        conf.backend_interfaces.join_server.session_key_lifetime,
+    )
+    .await?;
+
+    Ok(session_key_id)
+}
+
+#[cfg(test)]
+pub mod test {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::storage::device_keys::test::create_device_keys;
+    use crate::test;
+    use lrwn::EUI64Prefix;
+
+    fn set_config(kek_label: &str) {
+        let mut conf = (*config::get()).clone();
+        conf.keks.push(config::Kek {
+            label: "kek".into(),
+            kek: AES128Key::from_bytes([1; 16]),
+        });
+        conf.backend_interfaces.join_server = config::BackendInterfacesJoinServer {
+            enabled: true,
+            allowed: vec![config::BackendInterfacesJoinServerAllowed {
+                net_id: NetID::from_str("010203").unwrap(),
+                join_eui_prefixes: vec![EUI64Prefix::new([1, 2, 3, 4, 5, 6, 7, 0], 56)],
+                kek_label: kek_label.into(),
+            }],
+            ..Default::default()
+        };
+        config::set(conf);
+    }
+
+    fn get_join_req_payload(
+        net_id: NetID,
+        join_eui: EUI64,
+        mac_version: &str,
+        dk: &device_keys::DeviceKeys,
+    ) -> backend::JoinReqPayload {
+        let mut phy = PhyPayload {
+            mhdr: MHDR {
+                f_type: FType::JoinRequest,
+                major: Major::LoRaWANR1,
+            },
+            payload: Payload::JoinRequest(lrwn::JoinRequestPayload {
+                join_eui,
+                dev_eui: dk.dev_eui,
+                dev_nonce: 123,
+            }),
+            mic: None,
+        };
+        phy.set_join_request_mic(&dk.nwk_key).unwrap();
+
+        backend::JoinReqPayload {
+            base: backend::BasePayload {
+                sender_id: net_id.to_vec(),
+                receiver_id: join_eui.to_vec(),
+                message_type: backend::MessageType::JoinReq,
+                ..Default::default()
+            },
+            mac_version: mac_version.into(),
+            phy_payload: phy.to_vec().unwrap(),
+            dev_eui: dk.dev_eui.to_vec(),
+            dev_addr: vec![1, 2, 3, 4],
+            dl_settings: vec![0],
+            rx_delay: 1,
+            cf_list: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_join_req() {
+        let _guard = test::prepare().await;
+        set_config("kek");
+
+        let mut dk = create_device_keys(None).await;
+        dk.nwk_key = AES128Key::from_bytes([2; 16]);
+        dk.app_key = AES128Key::from_bytes([3; 16]);
+        let dk = device_keys::update(dk).await.unwrap();
+
+        let net_id = NetID::from_str("010203").unwrap();
+        let join_eui = EUI64::from_str("0102030405060708").unwrap();
+
+        // Not allowed NetID.
+        let pl = get_join_req_payload(NetID::from_str("030201").unwrap(), join_eui, "1.0.3", &dk);
+        let err = _handle_join_req(pl).await.unwrap_err();
+        assert_eq!(
+            backend::ResultCode::ActivationDisallowed,
+            err_to_result_code(err)
+        );
+
+        // Not allowed JoinEUI.
+        let pl = get_join_req_payload(
+            net_id,
+            EUI64::from_str("0807060504030201").unwrap(),
+            "1.0.3",
+            &dk,
+        );
+        let err = _handle_join_req(pl).await.unwrap_err();
+        assert_eq!(
+            backend::ResultCode::ActivationDisallowed,
+            err_to_result_code(err)
+        );
+
+        // Invalid MIC.
+        let mut pl = get_join_req_payload(net_id, join_eui, "1.0.3", &dk);
+        let len = pl.phy_payload.len();
+        pl.phy_payload[len - 1] ^= 0xff;
+        let err = _handle_join_req(pl).await.unwrap_err();
+        assert_eq!(backend::ResultCode::MICFailed, err_to_result_code(err));
+
+        // LoRaWAN 1.0.
+        let pl = get_join_req_payload(net_id, join_eui, "1.0.3", &dk);
+        let ans = _handle_join_req(pl.clone()).await.unwrap();
+        assert_eq!(backend::ResultCode::Success, ans.base.result.result_code);
+        assert_eq!(backend::MessageType::JoinAns, ans.base.base.message_type);
+        assert!(ans.f_nwk_s_int_key.is_none());
+
+        let nwk_s_key = ans.nwk_s_key.as_ref().unwrap();
+        assert_eq!("kek", nwk_s_key.kek_label);
+        assert_eq!(
+            keys::get_f_nwk_s_int_key(false, &dk.nwk_key, &net_id, &join_eui, 0, 123).unwrap(),
+            keywrap::unwrap(nwk_s_key).unwrap()
+        );
+
+        let mut join_accept = PhyPayload::from_slice(&ans.phy_payload).unwrap();
+        join_accept
+            .decrypt_join_accept_payload(&dk.nwk_key)
+            .unwrap();
+        assert!(
+            join_accept
+                .validate_join_accept_mic(JoinType::Join, &join_eui, 123, &dk.nwk_key)
+                .unwrap()
+        );
+        if let Payload::JoinAccept(ja) = &join_accept.payload {
+            assert_eq!(net_id, ja.home_netid);
+            assert_eq!(DevAddr::from_be_bytes([1, 2, 3, 4]), ja.devaddr);
+        } else {
+            panic!("Expected JoinAccept payload");
+        }
+
+        // DevNonce replay.
+        let err = _handle_join_req(pl).await.unwrap_err();
+        assert_eq!(backend::ResultCode::JoinReqFailed, err_to_result_code(err));
+
+        // AppSKeyReq.
+        let ans = _handle_app_s_key_req(backend::AppSKeyReqPayload {
+            base: backend::BasePayload {
+                sender_id: net_id.to_vec(),
+                message_type: backend::MessageType::AppSKeyReq,
+                ..Default::default()
+            },
+            dev_eui: dk.dev_eui.to_vec(),
+            session_key_id: ans.session_key_id.clone(),
+        })
+        .await
+        .unwrap();
+        assert_eq!(backend::MessageType::AppSKeyAns, ans.base.base.message_type);
+        assert_eq!(
+            keys::get_app_s_key(false, &dk.nwk_key, &net_id, &join_eui, 0, 123).unwrap(),
+            keywrap::unwrap(ans.app_s_key.as_ref().unwrap()).unwrap()
+        );
+
+        // AppSKeyReq from other NetID.
+        let err = _handle_app_s_key_req(backend::AppSKeyReqPayload {
+            base: backend::BasePayload {
+                sender_id: vec![3, 2, 1],
+                message_type: backend::MessageType::AppSKeyReq,
+                ..Default::default()
+            },
+            dev_eui: dk.dev_eui.to_vec(),
+            session_key_id: ans.session_key_id.clone(),
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(backend::ResultCode::UnknownDevEUI, err_to_result_code(err));
+
+        // LoRaWAN 1.1.
+        device_keys::test::reset_nonces(&dk.dev_eui).await.unwrap();
+        let pl = get_join_req_payload(net_id, join_eui, "1.1.0", &dk);
+        let ans = _handle_join_req(pl).await.unwrap();
+        assert!(ans.nwk_s_key.is_none());
+        assert_eq!(
+            keys::get_s_nwk_s_int_key(true, &dk.nwk_key, &net_id, &join_eui, 0, 123).unwrap(),
+            keywrap::unwrap(ans.s_nwk_s_int_key.as_ref().unwrap()).unwrap()
+        );
+        assert_eq!(
+            keys::get_app_s_key(true, &dk.app_key, &net_id, &join_eui, 0, 123).unwrap(),
+            keywrap::unwrap(ans.app_s_key.as_ref().unwrap()).unwrap()
+        );
+
+        let js_int_key = keys::get_js_int_key(&dk.dev_eui, &dk.nwk_key).unwrap();
+        let mut join_accept = PhyPayload::from_slice(&ans.phy_payload).unwrap();
+        join_accept
+            .decrypt_join_accept_payload(&dk.nwk_key)
+            .unwrap();
+        assert!(
+            join_accept
+                .validate_join_accept_mic(JoinType::Join, &join_eui, 123, &js_int_key)
+                .unwrap()
+        );
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use axum::{
@@ -28,7 +28,8 @@ use crate::downlink::data_fns;
 use crate::helpers::errors::PrintFullError;
 use crate::helpers::tls::{get_root_certs, load_cert, load_key};
 use crate::storage::{
-    device, error::Error as StorageError, get_async_redis_conn, passive_roaming, redis_key,
+    device, error::Error as StorageError, get_async_redis_conn, helpers::get_all_device_data,
+    passive_roaming, redis_key, tenant,
 };
 use crate::uplink::{
     RoamingMetaData, UplinkFrameSet, data_sns, error::Error as UplinkError, helpers, join_sns,
@@ -39,6 +40,14 @@ use chirpstack_api::stream as stream_pb;
 use lrwn::region::CommonName;
 use lrwn::{AES128Key, EUI64, NetID};
 
+mod handover_roaming;
+mod join_server;
+
+tokio::task_local! {
+    // Time at which the request was received, used to log the request latency.
+    static REQUEST_START: Instant;
+}
+
 pub async fn setup() -> Result<()> {
     let conf = config::get();
     if conf.backend_interfaces.bind.is_empty() {
@@ -94,7 +103,9 @@ pub async fn handle_request(b: Bytes) -> Response {
     };
 
     let span = span!(Level::INFO, "request", sender_id = %hex::encode(&bp.sender_id), receiver_id = %hex::encode(&bp.receiver_id), message_type = ?bp.message_type, transaction_id = bp.transaction_id);
-    _handle_request(bp, b).instrument(span).await
+    REQUEST_START
+        .scope(Instant::now(), _handle_request(bp, b).instrument(span))
+        .await
 }
 
 pub async fn _handle_request(bp: BasePayload, b: Vec<u8>) -> Response {
@@ -159,6 +170,38 @@ pub async fn _handle_request(bp: BasePayload, b: Vec<u8>) -> Response {
         }
     };
 
+    // In case the ReceiverID is a NetID, it must match the NetID of one of the tenants.
+    if bp.receiver_id.len() == 3 {
+        let receiver_id = match NetID::from_slice(&bp.receiver_id) {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(error = %e.full(), "Error decoding ReceiverID as NetID");
+                let msg = format!("Error decoding ReceiverID: {}", e);
+                let pl = bp.to_base_payload_result(backend::ResultCode::MalformedRequest, &msg);
+                log_request_response(&bp, &b, &pl).await;
+                return Json(&pl).into_response();
+            }
+        };
+
+        match tenant::get_net_ids().await {
+            Ok(net_ids) => {
+                if !net_ids.contains(&receiver_id) {
+                    warn!("Unknown ReceiverID");
+                    let msg = format!("Unknown ReceiverID: {}", receiver_id);
+                    let pl = bp.to_base_payload_result(backend::ResultCode::UnknownReceiver, &msg);
+                    log_request_response(&bp, &b, &pl).await;
+                    return Json(&pl).into_response();
+                }
+            }
+            Err(e) => {
+                error!(error = %e.full(), "Get tenant NetIDs error");
+                let pl = bp.to_base_payload_result(backend::ResultCode::Other, &e.to_string());
+                log_request_response(&bp, &b, &pl).await;
+                return Json(&pl).into_response();
+            }
+        }
+    }
+
     // Request is an async answer.
     if bp.is_answer() {
         tokio::spawn(async move {
@@ -174,6 +217,16 @@ pub async fn _handle_request(bp: BasePayload, b: Vec<u8>) -> Response {
         MessageType::PRStopReq => handle_pr_stop_req(sender_client, bp, &b).await,
         MessageType::XmitDataReq => handle_xmit_data_req(sender_client, bp, &b).await,
         MessageType::HomeNSReq => handle_home_ns_req(sender_client, bp, &b).await,
+        MessageType::ProfileReq => {
+            handover_roaming::handle_profile_req(sender_client, bp, &b).await
+        }
+        MessageType::HRStartReq => {
+            handover_roaming::handle_hr_start_req(sender_client, bp, &b).await
+        }
+        MessageType::HRStopReq => handover_roaming::handle_hr_stop_req(sender_client, bp, &b).await,
+        MessageType::JoinReq => join_server::handle_join_req(sender_client, bp, &b).await,
+        MessageType::RejoinReq => join_server::handle_rejoin_req(sender_client, bp, &b).await,
+        MessageType::AppSKeyReq => join_server::handle_app_s_key_req(sender_client, bp, &b).await,
         // Unknown message
         _ => (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -198,6 +251,9 @@ fn err_to_result_code(e: anyhow::Error) -> backend::ResultCode {
             _ => backend::ResultCode::Other,
         };
     }
+    if let Some(e) = e.downcast_ref::<join_server::Error>() {
+        return e.result_code();
+    }
     if let Some(e) = e.downcast_ref::<UplinkError>() {
         return match e {
             UplinkError::RoamingIsNotAllowed => backend::ResultCode::DevRoamingDisallowed,
@@ -251,12 +307,32 @@ async fn handle_pr_start_req(
 async fn _handle_pr_start_req(b: &[u8]) -> Result<backend::PRStartAnsPayload> {
     let pl: backend::PRStartReqPayload = serde_json::from_slice(b)?;
     let phy = lrwn::PhyPayload::from_slice(&pl.phy_payload)?;
+    let sender_id = NetID::from_slice(&pl.base.sender_id)?;
+    let phy_payload = pl.phy_payload.clone();
+    let ul_meta_data = pl.ul_meta_data.clone();
 
-    if phy.mhdr.f_type == lrwn::FType::JoinRequest {
-        _handle_pr_start_req_join(pl, phy).await
+    let ans = if phy.mhdr.f_type == lrwn::FType::JoinRequest {
+        _handle_pr_start_req_join(pl, phy).await?
     } else {
-        _handle_pr_start_req_data(pl, phy).await
+        _handle_pr_start_req_data(pl, phy).await?
+    };
+
+    if ans.base.result.result_code == backend::ResultCode::Success {
+        roaming::record_uplink(sender_id, backend::Role::SNS, &phy_payload, &ul_meta_data).await;
+
+        if let Some(dl_meta_data) = &ans.dl_meta_data {
+            roaming::record_downlink(
+                sender_id,
+                backend::Role::SNS,
+                &ans.phy_payload,
+                dl_meta_data,
+                Some(ul_meta_data.recv_time),
+            )
+            .await;
+        }
     }
+
+    Ok(ans)
 }
 
 async fn _handle_pr_start_req_join(
@@ -274,6 +350,7 @@ async fn _handle_pr_start_req_join(
         dr,
         ch: helpers::get_uplink_ch(&region_config_id, tx_info.frequency, dr)?,
         phy_payload: phy,
+        phy_payload_b: pl.phy_payload.clone(),
         tx_info,
         rx_info_set: rx_info,
         gateway_private_up_map: HashMap::new(),
@@ -310,6 +387,7 @@ async fn _handle_pr_start_req_data(
         dr,
         ch: helpers::get_uplink_ch(&region_config_id, tx_info.frequency, dr)?,
         phy_payload: phy,
+        phy_payload_b: pl.phy_payload.clone(),
         tx_info,
         rx_info_set: rx_info,
         gateway_private_up_map: HashMap::new(),
@@ -325,7 +403,13 @@ async fn _handle_pr_start_req_data(
     };
 
     // get device-session
-    let d = device::get_for_phypayload(&mut ufs.phy_payload, ufs.dr, ufs.ch as u8).await?;
+    let d = device::get_for_phypayload(
+        &ufs.phy_payload_b,
+        &mut ufs.phy_payload,
+        ufs.dr,
+        ufs.ch as u8,
+    )
+    .await?;
     let pr_lifetime = roaming::get_passive_roaming_lifetime(sender_id)?;
     let kek_label = roaming::get_passive_roaming_kek_label(sender_id)?;
     let ds = d.get_device_session()?;
@@ -455,7 +539,11 @@ async fn handle_xmit_data_req(
     if sender_client.is_async() {
         let b = b.to_vec();
         task::spawn(async move {
-            let sender_role = if pl.ul_meta_data.is_some() {
+            let sender_role = if pl.ul_meta_data.is_some()
+                && !handover_roaming::is_xmit_data_req_uplink(&pl)
+                    .await
+                    .unwrap_or_default()
+            {
                 backend::Role::FNS
             } else {
                 backend::Role::SNS
@@ -496,7 +584,12 @@ async fn handle_xmit_data_req(
 async fn _handle_xmit_data_req(
     pl: backend::XmitDataReqPayload,
 ) -> Result<backend::XmitDataAnsPayload> {
-    if let Some(ul_meta_data) = &pl.ul_meta_data {
+    if let Some(ul_meta_data) = &pl.ul_meta_data
+        && handover_roaming::is_xmit_data_req_uplink(&pl).await?
+    {
+        // Handover-roaming, the serving NS only forwards the FRMPayload.
+        handover_roaming::handle_xmit_data_req_uplink(&pl, ul_meta_data).await?;
+    } else if let Some(ul_meta_data) = &pl.ul_meta_data {
         let rx_info = roaming::ul_meta_data_to_rx_info(ul_meta_data)?;
         let tx_info = roaming::ul_meta_data_to_tx_info(ul_meta_data)?;
         let region_common_name = CommonName::from_str(&ul_meta_data.rf_region)?;
@@ -509,6 +602,7 @@ async fn _handle_xmit_data_req(
             dr,
             ch: helpers::get_uplink_ch(&region_config_id, tx_info.frequency, dr)?,
             phy_payload: phy,
+            phy_payload_b: pl.phy_payload.clone(),
             tx_info,
             rx_info_set: rx_info,
             gateway_private_up_map: HashMap::new(),
@@ -524,10 +618,27 @@ async fn _handle_xmit_data_req(
         };
 
         data_sns::Data::handle(ufs).await?;
+
+        roaming::record_uplink(
+            NetID::from_slice(&pl.base.sender_id)?,
+            backend::Role::SNS,
+            &pl.phy_payload,
+            ul_meta_data,
+        )
+        .await;
     }
 
     if let Some(dl_meta_data) = &pl.dl_meta_data {
         data_fns::Data::handle(pl.clone(), dl_meta_data.clone()).await?;
+
+        roaming::record_downlink(
+            NetID::from_slice(&pl.base.sender_id)?,
+            backend::Role::FNS,
+            &pl.phy_payload,
+            dl_meta_data,
+            None,
+        )
+        .await;
     }
 
     Ok(backend::XmitDataAnsPayload {
@@ -591,11 +702,24 @@ async fn handle_home_ns_req(
 async fn _handle_home_ns_req(pl: backend::HomeNSReqPayload) -> Result<backend::HomeNSAnsPayload> {
     let conf = config::get();
 
+    // In case the device is known, the HNetID is the NetID of the tenant of the device.
+    let tenant = match EUI64::from_slice(&pl.dev_eui) {
+        Ok(dev_eui) => get_all_device_data(dev_eui)
+            .await
+            .ok()
+            .map(|(_, _, t, _)| t),
+        Err(_) => None,
+    };
+    let h_net_id = match tenant {
+        Some(t) => t.get_net_id(),
+        None => conf.network.net_id,
+    };
+
     Ok(backend::HomeNSAnsPayload {
         base: pl
             .base
             .to_base_payload_result(backend::ResultCode::Success, ""),
-        h_net_id: conf.network.net_id.to_vec(),
+        h_net_id: h_net_id.to_vec(),
     })
 }
 
@@ -703,6 +827,9 @@ where
         response_body: serde_json::to_string(resp).unwrap_or_default(),
         result_code: format!("{:?}", resp.base_payload().result.result_code),
         time: Some(Utc::now().into()),
+        latency: REQUEST_START
+            .try_with(|v| v.elapsed().as_millis() as u32)
+            .unwrap_or_default(),
         ..Default::default()
     };
 
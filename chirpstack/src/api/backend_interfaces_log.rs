@@ -0,0 +1,417 @@
+use std::str::FromStr;
+
+use chrono::{TimeDelta, Utc};
+
+use chirpstack_api::api;
+use chirpstack_api::api::backend_interfaces_log_service_server::BackendInterfacesLogService;
+use chirpstack_api::tonic::{self, Request, Response, Status};
+use uuid::Uuid;
+
+use super::auth::validator;
+use super::error::ToStatus;
+use super::helpers;
+use crate::api::backend::get_async_receiver;
+use crate::backend::roaming;
+use crate::config;
+use crate::storage::backend_interfaces_log;
+use lrwn::{EUI64, NetID};
+
+pub struct BackendInterfacesLog {
+    validator: validator::RequestValidator,
+}
+
+impl BackendInterfacesLog {
+    pub fn new(validator: validator::RequestValidator) -> Self {
+        BackendInterfacesLog { validator }
+    }
+}
+
+#[tonic::async_trait]
+impl BackendInterfacesLogService for BackendInterfacesLog {
+    async fn get(
+        &self,
+        request: Request<api::GetBackendInterfacesLogRequest>,
+    ) -> Result<Response<api::GetBackendInterfacesLogResponse>, Status> {
+        let req = request.get_ref();
+        let id = Uuid::from_str(&req.id).map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateBackendInterfacesAccess::new(validator::Flag::Read),
+            )
+            .await?;
+
+        let l = backend_interfaces_log::get(&id)
+            .await
+            .map_err(|e| e.status())?;
+
+        let mut resp = Response::new(api::GetBackendInterfacesLogResponse {
+            backend_interfaces_log: Some(api::BackendInterfacesLog {
+                id: l.id.to_string(),
+                created_at: Some(helpers::datetime_to_prost_timestamp(&l.created_at)),
+                sender_id: l.sender_id.clone(),
+                receiver_id: l.receiver_id.clone(),
+                transaction_id: l.transaction_id as u32,
+                message_type: l.message_type.clone(),
+                result_code: l.result_code.clone(),
+                target_role: l.target_role.clone(),
+                latency: l.latency as u32,
+                dev_eui: l.dev_eui.map(|v| v.to_string()).unwrap_or_default(),
+                request_body: l.request_body.clone(),
+                request_error: l.request_error.clone(),
+                response_body: l.response_body.clone(),
+            }),
+        });
+        resp.metadata_mut()
+            .insert("x-log-backend_interfaces_log_id", req.id.parse().unwrap());
+
+        Ok(resp)
+    }
+
+    async fn list(
+        &self,
+        request: Request<api::ListBackendInterfacesLogRequest>,
+    ) -> Result<Response<api::ListBackendInterfacesLogResponse>, Status> {
+        let req = request.get_ref();
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateBackendInterfacesAccess::new(validator::Flag::List),
+            )
+            .await?;
+
+        let filters = backend_interfaces_log::Filters {
+            net_id: if req.net_id.is_empty() {
+                None
+            } else {
+                Some(NetID::from_str(&req.net_id).map_err(|e| e.status())?)
+            },
+            dev_eui: if req.dev_eui.is_empty() {
+                None
+            } else {
+                Some(EUI64::from_str(&req.dev_eui).map_err(|e| e.status())?)
+            },
+            message_type: if req.message_type.is_empty() {
+                None
+            } else {
+                Some(req.message_type.clone())
+            },
+        };
+
+        let count = backend_interfaces_log::get_count(&filters)
+            .await
+            .map_err(|e| e.status())?;
+        let items = backend_interfaces_log::list(req.limit as i64, req.offset as i64, &filters)
+            .await
+            .map_err(|e| e.status())?;
+
+        Ok(Response::new(api::ListBackendInterfacesLogResponse {
+            total_count: count as u32,
+            result: items
+                .iter()
+                .map(|l| api::BackendInterfacesLogListItem {
+                    id: l.id.to_string(),
+                    created_at: Some(helpers::datetime_to_prost_timestamp(&l.created_at)),
+                    sender_id: l.sender_id.clone(),
+                    receiver_id: l.receiver_id.clone(),
+                    transaction_id: l.transaction_id as u32,
+                    message_type: l.message_type.clone(),
+                    result_code: l.result_code.clone(),
+                    target_role: l.target_role.clone(),
+                    latency: l.latency as u32,
+                    dev_eui: l.dev_eui.map(|v| v.to_string()).unwrap_or_default(),
+                    request_error: l.request_error.clone(),
+                })
+                .collect(),
+        }))
+    }
+
+    async fn replay_xmit_data_req(
+        &self,
+        request: Request<api::ReplayXmitDataReqRequest>,
+    ) -> Result<Response<api::ReplayXmitDataReqResponse>, Status> {
+        let req = request.get_ref();
+        let id = Uuid::from_str(&req.id).map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateBackendInterfacesAccess::new(validator::Flag::Update),
+            )
+            .await?;
+
+        let l = backend_interfaces_log::get(&id)
+            .await
+            .map_err(|e| e.status())?;
+
+        // Only outgoing requests have a target role.
+        if l.message_type != "XmitDataReq" || l.target_role.is_empty() {
+            return Err(Status::invalid_argument(
+                "Only outgoing XmitDataReq requests can be replayed",
+            ));
+        }
+        if !l.is_failed() {
+            return Err(Status::failed_precondition(
+                "Only failed XmitDataReq requests can be replayed",
+            ));
+        }
+
+        let max_age = config::get()
+            .monitoring
+            .backend_interfaces_log_replay_max_age;
+        if !max_age.is_zero()
+            && let Ok(max_age) = TimeDelta::from_std(max_age)
+            && Utc::now() - l.created_at > max_age
+        {
+            return Err(Status::failed_precondition(
+                "XmitDataReq request exceeds the max. replay age",
+            ));
+        }
+
+        let target_role = match l.target_role.as_ref() {
+            "FNS" => backend::Role::FNS,
+            "SNS" => backend::Role::SNS,
+            "HNS" => backend::Role::HNS,
+            _ => {
+                return Err(Status::internal(format!(
+                    "Unexpected target role: {}",
+                    l.target_role
+                )));
+            }
+        };
+
+        let mut pl: backend::XmitDataReqPayload = serde_json::from_str(&l.request_body)
+            .map_err(|e| Status::internal(format!("Decode request body error: {}", e)))?;
+        pl.base.transaction_id = rand::random();
+
+        #[cfg(test)]
+        {
+            pl.base.transaction_id = 1234;
+        }
+
+        let net_id = NetID::from_str(&l.receiver_id).map_err(|e| e.status())?;
+        let client = roaming::get(&net_id).await.map_err(|e| e.status())?;
+        let async_receiver = match client.is_async() {
+            false => None,
+            true => Some(
+                get_async_receiver(pl.base.transaction_id, client.get_async_timeout())
+                    .await
+                    .map_err(|e| e.status())?,
+            ),
+        };
+
+        client
+            .xmit_data_req(target_role, &mut pl, async_receiver)
+            .await
+            .map_err(|e| Status::unavailable(format!("{:#}", e)))?;
+
+        let mut resp = Response::new(api::ReplayXmitDataReqResponse {
+            transaction_id: pl.base.transaction_id,
+        });
+        resp.metadata_mut()
+            .insert("x-log-backend_interfaces_log_id", req.id.parse().unwrap());
+        resp.metadata_mut()
+            .insert("x-log-net_id", net_id.to_string().parse().unwrap());
+
+        Ok(resp)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::api::auth::AuthID;
+    use crate::api::auth::validator::RequestValidator;
+    use crate::storage::{roaming_agreement, user};
+    use crate::test;
+    use httpmock::prelude::*;
+
+    #[tokio::test]
+    async fn test_backend_interfaces_log() {
+        let _guard = test::prepare().await;
+        let server = MockServer::start();
+
+        // setup admin user
+        let u = user::User {
+            is_admin: true,
+            is_active: true,
+            email: "admin@admin".into(),
+            email_verified: true,
+            ..Default::default()
+        };
+        let u = user::create(u).await.unwrap();
+
+        // setup roaming agreement
+        roaming_agreement::create(roaming_agreement::RoamingAgreement {
+            net_id: NetID::from_be_bytes([1, 2, 3]),
+            name: "test-ra".into(),
+            server: server.url("/"),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        roaming::reload().await.unwrap();
+
+        // setup log items
+        let xmit_data_req = backend::XmitDataReqPayload {
+            base: backend::BasePayload {
+                sender_id: vec![0, 0, 0],
+                receiver_id: vec![1, 2, 3],
+                message_type: backend::MessageType::XmitDataReq,
+                transaction_id: 1,
+                ..Default::default()
+            },
+            phy_payload: vec![1, 2, 3],
+            ..Default::default()
+        };
+        let l_failed =
+            backend_interfaces_log::create(backend_interfaces_log::BackendInterfacesLog {
+                sender_id: "000000".into(),
+                receiver_id: "010203".into(),
+                transaction_id: 1,
+                message_type: "XmitDataReq".into(),
+                target_role: "FNS".into(),
+                request_body: serde_json::to_string(&xmit_data_req).unwrap(),
+                request_error: "Async timeout".into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let l_expired =
+            backend_interfaces_log::create(backend_interfaces_log::BackendInterfacesLog {
+                created_at: Utc::now() - TimeDelta::days(2),
+                sender_id: "000000".into(),
+                receiver_id: "010203".into(),
+                transaction_id: 3,
+                message_type: "XmitDataReq".into(),
+                target_role: "FNS".into(),
+                request_body: serde_json::to_string(&xmit_data_req).unwrap(),
+                request_error: "Async timeout".into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let l_ok = backend_interfaces_log::create(backend_interfaces_log::BackendInterfacesLog {
+            sender_id: "040506".into(),
+            receiver_id: "000000".into(),
+            transaction_id: 2,
+            message_type: "HomeNSReq".into(),
+            result_code: "Success".into(),
+            dev_eui: Some(EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8])),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        // setup api
+        let service = BackendInterfacesLog::new(RequestValidator::new());
+
+        // get
+        let get_req = api::GetBackendInterfacesLogRequest {
+            id: l_ok.id.to_string(),
+        };
+        let mut get_req = Request::new(get_req);
+        get_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let get_resp = service.get(get_req).await.unwrap();
+        let get_resp = get_resp.get_ref().backend_interfaces_log.as_ref().unwrap();
+        assert_eq!("HomeNSReq", get_resp.message_type);
+        assert_eq!("0102030405060708", get_resp.dev_eui);
+
+        // list by NetID
+        let list_req = api::ListBackendInterfacesLogRequest {
+            limit: 10,
+            net_id: "010203".into(),
+            ..Default::default()
+        };
+        let mut list_req = Request::new(list_req);
+        list_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let list_resp = service.list(list_req).await.unwrap();
+        assert_eq!(2, list_resp.get_ref().total_count);
+        assert_eq!(l_failed.id.to_string(), list_resp.get_ref().result[0].id);
+
+        // list by DevEUI
+        let list_req = api::ListBackendInterfacesLogRequest {
+            limit: 10,
+            dev_eui: "0102030405060708".into(),
+            ..Default::default()
+        };
+        let mut list_req = Request::new(list_req);
+        list_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let list_resp = service.list(list_req).await.unwrap();
+        assert_eq!(1, list_resp.get_ref().total_count);
+        assert_eq!(l_ok.id.to_string(), list_resp.get_ref().result[0].id);
+
+        // replay non XmitDataReq
+        let replay_req = api::ReplayXmitDataReqRequest {
+            id: l_ok.id.to_string(),
+        };
+        let mut replay_req = Request::new(replay_req);
+        replay_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        assert!(service.replay_xmit_data_req(replay_req).await.is_err());
+
+        // replay expired XmitDataReq
+        let replay_req = api::ReplayXmitDataReqRequest {
+            id: l_expired.id.to_string(),
+        };
+        let mut replay_req = Request::new(replay_req);
+        replay_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        assert!(service.replay_xmit_data_req(replay_req).await.is_err());
+
+        // replay failed XmitDataReq
+        let mut mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_obj(&backend::XmitDataReqPayload {
+                    base: backend::BasePayload {
+                        sender_id: vec![0, 0, 0],
+                        receiver_id: vec![1, 2, 3],
+                        message_type: backend::MessageType::XmitDataReq,
+                        transaction_id: 1234,
+                        ..Default::default()
+                    },
+                    phy_payload: vec![1, 2, 3],
+                    ..Default::default()
+                });
+            then.json_body_obj(&backend::XmitDataAnsPayload {
+                base: backend::BasePayloadResult {
+                    base: backend::BasePayload {
+                        sender_id: vec![1, 2, 3],
+                        receiver_id: vec![0, 0, 0],
+                        message_type: backend::MessageType::XmitDataAns,
+                        transaction_id: 1234,
+                        ..Default::default()
+                    },
+                    result: backend::ResultPayload {
+                        result_code: backend::ResultCode::Success,
+                        ..Default::default()
+                    },
+                },
+            });
+        });
+
+        let replay_req = api::ReplayXmitDataReqRequest {
+            id: l_failed.id.to_string(),
+        };
+        let mut replay_req = Request::new(replay_req);
+        replay_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let replay_resp = service.replay_xmit_data_req(replay_req).await.unwrap();
+        assert_eq!(1234, replay_resp.get_ref().transaction_id);
+
+        mock.assert();
+        mock.delete();
+    }
+}
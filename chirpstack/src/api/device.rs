@@ -19,9 +19,12 @@ use crate::storage::{
     device::{self, DeviceClass},
     device_keys, device_profile, device_queue,
     error::Error as StorageError,
-    fields, metrics, tenant,
+    fields,
+    helpers::get_all_device_data,
+    metrics, tenant,
 };
-use crate::{codec, devaddr::get_random_dev_addr};
+use crate::{applayer, codec, devaddr::allocate_dev_addr, maccommand::device_mode_ind, manifest};
+use lrwn::applayer::certification;
 
 pub struct Device {
     validator: validator::RequestValidator,
@@ -136,6 +139,13 @@ impl DeviceService for Device {
                 false => None,
             },
             class_enabled: d.enabled_class.to_proto().into(),
+            firmware_status: d.app_layer_params.ts006_dev_version.as_ref().map(|v| {
+                api::DeviceFirmwareStatus {
+                    fw_version: v.fw_version,
+                    hw_version: v.hw_version,
+                    reported_at: Some(helpers::datetime_to_prost_timestamp(&v.reported_at)),
+                }
+            }),
         });
         resp.metadata_mut()
             .insert("x-log-dev_eui", req.dev_eui.parse().unwrap());
@@ -496,6 +506,54 @@ impl DeviceService for Device {
         Ok(resp)
     }
 
+    async fn import_manifest(
+        &self,
+        request: Request<api::ImportDeviceManifestRequest>,
+    ) -> Result<Response<api::ImportDeviceManifestResponse>, Status> {
+        let req = request.get_ref();
+        let app_id = Uuid::from_str(&req.application_id).map_err(|e| e.status())?;
+        let dp_id = Uuid::from_str(&req.device_profile_id).map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateDevicesAccess::new(validator::Flag::Create, app_id),
+            )
+            .await?;
+
+        let report = manifest::import(
+            app_id,
+            dp_id,
+            req.format().from_proto(),
+            &req.manifest,
+            &req.kek_label,
+            req.dry_run,
+        )
+        .await
+        .map_err(|e| match e.downcast_ref::<StorageError>() {
+            Some(e) => e.status(),
+            None => Status::invalid_argument(format!("{:#}", e)),
+        })?;
+
+        let mut resp = Response::new(api::ImportDeviceManifestResponse {
+            device_count: report.device_count as u32,
+            imported: report.imported,
+            items: report
+                .items
+                .into_iter()
+                .map(|i| api::ImportDeviceManifestItem {
+                    line: i.line as u32,
+                    dev_eui: i.dev_eui,
+                    error: i.error,
+                })
+                .collect(),
+        });
+        resp.metadata_mut()
+            .insert("x-log-application_id", req.application_id.parse().unwrap());
+
+        Ok(resp)
+    }
+
     async fn activate(
         &self,
         request: Request<api::ActivateDeviceRequest>,
@@ -687,6 +745,197 @@ impl DeviceService for Device {
         Ok(resp)
     }
 
+    async fn set_class(
+        &self,
+        request: Request<api::SetDeviceClassRequest>,
+    ) -> Result<Response<()>, Status> {
+        let req = request.get_ref();
+        let dev_eui = EUI64::from_str(&req.dev_eui).map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateDeviceAccess::new(validator::Flag::Update, dev_eui),
+            )
+            .await?;
+
+        let (d, app, t, dp) = get_all_device_data(dev_eui).await.map_err(|e| e.status())?;
+
+        let enabled_class = req.device_class().from_proto();
+        match enabled_class {
+            DeviceClass::A => {}
+            DeviceClass::B => {
+                return Err(Status::invalid_argument(
+                    "Class-B is enabled by the device once it is beacon locked",
+                ));
+            }
+            DeviceClass::C => {
+                if !dp.supports_class_c {
+                    return Err(Status::invalid_argument(
+                        "Device-profile does not support Class-C",
+                    ));
+                }
+            }
+        }
+
+        // This only updates the server-side device-class. There is no mac-command for a
+        // network initiated class switch, the device must be instructed by the application.
+        if d.enabled_class != enabled_class {
+            let previous_class = d.enabled_class;
+
+            // Resetting the scheduler_run_after makes sure that pending queue-items are
+            // picked up by the scheduler using the new device-class.
+            let d = device::partial_update(
+                dev_eui,
+                &device::DeviceChangeset {
+                    enabled_class: Some(enabled_class),
+                    scheduler_run_after: Some(None),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(|e| e.status())?;
+
+            device_mode_ind::class_change_event(&t, &app, &dp, &d, previous_class).await;
+        }
+
+        let mut resp = Response::new(());
+        resp.metadata_mut()
+            .insert("x-log-dev_eui", req.dev_eui.parse().unwrap());
+
+        Ok(resp)
+    }
+
+    async fn enqueue_certification_command(
+        &self,
+        request: Request<api::EnqueueCertificationCommandRequest>,
+    ) -> Result<Response<api::EnqueueDeviceQueueItemResponse>, Status> {
+        let req = request.get_ref();
+        let dev_eui = EUI64::from_str(&req.dev_eui).map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateDeviceQueueAccess::new(validator::Flag::Create, dev_eui),
+            )
+            .await?;
+
+        let (d, _, _, dp) = get_all_device_data(dev_eui).await.map_err(|e| e.status())?;
+        if dp.app_layer_params.ts009_version.is_none() {
+            return Err(Status::failed_precondition(
+                "TS009 is not enabled for the device-profile",
+            ));
+        }
+
+        let pl = match req.command() {
+            api::CertificationCommand::PackageVersionReq => {
+                certification::v1::Payload::PackageVersionReq
+            }
+            api::CertificationCommand::DutResetReq => certification::v1::Payload::DutResetReq,
+            api::CertificationCommand::DutJoinReq => certification::v1::Payload::DutJoinReq,
+            api::CertificationCommand::SwitchClassReq => {
+                certification::v1::Payload::SwitchClassReq(
+                    certification::v1::SwitchClassReqPayload {
+                        class: match req.device_class() {
+                            common::DeviceClass::ClassA => certification::v1::DeviceClass::A,
+                            common::DeviceClass::ClassB => certification::v1::DeviceClass::B,
+                            common::DeviceClass::ClassC => certification::v1::DeviceClass::C,
+                        },
+                    },
+                )
+            }
+            api::CertificationCommand::AdrBitChangeReq => {
+                certification::v1::Payload::AdrBitChangeReq(
+                    certification::v1::AdrBitChangeReqPayload {
+                        adr_enable: req.enabled,
+                    },
+                )
+            }
+            api::CertificationCommand::RegionalDutyCycleCtrlReq => {
+                certification::v1::Payload::RegionalDutyCycleCtrlReq(
+                    certification::v1::RegionalDutyCycleCtrlReqPayload {
+                        duty_cycle_enable: req.enabled,
+                    },
+                )
+            }
+            api::CertificationCommand::TxPeriodicityChangeReq => {
+                certification::v1::Payload::TxPeriodicityChangeReq(
+                    certification::v1::TxPeriodicityChangeReqPayload {
+                        periodicity: u8::try_from(req.periodicity)
+                            .map_err(|_| Status::invalid_argument("periodicity is out of range"))?,
+                    },
+                )
+            }
+            api::CertificationCommand::TxFramesCtrlReq => {
+                certification::v1::Payload::TxFramesCtrlReq(
+                    certification::v1::TxFramesCtrlReqPayload {
+                        frame_type: match req.frame_type() {
+                            api::CertificationFrameType::FrameTypeNoChange => {
+                                certification::v1::FrameType::NoChange
+                            }
+                            api::CertificationFrameType::FrameTypeUnconfirmed => {
+                                certification::v1::FrameType::Unconfirmed
+                            }
+                            api::CertificationFrameType::FrameTypeConfirmed => {
+                                certification::v1::FrameType::Confirmed
+                            }
+                        },
+                    },
+                )
+            }
+            api::CertificationCommand::EchoPayloadReq => {
+                certification::v1::Payload::EchoPayloadReq(
+                    certification::v1::EchoPayloadReqPayload {
+                        payload: req.payload.clone(),
+                    },
+                )
+            }
+            api::CertificationCommand::RxAppCntReq => certification::v1::Payload::RxAppCntReq,
+            api::CertificationCommand::RxAppCntResetReq => {
+                certification::v1::Payload::RxAppCntResetReq
+            }
+            api::CertificationCommand::LinkCheckReq => certification::v1::Payload::LinkCheckReq,
+            api::CertificationCommand::DeviceTimeReq => certification::v1::Payload::DeviceTimeReq,
+            api::CertificationCommand::PingSlotInfoReq => {
+                certification::v1::Payload::PingSlotInfoReq(
+                    certification::v1::PingSlotInfoReqPayload {
+                        periodicity: u8::try_from(req.periodicity)
+                            .map_err(|_| Status::invalid_argument("periodicity is out of range"))?,
+                    },
+                )
+            }
+            api::CertificationCommand::TxCwReq => {
+                certification::v1::Payload::TxCwReq(certification::v1::TxCwReqPayload {
+                    timeout: u16::try_from(req.timeout)
+                        .map_err(|_| Status::invalid_argument("timeout is out of range"))?,
+                    frequency: req.frequency,
+                    tx_power: i8::try_from(req.tx_power)
+                        .map_err(|_| Status::invalid_argument("tx_power is out of range"))?,
+                })
+            }
+            api::CertificationCommand::DutFport224DisableReq => {
+                certification::v1::Payload::DutFPort224DisableReq
+            }
+            api::CertificationCommand::DutVersionsReq => certification::v1::Payload::DutVersionsReq,
+        };
+
+        // Validate the payload before storing any state.
+        pl.to_vec()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let qi = applayer::certification::enqueue(&d, &dp, pl)
+            .await
+            .map_err(|e| e.status())?;
+
+        let mut resp = Response::new(api::EnqueueDeviceQueueItemResponse {
+            id: qi.id.to_string(),
+        });
+        resp.metadata_mut()
+            .insert("x-log-dev_eui", req.dev_eui.parse().unwrap());
+
+        Ok(resp)
+    }
+
     async fn get_random_dev_addr(
         &self,
         request: Request<api::GetRandomDevAddrRequest>,
@@ -732,7 +981,10 @@ impl DeviceService for Device {
         };
 
         Ok(Response::new(api::GetRandomDevAddrResponse {
-            dev_addr: get_random_dev_addr(&t.get_dev_addr_prefixes()).to_string(),
+            dev_addr: allocate_dev_addr(&t.get_dev_addr_prefixes())
+                .await
+                .map_err(|e| e.status())?
+                .to_string(),
         }))
     }
 
@@ -1175,6 +1427,21 @@ impl DeviceService for Device {
             } else {
                 None
             },
+            priority: i16::try_from(req_qi.priority)
+                .map_err(|_| Status::invalid_argument("priority must be <= 32767"))?,
+            dedup_key: if req_qi.dedup_key.is_empty() {
+                None
+            } else {
+                Some(req_qi.dedup_key.clone())
+            },
+            not_before: if let Some(not_before) = req_qi.not_before {
+                let not_before: std::time::SystemTime = not_before
+                    .try_into()
+                    .map_err(|e: prost_types::TimestampError| e.status())?;
+                Some(not_before.into())
+            } else {
+                None
+            },
             data,
             ..Default::default()
         };
@@ -1253,6 +1520,12 @@ impl DeviceService for Device {
                         let v: std::time::SystemTime = v.into();
                         v.into()
                     }),
+                    priority: qi.priority as u32,
+                    dedup_key: qi.dedup_key.clone().unwrap_or_default(),
+                    not_before: qi.not_before.map(|v| {
+                        let v: std::time::SystemTime = v.into();
+                        v.into()
+                    }),
                 })
                 .collect(),
         });
@@ -1611,6 +1884,41 @@ pub mod test {
             .unwrap();
         assert_eq!(1, get_next_f_cnt_resp.get_ref().f_cnt_down);
 
+        // set class
+        for (device_class, code) in [
+            (common::DeviceClass::ClassB, tonic::Code::InvalidArgument),
+            (common::DeviceClass::ClassC, tonic::Code::InvalidArgument),
+        ] {
+            let set_class_req = get_request(
+                &u.id,
+                api::SetDeviceClassRequest {
+                    dev_eui: "0102030405060708".into(),
+                    device_class: device_class.into(),
+                },
+            );
+            let set_class_resp = service.set_class(set_class_req).await;
+            assert_eq!(code, set_class_resp.err().unwrap().code());
+        }
+
+        device_profile::update(device_profile::DeviceProfile {
+            supports_class_c: true,
+            ..dp.clone()
+        })
+        .await
+        .unwrap();
+        let set_class_req = get_request(
+            &u.id,
+            api::SetDeviceClassRequest {
+                dev_eui: "0102030405060708".into(),
+                device_class: common::DeviceClass::ClassC.into(),
+            },
+        );
+        let _ = service.set_class(set_class_req).await.unwrap();
+        let d = device::get(&EUI64::from_str("0102030405060708").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(DeviceClass::C, d.enabled_class);
+
         // deactivate
         let deactivate_req = get_request(
             &u.id,
@@ -1814,6 +2122,40 @@ pub mod test {
         assert_eq!(1, get_queue_resp.result.len());
         assert_eq!(vec![4, 5, 6], get_queue_resp.result[0].data);
 
+        // enqueue with priority and dedup key (replaced on re-enqueue)
+        for data in [vec![7], vec![8]] {
+            let enqueue_req = get_request(
+                &u.id,
+                api::EnqueueDeviceQueueItemRequest {
+                    queue_item: Some(api::DeviceQueueItem {
+                        dev_eui: "0102030405060708".into(),
+                        f_port: 2,
+                        data,
+                        priority: 5,
+                        dedup_key: "setpoint".into(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            );
+            let _ = service.enqueue(enqueue_req).await.unwrap();
+        }
+
+        let get_queue_req = get_request(
+            &u.id,
+            api::GetDeviceQueueItemsRequest {
+                dev_eui: "0102030405060708".into(),
+                count_only: false,
+            },
+        );
+        let get_queue_resp = service.get_queue(get_queue_req).await.unwrap();
+        let get_queue_resp = get_queue_resp.get_ref();
+        assert_eq!(2, get_queue_resp.total_count);
+        assert_eq!(vec![8], get_queue_resp.result[0].data);
+        assert_eq!(5, get_queue_resp.result[0].priority);
+        assert_eq!("setpoint", get_queue_resp.result[0].dedup_key);
+        assert_eq!(vec![4, 5, 6], get_queue_resp.result[1].data);
+
         // flush queue
         let flush_queue_req = get_request(
             &u.id,
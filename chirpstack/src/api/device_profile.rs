@@ -9,8 +9,8 @@ use super::auth::validator;
 use super::error::ToStatus;
 use super::helpers;
 use super::helpers::{FromProto, ToProto};
-use crate::adr;
 use crate::storage::{device_profile, fields};
+use crate::{adr, applayer};
 
 pub struct DeviceProfile {
     validator: validator::RequestValidator,
@@ -83,6 +83,7 @@ impl DeviceProfileService for DeviceProfile {
             region_config_id: (!req_dp.region_config_id.is_empty())
                 .then(|| req_dp.region_config_id.clone()),
             allow_roaming: req_dp.allow_roaming,
+            allow_handover_roaming: req_dp.allow_handover_roaming,
             rx1_delay: req_dp.rx1_delay as i16,
             abp_params: if req_dp.supports_otaa {
                 None
@@ -145,12 +146,16 @@ impl DeviceProfileService for DeviceProfile {
                 None
             },
             app_layer_params: {
-                let app_layer_params = req_dp.app_layer_params.unwrap_or_default();
+                let app_layer_params = req_dp.app_layer_params.clone().unwrap_or_default();
 
                 fields::AppLayerParams {
                     ts003_version: app_layer_params.ts003_version().from_proto(),
                     ts004_version: app_layer_params.ts004_version().from_proto(),
                     ts005_version: app_layer_params.ts005_version().from_proto(),
+                    ts006_version: app_layer_params.ts006_version().from_proto(),
+                    ts007_version: app_layer_params.ts007_version().from_proto(),
+                    ts009_version: app_layer_params.ts009_version().from_proto(),
+                    packages: app_layer_params.packages.from_proto(),
                     ..Default::default()
                 }
             },
@@ -164,6 +169,9 @@ impl DeviceProfileService for DeviceProfile {
             ..Default::default()
         };
 
+        applayer::validate_app_layer_params(&dp.app_layer_params)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
         dp = device_profile::create(dp).await.map_err(|e| e.status())?;
 
         let mut resp = Response::new(api::CreateDeviceProfileResponse {
@@ -273,6 +281,7 @@ impl DeviceProfileService for DeviceProfile {
                 relay_overall_limit_bucket_size: relay_params.relay_overall_limit_bucket_size
                     as u32,
                 allow_roaming: dp.allow_roaming,
+                allow_handover_roaming: dp.allow_handover_roaming,
                 rx1_delay: dp.rx1_delay as u32,
                 app_layer_params: Some(api::AppLayerParams {
                     ts003_version: dp.app_layer_params.ts003_version.to_proto().into(),
@@ -281,6 +290,13 @@ impl DeviceProfileService for DeviceProfile {
                     ts004_f_port: dp.app_layer_params.ts004_f_port as u32,
                     ts005_version: dp.app_layer_params.ts005_version.to_proto().into(),
                     ts005_f_port: dp.app_layer_params.ts005_f_port as u32,
+                    ts006_version: dp.app_layer_params.ts006_version.to_proto().into(),
+                    ts006_f_port: dp.app_layer_params.ts006_f_port as u32,
+                    ts007_version: dp.app_layer_params.ts007_version.to_proto().into(),
+                    ts007_f_port: dp.app_layer_params.ts007_f_port as u32,
+                    ts009_version: dp.app_layer_params.ts009_version.to_proto().into(),
+                    ts009_f_port: dp.app_layer_params.ts009_f_port as u32,
+                    packages: dp.app_layer_params.packages.to_proto(),
                 }),
                 device_id: dp.device_id.map(|v| v.to_string()).unwrap_or_default(),
                 firmware_version: dp.firmware_version.clone(),
@@ -397,6 +413,7 @@ impl DeviceProfileService for DeviceProfile {
                 relay_overall_limit_bucket_size: relay_params.relay_overall_limit_bucket_size
                     as u32,
                 allow_roaming: dp.allow_roaming,
+                allow_handover_roaming: dp.allow_handover_roaming,
                 rx1_delay: dp.rx1_delay as u32,
                 app_layer_params: Some(api::AppLayerParams {
                     ts003_version: dp.app_layer_params.ts003_version.to_proto().into(),
@@ -405,6 +422,13 @@ impl DeviceProfileService for DeviceProfile {
                     ts004_f_port: dp.app_layer_params.ts004_f_port as u32,
                     ts005_version: dp.app_layer_params.ts005_version.to_proto().into(),
                     ts005_f_port: dp.app_layer_params.ts005_f_port as u32,
+                    ts006_version: dp.app_layer_params.ts006_version.to_proto().into(),
+                    ts006_f_port: dp.app_layer_params.ts006_f_port as u32,
+                    ts007_version: dp.app_layer_params.ts007_version.to_proto().into(),
+                    ts007_f_port: dp.app_layer_params.ts007_f_port as u32,
+                    ts009_version: dp.app_layer_params.ts009_version.to_proto().into(),
+                    ts009_f_port: dp.app_layer_params.ts009_f_port as u32,
+                    packages: dp.app_layer_params.packages.to_proto(),
                 }),
                 device_id: dp.device_id.map(|v| v.to_string()).unwrap_or_default(),
                 firmware_version: dp.firmware_version.clone(),
@@ -446,6 +470,29 @@ impl DeviceProfileService for DeviceProfile {
             )
             .await?;
 
+        let app_layer_params = {
+            let app_layer_params = req_dp.app_layer_params.clone().unwrap_or_default();
+
+            fields::AppLayerParams {
+                ts003_version: app_layer_params.ts003_version().from_proto(),
+                ts003_f_port: app_layer_params.ts003_f_port as u8,
+                ts004_version: app_layer_params.ts004_version().from_proto(),
+                ts004_f_port: app_layer_params.ts004_f_port as u8,
+                ts005_version: app_layer_params.ts005_version().from_proto(),
+                ts005_f_port: app_layer_params.ts005_f_port as u8,
+                ts006_version: app_layer_params.ts006_version().from_proto(),
+                ts006_f_port: app_layer_params.ts006_f_port as u8,
+                ts007_version: app_layer_params.ts007_version().from_proto(),
+                ts007_f_port: app_layer_params.ts007_f_port as u8,
+                ts009_version: app_layer_params.ts009_version().from_proto(),
+                ts009_f_port: app_layer_params.ts009_f_port as u8,
+                packages: app_layer_params.packages.from_proto(),
+            }
+        };
+
+        applayer::validate_app_layer_params(&app_layer_params)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
         // update
         let _ = device_profile::update(device_profile::DeviceProfile {
             id: dp_id.into(),
@@ -483,6 +530,7 @@ impl DeviceProfileService for DeviceProfile {
             region_config_id: (!req_dp.region_config_id.is_empty())
                 .then(|| req_dp.region_config_id.clone()),
             allow_roaming: req_dp.allow_roaming,
+            allow_handover_roaming: req_dp.allow_handover_roaming,
             rx1_delay: req_dp.rx1_delay as i16,
             abp_params: if req_dp.supports_otaa {
                 None
@@ -544,18 +592,7 @@ impl DeviceProfileService for DeviceProfile {
             } else {
                 None
             },
-            app_layer_params: {
-                let app_layer_params = req_dp.app_layer_params.unwrap_or_default();
-
-                fields::AppLayerParams {
-                    ts003_version: app_layer_params.ts003_version().from_proto(),
-                    ts003_f_port: app_layer_params.ts003_f_port as u8,
-                    ts004_version: app_layer_params.ts004_version().from_proto(),
-                    ts004_f_port: app_layer_params.ts004_f_port as u8,
-                    ts005_version: app_layer_params.ts005_version().from_proto(),
-                    ts005_f_port: app_layer_params.ts005_f_port as u8,
-                }
-            },
+            app_layer_params,
             supported_uplink_data_rates: fields::DataRates::new(
                 req_dp
                     .supported_uplink_data_rates
@@ -919,6 +956,8 @@ impl DeviceProfileService for DeviceProfile {
 
 #[cfg(test)]
 pub mod test {
+    use std::collections::HashMap;
+
     use super::*;
     use crate::api::auth::AuthID;
     use crate::api::auth::validator::RequestValidator;
@@ -1023,6 +1062,13 @@ pub mod test {
                     ts004_f_port: 201,
                     ts005_version: api::Ts005Version::Ts005NotImplemented.into(),
                     ts005_f_port: 200,
+                    ts006_version: api::Ts006Version::Ts006NotImplemented.into(),
+                    ts006_f_port: 203,
+                    ts007_version: api::Ts007Version::Ts007NotImplemented.into(),
+                    ts007_f_port: 225,
+                    ts009_version: api::Ts009Version::Ts009NotImplemented.into(),
+                    ts009_f_port: 224,
+                    packages: HashMap::new(),
                 }),
                 ..Default::default()
             }),
@@ -518,6 +518,14 @@ impl FuotaService for Fuota {
                         .as_ref()
                         .map(helpers::datetime_to_prost_timestamp),
                     error_msg: d.error_msg.clone(),
+                    upgrade_image_completed_at: d
+                        .upgrade_image_completed_at
+                        .as_ref()
+                        .map(helpers::datetime_to_prost_timestamp),
+                    reboot_completed_at: d
+                        .reboot_completed_at
+                        .as_ref()
+                        .map(helpers::datetime_to_prost_timestamp),
                 })
                 .collect(),
         });
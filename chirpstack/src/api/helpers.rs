@@ -1,9 +1,12 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 
 use chirpstack_api::{api, common};
 use lrwn::region::{CommonName, MacVersion, Revision};
 
 use crate::codec::Codec;
+use crate::manifest;
 use crate::storage::fields::{
     self, MeasurementKind, MulticastGroupSchedulingType, RequestFragmentationSessionStatus,
 };
@@ -268,6 +271,16 @@ impl ToProto<common::DeviceClass> for DeviceClass {
     }
 }
 
+impl FromProto<DeviceClass> for common::DeviceClass {
+    fn from_proto(self) -> DeviceClass {
+        match self {
+            common::DeviceClass::ClassA => DeviceClass::A,
+            common::DeviceClass::ClassB => DeviceClass::B,
+            common::DeviceClass::ClassC => DeviceClass::C,
+        }
+    }
+}
+
 impl FromProto<device::OrderBy> for api::list_devices_request::OrderBy {
     fn from_proto(self) -> device::OrderBy {
         match self {
@@ -279,6 +292,15 @@ impl FromProto<device::OrderBy> for api::list_devices_request::OrderBy {
     }
 }
 
+impl FromProto<manifest::Format> for api::DeviceManifestFormat {
+    fn from_proto(self) -> manifest::Format {
+        match self {
+            Self::ManifestJson => manifest::Format::Json,
+            Self::ManifestCsv => manifest::Format::Csv,
+        }
+    }
+}
+
 impl FromProto<gateway::OrderBy> for api::list_gateways_request::OrderBy {
     fn from_proto(self) -> gateway::OrderBy {
         match self {
@@ -349,6 +371,98 @@ impl FromProto<Option<fields::device_profile::Ts005Version>> for api::Ts005Versi
     }
 }
 
+impl ToProto<api::Ts006Version> for Option<fields::device_profile::Ts006Version> {
+    fn to_proto(self) -> api::Ts006Version {
+        match self {
+            None => api::Ts006Version::Ts006NotImplemented,
+            Some(fields::device_profile::Ts006Version::V100) => api::Ts006Version::Ts006V100,
+        }
+    }
+}
+
+impl FromProto<Option<fields::device_profile::Ts006Version>> for api::Ts006Version {
+    fn from_proto(self) -> Option<fields::device_profile::Ts006Version> {
+        match self {
+            api::Ts006Version::Ts006NotImplemented => None,
+            api::Ts006Version::Ts006V100 => Some(fields::device_profile::Ts006Version::V100),
+        }
+    }
+}
+
+impl ToProto<api::Ts007Version> for Option<fields::device_profile::Ts007Version> {
+    fn to_proto(self) -> api::Ts007Version {
+        match self {
+            None => api::Ts007Version::Ts007NotImplemented,
+            Some(fields::device_profile::Ts007Version::V100) => api::Ts007Version::Ts007V100,
+        }
+    }
+}
+
+impl FromProto<Option<fields::device_profile::Ts007Version>> for api::Ts007Version {
+    fn from_proto(self) -> Option<fields::device_profile::Ts007Version> {
+        match self {
+            api::Ts007Version::Ts007NotImplemented => None,
+            api::Ts007Version::Ts007V100 => Some(fields::device_profile::Ts007Version::V100),
+        }
+    }
+}
+
+impl ToProto<api::Ts009Version> for Option<fields::device_profile::Ts009Version> {
+    fn to_proto(self) -> api::Ts009Version {
+        match self {
+            None => api::Ts009Version::Ts009NotImplemented,
+            Some(fields::device_profile::Ts009Version::V100) => api::Ts009Version::Ts009V100,
+        }
+    }
+}
+
+impl FromProto<Option<fields::device_profile::Ts009Version>> for api::Ts009Version {
+    fn from_proto(self) -> Option<fields::device_profile::Ts009Version> {
+        match self {
+            api::Ts009Version::Ts009NotImplemented => None,
+            api::Ts009Version::Ts009V100 => Some(fields::device_profile::Ts009Version::V100),
+        }
+    }
+}
+
+impl ToProto<HashMap<String, api::AppLayerPackage>>
+    for &HashMap<String, fields::AppLayerPackageParams>
+{
+    fn to_proto(self) -> HashMap<String, api::AppLayerPackage> {
+        self.iter()
+            .map(|(k, v)| {
+                (
+                    k.clone(),
+                    api::AppLayerPackage {
+                        version: v.version.clone(),
+                        f_port: v.f_port as u32,
+                        settings: v.settings.clone(),
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+impl FromProto<HashMap<String, fields::AppLayerPackageParams>>
+    for &HashMap<String, api::AppLayerPackage>
+{
+    fn from_proto(self) -> HashMap<String, fields::AppLayerPackageParams> {
+        self.iter()
+            .map(|(k, v)| {
+                (
+                    k.clone(),
+                    fields::AppLayerPackageParams {
+                        version: v.version.clone(),
+                        f_port: v.f_port as u8,
+                        settings: v.settings.clone(),
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
 impl ToProto<api::RequestFragmentationSessionStatus> for RequestFragmentationSessionStatus {
     fn to_proto(self) -> api::RequestFragmentationSessionStatus {
         match self {
@@ -0,0 +1,377 @@
+use std::str::FromStr;
+
+use chirpstack_api::api;
+use chirpstack_api::api::join_server_service_server::JoinServerService;
+use chirpstack_api::tonic::{self, Request, Response, Status};
+use uuid::Uuid;
+
+use super::auth::validator;
+use super::error::ToStatus;
+use super::helpers;
+use crate::backend::joinserver;
+use crate::storage::join_server;
+use lrwn::EUI64Prefix;
+
+pub struct JoinServer {
+    validator: validator::RequestValidator,
+}
+
+impl JoinServer {
+    pub fn new(validator: validator::RequestValidator) -> Self {
+        JoinServer { validator }
+    }
+}
+
+#[tonic::async_trait]
+impl JoinServerService for JoinServer {
+    async fn create(
+        &self,
+        request: Request<api::CreateJoinServerRequest>,
+    ) -> Result<Response<api::CreateJoinServerResponse>, Status> {
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateBackendInterfacesAccess::new(validator::Flag::Create),
+            )
+            .await?;
+
+        let req_js = match &request.get_ref().join_server {
+            Some(v) => v,
+            None => {
+                return Err(Status::invalid_argument("join_server is missing"));
+            }
+        };
+
+        let js = join_server::JoinServer {
+            name: req_js.name.clone(),
+            join_eui_prefix: EUI64Prefix::from_str(&req_js.join_eui_prefix)
+                .map_err(|e| e.status())?,
+            server: req_js.server.clone(),
+            async_timeout: req_js.async_timeout as i32,
+            ca_cert: req_js.ca_cert.clone(),
+            tls_cert: req_js.tls_cert.clone(),
+            tls_key: req_js.tls_key.clone(),
+            authorization_header: req_js.authorization_header.clone(),
+            ..Default::default()
+        };
+        joinserver::validate_join_server(&js)
+            .map_err(|e| Status::invalid_argument(format!("{:#}", e)))?;
+
+        let js = join_server::create(js).await.map_err(|e| e.status())?;
+        joinserver::reload().await.map_err(|e| e.status())?;
+
+        let mut resp = Response::new(api::CreateJoinServerResponse {
+            id: js.id.to_string(),
+        });
+        resp.metadata_mut()
+            .insert("x-log-join_server_id", js.id.to_string().parse().unwrap());
+
+        Ok(resp)
+    }
+
+    async fn get(
+        &self,
+        request: Request<api::GetJoinServerRequest>,
+    ) -> Result<Response<api::GetJoinServerResponse>, Status> {
+        let req = request.get_ref();
+        let id = Uuid::from_str(&req.id).map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateBackendInterfacesAccess::new(validator::Flag::Read),
+            )
+            .await?;
+
+        let js = join_server::get(&id).await.map_err(|e| e.status())?;
+
+        let mut resp = Response::new(api::GetJoinServerResponse {
+            join_server: Some(api::JoinServer {
+                id: js.id.to_string(),
+                name: js.name.clone(),
+                join_eui_prefix: js.join_eui_prefix.to_string(),
+                server: js.server.clone(),
+                async_timeout: js.async_timeout as u32,
+                ca_cert: js.ca_cert.clone(),
+                tls_cert: js.tls_cert.clone(),
+                // The TLS key and authorization header contain secrets and are never
+                // returned.
+                tls_key: "".into(),
+                authorization_header: "".into(),
+            }),
+            created_at: Some(helpers::datetime_to_prost_timestamp(&js.created_at)),
+            updated_at: Some(helpers::datetime_to_prost_timestamp(&js.updated_at)),
+        });
+        resp.metadata_mut()
+            .insert("x-log-join_server_id", req.id.parse().unwrap());
+
+        Ok(resp)
+    }
+
+    async fn update(
+        &self,
+        request: Request<api::UpdateJoinServerRequest>,
+    ) -> Result<Response<()>, Status> {
+        let req_js = match &request.get_ref().join_server {
+            Some(v) => v,
+            None => {
+                return Err(Status::invalid_argument("join_server is missing"));
+            }
+        };
+        let id = Uuid::from_str(&req_js.id).map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateBackendInterfacesAccess::new(validator::Flag::Update),
+            )
+            .await?;
+
+        let mut js = join_server::JoinServer {
+            id: id.into(),
+            name: req_js.name.clone(),
+            join_eui_prefix: EUI64Prefix::from_str(&req_js.join_eui_prefix)
+                .map_err(|e| e.status())?,
+            server: req_js.server.clone(),
+            async_timeout: req_js.async_timeout as i32,
+            ca_cert: req_js.ca_cert.clone(),
+            tls_cert: req_js.tls_cert.clone(),
+            tls_key: req_js.tls_key.clone(),
+            authorization_header: req_js.authorization_header.clone(),
+            ..Default::default()
+        };
+
+        // As the TLS key and authorization header are not returned by the API, an
+        // empty value means that the current value must be kept.
+        if js.tls_key.is_empty() || js.authorization_header.is_empty() {
+            let current = join_server::get(&id).await.map_err(|e| e.status())?;
+            if js.tls_key.is_empty() {
+                js.tls_key = current.tls_key;
+            }
+            if js.authorization_header.is_empty() {
+                js.authorization_header = current.authorization_header;
+            }
+        }
+
+        joinserver::validate_join_server(&js)
+            .map_err(|e| Status::invalid_argument(format!("{:#}", e)))?;
+
+        let _ = join_server::update(js).await.map_err(|e| e.status())?;
+        joinserver::reload().await.map_err(|e| e.status())?;
+
+        let mut resp = Response::new(());
+        resp.metadata_mut()
+            .insert("x-log-join_server_id", req_js.id.parse().unwrap());
+
+        Ok(resp)
+    }
+
+    async fn delete(
+        &self,
+        request: Request<api::DeleteJoinServerRequest>,
+    ) -> Result<Response<()>, Status> {
+        let req = request.get_ref();
+        let id = Uuid::from_str(&req.id).map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateBackendInterfacesAccess::new(validator::Flag::Delete),
+            )
+            .await?;
+
+        join_server::delete(&id).await.map_err(|e| e.status())?;
+        joinserver::reload().await.map_err(|e| e.status())?;
+
+        let mut resp = Response::new(());
+        resp.metadata_mut()
+            .insert("x-log-join_server_id", req.id.parse().unwrap());
+
+        Ok(resp)
+    }
+
+    async fn list(
+        &self,
+        request: Request<api::ListJoinServersRequest>,
+    ) -> Result<Response<api::ListJoinServersResponse>, Status> {
+        let req = request.get_ref();
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateBackendInterfacesAccess::new(validator::Flag::List),
+            )
+            .await?;
+
+        let count = join_server::get_count().await.map_err(|e| e.status())?;
+        let items = join_server::list(req.limit as i64, req.offset as i64)
+            .await
+            .map_err(|e| e.status())?;
+
+        Ok(Response::new(api::ListJoinServersResponse {
+            total_count: count as u32,
+            result: items
+                .iter()
+                .map(|js| api::JoinServerListItem {
+                    id: js.id.to_string(),
+                    created_at: Some(helpers::datetime_to_prost_timestamp(&js.created_at)),
+                    updated_at: Some(helpers::datetime_to_prost_timestamp(&js.updated_at)),
+                    name: js.name.clone(),
+                    join_eui_prefix: js.join_eui_prefix.to_string(),
+                    server: js.server.clone(),
+                })
+                .collect(),
+        }))
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::api::auth::AuthID;
+    use crate::api::auth::validator::RequestValidator;
+    use crate::storage::user;
+    use crate::test;
+    use lrwn::EUI64;
+
+    #[tokio::test]
+    async fn test_join_server() {
+        let _guard = test::prepare().await;
+
+        // setup admin user
+        let u = user::User {
+            is_admin: true,
+            is_active: true,
+            email: "admin@admin".into(),
+            email_verified: true,
+            ..Default::default()
+        };
+        let u = user::create(u).await.unwrap();
+
+        // setup api
+        let service = JoinServer::new(RequestValidator::new());
+
+        // create
+        let create_req = api::CreateJoinServerRequest {
+            join_server: Some(api::JoinServer {
+                name: "test-js".into(),
+                join_eui_prefix: "0102030405060700/56".into(),
+                server: "https://example.com".into(),
+                authorization_header: "Bearer secret".into(),
+                ..Default::default()
+            }),
+        };
+        let mut create_req = Request::new(create_req);
+        create_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let create_resp = service.create(create_req).await.unwrap();
+        let id = create_resp.get_ref().id.clone();
+
+        // the Join Server client has been reloaded
+        assert!(
+            joinserver::get(EUI64::from_str("0102030405060708").unwrap())
+                .await
+                .is_ok()
+        );
+
+        // get
+        let get_req = api::GetJoinServerRequest { id: id.clone() };
+        let mut get_req = Request::new(get_req);
+        get_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let get_resp = service.get(get_req).await.unwrap();
+        assert_eq!(
+            Some(api::JoinServer {
+                id: id.clone(),
+                name: "test-js".into(),
+                join_eui_prefix: "0102030405060700/56".into(),
+                server: "https://example.com".into(),
+                ..Default::default()
+            }),
+            get_resp.get_ref().join_server
+        );
+
+        // update
+        let up_req = api::UpdateJoinServerRequest {
+            join_server: Some(api::JoinServer {
+                id: id.clone(),
+                name: "test-js-updated".into(),
+                join_eui_prefix: "0807060504030200/56".into(),
+                server: "https://example.com".into(),
+                ..Default::default()
+            }),
+        };
+        let mut up_req = Request::new(up_req);
+        up_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let _ = service.update(up_req).await.unwrap();
+        assert!(
+            joinserver::get(EUI64::from_str("0807060504030201").unwrap())
+                .await
+                .is_ok()
+        );
+        assert!(
+            joinserver::get(EUI64::from_str("0102030405060708").unwrap())
+                .await
+                .is_err()
+        );
+
+        // the authorization header is kept on update
+        assert_eq!(
+            "Bearer secret",
+            join_server::get(&Uuid::from_str(&id).unwrap())
+                .await
+                .unwrap()
+                .authorization_header
+        );
+
+        // update using a file path instead of PEM content
+        let up_req = api::UpdateJoinServerRequest {
+            join_server: Some(api::JoinServer {
+                id: id.clone(),
+                name: "test-js-updated".into(),
+                join_eui_prefix: "0807060504030200/56".into(),
+                server: "https://example.com".into(),
+                tls_key: "/etc/hostname".into(),
+                ..Default::default()
+            }),
+        };
+        let mut up_req = Request::new(up_req);
+        up_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let up_resp = service.update(up_req).await;
+        assert_eq!(tonic::Code::InvalidArgument, up_resp.err().unwrap().code());
+
+        // list
+        let list_req = api::ListJoinServersRequest {
+            limit: 10,
+            offset: 0,
+        };
+        let mut list_req = Request::new(list_req);
+        list_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let list_resp = service.list(list_req).await.unwrap();
+        assert_eq!(1, list_resp.get_ref().total_count);
+        assert_eq!(1, list_resp.get_ref().result.len());
+        assert_eq!("test-js-updated", list_resp.get_ref().result[0].name);
+
+        // delete
+        let del_req = api::DeleteJoinServerRequest { id: id.clone() };
+        let mut del_req = Request::new(del_req);
+        del_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let _ = service.delete(del_req).await.unwrap();
+
+        let del_req = api::DeleteJoinServerRequest { id };
+        let mut del_req = Request::new(del_req);
+        del_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        assert!(service.delete(del_req).await.is_err());
+    }
+}
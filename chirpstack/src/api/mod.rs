@@ -9,13 +9,16 @@ use std::{
 use anyhow::{Context as AnyhowContext, Result};
 use axum::{Router, response::IntoResponse, routing::get};
 use chirpstack_api::api::application_service_server::ApplicationServiceServer;
+use chirpstack_api::api::backend_interfaces_log_service_server::BackendInterfacesLogServiceServer;
 use chirpstack_api::api::device_profile_service_server::DeviceProfileServiceServer;
 use chirpstack_api::api::device_service_server::DeviceServiceServer;
 use chirpstack_api::api::fuota_service_server::FuotaServiceServer;
 use chirpstack_api::api::gateway_service_server::GatewayServiceServer;
 use chirpstack_api::api::internal_service_server::InternalServiceServer;
+use chirpstack_api::api::join_server_service_server::JoinServerServiceServer;
 use chirpstack_api::api::multicast_group_service_server::MulticastGroupServiceServer;
 use chirpstack_api::api::relay_service_server::RelayServiceServer;
+use chirpstack_api::api::roaming_agreement_service_server::RoamingAgreementServiceServer;
 use chirpstack_api::api::tenant_service_server::TenantServiceServer;
 use chirpstack_api::api::user_service_server::UserServiceServer;
 use chirpstack_api::stream as stream_pb;
@@ -48,6 +51,7 @@ use crate::stream;
 pub mod application;
 pub mod auth;
 pub mod backend;
+pub mod backend_interfaces_log;
 pub mod device;
 pub mod device_profile;
 pub mod error;
@@ -56,11 +60,13 @@ pub mod gateway;
 mod grpc_multiplex;
 pub mod helpers;
 pub mod internal;
+pub mod join_server;
 pub mod monitoring;
 pub mod multicast;
 pub mod oauth2;
 pub mod oidc;
 pub mod relay;
+pub mod roaming_agreement;
 pub mod tenant;
 pub mod user;
 
@@ -169,6 +175,18 @@ pub async fn setup() -> Result<()> {
         .add_service(FuotaServiceServer::with_interceptor(
             fuota::Fuota::new(validator::RequestValidator::new()),
             auth::auth_interceptor,
+        ))
+        .add_service(RoamingAgreementServiceServer::with_interceptor(
+            roaming_agreement::RoamingAgreement::new(validator::RequestValidator::new()),
+            auth::auth_interceptor,
+        ))
+        .add_service(JoinServerServiceServer::with_interceptor(
+            join_server::JoinServer::new(validator::RequestValidator::new()),
+            auth::auth_interceptor,
+        ))
+        .add_service(BackendInterfacesLogServiceServer::with_interceptor(
+            backend_interfaces_log::BackendInterfacesLog::new(validator::RequestValidator::new()),
+            auth::auth_interceptor,
         ));
 
     let backend_handle = tokio::spawn(backend::setup());
@@ -514,6 +514,45 @@ impl MulticastGroupService for MulticastGroup {
 
         Ok(resp)
     }
+
+    async fn get_coverage(
+        &self,
+        request: Request<api::GetMulticastGroupCoverageRequest>,
+    ) -> Result<Response<api::GetMulticastGroupCoverageResponse>, Status> {
+        let req = request.get_ref();
+        let mg_id = Uuid::from_str(&req.multicast_group_id).map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateMulticastGroupAccess::new(validator::Flag::Read, mg_id),
+            )
+            .await?;
+
+        let coverage = downlink::multicast::get_coverage(&mg_id)
+            .await
+            .map_err(|e| e.status())?;
+
+        let mut resp = Response::new(api::GetMulticastGroupCoverageResponse {
+            total_count: coverage.len() as u32,
+            covered_count: coverage.iter().filter(|c| c.covered).count() as u32,
+            result: coverage
+                .iter()
+                .map(|c| api::MulticastGroupDeviceCoverage {
+                    dev_eui: c.dev_eui.to_string(),
+                    gateway_ids: c.gateway_ids.iter().map(|v| v.to_string()).collect(),
+                    class_b_enabled: c.class_b_enabled,
+                    covered: c.covered,
+                })
+                .collect(),
+        });
+        resp.metadata_mut().insert(
+            "x-log-multicast_group_id",
+            req.multicast_group_id.parse().unwrap(),
+        );
+
+        Ok(resp)
+    }
 }
 
 #[cfg(test)]
@@ -863,6 +902,28 @@ pub mod test {
             .unwrap();
         assert_eq!(2, queue_items.len());
 
+        // get coverage
+        let get_coverage_req = get_request(
+            &u.id,
+            api::GetMulticastGroupCoverageRequest {
+                multicast_group_id: create_resp.id.clone(),
+            },
+        );
+        let get_coverage_resp = service.get_coverage(get_coverage_req).await.unwrap();
+        assert_eq!(
+            api::GetMulticastGroupCoverageResponse {
+                total_count: 1,
+                covered_count: 1,
+                result: vec![api::MulticastGroupDeviceCoverage {
+                    dev_eui: d.dev_eui.to_string(),
+                    gateway_ids: vec![gw1.gateway_id.to_string()],
+                    class_b_enabled: false,
+                    covered: true,
+                }],
+            },
+            *get_coverage_resp.get_ref()
+        );
+
         // remove device
         let remove_dev_req = get_request(
             &u.id,
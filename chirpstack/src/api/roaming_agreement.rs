@@ -0,0 +1,643 @@
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+
+use chirpstack_api::api;
+use chirpstack_api::api::roaming_agreement_service_server::RoamingAgreementService;
+use chirpstack_api::tonic::{self, Request, Response, Status};
+
+use super::auth::validator;
+use super::error::ToStatus;
+use super::helpers;
+use crate::backend::roaming;
+use crate::storage::{fields, roaming_agreement, roaming_settlement};
+use lrwn::NetID;
+
+pub struct RoamingAgreement {
+    validator: validator::RequestValidator,
+}
+
+impl RoamingAgreement {
+    pub fn new(validator: validator::RequestValidator) -> Self {
+        RoamingAgreement { validator }
+    }
+}
+
+fn from_proto(ra: &api::RoamingAgreement) -> Result<roaming_agreement::RoamingAgreement, Status> {
+    Ok(roaming_agreement::RoamingAgreement {
+        net_id: NetID::from_str(&ra.net_id).map_err(|e| e.status())?,
+        name: ra.name.clone(),
+        secondary_net_ids: fields::StringVec::new(
+            ra.secondary_net_ids.iter().cloned().map(Some).collect(),
+        ),
+        server: ra.server.clone(),
+        use_target_role_suffix: ra.use_target_role_suffix,
+        async_timeout: ra.async_timeout as i32,
+        passive_roaming_lifetime: ra.passive_roaming_lifetime as i32,
+        passive_roaming_kek_label: ra.passive_roaming_kek_label.clone(),
+        passive_roaming_validate_mic: ra.passive_roaming_validate_mic,
+        handover_roaming: ra.handover_roaming,
+        handover_roaming_lifetime: ra.handover_roaming_lifetime as i32,
+        handover_roaming_kek_label: ra.handover_roaming_kek_label.clone(),
+        ca_cert: ra.ca_cert.clone(),
+        tls_cert: ra.tls_cert.clone(),
+        tls_key: ra.tls_key.clone(),
+        authorization_header: ra.authorization_header.clone(),
+        allowed_device_profile_ids: fields::StringVec::new(
+            ra.allowed_device_profile_ids
+                .iter()
+                .cloned()
+                .map(Some)
+                .collect(),
+        ),
+        ..Default::default()
+    })
+}
+
+fn to_proto(ra: &roaming_agreement::RoamingAgreement) -> api::RoamingAgreement {
+    api::RoamingAgreement {
+        net_id: ra.net_id.to_string(),
+        name: ra.name.clone(),
+        secondary_net_ids: ra.secondary_net_ids.iter().flatten().cloned().collect(),
+        server: ra.server.clone(),
+        use_target_role_suffix: ra.use_target_role_suffix,
+        async_timeout: ra.async_timeout as u32,
+        passive_roaming_lifetime: ra.passive_roaming_lifetime as u32,
+        passive_roaming_kek_label: ra.passive_roaming_kek_label.clone(),
+        passive_roaming_validate_mic: ra.passive_roaming_validate_mic,
+        handover_roaming: ra.handover_roaming,
+        handover_roaming_lifetime: ra.handover_roaming_lifetime as u32,
+        handover_roaming_kek_label: ra.handover_roaming_kek_label.clone(),
+        ca_cert: ra.ca_cert.clone(),
+        tls_cert: ra.tls_cert.clone(),
+        // The TLS key and authorization header contain secrets and are never returned.
+        tls_key: "".into(),
+        authorization_header: "".into(),
+        allowed_device_profile_ids: ra
+            .allowed_device_profile_ids
+            .iter()
+            .flatten()
+            .cloned()
+            .collect(),
+    }
+}
+
+fn get_settlement_filters(
+    req: &api::GetRoamingSettlementReportRequest,
+) -> Result<roaming_settlement::Filters, Status> {
+    let start = SystemTime::try_from(
+        *req.start
+            .as_ref()
+            .ok_or_else(|| anyhow!("start is None"))
+            .map_err(|e| e.status())?,
+    )
+    .map_err(|e| e.status())?;
+
+    let end = SystemTime::try_from(
+        *req.end
+            .as_ref()
+            .ok_or_else(|| anyhow!("end is None"))
+            .map_err(|e| e.status())?,
+    )
+    .map_err(|e| e.status())?;
+
+    Ok(roaming_settlement::Filters {
+        net_id: if req.net_id.is_empty() {
+            None
+        } else {
+            Some(NetID::from_str(&req.net_id).map_err(|e| e.status())?)
+        },
+        start: DateTime::<Utc>::from(start).date_naive(),
+        end: DateTime::<Utc>::from(end).date_naive(),
+    })
+}
+
+fn settlement_to_csv(items: &[roaming_settlement::RoamingSettlement]) -> String {
+    let mut out = "NetID,Date,Role,Direction,FrameCount,PHYPayloadBytes,GWCnt\n".to_string();
+    for rs in items {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            rs.net_id,
+            rs.day.format("%Y-%m-%d"),
+            rs.role,
+            rs.direction,
+            rs.frame_count,
+            rs.phy_payload_bytes,
+            rs.gw_count
+        ));
+    }
+    out
+}
+
+#[tonic::async_trait]
+impl RoamingAgreementService for RoamingAgreement {
+    async fn create(
+        &self,
+        request: Request<api::CreateRoamingAgreementRequest>,
+    ) -> Result<Response<()>, Status> {
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateBackendInterfacesAccess::new(validator::Flag::Create),
+            )
+            .await?;
+
+        let req_ra = match &request.get_ref().roaming_agreement {
+            Some(v) => v,
+            None => {
+                return Err(Status::invalid_argument("roaming_agreement is missing"));
+            }
+        };
+
+        let ra = from_proto(req_ra)?;
+        roaming::validate_agreement(&ra)
+            .map_err(|e| Status::invalid_argument(format!("{:#}", e)))?;
+
+        let ra = roaming_agreement::create(ra)
+            .await
+            .map_err(|e| e.status())?;
+        roaming::reload().await.map_err(|e| e.status())?;
+
+        let mut resp = Response::new(());
+        resp.metadata_mut()
+            .insert("x-log-net_id", ra.net_id.to_string().parse().unwrap());
+
+        Ok(resp)
+    }
+
+    async fn get(
+        &self,
+        request: Request<api::GetRoamingAgreementRequest>,
+    ) -> Result<Response<api::GetRoamingAgreementResponse>, Status> {
+        let req = request.get_ref();
+        let net_id = NetID::from_str(&req.net_id).map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateBackendInterfacesAccess::new(validator::Flag::Read),
+            )
+            .await?;
+
+        let ra = roaming_agreement::get(&net_id)
+            .await
+            .map_err(|e| e.status())?;
+
+        let mut resp = Response::new(api::GetRoamingAgreementResponse {
+            roaming_agreement: Some(to_proto(&ra)),
+            created_at: Some(helpers::datetime_to_prost_timestamp(&ra.created_at)),
+            updated_at: Some(helpers::datetime_to_prost_timestamp(&ra.updated_at)),
+        });
+        resp.metadata_mut()
+            .insert("x-log-net_id", req.net_id.parse().unwrap());
+
+        Ok(resp)
+    }
+
+    async fn update(
+        &self,
+        request: Request<api::UpdateRoamingAgreementRequest>,
+    ) -> Result<Response<()>, Status> {
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateBackendInterfacesAccess::new(validator::Flag::Update),
+            )
+            .await?;
+
+        let req_ra = match &request.get_ref().roaming_agreement {
+            Some(v) => v,
+            None => {
+                return Err(Status::invalid_argument("roaming_agreement is missing"));
+            }
+        };
+
+        let mut ra = from_proto(req_ra)?;
+
+        // As the TLS key and authorization header are not returned by the API, an
+        // empty value means that the current value must be kept.
+        if ra.tls_key.is_empty() || ra.authorization_header.is_empty() {
+            let current = roaming_agreement::get(&ra.net_id)
+                .await
+                .map_err(|e| e.status())?;
+            if ra.tls_key.is_empty() {
+                ra.tls_key = current.tls_key;
+            }
+            if ra.authorization_header.is_empty() {
+                ra.authorization_header = current.authorization_header;
+            }
+        }
+
+        roaming::validate_agreement(&ra)
+            .map_err(|e| Status::invalid_argument(format!("{:#}", e)))?;
+
+        let ra = roaming_agreement::update(ra)
+            .await
+            .map_err(|e| e.status())?;
+        roaming::reload().await.map_err(|e| e.status())?;
+
+        let mut resp = Response::new(());
+        resp.metadata_mut()
+            .insert("x-log-net_id", ra.net_id.to_string().parse().unwrap());
+
+        Ok(resp)
+    }
+
+    async fn delete(
+        &self,
+        request: Request<api::DeleteRoamingAgreementRequest>,
+    ) -> Result<Response<()>, Status> {
+        let req = request.get_ref();
+        let net_id = NetID::from_str(&req.net_id).map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateBackendInterfacesAccess::new(validator::Flag::Delete),
+            )
+            .await?;
+
+        roaming_agreement::delete(&net_id)
+            .await
+            .map_err(|e| e.status())?;
+        roaming::reload().await.map_err(|e| e.status())?;
+
+        let mut resp = Response::new(());
+        resp.metadata_mut()
+            .insert("x-log-net_id", req.net_id.parse().unwrap());
+
+        Ok(resp)
+    }
+
+    async fn list(
+        &self,
+        request: Request<api::ListRoamingAgreementsRequest>,
+    ) -> Result<Response<api::ListRoamingAgreementsResponse>, Status> {
+        let req = request.get_ref();
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateBackendInterfacesAccess::new(validator::Flag::List),
+            )
+            .await?;
+
+        let count = roaming_agreement::get_count()
+            .await
+            .map_err(|e| e.status())?;
+        let items = roaming_agreement::list(req.limit as i64, req.offset as i64)
+            .await
+            .map_err(|e| e.status())?;
+
+        Ok(Response::new(api::ListRoamingAgreementsResponse {
+            total_count: count as u32,
+            result: items
+                .iter()
+                .map(|ra| api::RoamingAgreementListItem {
+                    net_id: ra.net_id.to_string(),
+                    created_at: Some(helpers::datetime_to_prost_timestamp(&ra.created_at)),
+                    updated_at: Some(helpers::datetime_to_prost_timestamp(&ra.updated_at)),
+                    name: ra.name.clone(),
+                    server: ra.server.clone(),
+                    handover_roaming: ra.handover_roaming,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn get_settlement_report(
+        &self,
+        request: Request<api::GetRoamingSettlementReportRequest>,
+    ) -> Result<Response<api::GetRoamingSettlementReportResponse>, Status> {
+        let req = request.get_ref();
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateBackendInterfacesAccess::new(validator::Flag::Read),
+            )
+            .await?;
+
+        let filters = get_settlement_filters(req)?;
+        let items = roaming_settlement::list(&filters)
+            .await
+            .map_err(|e| e.status())?;
+
+        Ok(Response::new(api::GetRoamingSettlementReportResponse {
+            result: items
+                .iter()
+                .map(|rs| api::RoamingSettlementReportItem {
+                    net_id: rs.net_id.to_string(),
+                    date: rs.day.format("%Y-%m-%d").to_string(),
+                    role: rs.role.clone(),
+                    direction: rs.direction.clone(),
+                    frame_count: rs.frame_count as u64,
+                    phy_payload_bytes: rs.phy_payload_bytes as u64,
+                    gw_count: rs.gw_count as u64,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn export_settlement_report(
+        &self,
+        request: Request<api::GetRoamingSettlementReportRequest>,
+    ) -> Result<Response<api::ExportRoamingSettlementReportResponse>, Status> {
+        let req = request.get_ref();
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateBackendInterfacesAccess::new(validator::Flag::Read),
+            )
+            .await?;
+
+        let filters = get_settlement_filters(req)?;
+        let items = roaming_settlement::list(&filters)
+            .await
+            .map_err(|e| e.status())?;
+
+        Ok(Response::new(api::ExportRoamingSettlementReportResponse {
+            csv: settlement_to_csv(&items).into_bytes(),
+        }))
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::api::auth::AuthID;
+    use crate::api::auth::validator::RequestValidator;
+    use crate::storage::user;
+    use crate::test;
+    use chrono::TimeZone;
+
+    #[tokio::test]
+    async fn test_roaming_agreement() {
+        let _guard = test::prepare().await;
+
+        // setup admin user
+        let u = user::User {
+            is_admin: true,
+            is_active: true,
+            email: "admin@admin".into(),
+            email_verified: true,
+            ..Default::default()
+        };
+        let u = user::create(u).await.unwrap();
+
+        // setup api
+        let service = RoamingAgreement::new(RequestValidator::new());
+
+        // create
+        let create_req = api::CreateRoamingAgreementRequest {
+            roaming_agreement: Some(api::RoamingAgreement {
+                net_id: "010203".into(),
+                name: "test-ra".into(),
+                secondary_net_ids: vec!["010204".into()],
+                server: "https://example.com".into(),
+                passive_roaming_lifetime: 60,
+                authorization_header: "Bearer secret".into(),
+                ..Default::default()
+            }),
+        };
+        let mut create_req = Request::new(create_req);
+        create_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let _ = service.create(create_req).await.unwrap();
+
+        // the roaming client has been reloaded
+        let net_id = NetID::from_str("010203").unwrap();
+        assert!(roaming::get(&net_id).await.is_ok());
+        assert_eq!(
+            std::time::Duration::from_secs(60),
+            roaming::get_passive_roaming_lifetime(net_id).unwrap()
+        );
+
+        // get
+        let get_req = api::GetRoamingAgreementRequest {
+            net_id: "010203".into(),
+        };
+        let mut get_req = Request::new(get_req);
+        get_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let get_resp = service.get(get_req).await.unwrap();
+        assert_eq!(
+            Some(api::RoamingAgreement {
+                net_id: "010203".into(),
+                name: "test-ra".into(),
+                secondary_net_ids: vec!["010204".into()],
+                server: "https://example.com".into(),
+                passive_roaming_lifetime: 60,
+                ..Default::default()
+            }),
+            get_resp.get_ref().roaming_agreement
+        );
+
+        // update
+        let up_req = api::UpdateRoamingAgreementRequest {
+            roaming_agreement: Some(api::RoamingAgreement {
+                net_id: "010203".into(),
+                name: "test-ra-updated".into(),
+                server: "https://example.com".into(),
+                handover_roaming: true,
+                ..Default::default()
+            }),
+        };
+        let mut up_req = Request::new(up_req);
+        up_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let _ = service.update(up_req).await.unwrap();
+        assert!(roaming::get_handover_roaming(net_id).unwrap());
+
+        // the authorization header is kept on update
+        assert_eq!(
+            "Bearer secret",
+            roaming_agreement::get(&net_id)
+                .await
+                .unwrap()
+                .authorization_header
+        );
+
+        // update with invalid TLS material
+        let up_req = api::UpdateRoamingAgreementRequest {
+            roaming_agreement: Some(api::RoamingAgreement {
+                net_id: "010203".into(),
+                name: "test-ra-updated".into(),
+                ca_cert: "/does/not/exist.pem".into(),
+                ..Default::default()
+            }),
+        };
+        let mut up_req = Request::new(up_req);
+        up_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        assert!(service.update(up_req).await.is_err());
+
+        // update using a file path instead of PEM content
+        let up_req = api::UpdateRoamingAgreementRequest {
+            roaming_agreement: Some(api::RoamingAgreement {
+                net_id: "010203".into(),
+                name: "test-ra-updated".into(),
+                tls_key: "/etc/hostname".into(),
+                ..Default::default()
+            }),
+        };
+        let mut up_req = Request::new(up_req);
+        up_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let up_resp = service.update(up_req).await;
+        assert_eq!(tonic::Code::InvalidArgument, up_resp.err().unwrap().code());
+
+        // list
+        let list_req = api::ListRoamingAgreementsRequest {
+            limit: 10,
+            offset: 0,
+        };
+        let mut list_req = Request::new(list_req);
+        list_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let list_resp = service.list(list_req).await.unwrap();
+        assert_eq!(1, list_resp.get_ref().total_count);
+        assert_eq!(1, list_resp.get_ref().result.len());
+        assert_eq!("test-ra-updated", list_resp.get_ref().result[0].name);
+
+        // settlement report
+        roaming::record_uplink(
+            net_id,
+            backend::Role::FNS,
+            &[1, 2, 3, 4],
+            &backend::ULMetaData {
+                recv_time: Utc::now(),
+                gw_cnt: Some(2),
+                ..Default::default()
+            },
+        )
+        .await;
+        let report_req = api::GetRoamingSettlementReportRequest {
+            net_id: "010203".into(),
+            start: Some(helpers::datetime_to_prost_timestamp(&Utc::now())),
+            end: Some(helpers::datetime_to_prost_timestamp(&Utc::now())),
+        };
+        let mut report_req = Request::new(report_req);
+        report_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let report_resp = service.get_settlement_report(report_req).await.unwrap();
+        assert_eq!(
+            vec![api::RoamingSettlementReportItem {
+                net_id: "010203".into(),
+                date: Utc::now().format("%Y-%m-%d").to_string(),
+                role: "FNS".into(),
+                direction: "uplink".into(),
+                frame_count: 1,
+                phy_payload_bytes: 4,
+                gw_count: 2,
+            }],
+            report_resp.get_ref().result
+        );
+
+        let export_req = api::GetRoamingSettlementReportRequest {
+            net_id: "010203".into(),
+            start: Some(helpers::datetime_to_prost_timestamp(&Utc::now())),
+            end: Some(helpers::datetime_to_prost_timestamp(&Utc::now())),
+        };
+        let mut export_req = Request::new(export_req);
+        export_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let export_resp = service.export_settlement_report(export_req).await.unwrap();
+        assert_eq!(
+            format!(
+                "NetID,Date,Role,Direction,FrameCount,PHYPayloadBytes,GWCnt\n010203,{},FNS,uplink,1,4,2\n",
+                Utc::now().format("%Y-%m-%d")
+            ),
+            String::from_utf8(export_resp.get_ref().csv.clone()).unwrap()
+        );
+
+        // settlement report around midnight, frames are accounted to the day of
+        // the uplink RecvTime and downlink transmission time
+        let recv_time = Utc.with_ymd_and_hms(2025, 1, 1, 23, 59, 59).unwrap();
+        roaming::record_uplink(
+            net_id,
+            backend::Role::SNS,
+            &[1, 2, 3, 4],
+            &backend::ULMetaData {
+                recv_time,
+                gw_cnt: Some(1),
+                ..Default::default()
+            },
+        )
+        .await;
+        roaming::record_downlink(
+            net_id,
+            backend::Role::SNS,
+            &[1, 2, 3],
+            &backend::DLMetaData {
+                rx_delay_1: Some(1),
+                gw_info: vec![Default::default()],
+                ..Default::default()
+            },
+            Some(recv_time),
+        )
+        .await;
+        let report_req = api::GetRoamingSettlementReportRequest {
+            net_id: "010203".into(),
+            start: Some(helpers::datetime_to_prost_timestamp(&recv_time)),
+            end: Some(helpers::datetime_to_prost_timestamp(
+                &(recv_time + chrono::Duration::days(1)),
+            )),
+        };
+        let mut report_req = Request::new(report_req);
+        report_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let report_resp = service.get_settlement_report(report_req).await.unwrap();
+        assert_eq!(
+            vec![
+                api::RoamingSettlementReportItem {
+                    net_id: "010203".into(),
+                    date: "2025-01-01".into(),
+                    role: "SNS".into(),
+                    direction: "uplink".into(),
+                    frame_count: 1,
+                    phy_payload_bytes: 4,
+                    gw_count: 1,
+                },
+                api::RoamingSettlementReportItem {
+                    net_id: "010203".into(),
+                    date: "2025-01-02".into(),
+                    role: "SNS".into(),
+                    direction: "downlink".into(),
+                    frame_count: 1,
+                    phy_payload_bytes: 3,
+                    gw_count: 1,
+                },
+            ],
+            report_resp.get_ref().result
+        );
+
+        // delete
+        let del_req = api::DeleteRoamingAgreementRequest {
+            net_id: "010203".into(),
+        };
+        let mut del_req = Request::new(del_req);
+        del_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let _ = service.delete(del_req).await.unwrap();
+        assert!(roaming::get(&net_id).await.is_err());
+
+        let del_req = api::DeleteRoamingAgreementRequest {
+            net_id: "010203".into(),
+        };
+        let mut del_req = Request::new(del_req);
+        del_req
+            .extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        assert!(service.delete(del_req).await.is_err());
+    }
+}
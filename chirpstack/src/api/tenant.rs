@@ -4,13 +4,13 @@ use std::str::FromStr;
 use chirpstack_api::api;
 use chirpstack_api::api::tenant_service_server::TenantService;
 use chirpstack_api::tonic::{self, Request, Response, Status};
-use lrwn::DevAddrPrefix;
+use lrwn::{DevAddrPrefix, NetID};
 use uuid::Uuid;
 
 use super::auth::{AuthID, validator};
 use super::error::ToStatus;
 use super::helpers;
-use crate::storage::{fields, tenant, user};
+use crate::storage::{device, fields, tenant, user};
 
 pub struct Tenant {
     validator: validator::RequestValidator,
@@ -51,6 +51,12 @@ impl TenantService for Tenant {
             dev_addr_prefixes.push(Some(prefix));
         }
 
+        let net_id = if req_tenant.net_id.is_empty() {
+            None
+        } else {
+            Some(NetID::from_str(&req_tenant.net_id).map_err(|e| e.status())?)
+        };
+
         let t = tenant::Tenant {
             name: req_tenant.name.clone(),
             description: req_tenant.description.clone(),
@@ -61,6 +67,7 @@ impl TenantService for Tenant {
             private_gateways_down: req_tenant.private_gateways_down,
             tags: fields::KeyValue::new(req_tenant.tags.clone()),
             dev_addr_prefixes: fields::DevAddrPrefixVec::new(dev_addr_prefixes),
+            net_id,
             ..Default::default()
         };
 
@@ -108,6 +115,7 @@ impl TenantService for Tenant {
                     .iter()
                     .filter_map(|v| v.map(|v| v.to_string()))
                     .collect(),
+                net_id: t.net_id.map(|v| v.to_string()).unwrap_or_default(),
             }),
             created_at: Some(helpers::datetime_to_prost_timestamp(&t.created_at)),
             updated_at: Some(helpers::datetime_to_prost_timestamp(&t.updated_at)),
@@ -145,6 +153,12 @@ impl TenantService for Tenant {
             dev_addr_prefixes.push(Some(prefix));
         }
 
+        let net_id = if req_tenant.net_id.is_empty() {
+            None
+        } else {
+            Some(NetID::from_str(&req_tenant.net_id).map_err(|e| e.status())?)
+        };
+
         // update
         let _ = tenant::update(tenant::Tenant {
             id: tenant_id.into(),
@@ -157,6 +171,7 @@ impl TenantService for Tenant {
             private_gateways_down: req_tenant.private_gateways_down,
             tags: fields::KeyValue::new(req_tenant.tags.clone()),
             dev_addr_prefixes: fields::DevAddrPrefixVec::new(dev_addr_prefixes),
+            net_id,
             ..Default::default()
         })
         .await
@@ -575,6 +590,43 @@ impl TenantService for Tenant {
 
         Ok(resp)
     }
+
+    async fn get_dev_addr_prefix_utilization(
+        &self,
+        request: Request<api::GetTenantDevAddrPrefixUtilizationRequest>,
+    ) -> Result<Response<api::GetTenantDevAddrPrefixUtilizationResponse>, Status> {
+        let req = request.get_ref();
+        let tenant_id = Uuid::from_str(&req.tenant_id).map_err(|e| e.status())?;
+
+        self.validator
+            .validate(
+                request.extensions(),
+                validator::ValidateTenantAccess::new(validator::Flag::Read, tenant_id),
+            )
+            .await?;
+
+        let t = tenant::get(&tenant_id).await.map_err(|e| e.status())?;
+
+        let mut result = vec![];
+        for prefix in t.get_dev_addr_prefixes() {
+            let u = device::get_dev_addr_utilization(prefix)
+                .await
+                .map_err(|e| e.status())?;
+
+            result.push(api::DevAddrPrefixUtilization {
+                dev_addr_prefix: prefix.to_string(),
+                size: prefix.dev_addr_count(),
+                used_count: u.dev_addr_count as u64,
+                device_count: u.device_count as u64,
+            });
+        }
+
+        let mut resp = Response::new(api::GetTenantDevAddrPrefixUtilizationResponse { result });
+        resp.metadata_mut()
+            .insert("x-log-tenant_id", req.tenant_id.parse().unwrap());
+
+        Ok(resp)
+    }
 }
 
 #[cfg(test)]
@@ -582,7 +634,81 @@ pub mod test {
     use super::*;
     use crate::api::auth::AuthID;
     use crate::api::auth::validator::RequestValidator;
+    use crate::storage;
     use crate::test;
+    use lrwn::{DevAddr, EUI64};
+
+    #[tokio::test]
+    async fn test_dev_addr_prefix_utilization() {
+        let _guard = test::prepare().await;
+
+        let u = user::create(user::User {
+            is_admin: true,
+            is_active: true,
+            email: "admin@admin".into(),
+            email_verified: true,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let t = tenant::create(tenant::Tenant {
+            name: "test-tenant".into(),
+            dev_addr_prefixes: fields::DevAddrPrefixVec::new(vec![
+                Some(DevAddrPrefix::from_str("01020300/24").unwrap()),
+                Some(DevAddrPrefix::from_str("01020400/24").unwrap()),
+            ]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let dp = storage::device_profile::test::create_device_profile(Some(t.id.into())).await;
+        let app = storage::application::test::create_application(Some(t.id.into())).await;
+
+        for (dev_eui, dev_addr) in [
+            ([1, 1, 1, 1, 1, 1, 1, 1], [1, 2, 3, 4]),
+            ([2, 2, 2, 2, 2, 2, 2, 2], [1, 2, 3, 4]),
+            ([3, 3, 3, 3, 3, 3, 3, 3], [1, 2, 3, 5]),
+        ] {
+            device::create(device::Device {
+                name: "test-dev".into(),
+                dev_eui: EUI64::from_be_bytes(dev_eui),
+                application_id: app.id,
+                device_profile_id: dp.id,
+                dev_addr: Some(DevAddr::from_be_bytes(dev_addr)),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        }
+
+        let service = Tenant::new(RequestValidator::new());
+        let mut req = Request::new(api::GetTenantDevAddrPrefixUtilizationRequest {
+            tenant_id: t.id.to_string(),
+        });
+        req.extensions_mut()
+            .insert(AuthID::User(Into::<uuid::Uuid>::into(u.id)));
+        let resp = service.get_dev_addr_prefix_utilization(req).await.unwrap();
+        assert_eq!(
+            api::GetTenantDevAddrPrefixUtilizationResponse {
+                result: vec![
+                    api::DevAddrPrefixUtilization {
+                        dev_addr_prefix: "01020300/24".into(),
+                        size: 256,
+                        used_count: 2,
+                        device_count: 3,
+                    },
+                    api::DevAddrPrefixUtilization {
+                        dev_addr_prefix: "01020400/24".into(),
+                        size: 256,
+                        used_count: 0,
+                        device_count: 0,
+                    },
+                ],
+            },
+            *resp.get_ref()
+        );
+    }
 
     #[tokio::test]
     async fn test_tenant() {
@@ -649,6 +775,7 @@ pub mod test {
                 can_have_gateways: true,
                 max_device_count: 10,
                 max_gateway_count: 3,
+                net_id: "000000".into(),
                 ..Default::default()
             }),
         };
@@ -675,6 +802,7 @@ pub mod test {
                 can_have_gateways: true,
                 max_device_count: 10,
                 max_gateway_count: 3,
+                net_id: "000000".into(),
                 ..Default::default()
             }),
             get_resp.get_ref().tenant
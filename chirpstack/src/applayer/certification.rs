@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use tracing::{info, warn};
+
+use crate::api::helpers::ToProto;
+use crate::integration;
+use crate::storage::fields::device_profile::Ts009Version;
+use crate::storage::{application, device, device_profile, device_queue, fields, tenant};
+use chirpstack_api::gw;
+use chirpstack_api::integration as integration_pb;
+use lrwn::applayer::certification;
+
+pub struct Package;
+
+#[async_trait]
+impl super::Package for Package {
+    fn name(&self) -> &'static str {
+        "ts009"
+    }
+
+    fn versions(&self) -> &'static [&'static str] {
+        &["v1.0.0"]
+    }
+
+    fn f_port(&self, params: &fields::AppLayerParams) -> Option<u8> {
+        params.ts009_version.map(|_| params.ts009_f_port)
+    }
+
+    async fn handle_uplink(
+        &self,
+        dev: &device::Device,
+        dp: &device_profile::DeviceProfile,
+        _rx_info: &[gw::UplinkRxInfo],
+        data: &[u8],
+    ) -> Result<()> {
+        handle_uplink(dev, dp, data).await
+    }
+}
+
+pub async fn handle_uplink(
+    dev: &device::Device,
+    dp: &device_profile::DeviceProfile,
+    data: &[u8],
+) -> Result<()> {
+    let version = dp
+        .app_layer_params
+        .ts009_version
+        .ok_or_else(|| anyhow!("Device does not support TS009"))?;
+
+    match version {
+        Ts009Version::V100 => handle_uplink_v100(dev, dp, data).await,
+    }
+}
+
+// Enqueue the given certification command for the device under test. In case of an
+// EchoPayloadReq, the payload is stored such that the EchoPayloadAns can be validated.
+pub async fn enqueue(
+    dev: &device::Device,
+    dp: &device_profile::DeviceProfile,
+    pl: certification::v1::Payload,
+) -> Result<device_queue::DeviceQueueItem> {
+    let version = dp
+        .app_layer_params
+        .ts009_version
+        .ok_or_else(|| anyhow!("Device does not support TS009"))?;
+
+    let data = match version {
+        Ts009Version::V100 => pl.to_vec()?,
+    };
+
+    if let certification::v1::Payload::EchoPayloadReq(pl) = &pl {
+        let mut app_layer_params = dev.app_layer_params.clone();
+        app_layer_params.ts009_echo_payload = Some(pl.payload.clone());
+
+        device::partial_update(
+            dev.dev_eui,
+            &device::DeviceChangeset {
+                app_layer_params: Some(app_layer_params),
+                ..Default::default()
+            },
+        )
+        .await?;
+    }
+
+    let qi = device_queue::enqueue_item(device_queue::DeviceQueueItem {
+        dev_eui: dev.dev_eui,
+        f_port: dp.app_layer_params.ts009_f_port.into(),
+        data,
+        ..Default::default()
+    })
+    .await?;
+
+    Ok(qi)
+}
+
+async fn handle_uplink_v100(
+    dev: &device::Device,
+    dp: &device_profile::DeviceProfile,
+    data: &[u8],
+) -> Result<()> {
+    let pl = certification::v1::Payload::from_slice(true, data)?;
+
+    match pl {
+        certification::v1::Payload::PackageVersionAns(pl) => {
+            handle_v1_package_version_ans(dev, dp, pl).await?
+        }
+        certification::v1::Payload::EchoPayloadAns(pl) => {
+            handle_v1_echo_payload_ans(dev, dp, pl).await?
+        }
+        certification::v1::Payload::RxAppCntAns(pl) => {
+            handle_v1_rx_app_cnt_ans(dev, dp, pl).await?
+        }
+        certification::v1::Payload::DutVersionsAns(pl) => {
+            handle_v1_dut_versions_ans(dev, dp, pl).await?
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+async fn handle_v1_package_version_ans(
+    dev: &device::Device,
+    dp: &device_profile::DeviceProfile,
+    pl: certification::v1::PackageVersionAnsPayload,
+) -> Result<()> {
+    info!(
+        package_identifier = pl.package_identifier,
+        package_version = pl.package_version,
+        "Handling PackageVersionAns"
+    );
+
+    log_event(
+        dev,
+        dp,
+        integration_pb::LogLevel::Info,
+        "PackageVersionAns received".into(),
+        [
+            (
+                "package_identifier".to_string(),
+                pl.package_identifier.to_string(),
+            ),
+            (
+                "package_version".to_string(),
+                pl.package_version.to_string(),
+            ),
+        ]
+        .into(),
+    )
+    .await
+}
+
+async fn handle_v1_echo_payload_ans(
+    dev: &device::Device,
+    dp: &device_profile::DeviceProfile,
+    pl: certification::v1::EchoPayloadAnsPayload,
+) -> Result<()> {
+    info!("Handling EchoPayloadAns");
+
+    let echo_payload = match &dev.app_layer_params.ts009_echo_payload {
+        Some(v) => v.clone(),
+        None => {
+            warn!("Received EchoPayloadAns without pending EchoPayloadReq");
+            return log_event(
+                dev,
+                dp,
+                integration_pb::LogLevel::Warning,
+                "EchoPayloadAns received without pending EchoPayloadReq".into(),
+                [("payload".to_string(), hex::encode(&pl.payload))].into(),
+            )
+            .await;
+        }
+    };
+
+    let expected = certification::v1::EchoPayloadReqPayload {
+        payload: echo_payload,
+    }
+    .expected_ans();
+
+    let mut app_layer_params = dev.app_layer_params.clone();
+    app_layer_params.ts009_echo_payload = None;
+
+    device::partial_update(
+        dev.dev_eui,
+        &device::DeviceChangeset {
+            app_layer_params: Some(app_layer_params),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let context: HashMap<String, String> = [
+        ("payload".to_string(), hex::encode(&pl.payload)),
+        (
+            "expected_payload".to_string(),
+            hex::encode(&expected.payload),
+        ),
+    ]
+    .into();
+
+    if expected == pl {
+        log_event(
+            dev,
+            dp,
+            integration_pb::LogLevel::Info,
+            "EchoPayloadAns matches the expected payload".into(),
+            context,
+        )
+        .await
+    } else {
+        warn!("EchoPayloadAns does not match the expected payload");
+        log_event(
+            dev,
+            dp,
+            integration_pb::LogLevel::Error,
+            "EchoPayloadAns does not match the expected payload".into(),
+            context,
+        )
+        .await
+    }
+}
+
+async fn handle_v1_rx_app_cnt_ans(
+    dev: &device::Device,
+    dp: &device_profile::DeviceProfile,
+    pl: certification::v1::RxAppCntAnsPayload,
+) -> Result<()> {
+    info!(rx_app_cnt = pl.rx_app_cnt, "Handling RxAppCntAns");
+
+    log_event(
+        dev,
+        dp,
+        integration_pb::LogLevel::Info,
+        "RxAppCntAns received".into(),
+        [("rx_app_cnt".to_string(), pl.rx_app_cnt.to_string())].into(),
+    )
+    .await
+}
+
+async fn handle_v1_dut_versions_ans(
+    dev: &device::Device,
+    dp: &device_profile::DeviceProfile,
+    pl: certification::v1::DutVersionsAnsPayload,
+) -> Result<()> {
+    info!("Handling DutVersionsAns");
+
+    let fmt = |v: &certification::v1::Version| {
+        format!("{}.{}.{}.{}", v.major, v.minor, v.patch, v.revision)
+    };
+
+    log_event(
+        dev,
+        dp,
+        integration_pb::LogLevel::Info,
+        "DutVersionsAns received".into(),
+        [
+            ("fw_version".to_string(), fmt(&pl.fw_version)),
+            ("lrwan_version".to_string(), fmt(&pl.lrwan_version)),
+            ("lrwan_rp_version".to_string(), fmt(&pl.lrwan_rp_version)),
+        ]
+        .into(),
+    )
+    .await
+}
+
+async fn log_event(
+    dev: &device::Device,
+    dp: &device_profile::DeviceProfile,
+    level: integration_pb::LogLevel,
+    description: String,
+    context: HashMap<String, String>,
+) -> Result<()> {
+    let app = application::get(&dev.application_id).await?;
+    let t = tenant::get(&app.tenant_id).await?;
+
+    let log_event = integration_pb::LogEvent {
+        time: Some(Utc::now().into()),
+        device_info: Some(integration_pb::DeviceInfo {
+            tenant_id: t.id.to_string(),
+            tenant_name: t.name.clone(),
+            application_id: app.id.to_string(),
+            application_name: app.name.to_string(),
+            device_profile_id: dp.id.to_string(),
+            device_profile_name: dp.name.clone(),
+            device_name: dev.name.clone(),
+            device_class_enabled: dev.enabled_class.to_proto().into(),
+            dev_eui: dev.dev_eui.to_string(),
+            tags: {
+                let mut tags = (*app.tags).clone();
+                tags.extend((*dp.tags).clone());
+                tags.extend((*dev.tags).clone());
+                tags
+            },
+        }),
+        level: level.into(),
+        code: integration_pb::LogCode::Certification.into(),
+        description,
+        context,
+    };
+
+    integration::log_event(app.id.into(), &dev.variables, &log_event).await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::applayer::handle_uplink;
+    use crate::storage::fields;
+    use crate::test;
+    use lrwn::EUI64;
+
+    #[tokio::test]
+    async fn test_echo_payload() {
+        let _guard = test::prepare().await;
+        integration::set_mock().await;
+
+        let t = tenant::create(tenant::Tenant {
+            name: "test-tenant".into(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let app = application::create(application::Application {
+            name: "test-app".into(),
+            tenant_id: t.id,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let dp = device_profile::create(device_profile::DeviceProfile {
+            name: "test-dp".into(),
+            tenant_id: Some(t.id),
+            app_layer_params: fields::AppLayerParams {
+                ts009_version: Some(Ts009Version::V100),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let d = device::create(device::Device {
+            name: "test-dev".into(),
+            dev_eui: EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8]),
+            application_id: app.id,
+            device_profile_id: dp.id,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let qi = enqueue(
+            &d,
+            &dp,
+            certification::v1::Payload::EchoPayloadReq(certification::v1::EchoPayloadReqPayload {
+                payload: vec![0x01, 0xff],
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(224, qi.f_port);
+        assert_eq!(vec![0x08, 0x01, 0xff], qi.data);
+
+        let d = device::get(&d.dev_eui).await.unwrap();
+        assert_eq!(
+            Some(vec![0x01, 0xff]),
+            d.app_layer_params.ts009_echo_payload
+        );
+
+        let pl =
+            certification::v1::Payload::EchoPayloadAns(certification::v1::EchoPayloadAnsPayload {
+                payload: vec![0x02, 0x00],
+            });
+
+        handle_uplink(
+            &d,
+            &dp,
+            &[],
+            dp.app_layer_params.ts009_f_port,
+            &pl.to_vec().unwrap(),
+        )
+        .await;
+
+        let d = device::get(&d.dev_eui).await.unwrap();
+        assert!(d.app_layer_params.ts009_echo_payload.is_none());
+
+        let log_event = integration::mock::get_log_event().await.unwrap();
+        assert_eq!(
+            integration_pb::LogCode::Certification as i32,
+            log_event.code
+        );
+        assert_eq!(integration_pb::LogLevel::Info as i32, log_event.level);
+        assert_eq!(
+            "EchoPayloadAns matches the expected payload",
+            log_event.description
+        );
+    }
+}
@@ -1,84 +1,107 @@
 use anyhow::Result;
+use async_trait::async_trait;
 use tracing::info;
 
 use crate::gpstime::ToGpsTime;
 use crate::storage::fields::device_profile::Ts003Version;
-use crate::storage::{device, device_profile, device_queue};
+use crate::storage::{device, device_profile, device_queue, fields};
 use crate::uplink::helpers;
 use chirpstack_api::gw;
 use lrwn::applayer::clocksync;
 
+pub struct Package;
+
+#[async_trait]
+impl super::Package for Package {
+    fn name(&self) -> &'static str {
+        "ts003"
+    }
+
+    fn versions(&self) -> &'static [&'static str] {
+        &["v1.0.0", "v2.0.0"]
+    }
+
+    fn f_port(&self, params: &fields::AppLayerParams) -> Option<u8> {
+        params.ts003_version.map(|_| params.ts003_f_port)
+    }
+
+    async fn handle_uplink(
+        &self,
+        dev: &device::Device,
+        dp: &device_profile::DeviceProfile,
+        rx_info: &[gw::UplinkRxInfo],
+        data: &[u8],
+    ) -> Result<()> {
+        handle_uplink(dev, dp, rx_info, data).await
+    }
+}
+
 pub async fn handle_uplink(
     dev: &device::Device,
     dp: &device_profile::DeviceProfile,
     rx_info: &[gw::UplinkRxInfo],
     data: &[u8],
 ) -> Result<()> {
+    if let Some(ans) = handle_request(dp, rx_info, data)? {
+        device_queue::enqueue_item(device_queue::DeviceQueueItem {
+            dev_eui: dev.dev_eui,
+            f_port: dp.app_layer_params.ts003_f_port.into(),
+            data: ans,
+            ..Default::default()
+        })
+        .await?;
+    }
+
+    Ok(())
+}
+
+// Handles the given request and returns the answer to send (if any). This is
+// also used by the multi-package handler, which encapsulates the answer.
+pub fn handle_request(
+    dp: &device_profile::DeviceProfile,
+    rx_info: &[gw::UplinkRxInfo],
+    data: &[u8],
+) -> Result<Option<Vec<u8>>> {
     let version = dp
         .app_layer_params
         .ts003_version
         .ok_or_else(|| anyhow!("Device does not support TS003"))?;
 
     match version {
-        Ts003Version::V100 => handle_uplink_v100(dev, dp, rx_info, data).await,
-        Ts003Version::V200 => handle_uplink_v200(dev, dp, rx_info, data).await,
+        Ts003Version::V100 => handle_request_v100(rx_info, data),
+        Ts003Version::V200 => handle_request_v200(rx_info, data),
     }
 }
 
-async fn handle_uplink_v100(
-    dev: &device::Device,
-    dp: &device_profile::DeviceProfile,
-    rx_info: &[gw::UplinkRxInfo],
-    data: &[u8],
-) -> Result<()> {
+fn handle_request_v100(rx_info: &[gw::UplinkRxInfo], data: &[u8]) -> Result<Option<Vec<u8>>> {
     let pl = clocksync::v1::Payload::from_slice(true, data)?;
 
     if let clocksync::v1::Payload::AppTimeReq(pl) = pl {
-        handle_v1_app_time_req(dev, dp, rx_info, pl).await?
+        return handle_v1_app_time_req(rx_info, pl);
     }
 
-    Ok(())
+    Ok(None)
 }
 
-async fn handle_uplink_v200(
-    dev: &device::Device,
-    dp: &device_profile::DeviceProfile,
-    rx_info: &[gw::UplinkRxInfo],
-    data: &[u8],
-) -> Result<()> {
+fn handle_request_v200(rx_info: &[gw::UplinkRxInfo], data: &[u8]) -> Result<Option<Vec<u8>>> {
     let pl = clocksync::v2::Payload::from_slice(true, data)?;
 
     if let clocksync::v2::Payload::AppTimeReq(pl) = pl {
-        handle_v2_app_time_req(dev, dp, rx_info, pl).await?
+        return handle_v2_app_time_req(rx_info, pl);
     }
 
-    Ok(())
+    Ok(None)
 }
 
-async fn handle_v1_app_time_req(
-    dev: &device::Device,
-    dp: &device_profile::DeviceProfile,
+fn handle_v1_app_time_req(
     rx_info: &[gw::UplinkRxInfo],
     pl: clocksync::v1::AppTimeReqPayload,
-) -> Result<()> {
+) -> Result<Option<Vec<u8>>> {
     info!("Handling AppTimeReq");
 
-    let now_time_since_gps = if let Some(t) = helpers::get_time_since_gps_epoch(rx_info) {
-        chrono::Duration::from_std(t)?
-    } else {
-        helpers::get_rx_timestamp_chrono(rx_info).to_gps_time()
-    };
-    let dev_time_since_gps = chrono::Duration::seconds(pl.device_time.into());
-
-    let time_diff = (now_time_since_gps - dev_time_since_gps).num_seconds();
-    let time_correction: i32 = if time_diff < 0 {
-        time_diff.try_into().unwrap_or(i32::MIN)
-    } else {
-        time_diff.try_into().unwrap_or(i32::MAX)
-    };
-
-    if time_diff == 0 && !pl.param.ans_required {
-        return Ok(());
+    let time_correction = get_time_correction(rx_info, pl.device_time)?;
+    if time_correction == 0 && !pl.param.ans_required {
+        return Ok(None);
     }
 
     info!(
@@ -93,41 +116,18 @@ async fn handle_v1_app_time_req(
         },
     });
 
-    device_queue::enqueue_item(device_queue::DeviceQueueItem {
-        dev_eui: dev.dev_eui,
-        f_port: dp.app_layer_params.ts003_f_port.into(),
-        data: ans.to_vec()?,
-        ..Default::default()
-    })
-    .await?;
-
-    Ok(())
+    Ok(Some(ans.to_vec()?))
 }
 
-async fn handle_v2_app_time_req(
-    dev: &device::Device,
-    dp: &device_profile::DeviceProfile,
+fn handle_v2_app_time_req(
     rx_info: &[gw::UplinkRxInfo],
     pl: clocksync::v2::AppTimeReqPayload,
-) -> Result<()> {
+) -> Result<Option<Vec<u8>>> {
     info!("Handling AppTimeReq");
 
-    let now_time_since_gps = if let Some(t) = helpers::get_time_since_gps_epoch(rx_info) {
-        chrono::Duration::from_std(t)?
-    } else {
-        helpers::get_rx_timestamp_chrono(rx_info).to_gps_time()
-    };
-    let dev_time_since_gps = chrono::Duration::seconds(pl.device_time.into());
-
-    let time_diff = (now_time_since_gps - dev_time_since_gps).num_seconds();
-    let time_correction: i32 = if time_diff < 0 {
-        time_diff.try_into().unwrap_or(i32::MIN)
-    } else {
-        time_diff.try_into().unwrap_or(i32::MAX)
-    };
-
-    if time_diff == 0 && !pl.param.ans_required {
-        return Ok(());
+    let time_correction = get_time_correction(rx_info, pl.device_time)?;
+    if time_correction == 0 && !pl.param.ans_required {
+        return Ok(None);
     }
 
     info!(
@@ -142,15 +142,23 @@ async fn handle_v2_app_time_req(
         },
     });
 
-    device_queue::enqueue_item(device_queue::DeviceQueueItem {
-        dev_eui: dev.dev_eui,
-        f_port: dp.app_layer_params.ts003_f_port.into(),
-        data: ans.to_vec()?,
-        ..Default::default()
-    })
-    .await?;
+    Ok(Some(ans.to_vec()?))
+}
 
-    Ok(())
+fn get_time_correction(rx_info: &[gw::UplinkRxInfo], device_time: u32) -> Result<i32> {
+    let now_time_since_gps = if let Some(t) = helpers::get_time_since_gps_epoch(rx_info) {
+        chrono::Duration::from_std(t)?
+    } else {
+        helpers::get_rx_timestamp_chrono(rx_info).to_gps_time()
+    };
+    let dev_time_since_gps = chrono::Duration::seconds(device_time.into());
+
+    let time_diff = (now_time_since_gps - dev_time_since_gps).num_seconds();
+    Ok(if time_diff < 0 {
+        time_diff.try_into().unwrap_or(i32::MIN)
+    } else {
+        time_diff.try_into().unwrap_or(i32::MAX)
+    })
 }
 
 #[cfg(test)]
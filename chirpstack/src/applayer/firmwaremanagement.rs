@@ -0,0 +1,223 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use tracing::{info, warn};
+
+use crate::storage::fields::device::Ts006DevVersion;
+use crate::storage::fields::device_profile::Ts006Version;
+use crate::storage::{device, device_profile, fields, fuota};
+use chirpstack_api::gw;
+use lrwn::applayer::firmwaremanagement;
+
+pub struct Package;
+
+#[async_trait]
+impl super::Package for Package {
+    fn name(&self) -> &'static str {
+        "ts006"
+    }
+
+    fn versions(&self) -> &'static [&'static str] {
+        &["v1.0.0"]
+    }
+
+    fn f_port(&self, params: &fields::AppLayerParams) -> Option<u8> {
+        params.ts006_version.map(|_| params.ts006_f_port)
+    }
+
+    async fn handle_uplink(
+        &self,
+        dev: &device::Device,
+        dp: &device_profile::DeviceProfile,
+        _rx_info: &[gw::UplinkRxInfo],
+        data: &[u8],
+    ) -> Result<()> {
+        handle_uplink(dev, dp, data).await
+    }
+}
+
+pub async fn handle_uplink(
+    dev: &device::Device,
+    dp: &device_profile::DeviceProfile,
+    data: &[u8],
+) -> Result<()> {
+    let version = dp
+        .app_layer_params
+        .ts006_version
+        .ok_or_else(|| anyhow!("Device does not support TS006"))?;
+
+    match version {
+        Ts006Version::V100 => handle_uplink_v100(dev, data).await,
+    }
+}
+
+async fn handle_uplink_v100(dev: &device::Device, data: &[u8]) -> Result<()> {
+    let pl = firmwaremanagement::v1::Payload::from_slice(true, data)?;
+
+    match pl {
+        firmwaremanagement::v1::Payload::DevVersionAns(pl) => {
+            handle_v1_dev_version_ans(dev, pl).await?
+        }
+        firmwaremanagement::v1::Payload::DevUpgradeImageAns(pl) => {
+            handle_v1_dev_upgrade_image_ans(dev, pl).await?
+        }
+        firmwaremanagement::v1::Payload::DevRebootCountdownAns(pl) => {
+            handle_v1_dev_reboot_countdown_ans(dev, pl).await?
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+async fn handle_v1_dev_version_ans(
+    dev: &device::Device,
+    pl: firmwaremanagement::v1::DevVersionAnsPayload,
+) -> Result<()> {
+    info!(
+        fw_version = pl.fw_version,
+        hw_version = pl.hw_version,
+        "Handling DevVersionAns"
+    );
+
+    let mut app_layer_params = dev.app_layer_params.clone();
+    app_layer_params.ts006_dev_version = Some(Ts006DevVersion {
+        fw_version: pl.fw_version,
+        hw_version: pl.hw_version,
+        reported_at: Utc::now(),
+    });
+
+    device::partial_update(
+        dev.dev_eui,
+        &device::DeviceChangeset {
+            app_layer_params: Some(app_layer_params),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn handle_v1_dev_upgrade_image_ans(
+    dev: &device::Device,
+    pl: firmwaremanagement::v1::DevUpgradeImageAnsPayload,
+) -> Result<()> {
+    info!("Handling DevUpgradeImageAns");
+
+    let mut fuota_dev = fuota::get_latest_device_by_dev_eui(dev.dev_eui).await?;
+
+    if pl.status.up_image_status != firmwaremanagement::v1::UpImageStatus::ValidImage {
+        warn!(
+            up_image_status = ?pl.status.up_image_status,
+            "DevUpgradeImageAns contains errors"
+        );
+        fuota_dev.error_msg = format!(
+            "Error: DevUpgradeImageAns response up_image_status={:?}",
+            pl.status.up_image_status
+        );
+    } else {
+        info!(
+            next_firmware_version = pl.next_firmware_version,
+            "Upgrade image is valid"
+        );
+        fuota_dev.upgrade_image_completed_at = Some(Utc::now());
+    }
+
+    let _ = fuota::update_device(fuota_dev).await?;
+
+    Ok(())
+}
+
+async fn handle_v1_dev_reboot_countdown_ans(
+    dev: &device::Device,
+    pl: firmwaremanagement::v1::DevRebootCountdownAnsPayload,
+) -> Result<()> {
+    info!(countdown = pl.countdown, "Handling DevRebootCountdownAns");
+
+    let mut fuota_dev = fuota::get_latest_device_by_dev_eui(dev.dev_eui).await?;
+
+    if pl.countdown == 0xffffff {
+        warn!("DevRebootCountdownAns indicates that no reboot is scheduled");
+        fuota_dev.error_msg =
+            "Error: DevRebootCountdownAns response indicates that no reboot is scheduled".into();
+    } else {
+        fuota_dev.reboot_completed_at = Some(Utc::now());
+    }
+
+    let _ = fuota::update_device(fuota_dev).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::applayer::handle_uplink;
+    use crate::storage::{application, fields, tenant};
+    use crate::test;
+    use lrwn::EUI64;
+
+    #[tokio::test]
+    async fn test_handle_v1_dev_version_ans() {
+        let _guard = test::prepare().await;
+        let t = tenant::create(tenant::Tenant {
+            name: "test-tenant".into(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let app = application::create(application::Application {
+            name: "test-app".into(),
+            tenant_id: t.id,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let dp = device_profile::create(device_profile::DeviceProfile {
+            name: "test-dp".into(),
+            tenant_id: Some(t.id),
+            app_layer_params: fields::AppLayerParams {
+                ts006_version: Some(Ts006Version::V100),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let d = device::create(device::Device {
+            name: "test-dev".into(),
+            dev_eui: EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8]),
+            application_id: app.id,
+            device_profile_id: dp.id,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        assert!(d.app_layer_params.ts006_dev_version.is_none());
+
+        let pl = firmwaremanagement::v1::Payload::DevVersionAns(
+            firmwaremanagement::v1::DevVersionAnsPayload {
+                fw_version: 0x01020304,
+                hw_version: 0x05060708,
+            },
+        );
+
+        handle_uplink(
+            &d,
+            &dp,
+            &[],
+            dp.app_layer_params.ts006_f_port,
+            &pl.to_vec().unwrap(),
+        )
+        .await;
+
+        let d = device::get(&d.dev_eui).await.unwrap();
+        let dev_version = d.app_layer_params.ts006_dev_version.unwrap();
+        assert_eq!(0x01020304, dev_version.fw_version);
+        assert_eq!(0x05060708, dev_version.hw_version);
+    }
+}
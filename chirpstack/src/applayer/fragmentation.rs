@@ -1,11 +1,40 @@
 use anyhow::Result;
+use async_trait::async_trait;
 use chrono::Utc;
 use tracing::{info, warn};
 
 use crate::storage::fields::device_profile::Ts004Version;
-use crate::storage::{device, device_profile, fuota};
+use crate::storage::{device, device_profile, fields, fuota};
+use chirpstack_api::gw;
 use lrwn::applayer::fragmentation;
 
+pub struct Package;
+
+#[async_trait]
+impl super::Package for Package {
+    fn name(&self) -> &'static str {
+        "ts004"
+    }
+
+    fn versions(&self) -> &'static [&'static str] {
+        &["v1.0.0", "v2.0.0"]
+    }
+
+    fn f_port(&self, params: &fields::AppLayerParams) -> Option<u8> {
+        params.ts004_version.map(|_| params.ts004_f_port)
+    }
+
+    async fn handle_uplink(
+        &self,
+        dev: &device::Device,
+        dp: &device_profile::DeviceProfile,
+        _rx_info: &[gw::UplinkRxInfo],
+        data: &[u8],
+    ) -> Result<()> {
+        handle_uplink(dev, dp, data).await
+    }
+}
+
 pub async fn handle_uplink(
     dev: &device::Device,
     dp: &device_profile::DeviceProfile,
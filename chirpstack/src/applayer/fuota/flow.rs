@@ -5,7 +5,7 @@ use anyhow::Result;
 use chrono::{DateTime, TimeDelta, Utc};
 use tracing::info;
 
-use lrwn::applayer::{fragmentation, multicastsetup};
+use lrwn::applayer::{firmwaremanagement, fragmentation, multicastsetup};
 use lrwn::region::MacVersion;
 
 use crate::config;
@@ -13,10 +13,14 @@ use crate::downlink;
 use crate::gpstime::ToGpsTime;
 use crate::storage::fields::{
     FuotaJob, RequestFragmentationSessionStatus, device_profile::Ts004Version,
-    device_profile::Ts005Version,
+    device_profile::Ts005Version, device_profile::Ts006Version,
 };
 use crate::storage::{device, device_keys, device_profile, device_queue, fuota, multicast};
 
+// Countdown (seconds) used in the DevRebootCountdownReq. This gives the device the time to
+// send the DevRebootCountdownAns before it reboots into the new firmware.
+const REBOOT_COUNTDOWN: u32 = 60;
+
 pub struct Flow {
     scheduler_interval: Duration,
     job: fuota::FuotaDeploymentJob,
@@ -52,6 +56,8 @@ impl Flow {
             FuotaJob::Enqueue => self.enqueue().await,
             FuotaJob::FragStatus => self.fragmentation_status().await,
             FuotaJob::DeleteMcGroup => self.delete_mc_group().await,
+            FuotaJob::UpgradeImage => self.upgrade_image().await,
+            FuotaJob::Reboot => self.reboot().await,
             FuotaJob::Complete => self.complete().await,
         };
 
@@ -74,7 +80,9 @@ impl Flow {
                         max_retry_count: match next_job {
                             FuotaJob::McGroupSetup
                             | FuotaJob::FragSessionSetup
-                            | FuotaJob::McSession => self.fuota_deployment.unicast_max_retry_count,
+                            | FuotaJob::McSession
+                            | FuotaJob::UpgradeImage
+                            | FuotaJob::Reboot => self.fuota_deployment.unicast_max_retry_count,
                             _ => 0,
                         },
                         scheduler_run_after,
@@ -211,10 +219,10 @@ impl Flow {
             info!("Set timeout error to devices that did not respond to McGroupSetupReq");
             fuota::set_device_timeout_error(
                 self.fuota_deployment.id.into(),
-                true,
-                false,
-                false,
-                false,
+                fuota::DeviceSteps {
+                    mc_group_setup: true,
+                    ..Default::default()
+                },
             )
             .await?;
 
@@ -349,10 +357,10 @@ impl Flow {
             info!("Set timeout error to devices that did not respond to FragSessionSetupReq");
             fuota::set_device_timeout_error(
                 self.fuota_deployment.id.into(),
-                false,
-                false,
-                true,
-                false,
+                fuota::DeviceSteps {
+                    frag_session_setup: true,
+                    ..Default::default()
+                },
             )
             .await?;
 
@@ -507,10 +515,10 @@ impl Flow {
             info!("Set timeout error to devices that did not respond to McSessionReq");
             fuota::set_device_timeout_error(
                 self.fuota_deployment.id.into(),
-                false,
-                true,
-                false,
-                false,
+                fuota::DeviceSteps {
+                    mc_session: true,
+                    ..Default::default()
+                },
             )
             .await?;
 
@@ -814,10 +822,10 @@ impl Flow {
             info!("Set timeout error to devices that did not respond to FragSessionStatusReq");
             fuota::set_device_timeout_error(
                 self.fuota_deployment.id.into(),
-                false,
-                false,
-                false,
-                true,
+                fuota::DeviceSteps {
+                    frag_status: true,
+                    ..Default::default()
+                },
             )
             .await?;
 
@@ -883,9 +891,17 @@ impl Flow {
     }
 
     async fn delete_mc_group(&mut self) -> Result<Option<(FuotaJob, DateTime<Utc>)>> {
+        // In case the device-profile supports TS006, the received firmware image must
+        // be verified and the device must be rebooted before completing the deployment.
+        let next_job = if self.device_profile.app_layer_params.ts006_version.is_some() {
+            FuotaJob::UpgradeImage
+        } else {
+            FuotaJob::Complete
+        };
+
         // Proceed with next step after reaching the max attempts.
         if self.job.attempt_count > self.job.max_retry_count {
-            return Ok(Some((FuotaJob::Complete, Utc::now())));
+            return Ok(Some((next_job, Utc::now())));
         }
 
         info!("Delete multicast group");
@@ -893,7 +909,174 @@ impl Flow {
 
         multicast::delete(&self.fuota_deployment.id).await?;
 
-        Ok(Some((FuotaJob::Complete, Utc::now())))
+        Ok(Some((next_job, Utc::now())))
+    }
+
+    async fn upgrade_image(&mut self) -> Result<Option<(FuotaJob, DateTime<Utc>)>> {
+        let no_frag_status_request = self.fuota_deployment.request_fragmentation_session_status
+            == RequestFragmentationSessionStatus::NoRequest;
+
+        let fuota_devices = fuota::get_devices(self.job.fuota_deployment_id.into(), -1, 0).await?;
+
+        // Filter on devices that have received the fragmented payload (or are assumed to
+        // have received it in case the fragmentation status was not requested).
+        let fuota_devices: Vec<fuota::FuotaDeploymentDevice> = fuota_devices
+            .into_iter()
+            .filter(|d| {
+                d.error_msg.is_empty()
+                    && if no_frag_status_request {
+                        d.mc_session_completed_at.is_some()
+                    } else {
+                        d.frag_status_completed_at.is_some()
+                    }
+            })
+            .collect();
+        let fuota_devices_received_count = fuota_devices.len();
+
+        // Filter on devices that have not yet responded to the DevUpgradeImageReq.
+        let fuota_devices: Vec<fuota::FuotaDeploymentDevice> = fuota_devices
+            .into_iter()
+            .filter(|d| d.upgrade_image_completed_at.is_none())
+            .collect();
+
+        // Proceed with next step after reaching the max attempts.
+        if self.job.attempt_count > self.job.max_retry_count {
+            info!("Set timeout error to devices that did not respond to DevUpgradeImageReq");
+            fuota::set_device_timeout_error(
+                self.fuota_deployment.id.into(),
+                fuota::DeviceSteps {
+                    upgrade_image: true,
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+            if !fuota_devices.is_empty() {
+                self.job.warning_msg = format!(
+                    "{} devices did not complete the upgrade image verification",
+                    fuota_devices.len()
+                );
+            }
+
+            return Ok(Some((FuotaJob::Reboot, Utc::now())));
+        }
+
+        info!("Enqueue DevUpgradeImageReq");
+        self.job.attempt_count += 1;
+
+        if fuota_devices_received_count == 0 {
+            self.job.error_msg = "There are no devices available to complete this step".into();
+            return Ok(Some((FuotaJob::Complete, Utc::now())));
+        }
+
+        for fuota_dev in &fuota_devices {
+            let pl = match self.device_profile.app_layer_params.ts006_version {
+                Some(Ts006Version::V100) => {
+                    firmwaremanagement::v1::Payload::DevUpgradeImageReq.to_vec()?
+                }
+                None => return Err(anyhow!("Device-profile does not support TS006")),
+            };
+
+            device_queue::enqueue_item(device_queue::DeviceQueueItem {
+                dev_eui: fuota_dev.dev_eui,
+                f_port: self.device_profile.app_layer_params.ts006_f_port.into(),
+                data: pl,
+                ..Default::default()
+            })
+            .await?;
+        }
+
+        if !fuota_devices.is_empty() {
+            // There are devices pending verification, we need to re-run this job.
+            let scheduler_run_after =
+                Utc::now() + TimeDelta::seconds(self.device_profile.uplink_interval as i64);
+            Ok(Some((FuotaJob::UpgradeImage, scheduler_run_after)))
+        } else {
+            Ok(Some((FuotaJob::Reboot, Utc::now())))
+        }
+    }
+
+    async fn reboot(&mut self) -> Result<Option<(FuotaJob, DateTime<Utc>)>> {
+        let fuota_devices = fuota::get_devices(self.job.fuota_deployment_id.into(), -1, 0).await?;
+        let fuota_devices_completed_upgrade_image_count = fuota_devices
+            .iter()
+            .filter(|d| d.upgrade_image_completed_at.is_some())
+            .count();
+
+        // Filter on devices that have a verified upgrade image but not yet responded
+        // to the DevRebootCountdownReq.
+        let fuota_devices: Vec<fuota::FuotaDeploymentDevice> = fuota_devices
+            .into_iter()
+            .filter(|d| {
+                d.error_msg.is_empty()
+                    && d.upgrade_image_completed_at.is_some()
+                    && d.reboot_completed_at.is_none()
+            })
+            .collect();
+
+        // Proceed with next step after reaching the max attempts.
+        if self.job.attempt_count > self.job.max_retry_count {
+            info!("Set timeout error to devices that did not respond to DevRebootCountdownReq");
+            fuota::set_device_timeout_error(
+                self.fuota_deployment.id.into(),
+                fuota::DeviceSteps {
+                    reboot: true,
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+            if !fuota_devices.is_empty() {
+                self.job.warning_msg = format!(
+                    "{} devices did not complete the reboot",
+                    fuota_devices.len()
+                );
+            }
+
+            return Ok(Some((FuotaJob::Complete, Utc::now())));
+        }
+
+        info!("Enqueue DevRebootCountdownReq");
+        self.job.attempt_count += 1;
+
+        if fuota_devices_completed_upgrade_image_count == 0 {
+            self.job.error_msg = "There are no devices available to complete this step".into();
+            return Ok(Some((FuotaJob::Complete, Utc::now())));
+        }
+
+        for fuota_dev in &fuota_devices {
+            let pl = match self.device_profile.app_layer_params.ts006_version {
+                Some(Ts006Version::V100) => firmwaremanagement::v1::Payload::DevRebootCountdownReq(
+                    firmwaremanagement::v1::DevRebootCountdownReqPayload {
+                        countdown: REBOOT_COUNTDOWN,
+                    },
+                )
+                .to_vec()?,
+                None => return Err(anyhow!("Device-profile does not support TS006")),
+            };
+
+            device_queue::enqueue_item(device_queue::DeviceQueueItem {
+                dev_eui: fuota_dev.dev_eui,
+                f_port: self.device_profile.app_layer_params.ts006_f_port.into(),
+                data: pl,
+                ..Default::default()
+            })
+            .await?;
+        }
+
+        if !fuota_devices.is_empty() {
+            // There are devices pending reboot, we need to re-run this job.
+            let scheduler_run_after =
+                Utc::now() + TimeDelta::seconds(self.device_profile.uplink_interval as i64);
+            Ok(Some((FuotaJob::Reboot, scheduler_run_after)))
+        } else {
+            // Complete after the devices have rebooted, such that the DevVersionReq
+            // enqueued on completion is answered by the new firmware.
+            Ok(Some((
+                FuotaJob::Complete,
+                Utc::now() + TimeDelta::seconds(REBOOT_COUNTDOWN.into()),
+            )))
+        }
     }
 
     async fn complete(&mut self) -> Result<Option<(FuotaJob, DateTime<Utc>)>> {
@@ -905,14 +1088,36 @@ impl Flow {
         info!("Completing FUOTA deployment");
         self.job.attempt_count += 1;
 
+        let ts006 = self.device_profile.app_layer_params.ts006_version.is_some();
+
         if self.fuota_deployment.request_fragmentation_session_status
             == RequestFragmentationSessionStatus::NoRequest
         {
-            fuota::set_device_completed(self.fuota_deployment.id.into(), true, true, true, false)
-                .await?;
+            fuota::set_device_completed(
+                self.fuota_deployment.id.into(),
+                fuota::DeviceSteps {
+                    mc_group_setup: true,
+                    mc_session: true,
+                    frag_session_setup: true,
+                    frag_status: false,
+                    upgrade_image: ts006,
+                    reboot: ts006,
+                },
+            )
+            .await?;
         } else {
-            fuota::set_device_completed(self.fuota_deployment.id.into(), true, true, true, true)
-                .await?;
+            fuota::set_device_completed(
+                self.fuota_deployment.id.into(),
+                fuota::DeviceSteps {
+                    mc_group_setup: true,
+                    mc_session: true,
+                    frag_session_setup: true,
+                    frag_status: true,
+                    upgrade_image: ts006,
+                    reboot: ts006,
+                },
+            )
+            .await?;
         }
 
         let fuota_devices = fuota::get_devices(self.job.fuota_deployment_id.into(), -1, 0).await?;
@@ -929,6 +1134,17 @@ impl Flow {
                 d.tags.deref_mut().insert(k.to_string(), v.to_string());
             }
             let _ = device::update(d).await?;
+
+            // Request the version of the (new) firmware, which will be stored on the device.
+            if let Some(Ts006Version::V100) = self.device_profile.app_layer_params.ts006_version {
+                device_queue::enqueue_item(device_queue::DeviceQueueItem {
+                    dev_eui: fuota_device.dev_eui,
+                    f_port: self.device_profile.app_layer_params.ts006_f_port.into(),
+                    data: firmwaremanagement::v1::Payload::DevVersionReq.to_vec()?,
+                    ..Default::default()
+                })
+                .await?;
+            }
         }
 
         if fuota_devices_count != fuota_devices_completed_count {
@@ -1,13 +1,168 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
 use anyhow::Result;
+use async_trait::async_trait;
 use tracing::{Instrument, Level, span, warn};
 
-use crate::storage::{device, device_profile};
+use crate::storage::{device, device_profile, fields};
 use chirpstack_api::gw;
 
+pub mod certification;
 pub mod clocksync;
+pub mod firmwaremanagement;
 pub mod fragmentation;
 pub mod fuota;
 pub mod multicastsetup;
+pub mod multipackage;
+
+static PACKAGES: LazyLock<Registry> = LazyLock::new(Registry::default);
+
+// Package implements an application-layer package. The built-in packages are
+// configured through their dedicated device-profile settings, other packages
+// (e.g. vendor-specific packages) through the device-profile packages settings,
+// using the package name as key.
+#[async_trait]
+pub trait Package {
+    // Returns the package name.
+    fn name(&self) -> &'static str;
+
+    // Returns the package versions that are supported.
+    fn versions(&self) -> &'static [&'static str];
+
+    // Returns the package settings that must be set in the device-profile.
+    fn required_settings(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    // Returns the fPort in case the package is enabled for the given params.
+    fn f_port(&self, params: &fields::AppLayerParams) -> Option<u8> {
+        params.packages.get(self.name()).map(|p| p.f_port)
+    }
+
+    // Handle the uplink payload received on the package fPort.
+    async fn handle_uplink(
+        &self,
+        dev: &device::Device,
+        dp: &device_profile::DeviceProfile,
+        rx_info: &[gw::UplinkRxInfo],
+        data: &[u8],
+    ) -> Result<()>;
+}
+
+// Registry contains the application-layer packages. Packages (e.g. vendor-specific
+// packages) are added by registering them in the default registry.
+pub struct Registry {
+    packages: Vec<Box<dyn Package + Sync + Send>>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Registry { packages: vec![] };
+        let packages: [Box<dyn Package + Sync + Send>; 6] = [
+            Box::new(clocksync::Package),
+            Box::new(fragmentation::Package),
+            Box::new(multicastsetup::Package),
+            Box::new(firmwaremanagement::Package),
+            Box::new(multipackage::Package),
+            Box::new(certification::Package),
+        ];
+        for p in packages {
+            registry
+                .register(p)
+                .expect("Register application-layer package");
+        }
+        registry
+    }
+}
+
+impl Registry {
+    // Register adds the given package to the registry. A package must be registered
+    // before it can be enabled in the device-profile.
+    pub fn register(&mut self, p: Box<dyn Package + Sync + Send>) -> Result<()> {
+        if self.packages.iter().any(|v| v.name() == p.name()) {
+            return Err(anyhow!("Package {} is already registered", p.name()));
+        }
+        self.packages.push(p);
+        Ok(())
+    }
+
+    pub fn validate_app_layer_params(&self, params: &fields::AppLayerParams) -> Result<()> {
+        for (name, pkg_params) in &params.packages {
+            let p = self
+                .packages
+                .iter()
+                .find(|p| p.name() == name)
+                .ok_or_else(|| anyhow!("Package {} is not registered", name))?;
+
+            if p.f_port(params) != Some(pkg_params.f_port) {
+                return Err(anyhow!(
+                    "Package {} must be configured using its device-profile settings",
+                    name
+                ));
+            }
+
+            if !p.versions().contains(&pkg_params.version.as_str()) {
+                return Err(anyhow!(
+                    "Package {} does not support version {}, supported versions: {}",
+                    name,
+                    pkg_params.version,
+                    p.versions().join(", ")
+                ));
+            }
+
+            for setting in p.required_settings() {
+                if !pkg_params.settings.contains_key(*setting) {
+                    return Err(anyhow!("Package {} requires setting {}", name, setting));
+                }
+            }
+        }
+
+        let mut f_ports: HashMap<u8, &'static str> = HashMap::new();
+        for p in self.packages.iter() {
+            if let Some(f_port) = p.f_port(params) {
+                if f_port == 0 {
+                    return Err(anyhow!("Package {} has invalid fPort {}", p.name(), f_port));
+                }
+
+                if let Some(other) = f_ports.insert(f_port, p.name()) {
+                    return Err(anyhow!(
+                        "Packages {} and {} use the same fPort {}",
+                        other,
+                        p.name(),
+                        f_port
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn handle_uplink(
+        &self,
+        dev: &device::Device,
+        dp: &device_profile::DeviceProfile,
+        rx_info: &[gw::UplinkRxInfo],
+        f_port: u8,
+        data: &[u8],
+    ) -> Result<()> {
+        let p = self
+            .packages
+            .iter()
+            .find(|p| p.f_port(&dp.app_layer_params) == Some(f_port))
+            .ok_or_else(|| anyhow!("Unexpected f_port {}", f_port))?;
+
+        let span = span!(Level::INFO, "applayer", package = p.name());
+        p.handle_uplink(dev, dp, rx_info, data)
+            .instrument(span)
+            .await
+    }
+}
+
+pub fn validate_app_layer_params(params: &fields::AppLayerParams) -> Result<()> {
+    PACKAGES.validate_app_layer_params(params)
+}
 
 pub async fn handle_uplink(
     dev: &device::Device,
@@ -28,22 +183,131 @@ async fn _handle_uplink(
     f_port: u8,
     data: &[u8],
 ) -> Result<()> {
-    if dp.app_layer_params.ts003_f_port == f_port {
-        let span = span!(Level::INFO, "ts003");
-        clocksync::handle_uplink(dev, dp, rx_info, data)
-            .instrument(span)
-            .await
-    } else if dp.app_layer_params.ts004_f_port == f_port {
-        let span = span!(Level::INFO, "ts004");
-        fragmentation::handle_uplink(dev, dp, data)
-            .instrument(span)
-            .await
-    } else if dp.app_layer_params.ts005_f_port == f_port {
-        let span = span!(Level::INFO, "ts005");
-        multicastsetup::handle_uplink(dev, dp, data)
-            .instrument(span)
+    PACKAGES.handle_uplink(dev, dp, rx_info, f_port, data).await
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+
+    static VENDOR_HANDLED: AtomicBool = AtomicBool::new(false);
+
+    struct VendorPackage;
+
+    #[async_trait]
+    impl Package for VendorPackage {
+        fn name(&self) -> &'static str {
+            "vendor-test"
+        }
+
+        fn versions(&self) -> &'static [&'static str] {
+            &["v1"]
+        }
+
+        fn required_settings(&self) -> &'static [&'static str] {
+            &["key"]
+        }
+
+        async fn handle_uplink(
+            &self,
+            _dev: &device::Device,
+            _dp: &device_profile::DeviceProfile,
+            _rx_info: &[gw::UplinkRxInfo],
+            data: &[u8],
+        ) -> Result<()> {
+            assert_eq!(vec![0x01, 0x02], data);
+            VENDOR_HANDLED.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn vendor_params(version: &str, f_port: u8, key: bool) -> fields::AppLayerParams {
+        fields::AppLayerParams {
+            packages: [(
+                "vendor-test".to_string(),
+                fields::AppLayerPackageParams {
+                    version: version.to_string(),
+                    f_port,
+                    settings: if key {
+                        [("key".to_string(), "value".to_string())].into()
+                    } else {
+                        HashMap::new()
+                    },
+                },
+            )]
+            .into(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registry() {
+        let mut registry = Registry::default();
+        registry.register(Box::new(VendorPackage)).unwrap();
+        assert!(registry.register(Box::new(VendorPackage)).is_err());
+
+        // Validation.
+        registry
+            .validate_app_layer_params(&fields::AppLayerParams::default())
+            .unwrap();
+        registry
+            .validate_app_layer_params(&vendor_params("v1", 150, true))
+            .unwrap();
+        assert_eq!(
+            "Package vendor-test does not support version v2, supported versions: v1",
+            registry
+                .validate_app_layer_params(&vendor_params("v2", 150, true))
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "Package vendor-test requires setting key",
+            registry
+                .validate_app_layer_params(&vendor_params("v1", 150, false))
+                .unwrap_err()
+                .to_string()
+        );
+
+        let mut params = vendor_params("v1", 202, true);
+        params.ts003_version = Some(fields::device_profile::Ts003Version::V100);
+        assert_eq!(
+            "Packages ts003 and vendor-test use the same fPort 202",
+            registry
+                .validate_app_layer_params(&params)
+                .unwrap_err()
+                .to_string()
+        );
+
+        let mut params = fields::AppLayerParams::default();
+        params.packages.insert(
+            "ts003".to_string(),
+            fields::AppLayerPackageParams {
+                version: "v1.0.0".into(),
+                f_port: 202,
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            "Package ts003 must be configured using its device-profile settings",
+            registry
+                .validate_app_layer_params(&params)
+                .unwrap_err()
+                .to_string()
+        );
+
+        // Uplink routing.
+        let dp = device_profile::DeviceProfile {
+            app_layer_params: vendor_params("v1", 150, true),
+            ..Default::default()
+        };
+        assert!(dp.app_layer_params.is_app_layer_f_port(150));
+
+        registry
+            .handle_uplink(&device::Device::default(), &dp, &[], 150, &[0x01, 0x02])
             .await
-    } else {
-        Err(anyhow!("Unexpected f_port {}", f_port))
+            .unwrap();
+        assert!(VENDOR_HANDLED.load(Ordering::SeqCst));
     }
 }
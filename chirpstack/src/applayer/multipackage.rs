@@ -0,0 +1,230 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::{Instrument, Level, span, warn};
+
+use super::{clocksync, firmwaremanagement, fragmentation, multicastsetup};
+use crate::storage::fields::device_profile::Ts007Version;
+use crate::storage::{device, device_profile, device_queue, fields};
+use chirpstack_api::gw;
+use lrwn::applayer::multipackage;
+
+pub struct Package;
+
+#[async_trait]
+impl super::Package for Package {
+    fn name(&self) -> &'static str {
+        "ts007"
+    }
+
+    fn versions(&self) -> &'static [&'static str] {
+        &["v1.0.0"]
+    }
+
+    fn f_port(&self, params: &fields::AppLayerParams) -> Option<u8> {
+        params.ts007_version.map(|_| params.ts007_f_port)
+    }
+
+    async fn handle_uplink(
+        &self,
+        dev: &device::Device,
+        dp: &device_profile::DeviceProfile,
+        rx_info: &[gw::UplinkRxInfo],
+        data: &[u8],
+    ) -> Result<()> {
+        handle_uplink(dev, dp, rx_info, data).await
+    }
+}
+
+pub async fn handle_uplink(
+    dev: &device::Device,
+    dp: &device_profile::DeviceProfile,
+    rx_info: &[gw::UplinkRxInfo],
+    data: &[u8],
+) -> Result<()> {
+    let version = dp
+        .app_layer_params
+        .ts007_version
+        .ok_or_else(|| anyhow!("Device does not support TS007"))?;
+
+    match version {
+        Ts007Version::V100 => handle_uplink_v100(dev, dp, rx_info, data).await,
+    }
+}
+
+async fn handle_uplink_v100(
+    dev: &device::Device,
+    dp: &device_profile::DeviceProfile,
+    rx_info: &[gw::UplinkRxInfo],
+    data: &[u8],
+) -> Result<()> {
+    let pl = multipackage::v1::Payload::from_slice(data)?;
+    let mut ans = multipackage::v1::Payload::default();
+
+    // Each package message is handled by the package handler, as if it was received
+    // on the fPort of the package. The answers are encapsulated in a single
+    // Multi-Package answer, which is sent on the TS007 fPort.
+    for msg in &pl.messages {
+        match handle_package_message(dev, dp, rx_info, msg).await {
+            Ok(Some(data)) => ans.messages.push(multipackage::v1::PackageMessage {
+                package_identifier: msg.package_identifier,
+                data,
+            }),
+            Ok(None) => {}
+            Err(e) => {
+                warn!(package_identifier = msg.package_identifier, error = %e, "Handle package message error");
+            }
+        }
+    }
+
+    if !ans.messages.is_empty() {
+        device_queue::enqueue_item(device_queue::DeviceQueueItem {
+            dev_eui: dev.dev_eui,
+            f_port: dp.app_layer_params.ts007_f_port.into(),
+            data: ans.to_vec()?,
+            ..Default::default()
+        })
+        .await?;
+    }
+
+    Ok(())
+}
+
+// Handles the package message and returns the answer (if any).
+async fn handle_package_message(
+    dev: &device::Device,
+    dp: &device_profile::DeviceProfile,
+    rx_info: &[gw::UplinkRxInfo],
+    msg: &multipackage::v1::PackageMessage,
+) -> Result<Option<Vec<u8>>> {
+    match msg.package_identifier {
+        multipackage::v1::PACKAGE_IDENTIFIER_CLOCK_SYNC => {
+            let _span = span!(Level::INFO, "ts003").entered();
+            clocksync::handle_request(dp, rx_info, &msg.data)
+        }
+        multipackage::v1::PACKAGE_IDENTIFIER_MULTICAST_SETUP => {
+            let span = span!(Level::INFO, "ts005");
+            multicastsetup::handle_uplink(dev, dp, &msg.data)
+                .instrument(span)
+                .await
+                .map(|_| None)
+        }
+        multipackage::v1::PACKAGE_IDENTIFIER_FRAGMENTATION => {
+            let span = span!(Level::INFO, "ts004");
+            fragmentation::handle_uplink(dev, dp, &msg.data)
+                .instrument(span)
+                .await
+                .map(|_| None)
+        }
+        multipackage::v1::PACKAGE_IDENTIFIER_FIRMWARE_MANAGEMENT => {
+            let span = span!(Level::INFO, "ts006");
+            firmwaremanagement::handle_uplink(dev, dp, &msg.data)
+                .instrument(span)
+                .await
+                .map(|_| None)
+        }
+        _ => Err(anyhow!(
+            "Unexpected package identifier {}",
+            msg.package_identifier
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::applayer;
+    use crate::storage::fields::device_profile::Ts003Version;
+    use crate::storage::{application, tenant};
+    use crate::test;
+    use lrwn::EUI64;
+    use lrwn::applayer::clocksync;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_handle_v1_clock_sync() {
+        let _guard = test::prepare().await;
+        let t = tenant::create(tenant::Tenant {
+            name: "test-tenant".into(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let app = application::create(application::Application {
+            name: "test-app".into(),
+            tenant_id: t.id,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let dp = device_profile::create(device_profile::DeviceProfile {
+            name: "test-dp".into(),
+            tenant_id: Some(t.id),
+            app_layer_params: fields::AppLayerParams {
+                ts003_version: Some(Ts003Version::V100),
+                ts007_version: Some(Ts007Version::V100),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let d = device::create(device::Device {
+            name: "test-dev".into(),
+            dev_eui: EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8]),
+            application_id: app.id,
+            device_profile_id: dp.id,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let pl = multipackage::v1::Payload {
+            messages: vec![multipackage::v1::PackageMessage {
+                package_identifier: multipackage::v1::PACKAGE_IDENTIFIER_CLOCK_SYNC,
+                data: clocksync::v1::Payload::AppTimeReq(clocksync::v1::AppTimeReqPayload {
+                    device_time: 1234,
+                    param: clocksync::v1::AppTimeReqPayloadParam {
+                        token_req: 8,
+                        ans_required: true,
+                    },
+                })
+                .to_vec()
+                .unwrap(),
+            }],
+        };
+
+        applayer::handle_uplink(
+            &d,
+            &dp,
+            &[gw::UplinkRxInfo {
+                time_since_gps_epoch: Some(Duration::from_secs(1234).into()),
+                ..Default::default()
+            }],
+            dp.app_layer_params.ts007_f_port,
+            &pl.to_vec().unwrap(),
+        )
+        .await;
+
+        let queue_items = device_queue::get_for_dev_eui(&d.dev_eui).await.unwrap();
+        assert_eq!(1, queue_items.len());
+        let qi = queue_items.first().unwrap();
+        assert_eq!(dp.app_layer_params.ts007_f_port as i16, qi.f_port);
+
+        let ans = multipackage::v1::Payload::from_slice(&qi.data).unwrap();
+        assert_eq!(1, ans.messages.len());
+        assert_eq!(
+            multipackage::v1::PACKAGE_IDENTIFIER_CLOCK_SYNC,
+            ans.messages[0].package_identifier
+        );
+        assert_eq!(
+            clocksync::v1::Payload::AppTimeAns(clocksync::v1::AppTimeAnsPayload {
+                time_correction: 0,
+                param: clocksync::v1::AppTimeAnsPayloadParam { token_ans: 8 },
+            }),
+            clocksync::v1::Payload::from_slice(false, &ans.messages[0].data).unwrap()
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use std::net::SocketAddr;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use hickory_resolver::TokioResolver;
+use hickory_resolver::config::{ConnectionConfig, NameServerConfig, ResolverConfig};
+use hickory_resolver::lookup::Lookup;
+use hickory_resolver::net::runtime::TokioRuntimeProvider;
+use hickory_resolver::proto::rr::{RData, RecordType};
+
+use super::{NaptrRecord, Resolver, SrvRecord};
+
+pub struct HickoryResolver {
+    resolver: TokioResolver,
+}
+
+impl HickoryResolver {
+    // In case no nameservers are given, the system configuration is used.
+    pub fn new(nameservers: &[String]) -> Result<Self> {
+        let resolver = if nameservers.is_empty() {
+            TokioResolver::builder_tokio().context("Read system DNS configuration")?
+        } else {
+            let mut name_servers = Vec::new();
+            for ns in nameservers {
+                let addr: SocketAddr = ns
+                    .parse()
+                    .with_context(|| format!("Parse nameserver: {}", ns))?;
+
+                let mut udp = ConnectionConfig::udp();
+                udp.port = addr.port();
+                let mut tcp = ConnectionConfig::tcp();
+                tcp.port = addr.port();
+
+                name_servers.push(NameServerConfig::new(addr.ip(), true, vec![udp, tcp]));
+            }
+
+            TokioResolver::builder_with_config(
+                ResolverConfig::from_parts(None, vec![], name_servers),
+                TokioRuntimeProvider::default(),
+            )
+        }
+        .build()
+        .context("Build DNS resolver")?;
+
+        Ok(HickoryResolver { resolver })
+    }
+
+    async fn lookup(&self, name: &str, record_type: RecordType) -> Result<Option<Lookup>> {
+        match self.resolver.lookup(name, record_type).await {
+            Ok(v) => Ok(Some(v)),
+            Err(e) if e.is_no_records_found() => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Lookup {} {}", record_type, name)),
+        }
+    }
+}
+
+#[async_trait]
+impl Resolver for HickoryResolver {
+    async fn lookup_naptr(&self, name: &str) -> Result<Vec<NaptrRecord>> {
+        let lookup = match self.lookup(name, RecordType::NAPTR).await? {
+            Some(v) => v,
+            None => return Ok(Vec::new()),
+        };
+
+        Ok(lookup
+            .answers()
+            .iter()
+            .filter_map(|r| match &r.data {
+                RData::NAPTR(v) => Some(NaptrRecord {
+                    order: v.order,
+                    preference: v.preference,
+                    flags: String::from_utf8_lossy(&v.flags).into(),
+                    services: String::from_utf8_lossy(&v.services).into(),
+                    regexp: String::from_utf8_lossy(&v.regexp).into(),
+                    replacement: v.replacement.to_utf8(),
+                    ttl: r.ttl,
+                }),
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn lookup_srv(&self, name: &str) -> Result<Vec<SrvRecord>> {
+        let lookup = match self.lookup(name, RecordType::SRV).await? {
+            Some(v) => v,
+            None => return Ok(Vec::new()),
+        };
+
+        Ok(lookup
+            .answers()
+            .iter()
+            .filter_map(|r| match &r.data {
+                RData::SRV(v) => Some(SrvRecord {
+                    priority: v.priority,
+                    weight: v.weight,
+                    port: v.port,
+                    target: v.target.to_utf8(),
+                    ttl: r.ttl,
+                }),
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use hickory_resolver::proto::op::{Message, OpCode};
+    use hickory_resolver::proto::rr::rdata::{NAPTR, SRV};
+    use hickory_resolver::proto::rr::{Name, Record};
+    use tokio::net::UdpSocket;
+
+    use super::*;
+
+    // Starts a local DNS responder, which answers the queries using the given
+    // records. It returns the address of the responder.
+    async fn start_responder(records: Vec<Record>) -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let mut buf = [0; 4096];
+            loop {
+                let (size, peer) = socket.recv_from(&mut buf).await.unwrap();
+                let req = Message::from_vec(&buf[..size]).unwrap();
+
+                let mut resp = Message::response(req.metadata.id, OpCode::Query);
+                resp.metadata.recursion_desired = req.metadata.recursion_desired;
+                resp.metadata.recursion_available = true;
+                for q in &req.queries {
+                    resp.add_query(q.clone());
+                    resp.add_answers(
+                        records
+                            .iter()
+                            .filter(|r| r.name == q.name && r.record_type() == q.query_type)
+                            .cloned(),
+                    );
+                }
+
+                socket.send_to(&resp.to_vec().unwrap(), peer).await.unwrap();
+            }
+        });
+
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_hickory_resolver() {
+        let domain = "8.0.7.0.6.0.5.0.4.0.3.0.2.0.1.0.joineuis.example.com";
+        let addr = start_responder(vec![
+            Record::from_rdata(
+                Name::from_ascii(format!("{}.", domain)).unwrap(),
+                300,
+                RData::NAPTR(NAPTR::new(
+                    10,
+                    20,
+                    b"S".to_vec().into(),
+                    b"x-lorawan+https".to_vec().into(),
+                    b"".to_vec().into(),
+                    Name::from_ascii("_lorawan._tcp.example.com.").unwrap(),
+                )),
+            ),
+            Record::from_rdata(
+                Name::from_ascii("_lorawan._tcp.example.com.").unwrap(),
+                60,
+                RData::SRV(SRV::new(
+                    10,
+                    0,
+                    8443,
+                    Name::from_ascii("js.example.com.").unwrap(),
+                )),
+            ),
+        ])
+        .await;
+
+        let r = HickoryResolver::new(&[addr.to_string()]).unwrap();
+
+        assert_eq!(
+            vec![NaptrRecord {
+                order: 10,
+                preference: 20,
+                flags: "S".into(),
+                services: "x-lorawan+https".into(),
+                regexp: "".into(),
+                replacement: "_lorawan._tcp.example.com.".into(),
+                ttl: 300,
+            }],
+            r.lookup_naptr(domain).await.unwrap()
+        );
+        assert_eq!(
+            vec![SrvRecord {
+                priority: 10,
+                weight: 0,
+                port: 8443,
+                target: "js.example.com.".into(),
+                ttl: 60,
+            }],
+            r.lookup_srv("_lorawan._tcp.example.com.").await.unwrap()
+        );
+
+        // Name without records.
+        assert!(
+            r.lookup_naptr("0.joineuis.example.com")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // NAPTR + SRV resolving.
+        assert_eq!(
+            Some(("https://js.example.com:8443".to_string(), 60)),
+            super::super::resolve_naptr(&r, domain).await.unwrap()
+        );
+    }
+}
@@ -0,0 +1,475 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use regex::RegexBuilder;
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+use crate::config;
+use crate::helpers::errors::PrintFullError;
+use lrwn::{EUI64, NetID};
+
+mod hickory;
+#[cfg(test)]
+pub mod stub;
+
+static RESOLVER: LazyLock<RwLock<Option<Arc<dyn Resolver + Sync + Send>>>> =
+    LazyLock::new(|| RwLock::new(None));
+static CACHE: LazyLock<RwLock<HashMap<String, CacheItem>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NaptrRecord {
+    pub order: u16,
+    pub preference: u16,
+    pub flags: String,
+    pub services: String,
+    pub regexp: String,
+    pub replacement: String,
+    pub ttl: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrvRecord {
+    pub priority: u16,
+    pub weight: u16,
+    pub port: u16,
+    pub target: String,
+    pub ttl: u32,
+}
+
+#[async_trait]
+pub trait Resolver {
+    // Returns the NAPTR records for the given name. An empty Vec must be returned
+    // in case the name does not exist or does not have NAPTR records.
+    async fn lookup_naptr(&self, name: &str) -> Result<Vec<NaptrRecord>>;
+
+    // Returns the SRV records for the given name. An empty Vec must be returned
+    // in case the name does not exist or does not have SRV records.
+    async fn lookup_srv(&self, name: &str) -> Result<Vec<SrvRecord>>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolved {
+    pub server: String,
+    // In case set, the server must be resolved again after this time.
+    pub expires_at: Option<Instant>,
+}
+
+struct CacheItem {
+    server: Option<String>,
+    expires_at: Instant,
+}
+
+pub async fn setup() -> Result<()> {
+    let conf = config::get();
+    if !conf.join_server.resolve_join_eui_dns && !conf.roaming.resolve_net_id_dns {
+        return Ok(());
+    }
+
+    info!(nameservers = ?conf.dns.nameservers, "Setting up DNS resolver");
+    let r =
+        hickory::HickoryResolver::new(&conf.dns.nameservers).context("Setup DNS resolver error")?;
+    set_resolver(Arc::new(r)).await;
+
+    Ok(())
+}
+
+pub async fn set_resolver(r: Arc<dyn Resolver + Sync + Send>) {
+    *RESOLVER.write().await = Some(r);
+    CACHE.write().await.clear();
+}
+
+pub fn join_eui_domain(join_eui: EUI64) -> String {
+    let conf = config::get();
+
+    let join_eui: String = join_eui
+        .to_string()
+        .chars()
+        .rev()
+        .map(|v| v.to_string())
+        .collect::<Vec<String>>()
+        .join(".");
+
+    format!(
+        "{}{}",
+        join_eui, conf.join_server.resolve_join_eui_domain_suffix
+    )
+}
+
+pub fn net_id_domain(net_id: NetID) -> String {
+    let conf = config::get();
+    format!("{}{}", net_id, conf.roaming.resolve_net_id_domain_suffix)
+}
+
+// Returns the Join Server for the given JoinEUI. In case DNS resolving is disabled or
+// in case no usable records were found, this falls back to https://[JoinEUI domain].
+pub async fn resolve_join_eui(join_eui: EUI64) -> Resolved {
+    let conf = config::get();
+    let domain = join_eui_domain(join_eui);
+
+    if !conf.join_server.resolve_join_eui_dns {
+        return Resolved {
+            server: format!("https://{}", domain),
+            expires_at: None,
+        };
+    }
+
+    resolve(&domain).await
+}
+
+// Returns the roaming server for the given NetID. In case DNS resolving is disabled or
+// in case no usable records were found, this falls back to https://[NetID domain].
+pub async fn resolve_net_id(net_id: NetID) -> Resolved {
+    let conf = config::get();
+    let domain = net_id_domain(net_id);
+
+    if !conf.roaming.resolve_net_id_dns {
+        return Resolved {
+            server: format!("https://{}", domain),
+            expires_at: None,
+        };
+    }
+
+    resolve(&domain).await
+}
+
+async fn resolve(domain: &str) -> Resolved {
+    let conf = config::get();
+    let now = Instant::now();
+    let fallback = format!("https://{}", domain);
+
+    if let Some(item) = CACHE.read().await.get(domain)
+        && item.expires_at > now
+    {
+        return Resolved {
+            server: item.server.clone().unwrap_or(fallback),
+            expires_at: Some(item.expires_at),
+        };
+    }
+
+    let resolver = RESOLVER.read().await.clone();
+    let res = match resolver {
+        Some(r) => resolve_naptr(r.as_ref(), domain).await,
+        None => Err(anyhow!("DNS resolver is not configured")),
+    };
+
+    let (server, ttl) = match res {
+        Ok(Some((server, ttl))) => {
+            info!(domain = %domain, server = %server, ttl = ttl, "Server resolved using DNS");
+            (
+                Some(server),
+                Duration::from_secs(ttl.into()).min(conf.dns.max_cache_ttl),
+            )
+        }
+        Ok(None) => {
+            warn!(domain = %domain, fallback = %fallback, "No usable DNS records found, using fallback server");
+            (None, conf.dns.negative_cache_ttl)
+        }
+        Err(e) => {
+            warn!(domain = %domain, fallback = %fallback, error = %e.full(), "Resolving server error, using fallback server");
+            (None, conf.dns.negative_cache_ttl)
+        }
+    };
+
+    let expires_at = now + ttl;
+    CACHE.write().await.insert(
+        domain.to_string(),
+        CacheItem {
+            server: server.clone(),
+            expires_at,
+        },
+    );
+
+    Resolved {
+        server: server.unwrap_or(fallback),
+        expires_at: Some(expires_at),
+    }
+}
+
+// Resolves the server using the NAPTR records of the given domain. Records are
+// processed by order and preference. The first usable record wins:
+//  * flag "U": the URI is the result of the regexp applied to the domain
+//  * flag "S": the server is resolved using the SRV records of the replacement
+//  * flag "A": the server is the replacement
+//
+// It returns the server and the TTL (the lowest TTL of the involved records).
+async fn resolve_naptr(
+    resolver: &(dyn Resolver + Sync + Send),
+    domain: &str,
+) -> Result<Option<(String, u32)>> {
+    let mut records = resolver.lookup_naptr(domain).await?;
+    records.sort_by_key(|r| (r.order, r.preference));
+
+    for r in &records {
+        let scheme = get_scheme(&r.services);
+
+        match r.flags.to_lowercase().as_str() {
+            "u" => match apply_regexp(&r.regexp, domain) {
+                Ok(v) => return Ok(Some((v, r.ttl))),
+                Err(e) => {
+                    warn!(domain = %domain, regexp = %r.regexp, error = %e.full(), "Applying NAPTR regexp error");
+                }
+            },
+            "s" => {
+                let mut srv_records = resolver.lookup_srv(&r.replacement).await?;
+                srv_records.sort_by_key(|v| (v.priority, Reverse(v.weight)));
+
+                // A target of "." means that the service is not available.
+                if let Some(srv) = srv_records.iter().find(|v| !trim_dot(&v.target).is_empty()) {
+                    return Ok(Some((
+                        format!("{}://{}:{}", scheme, trim_dot(&srv.target), srv.port),
+                        r.ttl.min(srv.ttl),
+                    )));
+                }
+            }
+            "a" => {
+                let host = trim_dot(&r.replacement);
+                if !host.is_empty() {
+                    return Ok(Some((format!("{}://{}", scheme, host), r.ttl)));
+                }
+            }
+            _ => {
+                debug!(domain = %domain, flags = %r.flags, "Skipping NAPTR record with unsupported flags");
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+// The service field indicates the protocol, e.g. "x-lorawan+https" or
+// "x-lorawan+http". HTTPS is assumed unless HTTP is explicitly requested.
+fn get_scheme(services: &str) -> &'static str {
+    let services = services.to_lowercase();
+    if services == "http" || services.ends_with("+http") {
+        "http"
+    } else {
+        "https"
+    }
+}
+
+fn trim_dot(s: &str) -> &str {
+    s.trim_end_matches('.')
+}
+
+// Applies the NAPTR regexp (RFC 3402), e.g. "!^.*$!https://js.example.com!", to
+// the input string.
+fn apply_regexp(regexp: &str, input: &str) -> Result<String> {
+    let delim = regexp
+        .chars()
+        .next()
+        .ok_or_else(|| anyhow!("Regexp is empty"))?;
+    let parts: Vec<&str> = regexp[delim.len_utf8()..].split(delim).collect();
+    if parts.len() != 3 {
+        return Err(anyhow!("Invalid regexp: {}", regexp));
+    }
+
+    let re = RegexBuilder::new(parts[0])
+        .case_insensitive(parts[2].contains('i'))
+        .build()?;
+    if !re.is_match(input) {
+        return Err(anyhow!("Regexp does not match input: {}", input));
+    }
+
+    // Translate the \1 - \9 back-references into the format used by the regex crate.
+    let mut replacement = String::new();
+    let mut chars = parts[1].chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(d) if d.is_ascii_digit() => replacement.push_str(&format!("${{{}}}", d)),
+                Some(v) => replacement.push(v),
+                None => {}
+            },
+            '$' => replacement.push_str("$$"),
+            _ => replacement.push(c),
+        }
+    }
+
+    let out = re.replace(input, replacement.as_str()).to_string();
+    if out.is_empty() {
+        return Err(anyhow!("Regexp resulted in empty string"));
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+pub async fn reset() {
+    *RESOLVER.write().await = None;
+    CACHE.write().await.clear();
+}
+
+#[cfg(test)]
+pub mod test {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::test;
+
+    fn naptr(order: u16, flags: &str, regexp: &str, replacement: &str) -> NaptrRecord {
+        NaptrRecord {
+            order,
+            preference: 10,
+            flags: flags.into(),
+            services: "x-lorawan+https".into(),
+            regexp: regexp.into(),
+            replacement: replacement.into(),
+            ttl: 300,
+        }
+    }
+
+    #[test]
+    fn test_apply_regexp() {
+        assert_eq!(
+            "https://js.example.com",
+            apply_regexp("!^.*$!https://js.example.com!", "0.1.joineuis.example.com").unwrap()
+        );
+        assert_eq!(
+            "https://000013.example.com/api",
+            apply_regexp(
+                "!^([0-9a-f]+)\\.netids\\.example\\.com$!https://\\1.example.com/api!i",
+                "000013.NETIDS.example.com"
+            )
+            .unwrap()
+        );
+        assert!(apply_regexp("!^foo$!https://example.com!", "bar").is_err());
+        assert!(apply_regexp("!^.*$!https://example.com", "bar").is_err());
+        assert!(apply_regexp("", "bar").is_err());
+    }
+
+    #[test]
+    fn test_get_scheme() {
+        assert_eq!("https", get_scheme("x-lorawan+https"));
+        assert_eq!("http", get_scheme("X-LoRaWAN+HTTP"));
+        assert_eq!("https", get_scheme(""));
+    }
+
+    #[tokio::test]
+    async fn test_resolve() {
+        let _guard = test::prepare().await;
+
+        let mut conf = (*config::get()).clone();
+        conf.join_server.resolve_join_eui_domain_suffix = ".joineuis.example.com".into();
+        conf.join_server.resolve_join_eui_dns = true;
+        conf.roaming.resolve_net_id_domain_suffix = ".netids.example.com".into();
+        conf.roaming.resolve_net_id_dns = true;
+        config::set(conf);
+
+        let join_eui = EUI64::from_str("0102030405060708").unwrap();
+        let net_id = NetID::from_str("000013").unwrap();
+        assert_eq!(
+            "8.0.7.0.6.0.5.0.4.0.3.0.2.0.1.0.joineuis.example.com",
+            join_eui_domain(join_eui)
+        );
+        assert_eq!("000013.netids.example.com", net_id_domain(net_id));
+
+        stub::reset().await;
+        set_resolver(Arc::new(stub::StubResolver {})).await;
+
+        // NAPTR + SRV, the record with the lowest order is used.
+        stub::set_naptr(
+            "8.0.7.0.6.0.5.0.4.0.3.0.2.0.1.0.joineuis.example.com",
+            vec![
+                naptr(20, "A", "", "backup.example.com."),
+                naptr(10, "S", "", "_lorawan._tcp.example.com."),
+            ],
+        )
+        .await;
+        stub::set_srv(
+            "_lorawan._tcp.example.com.",
+            vec![
+                SrvRecord {
+                    priority: 20,
+                    weight: 0,
+                    port: 8443,
+                    target: "js2.example.com.".into(),
+                    ttl: 60,
+                },
+                SrvRecord {
+                    priority: 10,
+                    weight: 0,
+                    port: 443,
+                    target: "js1.example.com.".into(),
+                    ttl: 60,
+                },
+            ],
+        )
+        .await;
+
+        let resolved = resolve_join_eui(join_eui).await;
+        assert_eq!("https://js1.example.com:443", resolved.server);
+        assert!(resolved.expires_at.is_some());
+        assert_eq!(2, stub::get_lookup_count().await);
+
+        // Resolved from cache.
+        assert_eq!(resolved, resolve_join_eui(join_eui).await);
+        assert_eq!(2, stub::get_lookup_count().await);
+
+        // NAPTR with regexp.
+        stub::set_naptr(
+            "000013.netids.example.com",
+            vec![naptr(10, "U", "!^.*$!https://ns.example.com/roaming!", "")],
+        )
+        .await;
+        assert_eq!(
+            "https://ns.example.com/roaming",
+            resolve_net_id(net_id).await.server
+        );
+
+        // No records, fallback.
+        let net_id = NetID::from_str("000014").unwrap();
+        assert_eq!(
+            "https://000014.netids.example.com",
+            resolve_net_id(net_id).await.server
+        );
+        let lookups = stub::get_lookup_count().await;
+
+        // The negative result is cached.
+        assert_eq!(
+            "https://000014.netids.example.com",
+            resolve_net_id(net_id).await.server
+        );
+        assert_eq!(lookups, stub::get_lookup_count().await);
+
+        // Records with a zero TTL are not cached.
+        stub::set_naptr(
+            "000015.netids.example.com",
+            vec![NaptrRecord {
+                ttl: 0,
+                ..naptr(10, "A", "", "ns.example.com.")
+            }],
+        )
+        .await;
+        let net_id = NetID::from_str("000015").unwrap();
+        assert_eq!(
+            "https://ns.example.com",
+            resolve_net_id(net_id).await.server
+        );
+        assert_eq!(
+            "https://ns.example.com",
+            resolve_net_id(net_id).await.server
+        );
+        assert_eq!(lookups + 2, stub::get_lookup_count().await);
+
+        // DNS resolving disabled.
+        let mut conf = (*config::get()).clone();
+        conf.roaming.resolve_net_id_dns = false;
+        config::set(conf);
+        assert_eq!(
+            Resolved {
+                server: "https://000015.netids.example.com".into(),
+                expires_at: None,
+            },
+            resolve_net_id(net_id).await
+        );
+
+        reset().await;
+    }
+}
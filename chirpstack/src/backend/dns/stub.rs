@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use super::{NaptrRecord, Resolver, SrvRecord};
+
+static NAPTR_RECORDS: LazyLock<RwLock<HashMap<String, Vec<NaptrRecord>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+static SRV_RECORDS: LazyLock<RwLock<HashMap<String, Vec<SrvRecord>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+static LOOKUP_COUNT: LazyLock<RwLock<usize>> = LazyLock::new(|| RwLock::new(0));
+
+pub async fn reset() {
+    NAPTR_RECORDS.write().await.clear();
+    SRV_RECORDS.write().await.clear();
+    *LOOKUP_COUNT.write().await = 0;
+}
+
+pub async fn set_naptr(name: &str, records: Vec<NaptrRecord>) {
+    NAPTR_RECORDS
+        .write()
+        .await
+        .insert(name.to_string(), records);
+}
+
+pub async fn set_srv(name: &str, records: Vec<SrvRecord>) {
+    SRV_RECORDS.write().await.insert(name.to_string(), records);
+}
+
+pub async fn get_lookup_count() -> usize {
+    *LOOKUP_COUNT.read().await
+}
+
+pub struct StubResolver {}
+
+#[async_trait]
+impl Resolver for StubResolver {
+    async fn lookup_naptr(&self, name: &str) -> Result<Vec<NaptrRecord>> {
+        *LOOKUP_COUNT.write().await += 1;
+        Ok(NAPTR_RECORDS
+            .read()
+            .await
+            .get(name)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn lookup_srv(&self, name: &str) -> Result<Vec<SrvRecord>> {
+        *LOOKUP_COUNT.write().await += 1;
+        Ok(SRV_RECORDS
+            .read()
+            .await
+            .get(name)
+            .cloned()
+            .unwrap_or_default())
+    }
+}
@@ -1,49 +1,151 @@
+use std::collections::HashMap;
 use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 
 use anyhow::Result;
 use tokio::sync::RwLock;
-use tracing::{debug, info};
+use tokio::sync::mpsc::Sender;
+use tokio::time::{Instant, sleep};
+use tracing::{debug, error, info};
 
+use super::dns;
+use crate::helpers::errors::PrintFullError;
+use crate::storage::join_server;
 use crate::{config, stream};
 use backend::{Client, ClientConfig};
+use chirpstack_api::stream as stream_pb;
 use lrwn::{EUI64, EUI64Prefix};
 
 type EuiClientList = Vec<(EUI64Prefix, Arc<Client>)>;
 
 static CLIENTS: LazyLock<RwLock<EuiClientList>> = LazyLock::new(|| RwLock::new(vec![]));
+static JOIN_SERVERS: LazyLock<RwLock<Vec<join_server::JoinServer>>> =
+    LazyLock::new(|| RwLock::new(vec![]));
+static DEFAULT_CLIENTS: LazyLock<RwLock<HashMap<EUI64, DefaultClient>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+struct DefaultClient {
+    client: Arc<Client>,
+    // Set in case the server was resolved using DNS.
+    expires_at: Option<Instant>,
+}
+
+impl DefaultClient {
+    fn is_valid(&self) -> bool {
+        match self.expires_at {
+            Some(v) => v > Instant::now(),
+            None => true,
+        }
+    }
+}
 
 pub async fn setup() -> Result<()> {
     info!("Setting up Join Server clients");
+    let join_servers = join_server::list_all().await?;
+    setup_clients(join_servers).await
+}
+
+// Reloads the Join Server clients in case the Join Servers stored in the database
+// have been changed.
+pub async fn reload() -> Result<()> {
+    let join_servers = join_server::list_all().await?;
+    if *JOIN_SERVERS.read().await == join_servers {
+        return Ok(());
+    }
+
+    info!("Join Servers have been changed, reloading Join Server clients");
+    setup_clients(join_servers).await
+}
+
+pub async fn reload_loop() {
     let conf = config::get();
 
-    let mut clients_w = CLIENTS.write().await;
-    *clients_w = vec![];
+    loop {
+        sleep(conf.join_server.reload_interval).await;
+
+        if let Err(e) = reload().await {
+            error!(error = %e.full(), "Reloading Join Server clients error");
+        }
+    }
+}
+
+// The Join Servers from the configuration file are matched first, followed by the
+// Join Servers stored in the database.
+async fn setup_clients(join_servers: Vec<join_server::JoinServer>) -> Result<()> {
+    let conf = config::get();
+    let request_log_sender = stream::backend_interfaces::get_log_sender().await;
+
+    let mut clients: EuiClientList = vec![];
 
     for js in &conf.join_server.servers {
         info!(join_eui_prefix = %js.join_eui_prefix, "Configuring Join Server");
+        let c = new_client(js, request_log_sender.clone())?;
+        clients.push((js.join_eui_prefix, Arc::new(c)));
+    }
 
-        let c = Client::new(ClientConfig {
-            sender_id: conf.network.net_id.to_vec(),
-            server: js.server.clone(),
-            ca_cert: js.ca_cert.clone(),
-            tls_cert: js.tls_cert.clone(),
-            tls_key: js.tls_key.clone(),
-            async_timeout: js.async_timeout,
-            request_log_sender: stream::backend_interfaces::get_log_sender().await,
-            authorization: if js.authorization_header.is_empty() {
-                None
-            } else {
-                Some(js.authorization_header.clone())
-            },
-            ..Default::default()
-        })?;
+    for js in &join_servers {
+        info!(id = %js.id, join_eui_prefix = %js.join_eui_prefix, "Configuring Join Server");
 
-        clients_w.push((js.join_eui_prefix, Arc::new(c)));
+        // An invalid Join Server from the database must not affect the other
+        // Join Servers.
+        match new_client(&join_server_to_server(js), request_log_sender.clone()) {
+            Ok(c) => clients.push((js.join_eui_prefix, Arc::new(c))),
+            Err(e) => {
+                error!(id = %js.id, error = %e.full(), "Configuring Join Server error");
+            }
+        }
     }
 
+    *JOIN_SERVERS.write().await = join_servers;
+    *CLIENTS.write().await = clients;
+
     Ok(())
 }
 
+fn new_client(
+    js: &config::JoinServerServer,
+    request_log_sender: Option<Sender<stream_pb::BackendInterfacesRequest>>,
+) -> Result<Client> {
+    let conf = config::get();
+
+    Client::new(ClientConfig {
+        sender_id: conf.network.net_id.to_vec(),
+        server: js.server.clone(),
+        ca_cert: js.ca_cert.clone(),
+        tls_cert: js.tls_cert.clone(),
+        tls_key: js.tls_key.clone(),
+        async_timeout: js.async_timeout,
+        request_log_sender,
+        authorization: if js.authorization_header.is_empty() {
+            None
+        } else {
+            Some(js.authorization_header.clone())
+        },
+        ..Default::default()
+    })
+}
+
+fn join_server_to_server(js: &join_server::JoinServer) -> config::JoinServerServer {
+    config::JoinServerServer {
+        join_eui_prefix: js.join_eui_prefix,
+        server: js.server.clone(),
+        async_timeout: Duration::from_millis(js.async_timeout as u64),
+        ca_cert: js.ca_cert.clone(),
+        tls_cert: js.tls_cert.clone(),
+        tls_key: js.tls_key.clone(),
+        authorization_header: js.authorization_header.clone(),
+    }
+}
+
+// Validates that a Join Server client can be created for the given Join Server,
+// e.g. that the TLS material can be read.
+pub fn validate_join_server(js: &join_server::JoinServer) -> Result<()> {
+    super::validate_pem("ca_cert", &js.ca_cert)?;
+    super::validate_pem("tls_cert", &js.tls_cert)?;
+    super::validate_pem("tls_key", &js.tls_key)?;
+    new_client(&join_server_to_server(js), None).map(|_| ())
+}
+
 pub async fn get(join_eui: EUI64) -> Result<Arc<Client>> {
     for client in CLIENTS.read().await.iter() {
         if client.0.matches(join_eui) {
@@ -53,28 +155,26 @@ pub async fn get(join_eui: EUI64) -> Result<Arc<Client>> {
 
     let conf = config::get();
     if conf.join_server.default.enabled {
+        if let Some(c) = DEFAULT_CLIENTS.read().await.get(&join_eui) {
+            if c.is_valid() {
+                return Ok(c.client.clone());
+            }
+        }
+
         debug!(join_eui = %join_eui, "Configuring default join-server client");
 
-        let server = if conf.join_server.default.server.is_empty() {
-            let join_eui: String = join_eui
-                .to_string()
-                .chars()
-                .rev()
-                .map(|v| v.to_string())
-                .collect::<Vec<String>>()
-                .join(".");
-
-            format!(
-                "https://{}{}",
-                join_eui, conf.join_server.resolve_join_eui_domain_suffix
-            )
+        let resolved = if conf.join_server.default.server.is_empty() {
+            dns::resolve_join_eui(join_eui).await
         } else {
-            conf.join_server.default.server.clone()
+            dns::Resolved {
+                server: conf.join_server.default.server.clone(),
+                expires_at: None,
+            }
         };
 
         let c = Client::new(ClientConfig {
             sender_id: conf.network.net_id.to_vec(),
-            server,
+            server: resolved.server,
             ca_cert: conf.join_server.default.ca_cert.clone(),
             tls_cert: conf.join_server.default.tls_cert.clone(),
             tls_key: conf.join_server.default.tls_key.clone(),
@@ -89,14 +189,15 @@ pub async fn get(join_eui: EUI64) -> Result<Arc<Client>> {
         })?;
 
         let c = Arc::new(c);
-        let c_out = c.clone();
-
-        CLIENTS
-            .write()
-            .await
-            .push((EUI64Prefix::new(join_eui.to_be_bytes(), 64), c));
+        DEFAULT_CLIENTS.write().await.insert(
+            join_eui,
+            DefaultClient {
+                client: c.clone(),
+                expires_at: resolved.expires_at,
+            },
+        );
 
-        return Ok(c_out);
+        return Ok(c);
     }
 
     Err(anyhow!(
@@ -109,4 +210,5 @@ pub async fn get(join_eui: EUI64) -> Result<Arc<Client>> {
 pub async fn reset() {
     let mut clients_w = CLIENTS.write().await;
     *clients_w = vec![];
+    DEFAULT_CLIENTS.write().await.clear();
 }
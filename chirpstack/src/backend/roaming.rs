@@ -1,99 +1,272 @@
 use std::collections::HashMap;
 use std::io::Cursor;
 use std::str::FromStr;
-use std::sync::{Arc, LazyLock};
+use std::sync::{Arc, LazyLock, RwLock as StdRwLock};
 
 use anyhow::Result;
-use chrono::{Duration, DurationRound};
+use chrono::{DateTime, Duration, DurationRound, Utc};
 use prost::Message;
 use tokio::sync::RwLock;
-use tracing::{Level, debug, info, span};
+use tokio::sync::mpsc::Sender;
+use tokio::time::{Instant, sleep};
+use tracing::{Level, debug, error, info, span};
+use uuid::Uuid;
 
+use super::dns;
 use crate::gpstime::ToGpsTime;
+use crate::helpers::errors::PrintFullError;
+use crate::storage::{device_profile, roaming_agreement, roaming_settlement};
 use crate::{config, stream};
 use backend::{Client, ClientConfig, GWInfoElement, ULMetaData};
-use chirpstack_api::{common, gw};
+use chirpstack_api::{common, gw, stream as stream_pb};
 use lrwn::{DevAddr, EUI64, NetID, region};
 
 static CLIENTS: LazyLock<RwLock<HashMap<NetID, Arc<Client>>>> =
     LazyLock::new(|| RwLock::new(HashMap::new()));
+static AGREEMENTS: LazyLock<StdRwLock<Arc<Agreements>>> =
+    LazyLock::new(|| StdRwLock::new(Arc::new(Agreements::default())));
+static DEFAULT_CLIENTS: LazyLock<RwLock<HashMap<NetID, DefaultClient>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+// Snapshot of the roaming agreements stored in the database and the roaming servers
+// derived from these. The snapshot is replaced as a whole, after the roaming clients
+// have been configured.
+#[derive(Default)]
+struct Agreements {
+    agreements: Vec<roaming_agreement::RoamingAgreement>,
+    servers: Vec<config::RoamingServer>,
+}
+
+struct DefaultClient {
+    client: Arc<Client>,
+    // Set in case the server was resolved using DNS.
+    expires_at: Option<Instant>,
+}
+
+impl DefaultClient {
+    fn is_valid(&self) -> bool {
+        match self.expires_at {
+            Some(v) => v > Instant::now(),
+            None => true,
+        }
+    }
+}
 
 pub async fn setup() -> Result<()> {
     info!("Setting up roaming clients");
+    let agreements = roaming_agreement::list_all().await?;
+    setup_clients(agreements).await
+}
+
+// Reloads the roaming clients in case the roaming agreements stored in the database
+// have been changed.
+pub async fn reload() -> Result<()> {
+    let agreements = roaming_agreement::list_all().await?;
+    if get_agreements().agreements == agreements {
+        return Ok(());
+    }
+
+    info!("Roaming agreements have been changed, reloading roaming clients");
+    setup_clients(agreements).await
+}
+
+pub async fn reload_loop() {
+    let conf = config::get();
+
+    loop {
+        sleep(conf.roaming.reload_interval).await;
+
+        if let Err(e) = reload().await {
+            error!(error = %e.full(), "Reloading roaming clients error");
+        }
+    }
+}
+
+async fn setup_clients(agreements: Vec<roaming_agreement::RoamingAgreement>) -> Result<()> {
     let conf = config::get();
+    let request_log_sender = stream::backend_interfaces::get_log_sender().await;
+    let agreements = Agreements {
+        servers: agreements.iter().map(agreement_to_roaming_server).collect(),
+        agreements,
+    };
 
-    for s in &conf.roaming.servers {
+    let mut clients: HashMap<NetID, Arc<Client>> = HashMap::new();
+
+    for s in get_roaming_servers(&conf, &agreements) {
         let span = span!(Level::INFO, "setup", net_id  = %s.net_id);
         let _guard = span.enter();
 
-        let server = if s.server.is_empty() {
-            format!(
-                "https://{}{}",
-                s.net_id, conf.roaming.resolve_net_id_domain_suffix,
-            )
-        } else {
-            s.server.clone()
-        };
-
         info!(
             passive_roaming_lifetime = ?s.passive_roaming_lifetime,
-            server = %server,
+            server = %s.server,
             async_timeout = ?s.async_timeout,
             "Configuring roaming client"
         );
 
-        let c = Client::new(ClientConfig {
-            sender_id: conf.network.net_id.to_vec(),
-            receiver_id: s.net_id.to_vec(),
-            server,
-            use_target_role_suffix: s.use_target_role_suffix,
-            ca_cert: s.ca_cert.clone(),
-            tls_cert: s.tls_cert.clone(),
-            tls_key: s.tls_key.clone(),
-            authorization: if s.authorization_header.is_empty() {
-                None
-            } else {
-                Some(s.authorization_header.clone())
-            },
-            async_timeout: s.async_timeout,
-            request_log_sender: stream::backend_interfaces::get_log_sender().await,
-        })?;
+        let c = match new_client(s, request_log_sender.clone()) {
+            Ok(v) => v,
+            // An invalid roaming agreement from the configuration file is a configuration
+            // error. An invalid roaming agreement from the database must not affect the
+            // other roaming agreements.
+            Err(e) if !conf.roaming.servers.iter().any(|v| v.net_id == s.net_id) => {
+                error!(error = %e.full(), "Configuring roaming client error");
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
 
-        set(&s.net_id, c).await;
+        clients.insert(s.net_id, Arc::new(c));
     }
 
+    // Only swap the clients and agreements once all clients have been configured, such
+    // that a configuration error does not leave these in an inconsistent state.
+    let mut clients_w = CLIENTS.write().await;
+    *clients_w = clients;
+    *AGREEMENTS.write().unwrap() = Arc::new(agreements);
+
     Ok(())
 }
 
+fn new_client(
+    s: &config::RoamingServer,
+    request_log_sender: Option<Sender<stream_pb::BackendInterfacesRequest>>,
+) -> Result<Client> {
+    let conf = config::get();
+
+    let server = if s.server.is_empty() {
+        format!("https://{}", dns::net_id_domain(s.net_id))
+    } else {
+        s.server.clone()
+    };
+
+    Client::new(ClientConfig {
+        sender_id: conf.network.net_id.to_vec(),
+        receiver_id: s.net_id.to_vec(),
+        server,
+        use_target_role_suffix: s.use_target_role_suffix,
+        ca_cert: s.ca_cert.clone(),
+        tls_cert: s.tls_cert.clone(),
+        tls_key: s.tls_key.clone(),
+        authorization: if s.authorization_header.is_empty() {
+            None
+        } else {
+            Some(s.authorization_header.clone())
+        },
+        async_timeout: s.async_timeout,
+        request_log_sender,
+    })
+}
+
+// Validates that a roaming client can be created for the given roaming agreement,
+// e.g. that the TLS material can be read.
+pub fn validate_agreement(ra: &roaming_agreement::RoamingAgreement) -> Result<()> {
+    super::validate_pem("ca_cert", &ra.ca_cert)?;
+    super::validate_pem("tls_cert", &ra.tls_cert)?;
+    super::validate_pem("tls_key", &ra.tls_key)?;
+    new_client(&agreement_to_roaming_server(ra), None).map(|_| ())
+}
+
+// Returns the roaming servers from the configuration file, followed by the roaming
+// agreements stored in the database. In case a NetID is present in both, the
+// configuration file takes precedence.
+fn get_roaming_servers<'a>(
+    conf: &'a config::Configuration,
+    agreements: &'a Agreements,
+) -> impl Iterator<Item = &'a config::RoamingServer> {
+    conf.roaming.servers.iter().chain(
+        agreements
+            .servers
+            .iter()
+            .filter(|s| !conf.roaming.servers.iter().any(|v| v.net_id == s.net_id)),
+    )
+}
+
+fn get_roaming_server(net_id: NetID) -> Option<config::RoamingServer> {
+    let conf = config::get();
+    get_roaming_servers(&conf, &get_agreements())
+        .find(|s| s.net_id == net_id)
+        .cloned()
+}
+
+fn get_agreements() -> Arc<Agreements> {
+    AGREEMENTS.read().unwrap().clone()
+}
+
+fn agreement_to_roaming_server(ra: &roaming_agreement::RoamingAgreement) -> config::RoamingServer {
+    config::RoamingServer {
+        net_id: ra.net_id,
+        secondary_net_ids: ra.get_secondary_net_ids().unwrap_or_default(),
+        async_timeout: std::time::Duration::from_millis(ra.async_timeout as u64),
+        passive_roaming_lifetime: std::time::Duration::from_secs(
+            ra.passive_roaming_lifetime as u64,
+        ),
+        passive_roaming_kek_label: ra.passive_roaming_kek_label.clone(),
+        passive_roaming_validate_mic: ra.passive_roaming_validate_mic,
+        handover_roaming: ra.handover_roaming,
+        handover_roaming_lifetime: std::time::Duration::from_secs(
+            ra.handover_roaming_lifetime as u64,
+        ),
+        handover_roaming_kek_label: ra.handover_roaming_kek_label.clone(),
+        server: ra.server.clone(),
+        use_target_role_suffix: ra.use_target_role_suffix,
+        ca_cert: ra.ca_cert.clone(),
+        tls_cert: ra.tls_cert.clone(),
+        tls_key: ra.tls_key.clone(),
+        authorization_header: ra.authorization_header.clone(),
+    }
+}
+
+// Returns true if the device-profile is allowed by the roaming agreement with the
+// given NetID. Roaming agreements from the configuration file allow all device-profiles.
+pub fn is_device_profile_allowed(net_id: NetID, device_profile_id: &Uuid) -> bool {
+    let conf = config::get();
+    if conf.roaming.servers.iter().any(|s| s.net_id == net_id) {
+        return true;
+    }
+
+    match get_agreements()
+        .agreements
+        .iter()
+        .find(|ra| ra.net_id == net_id)
+    {
+        Some(ra) => ra.is_device_profile_allowed(device_profile_id),
+        None => true,
+    }
+}
+
 pub async fn set(net_id: &NetID, c: Client) {
     let mut clients_w = CLIENTS.write().await;
     clients_w.insert(*net_id, Arc::new(c));
 }
 
 pub async fn get(net_id: &NetID) -> Result<Arc<Client>> {
-    let mut clients_w = CLIENTS.write().await;
-
-    if let Some(client) = clients_w.get(net_id) {
+    if let Some(client) = CLIENTS.read().await.get(net_id) {
         return Ok(client.clone());
     }
 
     let conf = config::get();
     if conf.roaming.default.enabled {
+        if let Some(c) = DEFAULT_CLIENTS.read().await.get(net_id)
+            && c.is_valid()
+        {
+            return Ok(c.client.clone());
+        }
+
         debug!(net_id = %net_id, "Configuring default roaming client");
 
-        let server = if conf.roaming.default.server.is_empty() {
-            format!(
-                "https://{}{}",
-                net_id, conf.roaming.resolve_net_id_domain_suffix,
-            )
+        let resolved = if conf.roaming.default.server.is_empty() {
+            dns::resolve_net_id(*net_id).await
         } else {
-            conf.roaming.default.server.clone()
+            dns::Resolved {
+                server: conf.roaming.default.server.clone(),
+                expires_at: None,
+            }
         };
 
         let c = Client::new(ClientConfig {
             sender_id: conf.network.net_id.to_vec(),
             receiver_id: net_id.to_vec(),
-            server,
+            server: resolved.server,
             use_target_role_suffix: conf.roaming.default.use_target_role_suffix,
             ca_cert: conf.roaming.default.ca_cert.clone(),
             tls_cert: conf.roaming.default.tls_cert.clone(),
@@ -108,10 +281,15 @@ pub async fn get(net_id: &NetID) -> Result<Arc<Client>> {
         })?;
 
         let c = Arc::new(c);
-        let c_out = c.clone();
-        clients_w.insert(*net_id, c);
+        DEFAULT_CLIENTS.write().await.insert(
+            *net_id,
+            DefaultClient {
+                client: c.clone(),
+                expires_at: resolved.expires_at,
+            },
+        );
 
-        return Ok(c_out);
+        return Ok(c);
     }
 
     Err(anyhow!(
@@ -123,10 +301,8 @@ pub async fn get(net_id: &NetID) -> Result<Arc<Client>> {
 pub fn get_passive_roaming_lifetime(net_id: NetID) -> Result<std::time::Duration> {
     let conf = config::get();
 
-    for s in &conf.roaming.servers {
-        if s.net_id == net_id {
-            return Ok(s.passive_roaming_lifetime);
-        }
+    if let Some(s) = get_roaming_server(net_id) {
+        return Ok(s.passive_roaming_lifetime);
     }
 
     if conf.roaming.default.enabled {
@@ -140,12 +316,8 @@ pub fn get_passive_roaming_lifetime(net_id: NetID) -> Result<std::time::Duration
 }
 
 pub fn get_passive_roaming_kek_label(net_id: NetID) -> Result<String> {
-    let conf = config::get();
-
-    for s in &conf.roaming.servers {
-        if s.net_id == net_id {
-            return Ok(s.passive_roaming_kek_label.clone());
-        }
+    if let Some(s) = get_roaming_server(net_id) {
+        return Ok(s.passive_roaming_kek_label);
     }
 
     Err(anyhow!(
@@ -157,10 +329,8 @@ pub fn get_passive_roaming_kek_label(net_id: NetID) -> Result<String> {
 pub fn get_passive_roaming_validate_mic(net_id: NetID) -> Result<bool> {
     let conf = config::get();
 
-    for s in &conf.roaming.servers {
-        if s.net_id == net_id {
-            return Ok(s.passive_roaming_validate_mic);
-        }
+    if let Some(s) = get_roaming_server(net_id) {
+        return Ok(s.passive_roaming_validate_mic);
     }
 
     if conf.roaming.default.enabled {
@@ -173,9 +343,63 @@ pub fn get_passive_roaming_validate_mic(net_id: NetID) -> Result<bool> {
     ))
 }
 
+pub fn get_handover_roaming(net_id: NetID) -> Result<bool> {
+    let conf = config::get();
+
+    if let Some(s) = get_roaming_server(net_id) {
+        return Ok(s.handover_roaming);
+    }
+
+    if conf.roaming.default.enabled {
+        return Ok(conf.roaming.default.handover_roaming);
+    }
+
+    Err(anyhow!(
+        "Handover-roaming setting for net_id {} does not exist",
+        net_id
+    ))
+}
+
+pub fn get_handover_roaming_lifetime(net_id: NetID) -> Result<std::time::Duration> {
+    let conf = config::get();
+
+    if let Some(s) = get_roaming_server(net_id) {
+        return Ok(s.handover_roaming_lifetime);
+    }
+
+    if conf.roaming.default.enabled {
+        return Ok(conf.roaming.default.handover_roaming_lifetime);
+    }
+
+    Err(anyhow!(
+        "Handover-roaming lifetime for net_id {} does not exist",
+        net_id
+    ))
+}
+
+pub fn get_handover_roaming_kek_label(net_id: NetID) -> Result<String> {
+    let conf = config::get();
+
+    if let Some(s) = get_roaming_server(net_id) {
+        return Ok(s.handover_roaming_kek_label);
+    }
+
+    if conf.roaming.default.enabled {
+        return Ok(conf.roaming.default.handover_roaming_kek_label.clone());
+    }
+
+    Err(anyhow!(
+        "Handover-roaming kek-label for net_id {} does not exist",
+        net_id
+    ))
+}
+
 pub fn is_enabled() -> bool {
     let conf = config::get();
-    conf.roaming.default.enabled || !conf.roaming.servers.is_empty()
+    conf.roaming.default.enabled
+        || get_roaming_servers(&conf, &get_agreements())
+            .next()
+            .is_some()
 }
 
 pub fn is_roaming_dev_addr(dev_addr: DevAddr) -> bool {
@@ -210,10 +434,11 @@ pub fn is_roaming_dev_addr(dev_addr: DevAddr) -> bool {
 }
 
 pub fn get_net_ids_for_dev_addr(dev_addr: DevAddr) -> Vec<NetID> {
-    let mut out: Vec<NetID> = Vec::new();
     let conf = config::get();
+    let agreements = get_agreements();
+    let mut out: Vec<NetID> = Vec::new();
 
-    for agreement in &conf.roaming.servers {
+    for agreement in get_roaming_servers(&conf, &agreements) {
         if dev_addr.is_net_id(agreement.net_id) {
             out.push(agreement.net_id);
         }
@@ -362,15 +587,121 @@ pub fn dl_meta_data_to_uplink_rx_info(
     Ok(out)
 }
 
+pub fn device_profile_to_bi(dp: &device_profile::DeviceProfile) -> backend::DeviceProfile {
+    let class_b = dp.class_b_params.clone().unwrap_or_default();
+    let class_c = dp.class_c_params.clone().unwrap_or_default();
+
+    backend::DeviceProfile {
+        device_profile_id: dp.id.to_string(),
+        supports_class_b: dp.supports_class_b,
+        class_b_timeout: class_b.timeout as usize,
+        ping_slot_period: 1 << class_b.ping_slot_periodicity,
+        ping_slot_dr: class_b.ping_slot_dr as usize,
+        ping_slot_freq: class_b.ping_slot_freq as f64 / 1_000_000.0,
+        supports_class_c: dp.supports_class_c,
+        class_c_timeout: class_c.timeout as usize,
+        mac_version: dp.mac_version.to_string(),
+        reg_params_revision: dp.reg_params_revision.to_string(),
+        rx_delay_1: dp.rx1_delay as usize,
+        supports_join: dp.supports_otaa,
+        rf_region: dp.region.to_string().replace('_', "-"),
+        supports_32bit_f_cnt: true,
+        ..Default::default()
+    }
+}
+
+// Records the passive-roaming uplink for the settlement with the roaming partner.
+// The frame is accounted to the (UTC) day of the ULMetaData RecvTime, such that
+// both roaming partners account frames received around midnight to the same day.
+// Errors are logged, such that these do not affect the passive-roaming flow.
+pub async fn record_uplink(
+    net_id: NetID,
+    role: backend::Role,
+    phy_payload: &[u8],
+    ul_meta_data: &ULMetaData,
+) {
+    record_settlement(
+        net_id,
+        role,
+        roaming_settlement::Direction::Uplink,
+        ul_meta_data.recv_time,
+        phy_payload,
+        ul_meta_data.gw_cnt.unwrap_or(ul_meta_data.gw_info.len()),
+    )
+    .await;
+}
+
+// Records the passive-roaming downlink for the settlement with the roaming partner.
+// The frame is accounted to the (UTC) day of the downlink transmission time, see
+// get_downlink_tx_time. Errors are logged, such that these do not affect the
+// passive-roaming flow.
+pub async fn record_downlink(
+    net_id: NetID,
+    role: backend::Role,
+    phy_payload: &[u8],
+    dl_meta_data: &backend::DLMetaData,
+    ul_recv_time: Option<DateTime<Utc>>,
+) {
+    record_settlement(
+        net_id,
+        role,
+        roaming_settlement::Direction::Downlink,
+        get_downlink_tx_time(dl_meta_data, ul_recv_time),
+        phy_payload,
+        dl_meta_data.gw_info.len(),
+    )
+    .await;
+}
+
+// Returns the transmission time of the given downlink. In case of a downlink in
+// response to an uplink (ul_recv_time is set), this is the uplink RecvTime + RXDelay1.
+// Else the downlink is transmitted immediately (e.g. Class-C or a Class-A downlink
+// received through a XmitDataReq).
+fn get_downlink_tx_time(
+    dl_meta_data: &backend::DLMetaData,
+    ul_recv_time: Option<DateTime<Utc>>,
+) -> DateTime<Utc> {
+    match ul_recv_time {
+        Some(v) => v + Duration::seconds(dl_meta_data.rx_delay_1.unwrap_or(1).max(1) as i64),
+        None => Utc::now(),
+    }
+}
+
+async fn record_settlement(
+    net_id: NetID,
+    role: backend::Role,
+    direction: roaming_settlement::Direction,
+    time: DateTime<Utc>,
+    phy_payload: &[u8],
+    gw_count: usize,
+) {
+    if let Err(e) = roaming_settlement::increment(roaming_settlement::RoamingSettlement {
+        net_id,
+        day: time.date_naive(),
+        role: format!("{:?}", role),
+        direction: direction.to_string(),
+        frame_count: 1,
+        phy_payload_bytes: phy_payload.len() as i64,
+        gw_count: gw_count as i64,
+    })
+    .await
+    {
+        error!(net_id = %net_id, error = %e.full(), "Record roaming settlement error");
+    }
+}
+
 #[cfg(test)]
 pub async fn reset() {
     let mut clients_w = CLIENTS.write().await;
     *clients_w = HashMap::new();
+    DEFAULT_CLIENTS.write().await.clear();
+    *AGREEMENTS.write().unwrap() = Arc::new(Agreements::default());
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use chrono::TimeZone;
 
     #[test]
     fn test_is_roaming_dev_addr() {
@@ -476,4 +807,37 @@ mod test {
             }];
         }
     }
+
+    #[test]
+    fn test_get_downlink_tx_time() {
+        let recv_time = Utc.with_ymd_and_hms(2025, 1, 1, 23, 59, 59).unwrap();
+
+        // Uplink right before midnight, RX1 after midnight.
+        assert_eq!(
+            Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap(),
+            get_downlink_tx_time(
+                &backend::DLMetaData {
+                    rx_delay_1: Some(1),
+                    ..Default::default()
+                },
+                Some(recv_time)
+            )
+        );
+        assert_eq!(
+            Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 4).unwrap(),
+            get_downlink_tx_time(
+                &backend::DLMetaData {
+                    rx_delay_1: Some(5),
+                    ..Default::default()
+                },
+                Some(recv_time)
+            )
+        );
+
+        // RXDelay1 0 equals 1 second.
+        assert_eq!(
+            Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap(),
+            get_downlink_tx_time(&Default::default(), Some(recv_time))
+        );
+    }
 }
@@ -212,11 +212,16 @@ pub fn run() {
 
   # Secondary NetIDs.
   #
-  # Additional NetIDs. At this moment, the additional NetIDs are only used to
-  # validate if an uplink belongs to the ChirpStack instance or if it is a
-  # roaming device (if roaming is enabled).
+  # Additional NetIDs. These are used to validate if an uplink belongs to the
+  # ChirpStack instance or if it is a roaming device (if roaming is enabled).
   # If you would like to assign DevAddrs from multiple NetIDs, you must specify
   # these in the dev_addr_prefixes configuration.
+  #
+  # A tenant can be bound to one of the secondary NetIDs (see the NetID of the
+  # tenant). In this case, the NetID of the tenant is used as HomeNetID in
+  # join-accepts and as SenderID in Backend Interfaces requests for devices of
+  # this tenant. Unless the tenant has DevAddr prefixes configured, DevAddrs are
+  # allocated from the DevAddr space of this NetID.
   secondary_net_ids=[
     {{#each network.secondary_net_ids}}
     "{{this}}",
@@ -242,6 +247,22 @@ pub fn run() {
     {{/each}}
   ]
 
+  # DevAddr allocation.
+  #
+  # This defines how ChirpStack allocates a DevAddr (within the DevAddr
+  # prefixes) on OTAA join or when generating a random DevAddr through the API.
+  # In both modes, ChirpStack prefers DevAddrs which are not yet used by other
+  # devices, to reduce the number of sessions sharing the same DevAddr.
+  #
+  # Valid options are:
+  #  * random:      Allocate a random DevAddr.
+  #  * sequential:  Allocate the lowest DevAddr of the prefix which is not in
+  #                 use, such that DevAddrs are handed out as a sequential
+  #                 range and DevAddrs of deleted devices are re-used. Once all
+  #                 DevAddrs of the prefix are in use, ChirpStack logs a warning
+  #                 and falls back to random allocation.
+  dev_addr_allocation="{{ network.dev_addr_allocation }}"
+
   # Enabled regions.
   #
   # Multiple regions can be enabled simultaneously. Each region must match
@@ -334,8 +355,9 @@ pub fn run() {
     # Multicast Class-B margin.
     #
     # This defines the minimum margin between scheduling multiple multicast downlinks
-    # (within the same multicast-group). This value must be equal or greater than the
-    # scheduler interval.
+    # (within the same multicast-group) through the same gateway. When multiple gateways
+    # are used, each gateway will use a different ping-slot. This value must be equal or
+    # greater than the scheduler interval.
     multicast_class_b_margin="{{ network.scheduler.multicast_class_b_margin }}"
 
     # Class-B schedule advance.
@@ -363,6 +385,25 @@ pub fn run() {
   # in Redis Streams. Setting this value to 0 disables this features.
   backend_interfaces_log_max_history={{ monitoring.backend_interfaces_log_max_history }}
 
+  # Backend Interfaces log retention.
+  #
+  # If set, all Backend Interfaces requests (including the request and response
+  # payloads) are stored in the database, such that these can be queried and
+  # (in case of a failed XmitDataReq) be replayed through the API. Records older
+  # than the given duration are removed periodically. Setting this value to 0s
+  # disables this feature.
+  #
+  # Note: the AES keys of the key-envelopes (e.g. the AppSKey and NwkSKey) are
+  # redacted from the stored payloads.
+  backend_interfaces_log_retention="{{ monitoring.backend_interfaces_log_retention }}"
+
+  # Backend Interfaces log replay max age.
+  #
+  # Failed XmitDataReq requests older than the given duration can not be replayed,
+  # as the payload is likely to be outdated (e.g. the device or the roaming partner
+  # will reject it). Setting this value to 0s disables this check.
+  backend_interfaces_log_replay_max_age="{{ monitoring.backend_interfaces_log_replay_max_age }}"
+
   # Meta-log max history.
   #
   # This defines the max number of meta records that will be persisted in Redis Streams.
@@ -831,6 +872,23 @@ pub fn run() {
     # Resolve JoinEUI domain suffix.
     resolve_join_eui_domain_suffix="{{ join_server.resolve_join_eui_domain_suffix }}"
 
+    # Resolve JoinEUI using DNS.
+    #
+    # If enabled, the server of the default join-server is resolved using the
+    # NAPTR (and SRV) records of the JoinEUI domain, e.g.
+    # 0.0.7.0.6.0.5.0.4.0.3.0.2.0.1.0.joineuis.lora-alliance.org. If no
+    # usable records are found, ChirpStack falls back to
+    # https://[JoinEUI domain]. See also the [dns] configuration.
+    resolve_join_eui_dns={{ join_server.resolve_join_eui_dns }}
+
+    # Reload interval.
+    #
+    # Join Servers can also be managed through the API. This defines the
+    # interval in which ChirpStack checks the database for changes, such that
+    # changes made through other ChirpStack instances are picked up.
+    # Set this to 0s to disable.
+    reload_interval="{{ join_server.reload_interval }}"
+
 
     # Default join-server.
     [join_server.default]
@@ -867,7 +925,8 @@ pub fn run() {
     # join_eui_prefix in the same order as they appear in the configuration.
     #
     # If you configure a 'catch-all' Join Server, then this entry must appear
-    # as the last item in the list.
+    # as the last item in the list. Join Servers managed through the API are
+    # matched after the Join Servers configured in this file.
     #
     # Example:
     # [[join_server.servers]]
@@ -945,12 +1004,81 @@ pub fn run() {
   tls_key="{{ backend_interfaces.tls_key }}"
 
 
+  # Join Server role.
+  #
+  # When enabled, ChirpStack will handle the JoinReq, RejoinReq and AppSKeyReq
+  # messages sent by external Network Servers, using the device-keys that are
+  # stored in ChirpStack. The sender NetID must have a roaming configuration
+  # (see [roaming] section) and must be allowed to join the JoinEUI.
+  [backend_interfaces.join_server]
+
+    # Enable the Join Server role.
+    enabled={{ backend_interfaces.join_server.enabled }}
+
+    # AppSKey KEK label (optional).
+    #
+    # If set, the AppSKey will be encrypted using the given KEK.
+    app_s_key_kek_label="{{ backend_interfaces.join_server.app_s_key_kek_label }}"
+
+    # Session-key lifetime.
+    #
+    # This defines how long the AppSKey can be retrieved using an AppSKeyReq
+    # after a successful join.
+    session_key_lifetime="{{ backend_interfaces.join_server.session_key_lifetime }}"
+
+
+    # Allowed NetIDs (this can be repeated).
+    #
+    # Example:
+    # [[backend_interfaces.join_server.allowed]]
+    #
+    #   # NetID of the Network Server.
+    #   net_id="010203"
+    #
+    #   # JoinEUI prefixes that this Network Server is allowed to join.
+    #   join_eui_prefixes=["0102030405060700/56"]
+    #
+    #   # KEK label (optional).
+    #   #
+    #   # If set, the network session-keys will be encrypted using the given
+    #   # KEK.
+    #   kek_label=""
+    {{#each backend_interfaces.join_server.allowed}}
+
+    [[backend_interfaces.join_server.allowed]]
+      net_id="{{ this.net_id }}"
+      join_eui_prefixes=[
+        {{#each this.join_eui_prefixes}}
+        "{{this}}",
+        {{/each}}
+      ]
+      kek_label="{{ this.kek_label }}"
+    {{/each}}
+
+
 # Roaming configuration.
 [roaming]
 
   # Resolve NetID domain suffix.
   resolve_net_id_domain_suffix="{{ backend_interfaces.resolve_net_id_domain_suffix }}"
 
+  # Resolve NetID using DNS.
+  #
+  # If enabled, the server of the default roaming server is resolved using
+  # the NAPTR (and SRV) records of the NetID domain, e.g.
+  # 000013.netids.lora-alliance.org. If no usable records are found,
+  # ChirpStack falls back to https://[NetID domain]. See also the [dns]
+  # configuration.
+  resolve_net_id_dns={{ roaming.resolve_net_id_dns }}
+
+  # Reload interval.
+  #
+  # Roaming agreements can also be managed through the API. This defines the
+  # interval in which ChirpStack checks the database for changes, such that
+  # changes made through other ChirpStack instances are picked up.
+  # Set this to 0s to disable.
+  reload_interval="{{ roaming.reload_interval }}"
+
 
   # Default roaming server.
   [roaming.default]
@@ -975,6 +1103,23 @@ pub fn run() {
     # agreements). As well it means it will expose the NwkSKey / FNwkSIntKey
     # on PRStartAns.
     passive_roaming_validate_mic={{roaming.default.passive_roaming_validate_mic}}
+
+    # Handover-roaming.
+    #
+    # If set, ChirpStack will allow handover-roaming with the roaming
+    # partner. As home Network Server this means that devices of which the
+    # device-profile allows handover-roaming can be handed over to the
+    # serving Network Server. As serving Network Server this means that
+    # ChirpStack will request a handover-roaming activation on join.
+    handover_roaming={{roaming.default.handover_roaming}}
+
+    # Handover-roaming session lifetime (set to 0 for no expiration).
+    handover_roaming_lifetime="{{roaming.default.handover_roaming_lifetime}}"
+
+    # Handover-roaming KEK label (optional).
+    #
+    # If set, the network session-keys will be encrypted using the given KEK.
+    handover_roaming_kek_label="{{roaming.default.handover_roaming_kek_label}}"
    
     # Server.
     #
@@ -1003,6 +1148,9 @@ pub fn run() {
 
 
   # Per server roaming configuration (this can be repeated).
+  #
+  # Roaming agreements can also be managed through the API. In case a NetID
+  # is configured in both, the configuration below takes precedence.
   # Example:
   # [[roaming.servers]]
   #
@@ -1033,6 +1181,20 @@ pub fn run() {
   #  # on PRStartAns.
   #  passive_roaming_validate_mic=false
   #
+  #  # Handover-roaming.
+  #  #
+  #  # If set, ChirpStack will allow handover-roaming with the roaming
+  #  # partner.
+  #  handover_roaming=false
+  #
+  #  # Handover-roaming session lifetime (set to 0 for no expiration).
+  #  handover_roaming_lifetime="0s"
+  #
+  #  # Handover-roaming KEK label (optional).
+  #  #
+  #  # If set, the network session-keys will be encrypted using the given KEK.
+  #  handover_roaming_kek_label=""
+  #
   #  # Server.
   #  #
   #  # If set, this will bypass the DNS resolving of the server.
@@ -1070,6 +1232,9 @@ pub fn run() {
     passive_roaming_lifetime="{{ this.passive_roaming_lifetime }}"
     passive_roaming_kek_label="{{ this.passive_roaming_kek_label }}"
     passive_roaming_validate_mic={{ this.passive_roaming_validate_mic }}
+    handover_roaming={{ this.handover_roaming }}
+    handover_roaming_lifetime="{{ this.handover_roaming_lifetime }}"
+    handover_roaming_kek_label="{{ this.handover_roaming_kek_label }}"
     server="{{ this.server }}"
     use_target_role_suffix="{{ this.use_target_role_suffix }}"
     ca_cert="{{ this.ca_cert }}"
@@ -1079,6 +1244,34 @@ pub fn run() {
   {{/each}}
 
 
+# DNS configuration.
+#
+# This is used for resolving the JoinEUI and NetID domains when
+# join_server.resolve_join_eui_dns or roaming.resolve_net_id_dns is enabled.
+[dns]
+
+  # Nameservers (IP:PORT).
+  #
+  # If not set, the nameservers of the system configuration will be used.
+  nameservers=[
+    {{#each dns.nameservers}}
+    "{{this}}",
+    {{/each}}
+  ]
+
+  # Max. cache TTL.
+  #
+  # Resolved servers are cached for the TTL of the DNS records, with this
+  # value as upper limit.
+  max_cache_ttl="{{ dns.max_cache_ttl }}"
+
+  # Negative cache TTL.
+  #
+  # This defines how long a failed lookup is cached, before it is retried.
+  # During this period, the fallback server is used.
+  negative_cache_ttl="{{ dns.negative_cache_ttl }}"
+
+
 # Key encryption keys (KEKs).
 #
 # KEKs can be used to encrypt session-keys between two endpoints,
@@ -1104,6 +1297,108 @@ pub fn run() {
 {{/each}}
 
 
+# Manifest key encryption keys.
+#
+# These KEKs are used to unwrap the (AES key-wrapped) device root-keys of
+# device manifests provided by the device manufacturer. These are kept
+# separate from the KEKs above, as any user allowed to import a manifest
+# is able to unwrap keys using these KEKs.
+#
+# Example (can be repeated):
+# [[manifest_keks]]
+#
+#   # KEK label.
+#   label="manufacturer-kek"
+
+#   # Encryption key.
+#   kek="01020304050607080102030405060708"
+{{#each manifest_keks}}
+
+[[manifest_keks]]
+  label="{{ this.label }}"
+  kek="{{ this.kek }}"
+{{/each}}
+
+
+# Encryption at rest.
+#
+# If enabled, the device root-keys (NwkKey, AppKey) and the session-keys of
+# the device-sessions are stored encrypted in the database. The keys are
+# encrypted using a data-encryption key (DEK), which is generated by
+# ChirpStack. The DEK is stored in the database, wrapped by the configured
+# key encryption key (KEK).
+#
+# Existing (plaintext) keys are encrypted on their next update, or can be
+# encrypted at once using the encrypt-device-keys command. Use the
+# rotate-data-encryption-key command to rotate the DEK. To rotate the KEK,
+# add a new KEK, set kek_label to the new KEK and rotate the DEK.
+#
+# Note: once enabled, encryption can not be disabled as long as there are
+# keys encrypted in the database.
+[encryption]
+
+  # Enable encryption at rest.
+  enabled={{ encryption.enabled }}
+
+  # KEK label.
+  #
+  # The label of the KEK (see below) that is used to wrap new DEKs.
+  kek_label="{{ encryption.kek_label }}"
+
+  # Reload interval.
+  #
+  # This defines the interval in which ChirpStack re-loads the DEKs from
+  # the database, such that a DEK rotation is picked up. When running
+  # multiple ChirpStack instances, keys encrypted using a new DEK can only
+  # be decrypted by the other instances after they have re-loaded the DEKs.
+  # The rotate-data-encryption-key command waits for this before
+  # re-encrypting the keys. The DEKs are also re-loaded when a key encrypted
+  # using an unknown DEK is read.
+  reload_interval="{{ encryption.reload_interval }}"
+
+  # KEKs.
+  #
+  # Example local KEK (KEK is read from a file, HEX encoded):
+  # [[encryption.keks]]
+  #   label="local"
+  #   provider="local"
+  #   kek_file="/etc/chirpstack/kek.key"
+  #
+  # Example HTTP KEK (Vault transit-style API, the KEK never leaves the
+  # key-management service):
+  # [[encryption.keks]]
+  #   label="vault"
+  #   provider="http"
+  #   server="https://vault:8200/v1/transit"
+  #   key_name="chirpstack"
+  #   token_header="X-Vault-Token"
+  #   token="..."
+  #
+  # Example PKCS#11 KEK (AES key-wrap using the secret key with the given
+  # key_name as label, the KEK never leaves the token):
+  # [[encryption.keks]]
+  #   label="hsm"
+  #   provider="pkcs11"
+  #   library="/usr/lib/softhsm/libsofthsm2.so"
+  #   token_label="chirpstack"
+  #   pin="1234"
+  #   key_name="chirpstack-kek"
+  {{#each encryption.keks}}
+
+  [[encryption.keks]]
+    label="{{ this.label }}"
+    provider="{{ this.provider }}"
+    kek_file="{{ this.kek_file }}"
+    server="{{ this.server }}"
+    key_name="{{ this.key_name }}"
+    token_header="{{ this.token_header }}"
+    token="{{ this.token }}"
+    library="{{ this.library }}"
+    token_label="{{ this.token_label }}"
+    pin="{{ this.pin }}"
+  {{/each}}
+
+
 # UI configuration.
 [ui]
   # Tileserver URL.
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use tracing::info;
+
+use crate::encryption;
+use crate::storage::{self, device_keys, get_async_db_conn, schema::device};
+use lrwn::EUI64;
+
+pub async fn run() -> Result<()> {
+    storage::setup().await?;
+
+    if !encryption::is_enabled() {
+        return Err(anyhow!("Encryption is not enabled"));
+    }
+
+    reencrypt().await
+}
+
+// Re-encrypts all device root and session keys using the active data-encryption key.
+// Keys which are stored in plaintext will be encrypted.
+pub async fn reencrypt() -> Result<()> {
+    info!(
+        dek_id = encryption::get_active_dek_id(),
+        "Encrypting device-keys"
+    );
+
+    let dev_euis = device_keys::get_dev_euis().await?;
+    for (i, dev_eui) in dev_euis.iter().enumerate() {
+        if i % 1000 == 0 {
+            info!(
+                count = i,
+                total_count = dev_euis.len(),
+                "Encrypting device-keys"
+            );
+        }
+
+        device_keys::reencrypt(*dev_eui).await?;
+    }
+
+    info!("Encrypting device-sessions");
+
+    let dev_euis: Vec<EUI64> = device::table
+        .select(device::dev_eui)
+        .filter(device::device_session.is_not_null())
+        .load(&mut get_async_db_conn().await?)
+        .await?;
+    for (i, dev_eui) in dev_euis.iter().enumerate() {
+        if i % 1000 == 0 {
+            info!(
+                count = i,
+                total_count = dev_euis.len(),
+                "Encrypting device-sessions"
+            );
+        }
+
+        storage::device::reencrypt_device_session(*dev_eui).await?;
+    }
+
+    info!("Encryption completed");
+    Ok(())
+}
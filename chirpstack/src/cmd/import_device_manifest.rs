@@ -0,0 +1,60 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use uuid::Uuid;
+
+use crate::manifest::{self, Format};
+use crate::storage;
+
+pub async fn run(
+    file: &Path,
+    format: Option<Format>,
+    application_id: Uuid,
+    device_profile_id: Uuid,
+    kek_label: &str,
+    dry_run: bool,
+) -> Result<()> {
+    storage::setup().await?;
+
+    let format = match format {
+        Some(v) => v,
+        None => file
+            .extension()
+            .and_then(|v| v.to_str())
+            .unwrap_or_default()
+            .parse()
+            .context("Detect manifest format from file extension")?,
+    };
+    let b = fs::read(file).context(format!("Read manifest: {}", file.display()))?;
+
+    let report = manifest::import(
+        application_id,
+        device_profile_id,
+        format,
+        &b,
+        kek_label,
+        dry_run,
+    )
+    .await?;
+
+    for item in &report.items {
+        if item.error.is_empty() {
+            println!("{:>5}: {}: OK", item.line, item.dev_eui);
+        } else {
+            println!("{:>5}: {}: {}", item.line, item.dev_eui, item.error);
+        }
+    }
+
+    if report.has_errors() {
+        return Err(anyhow!("Manifest contains errors, no devices imported"));
+    }
+
+    if report.imported {
+        println!("{} devices imported", report.device_count);
+    } else {
+        println!("{} devices validated (dry-run)", report.device_count);
+    }
+
+    Ok(())
+}
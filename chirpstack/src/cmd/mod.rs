@@ -1,9 +1,13 @@
 pub mod configfile;
 pub mod create_api_key;
+pub mod encrypt_device_keys;
+pub mod import_device_manifest;
 pub mod import_device_profiles;
 pub mod migrate_device_gateway_rx_info;
 pub mod migrate_device_profile_templates;
 pub mod migrate_ds_to_pg;
 pub mod print_ds;
 pub mod root;
+pub mod rotate_data_encryption_key;
 pub mod set_password;
+pub mod validate_regions;
@@ -0,0 +1,169 @@
+use std::cmp;
+use std::collections::HashSet;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use tokio::time::sleep;
+use tracing::{info, warn};
+
+use super::encrypt_device_keys;
+use crate::storage::{self, data_encryption_key, device_keys, get_async_db_conn, schema::device};
+use crate::{config, encryption};
+use lrwn::EUI64;
+
+// Margin added to the reload interval, to account for instances that are busy
+// reloading the data-encryption keys.
+const RELOAD_MARGIN: Duration = Duration::from_secs(10);
+
+pub async fn run(delete_retired: bool) -> Result<()> {
+    storage::setup().await?;
+
+    if !encryption::is_enabled() {
+        return Err(anyhow!("Encryption is not enabled"));
+    }
+
+    let dek_id = encryption::create_dek().await?;
+    info!(dek_id = dek_id, "Data-encryption key rotated");
+
+    // Other instances are only able to decrypt the keys encrypted using the new DEK
+    // and switch to the new DEK for encryption after they have re-loaded the DEKs.
+    // Wait for this before re-encrypting the keys, such that the re-encryption also
+    // covers the keys that were written using the previous DEK in the meantime.
+    let conf = config::get();
+    if conf.encryption.reload_interval.is_zero() {
+        warn!(
+            "Reload interval is disabled, make sure all instances have been restarted to load the new data-encryption key"
+        );
+    } else {
+        let wait = conf.encryption.reload_interval + RELOAD_MARGIN;
+        info!(wait = ?wait, "Waiting for other instances to re-load the data-encryption keys");
+        sleep(wait).await;
+    }
+
+    encrypt_device_keys::reencrypt().await?;
+
+    if delete_retired {
+        let deks = data_encryption_key::list().await?;
+        let in_use = get_dek_ids_in_use().await?;
+        for dek_id in get_deletable_dek_ids(&deks, &in_use, get_redis_retention(), Utc::now()) {
+            data_encryption_key::delete(dek_id).await?;
+        }
+    }
+
+    Ok(())
+}
+
+// Scans all device root and session keys for the data-encryption keys that are
+// referenced. This makes sure that a key is never deleted while it is still needed
+// for decryption, e.g. when a device was updated during the re-encryption.
+async fn get_dek_ids_in_use() -> Result<HashSet<i32>> {
+    let mut out = HashSet::new();
+
+    for dev_eui in device_keys::get_dev_euis().await? {
+        out.extend(device_keys::get_dek_ids(&dev_eui).await?);
+    }
+
+    let dev_euis: Vec<EUI64> = device::table
+        .select(device::dev_eui)
+        .filter(device::device_session.is_not_null())
+        .load(&mut get_async_db_conn().await?)
+        .await?;
+    for dev_eui in dev_euis {
+        out.extend(storage::device::get_device_session_dek_ids(&dev_eui).await?);
+    }
+
+    Ok(out)
+}
+
+// The Join Server and handover-roaming sessions stored in Redis are not scanned.
+// These are only valid for a limited time, after which they expire.
+fn get_redis_retention() -> Duration {
+    let conf = config::get();
+    cmp::max(
+        conf.network.device_session_ttl,
+        conf.backend_interfaces.join_server.session_key_lifetime,
+    )
+}
+
+// Returns the IDs of the retired DEKs that can be deleted. A DEK is retired once
+// its successor has been created. It can be deleted when it is not referenced by
+// any of the keys stored in the database and when the Redis sessions that might
+// have been encrypted using this DEK have expired.
+fn get_deletable_dek_ids(
+    deks: &[data_encryption_key::DataEncryptionKey],
+    in_use: &HashSet<i32>,
+    redis_retention: Duration,
+    now: DateTime<Utc>,
+) -> Vec<i32> {
+    let mut out = Vec::new();
+
+    for (i, dek) in deks.iter().enumerate() {
+        if dek.is_active || in_use.contains(&dek.id) {
+            info!(dek_id = dek.id, "Data-encryption key is still in use");
+            continue;
+        }
+
+        let retired_at: DateTime<Utc> = match deks.get(i + 1) {
+            Some(v) => v.created_at,
+            None => continue,
+        };
+
+        if now - retired_at < chrono::Duration::from_std(redis_retention).unwrap_or_default() {
+            info!(
+                dek_id = dek.id,
+                "Data-encryption key might still be in use by Redis sessions"
+            );
+            continue;
+        }
+
+        out.push(dek.id);
+    }
+
+    out
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_deletable_dek_ids() {
+        let now = Utc::now();
+        let dek = |id: i32, created_at: DateTime<Utc>, is_active: bool| {
+            data_encryption_key::DataEncryptionKey {
+                id,
+                created_at,
+                kek_label: "kek".into(),
+                wrapped_key: vec![],
+                is_active,
+            }
+        };
+
+        let deks = vec![
+            dek(1, now - chrono::Duration::days(10), false),
+            dek(2, now - chrono::Duration::days(5), false),
+            dek(3, now - chrono::Duration::days(2), false),
+            dek(4, now, true),
+        ];
+
+        // DEK 1 is in use, DEK 3 was retired within the Redis retention and DEK 4
+        // is the active DEK.
+        assert_eq!(
+            vec![2],
+            get_deletable_dek_ids(
+                &deks,
+                &[1].into_iter().collect(),
+                Duration::from_secs(60 * 60 * 24),
+                now
+            )
+        );
+
+        assert_eq!(
+            vec![1, 2, 3],
+            get_deletable_dek_ids(&deks, &HashSet::new(), Duration::ZERO, now)
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::config;
+use lrwn::region::{self, DataRateModulation, MacVersion, Revision};
+
+pub fn run(file: Option<&Path>) -> Result<()> {
+    let regions = match file {
+        Some(file) => {
+            let content = fs::read_to_string(file)
+                .context(format!("Read region file: {}", file.display()))?;
+            let conf: config::Configuration =
+                toml::from_str(&content).context("Parse region file")?;
+            conf.regions
+        }
+        None => config::get().regions.clone(),
+    };
+
+    if regions.is_empty() {
+        return Err(anyhow!("No regions found"));
+    }
+
+    let mut error_count = 0;
+
+    for r in &regions {
+        println!("Region: {} ({})", r.id, r.common_name);
+        if !r.description.is_empty() {
+            println!("  Description: {}", r.description);
+        }
+
+        let errors = validate_region(r);
+        if errors.is_empty() {
+            println!("  Uplink channels:");
+            let region_conf = get_region_conf(r)?;
+            for i in region_conf.get_uplink_channel_indices() {
+                let c = region_conf.get_uplink_channel(i)?;
+                println!(
+                    "    {:>2}: {} Hz, data-rates: {:?}, enabled: {}",
+                    i, c.frequency, c.data_rates, c.enabled
+                );
+            }
+            println!("  OK");
+        } else {
+            for e in &errors {
+                println!("  ERROR: {}", e);
+            }
+        }
+
+        error_count += errors.len();
+    }
+
+    if error_count != 0 {
+        return Err(anyhow!(
+            "Region validation failed with {} error(s)",
+            error_count
+        ));
+    }
+
+    Ok(())
+}
+
+// Validates the region configuration against the Regional Parameters and returns
+// the validation errors.
+pub fn validate_region(r: &config::Region) -> Vec<String> {
+    let mut errors: Vec<String> = Vec::new();
+
+    let params = match region::get_regional_parameters(
+        r.common_name,
+        MacVersion::Latest,
+        Revision::Latest,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            errors.push(e.to_string());
+            return errors;
+        }
+    };
+
+    let region_conf = region::get(
+        r.common_name,
+        r.network.repeater_compatible,
+        r.network.dwell_time_400ms,
+    );
+
+    if !r.network.extra_channels.is_empty() && !params.user_channels {
+        errors.push(format!("{} does not allow extra channels", r.common_name));
+    }
+
+    if params.default_channels + r.network.extra_channels.len() > params.max_channels {
+        errors.push(format!(
+            "Number of channels ({}) exceeds the max. number of channels ({})",
+            params.default_channels + r.network.extra_channels.len(),
+            params.max_channels
+        ));
+    }
+
+    for ec in &r.network.extra_channels {
+        let data_rates: Vec<u8> = if ec.data_rates.is_empty() {
+            (ec.min_dr..=ec.max_dr).collect()
+        } else {
+            ec.data_rates.clone()
+        };
+
+        let mut bandwidth = 0;
+        for dr in &data_rates {
+            match region_conf.get_data_rate(true, *dr) {
+                Ok(v) => bandwidth = bandwidth.max(get_bandwidth(&v)),
+                Err(_) => errors.push(format!(
+                    "Extra channel {} Hz has invalid uplink data-rate {}",
+                    ec.frequency, dr
+                )),
+            }
+        }
+
+        if !params.frequency_in_range(ec.frequency, bandwidth) {
+            errors.push(format!(
+                "Extra channel {} Hz is outside the allowed frequency range",
+                ec.frequency
+            ));
+        }
+    }
+
+    match region_conf.get_data_rate(false, r.network.rx2_dr) {
+        Ok(dr) => {
+            if r.network.rx2_frequency != 0
+                && !params.frequency_in_range(r.network.rx2_frequency, get_bandwidth(&dr))
+            {
+                errors.push(format!(
+                    "RX2 frequency {} Hz is outside the allowed frequency range",
+                    r.network.rx2_frequency
+                ));
+            }
+        }
+        Err(_) => errors.push(format!(
+            "RX2 data-rate {} is not a valid downlink data-rate",
+            r.network.rx2_dr
+        )),
+    }
+
+    if r.network.class_b.ping_slot_frequency != 0
+        && !params.frequency_in_range(r.network.class_b.ping_slot_frequency, 0)
+    {
+        errors.push(format!(
+            "Class-B ping-slot frequency {} Hz is outside the allowed frequency range",
+            r.network.class_b.ping_slot_frequency
+        ));
+    }
+
+    if r.network.uplink_max_eirp > params.max_eirp {
+        errors.push(format!(
+            "Uplink max. EIRP {} dBm exceeds the regional max. EIRP {} dBm",
+            r.network.uplink_max_eirp, params.max_eirp
+        ));
+    }
+
+    let channel_count = params.default_channels + r.network.extra_channels.len();
+    for i in &r.network.enabled_uplink_channels {
+        if *i >= channel_count {
+            errors.push(format!("Enabled uplink channel {} does not exist", i));
+        }
+    }
+
+    for c in &r.gateway.channels {
+        if !params.frequency_in_range(c.frequency, c.bandwidth) {
+            errors.push(format!(
+                "Gateway channel {} Hz is outside the allowed frequency range",
+                c.frequency
+            ));
+        }
+    }
+
+    errors
+}
+
+fn get_region_conf(r: &config::Region) -> Result<Box<dyn region::Region + Sync + Send>> {
+    let mut region_conf = region::get(
+        r.common_name,
+        r.network.repeater_compatible,
+        r.network.dwell_time_400ms,
+    );
+
+    for ec in &r.network.extra_channels {
+        let data_rates: Vec<u8> = if ec.data_rates.is_empty() {
+            (ec.min_dr..=ec.max_dr).collect()
+        } else {
+            ec.data_rates.clone()
+        };
+
+        region_conf
+            .add_channel(ec.frequency, data_rates)
+            .context("Add channel")?;
+    }
+
+    if !r.network.enabled_uplink_channels.is_empty() {
+        for i in region_conf.get_enabled_uplink_channel_indices() {
+            region_conf.disable_uplink_channel_index(i)?;
+        }
+
+        for i in &r.network.enabled_uplink_channels {
+            region_conf.enable_uplink_channel_index(*i)?;
+        }
+    }
+
+    Ok(region_conf)
+}
+
+fn get_bandwidth(dr: &DataRateModulation) -> u32 {
+    match dr {
+        DataRateModulation::Lora(v) => v.bandwidth,
+        DataRateModulation::Fsk(_) => 0,
+        DataRateModulation::LrFhss(v) => v.occupied_channel_width,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lrwn::region::CommonName;
+
+    fn eu868_region() -> config::Region {
+        config::Region {
+            id: "eu868".into(),
+            common_name: CommonName::EU868,
+            network: config::RegionNetwork {
+                rx2_frequency: 869525000,
+                uplink_max_eirp: 16.0,
+                extra_channels: vec![
+                    config::ExtraChannel {
+                        frequency: 867100000,
+                        data_rates: vec![0, 1, 2, 3, 4, 5],
+                        ..Default::default()
+                    },
+                    config::ExtraChannel {
+                        frequency: 867300000,
+                        data_rates: vec![0, 1, 2, 3, 4, 5],
+                        ..Default::default()
+                    },
+                ],
+                class_b: config::ClassB {
+                    ping_slot_dr: 3,
+                    ping_slot_frequency: 869525000,
+                },
+                ..Default::default()
+            },
+            gateway: config::RegionGateway {
+                channels: vec![config::GatewayChannel {
+                    frequency: 868100000,
+                    bandwidth: 125000,
+                    modulation: config::GatewayChannelModulation::LORA,
+                    spreading_factors: vec![7, 8, 9, 10, 11, 12],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_region() {
+        let r = eu868_region();
+        assert!(validate_region(&r).is_empty());
+
+        let mut r = eu868_region();
+        r.network.rx2_frequency = 871000000;
+        r.network.uplink_max_eirp = 20.0;
+        r.network.extra_channels[0].frequency = 862000000;
+        r.network.extra_channels[1].data_rates = vec![0, 16];
+        r.network.enabled_uplink_channels = vec![0, 1, 2, 10];
+        assert_eq!(
+            vec![
+                "Extra channel 862000000 Hz is outside the allowed frequency range".to_string(),
+                "Extra channel 867300000 Hz has invalid uplink data-rate 16".to_string(),
+                "RX2 frequency 871000000 Hz is outside the allowed frequency range".to_string(),
+                "Uplink max. EIRP 20 dBm exceeds the regional max. EIRP 16 dBm".to_string(),
+                "Enabled uplink channel 10 does not exist".to_string(),
+            ],
+            validate_region(&r)
+        );
+
+        let mut r = eu868_region();
+        r.common_name = CommonName::US915;
+        r.network.rx2_dr = 8;
+        r.network.rx2_frequency = 923300000;
+        r.network.uplink_max_eirp = 30.0;
+        r.network.class_b.ping_slot_frequency = 0;
+        r.gateway.channels[0].frequency = 902300000;
+        r.network.extra_channels[0].frequency = 903000000;
+        r.network.extra_channels.truncate(1);
+        r.network.extra_channels[0].data_rates = vec![0];
+        assert_eq!(
+            vec![
+                "US915 does not allow extra channels".to_string(),
+                "Number of channels (73) exceeds the max. number of channels (72)".to_string(),
+            ],
+            validate_region(&r)
+        );
+    }
+}
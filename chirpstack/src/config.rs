@@ -30,7 +30,10 @@ pub struct Configuration {
     pub join_server: JoinServer,
     pub backend_interfaces: BackendInterfaces,
     pub roaming: Roaming,
+    pub dns: Dns,
     pub keks: Vec<Kek>,
+    pub manifest_keks: Vec<Kek>,
+    pub encryption: Encryption,
     pub regions: Vec<Region>,
     pub ui: UI,
 }
@@ -164,6 +167,7 @@ pub struct Network {
     pub net_id: NetID,
     pub secondary_net_ids: Vec<NetID>,
     pub dev_addr_prefixes: Vec<DevAddrPrefix>,
+    pub dev_addr_allocation: DevAddrAllocation,
     pub enabled_regions: Vec<String>,
     #[serde(with = "humantime_serde")]
     pub device_session_ttl: Duration,
@@ -183,6 +187,7 @@ impl Default for Network {
             net_id: NetID::from_be_bytes([0x00, 0x00, 0x00]),
             secondary_net_ids: vec![],
             dev_addr_prefixes: vec![],
+            dev_addr_allocation: DevAddrAllocation::Random,
             enabled_regions: vec![],
             device_session_ttl: Duration::from_secs(60 * 60 * 24 * 31),
             deduplication_delay: Duration::from_millis(200),
@@ -205,6 +210,13 @@ impl Network {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DevAddrAllocation {
+    Random,
+    Sequential,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(default)]
 pub struct Scheduler {
@@ -246,6 +258,10 @@ pub struct Monitoring {
     pub bind: String,
     pub api_request_log_max_history: usize,
     pub backend_interfaces_log_max_history: usize,
+    #[serde(with = "humantime_serde")]
+    pub backend_interfaces_log_retention: Duration,
+    #[serde(with = "humantime_serde")]
+    pub backend_interfaces_log_replay_max_age: Duration,
     pub meta_log_max_history: usize,
     pub gateway_frame_log_max_history: usize,
     pub device_frame_log_max_history: usize,
@@ -267,6 +283,8 @@ impl Default for Monitoring {
             bind: "".to_string(),
             api_request_log_max_history: 10,
             backend_interfaces_log_max_history: 10,
+            backend_interfaces_log_retention: Duration::ZERO,
+            backend_interfaces_log_replay_max_age: Duration::from_secs(60 * 60),
             meta_log_max_history: 10,
             gateway_frame_log_max_history: 10,
             device_frame_log_max_history: 10,
@@ -540,14 +558,29 @@ impl Default for OAuth2 {
     }
 }
 
-#[derive(Serialize, Deserialize, Default, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(default)]
 pub struct JoinServer {
     pub resolve_join_eui_domain_suffix: String,
+    pub resolve_join_eui_dns: bool,
+    #[serde(with = "humantime_serde")]
+    pub reload_interval: Duration,
     pub servers: Vec<JoinServerServer>,
     pub default: JoinServerServerDefault,
 }
 
+impl Default for JoinServer {
+    fn default() -> Self {
+        JoinServer {
+            resolve_join_eui_domain_suffix: "".into(),
+            resolve_join_eui_dns: false,
+            reload_interval: Duration::from_secs(60),
+            servers: Vec::new(),
+            default: JoinServerServerDefault::default(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Default, Clone)]
 #[serde(default)]
 pub struct JoinServerServer {
@@ -575,14 +608,49 @@ pub struct JoinServerServerDefault {
     pub authorization_header: String,
 }
 
-#[derive(Serialize, Deserialize, Default, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(default)]
 pub struct Roaming {
     pub resolve_net_id_domain_suffix: String,
+    pub resolve_net_id_dns: bool,
+    #[serde(with = "humantime_serde")]
+    pub reload_interval: Duration,
     pub servers: Vec<RoamingServer>,
     pub default: RoamingServerDefault,
 }
 
+impl Default for Roaming {
+    fn default() -> Self {
+        Roaming {
+            resolve_net_id_domain_suffix: "".into(),
+            resolve_net_id_dns: false,
+            reload_interval: Duration::from_secs(60),
+            servers: Vec::new(),
+            default: RoamingServerDefault::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Dns {
+    pub nameservers: Vec<String>,
+    #[serde(with = "humantime_serde")]
+    pub max_cache_ttl: Duration,
+    #[serde(with = "humantime_serde")]
+    pub negative_cache_ttl: Duration,
+}
+
+impl Default for Dns {
+    fn default() -> Self {
+        Dns {
+            nameservers: Vec::new(),
+            max_cache_ttl: Duration::from_secs(60 * 60),
+            negative_cache_ttl: Duration::from_secs(60 * 5),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Default, Clone)]
 #[serde(default)]
 pub struct BackendInterfaces {
@@ -590,6 +658,36 @@ pub struct BackendInterfaces {
     pub ca_cert: String,
     pub tls_cert: String,
     pub tls_key: String,
+    pub join_server: BackendInterfacesJoinServer,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct BackendInterfacesJoinServer {
+    pub enabled: bool,
+    pub app_s_key_kek_label: String,
+    #[serde(with = "humantime_serde")]
+    pub session_key_lifetime: Duration,
+    pub allowed: Vec<BackendInterfacesJoinServerAllowed>,
+}
+
+impl Default for BackendInterfacesJoinServer {
+    fn default() -> Self {
+        BackendInterfacesJoinServer {
+            enabled: false,
+            app_s_key_kek_label: "".into(),
+            session_key_lifetime: Duration::from_secs(60 * 60 * 24),
+            allowed: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct BackendInterfacesJoinServerAllowed {
+    pub net_id: NetID,
+    pub join_eui_prefixes: Vec<EUI64Prefix>,
+    pub kek_label: String,
 }
 
 #[derive(Serialize, Deserialize, Default, Clone)]
@@ -603,6 +701,10 @@ pub struct RoamingServer {
     pub passive_roaming_lifetime: Duration,
     pub passive_roaming_kek_label: String,
     pub passive_roaming_validate_mic: bool,
+    pub handover_roaming: bool,
+    #[serde(with = "humantime_serde")]
+    pub handover_roaming_lifetime: Duration,
+    pub handover_roaming_kek_label: String,
     pub server: String,
     pub use_target_role_suffix: bool,
     pub ca_cert: String,
@@ -621,6 +723,10 @@ pub struct RoamingServerDefault {
     pub passive_roaming_lifetime: Duration,
     pub passive_roaming_kek_label: String,
     pub passive_roaming_validate_mic: bool,
+    pub handover_roaming: bool,
+    #[serde(with = "humantime_serde")]
+    pub handover_roaming_lifetime: Duration,
+    pub handover_roaming_kek_label: String,
     pub server: String,
     pub use_target_role_suffix: bool,
     pub ca_cert: String,
@@ -636,6 +742,42 @@ pub struct Kek {
     pub kek: AES128Key,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Encryption {
+    pub enabled: bool,
+    pub kek_label: String,
+    #[serde(with = "humantime_serde")]
+    pub reload_interval: Duration,
+    pub keks: Vec<EncryptionKek>,
+}
+
+impl Default for Encryption {
+    fn default() -> Self {
+        Encryption {
+            enabled: false,
+            kek_label: "".into(),
+            reload_interval: Duration::from_secs(60),
+            keks: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct EncryptionKek {
+    pub label: String,
+    pub provider: String,
+    pub kek_file: String,
+    pub server: String,
+    pub key_name: String,
+    pub token_header: String,
+    pub token: String,
+    pub library: String,
+    pub token_label: String,
+    pub pin: String,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(default)]
 pub struct Region {
@@ -1,8 +1,23 @@
-use lrwn::DevAddr;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use lrwn::{DevAddr, DevAddrPrefix};
 use rand::Rng;
 use rand::seq::IndexedRandom;
+use tracing::warn;
+
+use crate::config;
+use crate::storage::{device, get_async_redis_conn, redis_key};
+
+// Number of random DevAddr candidates of which the usage is checked when allocating
+// a random DevAddr.
+const RANDOM_CANDIDATES: usize = 16;
+
+// Duration for which a sequentially allocated DevAddr is reserved. This must cover
+// the time between the allocation and storing the DevAddr of the device.
+const RESERVATION_TTL: Duration = Duration::from_secs(60);
 
-pub fn get_random_dev_addr(prefixes: &[lrwn::DevAddrPrefix]) -> DevAddr {
+pub fn get_random_dev_addr(prefixes: &[DevAddrPrefix]) -> DevAddr {
     let mut rng = rand::rng();
 
     // Pick a random one (in case multiple prefixes are configured).
@@ -21,3 +36,227 @@ pub fn get_random_dev_addr(prefixes: &[lrwn::DevAddrPrefix]) -> DevAddr {
     dev_addr.set_dev_addr_prefix(prefix);
     dev_addr
 }
+
+// Allocates a DevAddr within the given prefixes. Depending on the configured
+// allocation mode, the DevAddr is allocated randomly or sequentially. In both cases
+// DevAddrs that are not (or least) used by other devices are preferred, as each
+// device sharing the same DevAddr must be tried on uplink.
+pub async fn allocate_dev_addr(prefixes: &[DevAddrPrefix]) -> Result<DevAddr> {
+    let conf = config::get();
+
+    if conf.network.dev_addr_allocation == config::DevAddrAllocation::Sequential {
+        for prefix in prefixes {
+            if let Some(dev_addr) = get_next_sequential_dev_addr(*prefix).await? {
+                return Ok(dev_addr);
+            }
+        }
+
+        warn!(
+            dev_addr_prefixes = ?prefixes.iter().map(|p| p.to_string()).collect::<Vec<String>>(),
+            "All DevAddrs of the DevAddr prefixes are in use, falling back to random DevAddr allocation"
+        );
+    }
+
+    get_least_used_random_dev_addr(prefixes).await
+}
+
+// Returns the lowest DevAddr of the given prefix which is not in use and which could
+// be reserved. This returns None when all DevAddrs of the prefix are in use.
+async fn get_next_sequential_dev_addr(prefix: DevAddrPrefix) -> Result<Option<DevAddr>> {
+    let last = dev_addr_to_u64(prefix.last_dev_addr());
+    let mut from = dev_addr_to_u64(prefix.first_dev_addr());
+
+    while from <= last {
+        let dev_addr = match get_first_free_dev_addr(from, last).await? {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        // The DevAddr might have been allocated by a concurrent join, which has not
+        // yet been persisted.
+        if reserve_dev_addr(dev_addr).await? {
+            return Ok(Some(dev_addr));
+        }
+
+        from = dev_addr_to_u64(dev_addr) + 1;
+    }
+
+    Ok(None)
+}
+
+// Returns the first DevAddr within the given range which is not in use. As the
+// range [from, x] is fully in use when it contains x - from + 1 distinct DevAddrs,
+// the first free DevAddr is found by a binary search using counts, rather than
+// loading all the DevAddrs in use.
+async fn get_first_free_dev_addr(from: u64, last: u64) -> Result<Option<DevAddr>> {
+    let in_use = |to: u64| async move {
+        let count =
+            device::get_dev_addr_count(dev_addr_from_u64(from), dev_addr_from_u64(to)).await?;
+        Ok::<bool, anyhow::Error>(count as u64 == to - from + 1)
+    };
+
+    if in_use(last).await? {
+        return Ok(None);
+    }
+
+    let (mut lo, mut hi) = (from, last);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if in_use(mid).await? {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    Ok(Some(dev_addr_from_u64(lo)))
+}
+
+// Reserves the given DevAddr for the duration of the join, such that it is not
+// allocated twice by concurrent joins. This returns false if the DevAddr is
+// already reserved.
+async fn reserve_dev_addr(dev_addr: DevAddr) -> Result<bool> {
+    let key = redis_key(format!("devaddr:{{{}}}:reserved", dev_addr));
+    let set: bool = redis::cmd("SET")
+        .arg(key)
+        .arg("reserved")
+        .arg("PX")
+        .arg(RESERVATION_TTL.as_millis() as usize)
+        .arg("NX")
+        .query_async(&mut get_async_redis_conn().await?)
+        .await
+        .context("Reserve DevAddr")?;
+
+    Ok(set)
+}
+
+fn dev_addr_to_u64(dev_addr: DevAddr) -> u64 {
+    u32::from_be_bytes(dev_addr.to_be_bytes()).into()
+}
+
+fn dev_addr_from_u64(v: u64) -> DevAddr {
+    DevAddr::from_be_bytes((v as u32).to_be_bytes())
+}
+
+// Returns the first unused DevAddr out of a set of random candidates. In case all
+// candidates are in use, the least used candidate is returned.
+async fn get_least_used_random_dev_addr(prefixes: &[DevAddrPrefix]) -> Result<DevAddr> {
+    let candidates: Vec<DevAddr> = (0..RANDOM_CANDIDATES)
+        .map(|_| get_random_dev_addr(prefixes))
+        .collect();
+    let usage = device::get_dev_addr_usage(&candidates).await?;
+
+    candidates
+        .into_iter()
+        .min_by_key(|c| {
+            usage
+                .iter()
+                .find(|u| u.dev_addr == Some(*c))
+                .map(|u| u.count)
+                .unwrap_or_default()
+        })
+        .ok_or_else(|| anyhow!("No DevAddr candidates"))
+}
+
+#[cfg(test)]
+pub mod test {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::storage::{self, device::Device};
+    use crate::test;
+    use lrwn::EUI64;
+
+    #[tokio::test]
+    async fn test_allocate_dev_addr() {
+        let _guard = test::prepare().await;
+
+        let prefix = DevAddrPrefix::from_str("01020300/24").unwrap();
+        let dp = storage::device_profile::test::create_device_profile(None).await;
+        let app =
+            storage::application::test::create_application(dp.tenant_id.map(|v| v.into())).await;
+
+        // Random: the test DevAddr is always 01020304.
+        assert_eq!(
+            DevAddr::from_be_bytes([1, 2, 3, 4]),
+            allocate_dev_addr(&[prefix]).await.unwrap()
+        );
+
+        // Sequential
+        let mut conf = (*config::get()).clone();
+        conf.network.dev_addr_allocation = config::DevAddrAllocation::Sequential;
+        config::set(conf);
+
+        assert_eq!(
+            DevAddr::from_be_bytes([1, 2, 3, 0]),
+            allocate_dev_addr(&[prefix]).await.unwrap()
+        );
+
+        // Sequential, the previous DevAddr is reserved.
+        assert_eq!(
+            DevAddr::from_be_bytes([1, 2, 3, 1]),
+            allocate_dev_addr(&[prefix]).await.unwrap()
+        );
+
+        for (dev_eui, dev_addr) in [
+            ([1, 1, 1, 1, 1, 1, 1, 1], [1, 2, 3, 0]),
+            ([2, 2, 2, 2, 2, 2, 2, 2], [1, 2, 3, 1]),
+        ] {
+            device::create(Device {
+                name: "test-dev".into(),
+                dev_eui: EUI64::from_be_bytes(dev_eui),
+                application_id: app.id,
+                device_profile_id: dp.id,
+                dev_addr: Some(DevAddr::from_be_bytes(dev_addr)),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(
+            DevAddr::from_be_bytes([1, 2, 3, 2]),
+            allocate_dev_addr(&[prefix]).await.unwrap()
+        );
+
+        // Sequential, gaps: the first gap which is not reserved is returned.
+        device::create(Device {
+            name: "test-dev".into(),
+            dev_eui: EUI64::from_be_bytes([3, 3, 3, 3, 3, 3, 3, 3]),
+            application_id: app.id,
+            device_profile_id: dp.id,
+            dev_addr: Some(DevAddr::from_be_bytes([1, 2, 3, 255])),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            DevAddr::from_be_bytes([1, 2, 3, 3]),
+            allocate_dev_addr(&[prefix]).await.unwrap()
+        );
+
+        // Sequential, all DevAddrs of the prefix in use: fall back to random.
+        let prefix = DevAddrPrefix::from_str("01020400/31").unwrap();
+        for (dev_eui, dev_addr) in [
+            ([4, 4, 4, 4, 4, 4, 4, 4], [1, 2, 4, 0]),
+            ([5, 5, 5, 5, 5, 5, 5, 5], [1, 2, 4, 1]),
+        ] {
+            device::create(Device {
+                name: "test-dev".into(),
+                dev_eui: EUI64::from_be_bytes(dev_eui),
+                application_id: app.id,
+                device_profile_id: dp.id,
+                dev_addr: Some(DevAddr::from_be_bytes(dev_addr)),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(
+            DevAddr::from_be_bytes([1, 2, 4, 0]),
+            allocate_dev_addr(&[prefix]).await.unwrap()
+        );
+    }
+}
@@ -1099,6 +1099,10 @@ impl Data {
         let client = roaming::get(&net_id).await?;
 
         let mut req = backend::XmitDataReqPayload {
+            base: backend::BasePayload {
+                sender_id: self.tenant.get_net_id().to_vec(),
+                ..Default::default()
+            },
             phy_payload: self.downlink_frame.items[0].phy_payload.clone(),
             dl_meta_data: Some(backend::DLMetaData {
                 class_mode: Some("A".to_string()),
@@ -1149,6 +1153,17 @@ impl Data {
             .xmit_data_req(backend::Role::FNS, &mut req, async_receiver)
             .await?;
 
+        if let Some(dl_meta_data) = &req.dl_meta_data {
+            roaming::record_downlink(
+                net_id,
+                backend::Role::SNS,
+                &req.phy_payload,
+                dl_meta_data,
+                Some(roaming_meta.ul_meta_data.recv_time),
+            )
+            .await;
+        }
+
         Ok(())
     }
 
@@ -1523,10 +1538,10 @@ impl Data {
         trace!("Setting tx parameters");
         let ds = self.device.get_device_session_mut()?;
 
-        if !self
-            .region_conf
-            .implements_tx_param_setup(ds.mac_version().from_proto())
-        {
+        if !self.region_conf.implements_tx_param_setup(
+            ds.mac_version().from_proto(),
+            self.device_profile.reg_params_revision,
+        ) {
             return Ok(());
         }
 
@@ -4155,6 +4170,58 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_set_tx_parameters() {
+        let network_conf = config::RegionNetwork {
+            uplink_dwell_time_400ms: true,
+            downlink_dwell_time_400ms: true,
+            ..Default::default()
+        };
+
+        // AS923-2 was introduced by RP002-1.0.1, but the device-profile defaults to
+        // revision A.
+        let mut ctx = Data {
+            relay_context: None,
+            uplink_frame_set: None,
+            tenant: tenant::Tenant::default(),
+            application: application::Application::default(),
+            device_profile: device_profile::DeviceProfile {
+                reg_params_revision: lrwn::region::Revision::A,
+                ..Default::default()
+            },
+            device: device::Device {
+                device_session: Some(internal::DeviceSession::default().into()),
+                ..Default::default()
+            },
+            network_conf: network_conf.clone(),
+            region_conf: Arc::new(Box::new(lrwn::region::as923::Configuration::new(
+                lrwn::region::CommonName::AS923_2,
+                false,
+                false,
+            ))),
+            must_send: false,
+            must_ack: false,
+            mac_commands: vec![],
+            downlink_gateway: None,
+            downlink_frame: Default::default(),
+            downlink_frame_items: vec![],
+            immediately: false,
+            device_queue_item: None,
+            more_device_queue_items: false,
+        };
+
+        ctx._set_tx_parameters().await.unwrap();
+
+        assert_eq!(
+            vec![maccommand::tx_param_setup::request(
+                true,
+                true,
+                lrwn::get_tx_param_setup_eirp_index(network_conf.uplink_max_eirp),
+            )],
+            ctx.mac_commands
+        );
+    }
+
     #[tokio::test]
     async fn test_update_end_device_conf() {
         struct Test {
@@ -8,6 +8,7 @@ mod helpers;
 pub mod join;
 pub mod multicast;
 pub mod roaming;
+pub mod schedule;
 pub mod scheduler;
 pub mod tx_ack;
 
@@ -21,4 +22,9 @@ pub async fn setup() {
     tokio::spawn(async move {
         scheduler::multicast_group_queue_scheduler_loop().await;
     });
+
+    info!("Setting up downlink schedule scheduler loop");
+    tokio::spawn(async move {
+        scheduler::downlink_schedule_scheduler_loop().await;
+    });
 }
@@ -7,10 +7,14 @@ use petgraph::data::FromElements;
 use petgraph::graph::{DefaultIx, Graph, NodeIndex, UnGraph};
 use rand::RngExt;
 use tracing::{Instrument, Level, span, trace, warn};
+use uuid::Uuid;
 
 use crate::downlink::{error::Error, helpers};
 use crate::gateway::backend as gateway_backend;
-use crate::storage::{device, downlink_frame, gateway, multicast};
+use crate::storage::{
+    device::{self, DeviceClass},
+    downlink_frame, gateway, multicast,
+};
 use crate::{config, region};
 use chirpstack_api::{gw, internal};
 use lrwn::EUI64;
@@ -21,6 +25,16 @@ enum Node {
     Device(EUI64),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceCoverage {
+    pub dev_eui: EUI64,
+    // Gateways of the multicast gateway-set which have received the device.
+    pub gateway_ids: Vec<EUI64>,
+    // Device is operating as Class-B device (only set for Class-B multicast-groups).
+    pub class_b_enabled: bool,
+    pub covered: bool,
+}
+
 pub struct Multicast {
     multicast_group_queue_item: multicast::MulticastGroupQueueItem,
     downlink_frame: gw::DownlinkFrame,
@@ -259,24 +273,99 @@ impl Multicast {
 }
 
 pub async fn enqueue(qi: multicast::MulticastGroupQueueItem) -> Result<u32> {
+    let mg = multicast::get(&qi.multicast_group_id).await?;
+    let gateway_ids = get_gateway_ids(&mg).await?;
+
+    // Enqueue multicast downlink for the given gw set.
+    let (_, f_cnt) = multicast::enqueue(qi, &gateway_ids).await?;
+    Ok(f_cnt)
+}
+
+/// Returns for each device of the multicast-group if it is covered by the gateway-set that is
+/// used for sending the multicast downlinks.
+pub async fn get_coverage(multicast_group_id: &Uuid) -> Result<Vec<DeviceCoverage>> {
+    let mg = multicast::get(multicast_group_id).await?;
+    let gateway_ids = get_gateway_ids(&mg).await?;
+    let dev_euis = multicast::get_dev_euis(multicast_group_id).await?;
+    let class_b_dev_euis: HashSet<EUI64> = if mg.group_type == "B" {
+        multicast::get_dev_euis_for_enabled_class(multicast_group_id, DeviceClass::B)
+            .await?
+            .into_iter()
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    let dev_gw_history = device::get_gateway_history_for_dev_euis(&dev_euis).await?;
+
+    get_device_coverage(
+        mg.group_type == "B",
+        &dev_euis,
+        &class_b_dev_euis,
+        &dev_gw_history,
+        &gateway_ids,
+    )
+}
+
+async fn get_gateway_ids(mg: &multicast::MulticastGroup) -> Result<Vec<EUI64>> {
     // Try first to get configured gateways for multicast-group.
-    let mut gateway_ids = multicast::get_gateway_ids(&qi.multicast_group_id).await?;
+    let gateway_ids = multicast::get_gateway_ids(&mg.id).await?;
+    if !gateway_ids.is_empty() {
+        return Ok(gateway_ids);
+    }
 
     // Fallback to automatic gateway-set detection.
-    if gateway_ids.is_empty() {
-        // get deveuis for multicast-group
-        let dev_euis = multicast::get_dev_euis(&qi.multicast_group_id).await?;
+    // In case of Class-B, only the devices that are beacon locked (thus operating as Class-B
+    // device) are able to receive the downlink in the ping-slot. Other devices are not taken
+    // into account for selecting the gateway-set.
+    let dev_euis = if mg.group_type == "B" {
+        multicast::get_dev_euis_for_enabled_class(&mg.id, DeviceClass::B).await?
+    } else {
+        multicast::get_dev_euis(&mg.id).await?
+    };
+
+    // get gateway history
+    let dev_gw_history = device::get_gateway_history_for_dev_euis(&dev_euis).await?;
+
+    // get minimum gateway set to cover all devices
+    get_minimum_gateway_set(&dev_gw_history)
+}
+
+fn get_device_coverage(
+    class_b: bool,
+    dev_euis: &[EUI64],
+    class_b_dev_euis: &HashSet<EUI64>,
+    dev_gw_history: &HashMap<EUI64, Vec<internal::GatewayRxInfoHistory>>,
+    gateway_ids: &[EUI64],
+) -> Result<Vec<DeviceCoverage>> {
+    let gateway_ids: HashSet<EUI64> = gateway_ids.iter().cloned().collect();
+    let mut out: Vec<DeviceCoverage> = Vec::with_capacity(dev_euis.len());
+
+    for dev_eui in dev_euis {
+        let mut dev_gateway_ids: Vec<EUI64> = Vec::new();
+        for history in dev_gw_history
+            .get(dev_eui)
+            .map(|v| v.as_slice())
+            .unwrap_or(&[])
+        {
+            for i in &history.items {
+                let gateway_id = EUI64::from_slice(&i.gateway_id)?;
+                if gateway_ids.contains(&gateway_id) && !dev_gateway_ids.contains(&gateway_id) {
+                    dev_gateway_ids.push(gateway_id);
+                }
+            }
+        }
 
-        // get gateway history
-        let dev_gw_history = device::get_gateway_history_for_dev_euis(&dev_euis).await?;
+        let class_b_enabled = class_b_dev_euis.contains(dev_eui);
 
-        // get minimum gateway set to cover all devices
-        gateway_ids = get_minimum_gateway_set(&dev_gw_history)?;
+        out.push(DeviceCoverage {
+            dev_eui: *dev_eui,
+            covered: !dev_gateway_ids.is_empty() && (!class_b || class_b_enabled),
+            gateway_ids: dev_gateway_ids,
+            class_b_enabled,
+        });
     }
 
-    // Enqueue multicast downlink for the given gw set.
-    let (_, f_cnt) = multicast::enqueue(qi, &gateway_ids).await?;
-    Ok(f_cnt)
+    Ok(out)
 }
 
 fn get_minimum_gateway_set(
@@ -527,4 +616,104 @@ pub mod test {
             assert_eq!(expected, gws);
         }
     }
+
+    #[test]
+    fn test_get_device_coverage() {
+        let dev_1 = EUI64::from_be_bytes([1, 1, 1, 1, 1, 1, 1, 1]);
+        let dev_2 = EUI64::from_be_bytes([1, 1, 1, 1, 1, 1, 1, 2]);
+        let dev_3 = EUI64::from_be_bytes([1, 1, 1, 1, 1, 1, 1, 3]);
+        let gw_1 = EUI64::from_be_bytes([2, 2, 2, 2, 2, 2, 2, 1]);
+        let gw_2 = EUI64::from_be_bytes([2, 2, 2, 2, 2, 2, 2, 2]);
+
+        let dev_gw_history: HashMap<EUI64, Vec<internal::GatewayRxInfoHistory>> = [
+            (
+                dev_1,
+                vec![internal::GatewayRxInfoHistory {
+                    items: vec![
+                        internal::GatewayRxInfoHistoryItem {
+                            gateway_id: gw_1.to_vec(),
+                            ..Default::default()
+                        },
+                        internal::GatewayRxInfoHistoryItem {
+                            gateway_id: gw_2.to_vec(),
+                            ..Default::default()
+                        },
+                    ],
+                    ..Default::default()
+                }],
+            ),
+            (
+                dev_2,
+                vec![internal::GatewayRxInfoHistory {
+                    items: vec![internal::GatewayRxInfoHistoryItem {
+                        gateway_id: gw_2.to_vec(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        // Class-C, gw_2 is not part of the gateway-set.
+        let coverage = get_device_coverage(
+            false,
+            &[dev_1, dev_2, dev_3],
+            &HashSet::new(),
+            &dev_gw_history,
+            &[gw_1],
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                DeviceCoverage {
+                    dev_eui: dev_1,
+                    gateway_ids: vec![gw_1],
+                    class_b_enabled: false,
+                    covered: true,
+                },
+                DeviceCoverage {
+                    dev_eui: dev_2,
+                    gateway_ids: vec![],
+                    class_b_enabled: false,
+                    covered: false,
+                },
+                DeviceCoverage {
+                    dev_eui: dev_3,
+                    gateway_ids: vec![],
+                    class_b_enabled: false,
+                    covered: false,
+                },
+            ],
+            coverage
+        );
+
+        // Class-B, dev_1 is not beacon locked.
+        let coverage = get_device_coverage(
+            true,
+            &[dev_1, dev_2],
+            &[dev_2].into_iter().collect(),
+            &dev_gw_history,
+            &[gw_1, gw_2],
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                DeviceCoverage {
+                    dev_eui: dev_1,
+                    gateway_ids: vec![gw_1, gw_2],
+                    class_b_enabled: false,
+                    covered: false,
+                },
+                DeviceCoverage {
+                    dev_eui: dev_2,
+                    gateway_ids: vec![gw_2],
+                    class_b_enabled: true,
+                    covered: true,
+                },
+            ],
+            coverage
+        );
+    }
 }
@@ -0,0 +1,154 @@
+use anyhow::Result;
+use chrono::Utc;
+use tracing::{info, warn};
+
+use crate::downlink::multicast as mcast;
+use crate::helpers::errors::PrintFullError;
+use crate::storage::downlink_schedule::{self, DownlinkSchedule};
+use crate::storage::{device, device_queue, multicast};
+use lrwn::EUI64;
+
+// Materializes the given downlink schedule into the device or multicast-group queue(s) and
+// sets the next run of the schedule.
+pub async fn handle_schedule(s: DownlinkSchedule) -> Result<()> {
+    let now = Utc::now();
+    let res = enqueue(&s).await;
+
+    let next_run_at = downlink_schedule::get_next_run_at(&s.cron_expression, &now)?;
+    downlink_schedule::set_run(&s.id, now, next_run_at).await?;
+
+    res
+}
+
+async fn enqueue(s: &DownlinkSchedule) -> Result<()> {
+    if let Some(dev_eui) = s.dev_eui {
+        enqueue_device(s, dev_eui).await?;
+        info!(downlink_schedule_id = %s.id, dev_eui = %dev_eui, "Scheduled downlink enqueued");
+    } else if let Some(multicast_group_id) = s.multicast_group_id {
+        mcast::enqueue(multicast::MulticastGroupQueueItem {
+            multicast_group_id,
+            f_port: s.f_port,
+            data: s.data.clone(),
+            ..Default::default()
+        })
+        .await?;
+        info!(downlink_schedule_id = %s.id, multicast_group_id = %multicast_group_id, "Scheduled downlink enqueued");
+    } else {
+        let dev_euis = device::get_dev_euis(&device::Filters {
+            application_id: Some(s.application_id.into()),
+            tags: s.tags.into_hashmap(),
+            ..Default::default()
+        })
+        .await?;
+
+        let mut error_count = 0;
+        for dev_eui in &dev_euis {
+            if let Err(e) = enqueue_device(s, *dev_eui).await {
+                warn!(downlink_schedule_id = %s.id, dev_eui = %dev_eui, error = %e.full(), "Enqueue scheduled downlink error");
+                error_count += 1;
+            }
+        }
+
+        info!(downlink_schedule_id = %s.id, device_count = dev_euis.len(), error_count = error_count, "Scheduled downlink enqueued");
+    }
+
+    Ok(())
+}
+
+async fn enqueue_device(s: &DownlinkSchedule, dev_eui: EUI64) -> Result<()> {
+    device_queue::enqueue_item(device_queue::DeviceQueueItem {
+        dev_eui,
+        f_port: s.f_port,
+        confirmed: s.confirmed,
+        data: s.data.clone(),
+        dedup_key: Some(s.get_dedup_key()),
+        ..Default::default()
+    })
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::storage::{self, fields};
+    use crate::test;
+
+    #[tokio::test]
+    async fn test_handle_schedule() {
+        let _guard = test::prepare().await;
+        let t = storage::tenant::test::create_tenant().await;
+        let app = storage::application::test::create_application(Some(t.id.into())).await;
+        let dp = storage::device_profile::test::create_device_profile(Some(t.id.into())).await;
+
+        let d1 = storage::device::create(device::Device {
+            application_id: app.id,
+            device_profile_id: dp.id,
+            name: "dev-1".into(),
+            dev_eui: EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 1]),
+            tags: fields::KeyValue::new(
+                [("site".to_string(), "a".to_string())]
+                    .iter()
+                    .cloned()
+                    .collect(),
+            ),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let d2 = storage::device::create(device::Device {
+            application_id: app.id,
+            device_profile_id: dp.id,
+            name: "dev-2".into(),
+            dev_eui: EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 2]),
+            tags: fields::KeyValue::new(
+                [("site".to_string(), "b".to_string())]
+                    .iter()
+                    .cloned()
+                    .collect(),
+            ),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let s = downlink_schedule::create(DownlinkSchedule {
+            application_id: app.id,
+            name: "time-sync".into(),
+            tags: fields::KeyValue::new(
+                [("site".to_string(), "a".to_string())]
+                    .iter()
+                    .cloned()
+                    .collect::<HashMap<String, String>>(),
+            ),
+            cron_expression: "0 0 3 * * *".into(),
+            f_port: 10,
+            data: vec![1, 2, 3],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        // Running the schedule twice must result in a single queue-item as the
+        // previous (non-pending) item is replaced.
+        for _ in 0..2 {
+            handle_schedule(s.clone()).await.unwrap();
+        }
+
+        let queue = device_queue::get_for_dev_eui(&d1.dev_eui).await.unwrap();
+        assert_eq!(1, queue.len());
+        assert_eq!(vec![1, 2, 3], queue[0].data);
+        assert_eq!(Some(s.get_dedup_key()), queue[0].dedup_key);
+
+        let queue = device_queue::get_for_dev_eui(&d2.dev_eui).await.unwrap();
+        assert!(queue.is_empty());
+
+        let s_get = downlink_schedule::get(&s.id).await.unwrap();
+        assert!(s_get.last_run_at.is_some());
+        assert!(s_get.next_run_at > Utc::now());
+    }
+}
@@ -4,9 +4,10 @@ use tracing::{error, trace};
 
 use super::data;
 use super::multicast as mcast;
+use super::schedule;
 use crate::config;
 use crate::helpers::errors::PrintFullError;
-use crate::storage::{device, multicast};
+use crate::storage::{device, downlink_schedule, multicast};
 
 pub async fn class_b_c_scheduler_loop() {
     let conf = config::get();
@@ -42,6 +43,23 @@ pub async fn multicast_group_queue_scheduler_loop() {
     }
 }
 
+pub async fn downlink_schedule_scheduler_loop() {
+    let conf = config::get();
+
+    loop {
+        trace!("Starting downlink schedule scheduler loop run");
+
+        if let Err(err) = schedule_downlink_schedule_batch(conf.network.scheduler.batch_size).await
+        {
+            error!(error = %err, "Scheduling downlink schedule batch failed");
+        } else {
+            trace!("Downlink schedule scheduler run completed successfully");
+        }
+
+        sleep(conf.network.scheduler.interval).await;
+    }
+}
+
 pub async fn schedule_device_queue_batch(size: usize) -> Result<()> {
     trace!("Getting devices that have schedulable queue-items");
     let devices = device::get_with_class_b_c_queue_items(size).await?;
@@ -89,3 +107,23 @@ pub async fn schedule_multicast_group_queue_batch(size: usize) -> Result<()> {
     futures::future::join_all(handles).await;
     Ok(())
 }
+
+pub async fn schedule_downlink_schedule_batch(size: usize) -> Result<()> {
+    trace!("Getting schedulable downlink schedules");
+    let items = downlink_schedule::get_schedulable(size).await?;
+    trace!(count = items.len(), "Got this number of downlink schedules");
+
+    let mut handles = vec![];
+
+    for s in items {
+        let handle = tokio::spawn(async move {
+            if let Err(e) = schedule::handle_schedule(s).await {
+                error!(error = %e.full(), "Handle downlink schedule failed");
+            }
+        });
+        handles.push(handle);
+    }
+
+    futures::future::join_all(handles).await;
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::{Engine as _, engine::general_purpose};
+use reqwest::Client;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+
+use super::KekProvider;
+use crate::config;
+
+// The HTTP KEK provider implements a Vault transit-style API. The KEK never leaves the
+// remote key-management service:
+//  * POST [server]/encrypt/[key_name] {"plaintext": "[base64]"}
+//      => {"data": {"ciphertext": "..."}}
+//  * POST [server]/decrypt/[key_name] {"ciphertext": "..."}
+//      => {"data": {"plaintext": "[base64]"}}
+pub struct HttpKekProvider {
+    client: Client,
+    server: String,
+    key_name: String,
+}
+
+#[derive(Serialize)]
+struct EncryptRequest {
+    plaintext: String,
+}
+
+#[derive(Serialize)]
+struct DecryptRequest {
+    ciphertext: String,
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct EncryptResponse {
+    ciphertext: String,
+}
+
+#[derive(Deserialize)]
+struct DecryptResponse {
+    plaintext: String,
+}
+
+impl HttpKekProvider {
+    pub fn new(kek: &config::EncryptionKek) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        if !kek.token_header.is_empty() {
+            headers.insert(
+                HeaderName::from_bytes(kek.token_header.as_bytes())?,
+                HeaderValue::from_str(&kek.token)?,
+            );
+        }
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(5))
+            .default_headers(headers)
+            .build()?;
+
+        Ok(HttpKekProvider {
+            client,
+            server: kek.server.trim_end_matches('/').to_string(),
+            key_name: kek.key_name.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl KekProvider for HttpKekProvider {
+    async fn wrap(&self, dek: &[u8; 16]) -> Result<Vec<u8>> {
+        let resp: Response<EncryptResponse> = self
+            .client
+            .post(format!("{}/encrypt/{}", self.server, self.key_name))
+            .json(&EncryptRequest {
+                plaintext: general_purpose::STANDARD.encode(dek),
+            })
+            .send()
+            .await?
+            .error_for_status()
+            .context("Encrypt request error")?
+            .json()
+            .await?;
+
+        Ok(resp.data.ciphertext.into_bytes())
+    }
+
+    async fn unwrap(&self, wrapped: &[u8]) -> Result<[u8; 16]> {
+        let resp: Response<DecryptResponse> = self
+            .client
+            .post(format!("{}/decrypt/{}", self.server, self.key_name))
+            .json(&DecryptRequest {
+                ciphertext: String::from_utf8(wrapped.to_vec())?,
+            })
+            .send()
+            .await?
+            .error_for_status()
+            .context("Decrypt request error")?
+            .json()
+            .await?;
+
+        let b = general_purpose::STANDARD.decode(resp.data.plaintext)?;
+        b.try_into()
+            .map_err(|_| anyhow!("Decrypted key must be exactly 16 bytes"))
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use httpmock::prelude::*;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_http_kek_provider() {
+        let server = MockServer::start();
+
+        let p = HttpKekProvider::new(&config::EncryptionKek {
+            label: "vault".into(),
+            provider: "http".into(),
+            server: server.url("/v1/transit/"),
+            key_name: "chirpstack".into(),
+            token_header: "X-Vault-Token".into(),
+            token: "secret".into(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let mut encrypt_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/v1/transit/encrypt/chirpstack")
+                .header("X-Vault-Token", "secret")
+                .json_body(serde_json::json!({
+                    "plaintext": "AQEBAQEBAQEBAQEBAQEBAQ==",
+                }));
+            then.json_body(serde_json::json!({
+                "data": {
+                    "ciphertext": "vault:v1:abcd",
+                },
+            }));
+        });
+
+        let wrapped = p.wrap(&[1u8; 16]).await.unwrap();
+        assert_eq!(b"vault:v1:abcd".to_vec(), wrapped);
+        encrypt_mock.assert();
+        encrypt_mock.delete();
+
+        let mut decrypt_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/v1/transit/decrypt/chirpstack")
+                .header("X-Vault-Token", "secret")
+                .json_body(serde_json::json!({
+                    "ciphertext": "vault:v1:abcd",
+                }));
+            then.json_body(serde_json::json!({
+                "data": {
+                    "plaintext": "AQEBAQEBAQEBAQEBAQEBAQ==",
+                },
+            }));
+        });
+
+        assert_eq!([1u8; 16], p.unwrap(&wrapped).await.unwrap());
+        decrypt_mock.assert();
+        decrypt_mock.delete();
+
+        // Error response.
+        server.mock(|when, then| {
+            when.method(POST).path("/v1/transit/decrypt/chirpstack");
+            then.status(403);
+        });
+        assert!(p.unwrap(&wrapped).await.is_err());
+    }
+}
@@ -0,0 +1,45 @@
+use std::fs;
+use std::str::FromStr;
+
+use aes_kw::{KeyInit, KwAes128};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+
+use super::KekProvider;
+use lrwn::AES128Key;
+
+// The local KEK provider reads the (HEX encoded) KEK from a file.
+pub struct LocalKekProvider {
+    kek: AES128Key,
+}
+
+impl LocalKekProvider {
+    pub fn new(kek_file: &str) -> Result<Self> {
+        let kek = fs::read_to_string(kek_file).context("Read KEK file")?;
+        if kek.trim().is_empty() {
+            return Err(anyhow!("KEK file is empty"));
+        }
+        let kek = AES128Key::from_str(kek.trim()).context("Parse KEK file")?;
+
+        Ok(LocalKekProvider { kek })
+    }
+}
+
+#[async_trait]
+impl KekProvider for LocalKekProvider {
+    async fn wrap(&self, dek: &[u8; 16]) -> Result<Vec<u8>> {
+        let mut out = vec![0u8; 24];
+        KwAes128::new(&self.kek.to_bytes().into())
+            .wrap_key(dek, &mut out)
+            .map_err(|e| anyhow!("KEK wrap failed: {}", e))?;
+        Ok(out)
+    }
+
+    async fn unwrap(&self, wrapped: &[u8]) -> Result<[u8; 16]> {
+        let mut out = [0u8; 16];
+        KwAes128::new(&self.kek.to_bytes().into())
+            .unwrap_key(wrapped, &mut out)
+            .map_err(|e| anyhow!("KEK unwrap failed: {}", e))?;
+        Ok(out)
+    }
+}
@@ -0,0 +1,411 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+
+use aes_kw::{KeyInit, KwAes128};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rand::Rng;
+use tokio::sync::Notify;
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+
+use crate::config;
+use crate::helpers::errors::PrintFullError;
+use crate::storage::data_encryption_key;
+use chirpstack_api::internal;
+
+mod http;
+mod local;
+mod pkcs11;
+
+// Encrypted keys are stored as: MAGIC | DEK ID (4 bytes, BE) | AES key-wrap (24 bytes).
+// As plaintext keys are always 16 bytes, encrypted and plaintext keys can be stored
+// in the same column, which makes it possible to migrate existing rows in-place.
+const MAGIC: u8 = 0xe1;
+const ENCRYPTED_KEY_LEN: usize = 1 + 4 + 24;
+
+static STATE: LazyLock<RwLock<State>> = LazyLock::new(|| RwLock::new(State::default()));
+static RELOAD: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+#[derive(Default)]
+struct State {
+    deks: HashMap<i32, [u8; 16]>,
+    active: Option<i32>,
+}
+
+// A KEK provider wraps and unwraps the data-encryption keys (DEKs). Other providers
+// can be added by implementing this trait and adding them to get_kek_provider.
+#[async_trait]
+pub trait KekProvider {
+    async fn wrap(&self, dek: &[u8; 16]) -> Result<Vec<u8>>;
+    async fn unwrap(&self, wrapped: &[u8]) -> Result<[u8; 16]>;
+}
+
+pub async fn setup() -> Result<()> {
+    let conf = config::get();
+    if !conf.encryption.enabled {
+        *STATE.write().unwrap() = State::default();
+        return Ok(());
+    }
+
+    info!("Setting up data-encryption keys");
+    load().await?;
+
+    if STATE.read().unwrap().active.is_none() {
+        info!("No data-encryption key found, creating one");
+        create_dek().await?;
+    }
+
+    tokio::spawn(async move {
+        reload_loop().await;
+    });
+
+    Ok(())
+}
+
+// Loads and unwraps all data-encryption keys from the database.
+pub async fn load() -> Result<()> {
+    let mut state = State::default();
+
+    for dek in data_encryption_key::list().await? {
+        let kek = get_kek_provider(&dek.kek_label)?;
+        let key = kek
+            .unwrap(&dek.wrapped_key)
+            .await
+            .with_context(|| format!("Unwrap data-encryption key {}", dek.id))?;
+
+        state.deks.insert(dek.id, key);
+        if dek.is_active {
+            state.active = Some(dek.id);
+        }
+    }
+
+    *STATE.write().unwrap() = state;
+    Ok(())
+}
+
+// Periodically re-loads the data-encryption keys, such that a key rotation performed
+// by an other ChirpStack instance or CLI command is picked up. The keys are also
+// re-loaded when an unknown data-encryption key was encountered on decryption.
+async fn reload_loop() {
+    let conf = config::get();
+
+    loop {
+        if conf.encryption.reload_interval.is_zero() {
+            RELOAD.notified().await;
+        } else {
+            tokio::select! {
+                _ = sleep(conf.encryption.reload_interval) => {},
+                _ = RELOAD.notified() => {},
+            }
+        }
+
+        if let Err(e) = load().await {
+            error!(error = %e.full(), "Reloading data-encryption keys error");
+        }
+    }
+}
+
+// Creates a new data-encryption key, wrapped by the configured KEK. The new key
+// becomes the active key, used for all new encryptions.
+pub async fn create_dek() -> Result<i32> {
+    let conf = config::get();
+    let kek = get_kek_provider(&conf.encryption.kek_label)?;
+
+    let mut key = [0u8; 16];
+    rand::rng().fill_bytes(&mut key);
+    let wrapped = kek.wrap(&key).await.context("Wrap data-encryption key")?;
+
+    let dek = data_encryption_key::create(&conf.encryption.kek_label, wrapped).await?;
+
+    let mut state = STATE.write().unwrap();
+    state.deks.insert(dek.id, key);
+    state.active = Some(dek.id);
+
+    Ok(dek.id)
+}
+
+pub fn get_active_dek_id() -> Option<i32> {
+    STATE.read().unwrap().active
+}
+
+pub fn is_enabled() -> bool {
+    get_active_dek_id().is_some()
+}
+
+fn get_kek_provider(label: &str) -> Result<Box<dyn KekProvider + Sync + Send>> {
+    let conf = config::get();
+
+    for kek in &conf.encryption.keks {
+        if kek.label == label {
+            return match kek.provider.as_ref() {
+                "local" => Ok(Box::new(local::LocalKekProvider::new(&kek.kek_file)?)),
+                "http" => Ok(Box::new(http::HttpKekProvider::new(kek)?)),
+                "pkcs11" => Ok(Box::new(pkcs11::Pkcs11KekProvider::new(kek)?)),
+                _ => Err(anyhow!("Unexpected KEK provider: {}", kek.provider)),
+            };
+        }
+    }
+
+    Err(anyhow!("KEK label {} does not exist", label))
+}
+
+pub fn is_encrypted(b: &[u8]) -> bool {
+    b.len() == ENCRYPTED_KEY_LEN && b[0] == MAGIC
+}
+
+// Returns the ID of the data-encryption key used to encrypt the given key.
+pub fn get_dek_id(b: &[u8]) -> Option<i32> {
+    if is_encrypted(b) {
+        Some(i32::from_be_bytes([b[1], b[2], b[3], b[4]]))
+    } else {
+        None
+    }
+}
+
+// Encrypts the given 16 byte key using the active data-encryption key. In case
+// encryption is disabled or the key is not 16 bytes (e.g. it is not set), the key is
+// returned as-is.
+pub fn encrypt_key(key: &[u8]) -> Result<Vec<u8>> {
+    let state = STATE.read().unwrap();
+    let id = match state.active {
+        Some(v) => v,
+        None => return Ok(key.to_vec()),
+    };
+    if key.len() != 16 {
+        return Ok(key.to_vec());
+    }
+
+    let dek = state
+        .deks
+        .get(&id)
+        .ok_or_else(|| anyhow!("Data-encryption key {} does not exist", id))?;
+
+    let mut out = vec![0u8; ENCRYPTED_KEY_LEN];
+    out[0] = MAGIC;
+    out[1..5].copy_from_slice(&id.to_be_bytes());
+    KwAes128::new(dek.into())
+        .wrap_key(key, &mut out[5..])
+        .map_err(|e| anyhow!("Encrypt key error: {}", e))?;
+
+    Ok(out)
+}
+
+// Decrypts the given key. In case the key is not encrypted, it is returned as-is.
+// As keys are decrypted while deserializing database rows, this only uses the
+// loaded DEKs. In case the DEK is unknown, e.g. because an other instance rotated
+// the DEK since the last reload, an error is returned and the DEKs are reloaded
+// in the background.
+pub fn decrypt_key(b: &[u8]) -> Result<Vec<u8>> {
+    let id = match get_dek_id(b) {
+        Some(v) => v,
+        None => return Ok(b.to_vec()),
+    };
+
+    let dek = match get_dek(id) {
+        Some(v) => v,
+        None => {
+            warn!(
+                dek_id = id,
+                "Unknown data-encryption key, triggering reload"
+            );
+            RELOAD.notify_one();
+            return Err(anyhow!("Data-encryption key {} does not exist", id));
+        }
+    };
+
+    let mut out = vec![0u8; 16];
+    KwAes128::new(&dek.into())
+        .unwrap_key(&b[5..], &mut out)
+        .map_err(|e| anyhow!("Decrypt key error: {}", e))?;
+
+    Ok(out)
+}
+
+fn get_dek(id: i32) -> Option<[u8; 16]> {
+    STATE.read().unwrap().deks.get(&id).cloned()
+}
+
+// Returns a copy of the device-session with all session-keys encrypted.
+pub fn encrypt_device_session(ds: &internal::DeviceSession) -> Result<internal::DeviceSession> {
+    map_device_session_keys(ds.clone(), &encrypt_key)
+}
+
+// Returns the device-session with all session-keys decrypted.
+pub fn decrypt_device_session(ds: internal::DeviceSession) -> Result<internal::DeviceSession> {
+    map_device_session_keys(ds, &decrypt_key)
+}
+
+// Returns the IDs of the data-encryption keys used within the device-session.
+pub fn get_device_session_dek_ids(ds: &internal::DeviceSession) -> Vec<i32> {
+    let mut out: Vec<i32> = [&ds.f_nwk_s_int_key, &ds.s_nwk_s_int_key, &ds.nwk_s_enc_key]
+        .iter()
+        .filter_map(|v| get_dek_id(v))
+        .collect();
+
+    if let Some(app_s_key) = &ds.app_s_key {
+        out.extend(get_dek_id(&app_s_key.aes_key));
+    }
+
+    if let Some(pending_ds) = &ds.pending_rejoin_device_session {
+        out.extend(get_device_session_dek_ids(pending_ds));
+    }
+
+    out
+}
+
+fn map_device_session_keys(
+    mut ds: internal::DeviceSession,
+    f: &dyn Fn(&[u8]) -> Result<Vec<u8>>,
+) -> Result<internal::DeviceSession> {
+    ds.f_nwk_s_int_key = f(&ds.f_nwk_s_int_key)?;
+    ds.s_nwk_s_int_key = f(&ds.s_nwk_s_int_key)?;
+    ds.nwk_s_enc_key = f(&ds.nwk_s_enc_key)?;
+
+    // In case the AppSKey is wrapped by a KEK, it is already encrypted.
+    if let Some(app_s_key) = &mut ds.app_s_key
+        && app_s_key.kek_label.is_empty()
+    {
+        app_s_key.aes_key = f(&app_s_key.aes_key)?;
+    }
+
+    if let Some(pending_ds) = ds.pending_rejoin_device_session.take() {
+        ds.pending_rejoin_device_session = Some(Box::new(map_device_session_keys(*pending_ds, f)?));
+    }
+
+    Ok(ds)
+}
+
+#[cfg(test)]
+pub async fn reset() {
+    *STATE.write().unwrap() = State::default();
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::test;
+    use chirpstack_api::common;
+
+    // Enables encryption, using a local KEK file. It returns the path of the KEK
+    // file, which must be removed by the caller.
+    pub async fn setup_local_kek() -> String {
+        let path = std::env::temp_dir()
+            .join(format!("chirpstack-kek-{}", uuid::Uuid::new_v4()))
+            .to_string_lossy()
+            .to_string();
+        std::fs::write(&path, "01020304050607080102030405060708\n").unwrap();
+
+        let mut conf = (*config::get()).clone();
+        conf.encryption.enabled = true;
+        conf.encryption.kek_label = "kek-1".into();
+        conf.encryption.reload_interval = std::time::Duration::ZERO;
+        conf.encryption.keks = vec![config::EncryptionKek {
+            label: "kek-1".into(),
+            provider: "local".into(),
+            kek_file: path.clone(),
+            ..Default::default()
+        }];
+        config::set(conf);
+
+        setup().await.unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_encryption() {
+        let _guard = test::prepare().await;
+
+        // Encryption disabled.
+        setup().await.unwrap();
+        assert_eq!(vec![1u8; 16], encrypt_key(&[1u8; 16]).unwrap());
+
+        let kek_file = setup_local_kek().await;
+        assert_eq!(Some(1), get_active_dek_id());
+        // Encrypt / decrypt key.
+        let encrypted = encrypt_key(&[1u8; 16]).unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert_eq!(Some(1), get_dek_id(&encrypted));
+        assert_eq!(vec![1u8; 16], decrypt_key(&encrypted).unwrap());
+
+        // Plaintext keys are returned as-is.
+        assert_eq!(vec![2u8; 16], decrypt_key(&[2u8; 16]).unwrap());
+
+        // Keys that are not set remain unset.
+        assert!(encrypt_key(&[]).unwrap().is_empty());
+
+        // Device-session.
+        let ds = internal::DeviceSession {
+            f_nwk_s_int_key: vec![1u8; 16],
+            s_nwk_s_int_key: vec![2u8; 16],
+            nwk_s_enc_key: vec![3u8; 16],
+            app_s_key: Some(common::KeyEnvelope {
+                kek_label: "".into(),
+                aes_key: vec![4u8; 16],
+            }),
+            pending_rejoin_device_session: Some(Box::new(internal::DeviceSession {
+                nwk_s_enc_key: vec![5u8; 16],
+                app_s_key: Some(common::KeyEnvelope {
+                    kek_label: "kek".into(),
+                    aes_key: vec![6u8; 24],
+                }),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        let ds_enc = encrypt_device_session(&ds).unwrap();
+        assert!(is_encrypted(&ds_enc.f_nwk_s_int_key));
+        assert!(is_encrypted(&ds_enc.app_s_key.as_ref().unwrap().aes_key));
+        assert_eq!(
+            vec![6u8; 24],
+            ds_enc
+                .pending_rejoin_device_session
+                .as_ref()
+                .unwrap()
+                .app_s_key
+                .as_ref()
+                .unwrap()
+                .aes_key
+        );
+        assert_eq!(vec![1, 1, 1, 1, 1], get_device_session_dek_ids(&ds_enc));
+        assert_eq!(ds, decrypt_device_session(ds_enc).unwrap());
+
+        // New DEK, the old DEK can still be used for decryption.
+        assert_eq!(2, create_dek().await.unwrap());
+        assert_eq!(Some(2), get_dek_id(&encrypt_key(&[1u8; 16]).unwrap()));
+        assert_eq!(vec![1u8; 16], decrypt_key(&encrypted).unwrap());
+
+        // Reload from the database.
+        reset().await;
+        assert!(decrypt_key(&encrypted).is_err());
+        load().await.unwrap();
+        assert_eq!(Some(2), get_active_dek_id());
+        assert_eq!(vec![1u8; 16], decrypt_key(&encrypted).unwrap());
+
+        reset().await;
+        std::fs::remove_file(kek_file).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_key_unknown_dek() {
+        let _guard = test::prepare().await;
+        let kek_file = setup_local_kek().await;
+        let encrypted = encrypt_key(&[1u8; 16]).unwrap();
+
+        // The DEK is unknown, e.g. because it was created by an other instance. The
+        // decryption fails and the DEKs are reloaded in the background.
+        reset().await;
+        assert!(decrypt_key(&encrypted).is_err());
+        for _ in 0..10 {
+            if get_active_dek_id().is_some() {
+                break;
+            }
+            sleep(std::time::Duration::from_millis(100)).await;
+        }
+        assert_eq!(Some(1), get_active_dek_id());
+        assert_eq!(vec![1u8; 16], decrypt_key(&encrypted).unwrap());
+
+        reset().await;
+        std::fs::remove_file(kek_file).unwrap();
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use cryptoki::context::{CInitializeArgs, CInitializeFlags, Pkcs11};
+use cryptoki::error::{Error as Pkcs11Error, RvError};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::types::AuthPin;
+use tokio::task;
+
+use super::KekProvider;
+use crate::config;
+
+// A PKCS#11 library must only be initialized once per process.
+static CONTEXTS: LazyLock<Mutex<HashMap<String, Pkcs11>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// The PKCS#11 KEK provider wraps the DEKs using the AES key-wrap mechanism
+// (CKM_AES_KEY_WRAP) of a PKCS#11 token (e.g. a HSM). The KEK is looked up by
+// its label and never leaves the token.
+pub struct Pkcs11KekProvider {
+    library: String,
+    token_label: String,
+    pin: String,
+    key_label: String,
+}
+
+impl Pkcs11KekProvider {
+    pub fn new(kek: &config::EncryptionKek) -> Result<Self> {
+        if kek.library.is_empty() {
+            return Err(anyhow!("PKCS#11 library is not configured"));
+        }
+        if kek.key_name.is_empty() {
+            return Err(anyhow!("PKCS#11 key_name is not configured"));
+        }
+
+        Ok(Pkcs11KekProvider {
+            library: kek.library.clone(),
+            token_label: kek.token_label.clone(),
+            pin: kek.pin.clone(),
+            key_label: kek.key_name.clone(),
+        })
+    }
+
+    // Opens a session on the configured token and calls the given function with
+    // the handle of the KEK. PKCS#11 calls are blocking, therefore this is executed
+    // using spawn_blocking.
+    async fn with_kek<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Session, ObjectHandle) -> Result<T> + Send + 'static,
+    {
+        let library = self.library.clone();
+        let token_label = self.token_label.clone();
+        let pin = self.pin.clone();
+        let key_label = self.key_label.clone();
+
+        task::spawn_blocking(move || -> Result<T> {
+            let ctx = get_context(&library)?;
+
+            let slot = ctx
+                .get_slots_with_token()?
+                .into_iter()
+                .find(|s| {
+                    token_label.is_empty()
+                        || ctx
+                            .get_token_info(*s)
+                            .map(|t| t.label() == token_label)
+                            .unwrap_or(false)
+                })
+                .ok_or_else(|| anyhow!("PKCS#11 token {} not found", token_label))?;
+
+            let session = ctx.open_ro_session(slot)?;
+            if !pin.is_empty() {
+                match session.login(UserType::User, Some(&AuthPin::from(pin))) {
+                    Ok(_) | Err(Pkcs11Error::Pkcs11(RvError::UserAlreadyLoggedIn, _)) => {}
+                    Err(e) => return Err(e).context("PKCS#11 login"),
+                }
+            }
+
+            let kek = session
+                .find_objects(&[
+                    Attribute::Class(ObjectClass::SECRET_KEY),
+                    Attribute::Label(key_label.as_bytes().to_vec()),
+                ])?
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow!("PKCS#11 key {} not found", key_label))?;
+
+            f(&session, kek)
+        })
+        .await?
+    }
+}
+
+#[async_trait]
+impl KekProvider for Pkcs11KekProvider {
+    async fn wrap(&self, dek: &[u8; 16]) -> Result<Vec<u8>> {
+        let dek = *dek;
+        self.with_kek(move |session, kek| {
+            session
+                .encrypt(&Mechanism::AesKeyWrap, kek, &dek)
+                .context("PKCS#11 wrap")
+        })
+        .await
+    }
+
+    async fn unwrap(&self, wrapped: &[u8]) -> Result<[u8; 16]> {
+        let wrapped = wrapped.to_vec();
+        let b = self
+            .with_kek(move |session, kek| {
+                session
+                    .decrypt(&Mechanism::AesKeyWrap, kek, &wrapped)
+                    .context("PKCS#11 unwrap")
+            })
+            .await?;
+
+        b.try_into()
+            .map_err(|_| anyhow!("PKCS#11 unwrap returned an invalid key length"))
+    }
+}
+
+fn get_context(library: &str) -> Result<Pkcs11> {
+    let mut contexts = CONTEXTS.lock().unwrap();
+    if let Some(ctx) = contexts.get(library) {
+        return Ok(ctx.clone());
+    }
+
+    let ctx = Pkcs11::new(library).context("Load PKCS#11 library")?;
+    ctx.initialize(CInitializeArgs::new(CInitializeFlags::OS_LOCKING_OK))
+        .context("Initialize PKCS#11 library")?;
+    contexts.insert(library.to_string(), ctx.clone());
+
+    Ok(ctx)
+}
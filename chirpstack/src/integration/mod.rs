@@ -528,6 +528,22 @@ async fn handle_down_command(application_id: String, pl: integration::DownlinkCo
             } else {
                 None
             },
+            priority: i16::try_from(pl.priority)
+                .map_err(|_| anyhow!("Priority must be <= 32767"))?,
+            dedup_key: if pl.dedup_key.is_empty() {
+                None
+            } else {
+                Some(pl.dedup_key.clone())
+            },
+            not_before: if let Some(not_before) = pl.not_before {
+                Some(
+                    not_before
+                        .try_into()
+                        .map_err(|e| anyhow!("Parse not_before error: {}", e))?,
+                )
+            } else {
+                None
+            },
             ..Default::default()
         };
 
@@ -706,6 +706,9 @@ pub mod test {
             data: vec![1, 2, 3],
             object: None,
             expires_at: None,
+            priority: 0,
+            dedup_key: "".into(),
+            not_before: None,
         };
         let down_cmd_json = serde_json::to_string(&down_cmd).unwrap();
         client
@@ -1,26 +1,55 @@
 use anyhow::Result;
+use chrono::Utc;
+use tracing::info;
 
-use crate::storage::device::{self, DeviceClass};
+use crate::api::helpers::ToProto;
+use crate::integration;
+use crate::storage::{
+    application,
+    device::{self, DeviceClass},
+    device_profile, tenant,
+};
+use chirpstack_api::integration as integration_pb;
 
 pub async fn handle(
-    dev: &device::Device,
+    t: &tenant::Tenant,
+    app: &application::Application,
+    dp: &device_profile::DeviceProfile,
+    dev: &mut device::Device,
     block: &lrwn::MACCommandSet,
 ) -> Result<Option<lrwn::MACCommandSet>> {
     let mac = (**block)
         .first()
         .ok_or_else(|| anyhow!("Expected DeviceModeInd"))?;
     if let lrwn::MACCommand::DeviceModeInd(pl) = mac {
-        device::partial_update(
-            dev.dev_eui,
-            &device::DeviceChangeset {
-                enabled_class: Some(match pl.class {
-                    lrwn::DeviceModeClass::ClassA => DeviceClass::A,
-                    lrwn::DeviceModeClass::ClassC => DeviceClass::C,
-                }),
-                ..Default::default()
-            },
-        )
-        .await?;
+        let enabled_class = match pl.class {
+            lrwn::DeviceModeClass::ClassA => DeviceClass::A,
+            lrwn::DeviceModeClass::ClassC => DeviceClass::C,
+        };
+
+        if dev.enabled_class != enabled_class {
+            info!(dev_eui = %dev.dev_eui, previous_class = %dev.enabled_class, class = %enabled_class, "Device changed device-class");
+            let previous_class = dev.enabled_class;
+
+            // Resetting the scheduler_run_after makes sure that pending queue-items are picked up
+            // by the Class-B / Class-C scheduler without waiting for the previous lock to expire.
+            device::partial_update(
+                dev.dev_eui,
+                &device::DeviceChangeset {
+                    enabled_class: Some(enabled_class),
+                    scheduler_run_after: Some(None),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+            // Update the in-memory device as the downlink response must be scheduled using
+            // the new device-class.
+            dev.enabled_class = enabled_class;
+            dev.scheduler_run_after = None;
+
+            class_change_event(t, app, dp, dev, previous_class).await;
+        }
 
         return Ok(Some(lrwn::MACCommandSet::new(vec![
             lrwn::MACCommand::DeviceModeConf(lrwn::DeviceModeConfPayload { class: pl.class }),
@@ -30,18 +59,64 @@ pub async fn handle(
     Err(anyhow!("Expected DeviceModeInd payload"))
 }
 
+pub async fn class_change_event(
+    t: &tenant::Tenant,
+    app: &application::Application,
+    dp: &device_profile::DeviceProfile,
+    dev: &device::Device,
+    previous_class: DeviceClass,
+) {
+    let log_event = integration_pb::LogEvent {
+        time: Some(Utc::now().into()),
+        device_info: Some(integration_pb::DeviceInfo {
+            tenant_id: t.id.to_string(),
+            tenant_name: t.name.clone(),
+            application_id: app.id.to_string(),
+            application_name: app.name.to_string(),
+            device_profile_id: dp.id.to_string(),
+            device_profile_name: dp.name.clone(),
+            device_name: dev.name.clone(),
+            device_class_enabled: dev.enabled_class.to_proto().into(),
+            dev_eui: dev.dev_eui.to_string(),
+            tags: {
+                let mut tags = (*app.tags).clone();
+                tags.extend((*dp.tags).clone());
+                tags.extend((*dev.tags).clone());
+                tags
+            },
+        }),
+        level: integration_pb::LogLevel::Info.into(),
+        code: integration_pb::LogCode::DeviceClassChange.into(),
+        description: format!(
+            "Device changed from Class-{} to Class-{}",
+            previous_class, dev.enabled_class
+        ),
+        context: [
+            ("previous_class".to_string(), previous_class.to_string()),
+            ("class".to_string(), dev.enabled_class.to_string()),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+    };
+
+    integration::log_event(app.id.into(), &dev.variables, &log_event).await;
+}
+
 #[cfg(test)]
 pub mod test {
     use super::*;
     use crate::test;
     use std::str::FromStr;
+    use std::time::Duration;
+    use tokio::time::sleep;
 
-    use crate::storage::{application, device_profile, tenant};
     use lrwn::EUI64;
 
     #[tokio::test]
     async fn test_handle() {
         let _guard = test::prepare().await;
+        integration::set_mock().await;
 
         let tenant = tenant::create(tenant::Tenant {
             name: "test-tenant".into(),
@@ -63,11 +138,12 @@ pub mod test {
         })
         .await
         .unwrap();
-        let dev = device::create(device::Device {
+        let mut dev = device::create(device::Device {
             application_id: app.id,
             device_profile_id: dp.id,
             dev_eui: EUI64::from_str("0102030405060708").unwrap(),
             name: "test-device".into(),
+            scheduler_run_after: Some(Utc::now()),
             ..Default::default()
         })
         .await
@@ -79,7 +155,7 @@ pub mod test {
             },
         )]);
 
-        let resp = handle(&dev, &block).await.unwrap();
+        let resp = handle(&tenant, &app, &dp, &mut dev, &block).await.unwrap();
 
         assert_eq!(
             Some(lrwn::MACCommandSet::new(vec![
@@ -89,8 +165,35 @@ pub mod test {
             ])),
             resp
         );
+        assert_eq!(DeviceClass::C, dev.enabled_class);
 
         let d = device::get(&dev.dev_eui).await.unwrap();
         assert_eq!(DeviceClass::C, d.enabled_class);
+        assert!(d.scheduler_run_after.is_none());
+
+        sleep(Duration::from_millis(100)).await;
+        let mock_events = integration::mock::get_log_events().await;
+        assert_eq!(1, mock_events.len());
+        assert_eq!(
+            integration_pb::LogCode::DeviceClassChange as i32,
+            mock_events[0].code
+        );
+        assert_eq!(
+            [
+                ("previous_class".to_string(), "A".to_string()),
+                ("class".to_string(), "C".to_string()),
+            ]
+            .iter()
+            .cloned()
+            .collect::<std::collections::HashMap<String, String>>(),
+            mock_events[0].context
+        );
+
+        // Repeating the same DeviceModeInd must be confirmed, but must not emit a new event.
+        let resp = handle(&tenant, &app, &dp, &mut dev, &block).await.unwrap();
+        assert!(resp.is_some());
+        sleep(Duration::from_millis(100)).await;
+        let mock_events = integration::mock::get_log_events().await;
+        assert!(mock_events.is_empty());
     }
 }
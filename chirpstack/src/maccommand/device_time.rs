@@ -63,6 +63,7 @@ pub mod test {
                 }),
                 mic: None,
             },
+            phy_payload_b: vec![],
             tx_info: Default::default(),
             rx_info_set: vec![gw::UplinkRxInfo {
                 gw_time: Some(rx_time.into()),
@@ -348,6 +348,7 @@ pub mod test {
                 }),
                 mic: None,
             },
+            phy_payload_b: vec![],
             tx_info: Default::default(),
             rx_info_set: vec![],
             gateway_private_up_map: HashMap::new(),
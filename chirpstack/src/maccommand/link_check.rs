@@ -83,6 +83,7 @@ pub mod test {
                 }),
                 mic: None,
             },
+            phy_payload_b: vec![],
             tx_info: gw::UplinkTxInfo {
                 modulation: Some(gw::Modulation {
                     parameters: Some(gw::modulation::Parameters::Lora(gw::LoraModulationInfo {
@@ -133,7 +133,7 @@ async fn handle(
         lrwn::CID::DevStatusAns => {
             dev_status::handle(uplink_frame_set, tenant, app, dp, dev, block).await
         }
-        lrwn::CID::DeviceModeInd => device_mode_ind::handle(dev, block).await,
+        lrwn::CID::DeviceModeInd => device_mode_ind::handle(tenant, app, dp, dev, block).await,
         lrwn::CID::DeviceTimeReq => device_time::handle(uplink_frame_set, dev, block),
         lrwn::CID::LinkADRAns => link_adr::handle(uplink_frame_set, dev, block, pending_block),
         lrwn::CID::LinkCheckReq => link_check::handle(uplink_frame_set, dev, block),
@@ -192,6 +192,7 @@ pub mod test {
                 }),
                 mic: Some([0, 0, 0, 0]),
             },
+            phy_payload_b: vec![],
             tx_info: Default::default(),
             rx_info_set: Default::default(),
             gateway_private_up_map: Default::default(),
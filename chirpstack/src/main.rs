@@ -13,6 +13,7 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use tracing::Level;
 use tracing_subscriber::{filter, prelude::*};
+use uuid::Uuid;
 
 use lrwn::EUI64;
 
@@ -27,11 +28,13 @@ mod codec;
 mod config;
 mod devaddr;
 mod downlink;
+mod encryption;
 mod gateway;
 mod gpstime;
 mod helpers;
 mod integration;
 mod maccommand;
+mod manifest;
 mod monitoring;
 mod region;
 mod sensitivity;
@@ -71,6 +74,33 @@ enum Commands {
         dir: String,
     },
 
+    /// Import devices and device-keys from a manufacturer manifest.
+    ImportDeviceManifest {
+        /// Path to manifest file.
+        #[arg(short, long, value_name = "FILE")]
+        file: String,
+
+        /// Manifest format (json or csv, defaults to the file extension).
+        #[arg(long, value_name = "FORMAT")]
+        format: Option<String>,
+
+        /// Application ID.
+        #[arg(long, value_name = "APPLICATION_ID")]
+        application_id: String,
+
+        /// Device-profile ID.
+        #[arg(long, value_name = "DEVICE_PROFILE_ID")]
+        device_profile_id: String,
+
+        /// Label of the KEK used to unwrap the manifest keys.
+        #[arg(long, value_name = "KEK_LABEL", default_value = "")]
+        kek_label: String,
+
+        /// Only validate the manifest.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Create global API key.
     CreateApiKey {
         /// Name.
@@ -101,6 +131,23 @@ enum Commands {
 
     /// Migrate Device <> Gateway Rx Info.
     MigrateDeviceGatewayRxInfo {},
+
+    /// Encrypt the device root and session keys using the active data-encryption key.
+    EncryptDeviceKeys {},
+
+    /// Create a new data-encryption key and re-encrypt all device keys using this key.
+    RotateDataEncryptionKey {
+        /// Delete the data-encryption keys which are no longer in use.
+        #[arg(long)]
+        delete_retired: bool,
+    },
+
+    /// Validate region configuration against the Regional Parameters.
+    ValidateRegions {
+        /// Path to region configuration file (defaults to the loaded configuration).
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<String>,
+    },
 }
 
 #[tokio::main]
@@ -141,6 +188,27 @@ async fn main() -> Result<()> {
                 .await
                 .unwrap()
         }
+        Some(Commands::ImportDeviceManifest {
+            file,
+            format,
+            application_id,
+            device_profile_id,
+            kek_label,
+            dry_run,
+        }) => {
+            cmd::import_device_manifest::run(
+                Path::new(&file),
+                format
+                    .as_deref()
+                    .map(manifest::Format::from_str)
+                    .transpose()?,
+                Uuid::from_str(application_id)?,
+                Uuid::from_str(device_profile_id)?,
+                kek_label,
+                *dry_run,
+            )
+            .await?
+        }
         Some(Commands::CreateApiKey { name }) => cmd::create_api_key::run(name).await?,
         Some(Commands::SetPassword {
             email,
@@ -154,6 +222,13 @@ async fn main() -> Result<()> {
         Some(Commands::MigrateDeviceGatewayRxInfo {}) => {
             cmd::migrate_device_gateway_rx_info::run().await?
         }
+        Some(Commands::EncryptDeviceKeys {}) => cmd::encrypt_device_keys::run().await?,
+        Some(Commands::RotateDataEncryptionKey { delete_retired }) => {
+            cmd::rotate_data_encryption_key::run(*delete_retired).await?
+        }
+        Some(Commands::ValidateRegions { file }) => {
+            cmd::validate_regions::run(file.as_deref().map(Path::new))?
+        }
         None => cmd::root::run().await?,
     }
 
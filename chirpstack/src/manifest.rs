@@ -0,0 +1,579 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::config;
+use crate::storage::{application, device, device_keys, device_profile};
+use backend::KeyEnvelope;
+use lrwn::{AES128Key, EUI64};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s.to_lowercase().as_str() {
+            "json" => Format::Json,
+            "csv" => Format::Csv,
+            _ => return Err(anyhow!("Unexpected manifest format: {}", s)),
+        })
+    }
+}
+
+// Manifest entry as provided by the manufacturer. Keys are HEX encoded and are
+// either plain (16 bytes) or AES key-wrapped (24 bytes) using the KEK with the
+// given label.
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Entry {
+    dev_eui: String,
+    join_eui: String,
+    app_key: String,
+    nwk_key: String,
+    kek_label: String,
+    name: String,
+    description: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonManifest {
+    Devices(Vec<Entry>),
+    Manifest(JsonManifestObject),
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct JsonManifestObject {
+    kek_label: String,
+    devices: Vec<Entry>,
+}
+
+pub struct ImportItem {
+    pub dev_eui: EUI64,
+    pub join_eui: EUI64,
+    pub name: String,
+    pub description: String,
+    pub nwk_key: AES128Key,
+    pub app_key: AES128Key,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ReportItem {
+    // Line (CSV) or index (JSON, starting at 1) of the entry within the manifest.
+    pub line: usize,
+    pub dev_eui: String,
+    // Empty in case the entry is valid.
+    pub error: String,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    // Number of valid entries.
+    pub device_count: usize,
+    // Set to true when the devices have been created.
+    pub imported: bool,
+    pub items: Vec<ReportItem>,
+}
+
+impl Report {
+    pub fn has_errors(&self) -> bool {
+        self.items.iter().any(|i| !i.error.is_empty())
+    }
+}
+
+// Imports the devices and device-keys of the given manifest into the given
+// application. The manifest is only imported if all entries are valid, in which
+// case all devices are created within a single transaction. When dry_run is set,
+// the manifest is only validated.
+pub async fn import(
+    application_id: Uuid,
+    device_profile_id: Uuid,
+    format: Format,
+    manifest: &[u8],
+    kek_label: &str,
+    dry_run: bool,
+) -> Result<Report> {
+    let app = application::get(&application_id).await?;
+    let dp = device_profile::get(&device_profile_id).await?;
+    if dp.tenant_id.is_some() && dp.tenant_id != Some(app.tenant_id) {
+        return Err(anyhow!(
+            "Device-profile and application must belong to the same tenant"
+        ));
+    }
+
+    let entries = parse(format, manifest)?;
+    let mut report = Report::default();
+    let mut items: Vec<ImportItem> = Vec::new();
+    let mut seen: HashSet<EUI64> = HashSet::new();
+
+    for (line, entry, default_kek_label) in entries {
+        let default_kek_label = if default_kek_label.is_empty() {
+            kek_label
+        } else {
+            &default_kek_label
+        };
+
+        let res = to_import_item(&entry, default_kek_label).and_then(|item| {
+            if !seen.insert(item.dev_eui) {
+                return Err(anyhow!("Duplicate DevEUI"));
+            }
+            Ok(item)
+        });
+
+        report.items.push(ReportItem {
+            line,
+            dev_eui: entry.dev_eui.clone(),
+            error: match &res {
+                Ok(_) => "".into(),
+                Err(e) => format!("{:#}", e),
+            },
+        });
+
+        if let Ok(item) = res {
+            items.push(item);
+        }
+    }
+
+    // Validate that the devices do not exist yet.
+    let dev_euis: Vec<EUI64> = items.iter().map(|i| i.dev_eui).collect();
+    let existing: HashSet<EUI64> = device::get_many(&dev_euis)
+        .await?
+        .into_iter()
+        .map(|d| d.dev_eui)
+        .collect();
+    for ri in report.items.iter_mut() {
+        if let Ok(dev_eui) = EUI64::from_str(&ri.dev_eui)
+            && ri.error.is_empty()
+            && existing.contains(&dev_eui)
+        {
+            ri.error = "Device already exists".into();
+        }
+    }
+    items.retain(|i| !existing.contains(&i.dev_eui));
+    report.device_count = items.len();
+
+    if dry_run || report.has_errors() {
+        return Ok(report);
+    }
+
+    device::create_with_keys(
+        items
+            .into_iter()
+            .map(|i| {
+                (
+                    device::Device {
+                        dev_eui: i.dev_eui,
+                        application_id: app.id,
+                        device_profile_id: dp.id,
+                        name: i.name,
+                        description: i.description,
+                        join_eui: i.join_eui,
+                        ..Default::default()
+                    },
+                    device_keys::DeviceKeys {
+                        dev_eui: i.dev_eui,
+                        nwk_key: i.nwk_key,
+                        app_key: i.app_key,
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect(),
+    )
+    .await?;
+    report.imported = true;
+
+    info!(application_id = %application_id, count = report.device_count, "Manifest imported");
+
+    Ok(report)
+}
+
+// Returns the manifest entries, with for each entry the line (or index) and the
+// manifest-level KEK label (if any).
+fn parse(format: Format, manifest: &[u8]) -> Result<Vec<(usize, Entry, String)>> {
+    match format {
+        Format::Json => parse_json(manifest),
+        Format::Csv => parse_csv(manifest),
+    }
+}
+
+fn parse_json(manifest: &[u8]) -> Result<Vec<(usize, Entry, String)>> {
+    let (kek_label, devices) =
+        match serde_json::from_slice::<JsonManifest>(manifest).context("Parse JSON manifest")? {
+            JsonManifest::Devices(v) => ("".to_string(), v),
+            JsonManifest::Manifest(v) => (v.kek_label, v.devices),
+        };
+
+    Ok(devices
+        .into_iter()
+        .enumerate()
+        .map(|(i, e)| (i + 1, e, kek_label.clone()))
+        .collect())
+}
+
+fn parse_csv(manifest: &[u8]) -> Result<Vec<(usize, Entry, String)>> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(manifest);
+
+    // The header is used to lookup the column index, e.g. DevEUI, dev_eui or devEui.
+    let header: HashMap<String, usize> = rdr
+        .headers()
+        .context("Parse CSV manifest")?
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (v.to_lowercase().replace(['_', ' '], ""), i))
+        .collect();
+    if header.is_empty() {
+        return Err(anyhow!("CSV manifest is empty"));
+    }
+    for column in ["deveui", "appkey"] {
+        if !header.contains_key(column) {
+            return Err(anyhow!("CSV manifest does not contain {} column", column));
+        }
+    }
+
+    let mut out = Vec::new();
+    for record in rdr.records() {
+        let record = record.context("Parse CSV manifest")?;
+        let get = |column: &str| -> String {
+            header
+                .get(column)
+                .and_then(|i| record.get(*i))
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        // The line reported by the CSV reader does not account for skipped empty
+        // lines and the reported offset might point to these, therefore the line
+        // is derived from the first non-empty byte.
+        let line = match record.position() {
+            Some(p) => {
+                let offset = p.byte() as usize;
+                let offset = offset
+                    + manifest[offset..]
+                        .iter()
+                        .take_while(|b| **b == b'\r' || **b == b'\n')
+                        .count();
+                manifest[..offset].iter().filter(|b| **b == b'\n').count() + 1
+            }
+            None => 0,
+        };
+
+        out.push((
+            line,
+            Entry {
+                dev_eui: get("deveui"),
+                join_eui: get("joineui"),
+                app_key: get("appkey"),
+                nwk_key: get("nwkkey"),
+                kek_label: get("keklabel"),
+                name: get("name"),
+                description: get("description"),
+            },
+            "".to_string(),
+        ));
+    }
+
+    Ok(out)
+}
+
+// For LoRaWAN 1.0.x devices, the manufacturer only provides the AppKey. Within
+// ChirpStack, the LoRaWAN 1.0.x AppKey is stored as NwkKey.
+fn to_import_item(e: &Entry, default_kek_label: &str) -> Result<ImportItem> {
+    let kek_label = if e.kek_label.is_empty() {
+        default_kek_label
+    } else {
+        &e.kek_label
+    };
+
+    let dev_eui = EUI64::from_str(&e.dev_eui).context("Decode DevEUI")?;
+    let join_eui = if e.join_eui.is_empty() {
+        EUI64::default()
+    } else {
+        EUI64::from_str(&e.join_eui).context("Decode JoinEUI")?
+    };
+    let app_key = decode_key(&e.app_key, kek_label).context("Decode AppKey")?;
+    let (nwk_key, app_key) = if e.nwk_key.is_empty() {
+        (app_key, AES128Key::null())
+    } else {
+        (
+            decode_key(&e.nwk_key, kek_label).context("Decode NwkKey")?,
+            app_key,
+        )
+    };
+
+    Ok(ImportItem {
+        dev_eui,
+        join_eui,
+        name: if e.name.is_empty() {
+            dev_eui.to_string()
+        } else {
+            e.name.clone()
+        },
+        description: e.description.clone(),
+        nwk_key,
+        app_key,
+    })
+}
+
+// Plain keys are 16 bytes, AES key-wrapped keys are 24 bytes. The KEK label is
+// only used for the latter. Wrapped keys can only be unwrapped using the
+// manifest KEKs, as the other KEKs must not be exposed through the import.
+fn decode_key(s: &str, kek_label: &str) -> Result<AES128Key> {
+    let b = hex::decode(s)?;
+    match b.len() {
+        16 => Ok(AES128Key::from_slice(&b)?),
+        24 => {
+            if kek_label.is_empty() {
+                return Err(anyhow!("Key is wrapped, but KEK label is not set"));
+            }
+
+            let conf = config::get();
+            let kek = conf
+                .manifest_keks
+                .iter()
+                .find(|k| k.label == kek_label)
+                .ok_or_else(|| anyhow!("Manifest KEK label {} does not exist", kek_label))?;
+
+            let key = KeyEnvelope {
+                kek_label: kek_label.to_string(),
+                aes_key: b,
+            }
+            .unwrap(&kek.kek.to_bytes())?;
+            Ok(AES128Key::from_bytes(key))
+        }
+        _ => Err(anyhow!(
+            "Expected 16 (plain) or 24 (wrapped) bytes, got {}",
+            b.len()
+        )),
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::{storage, test};
+
+    #[tokio::test]
+    async fn test_import() {
+        let _guard = test::prepare().await;
+
+        let mut conf = (*config::get()).clone();
+        conf.manifest_keks.push(config::Kek {
+            label: "kek".into(),
+            kek: AES128Key::from_bytes([1; 16]),
+        });
+        config::set(conf);
+
+        let dp = storage::device_profile::test::create_device_profile(None).await;
+        let app =
+            storage::application::test::create_application(dp.tenant_id.map(|v| v.into())).await;
+
+        let wrapped = KeyEnvelope::new("kek", Some(&[1; 16]), &[2; 16]).unwrap();
+        let csv = format!(
+            "DevEUI,JoinEUI,AppKey,Name\n\
+            0101010101010101,0102030405060708,{},dev-1\n\
+            0202020202020202,,03030303030303030303030303030303,\n",
+            hex::encode(&wrapped.aes_key)
+        );
+
+        // Dry-run.
+        let report = import(
+            app.id.into(),
+            dp.id.into(),
+            Format::Csv,
+            csv.as_bytes(),
+            "kek",
+            true,
+        )
+        .await
+        .unwrap();
+        assert_eq!(2, report.device_count);
+        assert!(!report.imported);
+        assert!(!report.has_errors());
+        assert!(device::get(&EUI64::from_be_bytes([1; 8])).await.is_err());
+
+        // Invalid entry, nothing is imported.
+        let invalid = format!("{}0303030303030303,,zz,\n", csv);
+        let report = import(
+            app.id.into(),
+            dp.id.into(),
+            Format::Csv,
+            invalid.as_bytes(),
+            "kek",
+            false,
+        )
+        .await
+        .unwrap();
+        assert!(!report.imported);
+        assert_eq!(
+            ReportItem {
+                line: 4,
+                dev_eui: "0303030303030303".into(),
+                error: "Decode AppKey: Invalid character 'z' at position 0".into(),
+            },
+            report.items[2]
+        );
+        assert!(device::get(&EUI64::from_be_bytes([1; 8])).await.is_err());
+
+        // Import.
+        let report = import(
+            app.id.into(),
+            dp.id.into(),
+            Format::Csv,
+            csv.as_bytes(),
+            "kek",
+            false,
+        )
+        .await
+        .unwrap();
+        assert!(report.imported);
+
+        let d = device::get(&EUI64::from_be_bytes([1; 8])).await.unwrap();
+        assert_eq!("dev-1", d.name);
+        assert_eq!(EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8]), d.join_eui);
+        let dk = device_keys::get(&d.dev_eui).await.unwrap();
+        assert_eq!(AES128Key::from_bytes([2; 16]), dk.nwk_key);
+
+        let d = device::get(&EUI64::from_be_bytes([2; 8])).await.unwrap();
+        assert_eq!("0202020202020202", d.name);
+
+        // Devices already exist.
+        let report = import(
+            app.id.into(),
+            dp.id.into(),
+            Format::Csv,
+            csv.as_bytes(),
+            "kek",
+            true,
+        )
+        .await
+        .unwrap();
+        assert_eq!(0, report.device_count);
+        assert_eq!("Device already exists", report.items[0].error);
+
+        // Global device-profile.
+        let dp_global = device_profile::create(device_profile::DeviceProfile {
+            name: "global device-profile".into(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let report = import(
+            app.id.into(),
+            dp_global.id.into(),
+            Format::Csv,
+            b"DevEUI,AppKey\n0303030303030303,03030303030303030303030303030303\n",
+            "",
+            true,
+        )
+        .await
+        .unwrap();
+        assert_eq!(1, report.device_count);
+        assert!(!report.has_errors());
+
+        // Device-profile of an other tenant.
+        let dp_other = storage::device_profile::test::create_device_profile(None).await;
+        assert!(
+            import(
+                app.id.into(),
+                dp_other.id.into(),
+                Format::Csv,
+                csv.as_bytes(),
+                "kek",
+                true,
+            )
+            .await
+            .is_err()
+        );
+
+        // Only the manifest KEKs can be used for unwrapping.
+        let mut conf = (*config::get()).clone();
+        conf.keks.push(config::Kek {
+            label: "other".into(),
+            kek: AES128Key::from_bytes([1; 16]),
+        });
+        config::set(conf);
+        assert!(decode_key(&hex::encode(&wrapped.aes_key), "other").is_err());
+        assert_eq!(
+            AES128Key::from_bytes([2; 16]),
+            decode_key(&hex::encode(&wrapped.aes_key), "kek").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let manifest = r#"{
+            "kekLabel": "kek",
+            "devices": [
+                {"devEui": "0101010101010101", "appKey": "01010101010101010101010101010101"},
+                {"devEui": "0202020202020202", "appKey": "02020202020202020202020202020202", "kekLabel": ""}
+            ]
+        }"#;
+        let entries = parse(Format::Json, manifest.as_bytes()).unwrap();
+        assert_eq!(2, entries.len());
+        assert_eq!(1, entries[0].0);
+        assert_eq!("0101010101010101", entries[0].1.dev_eui);
+        assert_eq!("kek", entries[0].2);
+
+        let item = to_import_item(&entries[1].1, "").unwrap();
+        assert_eq!(AES128Key::from_bytes([2; 16]), item.nwk_key);
+        assert_eq!(AES128Key::null(), item.app_key);
+
+        let manifest = r#"[{"devEui": "0101010101010101", "appKey": ""}]"#;
+        let entries = parse(Format::Json, manifest.as_bytes()).unwrap();
+        assert_eq!("", entries[0].2);
+        assert!(to_import_item(&entries[0].1, "").is_err());
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let manifest = "dev_eui,app_key,name,description\n\
+            0101010101010101,01010101010101010101010101010101,\"dev, 1\",\"say \"\"hi\"\"\"\n\
+            \n\
+            0202020202020202, 02020202020202020202020202020202\n";
+        let entries = parse(Format::Csv, manifest.as_bytes()).unwrap();
+        assert_eq!(2, entries.len());
+        assert_eq!(2, entries[0].0);
+        assert_eq!("dev, 1", entries[0].1.name);
+        assert_eq!("say \"hi\"", entries[0].1.description);
+        assert_eq!(4, entries[1].0);
+        assert_eq!("02020202020202020202020202020202", entries[1].1.app_key);
+
+        assert!(parse(Format::Csv, b"name\ndev-1\n").is_err());
+    }
+
+    #[test]
+    fn test_decode_key() {
+        let wrapped = KeyEnvelope::new("kek", Some(&[1; 16]), &[2; 16]).unwrap();
+        let wrapped = hex::encode(wrapped.aes_key);
+
+        // Plain keys are not unwrapped, also when a KEK label is set.
+        assert_eq!(
+            AES128Key::from_bytes([3; 16]),
+            decode_key("03030303030303030303030303030303", "kek").unwrap()
+        );
+        assert_eq!(
+            AES128Key::from_bytes([3; 16]),
+            decode_key("03030303030303030303030303030303", "").unwrap()
+        );
+
+        // Wrapped key without KEK label.
+        assert!(decode_key(&wrapped, "").is_err());
+
+        // Invalid length.
+        assert!(decode_key("0303", "kek").is_err());
+    }
+}
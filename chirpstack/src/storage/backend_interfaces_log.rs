@@ -0,0 +1,256 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use diesel::{dsl, prelude::*};
+use diesel_async::RunQueryDsl;
+use tracing::info;
+use uuid::Uuid;
+
+use super::error::Error;
+use super::schema::backend_interfaces_log;
+use super::{fields, get_async_db_conn};
+use lrwn::{EUI64, NetID};
+
+#[derive(Clone, Queryable, Insertable, Debug, PartialEq, Eq)]
+#[diesel(table_name = backend_interfaces_log)]
+pub struct BackendInterfacesLog {
+    pub id: fields::Uuid,
+    pub created_at: DateTime<Utc>,
+    pub sender_id: String,
+    pub receiver_id: String,
+    pub transaction_id: i64,
+    pub message_type: String,
+    pub result_code: String,
+    pub target_role: String,
+    pub latency: i32,
+    pub dev_eui: Option<EUI64>,
+    pub request_body: String,
+    pub request_error: String,
+    pub response_body: String,
+}
+
+impl BackendInterfacesLog {
+    // Returns true when the request failed, either because of a request error
+    // (e.g. timeout) or because of a non-Success result code.
+    pub fn is_failed(&self) -> bool {
+        !self.request_error.is_empty() || self.result_code != "Success"
+    }
+}
+
+impl Default for BackendInterfacesLog {
+    fn default() -> Self {
+        BackendInterfacesLog {
+            id: Uuid::new_v4().into(),
+            created_at: Utc::now(),
+            sender_id: "".into(),
+            receiver_id: "".into(),
+            transaction_id: 0,
+            message_type: "".into(),
+            result_code: "".into(),
+            target_role: "".into(),
+            latency: 0,
+            dev_eui: None,
+            request_body: "".into(),
+            request_error: "".into(),
+            response_body: "".into(),
+        }
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct Filters {
+    // Matches either the SenderID or the ReceiverID.
+    pub net_id: Option<NetID>,
+    pub dev_eui: Option<EUI64>,
+    pub message_type: Option<String>,
+}
+
+pub async fn create(l: BackendInterfacesLog) -> Result<BackendInterfacesLog, Error> {
+    let l: BackendInterfacesLog = diesel::insert_into(backend_interfaces_log::table)
+        .values(&l)
+        .get_result(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, l.id.to_string()))?;
+    Ok(l)
+}
+
+pub async fn get(id: &Uuid) -> Result<BackendInterfacesLog, Error> {
+    backend_interfaces_log::dsl::backend_interfaces_log
+        .find(fields::Uuid::from(id))
+        .first(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, id.to_string()))
+}
+
+pub async fn get_count(filters: &Filters) -> Result<i64, Error> {
+    let mut q = backend_interfaces_log::dsl::backend_interfaces_log
+        .select(dsl::count_star())
+        .into_boxed();
+
+    if let Some(net_id) = &filters.net_id {
+        q = q.filter(
+            backend_interfaces_log::dsl::sender_id
+                .eq(net_id.to_string())
+                .or(backend_interfaces_log::dsl::receiver_id.eq(net_id.to_string())),
+        );
+    }
+
+    if let Some(dev_eui) = &filters.dev_eui {
+        q = q.filter(backend_interfaces_log::dsl::dev_eui.eq(dev_eui));
+    }
+
+    if let Some(message_type) = &filters.message_type {
+        q = q.filter(backend_interfaces_log::dsl::message_type.eq(message_type));
+    }
+
+    q.first(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, "".into()))
+}
+
+pub async fn list(
+    limit: i64,
+    offset: i64,
+    filters: &Filters,
+) -> Result<Vec<BackendInterfacesLog>, Error> {
+    let mut q = backend_interfaces_log::dsl::backend_interfaces_log.into_boxed();
+
+    if let Some(net_id) = &filters.net_id {
+        q = q.filter(
+            backend_interfaces_log::dsl::sender_id
+                .eq(net_id.to_string())
+                .or(backend_interfaces_log::dsl::receiver_id.eq(net_id.to_string())),
+        );
+    }
+
+    if let Some(dev_eui) = &filters.dev_eui {
+        q = q.filter(backend_interfaces_log::dsl::dev_eui.eq(dev_eui));
+    }
+
+    if let Some(message_type) = &filters.message_type {
+        q = q.filter(backend_interfaces_log::dsl::message_type.eq(message_type));
+    }
+
+    q.order_by(backend_interfaces_log::dsl::created_at.desc())
+        .limit(limit)
+        .offset(offset)
+        .load(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, "".into()))
+}
+
+// Deletes all log items created before the given timestamp. It returns the
+// number of deleted items.
+pub async fn delete_before(before: DateTime<Utc>) -> Result<usize, Error> {
+    let ra = diesel::delete(
+        backend_interfaces_log::dsl::backend_interfaces_log
+            .filter(backend_interfaces_log::dsl::created_at.lt(before)),
+    )
+    .execute(&mut get_async_db_conn().await?)
+    .await?;
+    info!(count = ra, "Backend Interfaces log items deleted");
+    Ok(ra)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::test;
+    use chrono::Duration;
+
+    #[tokio::test]
+    async fn test_backend_interfaces_log() {
+        let _guard = test::prepare().await;
+
+        let l1 = create(BackendInterfacesLog {
+            created_at: Utc::now() - Duration::days(2),
+            sender_id: "010203".into(),
+            receiver_id: "0102030405060708".into(),
+            transaction_id: 1234,
+            message_type: "HomeNSReq".into(),
+            result_code: "Success".into(),
+            dev_eui: Some(EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8])),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        assert!(!l1.is_failed());
+
+        let l2 = create(BackendInterfacesLog {
+            sender_id: "030201".into(),
+            receiver_id: "010203".into(),
+            transaction_id: 4321,
+            message_type: "XmitDataReq".into(),
+            request_error: "Async timeout".into(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        assert!(l2.is_failed());
+
+        let l3 = create(BackendInterfacesLog {
+            sender_id: "030201".into(),
+            receiver_id: "040506".into(),
+            message_type: "XmitDataReq".into(),
+            result_code: "Success".into(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        // get
+        assert_eq!(l1, get(&l1.id.into()).await.unwrap());
+
+        struct FilterTest {
+            filters: Filters,
+            logs: Vec<BackendInterfacesLog>,
+        }
+
+        let tests = vec![
+            FilterTest {
+                filters: Filters::default(),
+                logs: vec![l3.clone(), l2.clone(), l1.clone()],
+            },
+            FilterTest {
+                filters: Filters {
+                    net_id: Some(NetID::from_be_bytes([1, 2, 3])),
+                    ..Default::default()
+                },
+                logs: vec![l2.clone(), l1.clone()],
+            },
+            FilterTest {
+                filters: Filters {
+                    dev_eui: Some(EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8])),
+                    ..Default::default()
+                },
+                logs: vec![l1.clone()],
+            },
+            FilterTest {
+                filters: Filters {
+                    net_id: Some(NetID::from_be_bytes([3, 2, 1])),
+                    message_type: Some("XmitDataReq".into()),
+                    ..Default::default()
+                },
+                logs: vec![l3.clone(), l2.clone()],
+            },
+        ];
+
+        for tst in tests {
+            let count = get_count(&tst.filters).await.unwrap() as usize;
+            assert_eq!(tst.logs.len(), count);
+
+            let items = list(10, 0, &tst.filters).await.unwrap();
+            assert_eq!(
+                tst.logs.iter().map(|l| l.id).collect::<Vec<_>>(),
+                items.iter().map(|l| l.id).collect::<Vec<_>>()
+            );
+        }
+
+        // delete before
+        assert_eq!(
+            1,
+            delete_before(Utc::now() - Duration::days(1)).await.unwrap()
+        );
+        assert!(get(&l1.id.into()).await.is_err());
+        assert_eq!(2, get_count(&Filters::default()).await.unwrap());
+    }
+}
@@ -0,0 +1,108 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use diesel::{dsl, prelude::*};
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use tracing::info;
+
+use super::error::Error;
+use super::get_async_db_conn;
+use super::schema::data_encryption_key;
+
+// A data-encryption key (DEK) is used to encrypt the device root and session keys.
+// The DEK itself is stored wrapped by the KEK with the given label.
+#[derive(Clone, Queryable, Insertable, Debug, PartialEq, Eq)]
+#[diesel(table_name = data_encryption_key)]
+pub struct DataEncryptionKey {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub kek_label: String,
+    pub wrapped_key: Vec<u8>,
+    pub is_active: bool,
+}
+
+// Creates the given DEK and marks it as the active DEK. The id is assigned
+// automatically.
+pub async fn create(kek_label: &str, wrapped_key: Vec<u8>) -> Result<DataEncryptionKey, Error> {
+    let mut c = get_async_db_conn().await?;
+    let dek: DataEncryptionKey = c
+        .transaction::<DataEncryptionKey, Error, _>(async |c| {
+            let max_id: Option<i32> = data_encryption_key::dsl::data_encryption_key
+                .select(dsl::max(data_encryption_key::dsl::id))
+                .first(c)
+                .await?;
+
+            diesel::update(data_encryption_key::dsl::data_encryption_key)
+                .set(data_encryption_key::is_active.eq(false))
+                .execute(c)
+                .await?;
+
+            diesel::insert_into(data_encryption_key::table)
+                .values(&DataEncryptionKey {
+                    id: max_id.unwrap_or_default() + 1,
+                    created_at: Utc::now(),
+                    kek_label: kek_label.to_string(),
+                    wrapped_key,
+                    is_active: true,
+                })
+                .get_result(c)
+                .await
+                .map_err(|e| Error::from_diesel(e, "".into()))
+        })
+        .await?;
+
+    info!(id = dek.id, kek_label = %dek.kek_label, "Data-encryption key created");
+    Ok(dek)
+}
+
+pub async fn list() -> Result<Vec<DataEncryptionKey>, Error> {
+    data_encryption_key::dsl::data_encryption_key
+        .order_by(data_encryption_key::dsl::id)
+        .load(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, "".into()))
+}
+
+pub async fn delete(id: i32) -> Result<(), Error> {
+    let ra = diesel::delete(data_encryption_key::dsl::data_encryption_key.find(id))
+        .execute(&mut get_async_db_conn().await?)
+        .await?;
+    if ra == 0 {
+        return Err(Error::NotFound(id.to_string()));
+    }
+    info!(id = id, "Data-encryption key deleted");
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::test;
+
+    #[tokio::test]
+    async fn test_data_encryption_key() {
+        let _guard = test::prepare().await;
+
+        let dek1 = create("kek-1", vec![1, 2, 3]).await.unwrap();
+        assert_eq!(1, dek1.id);
+        assert!(dek1.is_active);
+
+        let dek2 = create("kek-2", vec![3, 2, 1]).await.unwrap();
+        assert_eq!(2, dek2.id);
+
+        let deks = list().await.unwrap();
+        assert_eq!(
+            vec![
+                DataEncryptionKey {
+                    is_active: false,
+                    ..dek1.clone()
+                },
+                dek2.clone()
+            ],
+            deks
+        );
+
+        delete(dek1.id).await.unwrap();
+        assert!(delete(dek1.id).await.is_err());
+        assert_eq!(vec![dek2], list().await.unwrap());
+    }
+}
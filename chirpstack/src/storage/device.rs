@@ -1,24 +1,28 @@
 use std::collections::HashMap;
 use std::fmt;
+use std::io::Cursor;
 use std::str::FromStr;
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Duration, Utc};
 use diesel::{backend::Backend, deserialize, dsl, prelude::*, serialize, sql_types::Text};
 use diesel_async::{AsyncConnection, RunQueryDsl};
+use prost::Message;
 use tracing::info;
 use uuid::Uuid;
 
 use chirpstack_api::internal;
 use lrwn::{DevAddr, EUI64};
 
+use super::device_keys::DeviceKeys;
 use super::schema::{
-    application, device, device_profile, multicast_group_device, tenant, tenant_user,
+    application, device, device_keys, device_profile, multicast_group_device, tenant, tenant_user,
     tenant_user_application, user,
 };
 use super::{error::Error, fields, get_async_db_conn};
 use crate::api::helpers::FromProto;
 use crate::config;
+use crate::encryption;
 
 pub enum ValidationStatus {
     Ok(u32, Device),
@@ -254,6 +258,20 @@ pub struct DevicesDataRate {
     pub count: i64,
 }
 
+#[derive(Queryable, PartialEq, Eq, Debug)]
+pub struct DevAddrUsage {
+    pub dev_addr: Option<DevAddr>, // as the column is nullable
+    pub count: i64,
+}
+
+#[derive(Queryable, PartialEq, Eq, Debug, Default)]
+pub struct DevAddrUtilization {
+    // Number of devices with a DevAddr within the prefix.
+    pub device_count: i64,
+    // Number of distinct DevAddrs in use within the prefix.
+    pub dev_addr_count: i64,
+}
+
 pub async fn create(d: Device) -> Result<Device, Error> {
     let mut c = get_async_db_conn().await?;
     let d: Device = c
@@ -291,6 +309,68 @@ pub async fn create(d: Device) -> Result<Device, Error> {
     Ok(d)
 }
 
+// Creates the given devices and device-keys within a single transaction. Either all
+// devices are created or none. All devices must belong to the same application.
+pub async fn create_with_keys(items: Vec<(Device, DeviceKeys)>) -> Result<(), Error> {
+    let application_id = match items.as_slice() {
+        [(d, _), ..] => d.application_id,
+        [] => return Ok(()),
+    };
+    if items
+        .iter()
+        .any(|(d, _)| d.application_id != application_id)
+    {
+        return Err(Error::Validation(
+            "All devices must belong to the same application".into(),
+        ));
+    }
+
+    let count = items.len();
+    let mut c = get_async_db_conn().await?;
+    c.transaction::<(), Error, _>(async |c| {
+        let query = tenant::dsl::tenant
+            .select(tenant::all_columns)
+            .inner_join(application::table)
+            .filter(application::dsl::id.eq(&application_id));
+        // use for update to lock the tenant
+        #[cfg(feature = "postgres")]
+        let query = query.for_update();
+        let t: super::tenant::Tenant = query.first(c).await?;
+
+        let dev_count: i64 = device::dsl::device
+            .select(dsl::count_star())
+            .inner_join(application::table)
+            .filter(application::dsl::tenant_id.eq(&t.id))
+            .first(c)
+            .await?;
+
+        if t.max_device_count != 0 && dev_count as usize + count > t.max_device_count as usize {
+            return Err(Error::NotAllowed(
+                "Max number of devices exceeded for tenant".into(),
+            ));
+        }
+
+        for (d, dk) in items {
+            diesel::insert_into(device::table)
+                .values(&d)
+                .execute(c)
+                .await
+                .map_err(|e| Error::from_diesel(e, d.dev_eui.to_string()))?;
+
+            diesel::insert_into(device_keys::table)
+                .values(dk)
+                .execute(c)
+                .await
+                .map_err(|e| Error::from_diesel(e, d.dev_eui.to_string()))?;
+        }
+
+        Ok(())
+    })
+    .await?;
+    info!(application_id = %application_id, count = count, "Devices with keys created");
+    Ok(())
+}
+
 pub async fn get(dev_eui: &EUI64) -> Result<Device, Error> {
     let d = device::dsl::device
         .find(&dev_eui)
@@ -300,16 +380,28 @@ pub async fn get(dev_eui: &EUI64) -> Result<Device, Error> {
     Ok(d)
 }
 
+/// Returns the devices for the given DevEUIs. DevEUIs that do not exist are ignored.
+pub async fn get_many(dev_euis: &[EUI64]) -> Result<Vec<Device>, Error> {
+    device::dsl::device
+        .filter(device::dsl::dev_eui.eq_any(dev_euis))
+        .order_by(device::dsl::dev_eui)
+        .load(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, "".into()))
+}
+
 // Return the device-session matching the given PhyPayload. This will fetch all device-session
 // associated with the used DevAddr and based on f_cont and mic, decides which one to use.
 // This function will increment the uplink frame-counter and will immediately update the
 // device-session in the database, to make sure that in case this function is called multiple
 // times, at most one will be valid.
+// The MIC is validated against phy_b, which must contain the received (encoded) PhyPayload.
 // On Ok response, the PhyPayload f_cnt will be set to the full 32bit frame-counter based on the
 // device-session context.
 pub async fn get_for_phypayload_and_incr_f_cnt_up(
     region_config_id: &str,
     relayed: bool,
+    phy_b: &[u8],
     phy: &mut lrwn::PhyPayload,
     tx_dr: u8,
     tx_ch: u8,
@@ -344,6 +436,10 @@ pub async fn get_for_phypayload_and_incr_f_cnt_up(
         return Err(Error::NotFound(dev_addr.to_string()));
     }
 
+    // Validate the MIC against the borrowed received bytes for each device-session and
+    // frame-counter, without re-encoding the PhyPayload.
+    let phy_ref = lrwn::PhyPayloadRef::from_slice(phy_b)?;
+
     for d in &mut devices {
         let mut sessions = vec![];
 
@@ -386,15 +482,12 @@ pub async fn get_for_phypayload_and_incr_f_cnt_up(
             // GetFullFCntUp will think the 16LSB has rolled over and will
             // increment the 16MSB bit.
             let mut mic_ok = false;
+            let mut full_f_cnt = full_f_cnt;
             for f_cnt in [full_f_cnt, f_cnt_orig] {
-                // Set the full f_cnt.
-                if let lrwn::Payload::MACPayload(pl) = &mut phy.payload {
-                    pl.fhdr.f_cnt = f_cnt;
-                }
-
-                mic_ok = phy
+                mic_ok = phy_ref
                     .validate_uplink_data_mic(
                         ds.mac_version().from_proto(),
+                        f_cnt,
                         ds.conf_f_cnt,
                         tx_dr,
                         tx_ch,
@@ -404,16 +497,16 @@ pub async fn get_for_phypayload_and_incr_f_cnt_up(
                     .context("Validate MIC")?;
 
                 if mic_ok {
+                    full_f_cnt = f_cnt;
                     break;
                 }
             }
 
             if mic_ok {
-                let full_f_cnt = if let lrwn::Payload::MACPayload(pl) = &phy.payload {
-                    pl.fhdr.f_cnt
-                } else {
-                    0
-                };
+                // Set the full f_cnt.
+                if let lrwn::Payload::MACPayload(pl) = &mut phy.payload {
+                    pl.fhdr.f_cnt = full_f_cnt;
+                }
 
                 if let Some(relay) = &ds.relay
                     && !relayed
@@ -491,18 +584,16 @@ pub async fn get_for_phypayload_and_incr_f_cnt_up(
                     return Ok(ValidationStatus::Reset(full_f_cnt, d.clone()));
                 }
             }
-
-            // Restore the original f_cnt.
-            if let lrwn::Payload::MACPayload(pl) = &mut phy.payload {
-                pl.fhdr.f_cnt = f_cnt_orig;
-            }
         }
     }
 
     Err(Error::InvalidMIC)
 }
 
+// Returns the device matching the given PhyPayload. The MIC is validated against phy_b,
+// which must contain the received (encoded) PhyPayload.
 pub async fn get_for_phypayload(
+    phy_b: &[u8],
     phy: &mut lrwn::PhyPayload,
     tx_dr: u8,
     tx_ch: u8,
@@ -528,6 +619,10 @@ pub async fn get_for_phypayload(
         return Err(Error::NotFound(dev_addr.to_string()));
     }
 
+    // Validate the MIC against the borrowed received bytes for each device-session and
+    // frame-counter, without re-encoding the PhyPayload.
+    let phy_ref = lrwn::PhyPayloadRef::from_slice(phy_b)?;
+
     for d in &mut devices {
         let mut sessions = vec![];
 
@@ -554,14 +649,10 @@ pub async fn get_for_phypayload(
             let f_nwk_s_int_key = lrwn::AES128Key::from_slice(&ds.f_nwk_s_int_key)?;
             let s_nwk_s_int_key = lrwn::AES128Key::from_slice(&ds.s_nwk_s_int_key)?;
 
-            // Set the full f_cnt
-            if let lrwn::Payload::MACPayload(pl) = &mut phy.payload {
-                pl.fhdr.f_cnt = full_f_cnt;
-            }
-
-            let mic_ok = phy
+            let mic_ok = phy_ref
                 .validate_uplink_data_mic(
                     ds.mac_version().from_proto(),
+                    full_f_cnt,
                     ds.conf_f_cnt,
                     tx_dr,
                     tx_ch,
@@ -571,12 +662,12 @@ pub async fn get_for_phypayload(
                 .context("Validate MIC")?;
 
             if mic_ok {
-                return Ok(d.clone());
-            }
+                // Set the full f_cnt
+                if let lrwn::Payload::MACPayload(pl) = &mut phy.payload {
+                    pl.fhdr.f_cnt = full_f_cnt;
+                }
 
-            // Restore the original f_cnt.
-            if let lrwn::Payload::MACPayload(pl) = &mut phy.payload {
-                pl.fhdr.f_cnt = f_cnt_orig;
+                return Ok(d.clone());
             }
         }
     }
@@ -619,6 +710,52 @@ pub async fn partial_update(dev_eui: EUI64, d: &DeviceChangeset) -> Result<Devic
     Ok(d)
 }
 
+// Re-writes the device-session, such that its session-keys are encrypted using the
+// active data-encryption key.
+pub async fn reencrypt_device_session(dev_eui: EUI64) -> Result<(), Error> {
+    let mut c = get_async_db_conn().await?;
+    c.transaction::<(), Error, _>(async |c| {
+        let query = device::dsl::device
+            .find(&dev_eui)
+            .select(device::device_session);
+        #[cfg(feature = "postgres")]
+        let query = query.for_update();
+        let ds: Option<fields::DeviceSession> = query
+            .first(c)
+            .await
+            .map_err(|e| Error::from_diesel(e, dev_eui.to_string()))?;
+
+        diesel::update(device::dsl::device.find(&dev_eui))
+            .set(device::device_session.eq(&ds))
+            .execute(c)
+            .await
+            .map_err(|e| Error::from_diesel(e, dev_eui.to_string()))?;
+
+        Ok(())
+    })
+    .await
+}
+
+// Returns the IDs of the data-encryption keys used by the device-session of the given
+// device.
+pub async fn get_device_session_dek_ids(dev_eui: &EUI64) -> Result<Vec<i32>, Error> {
+    let b: Option<Vec<u8>> = device::dsl::device
+        .find(&dev_eui)
+        .select(device::device_session)
+        .first(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, dev_eui.to_string()))?;
+
+    match b {
+        Some(b) => {
+            let ds = internal::DeviceSession::decode(&mut Cursor::new(b))
+                .map_err(|e| Error::Anyhow(e.into()))?;
+            Ok(encryption::get_device_session_dek_ids(&ds))
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
 pub async fn delete(dev_eui: &EUI64) -> Result<(), Error> {
     let ra = diesel::delete(device::dsl::device.find(&dev_eui))
         .execute(&mut get_async_db_conn().await?)
@@ -707,10 +844,7 @@ pub async fn get_count(filters: &Filters) -> Result<i64, Error> {
         #[cfg(feature = "sqlite")]
         {
             for (k, v) in filters.tags.iter() {
-                q = q.filter(
-                    dsl::sql::<diesel::sql_types::Bool>(&format!("device.tags->>'{}' =", k))
-                        .bind::<diesel::sql_types::Text, _>(v),
-                );
+                q = q.filter(sqlite_tag_filter(k, v));
             }
         }
     }
@@ -815,10 +949,7 @@ pub async fn list(
         #[cfg(feature = "sqlite")]
         {
             for (k, v) in filters.tags.iter() {
-                q = q.filter(
-                    dsl::sql::<diesel::sql_types::Bool>(&format!("device.tags->>'{}' =", k))
-                        .bind::<diesel::sql_types::Text, _>(v),
-                );
+                q = q.filter(sqlite_tag_filter(k, v));
             }
         }
     }
@@ -869,6 +1000,61 @@ pub async fn list(
         .map_err(|e| Error::from_diesel(e, "".into()))
 }
 
+/// Returns the DevEUIs of the devices matching the given filters. Only the application_id,
+/// device_profile_id and tags filters are taken into account.
+pub async fn get_dev_euis(filters: &Filters) -> Result<Vec<EUI64>, Error> {
+    let mut q = device::dsl::device.select(device::dev_eui).into_boxed();
+
+    if let Some(application_id) = &filters.application_id {
+        q = q.filter(device::dsl::application_id.eq(fields::Uuid::from(application_id)));
+    }
+
+    if let Some(device_profile_id) = &filters.device_profile_id {
+        q = q.filter(device::dsl::device_profile_id.eq(fields::Uuid::from(device_profile_id)));
+    }
+
+    if !filters.tags.is_empty() {
+        #[cfg(feature = "postgres")]
+        {
+            q = q.filter(device::dsl::tags.contains(serde_json::json!(&filters.tags)));
+        }
+        #[cfg(feature = "sqlite")]
+        {
+            for (k, v) in filters.tags.iter() {
+                q = q.filter(sqlite_tag_filter(k, v));
+            }
+        }
+    }
+
+    q.order_by(device::dsl::dev_eui)
+        .load(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, "".into()))
+}
+
+#[cfg(feature = "sqlite")]
+type SqliteTagFilter<'a> = diesel::expression::UncheckedBind<
+    diesel::expression::SqlLiteral<
+        diesel::sql_types::Bool,
+        diesel::expression::UncheckedBind<
+            diesel::expression::SqlLiteral<diesel::sql_types::Bool>,
+            dsl::AsExprOf<&'a String, Text>,
+        >,
+    >,
+    dsl::AsExprOf<&'a String, Text>,
+>;
+
+// Returns the filter for devices having the given tag key and value. As the key is user
+// provided, it is bound as a parameter (like the value) instead of being formatted into
+// the query.
+#[cfg(feature = "sqlite")]
+fn sqlite_tag_filter<'a>(k: &'a String, v: &'a String) -> SqliteTagFilter<'a> {
+    dsl::sql::<diesel::sql_types::Bool>("device.tags->>")
+        .bind::<Text, _>(k)
+        .sql(" = ")
+        .bind::<Text, _>(v)
+}
+
 #[cfg(feature = "postgres")]
 pub async fn get_active_inactive(tenant_id: &Option<Uuid>) -> Result<DevicesActiveInactive, Error> {
     diesel::sql_query(r#"
@@ -951,6 +1137,51 @@ pub async fn get_data_rates(tenant_id: &Option<Uuid>) -> Result<Vec<DevicesDataR
         .map_err(|e| Error::from_diesel(e, "".into()))
 }
 
+// Returns the number of devices using each of the given DevAddrs. DevAddrs which
+// are not used by any device are not included in the result.
+pub async fn get_dev_addr_usage(dev_addrs: &[DevAddr]) -> Result<Vec<DevAddrUsage>, Error> {
+    device::table
+        .select((
+            device::dev_addr,
+            diesel::dsl::sql::<diesel::sql_types::BigInt>("count(1)"),
+        ))
+        .group_by(device::dev_addr)
+        .filter(device::dev_addr.eq_any(dev_addrs))
+        .load(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, "".into()))
+}
+
+// Returns the DevAddr utilization of the given DevAddr prefix.
+pub async fn get_dev_addr_utilization(
+    prefix: lrwn::DevAddrPrefix,
+) -> Result<DevAddrUtilization, Error> {
+    device::table
+        .select((
+            diesel::dsl::sql::<diesel::sql_types::BigInt>("count(1)"),
+            diesel::dsl::sql::<diesel::sql_types::BigInt>("count(distinct dev_addr)"),
+        ))
+        .filter(device::dev_addr.ge(prefix.first_dev_addr()))
+        .filter(device::dev_addr.le(prefix.last_dev_addr()))
+        .first(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, prefix.to_string()))
+}
+
+// Returns the number of distinct DevAddrs in use within the given DevAddr range
+// (inclusive).
+pub async fn get_dev_addr_count(first: DevAddr, last: DevAddr) -> Result<i64, Error> {
+    device::table
+        .select(diesel::dsl::sql::<diesel::sql_types::BigInt>(
+            "count(distinct dev_addr)",
+        ))
+        .filter(device::dev_addr.ge(first))
+        .filter(device::dev_addr.le(last))
+        .first(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, format!("{}-{}", first, last)))
+}
+
 pub async fn get_with_class_b_c_queue_items(limit: usize) -> Result<Vec<Device>> {
     let mut c = get_async_db_conn().await?;
     c.transaction::<Vec<Device>, Error, _>(async |c| {
@@ -998,6 +1229,8 @@ pub async fn get_with_class_b_c_queue_items(limit: usize) -> Result<Vec<Device>>
                                             -- pending queue-item with timeout_after in the future
                                             (dq.is_pending = true and dq.timeout_after > ?2)
                                         )
+                                        -- skip queue-items with not_before in the future
+                                        and (dq.not_before is null or dq.not_before <= ?2)
                                 )
                             order by d.dev_eui
                             limit ?1
@@ -1031,6 +1264,8 @@ pub async fn get_with_class_b_c_queue_items(limit: usize) -> Result<Vec<Device>>
                                             -- pending queue-item with timeout_after in the future
                                             (dq.is_pending = true and dq.timeout_after > $2)
                                         )
+                                        -- skip queue-items with not_before in the future
+                                        and (dq.not_before is null or dq.not_before <= $2)
                                 )
                             order by d.dev_eui
                             limit $1
@@ -1356,6 +1591,129 @@ pub mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_create_with_keys() {
+        let _guard = test::prepare().await;
+        let dp = storage::device_profile::test::create_device_profile(None).await;
+        let app =
+            storage::application::test::create_application(dp.tenant_id.map(|v| v.into())).await;
+
+        let items = |dev_euis: &[[u8; 8]]| -> Vec<(Device, DeviceKeys)> {
+            dev_euis
+                .iter()
+                .map(|dev_eui| {
+                    (
+                        Device {
+                            name: "test-dev".into(),
+                            dev_eui: EUI64::from_be_bytes(*dev_eui),
+                            application_id: app.id,
+                            device_profile_id: dp.id,
+                            ..Default::default()
+                        },
+                        DeviceKeys {
+                            dev_eui: EUI64::from_be_bytes(*dev_eui),
+                            nwk_key: AES128Key::from_bytes([1; 16]),
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect()
+        };
+
+        create_with_keys(items(&[[1; 8], [2; 8]])).await.unwrap();
+        assert_eq!(
+            2,
+            get_many(&[EUI64::from_be_bytes([1; 8]), EUI64::from_be_bytes([2; 8])])
+                .await
+                .unwrap()
+                .len()
+        );
+        assert_eq!(
+            AES128Key::from_bytes([1; 16]),
+            storage::device_keys::get(&EUI64::from_be_bytes([2; 8]))
+                .await
+                .unwrap()
+                .nwk_key
+        );
+
+        // Device 2 already exists, nothing is created.
+        assert!(create_with_keys(items(&[[3; 8], [2; 8]])).await.is_err());
+        assert!(get(&EUI64::from_be_bytes([3; 8])).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dev_addr_usage() {
+        let _guard = test::prepare().await;
+        let dp = storage::device_profile::test::create_device_profile(None).await;
+        let app =
+            storage::application::test::create_application(dp.tenant_id.map(|v| v.into())).await;
+
+        for (dev_eui, dev_addr) in [
+            ([1, 1, 1, 1, 1, 1, 1, 1], [1, 2, 3, 4]),
+            ([2, 2, 2, 2, 2, 2, 2, 2], [1, 2, 3, 4]),
+            ([3, 3, 3, 3, 3, 3, 3, 3], [1, 2, 3, 5]),
+            ([4, 4, 4, 4, 4, 4, 4, 4], [1, 2, 4, 1]),
+        ] {
+            create(Device {
+                name: "test-dev".into(),
+                dev_eui: EUI64::from_be_bytes(dev_eui),
+                application_id: app.id,
+                device_profile_id: dp.id,
+                dev_addr: Some(DevAddr::from_be_bytes(dev_addr)),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        }
+
+        // usage
+        let mut usage: Vec<(String, i64)> = get_dev_addr_usage(&[
+            DevAddr::from_be_bytes([1, 2, 3, 4]),
+            DevAddr::from_be_bytes([1, 2, 3, 5]),
+            DevAddr::from_be_bytes([1, 2, 3, 6]),
+        ])
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|u| (u.dev_addr.unwrap().to_string(), u.count))
+        .collect();
+        usage.sort();
+        assert_eq!(
+            vec![("01020304".to_string(), 2), ("01020305".to_string(), 1)],
+            usage
+        );
+
+        // utilization
+        let prefix = lrwn::DevAddrPrefix::from_str("01020300/24").unwrap();
+        assert_eq!(
+            DevAddrUtilization {
+                device_count: 3,
+                dev_addr_count: 2,
+            },
+            get_dev_addr_utilization(prefix).await.unwrap()
+        );
+
+        // count
+        assert_eq!(
+            2,
+            get_dev_addr_count(
+                DevAddr::from_be_bytes([1, 2, 3, 0]),
+                DevAddr::from_be_bytes([1, 2, 3, 5])
+            )
+            .await
+            .unwrap()
+        );
+        assert_eq!(
+            1,
+            get_dev_addr_count(
+                DevAddr::from_be_bytes([1, 2, 3, 5]),
+                DevAddr::from_be_bytes([1, 2, 4, 0])
+            )
+            .await
+            .unwrap()
+        );
+    }
+
     #[tokio::test]
     async fn test_get_with_class_b_c_queue_items() {
         let _guard = test::prepare().await;
@@ -1836,7 +2194,9 @@ pub mod test {
                 pl.fhdr.f_cnt = tst.f_cnt % (1 << 16);
             }
 
-            let d = get_for_phypayload_and_incr_f_cnt_up("eu868", false, &mut phy, 0, 0).await;
+            let phy_b = phy.to_vec().unwrap();
+            let d =
+                get_for_phypayload_and_incr_f_cnt_up("eu868", false, &phy_b, &mut phy, 0, 0).await;
             if tst.expected_error.is_some() {
                 assert!(d.is_err());
                 assert_eq!(
@@ -9,6 +9,7 @@ use lrwn::{AES128Key, EUI64};
 use super::error::Error;
 use super::schema::device_keys;
 use super::{fields, get_async_db_conn};
+use crate::encryption;
 
 #[derive(Queryable, Insertable, AsChangeset, PartialEq, Eq, Debug, Clone)]
 #[diesel(table_name = device_keys)]
@@ -16,10 +17,22 @@ pub struct DeviceKeys {
     pub dev_eui: EUI64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[diesel(
+        serialize_as = fields::EncryptedKey,
+        deserialize_as = fields::EncryptedKey
+    )]
     pub nwk_key: AES128Key,
+    #[diesel(
+        serialize_as = fields::EncryptedKey,
+        deserialize_as = fields::EncryptedKey
+    )]
     pub app_key: AES128Key,
     pub dev_nonces: fields::DevNonces,
     pub join_nonce: i32,
+    #[diesel(
+        serialize_as = fields::EncryptedKey,
+        deserialize_as = fields::EncryptedKey
+    )]
     pub gen_app_key: AES128Key,
 }
 
@@ -41,11 +54,12 @@ impl Default for DeviceKeys {
 }
 
 pub async fn create(dk: DeviceKeys) -> Result<DeviceKeys, Error> {
+    let dev_eui = dk.dev_eui;
     let dk: DeviceKeys = diesel::insert_into(device_keys::table)
-        .values(&dk)
+        .values(dk)
         .get_result(&mut get_async_db_conn().await?)
         .await
-        .map_err(|e| Error::from_diesel(e, dk.dev_eui.to_string()))?;
+        .map_err(|e| Error::from_diesel(e, dev_eui.to_string()))?;
     info!(
         dev_eui = %dk.dev_eui,
         "Device-keys created"
@@ -63,11 +77,12 @@ pub async fn get(dev_eui: &EUI64) -> Result<DeviceKeys, Error> {
 }
 
 pub async fn update(dk: DeviceKeys) -> Result<DeviceKeys, Error> {
-    let dk: DeviceKeys = diesel::update(device_keys::dsl::device_keys.find(&dk.dev_eui))
-        .set(&dk)
+    let dev_eui = dk.dev_eui;
+    let dk: DeviceKeys = diesel::update(device_keys::dsl::device_keys.find(&dev_eui))
+        .set(dk)
         .get_result(&mut get_async_db_conn().await?)
         .await
-        .map_err(|e| Error::from_diesel(e, dk.dev_eui.to_string()))?;
+        .map_err(|e| Error::from_diesel(e, dev_eui.to_string()))?;
     info!(
         dev_eui = %dk.dev_eui,
         "Device-keys updated"
@@ -144,6 +159,77 @@ pub async fn validate_incr_join_and_store_dev_nonce(
     Ok(dk)
 }
 
+pub async fn incr_join_nonce(dev_eui: EUI64) -> Result<DeviceKeys, Error> {
+    let dk: DeviceKeys = diesel::update(device_keys::dsl::device_keys.find(&dev_eui))
+        .set((
+            device_keys::updated_at.eq(Utc::now()),
+            device_keys::join_nonce.eq(device_keys::join_nonce + 1),
+        ))
+        .get_result(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, dev_eui.to_string()))?;
+
+    info!(dev_eui = %dev_eui, "Join-nonce incremented");
+    Ok(dk)
+}
+
+// Re-writes the device root-keys, such that these are encrypted using the active
+// data-encryption key.
+pub async fn reencrypt(dev_eui: EUI64) -> Result<(), Error> {
+    let mut c = get_async_db_conn().await?;
+    c.transaction::<(), Error, _>(async |c| {
+        let query = device_keys::dsl::device_keys.find(&dev_eui);
+        #[cfg(feature = "postgres")]
+        let query = query.for_update();
+        let dk: DeviceKeys = query
+            .first(c)
+            .await
+            .map_err(|e| Error::from_diesel(e, dev_eui.to_string()))?;
+
+        diesel::update(device_keys::dsl::device_keys.find(&dev_eui))
+            .set((
+                device_keys::nwk_key.eq(fields::EncryptedKey::from(dk.nwk_key)),
+                device_keys::app_key.eq(fields::EncryptedKey::from(dk.app_key)),
+                device_keys::gen_app_key.eq(fields::EncryptedKey::from(dk.gen_app_key)),
+            ))
+            .execute(c)
+            .await
+            .map_err(|e| Error::from_diesel(e, dev_eui.to_string()))?;
+
+        Ok(())
+    })
+    .await
+}
+
+// Returns the DevEUIs of all device-keys.
+pub async fn get_dev_euis() -> Result<Vec<EUI64>, Error> {
+    device_keys::dsl::device_keys
+        .select(device_keys::dsl::dev_eui)
+        .load(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, "".into()))
+}
+
+// Returns the IDs of the data-encryption keys used by the device root-keys of the
+// given device.
+pub async fn get_dek_ids(dev_eui: &EUI64) -> Result<Vec<i32>, Error> {
+    let keys: (Vec<u8>, Vec<u8>, Vec<u8>) = device_keys::dsl::device_keys
+        .find(&dev_eui)
+        .select((
+            device_keys::nwk_key,
+            device_keys::app_key,
+            device_keys::gen_app_key,
+        ))
+        .first(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, dev_eui.to_string()))?;
+
+    Ok([keys.0, keys.1, keys.2]
+        .iter()
+        .filter_map(|v| encryption::get_dek_id(v))
+        .collect())
+}
+
 #[cfg(test)]
 pub mod test {
     use super::*;
@@ -209,8 +295,47 @@ pub mod test {
         let dk_get = get(&dk.dev_eui).await.unwrap();
         assert_eq!(dk, dk_get);
 
+        // incr join-nonce
+        let dk_incr = incr_join_nonce(dk.dev_eui).await.unwrap();
+        assert_eq!(11, dk_incr.join_nonce);
+
         // delete
         delete(&dk.dev_eui).await.unwrap();
         assert!(delete(&dk.dev_eui).await.is_err());
     }
+
+    #[tokio::test]
+    async fn test_device_keys_encryption() {
+        let _guard = test::prepare().await;
+
+        // plaintext keys
+        let dev_eui = create_device_keys(None).await.dev_eui;
+        update(DeviceKeys {
+            dev_eui,
+            nwk_key: AES128Key::from_bytes([1; 16]),
+            app_key: AES128Key::from_bytes([2; 16]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        assert!(get_dek_ids(&dev_eui).await.unwrap().is_empty());
+
+        // enable encryption and encrypt the existing keys
+        let kek_file = encryption::test::setup_local_kek().await;
+        reencrypt(dev_eui).await.unwrap();
+        assert_eq!(vec![1, 1, 1], get_dek_ids(&dev_eui).await.unwrap());
+
+        let dk = get(&dev_eui).await.unwrap();
+        assert_eq!(AES128Key::from_bytes([1; 16]), dk.nwk_key);
+        assert_eq!(AES128Key::from_bytes([2; 16]), dk.app_key);
+
+        // rotate
+        encryption::create_dek().await.unwrap();
+        reencrypt(dev_eui).await.unwrap();
+        assert_eq!(vec![2, 2, 2], get_dek_ids(&dev_eui).await.unwrap());
+        assert_eq!(dk, get(&dev_eui).await.unwrap());
+
+        encryption::reset().await;
+        std::fs::remove_file(kek_file).unwrap();
+    }
 }
@@ -107,6 +107,7 @@ pub struct DeviceProfile {
     pub firmware_version: String,
     pub vendor_profile_id: i32,
     pub supported_uplink_data_rates: fields::DataRates,
+    pub allow_handover_roaming: bool,
 }
 
 impl DeviceProfile {
@@ -151,6 +152,7 @@ impl Default for DeviceProfile {
             auto_detect_measurements: false,
             region_config_id: None,
             allow_roaming: false,
+            allow_handover_roaming: false,
             rx1_delay: 0,
             abp_params: None,
             class_b_params: None,
@@ -337,6 +339,7 @@ pub async fn update(dp: DeviceProfile) -> Result<DeviceProfile, Error> {
             device_profile::auto_detect_measurements.eq(&dp.auto_detect_measurements),
             device_profile::region_config_id.eq(&dp.region_config_id),
             device_profile::allow_roaming.eq(&dp.allow_roaming),
+            device_profile::allow_handover_roaming.eq(&dp.allow_handover_roaming),
             device_profile::rx1_delay.eq(&dp.rx1_delay),
             device_profile::abp_params.eq(&dp.abp_params),
             device_profile::class_b_params.eq(&dp.class_b_params),
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use diesel::{dsl, prelude::*};
-use diesel_async::RunQueryDsl;
+use diesel_async::{AsyncConnection, RunQueryDsl};
 use tracing::info;
 use uuid::Uuid;
 
@@ -23,6 +23,9 @@ pub struct DeviceQueueItem {
     pub timeout_after: Option<DateTime<Utc>>,
     pub is_encrypted: bool,
     pub expires_at: Option<DateTime<Utc>>,
+    pub priority: i16,
+    pub dedup_key: Option<String>,
+    pub not_before: Option<DateTime<Utc>>,
 }
 
 impl DeviceQueueItem {
@@ -39,6 +42,23 @@ impl DeviceQueueItem {
             ));
         }
 
+        if let Some(not_before) = &self.not_before
+            && let Some(expires_at) = &self.expires_at
+            && not_before >= expires_at
+        {
+            return Err(Error::Validation(
+                "Not before must be before expires at".to_string(),
+            ));
+        }
+
+        if let Some(dedup_key) = &self.dedup_key
+            && (dedup_key.is_empty() || dedup_key.len() > 100)
+        {
+            return Err(Error::Validation(
+                "Dedup key must be between 1 - 100 characters".to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -59,20 +79,72 @@ impl Default for DeviceQueueItem {
             timeout_after: None,
             is_encrypted: false,
             expires_at: None,
+            priority: 0,
+            dedup_key: None,
+            not_before: None,
         }
     }
 }
 
+/// Enqueue the given queue-item.
+/// In case the dedup_key is set, this replaces any non-pending queue-item of the same device
+/// with the same dedup_key.
 pub async fn enqueue_item(qi: DeviceQueueItem) -> Result<DeviceQueueItem, Error> {
-    qi.validate()?;
+    let mut items = enqueue_items(vec![qi]).await?;
+    Ok(items.remove(0))
+}
 
-    let qi: DeviceQueueItem = diesel::insert_into(device_queue_item::table)
-        .values(&qi)
-        .get_result(&mut get_async_db_conn().await?)
-        .await
-        .map_err(|e| Error::from_diesel(e, qi.id.to_string()))?;
-    info!(id = %qi.id, dev_eui = %qi.dev_eui, "Device queue-item enqueued");
-    Ok(qi)
+/// Enqueue the given queue-items within a single transaction. Either all or none of the
+/// queue-items are enqueued.
+/// In case the dedup_key is set, this replaces any non-pending queue-item of the same device
+/// with the same dedup_key.
+pub async fn enqueue_items(items: Vec<DeviceQueueItem>) -> Result<Vec<DeviceQueueItem>, Error> {
+    for qi in &items {
+        qi.validate()?;
+    }
+
+    let mut c = get_async_db_conn().await?;
+    let items: Vec<DeviceQueueItem> = c
+        .transaction::<Vec<DeviceQueueItem>, Error, _>(async |c| {
+            let mut out = Vec::with_capacity(items.len());
+
+            for qi in &items {
+                if let Some(dedup_key) = &qi.dedup_key {
+                    let count = diesel::delete(
+                        device_queue_item::dsl::device_queue_item.filter(
+                            device_queue_item::dev_eui
+                                .eq(&qi.dev_eui)
+                                .and(device_queue_item::dedup_key.eq(dedup_key))
+                                .and(device_queue_item::is_pending.eq(false)),
+                        ),
+                    )
+                    .execute(c)
+                    .await
+                    .map_err(|e| Error::from_diesel(e, qi.dev_eui.to_string()))?;
+
+                    if count > 0 {
+                        info!(dev_eui = %qi.dev_eui, dedup_key = %dedup_key, count = count, "Device queue-items replaced");
+                    }
+                }
+
+                out.push(
+                    diesel::insert_into(device_queue_item::table)
+                        .values(qi)
+                        .get_result(c)
+                        .await
+                        .map_err(|e| Error::from_diesel(e, qi.id.to_string()))?,
+                );
+            }
+
+            Ok(out)
+        })
+        .await?;
+
+    for qi in &items {
+        info!(id = %qi.id, dev_eui = %qi.dev_eui, "Device queue-item enqueued");
+    }
+
+    Ok(items)
 }
 
 pub async fn get_item(id: &Uuid) -> Result<DeviceQueueItem, Error> {
@@ -112,10 +184,21 @@ pub async fn delete_item(id: &Uuid) -> Result<(), Error> {
 }
 
 /// It returns the device queue-item and a bool indicating if there are more items in the queue.
+/// Pending queue-items are returned first, followed by the queue-items ordered by priority
+/// (highest first) and creation time. Queue-items with a not_before in the future are skipped.
 pub async fn get_next_for_dev_eui(dev_eui: &EUI64) -> Result<(DeviceQueueItem, bool), Error> {
     let items: Vec<DeviceQueueItem> = device_queue_item::dsl::device_queue_item
         .filter(device_queue_item::dev_eui.eq(&dev_eui))
-        .order_by(device_queue_item::created_at)
+        .filter(
+            device_queue_item::not_before
+                .is_null()
+                .or(device_queue_item::not_before.le(Utc::now())),
+        )
+        .order_by((
+            device_queue_item::is_pending.desc(),
+            device_queue_item::priority.desc(),
+            device_queue_item::created_at,
+        ))
         .limit(2)
         .load(&mut get_async_db_conn().await?)
         .await
@@ -142,7 +225,11 @@ pub async fn get_next_for_dev_eui(dev_eui: &EUI64) -> Result<(DeviceQueueItem, b
 pub async fn get_for_dev_eui(dev_eui: &EUI64) -> Result<Vec<DeviceQueueItem>, Error> {
     let items = device_queue_item::dsl::device_queue_item
         .filter(device_queue_item::dev_eui.eq(&dev_eui))
-        .order_by(device_queue_item::created_at)
+        .order_by((
+            device_queue_item::is_pending.desc(),
+            device_queue_item::priority.desc(),
+            device_queue_item::created_at,
+        ))
         .load(&mut get_async_db_conn().await?)
         .await
         .map_err(|e| Error::from_diesel(e, dev_eui.to_string()))?;
@@ -263,6 +350,244 @@ pub mod test {
         assert!(delete_item(&qi.id).await.is_err());
     }
 
+    #[tokio::test]
+    async fn test_queue_item_priority() {
+        let _guard = test::prepare().await;
+        let dp = storage::device_profile::test::create_device_profile(None).await;
+        let d = storage::device::test::create_device(
+            EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8]),
+            dp.id.into(),
+            None,
+        )
+        .await;
+
+        let qi_low = enqueue_item(DeviceQueueItem {
+            dev_eui: d.dev_eui,
+            f_port: 10,
+            data: vec![0x01],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let qi_high = enqueue_item(DeviceQueueItem {
+            dev_eui: d.dev_eui,
+            f_port: 10,
+            data: vec![0x02],
+            priority: 10,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        // high priority item is returned first
+        let resp = get_next_for_dev_eui(&d.dev_eui).await.unwrap();
+        assert_eq!(qi_high, resp.0);
+        assert!(resp.1);
+
+        let queue = get_for_dev_eui(&d.dev_eui).await.unwrap();
+        assert_eq!(vec![qi_high.clone(), qi_low.clone()], queue);
+
+        // pending item is always returned first
+        let mut qi_low = qi_low;
+        qi_low.is_pending = true;
+        let qi_low = update_item(qi_low).await.unwrap();
+        let resp = get_next_for_dev_eui(&d.dev_eui).await.unwrap();
+        assert_eq!(qi_low, resp.0);
+    }
+
+    #[tokio::test]
+    async fn test_queue_item_not_before() {
+        let _guard = test::prepare().await;
+        let dp = storage::device_profile::test::create_device_profile(None).await;
+        let d = storage::device::test::create_device(
+            EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8]),
+            dp.id.into(),
+            None,
+        )
+        .await;
+
+        // not_before must be before expires_at
+        let qi = DeviceQueueItem {
+            dev_eui: d.dev_eui,
+            f_port: 10,
+            data: vec![0x01],
+            not_before: Some(Utc::now() + chrono::Duration::hours(2)),
+            expires_at: Some(Utc::now() + chrono::Duration::hours(1)),
+            ..Default::default()
+        };
+        assert!(enqueue_item(qi).await.is_err());
+
+        enqueue_item(DeviceQueueItem {
+            dev_eui: d.dev_eui,
+            f_port: 10,
+            data: vec![0x01],
+            priority: 10,
+            not_before: Some(Utc::now() + chrono::Duration::hours(1)),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        // not_before is in the future
+        assert!(get_next_for_dev_eui(&d.dev_eui).await.is_err());
+
+        // the queue-item without not_before is returned first
+        let qi = enqueue_item(DeviceQueueItem {
+            dev_eui: d.dev_eui,
+            f_port: 10,
+            data: vec![0x02],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let resp = get_next_for_dev_eui(&d.dev_eui).await.unwrap();
+        assert_eq!(qi, resp.0);
+        assert!(!resp.1);
+
+        // both queue-items are returned by get_for_dev_eui
+        assert_eq!(2, get_for_dev_eui(&d.dev_eui).await.unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn test_queue_item_dedup_key() {
+        let _guard = test::prepare().await;
+        let dp = storage::device_profile::test::create_device_profile(None).await;
+        let d = storage::device::test::create_device(
+            EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8]),
+            dp.id.into(),
+            None,
+        )
+        .await;
+
+        // invalid dedup key
+        let qi = DeviceQueueItem {
+            dev_eui: d.dev_eui,
+            f_port: 10,
+            data: vec![0x01],
+            dedup_key: Some("".into()),
+            ..Default::default()
+        };
+        assert!(enqueue_item(qi).await.is_err());
+
+        let qi_1 = enqueue_item(DeviceQueueItem {
+            dev_eui: d.dev_eui,
+            f_port: 10,
+            data: vec![0x01],
+            dedup_key: Some("setpoint".into()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let qi_other = enqueue_item(DeviceQueueItem {
+            dev_eui: d.dev_eui,
+            f_port: 10,
+            data: vec![0x02],
+            dedup_key: Some("interval".into()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        // replaces qi_1
+        let qi_2 = enqueue_item(DeviceQueueItem {
+            dev_eui: d.dev_eui,
+            f_port: 10,
+            data: vec![0x03],
+            dedup_key: Some("setpoint".into()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        assert!(get_item(&qi_1.id).await.is_err());
+        let queue = get_for_dev_eui(&d.dev_eui).await.unwrap();
+        assert_eq!(vec![qi_other.clone(), qi_2.clone()], queue);
+
+        // pending items are not replaced
+        let mut qi_2 = qi_2;
+        qi_2.is_pending = true;
+        let qi_2 = update_item(qi_2).await.unwrap();
+        let qi_3 = enqueue_item(DeviceQueueItem {
+            dev_eui: d.dev_eui,
+            f_port: 10,
+            data: vec![0x04],
+            dedup_key: Some("setpoint".into()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let queue = get_for_dev_eui(&d.dev_eui).await.unwrap();
+        assert_eq!(vec![qi_2, qi_other, qi_3], queue);
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_items() {
+        let _guard = test::prepare().await;
+        let dp = storage::device_profile::test::create_device_profile(None).await;
+        let d1 = storage::device::test::create_device(
+            EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 1]),
+            dp.id.into(),
+            None,
+        )
+        .await;
+        let d2 = storage::device::test::create_device(
+            EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 2]),
+            dp.id.into(),
+            Some(d1.application_id.into()),
+        )
+        .await;
+
+        // one invalid item, nothing is enqueued
+        assert!(
+            enqueue_items(vec![
+                DeviceQueueItem {
+                    dev_eui: d1.dev_eui,
+                    f_port: 10,
+                    data: vec![0x01],
+                    ..Default::default()
+                },
+                DeviceQueueItem {
+                    dev_eui: d2.dev_eui,
+                    f_port: 0,
+                    data: vec![0x01],
+                    ..Default::default()
+                },
+            ])
+            .await
+            .is_err()
+        );
+
+        assert!(get_for_dev_eui(&d1.dev_eui).await.unwrap().is_empty());
+
+        let items = enqueue_items(vec![
+            DeviceQueueItem {
+                dev_eui: d1.dev_eui,
+                f_port: 10,
+                data: vec![0x01],
+                ..Default::default()
+            },
+            DeviceQueueItem {
+                dev_eui: d2.dev_eui,
+                f_port: 10,
+                data: vec![0x01],
+                ..Default::default()
+            },
+        ])
+        .await
+        .unwrap();
+        assert_eq!(
+            vec![items[0].clone()],
+            get_for_dev_eui(&d1.dev_eui).await.unwrap()
+        );
+        assert_eq!(
+            vec![items[1].clone()],
+            get_for_dev_eui(&d2.dev_eui).await.unwrap()
+        );
+    }
+
     #[tokio::test]
     async fn test_flush_queue() {
         let _guard = test::prepare().await;
@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use diesel::{dsl, prelude::*};
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use tracing::info;
+use uuid::Uuid;
+
+use super::error::Error;
+use super::schema::downlink_schedule;
+use super::{device, fields, get_async_db_conn, multicast};
+use crate::config;
+use lrwn::EUI64;
+
+#[derive(Clone, Queryable, QueryableByName, Insertable, Debug, PartialEq, Eq)]
+#[diesel(table_name = downlink_schedule)]
+pub struct DownlinkSchedule {
+    pub id: fields::Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub application_id: fields::Uuid,
+    pub name: String,
+    pub dev_eui: Option<EUI64>,
+    pub multicast_group_id: Option<fields::Uuid>,
+    pub tags: fields::KeyValue,
+    pub cron_expression: String,
+    pub f_port: i16,
+    pub confirmed: bool,
+    pub data: Vec<u8>,
+    pub is_disabled: bool,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub next_run_at: DateTime<Utc>,
+}
+
+impl DownlinkSchedule {
+    fn validate(&self) -> Result<(), Error> {
+        if self.name.is_empty() {
+            return Err(Error::Validation("name is not set".into()));
+        }
+
+        if self.f_port == 0 || self.f_port > 255 {
+            return Err(Error::Validation(
+                "FPort must be between 1 - 255".to_string(),
+            ));
+        }
+
+        let targets = [
+            self.dev_eui.is_some(),
+            self.multicast_group_id.is_some(),
+            !self.tags.is_empty(),
+        ];
+        if targets.iter().filter(|v| **v).count() != 1 {
+            return Err(Error::Validation(
+                "Exactly one of dev_eui, multicast_group_id or tags must be set".to_string(),
+            ));
+        }
+
+        if self.multicast_group_id.is_some() && self.confirmed {
+            return Err(Error::Validation(
+                "Multicast downlinks can not be confirmed".to_string(),
+            ));
+        }
+
+        get_next_run_at(&self.cron_expression, &Utc::now())?;
+
+        Ok(())
+    }
+
+    /// Returns the dedup key that is used for the device queue-items created by this schedule.
+    /// This avoids that the queue of a device grows when it does not receive the
+    /// scheduled downlinks (e.g. a Class-A device that has not sent an uplink).
+    pub fn get_dedup_key(&self) -> String {
+        format!("downlink_schedule:{}", self.id)
+    }
+}
+
+impl Default for DownlinkSchedule {
+    fn default() -> Self {
+        let now = Utc::now();
+
+        DownlinkSchedule {
+            id: Uuid::new_v4().into(),
+            created_at: now,
+            updated_at: now,
+            application_id: Uuid::nil().into(),
+            name: "".into(),
+            dev_eui: None,
+            multicast_group_id: None,
+            tags: fields::KeyValue::new(HashMap::new()),
+            cron_expression: "".into(),
+            f_port: 0,
+            confirmed: false,
+            data: Vec::new(),
+            is_disabled: false,
+            last_run_at: None,
+            next_run_at: now,
+        }
+    }
+}
+
+/// Returns the first time after the given timestamp matching the cron expression.
+/// The cron expression has the format: sec min hour day-of-month month day-of-week [year],
+/// e.g. "0 0 3 * * *" for every day at 03:00:00 UTC.
+pub fn get_next_run_at(
+    cron_expression: &str,
+    after: &DateTime<Utc>,
+) -> Result<DateTime<Utc>, Error> {
+    let schedule = cron::Schedule::from_str(cron_expression)
+        .map_err(|e| Error::Validation(format!("Invalid cron expression: {}", e)))?;
+
+    schedule
+        .after(after)
+        .next()
+        .ok_or_else(|| Error::Validation("Cron expression has no upcoming run".into()))
+}
+
+// Validate that the device or multicast-group target is under the same application.
+async fn validate_target(s: &DownlinkSchedule) -> Result<(), Error> {
+    if let Some(dev_eui) = &s.dev_eui {
+        let d = device::get(dev_eui).await?;
+        if d.application_id != s.application_id {
+            return Err(Error::Validation(
+                "The device must be under the same application".into(),
+            ));
+        }
+    }
+
+    if let Some(multicast_group_id) = &s.multicast_group_id {
+        let mg = multicast::get(multicast_group_id).await?;
+        if mg.application_id != s.application_id {
+            return Err(Error::Validation(
+                "The multicast-group must be under the same application".into(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn create(s: DownlinkSchedule) -> Result<DownlinkSchedule, Error> {
+    s.validate()?;
+    validate_target(&s).await?;
+
+    let mut s = s;
+    s.next_run_at = get_next_run_at(&s.cron_expression, &Utc::now())?;
+
+    let s: DownlinkSchedule = diesel::insert_into(downlink_schedule::table)
+        .values(&s)
+        .get_result(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, s.id.to_string()))?;
+
+    info!(id = %s.id, application_id = %s.application_id, "Downlink schedule created");
+    Ok(s)
+}
+
+pub async fn get(id: &Uuid) -> Result<DownlinkSchedule, Error> {
+    downlink_schedule::dsl::downlink_schedule
+        .find(&fields::Uuid::from(id))
+        .first(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, id.to_string()))
+}
+
+// The next_run_at is re-calculated from the (updated) cron expression.
+pub async fn update(s: DownlinkSchedule) -> Result<DownlinkSchedule, Error> {
+    s.validate()?;
+    validate_target(&s).await?;
+
+    let next_run_at = get_next_run_at(&s.cron_expression, &Utc::now())?;
+
+    let s: DownlinkSchedule = diesel::update(downlink_schedule::dsl::downlink_schedule.find(&s.id))
+        .set((
+            downlink_schedule::updated_at.eq(&Utc::now()),
+            downlink_schedule::name.eq(&s.name),
+            downlink_schedule::dev_eui.eq(&s.dev_eui),
+            downlink_schedule::multicast_group_id.eq(&s.multicast_group_id),
+            downlink_schedule::tags.eq(&s.tags),
+            downlink_schedule::cron_expression.eq(&s.cron_expression),
+            downlink_schedule::f_port.eq(&s.f_port),
+            downlink_schedule::confirmed.eq(&s.confirmed),
+            downlink_schedule::data.eq(&s.data),
+            downlink_schedule::is_disabled.eq(&s.is_disabled),
+            downlink_schedule::next_run_at.eq(&next_run_at),
+        ))
+        .get_result(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, s.id.to_string()))?;
+
+    info!(id = %s.id, "Downlink schedule updated");
+    Ok(s)
+}
+
+pub async fn delete(id: &Uuid) -> Result<(), Error> {
+    let ra =
+        diesel::delete(downlink_schedule::dsl::downlink_schedule.find(&fields::Uuid::from(id)))
+            .execute(&mut get_async_db_conn().await?)
+            .await?;
+    if ra == 0 {
+        return Err(Error::NotFound(id.to_string()));
+    }
+    info!(id = %id, "Downlink schedule deleted");
+    Ok(())
+}
+
+pub async fn get_count(application_id: &Uuid) -> Result<i64, Error> {
+    downlink_schedule::dsl::downlink_schedule
+        .select(dsl::count_star())
+        .filter(downlink_schedule::dsl::application_id.eq(fields::Uuid::from(application_id)))
+        .first(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, "".into()))
+}
+
+pub async fn list(
+    limit: i64,
+    offset: i64,
+    application_id: &Uuid,
+) -> Result<Vec<DownlinkSchedule>, Error> {
+    downlink_schedule::dsl::downlink_schedule
+        .filter(downlink_schedule::dsl::application_id.eq(fields::Uuid::from(application_id)))
+        .order_by(downlink_schedule::dsl::name)
+        .limit(limit)
+        .offset(offset)
+        .load(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, "".into()))
+}
+
+// Selected schedules will automatically have their next_run_at column updated to now + the
+// scheduler lock duration. This is such that concurrent queries will not result in the same
+// schedule being executed twice. After execution, set_run must be called to set the actual
+// next_run_at.
+pub async fn get_schedulable(limit: usize) -> Result<Vec<DownlinkSchedule>> {
+    let mut c = get_async_db_conn().await?;
+    c.transaction::<Vec<DownlinkSchedule>, Error, _>(async |c| {
+        let conf = config::get();
+        diesel::sql_query(if cfg!(feature = "sqlite") {
+            r#"
+                    update
+                        downlink_schedule
+                    set
+                        next_run_at = ?3
+                    where
+                        id in (
+                            select
+                                id
+                            from
+                                downlink_schedule
+                            where
+                                is_disabled = false
+                                and next_run_at <= ?2
+                            order by
+                                next_run_at
+                            limit ?1
+                        )
+                    returning *
+                "#
+        } else {
+            r#"
+                    update
+                        downlink_schedule
+                    set
+                        next_run_at = $3
+                    where
+                        id in (
+                            select
+                                id
+                            from
+                                downlink_schedule
+                            where
+                                is_disabled = false
+                                and next_run_at <= $2
+                            order by
+                                next_run_at
+                            limit $1
+                            for update skip locked
+                        )
+                    returning *
+                "#
+        })
+        .bind::<diesel::sql_types::Integer, _>(limit as i32)
+        .bind::<fields::sql_types::Timestamptz, _>(Utc::now())
+        .bind::<fields::sql_types::Timestamptz, _>(
+            Utc::now()
+                + Duration::from_std(conf.network.scheduler.scheduler_lock_duration).unwrap(),
+        )
+        .load(c)
+        .await
+        .map_err(|e| Error::from_diesel(e, "".into()))
+    })
+    .await
+    .context("Get schedulable downlink schedules")
+}
+
+pub async fn set_run(
+    id: &Uuid,
+    last_run_at: DateTime<Utc>,
+    next_run_at: DateTime<Utc>,
+) -> Result<(), Error> {
+    let ra =
+        diesel::update(downlink_schedule::dsl::downlink_schedule.find(&fields::Uuid::from(id)))
+            .set((
+                downlink_schedule::last_run_at.eq(Some(last_run_at)),
+                downlink_schedule::next_run_at.eq(next_run_at),
+            ))
+            .execute(&mut get_async_db_conn().await?)
+            .await
+            .map_err(|e| Error::from_diesel(e, id.to_string()))?;
+    if ra == 0 {
+        return Err(Error::NotFound(id.to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::storage;
+    use crate::test;
+
+    #[test]
+    fn test_get_next_run_at() {
+        let after = DateTime::parse_from_rfc3339("2026-01-01T10:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            DateTime::parse_from_rfc3339("2026-01-02T03:00:00Z").unwrap(),
+            get_next_run_at("0 0 3 * * *", &after).unwrap()
+        );
+        assert_eq!(
+            DateTime::parse_from_rfc3339("2026-01-01T10:45:00Z").unwrap(),
+            get_next_run_at("0 */15 * * * *", &after).unwrap()
+        );
+        assert!(get_next_run_at("invalid", &after).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_downlink_schedule() {
+        let _guard = test::prepare().await;
+        let t = storage::tenant::test::create_tenant().await;
+        let app = storage::application::test::create_application(Some(t.id.into())).await;
+        let dp = storage::device_profile::test::create_device_profile(Some(t.id.into())).await;
+        let d = storage::device::test::create_device(
+            EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8]),
+            dp.id.into(),
+            Some(app.id.into()),
+        )
+        .await;
+
+        // no target
+        assert!(
+            create(DownlinkSchedule {
+                application_id: app.id,
+                name: "test".into(),
+                cron_expression: "0 0 3 * * *".into(),
+                f_port: 10,
+                ..Default::default()
+            })
+            .await
+            .is_err()
+        );
+
+        // invalid cron expression
+        assert!(
+            create(DownlinkSchedule {
+                application_id: app.id,
+                name: "test".into(),
+                dev_eui: Some(d.dev_eui),
+                cron_expression: "every night".into(),
+                f_port: 10,
+                ..Default::default()
+            })
+            .await
+            .is_err()
+        );
+
+        // create
+        let mut s = create(DownlinkSchedule {
+            application_id: app.id,
+            name: "test".into(),
+            dev_eui: Some(d.dev_eui),
+            cron_expression: "0 0 3 * * *".into(),
+            f_port: 10,
+            data: vec![1, 2, 3],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        assert!(s.next_run_at > Utc::now());
+
+        // get
+        let s_get = get(&s.id).await.unwrap();
+        assert_eq!(s, s_get);
+
+        // update
+        s.name = "test-updated".into();
+        s.cron_expression = "0 */5 * * * *".into();
+        s = update(s).await.unwrap();
+        let s_get = get(&s.id).await.unwrap();
+        assert_eq!(s, s_get);
+
+        // count and list
+        assert_eq!(1, get_count(&app.id).await.unwrap());
+        let items = list(10, 0, &app.id).await.unwrap();
+        assert_eq!(vec![s.clone()], items);
+
+        // not schedulable
+        let items = get_schedulable(10).await.unwrap();
+        assert!(items.is_empty());
+
+        // schedulable
+        set_run(&s.id, Utc::now(), Utc::now() - Duration::seconds(1))
+            .await
+            .unwrap();
+        let items = get_schedulable(10).await.unwrap();
+        assert_eq!(1, items.len());
+        assert!(items[0].next_run_at > Utc::now());
+
+        // locked
+        let items = get_schedulable(10).await.unwrap();
+        assert!(items.is_empty());
+
+        // delete
+        delete(&s.id).await.unwrap();
+        assert!(delete(&s.id).await.is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use diesel::backend::Backend;
 use diesel::{deserialize, serialize};
 #[cfg(feature = "postgres")]
@@ -13,6 +14,15 @@ use serde::{Deserialize, Serialize};
 #[derive(Default)]
 pub struct AppLayerParams {
     pub ts004_session_cnt: [u16; 4],
+    pub ts006_dev_version: Option<Ts006DevVersion>,
+    pub ts009_echo_payload: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Ts006DevVersion {
+    pub fw_version: u32,
+    pub hw_version: u32,
+    pub reported_at: DateTime<Utc>,
 }
 
 #[cfg(feature = "postgres")]
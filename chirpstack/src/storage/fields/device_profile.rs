@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use diesel::backend::Backend;
 use diesel::{deserialize, serialize};
 #[cfg(feature = "postgres")]
@@ -249,9 +251,16 @@ pub struct AppLayerParams {
     pub ts003_version: Option<Ts003Version>,
     pub ts004_version: Option<Ts004Version>,
     pub ts005_version: Option<Ts005Version>,
+    pub ts006_version: Option<Ts006Version>,
+    pub ts007_version: Option<Ts007Version>,
+    pub ts009_version: Option<Ts009Version>,
     pub ts003_f_port: u8,
     pub ts004_f_port: u8,
     pub ts005_f_port: u8,
+    pub ts006_f_port: u8,
+    pub ts007_f_port: u8,
+    pub ts009_f_port: u8,
+    pub packages: HashMap<String, AppLayerPackageParams>,
 }
 
 impl Default for AppLayerParams {
@@ -260,9 +269,16 @@ impl Default for AppLayerParams {
             ts003_version: None,
             ts004_version: None,
             ts005_version: None,
+            ts006_version: None,
+            ts007_version: None,
+            ts009_version: None,
             ts003_f_port: 202,
             ts004_f_port: 201,
             ts005_f_port: 200,
+            ts006_f_port: 203,
+            ts007_f_port: 225,
+            ts009_f_port: 224,
+            packages: HashMap::new(),
         }
     }
 }
@@ -272,9 +288,23 @@ impl AppLayerParams {
         (self.ts003_version.is_some() && self.ts003_f_port == f_port)
             || (self.ts004_version.is_some() && self.ts004_f_port == f_port)
             || (self.ts005_version.is_some() && self.ts005_f_port == f_port)
+            || (self.ts006_version.is_some() && self.ts006_f_port == f_port)
+            || (self.ts007_version.is_some() && self.ts007_f_port == f_port)
+            || (self.ts009_version.is_some() && self.ts009_f_port == f_port)
+            || self.packages.values().any(|p| p.f_port == f_port)
     }
 }
 
+// Settings of an application-layer package that is registered through the
+// applayer package registry (e.g. vendor-specific packages).
+#[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AppLayerPackageParams {
+    pub version: String,
+    pub f_port: u8,
+    pub settings: HashMap<String, String>,
+}
+
 #[cfg(feature = "postgres")]
 impl deserialize::FromSql<Jsonb, Pg> for AppLayerParams {
     fn from_sql(value: <Pg as Backend>::RawValue<'_>) -> deserialize::Result<Self> {
@@ -331,3 +361,21 @@ pub enum Ts005Version {
     V100,
     V200,
 }
+
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum Ts006Version {
+    #[default]
+    V100,
+}
+
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum Ts007Version {
+    #[default]
+    V100,
+}
+
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum Ts009Version {
+    #[default]
+    V100,
+}
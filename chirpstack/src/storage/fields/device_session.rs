@@ -10,6 +10,7 @@ use diesel::sqlite::Sqlite;
 use diesel::{deserialize, serialize};
 use prost::Message;
 
+use crate::encryption;
 use chirpstack_api::internal;
 
 #[derive(Debug, Clone, PartialEq, AsExpression, FromSqlRow)]
@@ -62,14 +63,18 @@ where
     fn from_sql(value: <DB as Backend>::RawValue<'_>) -> deserialize::Result<Self> {
         let bindata = <*const [u8] as deserialize::FromSql<Binary, DB>>::from_sql(value)?;
         let ds = internal::DeviceSession::decode(&mut Cursor::new(unsafe { &*bindata }))?;
-        Ok(DeviceSession(ds))
+        Ok(DeviceSession(encryption::decrypt_device_session(ds)?))
     }
 }
 
 #[cfg(feature = "postgres")]
 impl serialize::ToSql<Binary, Pg> for DeviceSession {
     fn to_sql<'b>(&'b self, out: &mut serialize::Output<'b, '_, Pg>) -> serialize::Result {
-        let encoded = self.encode_to_vec();
+        let encoded = if encryption::is_enabled() {
+            encryption::encrypt_device_session(&self.0)?.encode_to_vec()
+        } else {
+            self.encode_to_vec()
+        };
         <Vec<u8> as serialize::ToSql<Binary, Pg>>::to_sql(&encoded, &mut out.reborrow())
     }
 }
@@ -77,7 +82,11 @@ impl serialize::ToSql<Binary, Pg> for DeviceSession {
 #[cfg(feature = "sqlite")]
 impl serialize::ToSql<Binary, Sqlite> for DeviceSession {
     fn to_sql<'b>(&'b self, out: &mut serialize::Output<'b, '_, Sqlite>) -> serialize::Result {
-        out.set_value(self.encode_to_vec());
+        out.set_value(if encryption::is_enabled() {
+            encryption::encrypt_device_session(&self.0)?.encode_to_vec()
+        } else {
+            self.encode_to_vec()
+        });
         Ok(serialize::IsNull::No)
     }
 }
@@ -0,0 +1,56 @@
+use diesel::backend::Backend;
+#[cfg(feature = "postgres")]
+use diesel::pg::Pg;
+use diesel::sql_types::Binary;
+#[cfg(feature = "sqlite")]
+use diesel::sqlite::Sqlite;
+use diesel::{deserialize, serialize};
+
+use crate::encryption;
+use lrwn::AES128Key;
+
+// EncryptedKey stores the AES128Key encrypted using the active data-encryption key
+// (if encryption at rest is enabled). Plaintext keys are read as-is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow)]
+#[diesel(sql_type = diesel::sql_types::Binary)]
+pub struct EncryptedKey(AES128Key);
+
+impl std::convert::From<AES128Key> for EncryptedKey {
+    fn from(k: AES128Key) -> Self {
+        EncryptedKey(k)
+    }
+}
+
+impl std::convert::From<EncryptedKey> for AES128Key {
+    fn from(k: EncryptedKey) -> Self {
+        k.0
+    }
+}
+
+impl<DB> deserialize::FromSql<Binary, DB> for EncryptedKey
+where
+    DB: Backend,
+    *const [u8]: deserialize::FromSql<Binary, DB>,
+{
+    fn from_sql(value: <DB as Backend>::RawValue<'_>) -> deserialize::Result<Self> {
+        let bindata = <*const [u8] as deserialize::FromSql<Binary, DB>>::from_sql(value)?;
+        let b = encryption::decrypt_key(unsafe { &*bindata })?;
+        Ok(EncryptedKey(AES128Key::from_slice(&b)?))
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl serialize::ToSql<Binary, Pg> for EncryptedKey {
+    fn to_sql<'b>(&'b self, out: &mut serialize::Output<'b, '_, Pg>) -> serialize::Result {
+        let b = encryption::encrypt_key(&self.0.to_bytes())?;
+        <Vec<u8> as serialize::ToSql<Binary, Pg>>::to_sql(&b, &mut out.reborrow())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl serialize::ToSql<Binary, Sqlite> for EncryptedKey {
+    fn to_sql<'b>(&'b self, out: &mut serialize::Output<'b, '_, Sqlite>) -> serialize::Result {
+        out.set_value(encryption::encrypt_key(&self.0.to_bytes())?);
+        Ok(serialize::IsNull::No)
+    }
+}
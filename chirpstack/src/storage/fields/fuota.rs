@@ -92,6 +92,8 @@ pub enum FuotaJob {
     Enqueue,
     FragStatus,
     DeleteMcGroup,
+    UpgradeImage,
+    Reboot,
     Complete,
 }
 
@@ -113,6 +115,8 @@ impl From<&FuotaJob> for String {
             FuotaJob::Enqueue => "ENQUEUE",
             FuotaJob::FragStatus => "FRAG_STATUS",
             FuotaJob::DeleteMcGroup => "DELETE_MC_GROUP",
+            FuotaJob::UpgradeImage => "UPGRADE_IMAGE",
+            FuotaJob::Reboot => "REBOOT",
             FuotaJob::Complete => "COMPLETE",
         }
         .to_string()
@@ -133,6 +137,8 @@ impl TryFrom<&str> for FuotaJob {
             "ENQUEUE" => Self::Enqueue,
             "FRAG_STATUS" => Self::FragStatus,
             "DELETE_MC_GROUP" => Self::DeleteMcGroup,
+            "UPGRADE_IMAGE" => Self::UpgradeImage,
+            "REBOOT" => Self::Reboot,
             "COMPLETE" => Self::Complete,
             _ => return Err(anyhow!("Invalid FuotaJob value: {}", value)),
         })
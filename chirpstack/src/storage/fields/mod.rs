@@ -5,6 +5,7 @@ mod dev_nonces;
 pub mod device;
 pub mod device_profile;
 mod device_session;
+mod encrypted_key;
 mod fuota;
 mod key_value;
 mod measurements;
@@ -16,8 +17,11 @@ pub use big_decimal::BigDecimal;
 pub use data_rates::DataRates;
 pub use dev_add_prefix_vec::DevAddrPrefixVec;
 pub use dev_nonces::DevNonces;
-pub use device_profile::{AbpParams, AppLayerParams, ClassBParams, ClassCParams, RelayParams};
+pub use device_profile::{
+    AbpParams, AppLayerPackageParams, AppLayerParams, ClassBParams, ClassCParams, RelayParams,
+};
 pub use device_session::DeviceSession;
+pub use encrypted_key::EncryptedKey;
 pub use fuota::{FuotaJob, RequestFragmentationSessionStatus};
 pub use key_value::KeyValue;
 pub use measurements::*;
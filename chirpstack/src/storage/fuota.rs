@@ -112,6 +112,8 @@ pub struct FuotaDeploymentDevice {
     pub frag_session_setup_completed_at: Option<DateTime<Utc>>,
     pub frag_status_completed_at: Option<DateTime<Utc>>,
     pub error_msg: String,
+    pub upgrade_image_completed_at: Option<DateTime<Utc>>,
+    pub reboot_completed_at: Option<DateTime<Utc>>,
 }
 
 impl Default for FuotaDeploymentDevice {
@@ -128,6 +130,8 @@ impl Default for FuotaDeploymentDevice {
             frag_session_setup_completed_at: None,
             frag_status_completed_at: None,
             error_msg: "".into(),
+            upgrade_image_completed_at: None,
+            reboot_completed_at: None,
         }
     }
 }
@@ -182,6 +186,18 @@ impl Default for FuotaDeploymentJob {
     }
 }
 
+// Selects the FUOTA steps which are taken into account by set_device_timeout_error
+// and set_device_completed.
+#[derive(Default, Clone, Copy)]
+pub struct DeviceSteps {
+    pub mc_group_setup: bool,
+    pub mc_session: bool,
+    pub frag_session_setup: bool,
+    pub frag_status: bool,
+    pub upgrade_image: bool,
+    pub reboot: bool,
+}
+
 pub async fn create_deployment(d: FuotaDeployment) -> Result<FuotaDeployment, Error> {
     d.validate()?;
 
@@ -489,6 +505,8 @@ pub async fn update_device(d: FuotaDeploymentDevice) -> Result<FuotaDeploymentDe
             .eq(&d.frag_session_setup_completed_at),
         fuota_deployment_device::frag_status_completed_at.eq(&d.frag_status_completed_at),
         fuota_deployment_device::error_msg.eq(&d.error_msg),
+        fuota_deployment_device::upgrade_image_completed_at.eq(&d.upgrade_image_completed_at),
+        fuota_deployment_device::reboot_completed_at.eq(&d.reboot_completed_at),
     ))
     .get_result(&mut get_async_db_conn().await?)
     .await
@@ -526,28 +544,28 @@ pub async fn get_device_count(fuota_deployment_id: Uuid) -> Result<i64, Error> {
         .map_err(|e| Error::from_diesel(e, "".into()))
 }
 
-pub async fn set_device_timeout_error(
-    fuota_deployment_id: Uuid,
-    mc_group_setup_timeout: bool,
-    mc_session_timeout: bool,
-    frag_session_setup_timeout: bool,
-    frag_status_timeout: bool,
-) -> Result<()> {
+pub async fn set_device_timeout_error(fuota_deployment_id: Uuid, steps: DeviceSteps) -> Result<()> {
     let fuota_deployment_id = fields::Uuid::from(fuota_deployment_id);
 
     let mut error_msg = String::new();
-    if mc_group_setup_timeout {
+    if steps.mc_group_setup {
         error_msg = "McGroupSetupReq timeout.".into();
     }
-    if mc_session_timeout {
+    if steps.mc_session {
         error_msg = "McSessionReq timeout".into();
     }
-    if frag_session_setup_timeout {
+    if steps.frag_session_setup {
         error_msg = "FragSessionSetupReq timeout.".into();
     }
-    if frag_status_timeout {
+    if steps.frag_status {
         error_msg = "FragStatusReq timeout.".into();
     }
+    if steps.upgrade_image {
+        error_msg = "DevUpgradeImageReq timeout.".into();
+    }
+    if steps.reboot {
+        error_msg = "DevRebootCountdownReq timeout.".into();
+    }
 
     let mut q = diesel::update(fuota_deployment_device::table)
         .set(fuota_deployment_device::dsl::error_msg.eq(&error_msg))
@@ -555,34 +573,36 @@ pub async fn set_device_timeout_error(
         .filter(fuota_deployment_device::dsl::error_msg.eq(""))
         .into_boxed();
 
-    if mc_group_setup_timeout {
+    if steps.mc_group_setup {
         q = q.filter(fuota_deployment_device::dsl::mc_group_setup_completed_at.is_null());
     }
 
-    if mc_session_timeout {
+    if steps.mc_session {
         q = q.filter(fuota_deployment_device::dsl::mc_session_completed_at.is_null());
     }
 
-    if frag_session_setup_timeout {
+    if steps.frag_session_setup {
         q = q.filter(fuota_deployment_device::dsl::frag_session_setup_completed_at.is_null());
     }
 
-    if frag_status_timeout {
+    if steps.frag_status {
         q = q.filter(fuota_deployment_device::dsl::frag_status_completed_at.is_null());
     }
 
+    if steps.upgrade_image {
+        q = q.filter(fuota_deployment_device::dsl::upgrade_image_completed_at.is_null());
+    }
+
+    if steps.reboot {
+        q = q.filter(fuota_deployment_device::dsl::reboot_completed_at.is_null());
+    }
+
     q.execute(&mut get_async_db_conn().await?).await?;
 
     Ok(())
 }
 
-pub async fn set_device_completed(
-    fuota_deployment_id: Uuid,
-    mc_group_setup_completed: bool,
-    mc_session_completed: bool,
-    frag_session_setup_completed: bool,
-    frag_status_completed: bool,
-) -> Result<()> {
+pub async fn set_device_completed(fuota_deployment_id: Uuid, steps: DeviceSteps) -> Result<()> {
     let fuota_deployment_id = fields::Uuid::from(fuota_deployment_id);
 
     let mut q = diesel::update(fuota_deployment_device::table)
@@ -590,22 +610,30 @@ pub async fn set_device_completed(
         .filter(fuota_deployment_device::dsl::fuota_deployment_id.eq(&fuota_deployment_id))
         .into_boxed();
 
-    if mc_group_setup_completed {
+    if steps.mc_group_setup {
         q = q.filter(fuota_deployment_device::dsl::mc_group_setup_completed_at.is_not_null());
     }
 
-    if mc_session_completed {
+    if steps.mc_session {
         q = q.filter(fuota_deployment_device::dsl::mc_session_completed_at.is_not_null());
     }
 
-    if frag_session_setup_completed {
+    if steps.frag_session_setup {
         q = q.filter(fuota_deployment_device::dsl::frag_session_setup_completed_at.is_not_null());
     }
 
-    if frag_status_completed {
+    if steps.frag_status {
         q = q.filter(fuota_deployment_device::dsl::frag_status_completed_at.is_not_null());
     }
 
+    if steps.upgrade_image {
+        q = q.filter(fuota_deployment_device::dsl::upgrade_image_completed_at.is_not_null());
+    }
+
+    if steps.reboot {
+        q = q.filter(fuota_deployment_device::dsl::reboot_completed_at.is_not_null());
+    }
+
     q.execute(&mut get_async_db_conn().await?).await?;
 
     Ok(())
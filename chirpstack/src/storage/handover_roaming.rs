@@ -0,0 +1,258 @@
+use std::cmp;
+use std::io::Cursor;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use prost::Message;
+use tracing::info;
+
+use super::error::Error;
+use super::passive_roaming::get_full_f_cnt_up;
+use super::{get_async_redis_conn, redis_key};
+use crate::{config, encryption};
+use chirpstack_api::internal;
+use lrwn::{AES128Key, DevAddr, EUI64};
+
+pub async fn save(ds: &internal::HandoverRoamingDeviceSession) -> Result<()> {
+    let dev_eui = EUI64::from_slice(&ds.dev_eui)?;
+    let dev_addr = DevAddr::from_slice(&ds.dev_addr)?;
+
+    // The TTL is capped to the device-session TTL, such that sessions encrypted
+    // using a retired data-encryption key expire within a bounded time.
+    let conf = config::get();
+    let max_ttl = conf.network.device_session_ttl.as_millis() as usize;
+    let ttl = match ds.lifetime {
+        Some(v) => {
+            let lifetime: DateTime<Utc> = v.try_into().map_err(anyhow::Error::msg)?;
+            let lifetime = lifetime - Utc::now();
+            if lifetime.num_milliseconds() <= 0 {
+                return Err(anyhow!("Lifetime of handover-roaming session expired"));
+            }
+            cmp::min(lifetime.num_milliseconds() as usize, max_ttl)
+        }
+        None => max_ttl,
+    };
+
+    let dev_eui_key = redis_key(format!("hr:dev:{{{}}}", dev_eui));
+    let dev_addr_key = redis_key(format!("hr:devaddr:{{{}}}", dev_addr));
+    let b = encrypt_session_keys(ds)?.encode_to_vec();
+
+    // The DevAddr pointer is only needed when ChirpStack is the serving
+    // Network Server, as in this case the session must be retrieved on
+    // uplink (using the MIC validation).
+    let mut pipe = redis::pipe();
+    pipe.atomic()
+        .cmd("PSETEX")
+        .arg(&dev_eui_key)
+        .arg(ttl)
+        .arg(b)
+        .ignore();
+
+    if ds.serving {
+        pipe.cmd("SADD")
+            .arg(&dev_addr_key)
+            .arg(dev_eui.to_string())
+            .ignore()
+            .cmd("PEXPIRE")
+            .arg(&dev_addr_key)
+            .arg(ttl)
+            .ignore();
+    }
+
+    () = pipe.query_async(&mut get_async_redis_conn().await?).await?;
+
+    info!(dev_eui = %dev_eui, dev_addr = %dev_addr, serving = ds.serving, "Handover-roaming device-session saved");
+
+    Ok(())
+}
+
+pub async fn get(dev_eui: &EUI64) -> Result<internal::HandoverRoamingDeviceSession, Error> {
+    let key = redis_key(format!("hr:dev:{{{}}}", dev_eui));
+
+    let v: Vec<u8> = redis::cmd("GET")
+        .arg(key)
+        .query_async(&mut get_async_redis_conn().await?)
+        .await
+        .context("Get handover-roaming device-session")?;
+    if v.is_empty() {
+        return Err(Error::NotFound(dev_eui.to_string()));
+    }
+    let ds = internal::HandoverRoamingDeviceSession::decode(&mut Cursor::new(v))
+        .context("Decode handover-roaming device-session")?;
+    Ok(decrypt_session_keys(ds)?)
+}
+
+pub async fn delete(dev_eui: &EUI64) -> Result<()> {
+    let ds = get(dev_eui).await?;
+    let dev_addr = DevAddr::from_slice(&ds.dev_addr)?;
+
+    let dev_eui_key = redis_key(format!("hr:dev:{{{}}}", dev_eui));
+    let dev_addr_key = redis_key(format!("hr:devaddr:{{{}}}", dev_addr));
+
+    () = redis::pipe()
+        .atomic()
+        .cmd("DEL")
+        .arg(&dev_eui_key)
+        .ignore()
+        .cmd("SREM")
+        .arg(&dev_addr_key)
+        .arg(dev_eui.to_string())
+        .ignore()
+        .query_async(&mut get_async_redis_conn().await?)
+        .await?;
+
+    info!(dev_eui = %dev_eui, "Handover-roaming device-session deleted");
+    Ok(())
+}
+
+// Returns the serving handover-roaming device-session matching the given (received)
+// PhyPayload bytes. On a MIC match, the f_cnt_up of the returned device-session is set
+// to the full frame-counter of the uplink.
+pub async fn get_for_phy_payload(
+    phy_b: &[u8],
+) -> Result<internal::HandoverRoamingDeviceSession, Error> {
+    let phy = lrwn::PhyPayloadRef::from_slice(phy_b)?;
+
+    let (dev_addr, f_cnt_orig) = match (phy.dev_addr(), phy.f_cnt()) {
+        (Some(dev_addr), Some(f_cnt)) => (dev_addr, f_cnt),
+        _ => return Err(Error::InvalidPayload("MacPayload".to_string())),
+    };
+
+    for ds in get_sessions_for_dev_addr(dev_addr).await? {
+        let f_nwk_s_int_key = AES128Key::from_slice(&ds.f_nwk_s_int_key)?;
+        let f_cnt = get_full_f_cnt_up(ds.f_cnt_up, f_cnt_orig);
+
+        let mic_ok = if ds.lorawan_1_1 {
+            phy.validate_uplink_data_micf(f_cnt, &f_nwk_s_int_key)?
+        } else {
+            phy.validate_uplink_data_mic(
+                lrwn::MACVersion::LoRaWAN1_0,
+                f_cnt,
+                0,
+                0,
+                0,
+                &f_nwk_s_int_key,
+                &f_nwk_s_int_key,
+            )?
+        };
+
+        if mic_ok {
+            return Ok(internal::HandoverRoamingDeviceSession {
+                f_cnt_up: f_cnt,
+                ..ds
+            });
+        }
+    }
+
+    Err(Error::NotFound(dev_addr.to_string()))
+}
+
+// The session-keys are encrypted using the active data-encryption key (if
+// encryption at rest is enabled).
+fn encrypt_session_keys(
+    ds: &internal::HandoverRoamingDeviceSession,
+) -> Result<internal::HandoverRoamingDeviceSession> {
+    Ok(internal::HandoverRoamingDeviceSession {
+        f_nwk_s_int_key: encryption::encrypt_key(&ds.f_nwk_s_int_key)?,
+        s_nwk_s_int_key: encryption::encrypt_key(&ds.s_nwk_s_int_key)?,
+        nwk_s_enc_key: encryption::encrypt_key(&ds.nwk_s_enc_key)?,
+        ..ds.clone()
+    })
+}
+
+fn decrypt_session_keys(
+    ds: internal::HandoverRoamingDeviceSession,
+) -> Result<internal::HandoverRoamingDeviceSession> {
+    Ok(internal::HandoverRoamingDeviceSession {
+        f_nwk_s_int_key: encryption::decrypt_key(&ds.f_nwk_s_int_key)?,
+        s_nwk_s_int_key: encryption::decrypt_key(&ds.s_nwk_s_int_key)?,
+        nwk_s_enc_key: encryption::decrypt_key(&ds.nwk_s_enc_key)?,
+        ..ds
+    })
+}
+
+async fn get_sessions_for_dev_addr(
+    dev_addr: DevAddr,
+) -> Result<Vec<internal::HandoverRoamingDeviceSession>> {
+    let key = redis_key(format!("hr:devaddr:{{{}}}", dev_addr));
+
+    let dev_euis: Vec<String> = redis::cmd("SMEMBERS")
+        .arg(key)
+        .query_async(&mut get_async_redis_conn().await?)
+        .await?;
+
+    let mut out: Vec<internal::HandoverRoamingDeviceSession> = Vec::new();
+    for dev_eui in &dev_euis {
+        if let Ok(v) = get(&EUI64::from_str(dev_eui)?).await
+            && v.serving
+            && v.dev_addr == dev_addr.to_vec()
+        {
+            out.push(v);
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::test;
+
+    #[tokio::test]
+    async fn test_handover_roaming_device_session() {
+        let _guard = test::prepare().await;
+
+        let f_nwk_s_int_key = AES128Key::from_bytes([1; 16]);
+        let dev_addr = DevAddr::from_be_bytes([1, 2, 3, 4]);
+        let dev_eui = EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let ds = internal::HandoverRoamingDeviceSession {
+            net_id: vec![1, 2, 3],
+            serving: true,
+            dev_eui: dev_eui.to_vec(),
+            dev_addr: dev_addr.to_vec(),
+            f_nwk_s_int_key: f_nwk_s_int_key.to_vec(),
+            f_cnt_up: 10,
+            ..Default::default()
+        };
+        save(&ds).await.unwrap();
+        assert_eq!(ds, get(&dev_eui).await.unwrap());
+
+        let mut phy = lrwn::PhyPayload {
+            mhdr: lrwn::MHDR {
+                f_type: lrwn::FType::UnconfirmedDataUp,
+                major: lrwn::Major::LoRaWANR1,
+            },
+            payload: lrwn::Payload::MACPayload(lrwn::MACPayload {
+                fhdr: lrwn::FHDR {
+                    devaddr: dev_addr,
+                    f_cnt: 12,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            mic: None,
+        };
+        phy.set_uplink_data_mic(
+            lrwn::MACVersion::LoRaWAN1_0,
+            0,
+            0,
+            0,
+            &f_nwk_s_int_key,
+            &f_nwk_s_int_key,
+        )
+        .unwrap();
+
+        let ds_get = get_for_phy_payload(&phy.to_vec().unwrap()).await.unwrap();
+        assert_eq!(12, ds_get.f_cnt_up);
+
+        // Invalid MIC.
+        phy.mic = Some([0, 0, 0, 0]);
+        assert!(get_for_phy_payload(&phy.to_vec().unwrap()).await.is_err());
+
+        delete(&dev_eui).await.unwrap();
+        assert!(get(&dev_eui).await.is_err());
+    }
+}
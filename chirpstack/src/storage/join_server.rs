@@ -0,0 +1,199 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use diesel::{dsl, prelude::*};
+use diesel_async::RunQueryDsl;
+use tracing::info;
+use uuid::Uuid;
+
+use super::error::Error;
+use super::schema::join_server;
+use super::{fields, get_async_db_conn};
+use lrwn::EUI64Prefix;
+
+#[derive(Clone, Queryable, Insertable, Debug, PartialEq, Eq)]
+#[diesel(table_name = join_server)]
+pub struct JoinServer {
+    pub id: fields::Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub name: String,
+    pub join_eui_prefix: EUI64Prefix,
+    pub server: String,
+    pub async_timeout: i32,
+    pub ca_cert: String,
+    pub tls_cert: String,
+    pub tls_key: String,
+    pub authorization_header: String,
+}
+
+impl JoinServer {
+    fn validate(&self) -> Result<(), Error> {
+        if self.name.is_empty() {
+            return Err(Error::Validation("name is not set".into()));
+        }
+
+        if self.server.is_empty() {
+            return Err(Error::Validation("server is not set".into()));
+        }
+
+        if self.async_timeout < 0 {
+            return Err(Error::Validation(
+                "async_timeout must not be negative".into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for JoinServer {
+    fn default() -> Self {
+        let now = Utc::now();
+
+        JoinServer {
+            id: Uuid::new_v4().into(),
+            created_at: now,
+            updated_at: now,
+            name: "".into(),
+            join_eui_prefix: EUI64Prefix::default(),
+            server: "".into(),
+            async_timeout: 0,
+            ca_cert: "".into(),
+            tls_cert: "".into(),
+            tls_key: "".into(),
+            authorization_header: "".into(),
+        }
+    }
+}
+
+pub async fn create(js: JoinServer) -> Result<JoinServer, Error> {
+    js.validate()?;
+
+    let js: JoinServer = diesel::insert_into(join_server::table)
+        .values(&js)
+        .get_result(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, js.id.to_string()))?;
+
+    info!(id = %js.id, join_eui_prefix = %js.join_eui_prefix, "Join Server created");
+    Ok(js)
+}
+
+pub async fn get(id: &Uuid) -> Result<JoinServer, Error> {
+    join_server::dsl::join_server
+        .find(&fields::Uuid::from(id))
+        .first(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, id.to_string()))
+}
+
+pub async fn update(js: JoinServer) -> Result<JoinServer, Error> {
+    js.validate()?;
+
+    let js: JoinServer = diesel::update(join_server::dsl::join_server.find(&js.id))
+        .set((
+            join_server::updated_at.eq(&Utc::now()),
+            join_server::name.eq(&js.name),
+            join_server::join_eui_prefix.eq(&js.join_eui_prefix),
+            join_server::server.eq(&js.server),
+            join_server::async_timeout.eq(&js.async_timeout),
+            join_server::ca_cert.eq(&js.ca_cert),
+            join_server::tls_cert.eq(&js.tls_cert),
+            join_server::tls_key.eq(&js.tls_key),
+            join_server::authorization_header.eq(&js.authorization_header),
+        ))
+        .get_result(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, js.id.to_string()))?;
+
+    info!(id = %js.id, join_eui_prefix = %js.join_eui_prefix, "Join Server updated");
+    Ok(js)
+}
+
+pub async fn delete(id: &Uuid) -> Result<(), Error> {
+    let ra = diesel::delete(join_server::dsl::join_server.find(&fields::Uuid::from(id)))
+        .execute(&mut get_async_db_conn().await?)
+        .await?;
+    if ra == 0 {
+        return Err(Error::NotFound(id.to_string()));
+    }
+    info!(id = %id, "Join Server deleted");
+    Ok(())
+}
+
+pub async fn get_count() -> Result<i64, Error> {
+    join_server::dsl::join_server
+        .select(dsl::count_star())
+        .first(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, "".into()))
+}
+
+pub async fn list(limit: i64, offset: i64) -> Result<Vec<JoinServer>, Error> {
+    join_server::dsl::join_server
+        .order_by(join_server::dsl::name)
+        .limit(limit)
+        .offset(offset)
+        .load(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, "".into()))
+}
+
+// Returns all Join Servers, this is used to (re)load the Join Server clients.
+pub async fn list_all() -> Result<Vec<JoinServer>, Error> {
+    join_server::dsl::join_server
+        .order_by(join_server::dsl::created_at)
+        .load(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, "".into()))
+}
+
+#[cfg(test)]
+pub mod test {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::test;
+
+    #[tokio::test]
+    async fn test_join_server() {
+        let _guard = test::prepare().await;
+
+        let mut js = create(JoinServer {
+            name: "test-js".into(),
+            join_eui_prefix: EUI64Prefix::from_str("0102030405060700/56").unwrap(),
+            server: "https://example.com".into(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        // get
+        let js_get = get(&js.id).await.unwrap();
+        assert_eq!(js, js_get);
+
+        // update
+        js.name = "test-js-updated".into();
+        js.join_eui_prefix = EUI64Prefix::from_str("0102030405060708/64").unwrap();
+        let js = update(js).await.unwrap();
+        let js_get = get(&js.id).await.unwrap();
+        assert_eq!(js, js_get);
+
+        // invalid
+        let mut js_invalid = js.clone();
+        js_invalid.server = "".into();
+        assert!(update(js_invalid).await.is_err());
+
+        // count and list
+        assert_eq!(1, get_count().await.unwrap());
+        let items = list(10, 0).await.unwrap();
+        assert_eq!(1, items.len());
+        assert_eq!(js.id, items[0].id);
+        assert_eq!(1, list_all().await.unwrap().len());
+
+        // delete
+        delete(&js.id).await.unwrap();
+        assert!(delete(&js.id).await.is_err());
+        assert!(get(&js.id).await.is_err());
+    }
+}
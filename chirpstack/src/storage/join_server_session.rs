@@ -0,0 +1,88 @@
+use std::io::Cursor;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use prost::Message;
+use tracing::info;
+
+use super::{error::Error, get_async_redis_conn, redis_key};
+use crate::encryption;
+use chirpstack_api::internal;
+
+// The AppSKey is encrypted using the active data-encryption key (if encryption at
+// rest is enabled).
+pub async fn save(s: &internal::JoinServerSession, lifetime: Duration) -> Result<()> {
+    let b = internal::JoinServerSession {
+        app_s_key: encryption::encrypt_key(&s.app_s_key)?,
+        ..s.clone()
+    }
+    .encode_to_vec();
+    let session_key_id = hex::encode(&s.session_key_id);
+    let key = redis_key(format!("js:sess:{{{}}}", session_key_id));
+
+    () = redis::cmd("PSETEX")
+        .arg(key)
+        .arg(lifetime.as_millis() as u64)
+        .arg(b)
+        .query_async(&mut get_async_redis_conn().await?)
+        .await?;
+
+    info!(session_key_id = %session_key_id, "Join Server session saved");
+    Ok(())
+}
+
+pub async fn get(session_key_id: &[u8]) -> Result<internal::JoinServerSession, Error> {
+    let session_key_id = hex::encode(session_key_id);
+    let key = redis_key(format!("js:sess:{{{}}}", session_key_id));
+
+    let v: Vec<u8> = redis::cmd("GET")
+        .arg(key)
+        .query_async(&mut get_async_redis_conn().await?)
+        .await
+        .context("Get Join Server session")?;
+    if v.is_empty() {
+        return Err(Error::NotFound(session_key_id));
+    }
+    let mut s = internal::JoinServerSession::decode(&mut Cursor::new(v))
+        .context("Decode Join Server session")?;
+    s.app_s_key = encryption::decrypt_key(&s.app_s_key)?;
+    Ok(s)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::test;
+
+    #[tokio::test]
+    async fn test_join_server_session() {
+        let _guard = test::prepare().await;
+        let s = internal::JoinServerSession {
+            session_key_id: vec![1, 2, 3, 4],
+            net_id: vec![1, 2, 3],
+            dev_eui: vec![1, 2, 3, 4, 5, 6, 7, 8],
+            app_s_key: vec![8; 16],
+        };
+
+        save(&s, Duration::from_secs(60)).await.unwrap();
+        let s_get = get(&[1, 2, 3, 4]).await.unwrap();
+        assert_eq!(s, s_get);
+
+        assert!(get(&[4, 3, 2, 1]).await.is_err());
+
+        // Encrypted at rest.
+        let kek_file = encryption::test::setup_local_kek().await;
+        save(&s, Duration::from_secs(60)).await.unwrap();
+        let b: Vec<u8> = redis::cmd("GET")
+            .arg(redis_key("js:sess:{01020304}".into()))
+            .query_async(&mut get_async_redis_conn().await.unwrap())
+            .await
+            .unwrap();
+        let s_raw = internal::JoinServerSession::decode(&mut Cursor::new(b)).unwrap();
+        assert!(encryption::is_encrypted(&s_raw.app_s_key));
+        assert_eq!(s, get(&[1, 2, 3, 4]).await.unwrap());
+
+        encryption::reset().await;
+        std::fs::remove_file(kek_file).unwrap();
+    }
+}
@@ -15,6 +15,8 @@ use crate::config;
 
 pub mod api_key;
 pub mod application;
+pub mod backend_interfaces_log;
+pub mod data_encryption_key;
 pub mod device;
 pub mod device_gateway;
 pub mod device_keys;
@@ -22,11 +24,15 @@ pub mod device_profile;
 pub mod device_queue;
 pub mod device_session;
 pub mod downlink_frame;
+pub mod downlink_schedule;
 pub mod error;
 pub mod fields;
 pub mod fuota;
 pub mod gateway;
+pub mod handover_roaming;
 pub mod helpers;
+pub mod join_server;
+pub mod join_server_session;
 pub mod mac_command;
 pub mod metrics;
 pub mod multicast;
@@ -34,6 +40,8 @@ pub mod passive_roaming;
 #[cfg(feature = "postgres")]
 mod postgres;
 pub mod relay;
+pub mod roaming_agreement;
+pub mod roaming_settlement;
 pub mod schema;
 #[cfg(feature = "postgres")]
 mod schema_postgres;
@@ -146,6 +154,8 @@ pub async fn setup() -> Result<()> {
             .clone_from(&conf.redis.key_prefix);
     }
 
+    crate::encryption::setup().await?;
+
     Ok(())
 }
 
@@ -8,6 +8,7 @@ use uuid::Uuid;
 use lrwn::region::CommonName;
 use lrwn::{AES128Key, DevAddr, EUI64};
 
+use super::device::DeviceClass;
 use super::error::Error;
 use super::schema::{
     application, device, gateway, multicast_group, multicast_group_device, multicast_group_gateway,
@@ -425,6 +426,20 @@ pub async fn get_dev_euis(group_id: &Uuid) -> Result<Vec<EUI64>, Error> {
         .map_err(|e| Error::from_diesel(e, group_id.to_string()))
 }
 
+pub async fn get_dev_euis_for_enabled_class(
+    group_id: &Uuid,
+    enabled_class: DeviceClass,
+) -> Result<Vec<EUI64>, Error> {
+    multicast_group_device::dsl::multicast_group_device
+        .inner_join(device::table)
+        .select(multicast_group_device::dev_eui)
+        .filter(multicast_group_device::dsl::multicast_group_id.eq(&fields::Uuid::from(group_id)))
+        .filter(device::dsl::enabled_class.eq(enabled_class))
+        .load(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, group_id.to_string()))
+}
+
 pub async fn get_gateway_ids(group_id: &Uuid) -> Result<Vec<EUI64>, Error> {
     multicast_group_gateway::dsl::multicast_group_gateway
         .select(multicast_group_gateway::gateway_id)
@@ -460,39 +475,49 @@ pub async fn enqueue(
                 "B" => {
                     // get ping nb
                     let ping_nb = 1 << (7 - mg.class_b_ping_slot_periodicity) as usize;
-
-                    // get max. gps epoch time.
-                    let res: Option<i64> =
+                    let margin =
+                        Duration::from_std(conf.network.scheduler.multicast_class_b_margin)
+                            .unwrap();
+
+                    // Get the last ping-slot of the already enqueued items. The margin is
+                    // applied between all transmissions, also between those of different
+                    // gateways, such that:
+                    //  * the (SF12) transmissions of different gateways do not overlap and
+                    //    collide at devices which are in range of multiple gateways
+                    //  * each gateway respects the margin between its own transmissions, such
+                    //    that its duty-cycle is not exceeded
+                    let mut last_ping_slot: Option<Duration> =
                         multicast_group_queue_item::dsl::multicast_group_queue_item
-                            .select(dsl::max(
-                                multicast_group_queue_item::dsl::emit_at_time_since_gps_epoch,
-                            ))
+                            .select(multicast_group_queue_item::dsl::emit_at_time_since_gps_epoch)
                             .filter(
                                 multicast_group_queue_item::dsl::multicast_group_id
                                     .eq(&qi.multicast_group_id),
                             )
-                            .first(c)
-                            .await?;
+                            .load::<Option<i64>>(c)
+                            .await?
+                            .into_iter()
+                            .flatten()
+                            .filter_map(Duration::try_milliseconds)
+                            .max();
 
-                    // Get timestamp after which we must generate the next ping-slot.
-                    let ping_slot_after_gps_time = match res {
-                        Some(v) => Duration::try_milliseconds(v).unwrap_or_default(),
-                        None => (Utc::now()
-                            + Duration::from_std(conf.network.scheduler.multicast_class_b_margin)
-                                .unwrap())
-                        .to_gps_time(),
-                    };
+                    for gateway_id in gateway_ids {
+                        // Get timestamp after which we must generate the next ping-slot.
+                        let mut ping_slot_after_gps_time = (Utc::now() + margin).to_gps_time();
+                        if let Some(v) = last_ping_slot {
+                            ping_slot_after_gps_time = ping_slot_after_gps_time.max(v + margin);
+                        }
 
-                    let emit_at_time_since_gps_epoch = classb::get_next_ping_slot_after(
-                        ping_slot_after_gps_time,
-                        &mg.mc_addr,
-                        ping_nb,
-                    )?;
+                        let emit_at_time_since_gps_epoch = classb::get_next_ping_slot_after(
+                            ping_slot_after_gps_time,
+                            &mg.mc_addr,
+                            ping_nb,
+                        )?;
 
-                    let scheduler_run_after_ts = emit_at_time_since_gps_epoch.to_date_time()
-                        - Duration::from_std(2 * conf.network.scheduler.interval).unwrap();
+                        last_ping_slot = Some(emit_at_time_since_gps_epoch);
+
+                        let scheduler_run_after_ts = emit_at_time_since_gps_epoch.to_date_time()
+                            - Duration::from_std(2 * conf.network.scheduler.interval).unwrap();
 
-                    for gateway_id in gateway_ids {
                         let qi = MulticastGroupQueueItem {
                             scheduler_run_after: scheduler_run_after_ts,
                             multicast_group_id: mg.id,
@@ -1222,6 +1247,72 @@ pub mod test {
         assert_eq!(10, qi_get.f_cnt);
         assert_eq!(vec![3, 2, 1], qi_get.data);
 
+        // Enqueue (Class-B) using the same gateway twice, the second transmission must respect
+        // the Class-B margin.
+        let (ids_2, _) = enqueue(
+            MulticastGroupQueueItem {
+                multicast_group_id: mg.id,
+                gateway_id: gw.gateway_id,
+                f_port: 2,
+                data: vec![3, 2, 1],
+                ..Default::default()
+            },
+            &[gw.gateway_id, gw.gateway_id],
+        )
+        .await
+        .unwrap();
+        assert_eq!(2, ids_2.len());
+        let qi_get_1 = get_queue_item(&ids_2[0]).await.unwrap();
+        let qi_get_2 = get_queue_item(&ids_2[1]).await.unwrap();
+        assert!(
+            qi_get_1.emit_at_time_since_gps_epoch.unwrap()
+                > qi_get.emit_at_time_since_gps_epoch.unwrap()
+        );
+        assert!(
+            qi_get_2.emit_at_time_since_gps_epoch.unwrap()
+                - qi_get_1.emit_at_time_since_gps_epoch.unwrap()
+                >= 5000
+        );
+
+        // Enqueue (Class-B) using multiple gateways, the transmissions of the different
+        // gateways must not overlap.
+        let gw_2 = gateway::create(gateway::Gateway {
+            gateway_id: EUI64::from_be_bytes([2, 2, 3, 4, 5, 6, 7, 8]),
+            name: "test-gw-2".into(),
+            tenant_id: t.id,
+            stats_interval_secs: 30,
+            last_seen_at: Some(Utc::now()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let (ids_3, _) = enqueue(
+            MulticastGroupQueueItem {
+                multicast_group_id: mg.id,
+                gateway_id: gw.gateway_id,
+                f_port: 2,
+                data: vec![3, 2, 1],
+                ..Default::default()
+            },
+            &[gw.gateway_id, gw_2.gateway_id],
+        )
+        .await
+        .unwrap();
+        assert_eq!(2, ids_3.len());
+        let qi_get_3 = get_queue_item(&ids_3[0]).await.unwrap();
+        let qi_get_4 = get_queue_item(&ids_3[1]).await.unwrap();
+        assert_eq!(gw_2.gateway_id, qi_get_4.gateway_id);
+        assert!(
+            qi_get_3.emit_at_time_since_gps_epoch.unwrap()
+                - qi_get_2.emit_at_time_since_gps_epoch.unwrap()
+                >= 5000
+        );
+        assert!(
+            qi_get_4.emit_at_time_since_gps_epoch.unwrap()
+                - qi_get_3.emit_at_time_since_gps_epoch.unwrap()
+                >= 5000
+        );
+
         // flush queue
         flush_queue(&mg.id.into()).await.unwrap();
         assert!(delete_queue_item(&ids[0]).await.is_err());
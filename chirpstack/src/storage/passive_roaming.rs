@@ -116,17 +116,17 @@ pub async fn delete(id: Uuid) -> Result<()> {
     Ok(())
 }
 
+// Returns the passive-roaming device-sessions matching the given (received) PhyPayload
+// bytes. The MIC is validated against the borrowed bytes, using the full (32bit)
+// frame-counter of each session.
 pub async fn get_for_phy_payload(
-    phy: &lrwn::PhyPayload,
+    phy_b: &[u8],
 ) -> Result<Vec<internal::PassiveRoamingDeviceSession>, Error> {
-    // Clone the PhyPayload, as we will update the f_cnt to the full (32bit) frame-counter value
-    // for calculating the MIC.
-    let mut phy = phy.clone();
+    let phy = lrwn::PhyPayloadRef::from_slice(phy_b)?;
 
-    let (dev_addr, f_cnt_orig) = if let lrwn::Payload::MACPayload(v) = &phy.payload {
-        (v.fhdr.devaddr, v.fhdr.f_cnt)
-    } else {
-        return Err(Error::InvalidPayload("MacPayload".to_string()));
+    let (dev_addr, f_cnt_orig) = match (phy.dev_addr(), phy.f_cnt()) {
+        (Some(dev_addr), Some(f_cnt)) => (dev_addr, f_cnt),
+        _ => return Err(Error::InvalidPayload("MacPayload".to_string())),
     };
 
     let sessions = get_sessions_for_dev_addr(dev_addr).await?;
@@ -140,17 +140,14 @@ pub async fn get_for_phy_payload(
         }
 
         let f_nwk_s_int_key = AES128Key::from_slice(&ds.f_nwk_s_int_key)?;
-
-        // Set the full frame-counter.
-        if let lrwn::Payload::MACPayload(pl) = &mut phy.payload {
-            pl.fhdr.f_cnt = get_full_f_cnt_up(ds.f_cnt_up, f_cnt_orig);
-        }
+        let f_cnt = get_full_f_cnt_up(ds.f_cnt_up, f_cnt_orig);
 
         let mic_ok = if ds.lorawan_1_1 {
-            phy.validate_uplink_data_micf(&f_nwk_s_int_key)?
+            phy.validate_uplink_data_micf(f_cnt, &f_nwk_s_int_key)?
         } else {
             phy.validate_uplink_data_mic(
                 lrwn::MACVersion::LoRaWAN1_0,
+                f_cnt,
                 0,
                 0,
                 0,
@@ -214,7 +211,7 @@ pub async fn get_session_ids_for_dev_eui(dev_eui: EUI64) -> Result<Vec<Uuid>> {
     Ok(out)
 }
 
-fn get_full_f_cnt_up(next_expected_full_fcnt: u32, truncated_f_cnt: u32) -> u32 {
+pub fn get_full_f_cnt_up(next_expected_full_fcnt: u32, truncated_f_cnt: u32) -> u32 {
     // Handle re-transmission.
     if truncated_f_cnt == (((next_expected_full_fcnt % (1 << 16)) as u16).wrapping_sub(1)) as u32 {
         return next_expected_full_fcnt - 1;
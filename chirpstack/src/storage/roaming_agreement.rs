@@ -0,0 +1,266 @@
+use std::str::FromStr;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use diesel::{dsl, prelude::*};
+use diesel_async::RunQueryDsl;
+use tracing::info;
+use uuid::Uuid;
+
+use super::error::Error;
+use super::schema::roaming_agreement;
+use super::{fields, get_async_db_conn};
+use lrwn::NetID;
+
+#[derive(Clone, Queryable, Insertable, Debug, PartialEq, Eq)]
+#[diesel(table_name = roaming_agreement)]
+pub struct RoamingAgreement {
+    pub net_id: NetID,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub name: String,
+    pub secondary_net_ids: fields::StringVec,
+    pub server: String,
+    pub use_target_role_suffix: bool,
+    pub async_timeout: i32,
+    pub passive_roaming_lifetime: i32,
+    pub passive_roaming_kek_label: String,
+    pub passive_roaming_validate_mic: bool,
+    pub handover_roaming: bool,
+    pub handover_roaming_lifetime: i32,
+    pub handover_roaming_kek_label: String,
+    pub ca_cert: String,
+    pub tls_cert: String,
+    pub tls_key: String,
+    pub authorization_header: String,
+    pub allowed_device_profile_ids: fields::StringVec,
+}
+
+impl RoamingAgreement {
+    fn validate(&self) -> Result<(), Error> {
+        if self.name.is_empty() {
+            return Err(Error::Validation("name is not set".into()));
+        }
+
+        if self.async_timeout < 0
+            || self.passive_roaming_lifetime < 0
+            || self.handover_roaming_lifetime < 0
+        {
+            return Err(Error::Validation(
+                "async_timeout and lifetimes must not be negative".into(),
+            ));
+        }
+
+        self.get_secondary_net_ids()?;
+        self.get_allowed_device_profile_ids()?;
+
+        Ok(())
+    }
+
+    pub fn get_secondary_net_ids(&self) -> Result<Vec<NetID>, Error> {
+        self.secondary_net_ids
+            .iter()
+            .flatten()
+            .map(|v| {
+                NetID::from_str(v)
+                    .map_err(|_| Error::Validation(format!("Invalid secondary NetID: {}", v)))
+            })
+            .collect()
+    }
+
+    pub fn get_allowed_device_profile_ids(&self) -> Result<Vec<Uuid>, Error> {
+        self.allowed_device_profile_ids
+            .iter()
+            .flatten()
+            .map(|v| {
+                Uuid::from_str(v)
+                    .map_err(|_| Error::Validation(format!("Invalid device-profile ID: {}", v)))
+            })
+            .collect()
+    }
+
+    // An empty list means that all device-profiles are allowed.
+    pub fn is_device_profile_allowed(&self, device_profile_id: &Uuid) -> bool {
+        let ids = self.get_allowed_device_profile_ids().unwrap_or_default();
+        ids.is_empty() || ids.contains(device_profile_id)
+    }
+}
+
+impl Default for RoamingAgreement {
+    fn default() -> Self {
+        let now = Utc::now();
+
+        RoamingAgreement {
+            net_id: NetID::default(),
+            created_at: now,
+            updated_at: now,
+            name: "".into(),
+            secondary_net_ids: fields::StringVec::default(),
+            server: "".into(),
+            use_target_role_suffix: false,
+            async_timeout: 0,
+            passive_roaming_lifetime: 0,
+            passive_roaming_kek_label: "".into(),
+            passive_roaming_validate_mic: false,
+            handover_roaming: false,
+            handover_roaming_lifetime: 0,
+            handover_roaming_kek_label: "".into(),
+            ca_cert: "".into(),
+            tls_cert: "".into(),
+            tls_key: "".into(),
+            authorization_header: "".into(),
+            allowed_device_profile_ids: fields::StringVec::default(),
+        }
+    }
+}
+
+pub async fn create(ra: RoamingAgreement) -> Result<RoamingAgreement, Error> {
+    ra.validate()?;
+
+    let ra: RoamingAgreement = diesel::insert_into(roaming_agreement::table)
+        .values(&ra)
+        .get_result(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, ra.net_id.to_string()))?;
+
+    info!(net_id = %ra.net_id, "Roaming agreement created");
+    Ok(ra)
+}
+
+pub async fn get(net_id: &NetID) -> Result<RoamingAgreement, Error> {
+    roaming_agreement::dsl::roaming_agreement
+        .find(net_id)
+        .first(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, net_id.to_string()))
+}
+
+pub async fn update(ra: RoamingAgreement) -> Result<RoamingAgreement, Error> {
+    ra.validate()?;
+
+    let ra: RoamingAgreement =
+        diesel::update(roaming_agreement::dsl::roaming_agreement.find(&ra.net_id))
+            .set((
+                roaming_agreement::updated_at.eq(&Utc::now()),
+                roaming_agreement::name.eq(&ra.name),
+                roaming_agreement::secondary_net_ids.eq(&ra.secondary_net_ids),
+                roaming_agreement::server.eq(&ra.server),
+                roaming_agreement::use_target_role_suffix.eq(&ra.use_target_role_suffix),
+                roaming_agreement::async_timeout.eq(&ra.async_timeout),
+                roaming_agreement::passive_roaming_lifetime.eq(&ra.passive_roaming_lifetime),
+                roaming_agreement::passive_roaming_kek_label.eq(&ra.passive_roaming_kek_label),
+                roaming_agreement::passive_roaming_validate_mic
+                    .eq(&ra.passive_roaming_validate_mic),
+                roaming_agreement::handover_roaming.eq(&ra.handover_roaming),
+                roaming_agreement::handover_roaming_lifetime.eq(&ra.handover_roaming_lifetime),
+                roaming_agreement::handover_roaming_kek_label.eq(&ra.handover_roaming_kek_label),
+                roaming_agreement::ca_cert.eq(&ra.ca_cert),
+                roaming_agreement::tls_cert.eq(&ra.tls_cert),
+                roaming_agreement::tls_key.eq(&ra.tls_key),
+                roaming_agreement::authorization_header.eq(&ra.authorization_header),
+                roaming_agreement::allowed_device_profile_ids.eq(&ra.allowed_device_profile_ids),
+            ))
+            .get_result(&mut get_async_db_conn().await?)
+            .await
+            .map_err(|e| Error::from_diesel(e, ra.net_id.to_string()))?;
+
+    info!(net_id = %ra.net_id, "Roaming agreement updated");
+    Ok(ra)
+}
+
+pub async fn delete(net_id: &NetID) -> Result<(), Error> {
+    let ra = diesel::delete(roaming_agreement::dsl::roaming_agreement.find(net_id))
+        .execute(&mut get_async_db_conn().await?)
+        .await?;
+    if ra == 0 {
+        return Err(Error::NotFound(net_id.to_string()));
+    }
+    info!(net_id = %net_id, "Roaming agreement deleted");
+    Ok(())
+}
+
+pub async fn get_count() -> Result<i64, Error> {
+    roaming_agreement::dsl::roaming_agreement
+        .select(dsl::count_star())
+        .first(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, "".into()))
+}
+
+pub async fn list(limit: i64, offset: i64) -> Result<Vec<RoamingAgreement>, Error> {
+    roaming_agreement::dsl::roaming_agreement
+        .order_by(roaming_agreement::dsl::name)
+        .limit(limit)
+        .offset(offset)
+        .load(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, "".into()))
+}
+
+// Returns all roaming agreements, this is used to (re)load the roaming clients.
+pub async fn list_all() -> Result<Vec<RoamingAgreement>, Error> {
+    roaming_agreement::dsl::roaming_agreement
+        .order_by(roaming_agreement::dsl::net_id)
+        .load(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, "".into()))
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::test;
+
+    #[tokio::test]
+    async fn test_roaming_agreement() {
+        let _guard = test::prepare().await;
+
+        let mut ra = create(RoamingAgreement {
+            net_id: NetID::from_be_bytes([1, 2, 3]),
+            name: "test-ra".into(),
+            server: "https://example.com".into(),
+            secondary_net_ids: fields::StringVec::new(vec![Some("010204".into())]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            vec![NetID::from_be_bytes([1, 2, 4])],
+            ra.get_secondary_net_ids().unwrap()
+        );
+        assert!(ra.is_device_profile_allowed(&Uuid::new_v4()));
+
+        // get
+        let ra_get = get(&ra.net_id).await.unwrap();
+        assert_eq!(ra, ra_get);
+
+        // update
+        let dp_id = Uuid::new_v4();
+        ra.name = "test-ra-updated".into();
+        ra.handover_roaming = true;
+        ra.allowed_device_profile_ids = fields::StringVec::new(vec![Some(dp_id.to_string())]);
+        let ra = update(ra).await.unwrap();
+        let ra_get = get(&ra.net_id).await.unwrap();
+        assert_eq!(ra, ra_get);
+        assert!(ra.is_device_profile_allowed(&dp_id));
+        assert!(!ra.is_device_profile_allowed(&Uuid::new_v4()));
+
+        // invalid secondary NetID
+        let mut ra_invalid = ra.clone();
+        ra_invalid.secondary_net_ids = fields::StringVec::new(vec![Some("foo".into())]);
+        assert!(update(ra_invalid).await.is_err());
+
+        // count and list
+        assert_eq!(1, get_count().await.unwrap());
+        let items = list(10, 0).await.unwrap();
+        assert_eq!(1, items.len());
+        assert_eq!(ra.net_id, items[0].net_id);
+        assert_eq!(1, list_all().await.unwrap().len());
+
+        // delete
+        delete(&ra.net_id).await.unwrap();
+        assert!(delete(&ra.net_id).await.is_err());
+        assert!(get(&ra.net_id).await.is_err());
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt;
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use diesel_async::RunQueryDsl;
+
+use super::error::Error;
+use super::get_async_db_conn;
+use super::schema::roaming_settlement;
+use lrwn::NetID;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Uplink,
+    Downlink,
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Direction::Uplink => write!(f, "uplink"),
+            Direction::Downlink => write!(f, "downlink"),
+        }
+    }
+}
+
+// RoamingSettlement contains the passive-roaming frame counts for the given
+// roaming partner NetID, day (UTC), role of this server (FNS or SNS) and direction.
+#[derive(Clone, Queryable, Insertable, Debug, PartialEq, Eq)]
+#[diesel(table_name = roaming_settlement)]
+pub struct RoamingSettlement {
+    pub net_id: NetID,
+    pub day: NaiveDate,
+    pub role: String,
+    pub direction: String,
+    pub frame_count: i64,
+    pub phy_payload_bytes: i64,
+    pub gw_count: i64,
+}
+
+#[derive(Clone)]
+pub struct Filters {
+    pub net_id: Option<NetID>,
+    // Start and end day (inclusive).
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+// Adds the counts of the given item to the stored counts. In case the item does
+// not yet exist, it is created.
+pub async fn increment(rs: RoamingSettlement) -> Result<(), Error> {
+    diesel::insert_into(roaming_settlement::table)
+        .values(&rs)
+        .on_conflict((
+            roaming_settlement::net_id,
+            roaming_settlement::day,
+            roaming_settlement::role,
+            roaming_settlement::direction,
+        ))
+        .do_update()
+        .set((
+            roaming_settlement::frame_count
+                .eq(roaming_settlement::frame_count + excluded(roaming_settlement::frame_count)),
+            roaming_settlement::phy_payload_bytes.eq(roaming_settlement::phy_payload_bytes
+                + excluded(roaming_settlement::phy_payload_bytes)),
+            roaming_settlement::gw_count
+                .eq(roaming_settlement::gw_count + excluded(roaming_settlement::gw_count)),
+        ))
+        .execute(&mut get_async_db_conn().await?)
+        .await
+        .map_err(|e| Error::from_diesel(e, rs.net_id.to_string()))?;
+
+    Ok(())
+}
+
+pub async fn list(filters: &Filters) -> Result<Vec<RoamingSettlement>, Error> {
+    let mut q = roaming_settlement::dsl::roaming_settlement
+        .filter(roaming_settlement::dsl::day.ge(filters.start))
+        .filter(roaming_settlement::dsl::day.le(filters.end))
+        .into_boxed();
+
+    if let Some(net_id) = &filters.net_id {
+        q = q.filter(roaming_settlement::dsl::net_id.eq(net_id));
+    }
+
+    q.order_by((
+        roaming_settlement::dsl::net_id,
+        roaming_settlement::dsl::day,
+        roaming_settlement::dsl::role,
+        roaming_settlement::dsl::direction,
+    ))
+    .load(&mut get_async_db_conn().await?)
+    .await
+    .map_err(|e| Error::from_diesel(e, "".into()))
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::test;
+
+    #[tokio::test]
+    async fn test_roaming_settlement() {
+        let _guard = test::prepare().await;
+
+        let day1 = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+        let day2 = NaiveDate::from_ymd_opt(2026, 10, 2).unwrap();
+        let net_id1 = NetID::from_be_bytes([1, 2, 3]);
+        let net_id2 = NetID::from_be_bytes([3, 2, 1]);
+
+        for (net_id, day, direction) in [
+            (net_id1, day1, Direction::Uplink),
+            (net_id1, day1, Direction::Uplink),
+            (net_id1, day1, Direction::Downlink),
+            (net_id1, day2, Direction::Uplink),
+            (net_id2, day1, Direction::Uplink),
+        ] {
+            increment(RoamingSettlement {
+                net_id,
+                day,
+                role: "FNS".into(),
+                direction: direction.to_string(),
+                frame_count: 1,
+                phy_payload_bytes: 20,
+                gw_count: 2,
+            })
+            .await
+            .unwrap();
+        }
+
+        let items = list(&Filters {
+            net_id: Some(net_id1),
+            start: day1,
+            end: day1,
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            vec![
+                RoamingSettlement {
+                    net_id: net_id1,
+                    day: day1,
+                    role: "FNS".into(),
+                    direction: "downlink".into(),
+                    frame_count: 1,
+                    phy_payload_bytes: 20,
+                    gw_count: 2,
+                },
+                RoamingSettlement {
+                    net_id: net_id1,
+                    day: day1,
+                    role: "FNS".into(),
+                    direction: "uplink".into(),
+                    frame_count: 2,
+                    phy_payload_bytes: 40,
+                    gw_count: 4,
+                },
+            ],
+            items
+        );
+
+        let items = list(&Filters {
+            net_id: None,
+            start: day1,
+            end: day2,
+        })
+        .await
+        .unwrap();
+        assert_eq!(4, items.len());
+    }
+}
@@ -37,6 +37,40 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    backend_interfaces_log (id) {
+        id -> Uuid,
+        created_at -> Timestamptz,
+        #[max_length = 32]
+        sender_id -> Varchar,
+        #[max_length = 32]
+        receiver_id -> Varchar,
+        transaction_id -> Int8,
+        #[max_length = 50]
+        message_type -> Varchar,
+        #[max_length = 50]
+        result_code -> Varchar,
+        #[max_length = 10]
+        target_role -> Varchar,
+        latency -> Int4,
+        dev_eui -> Nullable<Bytea>,
+        request_body -> Text,
+        request_error -> Text,
+        response_body -> Text,
+    }
+}
+
+diesel::table! {
+    data_encryption_key (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        #[max_length = 100]
+        kek_label -> Varchar,
+        wrapped_key -> Bytea,
+        is_active -> Bool,
+    }
+}
+
 diesel::table! {
     device (dev_eui) {
         dev_eui -> Bytea,
@@ -127,6 +161,7 @@ diesel::table! {
         firmware_version -> Varchar,
         vendor_profile_id -> Int4,
         supported_uplink_data_rates -> Array<Nullable<Int2>>,
+        allow_handover_roaming -> Bool,
     }
 }
 
@@ -212,6 +247,32 @@ diesel::table! {
         timeout_after -> Nullable<Timestamptz>,
         is_encrypted -> Bool,
         expires_at -> Nullable<Timestamptz>,
+        priority -> Int2,
+        #[max_length = 100]
+        dedup_key -> Nullable<Varchar>,
+        not_before -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    downlink_schedule (id) {
+        id -> Uuid,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        application_id -> Uuid,
+        #[max_length = 100]
+        name -> Varchar,
+        dev_eui -> Nullable<Bytea>,
+        multicast_group_id -> Nullable<Uuid>,
+        tags -> Jsonb,
+        #[max_length = 100]
+        cron_expression -> Varchar,
+        f_port -> Int2,
+        confirmed -> Bool,
+        data -> Bytea,
+        is_disabled -> Bool,
+        last_run_at -> Nullable<Timestamptz>,
+        next_run_at -> Timestamptz,
     }
 }
 
@@ -263,6 +324,8 @@ diesel::table! {
         frag_session_setup_completed_at -> Nullable<Timestamptz>,
         frag_status_completed_at -> Nullable<Timestamptz>,
         error_msg -> Text,
+        upgrade_image_completed_at -> Nullable<Timestamptz>,
+        reboot_completed_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -310,6 +373,25 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    join_server (id) {
+        id -> Uuid,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        #[max_length = 100]
+        name -> Varchar,
+        #[max_length = 20]
+        join_eui_prefix -> Varchar,
+        #[max_length = 255]
+        server -> Varchar,
+        async_timeout -> Int4,
+        ca_cert -> Text,
+        tls_cert -> Text,
+        tls_key -> Text,
+        authorization_header -> Text,
+    }
+}
+
 diesel::table! {
     multicast_group (id) {
         id -> Uuid,
@@ -389,6 +471,48 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    roaming_agreement (net_id) {
+        net_id -> Bytea,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        #[max_length = 100]
+        name -> Varchar,
+        secondary_net_ids -> Array<Nullable<Text>>,
+        #[max_length = 255]
+        server -> Varchar,
+        use_target_role_suffix -> Bool,
+        async_timeout -> Int4,
+        passive_roaming_lifetime -> Int4,
+        #[max_length = 100]
+        passive_roaming_kek_label -> Varchar,
+        passive_roaming_validate_mic -> Bool,
+        handover_roaming -> Bool,
+        handover_roaming_lifetime -> Int4,
+        #[max_length = 100]
+        handover_roaming_kek_label -> Varchar,
+        ca_cert -> Text,
+        tls_cert -> Text,
+        tls_key -> Text,
+        authorization_header -> Text,
+        allowed_device_profile_ids -> Array<Nullable<Text>>,
+    }
+}
+
+diesel::table! {
+    roaming_settlement (net_id, day, role, direction) {
+        net_id -> Bytea,
+        day -> Date,
+        #[max_length = 3]
+        role -> Varchar,
+        #[max_length = 10]
+        direction -> Varchar,
+        frame_count -> Int8,
+        phy_payload_bytes -> Int8,
+        gw_count -> Int8,
+    }
+}
+
 diesel::table! {
     tenant (id) {
         id -> Uuid,
@@ -404,6 +528,7 @@ diesel::table! {
         private_gateways_down -> Bool,
         tags -> Jsonb,
         dev_addr_prefixes -> Array<Nullable<Text>>,
+        net_id -> Nullable<Bytea>,
     }
 }
 
@@ -462,6 +587,9 @@ diesel::joinable!(device_profile -> device_profile_device (device_id));
 diesel::joinable!(device_profile -> tenant (tenant_id));
 diesel::joinable!(device_profile_device -> device_profile_vendor (vendor_id));
 diesel::joinable!(device_queue_item -> device (dev_eui));
+diesel::joinable!(downlink_schedule -> application (application_id));
+diesel::joinable!(downlink_schedule -> device (dev_eui));
+diesel::joinable!(downlink_schedule -> multicast_group (multicast_group_id));
 diesel::joinable!(fuota_deployment -> application (application_id));
 diesel::joinable!(fuota_deployment -> device_profile (device_profile_id));
 diesel::joinable!(fuota_deployment_device -> device (dev_eui));
@@ -489,6 +617,8 @@ diesel::allow_tables_to_appear_in_same_query!(
     api_key,
     application,
     application_integration,
+    backend_interfaces_log,
+    data_encryption_key,
     device,
     device_keys,
     device_profile,
@@ -496,17 +626,21 @@ diesel::allow_tables_to_appear_in_same_query!(
     device_profile_template,
     device_profile_vendor,
     device_queue_item,
+    downlink_schedule,
     fuota_deployment,
     fuota_deployment_device,
     fuota_deployment_gateway,
     fuota_deployment_job,
     gateway,
+    join_server,
     multicast_group,
     multicast_group_device,
     multicast_group_gateway,
     multicast_group_queue_item,
     relay_device,
     relay_gateway,
+    roaming_agreement,
+    roaming_settlement,
     tenant,
     tenant_user,
     tenant_user_application,
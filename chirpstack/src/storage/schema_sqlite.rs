@@ -34,6 +34,34 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    backend_interfaces_log (id) {
+        id -> Text,
+        created_at -> TimestamptzSqlite,
+        sender_id -> Text,
+        receiver_id -> Text,
+        transaction_id -> BigInt,
+        message_type -> Text,
+        result_code -> Text,
+        target_role -> Text,
+        latency -> Integer,
+        dev_eui -> Nullable<Binary>,
+        request_body -> Text,
+        request_error -> Text,
+        response_body -> Text,
+    }
+}
+
+diesel::table! {
+    data_encryption_key (id) {
+        id -> Integer,
+        created_at -> TimestamptzSqlite,
+        kek_label -> Text,
+        wrapped_key -> Binary,
+        is_active -> Bool,
+    }
+}
+
 diesel::table! {
     device (dev_eui) {
         dev_eui -> Binary,
@@ -114,6 +142,7 @@ diesel::table! {
         firmware_version -> Text,
         vendor_profile_id -> Integer,
         supported_uplink_data_rates -> Text,
+        allow_handover_roaming -> Bool,
     }
 }
 
@@ -190,6 +219,29 @@ diesel::table! {
         timeout_after -> Nullable<TimestamptzSqlite>,
         is_encrypted -> Bool,
         expires_at -> Nullable<TimestamptzSqlite>,
+        priority -> SmallInt,
+        dedup_key -> Nullable<Text>,
+        not_before -> Nullable<TimestamptzSqlite>,
+    }
+}
+
+diesel::table! {
+    downlink_schedule (id) {
+        id -> Text,
+        created_at -> TimestamptzSqlite,
+        updated_at -> TimestamptzSqlite,
+        application_id -> Text,
+        name -> Text,
+        dev_eui -> Nullable<Binary>,
+        multicast_group_id -> Nullable<Text>,
+        tags -> Text,
+        cron_expression -> Text,
+        f_port -> SmallInt,
+        confirmed -> Bool,
+        data -> Binary,
+        is_disabled -> Bool,
+        last_run_at -> Nullable<TimestamptzSqlite>,
+        next_run_at -> TimestamptzSqlite,
     }
 }
 
@@ -237,6 +289,8 @@ diesel::table! {
         frag_session_setup_completed_at -> Nullable<TimestamptzSqlite>,
         frag_status_completed_at -> Nullable<TimestamptzSqlite>,
         error_msg -> Text,
+        upgrade_image_completed_at -> Nullable<TimestamptzSqlite>,
+        reboot_completed_at -> Nullable<TimestamptzSqlite>,
     }
 }
 
@@ -282,6 +336,22 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    join_server (id) {
+        id -> Text,
+        created_at -> TimestamptzSqlite,
+        updated_at -> TimestamptzSqlite,
+        name -> Text,
+        join_eui_prefix -> Text,
+        server -> Text,
+        async_timeout -> Integer,
+        ca_cert -> Text,
+        tls_cert -> Text,
+        tls_key -> Text,
+        authorization_header -> Text,
+    }
+}
+
 diesel::table! {
     multicast_group (id) {
         id -> Text,
@@ -355,6 +425,42 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    roaming_agreement (net_id) {
+        net_id -> Binary,
+        created_at -> TimestamptzSqlite,
+        updated_at -> TimestamptzSqlite,
+        name -> Text,
+        secondary_net_ids -> Text,
+        server -> Text,
+        use_target_role_suffix -> Bool,
+        async_timeout -> Integer,
+        passive_roaming_lifetime -> Integer,
+        passive_roaming_kek_label -> Text,
+        passive_roaming_validate_mic -> Bool,
+        handover_roaming -> Bool,
+        handover_roaming_lifetime -> Integer,
+        handover_roaming_kek_label -> Text,
+        ca_cert -> Text,
+        tls_cert -> Text,
+        tls_key -> Text,
+        authorization_header -> Text,
+        allowed_device_profile_ids -> Text,
+    }
+}
+
+diesel::table! {
+    roaming_settlement (net_id, day, role, direction) {
+        net_id -> Binary,
+        day -> Date,
+        role -> Text,
+        direction -> Text,
+        frame_count -> BigInt,
+        phy_payload_bytes -> BigInt,
+        gw_count -> BigInt,
+    }
+}
+
 diesel::table! {
     tenant (id) {
         id -> Text,
@@ -369,6 +475,7 @@ diesel::table! {
         private_gateways_down -> Bool,
         tags -> Text,
         dev_addr_prefixes -> Text,
+        net_id -> Nullable<Binary>,
     }
 }
 
@@ -426,6 +533,9 @@ diesel::joinable!(device_profile -> device_profile_device (device_id));
 diesel::joinable!(device_profile -> tenant (tenant_id));
 diesel::joinable!(device_profile_device -> device_profile_vendor (vendor_id));
 diesel::joinable!(device_queue_item -> device (dev_eui));
+diesel::joinable!(downlink_schedule -> application (application_id));
+diesel::joinable!(downlink_schedule -> device (dev_eui));
+diesel::joinable!(downlink_schedule -> multicast_group (multicast_group_id));
 diesel::joinable!(fuota_deployment -> application (application_id));
 diesel::joinable!(fuota_deployment -> device_profile (device_profile_id));
 diesel::joinable!(fuota_deployment_device -> device (dev_eui));
@@ -453,6 +563,8 @@ diesel::allow_tables_to_appear_in_same_query!(
     api_key,
     application,
     application_integration,
+    backend_interfaces_log,
+    data_encryption_key,
     device,
     device_keys,
     device_profile,
@@ -460,17 +572,21 @@ diesel::allow_tables_to_appear_in_same_query!(
     device_profile_template,
     device_profile_vendor,
     device_queue_item,
+    downlink_schedule,
     fuota_deployment,
     fuota_deployment_device,
     fuota_deployment_gateway,
     fuota_deployment_job,
     gateway,
+    join_server,
     multicast_group,
     multicast_group_device,
     multicast_group_gateway,
     multicast_group_queue_item,
     relay_device,
     relay_gateway,
+    roaming_agreement,
+    roaming_settlement,
     tenant,
     tenant_user,
     tenant_user_application,
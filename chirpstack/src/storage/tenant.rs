@@ -15,7 +15,7 @@ use super::schema::{
 };
 use super::{fields, get_async_db_conn};
 use crate::{config, storage};
-use lrwn::EUI64;
+use lrwn::{EUI64, NetID};
 
 #[derive(Queryable, Insertable, PartialEq, Eq, Debug, Clone)]
 #[diesel(table_name = tenant)]
@@ -32,6 +32,7 @@ pub struct Tenant {
     pub private_gateways_down: bool,
     pub tags: fields::KeyValue,
     pub dev_addr_prefixes: fields::DevAddrPrefixVec,
+    pub net_id: Option<NetID>,
 }
 
 impl Tenant {
@@ -40,7 +41,19 @@ impl Tenant {
             return Err(Error::Validation("name is not set".into()));
         }
 
-        let nw_prefixes = config::get().network.get_dev_addr_prefixes();
+        let conf = config::get();
+
+        if let Some(net_id) = &self.net_id
+            && conf.network.net_id != *net_id
+            && !conf.network.secondary_net_ids.contains(net_id)
+        {
+            return Err(Error::Validation(format!(
+                "NetID {} is not configured as (secondary) network NetID",
+                net_id
+            )));
+        }
+
+        let nw_prefixes = self.get_net_id_dev_addr_prefixes();
         for prefix in self.dev_addr_prefixes.deref().iter().flatten() {
             let mut is_valid = false;
 
@@ -68,11 +81,29 @@ impl Tenant {
             .flatten()
             .collect();
         if prefixes.is_empty() {
-            config::get().network.get_dev_addr_prefixes()
+            self.get_net_id_dev_addr_prefixes()
         } else {
             prefixes
         }
     }
+
+    // Returns the DevAddr space of the NetID of the tenant. In case the tenant is
+    // bound to a secondary NetID, this returns the DevAddr prefix of this NetID,
+    // else the configured network DevAddr prefixes are returned.
+    fn get_net_id_dev_addr_prefixes(&self) -> Vec<lrwn::DevAddrPrefix> {
+        let conf = config::get();
+
+        match &self.net_id {
+            Some(v) if *v != conf.network.net_id => vec![v.dev_addr_prefix()],
+            _ => conf.network.get_dev_addr_prefixes(),
+        }
+    }
+
+    // Returns the NetID of the tenant, falling back to the configured network NetID
+    // in case the tenant is not bound to a NetID.
+    pub fn get_net_id(&self) -> NetID {
+        self.net_id.unwrap_or(config::get().network.net_id)
+    }
 }
 
 impl Default for Tenant {
@@ -92,6 +123,7 @@ impl Default for Tenant {
             private_gateways_down: false,
             tags: fields::KeyValue::new(HashMap::new()),
             dev_addr_prefixes: fields::DevAddrPrefixVec::new(vec![]),
+            net_id: None,
         }
     }
 }
@@ -226,6 +258,26 @@ pub async fn get_for_application_id(app_id: Uuid) -> Result<Tenant, Error> {
     Ok(t)
 }
 
+// Returns the NetIDs to which tenants are bound. Tenants which are not bound
+// to a NetID use the configured network NetID, which is always included.
+pub async fn get_net_ids() -> Result<Vec<NetID>, Error> {
+    let net_ids: Vec<Option<NetID>> = tenant::dsl::tenant
+        .select(tenant::dsl::net_id)
+        .filter(tenant::dsl::net_id.is_not_null())
+        .distinct()
+        .load(&mut get_async_db_conn().await?)
+        .await?;
+
+    let mut out = vec![config::get().network.net_id];
+    for net_id in net_ids.into_iter().flatten() {
+        if !out.contains(&net_id) {
+            out.push(net_id);
+        }
+    }
+
+    Ok(out)
+}
+
 pub async fn update(t: Tenant) -> Result<Tenant, Error> {
     t.validate()?;
 
@@ -241,6 +293,7 @@ pub async fn update(t: Tenant) -> Result<Tenant, Error> {
             tenant::private_gateways_down.eq(&t.private_gateways_down),
             tenant::tags.eq(&t.tags),
             tenant::dev_addr_prefixes.eq(&t.dev_addr_prefixes),
+            tenant::net_id.eq(&t.net_id),
         ))
         .get_result(&mut get_async_db_conn().await?)
         .await
@@ -707,6 +760,7 @@ pub mod test {
             private_gateways_down: true,
             tags: fields::KeyValue::new(HashMap::new()),
             dev_addr_prefixes: fields::DevAddrPrefixVec::new(vec![]),
+            net_id: None,
         };
         create(t).await.unwrap()
     }
@@ -993,4 +1047,100 @@ pub mod test {
             .get_dev_addr_prefixes()
         );
     }
+
+    #[tokio::test]
+    async fn test_net_id() {
+        let _guard = test::prepare().await;
+
+        let mut conf = (*config::get()).clone();
+        conf.network.net_id = NetID::from_be_bytes([0, 0, 1]);
+        conf.network.secondary_net_ids = vec![NetID::from_be_bytes([0, 0, 2])];
+        config::set(conf);
+
+        // validate
+        assert!(
+            Tenant {
+                name: "test-tenant".into(),
+                net_id: Some(NetID::from_be_bytes([0, 0, 2])),
+                ..Default::default()
+            }
+            .validate()
+            .is_ok()
+        );
+        assert!(
+            Tenant {
+                name: "test-tenant".into(),
+                net_id: Some(NetID::from_be_bytes([0, 0, 3])),
+                ..Default::default()
+            }
+            .validate()
+            .is_err(),
+            "000003 is not a configured NetID"
+        );
+        assert!(
+            Tenant {
+                name: "test-tenant".into(),
+                net_id: Some(NetID::from_be_bytes([0, 0, 2])),
+                dev_addr_prefixes: DevAddrPrefixVec::new(vec![Some(
+                    NetID::from_be_bytes([0, 0, 1]).dev_addr_prefix()
+                )]),
+                ..Default::default()
+            }
+            .validate()
+            .is_err(),
+            "DevAddr prefix is not within the DevAddr space of NetID 000002"
+        );
+
+        // get net_id
+        assert_eq!(
+            NetID::from_be_bytes([0, 0, 1]),
+            Tenant::default().get_net_id()
+        );
+        assert_eq!(
+            NetID::from_be_bytes([0, 0, 2]),
+            Tenant {
+                net_id: Some(NetID::from_be_bytes([0, 0, 2])),
+                ..Default::default()
+            }
+            .get_net_id()
+        );
+
+        // get net_ids
+        assert_eq!(
+            vec![NetID::from_be_bytes([0, 0, 1])],
+            get_net_ids().await.unwrap()
+        );
+        create(Tenant {
+            name: "test-tenant".into(),
+            net_id: Some(NetID::from_be_bytes([0, 0, 2])),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            vec![
+                NetID::from_be_bytes([0, 0, 1]),
+                NetID::from_be_bytes([0, 0, 2])
+            ],
+            get_net_ids().await.unwrap()
+        );
+
+        // get dev_addr prefixes
+        assert_eq!(
+            vec![NetID::from_be_bytes([0, 0, 2]).dev_addr_prefix()],
+            Tenant {
+                net_id: Some(NetID::from_be_bytes([0, 0, 2])),
+                ..Default::default()
+            }
+            .get_dev_addr_prefixes()
+        );
+        assert_eq!(
+            vec![NetID::from_be_bytes([0, 0, 1]).dev_addr_prefix()],
+            Tenant {
+                net_id: Some(NetID::from_be_bytes([0, 0, 1])),
+                ..Default::default()
+            }
+            .get_dev_addr_prefixes()
+        );
+    }
 }
@@ -1,15 +1,23 @@
+use std::str::FromStr;
+
 use anyhow::Result;
+use chrono::{TimeDelta, Utc};
 use prost::Message;
 use tokio::sync::mpsc::{self, Sender};
+use tokio::time::sleep;
 use tracing::error;
 
 use crate::config;
-use crate::storage::{get_async_redis_conn, redis_key};
+use crate::helpers::errors::PrintFullError;
+use crate::storage::{backend_interfaces_log, get_async_redis_conn, redis_key};
 use chirpstack_api::stream;
+use lrwn::EUI64;
 
 pub async fn get_log_sender() -> Option<Sender<stream::BackendInterfacesRequest>> {
     let conf = config::get();
-    if conf.monitoring.backend_interfaces_log_max_history == 0 {
+    if conf.monitoring.backend_interfaces_log_max_history == 0
+        && conf.monitoring.backend_interfaces_log_retention.is_zero()
+    {
         return None;
     }
 
@@ -31,6 +39,13 @@ pub async fn get_log_sender() -> Option<Sender<stream::BackendInterfacesRequest>
 pub async fn log_request(pl: stream::BackendInterfacesRequest) -> Result<()> {
     let conf = config::get();
 
+    // A database error must not prevent the request from being added to the stream.
+    if !conf.monitoring.backend_interfaces_log_retention.is_zero()
+        && let Err(e) = persist_request(&pl).await
+    {
+        error!(error = %e.full(), "Persist Backend Interfaces request error");
+    }
+
     if conf.monitoring.backend_interfaces_log_max_history == 0 {
         return Ok(());
     }
@@ -50,3 +65,165 @@ pub async fn log_request(pl: stream::BackendInterfacesRequest) -> Result<()> {
 
     Ok(())
 }
+
+// Periodically removes the persisted Backend Interfaces requests which are older
+// than the configured retention.
+pub async fn retention_loop() {
+    let conf = config::get();
+    let retention = conf.monitoring.backend_interfaces_log_retention;
+
+    loop {
+        if let Ok(retention) = TimeDelta::from_std(retention)
+            && let Err(e) = backend_interfaces_log::delete_before(Utc::now() - retention).await
+        {
+            error!(error = %e.full(), "Delete Backend Interfaces log items error");
+        }
+
+        sleep(std::time::Duration::from_secs(60 * 60)).await;
+    }
+}
+
+async fn persist_request(pl: &stream::BackendInterfacesRequest) -> Result<()> {
+    backend_interfaces_log::create(backend_interfaces_log::BackendInterfacesLog {
+        created_at: match &pl.time {
+            Some(v) => (*v).try_into().map_err(anyhow::Error::msg)?,
+            None => Utc::now(),
+        },
+        sender_id: pl.sender_id.clone(),
+        receiver_id: pl.receiver_id.clone(),
+        transaction_id: pl.transaction_id.into(),
+        message_type: pl.message_type.clone(),
+        result_code: pl.result_code.clone(),
+        target_role: pl.target_role.clone(),
+        latency: pl.latency as i32,
+        dev_eui: get_dev_eui(&pl.request_body).or_else(|| get_dev_eui(&pl.response_body)),
+        request_body: redact_key_envelopes(&pl.request_body),
+        request_error: pl.request_error.clone(),
+        response_body: redact_key_envelopes(&pl.response_body),
+        ..Default::default()
+    })
+    .await?;
+
+    Ok(())
+}
+
+// Returns the DevEUI from the given JSON payload (if any). Besides the top-level
+// DevEUI, this also looks at the DevEUI within the UL / DL meta-data (e.g. in case
+// of a PRStartReq or XmitDataReq).
+fn get_dev_eui(body: &str) -> Option<EUI64> {
+    let v: serde_json::Value = serde_json::from_str(body).ok()?;
+    [
+        v.get("DevEUI"),
+        v.get("ULMetaData").and_then(|v| v.get("DevEUI")),
+        v.get("DLMetaData").and_then(|v| v.get("DevEUI")),
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(|v| EUI64::from_str(v.as_str()?).ok())
+}
+
+// Returns the given JSON payload with the AES keys of all key-envelopes removed,
+// such that session-keys are not persisted in plain-text. Non-JSON payloads are
+// returned as-is.
+fn redact_key_envelopes(body: &str) -> String {
+    fn redact(v: &mut serde_json::Value) {
+        match v {
+            serde_json::Value::Object(m) => {
+                for (k, v) in m.iter_mut() {
+                    if k == "AESKey" {
+                        *v = serde_json::Value::String("".into());
+                    } else {
+                        redact(v);
+                    }
+                }
+            }
+            serde_json::Value::Array(a) => a.iter_mut().for_each(redact),
+            _ => {}
+        }
+    }
+
+    let mut v: serde_json::Value = match serde_json::from_str(body) {
+        Ok(v) => v,
+        Err(_) => return body.to_string(),
+    };
+    redact(&mut v);
+    serde_json::to_string(&v).unwrap_or_else(|_| body.to_string())
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::test;
+
+    #[test]
+    fn test_get_dev_eui() {
+        assert_eq!(
+            Some(EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8])),
+            get_dev_eui(r#"{"MessageType":"HomeNSReq","DevEUI":"0102030405060708"}"#)
+        );
+        assert_eq!(
+            Some(EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8])),
+            get_dev_eui(
+                r#"{"MessageType":"PRStartReq","ULMetaData":{"DevEUI":"0102030405060708"}}"#
+            )
+        );
+        assert_eq!(
+            Some(EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8])),
+            get_dev_eui(
+                r#"{"MessageType":"XmitDataReq","DLMetaData":{"DevEUI":"0102030405060708"}}"#
+            )
+        );
+        assert_eq!(None, get_dev_eui(r#"{"MessageType":"XmitDataReq"}"#));
+        assert_eq!(None, get_dev_eui(""));
+    }
+
+    #[test]
+    fn test_redact_key_envelopes() {
+        let body = redact_key_envelopes(
+            r#"{"MessageType":"PRStartAns","AppSKey":{"KEKLabel":"kek","AESKey":"01020304050607080102030405060708"},"Lifetime":60}"#,
+        );
+        let v: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!("", v["AppSKey"]["AESKey"]);
+        assert_eq!("kek", v["AppSKey"]["KEKLabel"]);
+        assert_eq!(60, v["Lifetime"]);
+
+        assert_eq!("invalid", redact_key_envelopes("invalid"));
+        assert_eq!("", redact_key_envelopes(""));
+    }
+
+    #[tokio::test]
+    async fn test_log_request() {
+        let _guard = test::prepare().await;
+
+        let mut conf = (*config::get()).clone();
+        conf.monitoring.backend_interfaces_log_max_history = 0;
+        conf.monitoring.backend_interfaces_log_retention = std::time::Duration::from_secs(3600);
+        config::set(conf);
+
+        log_request(stream::BackendInterfacesRequest {
+            sender_id: "010203".into(),
+            receiver_id: "0102030405060708".into(),
+            transaction_id: 1234,
+            message_type: "HomeNSReq".into(),
+            result_code: "Success".into(),
+            request_body: r#"{"DevEUI":"0807060504030201"}"#.into(),
+            latency: 25,
+            time: Some(Utc::now().into()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let items = backend_interfaces_log::list(10, 0, &Default::default())
+            .await
+            .unwrap();
+        assert_eq!(1, items.len());
+        assert_eq!("010203", items[0].sender_id);
+        assert_eq!(1234, items[0].transaction_id);
+        assert_eq!(25, items[0].latency);
+        assert_eq!(
+            Some(EUI64::from_be_bytes([8, 7, 6, 5, 4, 3, 2, 1])),
+            items[0].dev_eui
+        );
+    }
+}
@@ -0,0 +1,374 @@
+use std::str::FromStr;
+
+use bytes::Bytes;
+use chrono::Utc;
+use httpmock::prelude::*;
+use uuid::Uuid;
+
+use crate::api::backend as backend_api;
+use crate::backend::{joinserver, roaming};
+use crate::gateway::backend as gateway_backend;
+use crate::integration::mock;
+use crate::storage::{
+    application,
+    device::{self, DeviceClass},
+    device_profile, gateway, handover_roaming, tenant,
+};
+use crate::{config, integration, test, uplink};
+use chirpstack_api::{common, gw, integration as integration_pb, internal};
+use lrwn::region::CommonName;
+use lrwn::{AES128Key, EUI64, NetID};
+
+#[tokio::test]
+async fn test_sns_uplink() {
+    let _guard = test::prepare().await;
+
+    let hns_mock = MockServer::start();
+
+    let mut conf = (*config::get()).clone();
+
+    // Set NetID.
+    conf.network.net_id = NetID::from_str("000202").unwrap();
+
+    // Set roaming agreement.
+    conf.roaming.servers.push(config::RoamingServer {
+        net_id: NetID::from_str("000505").unwrap(),
+        server: hns_mock.url("/"),
+        ..Default::default()
+    });
+
+    config::set(conf);
+    joinserver::setup().await.unwrap();
+    roaming::setup().await.unwrap();
+
+    let t = tenant::create(tenant::Tenant {
+        name: "tenant".into(),
+        can_have_gateways: true,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let gw = gateway::create(gateway::Gateway {
+        name: "gateway".into(),
+        tenant_id: t.id,
+        gateway_id: EUI64::from_str("0102030405060708").unwrap(),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let dev_eui = EUI64::from_be_bytes([2, 2, 3, 4, 5, 6, 7, 8]);
+    let mut dev_addr = lrwn::DevAddr::from_be_bytes([0, 0, 0, 0]);
+    dev_addr.set_dev_addr_prefix(NetID::from_str("000505").unwrap().dev_addr_prefix());
+    let f_nwk_s_int_key =
+        AES128Key::from_bytes([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]);
+
+    // The device has been handed over to us by the home Network Server.
+    handover_roaming::save(&internal::HandoverRoamingDeviceSession {
+        net_id: vec![0, 5, 5],
+        serving: true,
+        dev_eui: dev_eui.to_vec(),
+        dev_addr: dev_addr.to_vec(),
+        f_nwk_s_int_key: f_nwk_s_int_key.to_vec(),
+        s_nwk_s_int_key: f_nwk_s_int_key.to_vec(),
+        nwk_s_enc_key: f_nwk_s_int_key.to_vec(),
+        f_cnt_up: 8,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let recv_time = Utc::now();
+
+    let rx_info = gw::UplinkRxInfo {
+        gateway_id: gw.gateway_id.to_string(),
+        gw_time: Some(recv_time.into()),
+        location: Some(common::Location {
+            latitude: 0.0,
+            longitude: 0.0,
+            altitude: 0.0,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let mut tx_info = gw::UplinkTxInfo {
+        frequency: 868100000,
+        ..Default::default()
+    };
+    uplink::helpers::set_uplink_modulation("eu868", &mut tx_info, 0).unwrap();
+
+    let mut data_phy = lrwn::PhyPayload {
+        mhdr: lrwn::MHDR {
+            f_type: lrwn::FType::UnconfirmedDataUp,
+            major: lrwn::Major::LoRaWANR1,
+        },
+        payload: lrwn::Payload::MACPayload(lrwn::MACPayload {
+            fhdr: lrwn::FHDR {
+                devaddr: dev_addr,
+                f_ctrl: Default::default(),
+                f_cnt: 8,
+                f_opts: lrwn::MACCommandSet::new(vec![]),
+            },
+            f_port: Some(10),
+            frm_payload: Some(lrwn::FRMPayload::Raw(vec![1, 2, 3, 4])),
+        }),
+        mic: None,
+    };
+    data_phy
+        .set_uplink_data_mic(
+            lrwn::MACVersion::LoRaWAN1_0,
+            0,
+            0,
+            0,
+            &f_nwk_s_int_key,
+            &f_nwk_s_int_key,
+        )
+        .unwrap();
+
+    // Setup hns mock. The FRMPayload is forwarded as-is, as the serving Network Server
+    // does not have the AppSKey.
+    let mut hns_xmit_data_req_mock = hns_mock.mock(|when, then| {
+        when.method(POST)
+            .path("/")
+            .json_body_obj(&backend::XmitDataReqPayload {
+                base: backend::BasePayload {
+                    sender_id: vec![0, 2, 2],
+                    receiver_id: vec![0, 5, 5],
+                    message_type: backend::MessageType::XmitDataReq,
+                    transaction_id: 1234,
+                    ..Default::default()
+                },
+                frm_payload: vec![1, 2, 3, 4],
+                ul_meta_data: Some(backend::ULMetaData {
+                    dev_eui: dev_eui.to_vec(),
+                    dev_addr: dev_addr.to_vec(),
+                    f_port: Some(10),
+                    f_cnt_up: Some(8),
+                    confirmed: Some(false),
+                    data_rate: Some(0),
+                    ul_freq: Some(868.1),
+                    recv_time,
+                    rf_region: "EU868".to_string(),
+                    gw_cnt: Some(1),
+                    gw_info: roaming::rx_info_to_gw_info("EU868", &[rx_info.clone()]).unwrap(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+
+        then.json_body_obj(&backend::XmitDataAnsPayload {
+            base: backend::BasePayloadResult {
+                base: backend::BasePayload {
+                    sender_id: vec![0, 5, 5],
+                    receiver_id: vec![0, 2, 2],
+                    message_type: backend::MessageType::XmitDataAns,
+                    transaction_id: 1234,
+                    ..Default::default()
+                },
+                result: backend::ResultPayload {
+                    result_code: backend::ResultCode::Success,
+                    ..Default::default()
+                },
+            },
+        })
+        .status(200);
+    });
+
+    gateway_backend::set_backend("eu868", Box::new(gateway_backend::mock::Backend {})).await;
+
+    // Simulate uplink
+    uplink::handle_uplink(
+        CommonName::EU868,
+        "eu868",
+        Uuid::new_v4(),
+        gw::UplinkFrameSet {
+            phy_payload: data_phy.to_vec().unwrap(),
+            tx_info: Some(tx_info),
+            rx_info: vec![rx_info],
+        },
+    )
+    .await
+    .unwrap();
+
+    hns_xmit_data_req_mock.assert();
+    hns_xmit_data_req_mock.delete();
+
+    // The next expected frame-counter has been stored.
+    let ds = handover_roaming::get(&dev_eui).await.unwrap();
+    assert_eq!(9, ds.f_cnt_up);
+
+    joinserver::reset().await;
+}
+
+#[tokio::test]
+async fn test_hns_uplink() {
+    let _guard = test::prepare().await;
+    let sns_mock = MockServer::start();
+    let mut conf = (*config::get()).clone();
+
+    // Set NetID.
+    conf.network.net_id = NetID::from_str("000505").unwrap();
+
+    // Set roaming agreement.
+    conf.roaming.servers.push(config::RoamingServer {
+        net_id: NetID::from_str("000202").unwrap(),
+        server: sns_mock.url("/"),
+        ..Default::default()
+    });
+
+    config::set(conf);
+    joinserver::setup().await.unwrap();
+    roaming::setup().await.unwrap();
+    integration::set_mock().await;
+    mock::reset().await;
+
+    let t = tenant::create(tenant::Tenant {
+        name: "tenant".into(),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let app = application::create(application::Application {
+        name: "app".into(),
+        tenant_id: t.id,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let dp = device_profile::create(device_profile::DeviceProfile {
+        name: "dp".into(),
+        tenant_id: Some(t.id),
+        region: lrwn::region::CommonName::EU868,
+        mac_version: lrwn::region::MacVersion::LORAWAN_1_0_2,
+        reg_params_revision: lrwn::region::Revision::A,
+        supports_otaa: true,
+        allow_roaming: true,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let mut dev_addr = lrwn::DevAddr::from_be_bytes([0, 0, 0, 0]);
+    dev_addr.set_dev_addr_prefix(NetID::from_str("000505").unwrap().dev_addr_prefix());
+    let app_s_key = AES128Key::from_bytes([16, 15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1]);
+
+    let dev = device::create(device::Device {
+        name: "device".into(),
+        application_id: app.id,
+        device_profile_id: dp.id,
+        dev_eui: EUI64::from_be_bytes([2, 2, 3, 4, 5, 6, 7, 8]),
+        enabled_class: DeviceClass::A,
+        dev_addr: Some(dev_addr),
+        f_cnt_up: 8,
+        device_session: Some(
+            internal::DeviceSession {
+                mac_version: common::MacVersion::Lorawan104.into(),
+                dev_addr: dev_addr.to_vec(),
+                f_nwk_s_int_key: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
+                s_nwk_s_int_key: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
+                nwk_s_enc_key: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
+                app_s_key: Some(common::KeyEnvelope {
+                    kek_label: "".into(),
+                    aes_key: app_s_key.to_vec(),
+                }),
+                region_config_id: "eu868".into(),
+                ..Default::default()
+            }
+            .into(),
+        ),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    // The device has been handed over to the serving Network Server.
+    handover_roaming::save(&internal::HandoverRoamingDeviceSession {
+        net_id: vec![0, 2, 2],
+        serving: false,
+        dev_eui: dev.dev_eui.to_vec(),
+        dev_addr: dev_addr.to_vec(),
+        f_cnt_up: 8,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let xmit_data_req = backend::XmitDataReqPayload {
+        base: backend::BasePayload {
+            sender_id: vec![0, 2, 2],
+            receiver_id: vec![0, 5, 5],
+            message_type: backend::MessageType::XmitDataReq,
+            transaction_id: 1234,
+            ..Default::default()
+        },
+        frm_payload: lrwn::encrypt_frm_payload(&app_s_key, true, &dev_addr, 8, &[1, 2, 3, 4])
+            .unwrap(),
+        ul_meta_data: Some(backend::ULMetaData {
+            dev_eui: dev.dev_eui.to_vec(),
+            dev_addr: dev_addr.to_vec(),
+            f_port: Some(10),
+            f_cnt_up: Some(8),
+            confirmed: Some(false),
+            data_rate: Some(0),
+            ul_freq: Some(868.1),
+            recv_time: Utc::now(),
+            rf_region: "EU868".to_string(),
+            gw_cnt: Some(1),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    // Uplink.
+    let resp =
+        backend_api::handle_request(Bytes::from(serde_json::to_string(&xmit_data_req).unwrap()))
+            .await;
+    let resp_b = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let xmit_data_ans: backend::XmitDataAnsPayload = serde_json::from_slice(&resp_b).unwrap();
+    assert_eq!(
+        backend::ResultCode::Success,
+        xmit_data_ans.base.result.result_code
+    );
+
+    let up_event = mock::get_uplink_event().await.unwrap();
+    assert_eq!(vec![1, 2, 3, 4], up_event.data);
+    assert_eq!(8, up_event.f_cnt);
+    assert_eq!(10, up_event.f_port);
+
+    let ds = handover_roaming::get(&dev.dev_eui).await.unwrap();
+    assert_eq!(9, ds.f_cnt_up);
+    let d = device::get(&dev.dev_eui).await.unwrap();
+    assert_eq!(9, d.f_cnt_up);
+
+    // Replay, the frame-counter did not increment.
+    mock::reset().await;
+    let resp =
+        backend_api::handle_request(Bytes::from(serde_json::to_string(&xmit_data_req).unwrap()))
+            .await;
+    let resp_b = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let xmit_data_ans: backend::XmitDataAnsPayload = serde_json::from_slice(&resp_b).unwrap();
+    assert_eq!(
+        backend::ResultCode::Other,
+        xmit_data_ans.base.result.result_code
+    );
+
+    assert!(mock::get_uplink_event().await.is_none());
+    let log_event = mock::get_log_event().await.unwrap();
+    assert_eq!(
+        integration_pb::LogCode::UplinkFCntRetransmission,
+        log_event.code()
+    );
+
+    let ds = handover_roaming::get(&dev.dev_eui).await.unwrap();
+    assert_eq!(9, ds.f_cnt_up);
+
+    joinserver::reset().await;
+}
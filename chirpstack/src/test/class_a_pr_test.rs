@@ -329,6 +329,22 @@ async fn test_sns_uplink() {
         },
     };
 
+    // PRStartReq with unknown ReceiverID.
+    let mut pr_start_req_unknown = pr_start_req.clone();
+    pr_start_req_unknown.base.receiver_id = vec![0, 6, 6];
+    let resp = backend_api::handle_request(Bytes::from(
+        serde_json::to_string(&pr_start_req_unknown).unwrap(),
+    ))
+    .await;
+    let resp_b = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let pr_start_ans: backend::PRStartAnsPayload = serde_json::from_slice(&resp_b).unwrap();
+    assert_eq!(
+        backend::ResultCode::UnknownReceiver,
+        pr_start_ans.base.result.result_code
+    );
+
     // Setup downlink xmit mock.
     let mut fns_xmit_data_req_mock = fns_mock.mock(|when, then| {
         when.method(POST)
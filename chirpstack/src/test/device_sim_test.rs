@@ -0,0 +1,212 @@
+use uuid::Uuid;
+
+use super::assert;
+use crate::downlink::tx_ack;
+use crate::gateway::backend as gateway_backend;
+use crate::storage::{
+    application,
+    device::{self, DeviceClass},
+    device_keys, device_profile, device_queue, gateway, tenant,
+};
+use crate::{integration, test, uplink};
+use chirpstack_api::gw;
+use lrwn::device::{Activation, Config, Device, Downlink, Uplink};
+use lrwn::region::CommonName;
+use lrwn::{AES128Key, EUI64};
+
+// Returns the UplinkFrame as it would be published by the gateway.
+fn get_uplink_frame(gateway_id: EUI64, up: &Uplink) -> gw::UplinkFrame {
+    let mut tx_info = gw::UplinkTxInfo {
+        frequency: up.frequency,
+        ..Default::default()
+    };
+    uplink::helpers::set_uplink_modulation("eu868", &mut tx_info, up.dr).unwrap();
+
+    gw::UplinkFrame {
+        phy_payload: up.phy_payload.to_vec().unwrap(),
+        tx_info: Some(tx_info),
+        rx_info: Some(gw::UplinkRxInfo {
+            gateway_id: gateway_id.to_string(),
+            location: Some(Default::default()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+// Sends the uplink of the simulated device to ChirpStack and returns the downlink (if any)
+// as received in the RX1 window. The transmission of the downlink is acknowledged.
+async fn send_uplink(gateway_id: EUI64, dev: &mut Device, up: &Uplink) -> Option<Downlink> {
+    gateway_backend::mock::reset().await;
+
+    let uf = get_uplink_frame(gateway_id, up);
+    uplink::handle_uplink(
+        CommonName::EU868,
+        "eu868",
+        Uuid::new_v4(),
+        gw::UplinkFrameSet {
+            phy_payload: uf.phy_payload,
+            tx_info: uf.tx_info,
+            rx_info: uf.rx_info.into_iter().collect(),
+        },
+    )
+    .await
+    .unwrap();
+
+    let df = gateway_backend::mock::get_downlink_frames()
+        .await
+        .first()
+        .cloned()?;
+
+    let rx_windows = dev.rx_windows(up).unwrap();
+    let item = df.items.first().unwrap();
+    assert_eq!(
+        rx_windows[0].frequency,
+        item.tx_info.as_ref().unwrap().frequency
+    );
+
+    tx_ack::TxAck::handle(gw::DownlinkTxAck {
+        gateway_id: df.gateway_id.clone(),
+        downlink_id: df.downlink_id,
+        items: df
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, _)| gw::DownlinkTxAckItem {
+                status: if i == 0 {
+                    gw::TxAckStatus::Ok.into()
+                } else {
+                    gw::TxAckStatus::Ignored.into()
+                },
+            })
+            .collect(),
+        ..Default::default()
+    })
+    .await;
+
+    Some(dev.handle_downlink(&item.phy_payload).unwrap())
+}
+
+#[tokio::test]
+async fn test_device_sim() {
+    let _guard = test::prepare().await;
+
+    integration::set_mock().await;
+    gateway_backend::set_backend("eu868", Box::new(gateway_backend::mock::Backend {})).await;
+
+    let t = tenant::create(tenant::Tenant {
+        name: "tenant".into(),
+        can_have_gateways: true,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let gw = gateway::create(gateway::Gateway {
+        name: "gateway".into(),
+        tenant_id: t.id,
+        gateway_id: EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8]),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let app = application::create(application::Application {
+        name: "app".into(),
+        tenant_id: t.id,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let dp = device_profile::create(device_profile::DeviceProfile {
+        name: "dp".into(),
+        tenant_id: Some(t.id),
+        region: CommonName::EU868,
+        mac_version: lrwn::region::MacVersion::LORAWAN_1_0_3,
+        reg_params_revision: lrwn::region::Revision::A,
+        supports_otaa: true,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let dev = device::create(device::Device {
+        name: "device".into(),
+        application_id: app.id,
+        device_profile_id: dp.id,
+        dev_eui: EUI64::from_be_bytes([2, 2, 3, 4, 5, 6, 7, 8]),
+        enabled_class: DeviceClass::A,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let dk = device_keys::create(device_keys::DeviceKeys {
+        dev_eui: dev.dev_eui,
+        nwk_key: AES128Key::from_bytes([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let mut sim = Device::new(Config {
+        dev_eui: dev.dev_eui,
+        activation: Activation::Otaa {
+            join_eui: EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8]),
+            app_key: dk.nwk_key,
+        },
+        region: CommonName::EU868,
+        dr: 0,
+        battery: 254,
+        margin: 5,
+    })
+    .unwrap();
+
+    // OTAA.
+    let up = sim.join_request().unwrap();
+    let dn = send_uplink(gw.gateway_id, &mut sim, &up).await.unwrap();
+    assert!(matches!(dn, Downlink::JoinAccept { .. }));
+
+    let d = device::get(&dev.dev_eui).await.unwrap();
+    assert_eq!(sim.get_dev_addr(), d.dev_addr);
+
+    // Confirmed downlink, sent as response to a confirmed uplink.
+    let qi = device_queue::enqueue_item(device_queue::DeviceQueueItem {
+        dev_eui: dev.dev_eui,
+        f_port: 10,
+        confirmed: true,
+        data: vec![1, 2, 3],
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let up = sim.uplink(1, &[1, 2, 3], true).unwrap();
+    match send_uplink(gw.gateway_id, &mut sim, &up).await.unwrap() {
+        Downlink::Data {
+            f_port,
+            data,
+            confirmed,
+            ack,
+            ..
+        } => {
+            assert_eq!(Some(10), f_port);
+            assert_eq!(vec![1, 2, 3], data);
+            assert!(confirmed);
+            assert!(ack);
+        }
+        _ => panic!("Expected Data"),
+    }
+    assert!(!sim.is_confirmed_uplink_pending());
+    assert::f_cnt_up(dev.dev_eui, 1)().await;
+
+    let qi = device_queue::get_item(&qi.id).await.unwrap();
+    assert!(qi.is_pending);
+
+    // The next uplink acknowledges the confirmed downlink.
+    let up = sim.uplink(1, &[], false).unwrap();
+    send_uplink(gw.gateway_id, &mut sim, &up).await;
+    assert::f_cnt_up(dev.dev_eui, 2)().await;
+    assert!(device_queue::get_item(&qi.id).await.is_err());
+}
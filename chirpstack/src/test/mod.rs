@@ -5,10 +5,12 @@ use std::time::Duration;
 use crate::{adr, config, region, storage};
 
 mod assert;
+mod class_a_hr_test;
 mod class_a_pr_test;
 mod class_a_test;
 mod class_b_test;
 mod class_c_test;
+mod device_sim_test;
 mod multicast_test;
 mod otaa_js_test;
 mod otaa_pr_test;
@@ -22,7 +22,7 @@ use crate::storage::{
 };
 use crate::{codec, config, downlink, integration, maccommand, region, stream};
 use chirpstack_api::{common, integration as integration_pb, internal, stream as stream_pb};
-use lrwn::{AES128Key, EUI64};
+use lrwn::{AES128Key, EUI64, NetID};
 
 pub struct Data {
     uplink_frame_set: UplinkFrameSet,
@@ -230,6 +230,7 @@ impl Data {
         match device::get_for_phypayload_and_incr_f_cnt_up(
             &self.uplink_frame_set.region_config_id,
             false,
+            &self.uplink_frame_set.phy_payload_b,
             &mut self.phy_payload,
             self.uplink_frame_set.dr,
             self.uplink_frame_set.ch as u8,
@@ -296,9 +297,14 @@ impl Data {
             dr,
         )? as u8;
 
+        // The relayed PhyPayload is decoded from the (decrypted) FRMPayload of the Relay
+        // uplink, there are no received bytes which can be borrowed.
+        let phy_b = self.phy_payload.to_vec()?;
+
         match device::get_for_phypayload_and_incr_f_cnt_up(
             &self.uplink_frame_set.region_config_id,
             true,
+            &phy_b,
             &mut self.phy_payload,
             dr,
             ch,
@@ -380,6 +386,14 @@ impl Data {
             if !dp.allow_roaming {
                 return Err(Error::RoamingIsNotAllowed);
             }
+
+            if let Some(roaming_meta_data) = &self.uplink_frame_set.roaming_meta_data {
+                let sender_id = NetID::from_slice(&roaming_meta_data.base_payload.sender_id)
+                    .map_err(anyhow::Error::from)?;
+                if !roaming::is_device_profile_allowed(sender_id, &dp.id.into()) {
+                    return Err(Error::RoamingIsNotAllowed);
+                }
+            }
         }
 
         Ok(())
@@ -694,13 +708,31 @@ impl Data {
             let locked = pl.fhdr.f_ctrl.class_b;
             enabled_class = match locked {
                 true => DeviceClass::B,
-                false => DeviceClass::A,
+                // A Class-C device does not set the Class-B bit. Only when the device was
+                // operating as Class-B device, we must fall back to Class-A.
+                false if dev.enabled_class == DeviceClass::B => DeviceClass::A,
+                false => dev.enabled_class,
             };
         }
 
         // Update if the enabled class has changed.
         if dev.enabled_class != enabled_class {
+            let previous_class = dev.enabled_class;
+            dev.enabled_class = enabled_class;
+
+            // Reset the scheduler_run_after such that pending queue-items are re-scheduled
+            // using the new device-class.
             self.device_changeset.enabled_class = Some(enabled_class);
+            self.device_changeset.scheduler_run_after = Some(None);
+
+            maccommand::device_mode_ind::class_change_event(
+                self.tenant.as_ref().unwrap(),
+                self.application.as_ref().unwrap(),
+                dp,
+                dev,
+                previous_class,
+            )
+            .await;
         }
 
         Ok(())
@@ -3,11 +3,11 @@ use chrono::{Duration, Utc};
 use tracing::{Instrument, Level, error, info, span, trace};
 use uuid::Uuid;
 
-use super::{UplinkFrameSet, error::Error, filter_rx_info_by_public_only};
+use super::{UplinkFrameSet, error::Error, filter_rx_info_by_public_only, get_sender_net_id};
 use crate::api::backend::get_async_receiver;
 use crate::backend::{keywrap, roaming};
 use crate::helpers::errors::PrintFullError;
-use crate::storage::passive_roaming;
+use crate::storage::{error::Error as StorageError, handover_roaming, passive_roaming};
 use crate::uplink::helpers;
 use chirpstack_api::internal;
 use lrwn::NetID;
@@ -15,6 +15,7 @@ use lrwn::NetID;
 pub struct Data {
     uplink_frame_set: UplinkFrameSet,
     mac_payload: lrwn::MACPayload,
+    sender_net_id: Option<NetID>,
     pr_device_sessions: Vec<internal::PassiveRoamingDeviceSession>,
 }
 
@@ -37,10 +38,13 @@ impl Data {
         let mut ctx = Data {
             uplink_frame_set: ufs,
             mac_payload: mac_pl,
+            sender_net_id: None,
             pr_device_sessions: Vec::new(),
         };
 
         ctx.filter_rx_info_by_public_only()?;
+        ctx.get_sender_net_id().await?;
+        ctx.handle_handover_roaming_device().await?;
         ctx.get_pr_device_sessions().await?;
         ctx.start_pr_sessions().await?;
         ctx.forward_uplink_for_sessions().await?;
@@ -55,10 +59,100 @@ impl Data {
         Ok(())
     }
 
+    async fn get_sender_net_id(&mut self) -> Result<()> {
+        trace!("Getting sender netid");
+        self.sender_net_id = Some(get_sender_net_id(&self.uplink_frame_set).await?);
+        Ok(())
+    }
+
+    // Returns the BasePayload with the SenderID set to the NetID of the tenant
+    // owning the receiving gateway(s).
+    fn get_base_payload(&self) -> backend::BasePayload {
+        backend::BasePayload {
+            sender_id: self.sender_net_id.unwrap().to_vec(),
+            ..Default::default()
+        }
+    }
+
+    // In case of a handed-over device, we are the serving Network Server and the uplink is
+    // forwarded as FRMPayload to the home Network Server. No passive-roaming is needed.
+    async fn handle_handover_roaming_device(&mut self) -> Result<()> {
+        trace!("Getting handover-roaming device-session");
+        let mut ds =
+            match handover_roaming::get_for_phy_payload(&self.uplink_frame_set.phy_payload_b).await
+            {
+                Ok(v) => v,
+                Err(StorageError::NotFound(_)) => return Ok(()),
+                Err(e) => return Err(e.into()),
+            };
+
+        // Only application payloads are forwarded, mac-commands are terminated by
+        // the serving Network Server.
+        if self.mac_payload.f_port.unwrap_or(0) == 0 {
+            ds.f_cnt_up += 1;
+            handover_roaming::save(&ds).await?;
+            return Err(Error::Abort.into());
+        }
+
+        let rf_region = self
+            .uplink_frame_set
+            .region_common_name
+            .to_string()
+            .replace('_', "-");
+        let mut req = backend::XmitDataReqPayload {
+            base: self.get_base_payload(),
+            frm_payload: match &self.mac_payload.frm_payload {
+                Some(lrwn::FRMPayload::Raw(b)) => b.clone(),
+                _ => Vec::new(),
+            },
+            ul_meta_data: Some(backend::ULMetaData {
+                dev_eui: ds.dev_eui.clone(),
+                dev_addr: self.mac_payload.fhdr.devaddr.to_vec(),
+                f_port: self.mac_payload.f_port,
+                f_cnt_up: Some(ds.f_cnt_up),
+                confirmed: Some(
+                    self.uplink_frame_set.phy_payload.mhdr.f_type == lrwn::FType::ConfirmedDataUp,
+                ),
+                data_rate: Some(self.uplink_frame_set.dr),
+                ul_freq: Some((self.uplink_frame_set.tx_info.frequency as f64) / 1_000_000.0),
+                recv_time: helpers::get_rx_timestamp_chrono(&self.uplink_frame_set.rx_info_set),
+                rf_region: rf_region.clone(),
+                gw_cnt: Some(self.uplink_frame_set.rx_info_set.len()),
+                gw_info: roaming::rx_info_to_gw_info(
+                    &rf_region,
+                    &self.uplink_frame_set.rx_info_set,
+                )?,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let net_id = NetID::from_slice(&ds.net_id)?;
+        let client = roaming::get(&net_id).await?;
+        let async_receiver = match client.is_async() {
+            false => None,
+            true => {
+                Some(get_async_receiver(req.base.transaction_id, client.get_async_timeout()).await?)
+            }
+        };
+
+        if let Err(e) = client
+            .xmit_data_req(backend::Role::HNS, &mut req, async_receiver)
+            .await
+        {
+            error!(net_id = %net_id, error = %e.full(), "XmitDataReq failed");
+        }
+
+        ds.f_cnt_up += 1;
+        handover_roaming::save(&ds).await?;
+
+        Err(Error::Abort.into())
+    }
+
     async fn get_pr_device_sessions(&mut self) -> Result<()> {
         trace!("Getting passive-roaming device-sessions");
         self.pr_device_sessions =
-            passive_roaming::get_for_phy_payload(&self.uplink_frame_set.phy_payload).await?;
+            passive_roaming::get_for_phy_payload(&self.uplink_frame_set.phy_payload_b).await?;
 
         for ds in &mut self.pr_device_sessions {
             ds.f_cnt_up = self.mac_payload.fhdr.f_cnt + 1;
@@ -111,7 +205,8 @@ impl Data {
                 .to_string()
                 .replace('_', "-");
             let mut req = backend::XmitDataReqPayload {
-                phy_payload: self.uplink_frame_set.phy_payload.to_vec()?,
+                base: self.get_base_payload(),
+                phy_payload: self.uplink_frame_set.phy_payload_b.clone(),
                 ul_meta_data: Some(backend::ULMetaData {
                     dev_addr: self.mac_payload.fhdr.devaddr.to_vec(),
                     data_rate: Some(self.uplink_frame_set.dr),
@@ -137,11 +232,24 @@ impl Data {
                 ),
             };
 
-            if let Err(e) = client
+            match client
                 .xmit_data_req(backend::Role::SNS, &mut req, async_receiver)
                 .await
             {
-                error!(net_id = %net_id, error = %e.full(), "XmitDataReq failed");
+                Ok(_) => {
+                    if let Some(ul_meta_data) = &req.ul_meta_data {
+                        roaming::record_uplink(
+                            net_id,
+                            backend::Role::FNS,
+                            &req.phy_payload,
+                            ul_meta_data,
+                        )
+                        .await;
+                    }
+                }
+                Err(e) => {
+                    error!(net_id = %net_id, error = %e.full(), "XmitDataReq failed");
+                }
             }
         }
 
@@ -170,7 +278,8 @@ impl Data {
             .replace('_', "-");
 
         let mut pr_req = backend::PRStartReqPayload {
-            phy_payload: self.uplink_frame_set.phy_payload.to_vec()?,
+            base: self.get_base_payload(),
+            phy_payload: self.uplink_frame_set.phy_payload_b.clone(),
             ul_meta_data: backend::ULMetaData {
                 ul_freq: Some((self.uplink_frame_set.tx_info.frequency as f64) / 1_000_000.0),
                 data_rate: Some(self.uplink_frame_set.dr),
@@ -183,7 +292,6 @@ impl Data {
                 )?,
                 ..Default::default()
             },
-            ..Default::default()
         };
 
         #[cfg(test)]
@@ -202,6 +310,14 @@ impl Data {
         let pr_start_ans = client
             .pr_start_req(backend::Role::SNS, &mut pr_req, async_receiver)
             .await?;
+        roaming::record_uplink(
+            net_id,
+            backend::Role::FNS,
+            &pr_req.phy_payload,
+            &pr_req.ul_meta_data,
+        )
+        .await;
+
         let sess_id = Uuid::new_v4();
 
         Ok(internal::PassiveRoamingDeviceSession {
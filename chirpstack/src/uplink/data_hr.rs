@@ -0,0 +1,336 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use tracing::{Instrument, Level, span, trace};
+use uuid::Uuid;
+
+use crate::api::helpers::ToProto;
+use crate::backend::roaming;
+use crate::storage::{
+    application, device, device_profile, handover_roaming, helpers::get_all_device_data, tenant,
+};
+use crate::{codec, integration};
+use backend::ULMetaData;
+use chirpstack_api::{common, integration as integration_pb, internal};
+use lrwn::{AES128Key, DevAddr, EUI64, NetID};
+
+// Handles the uplink payloads forwarded by the serving Network Server for devices that
+// have been handed over. In this case the serving Network Server terminates the MAC layer
+// and only forwards the (AppSKey encrypted) FRMPayload together with the ULMetaData.
+pub struct Data {
+    sender_id: NetID,
+    ul_meta_data: ULMetaData,
+    frm_payload: Vec<u8>,
+
+    hr_device_session: Option<internal::HandoverRoamingDeviceSession>,
+    device: Option<device::Device>,
+    device_profile: Option<device_profile::DeviceProfile>,
+    application: Option<application::Application>,
+    tenant: Option<tenant::Tenant>,
+    device_info: Option<integration_pb::DeviceInfo>,
+    f_cnt_up: u32,
+}
+
+impl Data {
+    pub async fn handle(
+        sender_id: NetID,
+        ul_meta_data: ULMetaData,
+        frm_payload: Vec<u8>,
+    ) -> Result<()> {
+        let span = span!(Level::INFO, "data_hr");
+
+        Data::_handle(sender_id, ul_meta_data, frm_payload)
+            .instrument(span)
+            .await
+    }
+
+    async fn _handle(
+        sender_id: NetID,
+        ul_meta_data: ULMetaData,
+        frm_payload: Vec<u8>,
+    ) -> Result<()> {
+        let mut ctx = Data {
+            sender_id,
+            f_cnt_up: ul_meta_data
+                .f_cnt_up
+                .ok_or_else(|| anyhow!("ULMetaData.FCntUp is not set"))?,
+            ul_meta_data,
+            frm_payload,
+
+            hr_device_session: None,
+            device: None,
+            device_profile: None,
+            application: None,
+            tenant: None,
+            device_info: None,
+        };
+
+        ctx.get_hr_device_session().await?;
+        ctx.get_device_data().await?;
+        ctx.set_device_info()?;
+        ctx.validate_f_cnt_up().await?;
+        ctx.decrypt_frm_payload()?;
+        ctx.send_uplink_event().await?;
+        ctx.update_device().await?;
+        ctx.save_hr_device_session().await?;
+
+        Ok(())
+    }
+
+    async fn get_hr_device_session(&mut self) -> Result<()> {
+        trace!("Getting handover-roaming device-session");
+        let dev_eui = EUI64::from_slice(&self.ul_meta_data.dev_eui)?;
+        let ds = handover_roaming::get(&dev_eui).await?;
+
+        if ds.serving || ds.net_id != self.sender_id.to_vec() {
+            return Err(anyhow!(
+                "Device {} is not handed over to NetID {}",
+                dev_eui,
+                self.sender_id
+            ));
+        }
+
+        self.hr_device_session = Some(ds);
+        Ok(())
+    }
+
+    async fn get_device_data(&mut self) -> Result<()> {
+        trace!("Getting device data");
+        let dev_eui = EUI64::from_slice(&self.ul_meta_data.dev_eui)?;
+        let (dev, app, t, dp) = get_all_device_data(dev_eui).await?;
+
+        self.tenant = Some(t);
+        self.application = Some(app);
+        self.device_profile = Some(dp);
+        self.device = Some(dev);
+
+        Ok(())
+    }
+
+    fn set_device_info(&mut self) -> Result<()> {
+        trace!("Setting device-info");
+
+        let tenant = self.tenant.as_ref().unwrap();
+        let app = self.application.as_ref().unwrap();
+        let dp = self.device_profile.as_ref().unwrap();
+        let dev = self.device.as_ref().unwrap();
+
+        let mut tags = (*app.tags).clone();
+        tags.extend((*dp.tags).clone());
+        tags.extend((*dev.tags).clone());
+
+        self.device_info = Some(integration_pb::DeviceInfo {
+            tenant_id: tenant.id.to_string(),
+            tenant_name: tenant.name.clone(),
+            application_id: app.id.to_string(),
+            application_name: app.name.to_string(),
+            device_profile_id: dp.id.to_string(),
+            device_profile_name: dp.name.clone(),
+            device_name: dev.name.clone(),
+            device_class_enabled: dev.enabled_class.to_proto().into(),
+            dev_eui: dev.dev_eui.to_string(),
+            tags,
+        });
+
+        Ok(())
+    }
+
+    // The f_cnt_up of the handover-roaming device-session holds the next expected
+    // frame-counter. Like for uplinks received directly, replayed uplinks and
+    // frame-counter resets are rejected, unless the frame-counter check is disabled.
+    async fn validate_f_cnt_up(&self) -> Result<()> {
+        trace!("Validating uplink frame-counter");
+        let expected = self.hr_device_session.as_ref().unwrap().f_cnt_up;
+        if self.f_cnt_up >= expected {
+            return Ok(());
+        }
+
+        let dev = self.device.as_ref().unwrap();
+        let ds = dev.get_device_session()?;
+        if ds.skip_f_cnt_check {
+            return Ok(());
+        }
+
+        let (code, description) = if self.f_cnt_up.wrapping_add(1) == expected {
+            (
+                integration_pb::LogCode::UplinkFCntRetransmission,
+                "Uplink was flagged as re-transmission / frame-counter did not increment",
+            )
+        } else {
+            (
+                integration_pb::LogCode::UplinkFCntReset,
+                "Frame-counter reset or rollover detected",
+            )
+        };
+
+        integration::log_event(
+            self.application.as_ref().unwrap().id.into(),
+            &dev.variables,
+            &integration_pb::LogEvent {
+                time: Some(self.ul_meta_data.recv_time.into()),
+                device_info: self.device_info.clone(),
+                level: integration_pb::LogLevel::Warning.into(),
+                code: code.into(),
+                description: description.into(),
+                context: [("f_cnt_up".to_string(), self.f_cnt_up.to_string())]
+                    .iter()
+                    .cloned()
+                    .collect(),
+            },
+        )
+        .await;
+
+        Err(anyhow!(
+            "Invalid frame-counter, expected: {}, got: {}",
+            expected,
+            self.f_cnt_up
+        ))
+    }
+
+    fn decrypt_frm_payload(&mut self) -> Result<()> {
+        if self._is_end_to_end_encrypted() || self.frm_payload.is_empty() {
+            return Ok(());
+        }
+
+        trace!("Decrypting FRMPayload");
+        let ds = self.device.as_ref().unwrap().get_device_session()?;
+        let dev_addr = DevAddr::from_slice(&self.hr_device_session.as_ref().unwrap().dev_addr)?;
+
+        if let Some(app_s_key) = &ds.app_s_key {
+            let app_s_key = AES128Key::from_slice(&app_s_key.aes_key)?;
+            self.frm_payload = lrwn::encrypt_frm_payload(
+                &app_s_key,
+                true,
+                &dev_addr,
+                self.f_cnt_up,
+                &self.frm_payload,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    async fn send_uplink_event(&self) -> Result<()> {
+        trace!("Sending uplink event");
+
+        let ts: DateTime<Utc> = self.ul_meta_data.recv_time;
+        let app = self.application.as_ref().unwrap();
+        let dp = self.device_profile.as_ref().unwrap();
+        let dev = self.device.as_ref().unwrap();
+        let ds = dev.get_device_session()?;
+        let f_port = self.ul_meta_data.f_port.unwrap_or(0);
+
+        let mut pl = integration_pb::UplinkEvent {
+            deduplication_id: Uuid::new_v4().to_string(),
+            time: Some(ts.into()),
+            device_info: self.device_info.clone(),
+            dev_addr: DevAddr::from_slice(&self.hr_device_session.as_ref().unwrap().dev_addr)?
+                .to_string(),
+            dr: self.ul_meta_data.data_rate.unwrap_or_default() as u32,
+            f_cnt: self.f_cnt_up,
+            f_port: f_port as u32,
+            confirmed: self.ul_meta_data.confirmed.unwrap_or_default(),
+            data: self.frm_payload.clone(),
+            rx_info: roaming::ul_meta_data_to_rx_info(&self.ul_meta_data)?,
+            tx_info: Some(roaming::ul_meta_data_to_tx_info(&self.ul_meta_data)?),
+            join_server_context: if self._is_end_to_end_encrypted() {
+                Some(common::JoinServerContext {
+                    session_key_id: hex::encode(&ds.js_session_key_id),
+                    app_s_key: ds.app_s_key.clone(),
+                })
+            } else {
+                None
+            },
+            ..Default::default()
+        };
+
+        if !self._is_end_to_end_encrypted() {
+            pl.object = match codec::binary_to_struct(
+                dp.payload_codec_runtime,
+                ts,
+                f_port,
+                &dev.variables,
+                &dp.payload_codec_script,
+                &pl.data,
+            )
+            .await
+            {
+                Ok(v) => v,
+                Err(e) => {
+                    integration::log_event(
+                        app.id.into(),
+                        &dev.variables,
+                        &integration_pb::LogEvent {
+                            time: Some(Utc::now().into()),
+                            device_info: self.device_info.clone(),
+                            level: integration_pb::LogLevel::Error.into(),
+                            code: integration_pb::LogCode::UplinkCodec.into(),
+                            description: format!("{:#}", e),
+                            context: [(
+                                "deduplication_id".to_string(),
+                                pl.deduplication_id.clone(),
+                            )]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        },
+                    )
+                    .await;
+                    None
+                }
+            };
+        }
+
+        integration::uplink_event(app.id.into(), &dev.variables, &pl).await;
+
+        Ok(())
+    }
+
+    async fn update_device(&mut self) -> Result<()> {
+        trace!("Updating device");
+        let dev_eui = self.device.as_ref().unwrap().dev_eui;
+
+        self.device = Some(
+            device::partial_update(
+                dev_eui,
+                &device::DeviceChangeset {
+                    last_seen_at: Some(Some(Utc::now())),
+                    dr: Some(self.ul_meta_data.data_rate.map(|v| v as i16)),
+                    f_cnt_up: Some(self.f_cnt_up.wrapping_add(1).into()),
+                    ..Default::default()
+                },
+            )
+            .await?,
+        );
+
+        Ok(())
+    }
+
+    async fn save_hr_device_session(&mut self) -> Result<()> {
+        trace!("Saving handover-roaming device-session");
+        let ds = self.hr_device_session.as_mut().unwrap();
+        ds.f_cnt_up = self.f_cnt_up.wrapping_add(1);
+
+        handover_roaming::save(ds)
+            .await
+            .context("Save handover-roaming device-session")
+    }
+
+    fn _is_end_to_end_encrypted(&self) -> bool {
+        let ds = match self.device.as_ref().unwrap().get_device_session() {
+            Ok(v) => v,
+            Err(_) => return false,
+        };
+
+        if !ds.js_session_key_id.is_empty() {
+            return true;
+        }
+
+        if let Some(app_s_key) = &ds.app_s_key
+            && !app_s_key.kek_label.is_empty()
+        {
+            return true;
+        }
+
+        false
+    }
+}
@@ -25,7 +25,7 @@ use crate::storage::{
     helpers::get_all_device_data,
     metrics, tenant,
 };
-use crate::{config, devaddr::get_random_dev_addr, downlink, integration, region, stream};
+use crate::{config, devaddr::allocate_dev_addr, downlink, integration, region, stream};
 use chirpstack_api::{common, integration as integration_pb, internal, stream as stream_pb};
 
 pub struct JoinRequest {
@@ -122,14 +122,15 @@ impl JoinRequest {
         ctx.abort_on_relay_only_comm()?;
         ctx.log_uplink_frame_set().await?;
         ctx.abort_on_otaa_is_disabled()?;
-        ctx.set_random_dev_addr()?;
         if ctx.js_client.is_some() {
             // Using join-server
+            ctx.set_random_dev_addr().await?;
             ctx.get_join_accept_from_js().await?;
         } else {
             // Using internal keys
             ctx.validate_mic().await?;
             ctx.validate_dev_nonce_and_get_device_keys().await?;
+            ctx.set_random_dev_addr().await?;
             ctx.construct_join_accept_and_set_keys()?;
         }
         ctx.log_uplink_meta().await?;
@@ -172,14 +173,15 @@ impl JoinRequest {
         ctx.abort_on_device_is_disabled()?;
         ctx.abort_on_otaa_is_disabled()?;
         ctx.abort_on_relay_only_comm()?;
-        ctx.set_random_dev_addr()?;
         if ctx.js_client.is_some() {
             // Using join-server
+            ctx.set_random_dev_addr().await?;
             ctx.get_join_accept_from_js().await?;
         } else {
             // Using internal keys
             ctx.validate_mic().await?;
             ctx.validate_dev_nonce_and_get_device_keys().await?;
+            ctx.set_random_dev_addr().await?;
             ctx.construct_join_accept_and_set_keys()?;
         }
         ctx.set_device_session().await?;
@@ -514,11 +516,12 @@ impl JoinRequest {
         Ok(())
     }
 
-    fn set_random_dev_addr(&mut self) -> Result<()> {
+    async fn set_random_dev_addr(&mut self) -> Result<()> {
         trace!("Setting random DevAddr");
         let tenant = self.tenant.as_ref().unwrap();
+        let dev_addr = allocate_dev_addr(&tenant.get_dev_addr_prefixes()).await?;
         let d = self.device.as_mut().unwrap();
-        d.dev_addr = Some(get_random_dev_addr(&tenant.get_dev_addr_prefixes()));
+        d.dev_addr = Some(dev_addr);
 
         Ok(())
     }
@@ -551,6 +554,10 @@ impl JoinRequest {
         };
 
         let mut join_req_pl = backend::JoinReqPayload {
+            base: backend::BasePayload {
+                sender_id: self.tenant.as_ref().unwrap().get_net_id().to_vec(),
+                ..Default::default()
+            },
             mac_version: dp.mac_version.to_string(),
             phy_payload: phy_b,
             dev_eui: dev.dev_eui.to_vec(),
@@ -619,7 +626,7 @@ impl JoinRequest {
     fn construct_join_accept_and_set_keys(&mut self) -> Result<()> {
         trace!("Constructing JoinAccept payload");
 
-        let conf = config::get();
+        let net_id = self.tenant.as_ref().unwrap().get_net_id();
         let region_network = config::get_region_network(&self.uplink_frame_set.region_config_id)?;
         let region_conf = region::get(&self.uplink_frame_set.region_config_id)?;
         let join_request = self.join_request.as_ref().unwrap();
@@ -648,7 +655,7 @@ impl JoinRequest {
             },
             payload: Payload::JoinAccept(JoinAcceptPayload {
                 join_nonce: join_nonce as u32,
-                home_netid: conf.network.net_id,
+                home_netid: net_id,
                 devaddr: d.dev_addr.unwrap(),
                 dl_settings: DLSettings {
                     opt_neg,
@@ -687,7 +694,7 @@ impl JoinRequest {
         self.f_nwk_s_int_key = Some(keys::get_f_nwk_s_int_key(
             opt_neg,
             &device_keys.nwk_key,
-            &conf.network.net_id,
+            &net_id,
             &join_request.join_eui,
             join_nonce as u32,
             join_request.dev_nonce,
@@ -697,7 +704,7 @@ impl JoinRequest {
             true => keys::get_s_nwk_s_int_key(
                 opt_neg,
                 &device_keys.nwk_key,
-                &conf.network.net_id,
+                &net_id,
                 &join_request.join_eui,
                 join_nonce as u32,
                 join_request.dev_nonce,
@@ -705,7 +712,7 @@ impl JoinRequest {
             false => keys::get_f_nwk_s_int_key(
                 opt_neg,
                 &device_keys.nwk_key,
-                &conf.network.net_id,
+                &net_id,
                 &join_request.join_eui,
                 join_nonce as u32,
                 join_request.dev_nonce,
@@ -716,7 +723,7 @@ impl JoinRequest {
             true => keys::get_nwk_s_enc_key(
                 opt_neg,
                 &device_keys.nwk_key,
-                &conf.network.net_id,
+                &net_id,
                 &join_request.join_eui,
                 join_nonce as u32,
                 join_request.dev_nonce,
@@ -724,7 +731,7 @@ impl JoinRequest {
             false => keys::get_f_nwk_s_int_key(
                 opt_neg,
                 &device_keys.nwk_key,
-                &conf.network.net_id,
+                &net_id,
                 &join_request.join_eui,
                 join_nonce as u32,
                 join_request.dev_nonce,
@@ -737,7 +744,7 @@ impl JoinRequest {
                 true => keys::get_app_s_key(
                     opt_neg,
                     &device_keys.app_key,
-                    &conf.network.net_id,
+                    &net_id,
                     &join_request.join_eui,
                     join_nonce as u32,
                     join_request.dev_nonce,
@@ -745,7 +752,7 @@ impl JoinRequest {
                 false => keys::get_app_s_key(
                     opt_neg,
                     &device_keys.nwk_key,
-                    &conf.network.net_id,
+                    &net_id,
                     &join_request.join_eui,
                     join_nonce as u32,
                     join_request.dev_nonce,
@@ -2,14 +2,14 @@ use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use chrono::{Duration, Utc};
-use tracing::{Instrument, Level, span, trace};
+use tracing::{Instrument, Level, debug, span, trace};
 use uuid::Uuid;
 
-use super::{UplinkFrameSet, filter_rx_info_by_public_only};
+use super::{UplinkFrameSet, filter_rx_info_by_public_only, get_sender_net_id};
 use crate::api::backend::get_async_receiver;
 use crate::backend::{joinserver, keywrap, roaming};
 use crate::downlink;
-use crate::storage::passive_roaming;
+use crate::storage::{handover_roaming, passive_roaming};
 use crate::uplink::helpers;
 use backend::Client;
 use chirpstack_api::internal;
@@ -18,9 +18,11 @@ use lrwn::{JoinRequestPayload, NetID};
 pub struct JoinRequest {
     uplink_frame_set: UplinkFrameSet,
     join_request: JoinRequestPayload,
+    sender_net_id: Option<NetID>,
     home_net_id: Option<NetID>,
     client: Option<Arc<Client>>,
     pr_start_ans: Option<backend::PRStartAnsPayload>,
+    hr_start_ans: Option<backend::HRStartAnsPayload>,
 }
 
 impl JoinRequest {
@@ -33,16 +35,24 @@ impl JoinRequest {
         let mut ctx = JoinRequest {
             uplink_frame_set: ufs,
             join_request: jr,
+            sender_net_id: None,
             home_net_id: None,
             client: None,
             pr_start_ans: None,
+            hr_start_ans: None,
         };
 
         ctx.filter_rx_info_by_public_only()?;
+        ctx.get_sender_net_id().await?;
         ctx.get_home_net_id().await?;
         ctx.get_client().await?;
-        ctx.start_roaming().await?;
-        ctx.save_roaming_session().await?;
+        if ctx.get_handover_roaming().await? {
+            ctx.start_handover_roaming().await?;
+            ctx.save_handover_roaming_session().await?;
+        } else {
+            ctx.start_roaming().await?;
+            ctx.save_roaming_session().await?;
+        }
 
         Ok(())
     }
@@ -54,6 +64,21 @@ impl JoinRequest {
         Ok(())
     }
 
+    async fn get_sender_net_id(&mut self) -> Result<()> {
+        trace!("Getting sender netid");
+        self.sender_net_id = Some(get_sender_net_id(&self.uplink_frame_set).await?);
+        Ok(())
+    }
+
+    // Returns the BasePayload with the SenderID set to the NetID of the tenant
+    // owning the receiving gateway(s).
+    fn get_base_payload(&self) -> backend::BasePayload {
+        backend::BasePayload {
+            sender_id: self.sender_net_id.unwrap().to_vec(),
+            ..Default::default()
+        }
+    }
+
     async fn get_home_net_id(&mut self) -> Result<()> {
         trace!("Getting home netid");
 
@@ -61,8 +86,8 @@ impl JoinRequest {
         let js_client = joinserver::get(self.join_request.join_eui).await?;
 
         let mut home_ns_req = backend::HomeNSReqPayload {
+            base: self.get_base_payload(),
             dev_eui: self.join_request.dev_eui.to_vec(),
-            ..Default::default()
         };
 
         #[cfg(test)]
@@ -101,6 +126,160 @@ impl JoinRequest {
         Ok(())
     }
 
+    // Returns true when the home Network Server activates the device using handover-roaming.
+    async fn get_handover_roaming(&mut self) -> Result<bool> {
+        let net_id = self.home_net_id.unwrap();
+        if !roaming::get_handover_roaming(net_id)? {
+            return Ok(false);
+        }
+
+        trace!("Requesting device-profile and roaming activation type");
+        let mut profile_req = backend::ProfileReqPayload {
+            base: self.get_base_payload(),
+            dev_eui: self.join_request.dev_eui.to_vec(),
+        };
+
+        #[cfg(test)]
+        {
+            profile_req.base.transaction_id = 1234;
+        }
+
+        let client = self.client.as_ref().unwrap();
+        let async_receiver = match client.is_async() {
+            false => None,
+            true => Some(
+                get_async_receiver(profile_req.base.transaction_id, client.get_async_timeout())
+                    .await?,
+            ),
+        };
+
+        let resp = client
+            .profile_req(backend::Role::HNS, &mut profile_req, async_receiver)
+            .await?;
+
+        let handover =
+            resp.roaming_activation_type == Some(backend::RoamingActivationType::Handover);
+        debug!(net_id = %net_id, handover = handover, "Got roaming activation type");
+
+        Ok(handover)
+    }
+
+    async fn start_handover_roaming(&mut self) -> Result<()> {
+        trace!("Starting handover-roaming");
+        let rf_region = self
+            .uplink_frame_set
+            .region_common_name
+            .to_string()
+            .replace('_', "-");
+
+        let mut hr_req = backend::HRStartReqPayload {
+            base: self.get_base_payload(),
+            phy_payload: self.uplink_frame_set.phy_payload_b.clone(),
+            ul_meta_data: backend::ULMetaData {
+                dev_eui: self.join_request.dev_eui.to_vec(),
+                ul_freq: Some((self.uplink_frame_set.tx_info.frequency as f64) / 1_000_000.0),
+                data_rate: Some(self.uplink_frame_set.dr),
+                recv_time: helpers::get_rx_timestamp_chrono(&self.uplink_frame_set.rx_info_set),
+                rf_region: rf_region.clone(),
+                gw_cnt: Some(self.uplink_frame_set.rx_info_set.len()),
+                gw_info: roaming::rx_info_to_gw_info(
+                    &rf_region,
+                    &self.uplink_frame_set.rx_info_set,
+                )?,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        #[cfg(test)]
+        {
+            hr_req.base.transaction_id = 1234;
+        }
+
+        let client = self.client.as_ref().unwrap();
+        let async_receiver = match client.is_async() {
+            false => None,
+            true => Some(
+                get_async_receiver(hr_req.base.transaction_id, client.get_async_timeout()).await?,
+            ),
+        };
+
+        let resp = client
+            .hr_start_req(backend::Role::HNS, &mut hr_req, async_receiver)
+            .await?;
+
+        if let Some(dl_meta) = &resp.dl_meta_data {
+            downlink::roaming::PassiveRoamingDownlink::handle(
+                self.uplink_frame_set.clone(),
+                resp.phy_payload.clone(),
+                dl_meta.clone(),
+            )
+            .await?;
+        } else {
+            return Err(anyhow!("DLMetaData is not set"));
+        }
+
+        self.hr_start_ans = Some(resp);
+        Ok(())
+    }
+
+    async fn save_handover_roaming_session(&mut self) -> Result<()> {
+        trace!("Saving handover-roaming session");
+
+        let hr_start_ans = self.hr_start_ans.as_ref().unwrap();
+        if hr_start_ans.dev_addr.is_empty() {
+            return Err(anyhow!("DevAddr is not set"));
+        }
+
+        // LoRaWAN 1.0.x devices use a single NwkSKey, LoRaWAN 1.1 devices use the
+        // SNwkSIntKey, FNwkSIntKey and NwkSEncKey.
+        let (f_nwk_s_int_key, s_nwk_s_int_key, nwk_s_enc_key) = match &hr_start_ans.nwk_s_key {
+            Some(ke) => {
+                let key = keywrap::unwrap(ke)?.to_vec();
+                (key.clone(), key.clone(), key)
+            }
+            None => (
+                match &hr_start_ans.f_nwk_s_int_key {
+                    Some(ke) => keywrap::unwrap(ke)?.to_vec(),
+                    None => return Err(anyhow!("FNwkSIntKey is not set")),
+                },
+                match &hr_start_ans.s_nwk_s_int_key {
+                    Some(ke) => keywrap::unwrap(ke)?.to_vec(),
+                    None => return Err(anyhow!("SNwkSIntKey is not set")),
+                },
+                match &hr_start_ans.nwk_s_enc_key {
+                    Some(ke) => keywrap::unwrap(ke)?.to_vec(),
+                    None => return Err(anyhow!("NwkSEncKey is not set")),
+                },
+            ),
+        };
+
+        let sess = internal::HandoverRoamingDeviceSession {
+            net_id: self.home_net_id.unwrap().to_vec(),
+            serving: true,
+            dev_eui: self.join_request.dev_eui.to_vec(),
+            dev_addr: hr_start_ans.dev_addr.clone(),
+            lorawan_1_1: hr_start_ans.nwk_s_key.is_none(),
+            f_nwk_s_int_key,
+            s_nwk_s_int_key,
+            nwk_s_enc_key,
+            f_cnt_up: 0,
+            n_f_cnt_down: 0,
+            lifetime: {
+                let lt = hr_start_ans.lifetime.unwrap_or_default() as i64;
+                if lt == 0 {
+                    None
+                } else {
+                    Some((Utc::now() + Duration::try_seconds(lt).unwrap_or_default()).into())
+                }
+            },
+        };
+
+        handover_roaming::save(&sess)
+            .await
+            .context("Save handover-roaming device-session")
+    }
+
     async fn start_roaming(&mut self) -> Result<()> {
         trace!("Starting passive-roaming");
         let rf_region = self
@@ -110,7 +289,8 @@ impl JoinRequest {
             .replace('_', "-");
 
         let mut pr_req = backend::PRStartReqPayload {
-            phy_payload: self.uplink_frame_set.phy_payload.to_vec()?,
+            base: self.get_base_payload(),
+            phy_payload: self.uplink_frame_set.phy_payload_b.clone(),
             ul_meta_data: backend::ULMetaData {
                 dev_eui: self.join_request.dev_eui.to_vec(),
                 ul_freq: Some((self.uplink_frame_set.tx_info.frequency as f64) / 1_000_000.0),
@@ -124,7 +304,6 @@ impl JoinRequest {
                 )?,
                 ..Default::default()
             },
-            ..Default::default()
         };
 
         #[cfg(test)]
@@ -144,7 +323,25 @@ impl JoinRequest {
             .pr_start_req(backend::Role::SNS, &mut pr_req, async_receiver)
             .await?;
 
+        let net_id = self.home_net_id.unwrap();
+        roaming::record_uplink(
+            net_id,
+            backend::Role::FNS,
+            &pr_req.phy_payload,
+            &pr_req.ul_meta_data,
+        )
+        .await;
+
         if let Some(dl_meta) = &resp.dl_meta_data {
+            roaming::record_downlink(
+                net_id,
+                backend::Role::FNS,
+                &resp.phy_payload,
+                dl_meta,
+                Some(pr_req.ul_meta_data.recv_time),
+            )
+            .await;
+
             downlink::roaming::PassiveRoamingDownlink::handle(
                 self.uplink_frame_set.clone(),
                 resp.phy_payload.clone(),
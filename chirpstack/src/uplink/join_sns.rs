@@ -12,11 +12,12 @@ use crate::storage::{
     device::{self, DeviceClass},
     device_keys, device_profile, device_queue,
     error::Error as StorageError,
+    handover_roaming,
     helpers::get_all_device_data,
     metrics, tenant,
 };
-use crate::{config, devaddr::get_random_dev_addr, integration, region, stream};
-use backend::{PRStartAnsPayload, PRStartReqPayload};
+use crate::{config, devaddr::allocate_dev_addr, integration, region, stream};
+use backend::{HRStartAnsPayload, HRStartReqPayload, PRStartAnsPayload, PRStartReqPayload};
 use chirpstack_api::{common, integration as integration_pb, internal, stream as stream_pb};
 use lrwn::{AES128Key, DevAddr, NetID, keys};
 
@@ -24,6 +25,8 @@ pub struct JoinRequest {
     uplink_frame_set: UplinkFrameSet,
     pr_start_req: PRStartReqPayload,
     pr_start_ans: Option<PRStartAnsPayload>,
+    hr_start_ans: Option<HRStartAnsPayload>,
+    handover: bool,
 
     join_request: Option<lrwn::JoinRequestPayload>,
     join_accept: Option<lrwn::PhyPayload>,
@@ -53,15 +56,57 @@ impl JoinRequest {
             .await
     }
 
+    pub async fn start_hr(
+        ufs: UplinkFrameSet,
+        hr_start_req: HRStartReqPayload,
+    ) -> Result<HRStartAnsPayload> {
+        let span = span!(Level::INFO, "start_hr");
+        JoinRequest::_start_hr(ufs, hr_start_req)
+            .instrument(span)
+            .await
+    }
+
     async fn _start_pr(
         ufs: UplinkFrameSet,
         pr_start_req: PRStartReqPayload,
     ) -> Result<PRStartAnsPayload> {
-        let mut ctx = JoinRequest {
+        let mut ctx = JoinRequest::new(ufs, pr_start_req, false);
+        ctx.handle().await?;
+        ctx.set_pr_start_ans_payload()?;
+
+        ctx.pr_start_ans
+            .ok_or_else(|| anyhow!("PRStartAnsPayload is not set"))
+    }
+
+    async fn _start_hr(
+        ufs: UplinkFrameSet,
+        hr_start_req: HRStartReqPayload,
+    ) -> Result<HRStartAnsPayload> {
+        // The HRStartReq carries the same join-request context as the PRStartReq, the
+        // difference is in the answer (NwkSKeys + device-profile) and in the session state.
+        let pr_start_req = PRStartReqPayload {
+            base: hr_start_req.base,
+            phy_payload: hr_start_req.phy_payload,
+            ul_meta_data: hr_start_req.ul_meta_data,
+        };
+
+        let mut ctx = JoinRequest::new(ufs, pr_start_req, true);
+        ctx.handle().await?;
+        ctx.set_hr_start_ans_payload()?;
+        ctx.save_handover_roaming_device_session().await?;
+
+        ctx.hr_start_ans
+            .ok_or_else(|| anyhow!("HRStartAnsPayload is not set"))
+    }
+
+    fn new(ufs: UplinkFrameSet, pr_start_req: PRStartReqPayload, handover: bool) -> Self {
+        JoinRequest {
             uplink_frame_set: ufs,
             pr_start_req,
+            handover,
 
             pr_start_ans: None,
+            hr_start_ans: None,
             join_request: None,
             join_accept: None,
             device: None,
@@ -77,34 +122,34 @@ impl JoinRequest {
             nwk_s_enc_key: None,
             app_s_key: None,
             js_session_key_id: "".to_string(),
-        };
+        }
+    }
 
-        ctx.get_join_request_payload()?;
-        ctx.get_device_data().await?;
-        ctx.check_roaming_allowed()?;
-        ctx.get_device_keys_or_js_client().await?;
-        ctx.set_device_info()?;
-        ctx.abort_on_device_is_disabled()?;
-        ctx.abort_on_otaa_is_disabled()?;
-        ctx.get_random_dev_addr()?;
-        if ctx.js_client.is_some() {
+    async fn handle(&mut self) -> Result<()> {
+        self.get_join_request_payload()?;
+        self.get_device_data().await?;
+        self.check_roaming_allowed()?;
+        self.get_device_keys_or_js_client().await?;
+        self.set_device_info()?;
+        self.abort_on_device_is_disabled()?;
+        self.abort_on_otaa_is_disabled()?;
+        self.get_random_dev_addr().await?;
+        if self.js_client.is_some() {
             // Using join-server
-            ctx.get_join_accept_from_js().await?;
+            self.get_join_accept_from_js().await?;
         } else {
             // Using internal keys
-            ctx.validate_mic().await?;
-            ctx.validate_dev_nonce_and_get_device_keys().await?;
-            ctx.construct_join_accept_and_set_keys()?;
+            self.validate_mic().await?;
+            self.validate_dev_nonce_and_get_device_keys().await?;
+            self.construct_join_accept_and_set_keys()?;
         }
-        ctx.log_uplink_meta().await?;
-        ctx.set_device_session().await?;
-        ctx.flush_device_queue().await?;
-        ctx.update_device().await?;
-        ctx.send_join_event().await?;
-        ctx.set_pr_start_ans_payload()?;
+        self.log_uplink_meta().await?;
+        self.set_device_session().await?;
+        self.flush_device_queue().await?;
+        self.update_device().await?;
+        self.send_join_event().await?;
 
-        ctx.pr_start_ans
-            .ok_or_else(|| anyhow!("PRStartAnsPayload is not set"))
+        Ok(())
     }
 
     fn get_join_request_payload(&mut self) -> Result<()> {
@@ -138,7 +183,13 @@ impl JoinRequest {
     fn check_roaming_allowed(&self) -> Result<(), Error> {
         trace!("Check if roaming is allowed");
         let dp = self.device_profile.as_ref().unwrap();
-        if !dp.allow_roaming {
+        if !dp.allow_roaming || (self.handover && !dp.allow_handover_roaming) {
+            return Err(Error::RoamingIsNotAllowed);
+        }
+
+        let sender_id =
+            NetID::from_slice(&self.pr_start_req.base.sender_id).map_err(anyhow::Error::from)?;
+        if !roaming::is_device_profile_allowed(sender_id, &dp.id.into()) {
             return Err(Error::RoamingIsNotAllowed);
         }
 
@@ -207,9 +258,9 @@ impl JoinRequest {
         Ok(())
     }
 
-    fn get_random_dev_addr(&mut self) -> Result<()> {
+    async fn get_random_dev_addr(&mut self) -> Result<()> {
         let tenant = self.tenant.as_ref().unwrap();
-        self.dev_addr = Some(get_random_dev_addr(&tenant.get_dev_addr_prefixes()));
+        self.dev_addr = Some(allocate_dev_addr(&tenant.get_dev_addr_prefixes()).await?);
         Ok(())
     }
 
@@ -241,6 +292,10 @@ impl JoinRequest {
         };
 
         let mut join_req_pl = backend::JoinReqPayload {
+            base: backend::BasePayload {
+                sender_id: self.tenant.as_ref().unwrap().get_net_id().to_vec(),
+                ..Default::default()
+            },
             mac_version: dp.mac_version.to_string(),
             phy_payload: phy_b,
             dev_eui: dev.dev_eui.to_vec(),
@@ -409,7 +464,7 @@ impl JoinRequest {
     fn construct_join_accept_and_set_keys(&mut self) -> Result<()> {
         trace!("Constructing JoinAccept payload");
 
-        let conf = config::get();
+        let net_id = self.tenant.as_ref().unwrap().get_net_id();
         let region_network = config::get_region_network(&self.uplink_frame_set.region_config_id)?;
         let region_conf = region::get(&self.uplink_frame_set.region_config_id)?;
         let join_request = self.join_request.as_ref().unwrap();
@@ -437,7 +492,7 @@ impl JoinRequest {
             },
             payload: lrwn::Payload::JoinAccept(lrwn::JoinAcceptPayload {
                 join_nonce: join_nonce as u32,
-                home_netid: conf.network.net_id,
+                home_netid: net_id,
                 devaddr: self.dev_addr.unwrap(),
                 dl_settings: lrwn::DLSettings {
                     opt_neg,
@@ -476,7 +531,7 @@ impl JoinRequest {
         self.f_nwk_s_int_key = Some(keys::get_f_nwk_s_int_key(
             opt_neg,
             &device_keys.nwk_key,
-            &conf.network.net_id,
+            &net_id,
             &join_request.join_eui,
             join_nonce as u32,
             join_request.dev_nonce,
@@ -486,7 +541,7 @@ impl JoinRequest {
             true => keys::get_s_nwk_s_int_key(
                 opt_neg,
                 &device_keys.nwk_key,
-                &conf.network.net_id,
+                &net_id,
                 &join_request.join_eui,
                 join_nonce as u32,
                 join_request.dev_nonce,
@@ -494,7 +549,7 @@ impl JoinRequest {
             false => keys::get_f_nwk_s_int_key(
                 opt_neg,
                 &device_keys.nwk_key,
-                &conf.network.net_id,
+                &net_id,
                 &join_request.join_eui,
                 join_nonce as u32,
                 join_request.dev_nonce,
@@ -505,7 +560,7 @@ impl JoinRequest {
             true => keys::get_nwk_s_enc_key(
                 opt_neg,
                 &device_keys.nwk_key,
-                &conf.network.net_id,
+                &net_id,
                 &join_request.join_eui,
                 join_nonce as u32,
                 join_request.dev_nonce,
@@ -513,7 +568,7 @@ impl JoinRequest {
             false => keys::get_f_nwk_s_int_key(
                 opt_neg,
                 &device_keys.nwk_key,
-                &conf.network.net_id,
+                &net_id,
                 &join_request.join_eui,
                 join_nonce as u32,
                 join_request.dev_nonce,
@@ -526,7 +581,7 @@ impl JoinRequest {
                 true => keys::get_app_s_key(
                     opt_neg,
                     &device_keys.app_key,
-                    &conf.network.net_id,
+                    &net_id,
                     &join_request.join_eui,
                     join_nonce as u32,
                     join_request.dev_nonce,
@@ -534,7 +589,7 @@ impl JoinRequest {
                 false => keys::get_app_s_key(
                     opt_neg,
                     &device_keys.nwk_key,
-                    &conf.network.net_id,
+                    &net_id,
                     &join_request.join_eui,
                     join_nonce as u32,
                     join_request.dev_nonce,
@@ -793,4 +848,127 @@ impl JoinRequest {
 
         Ok(())
     }
+
+    fn set_hr_start_ans_payload(&mut self) -> Result<()> {
+        trace!("Setting HRStartAnsPayload");
+        let d = self.device.as_ref().unwrap();
+        let dp = self.device_profile.as_ref().unwrap();
+        let ds = d.get_device_session()?;
+
+        let region_conf = region::get(&self.uplink_frame_set.region_config_id)?;
+
+        let sender_id = NetID::from_slice(&self.pr_start_req.base.sender_id)?;
+        let hr_lifetime = roaming::get_handover_roaming_lifetime(sender_id)?;
+        let kek_label = roaming::get_handover_roaming_kek_label(sender_id)?;
+        let lorawan_1_0 = ds.mac_version().to_string().starts_with("1.0");
+
+        // With LoRaWAN 1.0.x, the NwkSKey is used for both integrity and encryption.
+        let (nwk_s_key, s_nwk_s_int_key, f_nwk_s_int_key, nwk_s_enc_key) = if lorawan_1_0 {
+            (
+                Some(keywrap::wrap(
+                    &kek_label,
+                    AES128Key::from_slice(&ds.nwk_s_enc_key)?,
+                )?),
+                None,
+                None,
+                None,
+            )
+        } else {
+            (
+                None,
+                Some(keywrap::wrap(
+                    &kek_label,
+                    AES128Key::from_slice(&ds.s_nwk_s_int_key)?,
+                )?),
+                Some(keywrap::wrap(
+                    &kek_label,
+                    AES128Key::from_slice(&ds.f_nwk_s_int_key)?,
+                )?),
+                Some(keywrap::wrap(
+                    &kek_label,
+                    AES128Key::from_slice(&ds.nwk_s_enc_key)?,
+                )?),
+            )
+        };
+
+        let rx1_delay = region_conf.get_defaults().join_accept_delay1;
+        let rx1_dr = region_conf.get_rx1_data_rate_index(self.uplink_frame_set.dr, 0)?;
+        let rx1_freq = region_conf
+            .get_rx1_frequency_for_uplink_frequency(self.uplink_frame_set.tx_info.frequency)?;
+
+        let rx2_dr = region_conf.get_defaults().rx2_dr;
+        let rx2_freq = region_conf.get_defaults().rx2_frequency;
+
+        self.hr_start_ans = Some(HRStartAnsPayload {
+            base: self
+                .pr_start_req
+                .base
+                .to_base_payload_result(backend::ResultCode::Success, ""),
+            phy_payload: self.join_accept.as_ref().unwrap().to_vec()?,
+            dev_eui: d.dev_eui.to_vec(),
+            dev_addr: d.get_dev_addr()?.to_vec(),
+            lifetime: Some(hr_lifetime.as_secs() as usize),
+            s_nwk_s_int_key,
+            f_nwk_s_int_key,
+            nwk_s_enc_key,
+            nwk_s_key,
+            device_profile: Some(roaming::device_profile_to_bi(dp)),
+            device_profile_timestamp: Some(dp.updated_at),
+            dl_meta_data: Some(backend::DLMetaData {
+                dev_eui: d.dev_eui.to_vec(),
+                dl_freq_1: Some(rx1_freq as f64 / 1_000_000.0),
+                dl_freq_2: Some(rx2_freq as f64 / 1_000_000.0),
+                rx_delay_1: Some(rx1_delay.as_secs() as usize),
+                class_mode: Some("A".to_string()),
+                data_rate_1: Some(rx1_dr),
+                data_rate_2: Some(rx2_dr),
+                f_ns_ul_token: self.pr_start_req.ul_meta_data.f_ns_ul_token.clone(),
+                gw_info: self
+                    .pr_start_req
+                    .ul_meta_data
+                    .gw_info
+                    .iter()
+                    .map(|gw| backend::GWInfoElement {
+                        ul_token: gw.ul_token.clone(),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        Ok(())
+    }
+
+    async fn save_handover_roaming_device_session(&self) -> Result<()> {
+        trace!("Saving handover-roaming device-session");
+        let d = self.device.as_ref().unwrap();
+        let ds = d.get_device_session()?;
+
+        let sender_id = NetID::from_slice(&self.pr_start_req.base.sender_id)?;
+        let hr_lifetime = roaming::get_handover_roaming_lifetime(sender_id)?;
+
+        // As home Network Server, we keep track of the handed-over device, such that
+        // uplinks forwarded by the serving Network Server can be validated against it.
+        handover_roaming::save(&internal::HandoverRoamingDeviceSession {
+            net_id: sender_id.to_vec(),
+            serving: false,
+            dev_eui: d.dev_eui.to_vec(),
+            dev_addr: d.get_dev_addr()?.to_vec(),
+            lorawan_1_1: !ds.mac_version().to_string().starts_with("1.0"),
+            f_nwk_s_int_key: ds.f_nwk_s_int_key.clone(),
+            s_nwk_s_int_key: ds.s_nwk_s_int_key.clone(),
+            nwk_s_enc_key: ds.nwk_s_enc_key.clone(),
+            f_cnt_up: 0,
+            n_f_cnt_down: 0,
+            lifetime: if hr_lifetime.is_zero() {
+                None
+            } else {
+                Some((Utc::now() + hr_lifetime).into())
+            },
+        })
+        .await
+        .context("Save handover-roaming device-session")
+    }
 }
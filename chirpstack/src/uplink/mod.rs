@@ -20,14 +20,16 @@ use crate::helpers::errors::PrintFullError;
 use crate::monitoring::prometheus;
 use crate::storage::{
     device, device_profile, error::Error as StorageError, gateway, get_async_redis_conn, redis_key,
+    tenant,
 };
 use crate::stream;
 use chirpstack_api::{common, gw, stream as stream_pb};
 use lrwn::region::CommonName;
-use lrwn::{EUI64, FType, ForwardUplinkReq, PhyPayload};
+use lrwn::{EUI64, FType, ForwardUplinkReq, NetID, PhyPayload};
 
 mod data;
 mod data_fns;
+pub mod data_hr;
 pub mod data_sns;
 pub mod error;
 pub mod helpers;
@@ -85,6 +87,9 @@ pub struct UplinkFrameSet {
     pub dr: u8,
     pub ch: usize,
     pub phy_payload: PhyPayload,
+    // The received PhyPayload bytes, e.g. used for validating the MIC without
+    // re-encoding the PhyPayload.
+    pub phy_payload_b: Vec<u8>,
     pub tx_info: gw::UplinkTxInfo,
     pub rx_info_set: Vec<gw::UplinkRxInfo>,
     pub gateway_private_up_map: HashMap<EUI64, bool>,
@@ -308,13 +313,32 @@ pub async fn handle_uplink(
     deduplication_id: Uuid,
     uplink: gw::UplinkFrameSet,
 ) -> Result<()> {
+    // Validate the frame and its type using the borrowed view before decoding the full
+    // PhyPayload, so that unexpected frames are rejected without allocating.
+    let phy_ref = lrwn::PhyPayloadRef::from_slice(&uplink.phy_payload)?;
+
+    UPLINK_COUNTER
+        .get_or_create(&UplinkLabels {
+            f_type: phy_ref.mhdr().f_type.to_string(),
+        })
+        .inc();
+
+    match phy_ref.mhdr().f_type {
+        FType::JoinRequest | FType::UnconfirmedDataUp | FType::ConfirmedDataUp => {}
+        _ => {
+            return Err(anyhow!("Unexpected f_type: {}", phy_ref.mhdr().f_type));
+        }
+    }
+    let phy_payload = phy_ref.to_phy_payload()?;
+
     let mut uplink = UplinkFrameSet {
         uplink_set_id: deduplication_id,
         region_common_name,
         region_config_id: region_config_id.to_string(),
         dr: 0,
         ch: 0,
-        phy_payload: PhyPayload::from_slice(&uplink.phy_payload)?,
+        phy_payload,
+        phy_payload_b: uplink.phy_payload,
         tx_info: uplink.tx_info.context("tx_info must not be None")?,
         rx_info_set: uplink.rx_info,
         gateway_private_up_map: HashMap::new(),
@@ -324,12 +348,6 @@ pub async fn handle_uplink(
         roaming_meta_data: None,
     };
 
-    UPLINK_COUNTER
-        .get_or_create(&UplinkLabels {
-            f_type: uplink.phy_payload.mhdr.f_type.to_string(),
-        })
-        .inc();
-
     uplink.dr = helpers::get_uplink_dr(&uplink.region_config_id, &uplink.tx_info)?;
     uplink.ch = helpers::get_uplink_ch(
         &uplink.region_config_id,
@@ -469,3 +487,18 @@ fn filter_rx_info_by_public_only(uplink: &mut UplinkFrameSet) -> Result<()> {
 
     Ok(())
 }
+
+// Returns the NetID used as SenderID when forwarding the uplink to a roaming partner.
+// This is the NetID of the tenant owning the gateway that received the uplink, or the
+// configured network NetID in case the gateway is unknown.
+async fn get_sender_net_id(uplink: &UplinkFrameSet) -> Result<NetID> {
+    for rx_info in &uplink.rx_info_set {
+        let gateway_id = EUI64::from_str(&rx_info.gateway_id).context("Gateway ID")?;
+        if let Some(tenant_id) = uplink.gateway_tenant_id_map.get(&gateway_id) {
+            let t = tenant::get(tenant_id).await?;
+            return Ok(t.get_net_id());
+        }
+    }
+
+    Ok(config::get().network.net_id)
+}
@@ -1,5 +1,5 @@
-use std::fmt;
-use std::str::FromStr;
+use core::fmt;
+use core::str::FromStr;
 
 use anyhow::Result;
 #[cfg(feature = "postgres")]
@@ -15,6 +15,7 @@ use serde::{
 };
 
 use crate::Error;
+use crate::prelude::*;
 
 #[derive(Copy, Clone, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "diesel", derive(AsExpression, FromSqlRow))]
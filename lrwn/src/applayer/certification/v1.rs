@@ -0,0 +1,910 @@
+use anyhow::Result;
+
+use crate::applayer::PayloadCodec;
+use crate::prelude::*;
+
+pub enum Cid {
+    PackageVersionReq,
+    PackageVersionAns,
+    DutResetReq,
+    DutJoinReq,
+    SwitchClassReq,
+    AdrBitChangeReq,
+    RegionalDutyCycleCtrlReq,
+    TxPeriodicityChangeReq,
+    TxFramesCtrlReq,
+    EchoPayloadReq,
+    EchoPayloadAns,
+    RxAppCntReq,
+    RxAppCntAns,
+    RxAppCntResetReq,
+    LinkCheckReq,
+    DeviceTimeReq,
+    PingSlotInfoReq,
+    TxCwReq,
+    DutFPort224DisableReq,
+    DutVersionsReq,
+    DutVersionsAns,
+}
+
+impl Cid {
+    pub fn from_u8(uplink: bool, value: u8) -> Result<Cid> {
+        Ok(match uplink {
+            true => match value {
+                0x00 => Cid::PackageVersionAns,
+                0x08 => Cid::EchoPayloadAns,
+                0x09 => Cid::RxAppCntAns,
+                0x7f => Cid::DutVersionsAns,
+                _ => return Err(anyhow!("Invalid CID: {}", value)),
+            },
+            false => match value {
+                0x00 => Cid::PackageVersionReq,
+                0x01 => Cid::DutResetReq,
+                0x02 => Cid::DutJoinReq,
+                0x03 => Cid::SwitchClassReq,
+                0x04 => Cid::AdrBitChangeReq,
+                0x05 => Cid::RegionalDutyCycleCtrlReq,
+                0x06 => Cid::TxPeriodicityChangeReq,
+                0x07 => Cid::TxFramesCtrlReq,
+                0x08 => Cid::EchoPayloadReq,
+                0x09 => Cid::RxAppCntReq,
+                0x0a => Cid::RxAppCntResetReq,
+                0x20 => Cid::LinkCheckReq,
+                0x21 => Cid::DeviceTimeReq,
+                0x22 => Cid::PingSlotInfoReq,
+                0x7d => Cid::TxCwReq,
+                0x7e => Cid::DutFPort224DisableReq,
+                0x7f => Cid::DutVersionsReq,
+                _ => return Err(anyhow!("Invalid CID: {}", value)),
+            },
+        })
+    }
+
+    pub fn to_u8(&self) -> u8 {
+        match self {
+            Cid::PackageVersionReq | Cid::PackageVersionAns => 0x00,
+            Cid::DutResetReq => 0x01,
+            Cid::DutJoinReq => 0x02,
+            Cid::SwitchClassReq => 0x03,
+            Cid::AdrBitChangeReq => 0x04,
+            Cid::RegionalDutyCycleCtrlReq => 0x05,
+            Cid::TxPeriodicityChangeReq => 0x06,
+            Cid::TxFramesCtrlReq => 0x07,
+            Cid::EchoPayloadReq | Cid::EchoPayloadAns => 0x08,
+            Cid::RxAppCntReq | Cid::RxAppCntAns => 0x09,
+            Cid::RxAppCntResetReq => 0x0a,
+            Cid::LinkCheckReq => 0x20,
+            Cid::DeviceTimeReq => 0x21,
+            Cid::PingSlotInfoReq => 0x22,
+            Cid::TxCwReq => 0x7d,
+            Cid::DutFPort224DisableReq => 0x7e,
+            Cid::DutVersionsReq | Cid::DutVersionsAns => 0x7f,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Payload {
+    PackageVersionReq,
+    PackageVersionAns(PackageVersionAnsPayload),
+    DutResetReq,
+    DutJoinReq,
+    SwitchClassReq(SwitchClassReqPayload),
+    AdrBitChangeReq(AdrBitChangeReqPayload),
+    RegionalDutyCycleCtrlReq(RegionalDutyCycleCtrlReqPayload),
+    TxPeriodicityChangeReq(TxPeriodicityChangeReqPayload),
+    TxFramesCtrlReq(TxFramesCtrlReqPayload),
+    EchoPayloadReq(EchoPayloadReqPayload),
+    EchoPayloadAns(EchoPayloadAnsPayload),
+    RxAppCntReq,
+    RxAppCntAns(RxAppCntAnsPayload),
+    RxAppCntResetReq,
+    LinkCheckReq,
+    DeviceTimeReq,
+    PingSlotInfoReq(PingSlotInfoReqPayload),
+    TxCwReq(TxCwReqPayload),
+    DutFPort224DisableReq,
+    DutVersionsReq,
+    DutVersionsAns(DutVersionsAnsPayload),
+}
+
+impl Payload {
+    pub fn cid(&self) -> Cid {
+        match self {
+            Self::PackageVersionReq => Cid::PackageVersionReq,
+            Self::PackageVersionAns(_) => Cid::PackageVersionAns,
+            Self::DutResetReq => Cid::DutResetReq,
+            Self::DutJoinReq => Cid::DutJoinReq,
+            Self::SwitchClassReq(_) => Cid::SwitchClassReq,
+            Self::AdrBitChangeReq(_) => Cid::AdrBitChangeReq,
+            Self::RegionalDutyCycleCtrlReq(_) => Cid::RegionalDutyCycleCtrlReq,
+            Self::TxPeriodicityChangeReq(_) => Cid::TxPeriodicityChangeReq,
+            Self::TxFramesCtrlReq(_) => Cid::TxFramesCtrlReq,
+            Self::EchoPayloadReq(_) => Cid::EchoPayloadReq,
+            Self::EchoPayloadAns(_) => Cid::EchoPayloadAns,
+            Self::RxAppCntReq => Cid::RxAppCntReq,
+            Self::RxAppCntAns(_) => Cid::RxAppCntAns,
+            Self::RxAppCntResetReq => Cid::RxAppCntResetReq,
+            Self::LinkCheckReq => Cid::LinkCheckReq,
+            Self::DeviceTimeReq => Cid::DeviceTimeReq,
+            Self::PingSlotInfoReq(_) => Cid::PingSlotInfoReq,
+            Self::TxCwReq(_) => Cid::TxCwReq,
+            Self::DutFPort224DisableReq => Cid::DutFPort224DisableReq,
+            Self::DutVersionsReq => Cid::DutVersionsReq,
+            Self::DutVersionsAns(_) => Cid::DutVersionsAns,
+        }
+    }
+
+    pub fn from_slice(uplink: bool, b: &[u8]) -> Result<Self> {
+        if b.is_empty() {
+            return Err(anyhow!("At least one byte is expected"));
+        }
+
+        let cid = Cid::from_u8(uplink, b[0])?;
+
+        Ok(match cid {
+            Cid::PackageVersionReq => Payload::PackageVersionReq,
+            Cid::PackageVersionAns => {
+                Payload::PackageVersionAns(PackageVersionAnsPayload::decode(&b[1..])?)
+            }
+            Cid::DutResetReq => Payload::DutResetReq,
+            Cid::DutJoinReq => Payload::DutJoinReq,
+            Cid::SwitchClassReq => Payload::SwitchClassReq(SwitchClassReqPayload::decode(&b[1..])?),
+            Cid::AdrBitChangeReq => {
+                Payload::AdrBitChangeReq(AdrBitChangeReqPayload::decode(&b[1..])?)
+            }
+            Cid::RegionalDutyCycleCtrlReq => {
+                Payload::RegionalDutyCycleCtrlReq(RegionalDutyCycleCtrlReqPayload::decode(&b[1..])?)
+            }
+            Cid::TxPeriodicityChangeReq => {
+                Payload::TxPeriodicityChangeReq(TxPeriodicityChangeReqPayload::decode(&b[1..])?)
+            }
+            Cid::TxFramesCtrlReq => {
+                Payload::TxFramesCtrlReq(TxFramesCtrlReqPayload::decode(&b[1..])?)
+            }
+            Cid::EchoPayloadReq => Payload::EchoPayloadReq(EchoPayloadReqPayload::decode(&b[1..])?),
+            Cid::EchoPayloadAns => Payload::EchoPayloadAns(EchoPayloadAnsPayload::decode(&b[1..])?),
+            Cid::RxAppCntReq => Payload::RxAppCntReq,
+            Cid::RxAppCntAns => Payload::RxAppCntAns(RxAppCntAnsPayload::decode(&b[1..])?),
+            Cid::RxAppCntResetReq => Payload::RxAppCntResetReq,
+            Cid::LinkCheckReq => Payload::LinkCheckReq,
+            Cid::DeviceTimeReq => Payload::DeviceTimeReq,
+            Cid::PingSlotInfoReq => {
+                Payload::PingSlotInfoReq(PingSlotInfoReqPayload::decode(&b[1..])?)
+            }
+            Cid::TxCwReq => Payload::TxCwReq(TxCwReqPayload::decode(&b[1..])?),
+            Cid::DutFPort224DisableReq => Payload::DutFPort224DisableReq,
+            Cid::DutVersionsReq => Payload::DutVersionsReq,
+            Cid::DutVersionsAns => Payload::DutVersionsAns(DutVersionsAnsPayload::decode(&b[1..])?),
+        })
+    }
+
+    pub fn to_vec(&self) -> Result<Vec<u8>> {
+        let mut out = vec![self.cid().to_u8()];
+
+        match self {
+            Self::PackageVersionReq
+            | Self::DutResetReq
+            | Self::DutJoinReq
+            | Self::RxAppCntReq
+            | Self::RxAppCntResetReq
+            | Self::LinkCheckReq
+            | Self::DeviceTimeReq
+            | Self::DutFPort224DisableReq
+            | Self::DutVersionsReq => {}
+            Self::PackageVersionAns(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::SwitchClassReq(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::AdrBitChangeReq(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::RegionalDutyCycleCtrlReq(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::TxPeriodicityChangeReq(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::TxFramesCtrlReq(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::EchoPayloadReq(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::EchoPayloadAns(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::RxAppCntAns(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::PingSlotInfoReq(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::TxCwReq(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::DutVersionsAns(pl) => out.extend_from_slice(&pl.encode()?),
+        };
+
+        Ok(out)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PackageVersionAnsPayload {
+    pub package_identifier: u8,
+    pub package_version: u8,
+}
+
+impl PayloadCodec for PackageVersionAnsPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 2 {
+            return Err(anyhow!("Expected 2 bytes"));
+        }
+
+        Ok(PackageVersionAnsPayload {
+            package_identifier: b[0],
+            package_version: b[1],
+        })
+    }
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(vec![self.package_identifier, self.package_version])
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DeviceClass {
+    A,
+    B,
+    C,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SwitchClassReqPayload {
+    pub class: DeviceClass,
+}
+
+impl PayloadCodec for SwitchClassReqPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 1 {
+            return Err(anyhow!("Expected 1 byte"));
+        }
+
+        Ok(SwitchClassReqPayload {
+            class: match b[0] {
+                0x00 => DeviceClass::A,
+                0x01 => DeviceClass::B,
+                0x02 => DeviceClass::C,
+                _ => return Err(anyhow!("Invalid class: {}", b[0])),
+            },
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(vec![match self.class {
+            DeviceClass::A => 0x00,
+            DeviceClass::B => 0x01,
+            DeviceClass::C => 0x02,
+        }])
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct AdrBitChangeReqPayload {
+    pub adr_enable: bool,
+}
+
+impl PayloadCodec for AdrBitChangeReqPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 1 {
+            return Err(anyhow!("Expected 1 byte"));
+        }
+
+        Ok(AdrBitChangeReqPayload {
+            adr_enable: b[0] & 0x01 != 0,
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(vec![self.adr_enable as u8])
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RegionalDutyCycleCtrlReqPayload {
+    pub duty_cycle_enable: bool,
+}
+
+impl PayloadCodec for RegionalDutyCycleCtrlReqPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 1 {
+            return Err(anyhow!("Expected 1 byte"));
+        }
+
+        Ok(RegionalDutyCycleCtrlReqPayload {
+            duty_cycle_enable: b[0] & 0x01 != 0,
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(vec![self.duty_cycle_enable as u8])
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TxPeriodicityChangeReqPayload {
+    // 0 = application default, 1 - 10 = 5, 10, 20, 30, 40, 50, 60, 120, 240, 480 seconds.
+    pub periodicity: u8,
+}
+
+impl PayloadCodec for TxPeriodicityChangeReqPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 1 {
+            return Err(anyhow!("Expected 1 byte"));
+        }
+
+        Ok(TxPeriodicityChangeReqPayload { periodicity: b[0] })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        if self.periodicity > 10 {
+            return Err(anyhow!("Max periodicity value is 10"));
+        }
+
+        Ok(vec![self.periodicity])
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FrameType {
+    NoChange,
+    Unconfirmed,
+    Confirmed,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TxFramesCtrlReqPayload {
+    pub frame_type: FrameType,
+}
+
+impl PayloadCodec for TxFramesCtrlReqPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 1 {
+            return Err(anyhow!("Expected 1 byte"));
+        }
+
+        Ok(TxFramesCtrlReqPayload {
+            frame_type: match b[0] {
+                0x00 => FrameType::NoChange,
+                0x01 => FrameType::Unconfirmed,
+                0x02 => FrameType::Confirmed,
+                _ => return Err(anyhow!("Invalid frame_type: {}", b[0])),
+            },
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(vec![match self.frame_type {
+            FrameType::NoChange => 0x00,
+            FrameType::Unconfirmed => 0x01,
+            FrameType::Confirmed => 0x02,
+        }])
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct EchoPayloadReqPayload {
+    pub payload: Vec<u8>,
+}
+
+impl EchoPayloadReqPayload {
+    // Returns the payload that the DUT is expected to return in the EchoPayloadAns.
+    pub fn expected_ans(&self) -> EchoPayloadAnsPayload {
+        EchoPayloadAnsPayload {
+            payload: self.payload.iter().map(|b| b.wrapping_add(1)).collect(),
+        }
+    }
+}
+
+impl PayloadCodec for EchoPayloadReqPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        Ok(EchoPayloadReqPayload {
+            payload: b.to_vec(),
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(self.payload.clone())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct EchoPayloadAnsPayload {
+    pub payload: Vec<u8>,
+}
+
+impl PayloadCodec for EchoPayloadAnsPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        Ok(EchoPayloadAnsPayload {
+            payload: b.to_vec(),
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(self.payload.clone())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RxAppCntAnsPayload {
+    pub rx_app_cnt: u16,
+}
+
+impl PayloadCodec for RxAppCntAnsPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 2 {
+            return Err(anyhow!("Expected 2 bytes"));
+        }
+
+        Ok(RxAppCntAnsPayload {
+            rx_app_cnt: u16::from_le_bytes([b[0], b[1]]),
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(self.rx_app_cnt.to_le_bytes().to_vec())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PingSlotInfoReqPayload {
+    pub periodicity: u8,
+}
+
+impl PayloadCodec for PingSlotInfoReqPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 1 {
+            return Err(anyhow!("Expected 1 byte"));
+        }
+
+        Ok(PingSlotInfoReqPayload {
+            periodicity: b[0] & 0x07,
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        if self.periodicity > 7 {
+            return Err(anyhow!("Max periodicity value is 7"));
+        }
+
+        Ok(vec![self.periodicity])
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TxCwReqPayload {
+    // Timeout in seconds.
+    pub timeout: u16,
+    // Frequency in Hz, must be a multiple of 100.
+    pub frequency: u32,
+    // TX power in dBm.
+    pub tx_power: i8,
+}
+
+impl PayloadCodec for TxCwReqPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 6 {
+            return Err(anyhow!("Expected 6 bytes"));
+        }
+
+        Ok(TxCwReqPayload {
+            timeout: u16::from_le_bytes([b[0], b[1]]),
+            frequency: u32::from_le_bytes([b[2], b[3], b[4], 0x00]) * 100,
+            tx_power: b[5] as i8,
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        if !self.frequency.is_multiple_of(100) {
+            return Err(anyhow!("Frequency must be a multiple of 100"));
+        }
+
+        let frequency = self.frequency / 100;
+        if frequency >= 1 << 24 {
+            return Err(anyhow!("Max frequency value is 1677721500"));
+        }
+
+        let mut b = Vec::with_capacity(6);
+        b.extend_from_slice(&self.timeout.to_le_bytes());
+        b.extend_from_slice(&frequency.to_le_bytes()[0..3]);
+        b.push(self.tx_power as u8);
+        Ok(b)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Version {
+    pub major: u8,
+    pub minor: u8,
+    pub patch: u8,
+    pub revision: u8,
+}
+
+impl Version {
+    fn from_bytes(b: &[u8]) -> Self {
+        Version {
+            major: b[0],
+            minor: b[1],
+            patch: b[2],
+            revision: b[3],
+        }
+    }
+
+    fn to_bytes(self) -> [u8; 4] {
+        [self.major, self.minor, self.patch, self.revision]
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DutVersionsAnsPayload {
+    pub fw_version: Version,
+    pub lrwan_version: Version,
+    pub lrwan_rp_version: Version,
+}
+
+impl PayloadCodec for DutVersionsAnsPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 12 {
+            return Err(anyhow!("Expected 12 bytes"));
+        }
+
+        Ok(DutVersionsAnsPayload {
+            fw_version: Version::from_bytes(&b[0..4]),
+            lrwan_version: Version::from_bytes(&b[4..8]),
+            lrwan_rp_version: Version::from_bytes(&b[8..12]),
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut b = Vec::with_capacity(12);
+        b.extend_from_slice(&self.fw_version.to_bytes());
+        b.extend_from_slice(&self.lrwan_version.to_bytes());
+        b.extend_from_slice(&self.lrwan_rp_version.to_bytes());
+        Ok(b)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct CommandTest {
+        name: String,
+        uplink: bool,
+        command: Payload,
+        bytes: Vec<u8>,
+        expected_error: Option<String>,
+    }
+
+    #[test]
+    fn test_package_version() {
+        let tests = [
+            CommandTest {
+                name: "PackageVersionReq".into(),
+                uplink: false,
+                command: Payload::PackageVersionReq,
+                bytes: vec![0x00],
+                expected_error: None,
+            },
+            CommandTest {
+                name: "PackageVersionAns".into(),
+                uplink: true,
+                command: Payload::PackageVersionAns(PackageVersionAnsPayload {
+                    package_identifier: 6,
+                    package_version: 1,
+                }),
+                bytes: vec![0x00, 0x06, 0x01],
+                expected_error: None,
+            },
+        ];
+
+        run_tests_encode(&tests);
+        run_tests_decode(&tests);
+    }
+
+    #[test]
+    fn test_no_payload_commands() {
+        let tests = [
+            CommandTest {
+                name: "DutResetReq".into(),
+                uplink: false,
+                command: Payload::DutResetReq,
+                bytes: vec![0x01],
+                expected_error: None,
+            },
+            CommandTest {
+                name: "DutJoinReq".into(),
+                uplink: false,
+                command: Payload::DutJoinReq,
+                bytes: vec![0x02],
+                expected_error: None,
+            },
+            CommandTest {
+                name: "RxAppCntReq".into(),
+                uplink: false,
+                command: Payload::RxAppCntReq,
+                bytes: vec![0x09],
+                expected_error: None,
+            },
+            CommandTest {
+                name: "RxAppCntResetReq".into(),
+                uplink: false,
+                command: Payload::RxAppCntResetReq,
+                bytes: vec![0x0a],
+                expected_error: None,
+            },
+            CommandTest {
+                name: "LinkCheckReq".into(),
+                uplink: false,
+                command: Payload::LinkCheckReq,
+                bytes: vec![0x20],
+                expected_error: None,
+            },
+            CommandTest {
+                name: "DeviceTimeReq".into(),
+                uplink: false,
+                command: Payload::DeviceTimeReq,
+                bytes: vec![0x21],
+                expected_error: None,
+            },
+            CommandTest {
+                name: "DutFPort224DisableReq".into(),
+                uplink: false,
+                command: Payload::DutFPort224DisableReq,
+                bytes: vec![0x7e],
+                expected_error: None,
+            },
+            CommandTest {
+                name: "DutVersionsReq".into(),
+                uplink: false,
+                command: Payload::DutVersionsReq,
+                bytes: vec![0x7f],
+                expected_error: None,
+            },
+        ];
+
+        run_tests_encode(&tests);
+        run_tests_decode(&tests);
+    }
+
+    #[test]
+    fn test_switch_class_req() {
+        let tests = [CommandTest {
+            name: "SwitchClassReq".into(),
+            uplink: false,
+            command: Payload::SwitchClassReq(SwitchClassReqPayload {
+                class: DeviceClass::C,
+            }),
+            bytes: vec![0x03, 0x02],
+            expected_error: None,
+        }];
+
+        let decode_tests = [CommandTest {
+            name: "decode SwitchClassReq invalid class".into(),
+            uplink: false,
+            command: Payload::DutResetReq,
+            bytes: vec![0x03, 0x03],
+            expected_error: Some("Invalid class: 3".into()),
+        }];
+
+        run_tests_encode(&tests);
+        run_tests_decode(&tests);
+        run_tests_decode(&decode_tests);
+    }
+
+    #[test]
+    fn test_adr_bit_change_req() {
+        let tests = [CommandTest {
+            name: "AdrBitChangeReq".into(),
+            uplink: false,
+            command: Payload::AdrBitChangeReq(AdrBitChangeReqPayload { adr_enable: true }),
+            bytes: vec![0x04, 0x01],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&tests);
+        run_tests_decode(&tests);
+    }
+
+    #[test]
+    fn test_regional_duty_cycle_ctrl_req() {
+        let tests = [CommandTest {
+            name: "RegionalDutyCycleCtrlReq".into(),
+            uplink: false,
+            command: Payload::RegionalDutyCycleCtrlReq(RegionalDutyCycleCtrlReqPayload {
+                duty_cycle_enable: false,
+            }),
+            bytes: vec![0x05, 0x00],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&tests);
+        run_tests_decode(&tests);
+    }
+
+    #[test]
+    fn test_tx_periodicity_change_req() {
+        let tests = [CommandTest {
+            name: "TxPeriodicityChangeReq".into(),
+            uplink: false,
+            command: Payload::TxPeriodicityChangeReq(TxPeriodicityChangeReqPayload {
+                periodicity: 3,
+            }),
+            bytes: vec![0x06, 0x03],
+            expected_error: None,
+        }];
+
+        let encode_tests = [CommandTest {
+            name: "encode TxPeriodicityChangeReq invalid periodicity".into(),
+            uplink: false,
+            command: Payload::TxPeriodicityChangeReq(TxPeriodicityChangeReqPayload {
+                periodicity: 11,
+            }),
+            bytes: vec![],
+            expected_error: Some("Max periodicity value is 10".into()),
+        }];
+
+        run_tests_encode(&tests);
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&tests);
+    }
+
+    #[test]
+    fn test_tx_frames_ctrl_req() {
+        let tests = [CommandTest {
+            name: "TxFramesCtrlReq".into(),
+            uplink: false,
+            command: Payload::TxFramesCtrlReq(TxFramesCtrlReqPayload {
+                frame_type: FrameType::Confirmed,
+            }),
+            bytes: vec![0x07, 0x02],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&tests);
+        run_tests_decode(&tests);
+    }
+
+    #[test]
+    fn test_echo_payload() {
+        let req = EchoPayloadReqPayload {
+            payload: vec![0x01, 0x02, 0xff],
+        };
+        assert_eq!(
+            EchoPayloadAnsPayload {
+                payload: vec![0x02, 0x03, 0x00],
+            },
+            req.expected_ans()
+        );
+
+        let tests = [
+            CommandTest {
+                name: "EchoPayloadReq".into(),
+                uplink: false,
+                command: Payload::EchoPayloadReq(req.clone()),
+                bytes: vec![0x08, 0x01, 0x02, 0xff],
+                expected_error: None,
+            },
+            CommandTest {
+                name: "EchoPayloadAns".into(),
+                uplink: true,
+                command: Payload::EchoPayloadAns(req.expected_ans()),
+                bytes: vec![0x08, 0x02, 0x03, 0x00],
+                expected_error: None,
+            },
+        ];
+
+        run_tests_encode(&tests);
+        run_tests_decode(&tests);
+    }
+
+    #[test]
+    fn test_rx_app_cnt_ans() {
+        let tests = [CommandTest {
+            name: "RxAppCntAns".into(),
+            uplink: true,
+            command: Payload::RxAppCntAns(RxAppCntAnsPayload { rx_app_cnt: 258 }),
+            bytes: vec![0x09, 0x02, 0x01],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&tests);
+        run_tests_decode(&tests);
+    }
+
+    #[test]
+    fn test_ping_slot_info_req() {
+        let tests = [CommandTest {
+            name: "PingSlotInfoReq".into(),
+            uplink: false,
+            command: Payload::PingSlotInfoReq(PingSlotInfoReqPayload { periodicity: 7 }),
+            bytes: vec![0x22, 0x07],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&tests);
+        run_tests_decode(&tests);
+    }
+
+    #[test]
+    fn test_tx_cw_req() {
+        let tests = [CommandTest {
+            name: "TxCwReq".into(),
+            uplink: false,
+            command: Payload::TxCwReq(TxCwReqPayload {
+                timeout: 10,
+                frequency: 868100000,
+                tx_power: 14,
+            }),
+            bytes: vec![0x7d, 0x0a, 0x00, 0x28, 0x76, 0x84, 0x0e],
+            expected_error: None,
+        }];
+
+        let encode_tests = [CommandTest {
+            name: "encode TxCwReq invalid frequency".into(),
+            uplink: false,
+            command: Payload::TxCwReq(TxCwReqPayload {
+                timeout: 10,
+                frequency: 868100050,
+                tx_power: 14,
+            }),
+            bytes: vec![],
+            expected_error: Some("Frequency must be a multiple of 100".into()),
+        }];
+
+        run_tests_encode(&tests);
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&tests);
+    }
+
+    #[test]
+    fn test_dut_versions_ans() {
+        let tests = [CommandTest {
+            name: "DutVersionsAns".into(),
+            uplink: true,
+            command: Payload::DutVersionsAns(DutVersionsAnsPayload {
+                fw_version: Version {
+                    major: 1,
+                    minor: 2,
+                    patch: 3,
+                    revision: 4,
+                },
+                lrwan_version: Version {
+                    major: 1,
+                    minor: 0,
+                    patch: 4,
+                    revision: 0,
+                },
+                lrwan_rp_version: Version {
+                    major: 2,
+                    minor: 1,
+                    patch: 0,
+                    revision: 3,
+                },
+            }),
+            bytes: vec![
+                0x7f, 0x01, 0x02, 0x03, 0x04, 0x01, 0x00, 0x04, 0x00, 0x02, 0x01, 0x00, 0x03,
+            ],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&tests);
+        run_tests_decode(&tests);
+    }
+
+    fn run_tests_encode(tests: &[CommandTest]) {
+        for tst in tests {
+            println!("> {}", tst.name);
+            let resp = tst.command.to_vec();
+            if let Some(e) = &tst.expected_error {
+                assert!(resp.is_err());
+                assert_eq!(e, &resp.err().unwrap().to_string());
+            } else {
+                assert_eq!(tst.bytes, resp.unwrap());
+            }
+        }
+    }
+
+    fn run_tests_decode(tests: &[CommandTest]) {
+        for tst in tests {
+            println!("> {}", tst.name);
+            let resp = Payload::from_slice(tst.uplink, &tst.bytes);
+            if let Some(e) = &tst.expected_error {
+                assert!(resp.is_err());
+                assert_eq!(e, &resp.err().unwrap().to_string());
+            } else {
+                assert_eq!(tst.command, resp.unwrap());
+            }
+        }
+    }
+}
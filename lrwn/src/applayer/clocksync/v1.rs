@@ -1,6 +1,7 @@
 use anyhow::Result;
 
 use crate::applayer::PayloadCodec;
+use crate::prelude::*;
 
 pub enum Cid {
     PackageVersionReq,
@@ -0,0 +1,359 @@
+use anyhow::Result;
+
+use crate::applayer::PayloadCodec;
+use crate::prelude::*;
+
+pub enum Cid {
+    PackageVersionReq,
+    PackageVersionAns,
+    DataBlockReq,
+    DataBlockAns,
+}
+
+impl Cid {
+    pub fn from_u8(uplink: bool, value: u8) -> Result<Cid> {
+        Ok(match uplink {
+            true => match value {
+                0x00 => Cid::PackageVersionAns,
+                0x01 => Cid::DataBlockReq,
+                _ => return Err(anyhow!("Invalid CID: {}", value)),
+            },
+            false => match value {
+                0x00 => Cid::PackageVersionReq,
+                0x01 => Cid::DataBlockAns,
+                _ => return Err(anyhow!("Invalid CID: {}", value)),
+            },
+        })
+    }
+
+    pub fn to_u8(&self) -> u8 {
+        match self {
+            Cid::PackageVersionReq | Cid::PackageVersionAns => 0x00,
+            Cid::DataBlockReq | Cid::DataBlockAns => 0x01,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Payload {
+    PackageVersionReq,
+    PackageVersionAns(PackageVersionAnsPayload),
+    DataBlockReq(DataBlockReqPayload),
+    DataBlockAns(DataBlockAnsPayload),
+}
+
+impl Payload {
+    pub fn cid(&self) -> Cid {
+        match self {
+            Self::PackageVersionReq => Cid::PackageVersionReq,
+            Self::PackageVersionAns(_) => Cid::PackageVersionAns,
+            Self::DataBlockReq(_) => Cid::DataBlockReq,
+            Self::DataBlockAns(_) => Cid::DataBlockAns,
+        }
+    }
+
+    pub fn from_slice(uplink: bool, b: &[u8]) -> Result<Self> {
+        if b.is_empty() {
+            return Err(anyhow!("At least one byte is expected"));
+        }
+
+        let cid = Cid::from_u8(uplink, b[0])?;
+
+        Ok(match cid {
+            Cid::PackageVersionReq => Payload::PackageVersionReq,
+            Cid::PackageVersionAns => {
+                Payload::PackageVersionAns(PackageVersionAnsPayload::decode(&b[1..])?)
+            }
+            Cid::DataBlockReq => Payload::DataBlockReq(DataBlockReqPayload::decode(&b[1..])?),
+            Cid::DataBlockAns => Payload::DataBlockAns(DataBlockAnsPayload::decode(&b[1..])?),
+        })
+    }
+
+    pub fn to_vec(&self) -> Result<Vec<u8>> {
+        let mut out = vec![self.cid().to_u8()];
+
+        match self {
+            Self::PackageVersionReq => {}
+            Self::PackageVersionAns(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::DataBlockReq(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::DataBlockAns(pl) => out.extend_from_slice(&pl.encode()?),
+        };
+
+        Ok(out)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PackageVersionAnsPayload {
+    pub package_identifier: u8,
+    pub package_version: u8,
+}
+
+impl PayloadCodec for PackageVersionAnsPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 2 {
+            return Err(anyhow!("Expected 2 bytes"));
+        }
+
+        Ok(PackageVersionAnsPayload {
+            package_identifier: b[0],
+            package_version: b[1],
+        })
+    }
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(vec![self.package_identifier, self.package_version])
+    }
+}
+
+// DataBlockReq contains a single fragment of a (device originated) data block.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DataBlockReqPayload {
+    pub block_id: u8,
+    pub index_and_last: DataBlockReqPayloadIndexAndLast,
+    pub data: Vec<u8>,
+}
+
+impl PayloadCodec for DataBlockReqPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() < 3 {
+            return Err(anyhow!("At least 3 bytes expected"));
+        }
+
+        let index_and_last = u16::from_le_bytes([b[1], b[2]]);
+
+        Ok(DataBlockReqPayload {
+            block_id: b[0],
+            index_and_last: DataBlockReqPayloadIndexAndLast {
+                index: index_and_last & 0x7fff,
+                last: index_and_last & 0x8000 != 0,
+            },
+            data: b[3..].to_vec(),
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        if self.index_and_last.index > 32767 {
+            return Err(anyhow!("Max index value is 32767"));
+        }
+
+        let mut index_and_last = self.index_and_last.index;
+        if self.index_and_last.last {
+            index_and_last |= 0x8000;
+        }
+
+        let mut b = vec![self.block_id];
+        b.extend_from_slice(&index_and_last.to_le_bytes());
+        b.extend_from_slice(&self.data);
+        Ok(b)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DataBlockReqPayloadIndexAndLast {
+    pub index: u16,
+    pub last: bool,
+}
+
+// DataBlockAns acknowledges all the fragments of the data block before next_index.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DataBlockAnsPayload {
+    pub block_id: u8,
+    pub next_index: u16,
+}
+
+impl PayloadCodec for DataBlockAnsPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 3 {
+            return Err(anyhow!("Expected 3 bytes"));
+        }
+
+        Ok(DataBlockAnsPayload {
+            block_id: b[0],
+            next_index: u16::from_le_bytes([b[1], b[2]]),
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut b = vec![self.block_id];
+        b.extend_from_slice(&self.next_index.to_le_bytes());
+        Ok(b)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct CommandTest {
+        name: String,
+        uplink: bool,
+        command: Payload,
+        bytes: Vec<u8>,
+        expected_error: Option<String>,
+    }
+
+    #[test]
+    fn test_package_version_req() {
+        let encode_tests = [CommandTest {
+            name: "encode PackageVersionReq".into(),
+            uplink: false,
+            command: Payload::PackageVersionReq,
+            bytes: vec![0x00],
+            expected_error: None,
+        }];
+
+        let decode_tests = [CommandTest {
+            name: "decode PackageVersionReq".into(),
+            uplink: false,
+            command: Payload::PackageVersionReq,
+            bytes: vec![0x00],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&decode_tests);
+    }
+
+    #[test]
+    fn test_package_version_ans() {
+        let encode_tests = [CommandTest {
+            name: "encode PackageVersionAns".into(),
+            uplink: true,
+            command: Payload::PackageVersionAns(PackageVersionAnsPayload {
+                package_identifier: 10,
+                package_version: 1,
+            }),
+            bytes: vec![0x00, 0x0a, 0x01],
+            expected_error: None,
+        }];
+
+        let decode_tests = [CommandTest {
+            name: "decode PackageVersionAns".into(),
+            uplink: true,
+            command: Payload::PackageVersionAns(PackageVersionAnsPayload {
+                package_identifier: 10,
+                package_version: 1,
+            }),
+            bytes: vec![0x00, 0x0a, 0x01],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&decode_tests);
+    }
+
+    #[test]
+    fn test_data_block_req() {
+        let encode_tests = [
+            CommandTest {
+                name: "encode DataBlockReq".into(),
+                uplink: true,
+                command: Payload::DataBlockReq(DataBlockReqPayload {
+                    block_id: 2,
+                    index_and_last: DataBlockReqPayloadIndexAndLast {
+                        index: 258,
+                        last: true,
+                    },
+                    data: vec![0x01, 0x02, 0x03],
+                }),
+                bytes: vec![0x01, 0x02, 0x02, 0x81, 0x01, 0x02, 0x03],
+                expected_error: None,
+            },
+            CommandTest {
+                name: "encode DataBlockReq index too large".into(),
+                uplink: true,
+                command: Payload::DataBlockReq(DataBlockReqPayload {
+                    block_id: 2,
+                    index_and_last: DataBlockReqPayloadIndexAndLast {
+                        index: 32768,
+                        last: false,
+                    },
+                    data: vec![],
+                }),
+                bytes: vec![],
+                expected_error: Some("Max index value is 32767".into()),
+            },
+        ];
+
+        let decode_tests = [
+            CommandTest {
+                name: "decode DataBlockReq".into(),
+                uplink: true,
+                command: Payload::DataBlockReq(DataBlockReqPayload {
+                    block_id: 2,
+                    index_and_last: DataBlockReqPayloadIndexAndLast {
+                        index: 258,
+                        last: true,
+                    },
+                    data: vec![0x01, 0x02, 0x03],
+                }),
+                bytes: vec![0x01, 0x02, 0x02, 0x81, 0x01, 0x02, 0x03],
+                expected_error: None,
+            },
+            CommandTest {
+                name: "decode DataBlockReq too short".into(),
+                uplink: true,
+                command: Payload::PackageVersionReq,
+                bytes: vec![0x01, 0x02, 0x02],
+                expected_error: Some("At least 3 bytes expected".into()),
+            },
+        ];
+
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&decode_tests);
+    }
+
+    #[test]
+    fn test_data_block_ans() {
+        let encode_tests = [CommandTest {
+            name: "encode DataBlockAns".into(),
+            uplink: false,
+            command: Payload::DataBlockAns(DataBlockAnsPayload {
+                block_id: 2,
+                next_index: 258,
+            }),
+            bytes: vec![0x01, 0x02, 0x02, 0x01],
+            expected_error: None,
+        }];
+
+        let decode_tests = [CommandTest {
+            name: "decode DataBlockAns".into(),
+            uplink: false,
+            command: Payload::DataBlockAns(DataBlockAnsPayload {
+                block_id: 2,
+                next_index: 258,
+            }),
+            bytes: vec![0x01, 0x02, 0x02, 0x01],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&decode_tests);
+    }
+
+    fn run_tests_encode(tests: &[CommandTest]) {
+        for tst in tests {
+            println!("> {}", tst.name);
+            let resp = tst.command.to_vec();
+            if let Some(e) = &tst.expected_error {
+                assert!(resp.is_err());
+                assert_eq!(e, &resp.err().unwrap().to_string());
+            } else {
+                assert_eq!(tst.bytes, resp.unwrap());
+            }
+        }
+    }
+
+    fn run_tests_decode(tests: &[CommandTest]) {
+        for tst in tests {
+            println!("> {}", tst.name);
+            let resp = Payload::from_slice(tst.uplink, &tst.bytes);
+            if let Some(e) = &tst.expected_error {
+                assert!(resp.is_err());
+                assert_eq!(e, &resp.err().unwrap().to_string());
+            } else {
+                assert_eq!(tst.command, resp.unwrap());
+            }
+        }
+    }
+}
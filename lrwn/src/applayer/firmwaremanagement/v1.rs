@@ -0,0 +1,893 @@
+use anyhow::Result;
+
+use crate::applayer::PayloadCodec;
+use crate::prelude::*;
+
+pub enum Cid {
+    PackageVersionReq,
+    PackageVersionAns,
+    DevVersionReq,
+    DevVersionAns,
+    DevRebootTimeReq,
+    DevRebootTimeAns,
+    DevRebootCountdownReq,
+    DevRebootCountdownAns,
+    DevUpgradeImageReq,
+    DevUpgradeImageAns,
+    DevDeleteImageReq,
+    DevDeleteImageAns,
+}
+
+impl Cid {
+    pub fn from_u8(uplink: bool, value: u8) -> Result<Cid> {
+        Ok(match uplink {
+            true => match value {
+                0x00 => Cid::PackageVersionAns,
+                0x01 => Cid::DevVersionAns,
+                0x02 => Cid::DevRebootTimeAns,
+                0x03 => Cid::DevRebootCountdownAns,
+                0x04 => Cid::DevUpgradeImageAns,
+                0x05 => Cid::DevDeleteImageAns,
+                _ => return Err(anyhow!("Invalid CID: {}", value)),
+            },
+            false => match value {
+                0x00 => Cid::PackageVersionReq,
+                0x01 => Cid::DevVersionReq,
+                0x02 => Cid::DevRebootTimeReq,
+                0x03 => Cid::DevRebootCountdownReq,
+                0x04 => Cid::DevUpgradeImageReq,
+                0x05 => Cid::DevDeleteImageReq,
+                _ => return Err(anyhow!("Invalid CID: {}", value)),
+            },
+        })
+    }
+
+    pub fn to_u8(&self) -> u8 {
+        match self {
+            Cid::PackageVersionReq | Cid::PackageVersionAns => 0x00,
+            Cid::DevVersionReq | Cid::DevVersionAns => 0x01,
+            Cid::DevRebootTimeReq | Cid::DevRebootTimeAns => 0x02,
+            Cid::DevRebootCountdownReq | Cid::DevRebootCountdownAns => 0x03,
+            Cid::DevUpgradeImageReq | Cid::DevUpgradeImageAns => 0x04,
+            Cid::DevDeleteImageReq | Cid::DevDeleteImageAns => 0x05,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Payload {
+    PackageVersionReq,
+    PackageVersionAns(PackageVersionAnsPayload),
+    DevVersionReq,
+    DevVersionAns(DevVersionAnsPayload),
+    DevRebootTimeReq(DevRebootTimeReqPayload),
+    DevRebootTimeAns(DevRebootTimeAnsPayload),
+    DevRebootCountdownReq(DevRebootCountdownReqPayload),
+    DevRebootCountdownAns(DevRebootCountdownAnsPayload),
+    DevUpgradeImageReq,
+    DevUpgradeImageAns(DevUpgradeImageAnsPayload),
+    DevDeleteImageReq(DevDeleteImageReqPayload),
+    DevDeleteImageAns(DevDeleteImageAnsPayload),
+}
+
+impl Payload {
+    pub fn cid(&self) -> Cid {
+        match self {
+            Self::PackageVersionReq => Cid::PackageVersionReq,
+            Self::PackageVersionAns(_) => Cid::PackageVersionAns,
+            Self::DevVersionReq => Cid::DevVersionReq,
+            Self::DevVersionAns(_) => Cid::DevVersionAns,
+            Self::DevRebootTimeReq(_) => Cid::DevRebootTimeReq,
+            Self::DevRebootTimeAns(_) => Cid::DevRebootTimeAns,
+            Self::DevRebootCountdownReq(_) => Cid::DevRebootCountdownReq,
+            Self::DevRebootCountdownAns(_) => Cid::DevRebootCountdownAns,
+            Self::DevUpgradeImageReq => Cid::DevUpgradeImageReq,
+            Self::DevUpgradeImageAns(_) => Cid::DevUpgradeImageAns,
+            Self::DevDeleteImageReq(_) => Cid::DevDeleteImageReq,
+            Self::DevDeleteImageAns(_) => Cid::DevDeleteImageAns,
+        }
+    }
+
+    pub fn from_slice(uplink: bool, b: &[u8]) -> Result<Self> {
+        if b.is_empty() {
+            return Err(anyhow!("At least one byte is expected"));
+        }
+
+        let cid = Cid::from_u8(uplink, b[0])?;
+
+        Ok(match cid {
+            Cid::PackageVersionReq => Payload::PackageVersionReq,
+            Cid::PackageVersionAns => {
+                Payload::PackageVersionAns(PackageVersionAnsPayload::decode(&b[1..])?)
+            }
+            Cid::DevVersionReq => Payload::DevVersionReq,
+            Cid::DevVersionAns => Payload::DevVersionAns(DevVersionAnsPayload::decode(&b[1..])?),
+            Cid::DevRebootTimeReq => {
+                Payload::DevRebootTimeReq(DevRebootTimeReqPayload::decode(&b[1..])?)
+            }
+            Cid::DevRebootTimeAns => {
+                Payload::DevRebootTimeAns(DevRebootTimeAnsPayload::decode(&b[1..])?)
+            }
+            Cid::DevRebootCountdownReq => {
+                Payload::DevRebootCountdownReq(DevRebootCountdownReqPayload::decode(&b[1..])?)
+            }
+            Cid::DevRebootCountdownAns => {
+                Payload::DevRebootCountdownAns(DevRebootCountdownAnsPayload::decode(&b[1..])?)
+            }
+            Cid::DevUpgradeImageReq => Payload::DevUpgradeImageReq,
+            Cid::DevUpgradeImageAns => {
+                Payload::DevUpgradeImageAns(DevUpgradeImageAnsPayload::decode(&b[1..])?)
+            }
+            Cid::DevDeleteImageReq => {
+                Payload::DevDeleteImageReq(DevDeleteImageReqPayload::decode(&b[1..])?)
+            }
+            Cid::DevDeleteImageAns => {
+                Payload::DevDeleteImageAns(DevDeleteImageAnsPayload::decode(&b[1..])?)
+            }
+        })
+    }
+
+    pub fn to_vec(&self) -> Result<Vec<u8>> {
+        let mut out = vec![self.cid().to_u8()];
+
+        match self {
+            Self::PackageVersionReq | Self::DevVersionReq | Self::DevUpgradeImageReq => {}
+            Self::PackageVersionAns(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::DevVersionAns(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::DevRebootTimeReq(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::DevRebootTimeAns(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::DevRebootCountdownReq(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::DevRebootCountdownAns(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::DevUpgradeImageAns(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::DevDeleteImageReq(pl) => out.extend_from_slice(&pl.encode()?),
+            Self::DevDeleteImageAns(pl) => out.extend_from_slice(&pl.encode()?),
+        };
+
+        Ok(out)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PackageVersionAnsPayload {
+    pub package_identifier: u8,
+    pub package_version: u8,
+}
+
+impl PayloadCodec for PackageVersionAnsPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 2 {
+            return Err(anyhow!("Expected 2 bytes"));
+        }
+
+        Ok(PackageVersionAnsPayload {
+            package_identifier: b[0],
+            package_version: b[1],
+        })
+    }
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(vec![self.package_identifier, self.package_version])
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DevVersionAnsPayload {
+    pub fw_version: u32,
+    pub hw_version: u32,
+}
+
+impl PayloadCodec for DevVersionAnsPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 8 {
+            return Err(anyhow!("Expected 8 bytes"));
+        }
+
+        Ok(DevVersionAnsPayload {
+            fw_version: {
+                let mut bytes = [0; 4];
+                bytes.copy_from_slice(&b[0..4]);
+                u32::from_le_bytes(bytes)
+            },
+            hw_version: {
+                let mut bytes = [0; 4];
+                bytes.copy_from_slice(&b[4..8]);
+                u32::from_le_bytes(bytes)
+            },
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut b = vec![0; 8];
+        b[0..4].copy_from_slice(&self.fw_version.to_le_bytes());
+        b[4..8].copy_from_slice(&self.hw_version.to_le_bytes());
+        Ok(b)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DevRebootTimeReqPayload {
+    // Reboot time in GPS epoch seconds. 0 means reboot immediately,
+    // 0xFFFFFFFF cancels any pending reboot.
+    pub reboot_time: u32,
+}
+
+impl PayloadCodec for DevRebootTimeReqPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 4 {
+            return Err(anyhow!("Expected 4 bytes"));
+        }
+
+        Ok(DevRebootTimeReqPayload {
+            reboot_time: {
+                let mut bytes = [0; 4];
+                bytes.copy_from_slice(&b[0..4]);
+                u32::from_le_bytes(bytes)
+            },
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(self.reboot_time.to_le_bytes().to_vec())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DevRebootTimeAnsPayload {
+    pub reboot_time: u32,
+}
+
+impl PayloadCodec for DevRebootTimeAnsPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 4 {
+            return Err(anyhow!("Expected 4 bytes"));
+        }
+
+        Ok(DevRebootTimeAnsPayload {
+            reboot_time: {
+                let mut bytes = [0; 4];
+                bytes.copy_from_slice(&b[0..4]);
+                u32::from_le_bytes(bytes)
+            },
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(self.reboot_time.to_le_bytes().to_vec())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DevRebootCountdownReqPayload {
+    // Countdown in seconds (24 bits). 0 means reboot immediately,
+    // 0xFFFFFF cancels any pending reboot.
+    pub countdown: u32,
+}
+
+impl PayloadCodec for DevRebootCountdownReqPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 3 {
+            return Err(anyhow!("Expected 3 bytes"));
+        }
+
+        Ok(DevRebootCountdownReqPayload {
+            countdown: {
+                let mut bytes = [0; 4];
+                bytes[0..3].copy_from_slice(&b[0..3]);
+                u32::from_le_bytes(bytes)
+            },
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        if self.countdown > 0xffffff {
+            return Err(anyhow!("Max countdown value is 16777215"));
+        }
+
+        Ok(self.countdown.to_le_bytes()[0..3].to_vec())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DevRebootCountdownAnsPayload {
+    pub countdown: u32,
+}
+
+impl PayloadCodec for DevRebootCountdownAnsPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 3 {
+            return Err(anyhow!("Expected 3 bytes"));
+        }
+
+        Ok(DevRebootCountdownAnsPayload {
+            countdown: {
+                let mut bytes = [0; 4];
+                bytes[0..3].copy_from_slice(&b[0..3]);
+                u32::from_le_bytes(bytes)
+            },
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        if self.countdown > 0xffffff {
+            return Err(anyhow!("Max countdown value is 16777215"));
+        }
+
+        Ok(self.countdown.to_le_bytes()[0..3].to_vec())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum UpImageStatus {
+    NoImage,
+    CorruptImage,
+    IncompatibleImage,
+    ValidImage,
+}
+
+impl UpImageStatus {
+    pub fn from_u8(value: u8) -> Self {
+        match value & 0x03 {
+            0x00 => UpImageStatus::NoImage,
+            0x01 => UpImageStatus::CorruptImage,
+            0x02 => UpImageStatus::IncompatibleImage,
+            _ => UpImageStatus::ValidImage,
+        }
+    }
+
+    pub fn to_u8(&self) -> u8 {
+        match self {
+            UpImageStatus::NoImage => 0x00,
+            UpImageStatus::CorruptImage => 0x01,
+            UpImageStatus::IncompatibleImage => 0x02,
+            UpImageStatus::ValidImage => 0x03,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DevUpgradeImageAnsPayload {
+    pub status: DevUpgradeImageAnsPayloadStatus,
+    // Only present when the status is ValidImage.
+    pub next_firmware_version: Option<u32>,
+}
+
+impl PayloadCodec for DevUpgradeImageAnsPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.is_empty() {
+            return Err(anyhow!("At least 1 byte expected"));
+        }
+
+        let status = DevUpgradeImageAnsPayloadStatus {
+            up_image_status: UpImageStatus::from_u8(b[0]),
+        };
+
+        let next_firmware_version = if status.up_image_status == UpImageStatus::ValidImage {
+            if b.len() != 5 {
+                return Err(anyhow!("Expected 5 bytes"));
+            }
+
+            let mut bytes = [0; 4];
+            bytes.copy_from_slice(&b[1..5]);
+            Some(u32::from_le_bytes(bytes))
+        } else {
+            if b.len() != 1 {
+                return Err(anyhow!("Expected 1 byte"));
+            }
+
+            None
+        };
+
+        Ok(DevUpgradeImageAnsPayload {
+            status,
+            next_firmware_version,
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut b = vec![self.status.up_image_status.to_u8()];
+
+        match (self.status.up_image_status, self.next_firmware_version) {
+            (UpImageStatus::ValidImage, Some(v)) => b.extend_from_slice(&v.to_le_bytes()),
+            (UpImageStatus::ValidImage, None) => {
+                return Err(anyhow!(
+                    "next_firmware_version must be set when up_image_status is ValidImage"
+                ));
+            }
+            (_, Some(_)) => {
+                return Err(anyhow!(
+                    "next_firmware_version must only be set when up_image_status is ValidImage"
+                ));
+            }
+            (_, None) => {}
+        }
+
+        Ok(b)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DevUpgradeImageAnsPayloadStatus {
+    pub up_image_status: UpImageStatus,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DevDeleteImageReqPayload {
+    pub firmware_to_delete_version: u32,
+}
+
+impl PayloadCodec for DevDeleteImageReqPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 4 {
+            return Err(anyhow!("Expected 4 bytes"));
+        }
+
+        Ok(DevDeleteImageReqPayload {
+            firmware_to_delete_version: {
+                let mut bytes = [0; 4];
+                bytes.copy_from_slice(&b[0..4]);
+                u32::from_le_bytes(bytes)
+            },
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(self.firmware_to_delete_version.to_le_bytes().to_vec())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DevDeleteImageAnsPayload {
+    pub status: DevDeleteImageAnsPayloadStatus,
+}
+
+impl PayloadCodec for DevDeleteImageAnsPayload {
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != 1 {
+            return Err(anyhow!("Expected 1 byte"));
+        }
+
+        Ok(DevDeleteImageAnsPayload {
+            status: DevDeleteImageAnsPayloadStatus {
+                error_no_valid_image: b[0] & 0x01 != 0,
+                error_invalid_version: b[0] & 0x02 != 0,
+            },
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut b = vec![0; 1];
+        if self.status.error_no_valid_image {
+            b[0] |= 0x01;
+        }
+        if self.status.error_invalid_version {
+            b[0] |= 0x02;
+        }
+
+        Ok(b)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DevDeleteImageAnsPayloadStatus {
+    pub error_no_valid_image: bool,
+    pub error_invalid_version: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct CommandTest {
+        name: String,
+        uplink: bool,
+        command: Payload,
+        bytes: Vec<u8>,
+        expected_error: Option<String>,
+    }
+
+    #[test]
+    fn test_package_version_req() {
+        let encode_tests = [CommandTest {
+            name: "encode PackageVersionReq".into(),
+            uplink: false,
+            command: Payload::PackageVersionReq,
+            bytes: vec![0x00],
+            expected_error: None,
+        }];
+
+        let decode_tests = [CommandTest {
+            name: "decode PackageVersionReq".into(),
+            uplink: false,
+            command: Payload::PackageVersionReq,
+            bytes: vec![0x00],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&decode_tests);
+    }
+
+    #[test]
+    fn test_package_version_ans() {
+        let encode_tests = [CommandTest {
+            name: "encode PackageVersionAns".into(),
+            uplink: true,
+            command: Payload::PackageVersionAns(PackageVersionAnsPayload {
+                package_identifier: 4,
+                package_version: 1,
+            }),
+            bytes: vec![0x00, 0x04, 0x01],
+            expected_error: None,
+        }];
+
+        let decode_tests = [CommandTest {
+            name: "decode PackageVersionAns".into(),
+            uplink: true,
+            command: Payload::PackageVersionAns(PackageVersionAnsPayload {
+                package_identifier: 4,
+                package_version: 1,
+            }),
+            bytes: vec![0x00, 0x04, 0x01],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&decode_tests);
+    }
+
+    #[test]
+    fn test_dev_version_req() {
+        let encode_tests = [CommandTest {
+            name: "encode DevVersionReq".into(),
+            uplink: false,
+            command: Payload::DevVersionReq,
+            bytes: vec![0x01],
+            expected_error: None,
+        }];
+
+        let decode_tests = [CommandTest {
+            name: "decode DevVersionReq".into(),
+            uplink: false,
+            command: Payload::DevVersionReq,
+            bytes: vec![0x01],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&decode_tests);
+    }
+
+    #[test]
+    fn test_dev_version_ans() {
+        let encode_tests = [CommandTest {
+            name: "encode DevVersionAns".into(),
+            uplink: true,
+            command: Payload::DevVersionAns(DevVersionAnsPayload {
+                fw_version: 0x01020304,
+                hw_version: 0x05060708,
+            }),
+            bytes: vec![0x01, 0x04, 0x03, 0x02, 0x01, 0x08, 0x07, 0x06, 0x05],
+            expected_error: None,
+        }];
+
+        let decode_tests = [
+            CommandTest {
+                name: "decode DevVersionAns".into(),
+                uplink: true,
+                command: Payload::DevVersionAns(DevVersionAnsPayload {
+                    fw_version: 0x01020304,
+                    hw_version: 0x05060708,
+                }),
+                bytes: vec![0x01, 0x04, 0x03, 0x02, 0x01, 0x08, 0x07, 0x06, 0x05],
+                expected_error: None,
+            },
+            CommandTest {
+                name: "decode DevVersionAns invalid length".into(),
+                uplink: true,
+                command: Payload::DevVersionReq,
+                bytes: vec![0x01, 0x04, 0x03, 0x02, 0x01],
+                expected_error: Some("Expected 8 bytes".into()),
+            },
+        ];
+
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&decode_tests);
+    }
+
+    #[test]
+    fn test_dev_reboot_time_req() {
+        let encode_tests = [CommandTest {
+            name: "encode DevRebootTimeReq".into(),
+            uplink: false,
+            command: Payload::DevRebootTimeReq(DevRebootTimeReqPayload { reboot_time: 1024 }),
+            bytes: vec![0x02, 0x00, 0x04, 0x00, 0x00],
+            expected_error: None,
+        }];
+
+        let decode_tests = [CommandTest {
+            name: "decode DevRebootTimeReq".into(),
+            uplink: false,
+            command: Payload::DevRebootTimeReq(DevRebootTimeReqPayload { reboot_time: 1024 }),
+            bytes: vec![0x02, 0x00, 0x04, 0x00, 0x00],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&decode_tests);
+    }
+
+    #[test]
+    fn test_dev_reboot_time_ans() {
+        let encode_tests = [CommandTest {
+            name: "encode DevRebootTimeAns".into(),
+            uplink: true,
+            command: Payload::DevRebootTimeAns(DevRebootTimeAnsPayload {
+                reboot_time: 0xffffffff,
+            }),
+            bytes: vec![0x02, 0xff, 0xff, 0xff, 0xff],
+            expected_error: None,
+        }];
+
+        let decode_tests = [CommandTest {
+            name: "decode DevRebootTimeAns".into(),
+            uplink: true,
+            command: Payload::DevRebootTimeAns(DevRebootTimeAnsPayload {
+                reboot_time: 0xffffffff,
+            }),
+            bytes: vec![0x02, 0xff, 0xff, 0xff, 0xff],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&decode_tests);
+    }
+
+    #[test]
+    fn test_dev_reboot_countdown_req() {
+        let encode_tests = [
+            CommandTest {
+                name: "encode DevRebootCountdownReq".into(),
+                uplink: false,
+                command: Payload::DevRebootCountdownReq(DevRebootCountdownReqPayload {
+                    countdown: 0x010203,
+                }),
+                bytes: vec![0x03, 0x03, 0x02, 0x01],
+                expected_error: None,
+            },
+            CommandTest {
+                name: "encode DevRebootCountdownReq countdown too large".into(),
+                uplink: false,
+                command: Payload::DevRebootCountdownReq(DevRebootCountdownReqPayload {
+                    countdown: 0x01000000,
+                }),
+                bytes: vec![],
+                expected_error: Some("Max countdown value is 16777215".into()),
+            },
+        ];
+
+        let decode_tests = [CommandTest {
+            name: "decode DevRebootCountdownReq".into(),
+            uplink: false,
+            command: Payload::DevRebootCountdownReq(DevRebootCountdownReqPayload {
+                countdown: 0x010203,
+            }),
+            bytes: vec![0x03, 0x03, 0x02, 0x01],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&decode_tests);
+    }
+
+    #[test]
+    fn test_dev_reboot_countdown_ans() {
+        let encode_tests = [CommandTest {
+            name: "encode DevRebootCountdownAns".into(),
+            uplink: true,
+            command: Payload::DevRebootCountdownAns(DevRebootCountdownAnsPayload {
+                countdown: 0xffffff,
+            }),
+            bytes: vec![0x03, 0xff, 0xff, 0xff],
+            expected_error: None,
+        }];
+
+        let decode_tests = [CommandTest {
+            name: "decode DevRebootCountdownAns".into(),
+            uplink: true,
+            command: Payload::DevRebootCountdownAns(DevRebootCountdownAnsPayload {
+                countdown: 0xffffff,
+            }),
+            bytes: vec![0x03, 0xff, 0xff, 0xff],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&decode_tests);
+    }
+
+    #[test]
+    fn test_dev_upgrade_image_req() {
+        let encode_tests = [CommandTest {
+            name: "encode DevUpgradeImageReq".into(),
+            uplink: false,
+            command: Payload::DevUpgradeImageReq,
+            bytes: vec![0x04],
+            expected_error: None,
+        }];
+
+        let decode_tests = [CommandTest {
+            name: "decode DevUpgradeImageReq".into(),
+            uplink: false,
+            command: Payload::DevUpgradeImageReq,
+            bytes: vec![0x04],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&decode_tests);
+    }
+
+    #[test]
+    fn test_dev_upgrade_image_ans() {
+        let encode_tests = [
+            CommandTest {
+                name: "encode DevUpgradeImageAns valid image".into(),
+                uplink: true,
+                command: Payload::DevUpgradeImageAns(DevUpgradeImageAnsPayload {
+                    status: DevUpgradeImageAnsPayloadStatus {
+                        up_image_status: UpImageStatus::ValidImage,
+                    },
+                    next_firmware_version: Some(0x01020304),
+                }),
+                bytes: vec![0x04, 0x03, 0x04, 0x03, 0x02, 0x01],
+                expected_error: None,
+            },
+            CommandTest {
+                name: "encode DevUpgradeImageAns corrupt image".into(),
+                uplink: true,
+                command: Payload::DevUpgradeImageAns(DevUpgradeImageAnsPayload {
+                    status: DevUpgradeImageAnsPayloadStatus {
+                        up_image_status: UpImageStatus::CorruptImage,
+                    },
+                    next_firmware_version: None,
+                }),
+                bytes: vec![0x04, 0x01],
+                expected_error: None,
+            },
+            CommandTest {
+                name: "encode DevUpgradeImageAns valid image without version".into(),
+                uplink: true,
+                command: Payload::DevUpgradeImageAns(DevUpgradeImageAnsPayload {
+                    status: DevUpgradeImageAnsPayloadStatus {
+                        up_image_status: UpImageStatus::ValidImage,
+                    },
+                    next_firmware_version: None,
+                }),
+                bytes: vec![],
+                expected_error: Some(
+                    "next_firmware_version must be set when up_image_status is ValidImage".into(),
+                ),
+            },
+        ];
+
+        let decode_tests = [
+            CommandTest {
+                name: "decode DevUpgradeImageAns valid image".into(),
+                uplink: true,
+                command: Payload::DevUpgradeImageAns(DevUpgradeImageAnsPayload {
+                    status: DevUpgradeImageAnsPayloadStatus {
+                        up_image_status: UpImageStatus::ValidImage,
+                    },
+                    next_firmware_version: Some(0x01020304),
+                }),
+                bytes: vec![0x04, 0x03, 0x04, 0x03, 0x02, 0x01],
+                expected_error: None,
+            },
+            CommandTest {
+                name: "decode DevUpgradeImageAns no image".into(),
+                uplink: true,
+                command: Payload::DevUpgradeImageAns(DevUpgradeImageAnsPayload {
+                    status: DevUpgradeImageAnsPayloadStatus {
+                        up_image_status: UpImageStatus::NoImage,
+                    },
+                    next_firmware_version: None,
+                }),
+                bytes: vec![0x04, 0x00],
+                expected_error: None,
+            },
+            CommandTest {
+                name: "decode DevUpgradeImageAns valid image without version".into(),
+                uplink: true,
+                command: Payload::DevUpgradeImageReq,
+                bytes: vec![0x04, 0x03],
+                expected_error: Some("Expected 5 bytes".into()),
+            },
+        ];
+
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&decode_tests);
+    }
+
+    #[test]
+    fn test_dev_delete_image_req() {
+        let encode_tests = [CommandTest {
+            name: "encode DevDeleteImageReq".into(),
+            uplink: false,
+            command: Payload::DevDeleteImageReq(DevDeleteImageReqPayload {
+                firmware_to_delete_version: 0x01020304,
+            }),
+            bytes: vec![0x05, 0x04, 0x03, 0x02, 0x01],
+            expected_error: None,
+        }];
+
+        let decode_tests = [CommandTest {
+            name: "decode DevDeleteImageReq".into(),
+            uplink: false,
+            command: Payload::DevDeleteImageReq(DevDeleteImageReqPayload {
+                firmware_to_delete_version: 0x01020304,
+            }),
+            bytes: vec![0x05, 0x04, 0x03, 0x02, 0x01],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&decode_tests);
+    }
+
+    #[test]
+    fn test_dev_delete_image_ans() {
+        let encode_tests = [CommandTest {
+            name: "encode DevDeleteImageAns".into(),
+            uplink: true,
+            command: Payload::DevDeleteImageAns(DevDeleteImageAnsPayload {
+                status: DevDeleteImageAnsPayloadStatus {
+                    error_no_valid_image: true,
+                    error_invalid_version: true,
+                },
+            }),
+            bytes: vec![0x05, 0x03],
+            expected_error: None,
+        }];
+
+        let decode_tests = [CommandTest {
+            name: "decode DevDeleteImageAns".into(),
+            uplink: true,
+            command: Payload::DevDeleteImageAns(DevDeleteImageAnsPayload {
+                status: DevDeleteImageAnsPayloadStatus {
+                    error_no_valid_image: false,
+                    error_invalid_version: true,
+                },
+            }),
+            bytes: vec![0x05, 0x02],
+            expected_error: None,
+        }];
+
+        run_tests_encode(&encode_tests);
+        run_tests_decode(&decode_tests);
+    }
+
+    fn run_tests_encode(tests: &[CommandTest]) {
+        for tst in tests {
+            println!("> {}", tst.name);
+            let resp = tst.command.to_vec();
+            if let Some(e) = &tst.expected_error {
+                assert!(resp.is_err());
+                assert_eq!(e, &resp.err().unwrap().to_string());
+            } else {
+                assert_eq!(tst.bytes, resp.unwrap());
+            }
+        }
+    }
+
+    fn run_tests_decode(tests: &[CommandTest]) {
+        for tst in tests {
+            println!("> {}", tst.name);
+            let resp = Payload::from_slice(tst.uplink, &tst.bytes);
+            if let Some(e) = &tst.expected_error {
+                assert!(resp.is_err());
+                assert_eq!(e, &resp.err().unwrap().to_string());
+            } else {
+                assert_eq!(tst.command, resp.unwrap());
+            }
+        }
+    }
+}
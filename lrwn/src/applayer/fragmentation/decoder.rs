@@ -0,0 +1,224 @@
+use alloc::collections::BTreeSet;
+
+use anyhow::Result;
+
+use crate::prelude::*;
+
+// Decoder for the fragments as produced by the v1 and v2 encode functions. As the matrix
+// lines differ between v1 and v2, the matrix_line function must be provided.
+//
+// Each received fragment represents a linear (XOR) equation over the uncoded fragments.
+// These equations are reduced on reception (Gaussian elimination over GF(2)), such that
+// the uncoded fragments can be reconstructed once nb_frag linearly independent fragments
+// have been received, independent of the order in which the fragments were received.
+pub(super) struct Decoder {
+    nb_frag: usize,
+    frag_size: usize,
+    padding: usize,
+    matrix_line: fn(usize, usize) -> Vec<usize>,
+    received: BTreeSet<u16>,
+    // Row per uncoded fragment, containing the reduced coefficients and data. The row
+    // at index i has its first coefficient at position i.
+    rows: Vec<Option<(Vec<bool>, Vec<u8>)>>,
+    rank: usize,
+}
+
+impl Decoder {
+    pub fn new(
+        nb_frag: usize,
+        frag_size: usize,
+        padding: usize,
+        matrix_line: fn(usize, usize) -> Vec<usize>,
+    ) -> Result<Self> {
+        if nb_frag == 0 {
+            return Err(anyhow!("nb_frag must be > 0"));
+        }
+
+        if frag_size == 0 {
+            return Err(anyhow!("frag_size must be > 0"));
+        }
+
+        if padding >= frag_size {
+            return Err(anyhow!("padding must be < frag_size"));
+        }
+
+        Ok(Decoder {
+            nb_frag,
+            frag_size,
+            padding,
+            matrix_line,
+            received: BTreeSet::new(),
+            rows: vec![None; nb_frag],
+            rank: 0,
+        })
+    }
+
+    // Add the fragment with the given (1 based) index. It returns true when all the
+    // uncoded fragments can be reconstructed.
+    pub fn add(&mut self, n: u16, data: &[u8]) -> Result<bool> {
+        if n == 0 {
+            return Err(anyhow!("Fragment index must be > 0"));
+        }
+
+        if data.len() != self.frag_size {
+            return Err(anyhow!(
+                "Expected fragment size {}, got {}",
+                self.frag_size,
+                data.len()
+            ));
+        }
+
+        if self.is_complete() || !self.received.insert(n) {
+            return Ok(self.is_complete());
+        }
+
+        let n = n as usize;
+        let mut coeffs = vec![false; self.nb_frag];
+        if n <= self.nb_frag {
+            coeffs[n - 1] = true;
+        } else {
+            for (i, v) in (self.matrix_line)(n - self.nb_frag, self.nb_frag)
+                .iter()
+                .enumerate()
+            {
+                coeffs[i] = *v == 1;
+            }
+        }
+        let mut data = data.to_vec();
+
+        for i in 0..self.nb_frag {
+            if !coeffs[i] {
+                continue;
+            }
+
+            match &self.rows[i] {
+                Some((row_coeffs, row_data)) => {
+                    for (c, r) in coeffs.iter_mut().zip(row_coeffs.iter()).skip(i) {
+                        *c ^= *r;
+                    }
+                    for (d, r) in data.iter_mut().zip(row_data.iter()) {
+                        *d ^= *r;
+                    }
+                }
+                None => {
+                    self.rows[i] = Some((coeffs, data));
+                    self.rank += 1;
+                    return Ok(self.is_complete());
+                }
+            }
+        }
+
+        // The fragment did not add any new information.
+        Ok(self.is_complete())
+    }
+
+    pub fn received_count(&self) -> usize {
+        self.received.len()
+    }
+
+    pub fn missing_count(&self) -> usize {
+        self.nb_frag - self.rank
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.rank == self.nb_frag
+    }
+
+    // Returns the reconstructed payload, without padding.
+    pub fn get_payload(&self) -> Result<Vec<u8>> {
+        if !self.is_complete() {
+            return Err(anyhow!(
+                "Payload is not complete, missing {} fragments",
+                self.missing_count()
+            ));
+        }
+
+        // Back-substitution, starting with the last row which only has its own
+        // coefficient set.
+        let mut fragments: Vec<Vec<u8>> = vec![Vec::new(); self.nb_frag];
+        for i in (0..self.nb_frag).rev() {
+            let (row_coeffs, row_data) = self.rows[i].as_ref().unwrap();
+            let mut data = row_data.clone();
+
+            for (c, fragment) in row_coeffs.iter().zip(fragments.iter()).skip(i + 1) {
+                if *c {
+                    for (d, f) in data.iter_mut().zip(fragment.iter()) {
+                        *d ^= *f;
+                    }
+                }
+            }
+
+            fragments[i] = data;
+        }
+
+        let mut payload = fragments.concat();
+        payload.truncate(payload.len() - self.padding);
+        Ok(payload)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::applayer::fragmentation::{v1, v2};
+
+    type EncodeFn = fn(&[u8], usize, usize) -> Result<Vec<Vec<u8>>>;
+    type MatrixLineFn = fn(usize, usize) -> Vec<usize>;
+
+    #[test]
+    fn test_decoder() {
+        let tests: [(&str, EncodeFn, MatrixLineFn); 2] = [
+            ("v1", v1::encode, v1::matrix_line),
+            ("v2", v2::encode, v2::matrix_line),
+        ];
+
+        let mut data = vec![0; 95];
+        for (i, v) in data.iter_mut().enumerate() {
+            *v = i as u8;
+        }
+
+        let mut padded = data.clone();
+        padded.resize(100, 0);
+
+        for (name, encode, matrix_line) in tests {
+            println!("> {}", name);
+
+            let fragments = encode(&padded, 10, 5).unwrap();
+            let mut dec = Decoder::new(10, 10, 5, matrix_line).unwrap();
+
+            // Invalid fragment size.
+            assert_eq!(
+                "Expected fragment size 10, got 9",
+                dec.add(1, &fragments[0][..9]).err().unwrap().to_string()
+            );
+
+            // Fragments 2, 5 and 9 are lost and the remaining fragments are received
+            // in reverse order.
+            let mut complete = false;
+            for (i, f) in fragments.iter().enumerate().rev() {
+                let n = (i + 1) as u16;
+                if [2, 5, 9].contains(&n) {
+                    continue;
+                }
+
+                if n == 6 {
+                    assert!(!complete);
+                    assert_eq!(8, dec.received_count());
+                    assert_eq!(2, dec.missing_count());
+                    assert_eq!(
+                        "Payload is not complete, missing 2 fragments",
+                        dec.get_payload().err().unwrap().to_string()
+                    );
+                }
+
+                complete = dec.add(n, f).unwrap();
+            }
+
+            assert!(complete);
+            assert!(dec.is_complete());
+            assert_eq!(12, dec.received_count());
+            assert_eq!(0, dec.missing_count());
+            assert_eq!(data, dec.get_payload().unwrap());
+        }
+    }
+}
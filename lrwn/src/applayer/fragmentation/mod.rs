@@ -1,2 +1,3 @@
+mod decoder;
 pub mod v1;
 pub mod v2;
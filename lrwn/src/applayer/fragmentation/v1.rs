@@ -1,6 +1,7 @@
 use anyhow::Result;
 
 use crate::applayer::PayloadCodec;
+use crate::prelude::*;
 
 pub enum Cid {
     PackageVersionReq,
@@ -527,7 +528,7 @@ fn is_power_2(num: usize) -> bool {
     num != 0 && (num & (num - 1)) == 0
 }
 
-fn matrix_line(n: usize, m: usize) -> Vec<usize> {
+pub(super) fn matrix_line(n: usize, m: usize) -> Vec<usize> {
     let mut line = vec![0; m];
 
     let mm = if is_power_2(m) { 1 } else { 0 };
@@ -546,6 +547,60 @@ fn matrix_line(n: usize, m: usize) -> Vec<usize> {
     line
 }
 
+// Decoder for the fragments of a single fragmentation session. Fragments can be added in
+// any order. Missing uncoded fragments are reconstructed from the received parity
+// fragments.
+pub struct Decoder {
+    frag_index: u8,
+    inner: super::decoder::Decoder,
+}
+
+impl Decoder {
+    pub fn new(setup: &FragSessionSetupReqPayload) -> Result<Self> {
+        Ok(Decoder {
+            frag_index: setup.frag_session.frag_index,
+            inner: super::decoder::Decoder::new(
+                setup.nb_frag as usize,
+                setup.frag_size as usize,
+                setup.padding as usize,
+                matrix_line,
+            )?,
+        })
+    }
+
+    // Add the given fragment. It returns true when the payload is complete.
+    pub fn add_fragment(&mut self, pl: &DataFragmentPayload) -> Result<bool> {
+        if pl.index_and_n.frag_index != self.frag_index {
+            return Err(anyhow!(
+                "Expected frag_index {}, got {}",
+                self.frag_index,
+                pl.index_and_n.frag_index
+            ));
+        }
+
+        self.inner.add(pl.index_and_n.n, &pl.data)
+    }
+
+    // Returns the number of received (unique) fragments.
+    pub fn received_count(&self) -> usize {
+        self.inner.received_count()
+    }
+
+    // Returns the minimum number of fragments still needed to complete the payload.
+    pub fn missing_count(&self) -> usize {
+        self.inner.missing_count()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.inner.is_complete()
+    }
+
+    // Returns the reconstructed payload, with the padding removed.
+    pub fn get_payload(&self) -> Result<Vec<u8>> {
+        self.inner.get_payload()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -910,6 +965,62 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_decoder() {
+        let mut data = vec![0; 95];
+        for (i, v) in data.iter_mut().enumerate() {
+            *v = i as u8;
+        }
+
+        let mut padded = data.clone();
+        padded.resize(100, 0);
+        let fragments = encode(&padded, 10, 5).unwrap();
+
+        let setup = FragSessionSetupReqPayload {
+            frag_session: FragSessionSetuReqPayloadFragSession {
+                mc_group_bit_mask: [true, false, false, false],
+                frag_index: 1,
+            },
+            nb_frag: 10,
+            frag_size: 10,
+            control: FragSessionSetuReqPayloadControl {
+                block_ack_delay: 0,
+                fragmentation_matrix: 0,
+            },
+            padding: 5,
+            descriptor: [0, 0, 0, 0],
+        };
+        let mut dec = Decoder::new(&setup).unwrap();
+
+        // Invalid frag_index.
+        assert_eq!(
+            "Expected frag_index 1, got 2",
+            dec.add_fragment(&DataFragmentPayload {
+                index_and_n: DataFragmentPayloadIndexAndN {
+                    n: 1,
+                    frag_index: 2,
+                },
+                data: fragments[0].clone(),
+            })
+            .err()
+            .unwrap()
+            .to_string()
+        );
+
+        // The reconstruction itself is tested in the decoder module.
+        for (i, f) in fragments.iter().enumerate() {
+            dec.add_fragment(&DataFragmentPayload {
+                index_and_n: DataFragmentPayloadIndexAndN {
+                    n: (i + 1) as u16,
+                    frag_index: 1,
+                },
+                data: f.clone(),
+            })
+            .unwrap();
+        }
+        assert_eq!(data, dec.get_payload().unwrap());
+    }
+
     fn run_tests_encode(tests: &[CommandTest]) {
         for tst in tests {
             println!("> {}", tst.name);
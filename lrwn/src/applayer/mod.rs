@@ -1,8 +1,14 @@
 use anyhow::Result;
 
+use crate::prelude::*;
+
+pub mod certification;
 pub mod clocksync;
+pub mod datablock;
+pub mod firmwaremanagement;
 pub mod fragmentation;
 pub mod multicastsetup;
+pub mod multipackage;
 
 pub trait PayloadCodec<Struct = Self> {
     fn decode(b: &[u8]) -> Result<Struct>;
@@ -7,6 +7,7 @@ use anyhow::Result;
 
 use crate::applayer::PayloadCodec;
 use crate::helpers::{decode_freq, encode_freq};
+use crate::prelude::*;
 use crate::{AES128Key, DevAddr};
 
 pub enum Cid {
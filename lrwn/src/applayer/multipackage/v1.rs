@@ -0,0 +1,210 @@
+use anyhow::Result;
+
+use crate::prelude::*;
+
+// Package identifiers of the application-layer packages that can be
+// multiplexed using the Multi-Package Access protocol.
+pub const PACKAGE_IDENTIFIER_CLOCK_SYNC: u8 = 1;
+pub const PACKAGE_IDENTIFIER_MULTICAST_SETUP: u8 = 2;
+pub const PACKAGE_IDENTIFIER_FRAGMENTATION: u8 = 3;
+pub const PACKAGE_IDENTIFIER_FIRMWARE_MANAGEMENT: u8 = 4;
+
+// Payload contains one or multiple package messages, sent on the
+// Multi-Package Access fPort. Each message is encoded as:
+// PackageIdentifier (1 byte) | Length (1 byte) | Package payload (Length bytes).
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Payload {
+    pub messages: Vec<PackageMessage>,
+}
+
+impl Payload {
+    pub fn from_slice(b: &[u8]) -> Result<Self> {
+        if b.is_empty() {
+            return Err(anyhow!("At least one package message is expected"));
+        }
+
+        let mut messages = Vec::new();
+        let mut b = b;
+
+        while !b.is_empty() {
+            if b.len() < 2 {
+                return Err(anyhow!("At least 2 bytes expected"));
+            }
+
+            let package_identifier = b[0];
+            let len = b[1] as usize;
+
+            if b.len() < 2 + len {
+                return Err(anyhow!(
+                    "Package message with identifier {} expects {} bytes, got {}",
+                    package_identifier,
+                    len,
+                    b.len() - 2
+                ));
+            }
+
+            messages.push(PackageMessage {
+                package_identifier,
+                data: b[2..2 + len].to_vec(),
+            });
+
+            b = &b[2 + len..];
+        }
+
+        Ok(Payload { messages })
+    }
+
+    pub fn to_vec(&self) -> Result<Vec<u8>> {
+        if self.messages.is_empty() {
+            return Err(anyhow!("At least one package message is expected"));
+        }
+
+        let mut out = Vec::new();
+
+        for msg in &self.messages {
+            if msg.data.len() > 255 {
+                return Err(anyhow!("Max package message length is 255 bytes"));
+            }
+
+            out.push(msg.package_identifier);
+            out.push(msg.data.len() as u8);
+            out.extend_from_slice(&msg.data);
+        }
+
+        Ok(out)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PackageMessage {
+    pub package_identifier: u8,
+    pub data: Vec<u8>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct PayloadTest {
+        name: String,
+        payload: Payload,
+        bytes: Vec<u8>,
+        expected_error: Option<String>,
+    }
+
+    #[test]
+    fn test_encode() {
+        let tests = [
+            PayloadTest {
+                name: "single message".into(),
+                payload: Payload {
+                    messages: vec![PackageMessage {
+                        package_identifier: PACKAGE_IDENTIFIER_CLOCK_SYNC,
+                        data: vec![0x01, 0x00, 0x04, 0x00, 0x00, 0x1f],
+                    }],
+                },
+                bytes: vec![0x01, 0x06, 0x01, 0x00, 0x04, 0x00, 0x00, 0x1f],
+                expected_error: None,
+            },
+            PayloadTest {
+                name: "multiple messages".into(),
+                payload: Payload {
+                    messages: vec![
+                        PackageMessage {
+                            package_identifier: PACKAGE_IDENTIFIER_FRAGMENTATION,
+                            data: vec![0x00],
+                        },
+                        PackageMessage {
+                            package_identifier: PACKAGE_IDENTIFIER_FIRMWARE_MANAGEMENT,
+                            data: vec![0x01],
+                        },
+                    ],
+                },
+                bytes: vec![0x03, 0x01, 0x00, 0x04, 0x01, 0x01],
+                expected_error: None,
+            },
+            PayloadTest {
+                name: "no messages".into(),
+                payload: Payload::default(),
+                bytes: vec![],
+                expected_error: Some("At least one package message is expected".into()),
+            },
+            PayloadTest {
+                name: "message too long".into(),
+                payload: Payload {
+                    messages: vec![PackageMessage {
+                        package_identifier: PACKAGE_IDENTIFIER_CLOCK_SYNC,
+                        data: vec![0; 256],
+                    }],
+                },
+                bytes: vec![],
+                expected_error: Some("Max package message length is 255 bytes".into()),
+            },
+        ];
+
+        for tst in &tests {
+            println!("> {}", tst.name);
+            let resp = tst.payload.to_vec();
+            if let Some(e) = &tst.expected_error {
+                assert!(resp.is_err());
+                assert_eq!(e, &resp.err().unwrap().to_string());
+            } else {
+                assert_eq!(tst.bytes, resp.unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let tests = [
+            PayloadTest {
+                name: "multiple messages".into(),
+                payload: Payload {
+                    messages: vec![
+                        PackageMessage {
+                            package_identifier: PACKAGE_IDENTIFIER_MULTICAST_SETUP,
+                            data: vec![0x02, 0x01],
+                        },
+                        PackageMessage {
+                            package_identifier: PACKAGE_IDENTIFIER_CLOCK_SYNC,
+                            data: vec![],
+                        },
+                    ],
+                },
+                bytes: vec![0x02, 0x02, 0x02, 0x01, 0x01, 0x00],
+                expected_error: None,
+            },
+            PayloadTest {
+                name: "empty".into(),
+                payload: Payload::default(),
+                bytes: vec![],
+                expected_error: Some("At least one package message is expected".into()),
+            },
+            PayloadTest {
+                name: "missing length".into(),
+                payload: Payload::default(),
+                bytes: vec![0x01, 0x01, 0x00, 0x03],
+                expected_error: Some("At least 2 bytes expected".into()),
+            },
+            PayloadTest {
+                name: "truncated message".into(),
+                payload: Payload::default(),
+                bytes: vec![0x03, 0x04, 0x01, 0x02],
+                expected_error: Some(
+                    "Package message with identifier 3 expects 4 bytes, got 2".into(),
+                ),
+            },
+        ];
+
+        for tst in &tests {
+            println!("> {}", tst.name);
+            let resp = Payload::from_slice(&tst.bytes);
+            if let Some(e) = &tst.expected_error {
+                assert!(resp.is_err());
+                assert_eq!(e, &resp.err().unwrap().to_string());
+            } else {
+                assert_eq!(tst.payload, resp.unwrap());
+            }
+        }
+    }
+}
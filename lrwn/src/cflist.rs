@@ -2,6 +2,8 @@ use anyhow::Result;
 #[cfg(feature = "serde")]
 use serde::Serialize;
 
+use crate::prelude::*;
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
 pub enum CFList {
@@ -96,7 +98,7 @@ impl CFListChannels {
         Ok(b)
     }
 
-    pub fn iter(&self) -> std::slice::Iter<'_, u32> {
+    pub fn iter(&self) -> core::slice::Iter<'_, u32> {
         self.0.iter()
     }
 }
@@ -142,7 +144,7 @@ impl CFListChannelMasks {
         Ok(b)
     }
 
-    pub fn iter(&self) -> std::slice::Iter<'_, ChMask> {
+    pub fn iter(&self) -> core::slice::Iter<'_, ChMask> {
         self.0.iter()
     }
 }
@@ -203,7 +205,7 @@ impl ChMask {
 
 impl IntoIterator for ChMask {
     type Item = bool;
-    type IntoIter = std::vec::IntoIter<Self::Item>;
+    type IntoIter = alloc::vec::IntoIter<Self::Item>;
 
     #[allow(clippy::unnecessary_to_owned)]
     fn into_iter(self) -> Self::IntoIter {
@@ -1,5 +1,5 @@
-use std::fmt;
-use std::str::FromStr;
+use core::fmt;
+use core::str::FromStr;
 
 use anyhow::Result;
 #[cfg(feature = "postgres")]
@@ -16,6 +16,7 @@ use serde::{
 
 use super::netid::NetID;
 use crate::Error;
+use crate::prelude::*;
 
 #[derive(PartialEq, Eq, Copy, Clone, Default)]
 pub struct DevAddrPrefix([u8; 4], u32);
@@ -29,6 +30,21 @@ impl DevAddrPrefix {
         self.range_min() >= other.range_min() && self.range_max() <= other.range_max()
     }
 
+    /// Returns the first DevAddr of the prefix range.
+    pub fn first_dev_addr(&self) -> DevAddr {
+        DevAddr::from_be_bytes(self.range_min().to_be_bytes())
+    }
+
+    /// Returns the last DevAddr of the prefix range.
+    pub fn last_dev_addr(&self) -> DevAddr {
+        DevAddr::from_be_bytes(self.range_max().to_be_bytes())
+    }
+
+    /// Returns the number of DevAddrs within the prefix range.
+    pub fn dev_addr_count(&self) -> u64 {
+        (self.range_max() - self.range_min()) as u64 + 1
+    }
+
     fn prefix(&self) -> [u8; 4] {
         self.0
     }
@@ -442,6 +458,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_prefix_range() {
+        let p = DevAddrPrefix::from_str("0000ff00/24").unwrap();
+        assert_eq!(DevAddr::from_be_bytes([0, 0, 0xff, 0]), p.first_dev_addr());
+        assert_eq!(
+            DevAddr::from_be_bytes([0, 0, 0xff, 0xff]),
+            p.last_dev_addr()
+        );
+        assert_eq!(256, p.dev_addr_count());
+
+        let p = DevAddrPrefix::from_str("fe000000/7").unwrap();
+        assert_eq!(DevAddr::from_be_bytes([0xfe, 0, 0, 0]), p.first_dev_addr());
+        assert_eq!(
+            DevAddr::from_be_bytes([0xff, 0xff, 0xff, 0xff]),
+            p.last_dev_addr()
+        );
+        assert_eq!(1 << 25, p.dev_addr_count());
+    }
+
     #[test]
     fn test_prefix_is_subset() {
         assert!(
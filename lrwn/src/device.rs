@@ -0,0 +1,1068 @@
+use core::time::Duration;
+
+use anyhow::Result;
+
+use crate::keys;
+use crate::prelude::*;
+use crate::region::{self, CommonName, Region};
+use crate::*;
+
+/// Activation of the simulated device.
+#[derive(Clone)]
+pub enum Activation {
+    /// Over-the-air activation. For LoRaWAN 1.0.x the AppKey is used as NwkKey.
+    Otaa { join_eui: EUI64, app_key: AES128Key },
+    /// Activation by personalization. For LoRaWAN 1.0.x the NwkSKey is used as FNwkSIntKey,
+    /// SNwkSIntKey and NwkSEncKey.
+    Abp {
+        dev_addr: DevAddr,
+        nwk_s_key: AES128Key,
+        app_s_key: AES128Key,
+    },
+}
+
+/// Configuration of the simulated device.
+#[derive(Clone)]
+pub struct Config {
+    pub dev_eui: EUI64,
+    pub activation: Activation,
+    pub region: CommonName,
+    /// Uplink data-rate, until changed by the network using a LinkADRReq.
+    pub dr: u8,
+    /// Battery level as reported in the DevStatusAns.
+    pub battery: u8,
+    /// Demodulation margin as reported in the DevStatusAns.
+    pub margin: i8,
+}
+
+/// Uplink transmission as produced by the simulated device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Uplink {
+    pub phy_payload: PhyPayload,
+    pub frequency: u32,
+    pub dr: u8,
+    pub channel: usize,
+}
+
+/// Class-A receive window, opened after an uplink transmission.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RxWindow {
+    /// Delay after the end of the uplink transmission.
+    pub delay: Duration,
+    pub frequency: u32,
+    pub dr: u8,
+}
+
+/// Result of handling a downlink.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Downlink {
+    JoinAccept {
+        dev_addr: DevAddr,
+    },
+    Data {
+        f_cnt: u32,
+        f_port: Option<u8>,
+        data: Vec<u8>,
+        confirmed: bool,
+        ack: bool,
+        f_pending: bool,
+        /// Mac-commands received from the network, either as FOpts or FRMPayload.
+        mac_commands: Vec<MACCommand>,
+    },
+}
+
+/// Session state after a successful activation.
+#[derive(Clone)]
+struct Session {
+    dev_addr: DevAddr,
+    nwk_s_key: AES128Key,
+    app_s_key: AES128Key,
+    f_cnt_up: u32,
+    n_f_cnt_down: u32,
+}
+
+/// LoRaWAN 1.0.x Class-A end-device state machine.
+///
+/// The device does not perform any I/O. It returns the uplinks to transmit, including the
+/// channel and data-rate, and it must be given the received downlinks. The rx_windows
+/// function returns the Class-A receive windows that must be used for an uplink.
+///
+/// Transporting the frames is up to the user. Within ChirpStack, the simulator is used to
+/// test the uplink / downlink flows using the mock gateway backend. Publishing the frames
+/// over MQTT (e.g. for soak-testing with many devices) is not provided.
+pub struct Device {
+    config: Config,
+    region: Box<dyn Region + Sync + Send>,
+    session: Option<Session>,
+    dev_nonce: u16,
+    pending_join: bool,
+
+    dr: u8,
+    tx_power: u8,
+    nb_trans: u8,
+    enabled_uplink_channel_indices: Vec<usize>,
+    next_channel: usize,
+
+    rx1_delay: u8,
+    rx1_dr_offset: u8,
+    rx2_frequency: u32,
+    rx2_dr: u8,
+
+    // Set when a confirmed downlink must be acknowledged in the next uplink.
+    ack_pending: bool,
+    // Set when a confirmed uplink has not yet been acknowledged by the network.
+    confirmed_uplink_pending: bool,
+    last_uplink: Option<Uplink>,
+    mac_commands: Vec<MACCommand>,
+
+    device_time: Option<Duration>,
+    link_check: Option<LinkCheckAnsPayload>,
+}
+
+impl Device {
+    pub fn new(config: Config) -> Result<Self> {
+        let region = region::get(config.region, false, false);
+        let defaults = region.get_defaults();
+
+        let session = match &config.activation {
+            Activation::Otaa { .. } => None,
+            Activation::Abp {
+                dev_addr,
+                nwk_s_key,
+                app_s_key,
+            } => Some(Session {
+                dev_addr: *dev_addr,
+                nwk_s_key: *nwk_s_key,
+                app_s_key: *app_s_key,
+                f_cnt_up: 0,
+                n_f_cnt_down: 0,
+            }),
+        };
+
+        // Validate the configured data-rate.
+        region.get_data_rate(true, config.dr)?;
+
+        Ok(Device {
+            dr: config.dr,
+            tx_power: 0,
+            nb_trans: 1,
+            enabled_uplink_channel_indices: region.get_default_uplink_channel_indices(),
+            next_channel: 0,
+            rx1_delay: defaults.rx1_delay.as_secs() as u8,
+            rx1_dr_offset: 0,
+            rx2_frequency: defaults.rx2_frequency,
+            rx2_dr: defaults.rx2_dr,
+            config,
+            region,
+            session,
+            dev_nonce: 0,
+            pending_join: false,
+            ack_pending: false,
+            confirmed_uplink_pending: false,
+            last_uplink: None,
+            mac_commands: Vec::new(),
+            device_time: None,
+            link_check: None,
+        })
+    }
+
+    pub fn get_dev_eui(&self) -> EUI64 {
+        self.config.dev_eui
+    }
+
+    pub fn is_activated(&self) -> bool {
+        self.session.is_some()
+    }
+
+    pub fn get_dev_addr(&self) -> Option<DevAddr> {
+        self.session.as_ref().map(|s| s.dev_addr)
+    }
+
+    pub fn get_f_cnt_up(&self) -> u32 {
+        self.session
+            .as_ref()
+            .map(|s| s.f_cnt_up)
+            .unwrap_or_default()
+    }
+
+    pub fn get_n_f_cnt_down(&self) -> u32 {
+        self.session
+            .as_ref()
+            .map(|s| s.n_f_cnt_down)
+            .unwrap_or_default()
+    }
+
+    pub fn get_dr(&self) -> u8 {
+        self.dr
+    }
+
+    pub fn get_tx_power(&self) -> u8 {
+        self.tx_power
+    }
+
+    pub fn get_nb_trans(&self) -> u8 {
+        self.nb_trans
+    }
+
+    pub fn get_enabled_uplink_channel_indices(&self) -> &[usize] {
+        &self.enabled_uplink_channel_indices
+    }
+
+    /// Returns true when the last confirmed uplink has not been acknowledged.
+    pub fn is_confirmed_uplink_pending(&self) -> bool {
+        self.confirmed_uplink_pending
+    }
+
+    /// Returns the time from the last received DeviceTimeAns (time since GPS epoch).
+    pub fn get_device_time(&self) -> Option<Duration> {
+        self.device_time
+    }
+
+    /// Returns the last received LinkCheckAns.
+    pub fn get_link_check(&self) -> Option<LinkCheckAnsPayload> {
+        self.link_check.clone()
+    }
+
+    /// Queue a device-initiated mac-command (e.g. DeviceTimeReq or LinkCheckReq), which will
+    /// be sent with the next uplink.
+    pub fn queue_mac_command(&mut self, mac: MACCommand) {
+        self.mac_commands.push(mac);
+    }
+
+    /// Returns the join-request to transmit. Every join-request uses a new DevNonce.
+    pub fn join_request(&mut self) -> Result<Uplink> {
+        let (join_eui, app_key) = match &self.config.activation {
+            Activation::Otaa { join_eui, app_key } => (*join_eui, *app_key),
+            Activation::Abp { .. } => {
+                return Err(anyhow!("Join-request requires OTAA activation"));
+            }
+        };
+
+        self.dev_nonce = self.dev_nonce.wrapping_add(1);
+        self.pending_join = true;
+
+        let mut phy = PhyPayload {
+            mhdr: MHDR {
+                f_type: FType::JoinRequest,
+                major: Major::LoRaWANR1,
+            },
+            payload: Payload::JoinRequest(JoinRequestPayload {
+                join_eui,
+                dev_eui: self.config.dev_eui,
+                dev_nonce: self.dev_nonce,
+            }),
+            mic: None,
+        };
+        phy.set_join_request_mic(&app_key)?;
+
+        let up = self.get_uplink(phy)?;
+        self.last_uplink = Some(up.clone());
+        Ok(up)
+    }
+
+    /// Returns the data uplink to transmit. Pending mac-command answers are sent as FOpts,
+    /// or in case they exceed 15 bytes and no payload is given, as FRMPayload on fPort 0.
+    pub fn uplink(&mut self, f_port: u8, data: &[u8], confirmed: bool) -> Result<Uplink> {
+        if f_port == 0 || f_port > 223 {
+            return Err(anyhow!("f_port must be between 1 and 223"));
+        }
+
+        let macs = MACCommandSet::new(self.mac_commands.clone());
+        let (f_opts, f_port, frm_payload) = if macs.size()? > 15 {
+            if !data.is_empty() {
+                return Err(anyhow!(
+                    "Pending mac-commands exceed FOpts, send an empty uplink first"
+                ));
+            }
+
+            (
+                MACCommandSet::new(vec![]),
+                0,
+                Some(FRMPayload::MACCommandSet(macs)),
+            )
+        } else if data.is_empty() {
+            (macs, f_port, None)
+        } else {
+            (macs, f_port, Some(FRMPayload::Raw(data.to_vec())))
+        };
+
+        let session = self
+            .session
+            .as_mut()
+            .ok_or_else(|| anyhow!("Device is not activated"))?;
+
+        let mut phy = PhyPayload {
+            mhdr: MHDR {
+                f_type: if confirmed {
+                    FType::ConfirmedDataUp
+                } else {
+                    FType::UnconfirmedDataUp
+                },
+                major: Major::LoRaWANR1,
+            },
+            payload: Payload::MACPayload(MACPayload {
+                fhdr: FHDR {
+                    devaddr: session.dev_addr,
+                    f_ctrl: FCtrl {
+                        adr: true,
+                        ack: self.ack_pending,
+                        ..Default::default()
+                    },
+                    f_cnt: session.f_cnt_up,
+                    f_opts,
+                },
+                f_port: Some(f_port),
+                frm_payload,
+            }),
+            mic: None,
+        };
+
+        if f_port == 0 {
+            phy.encrypt_frm_payload(&session.nwk_s_key)?;
+        } else {
+            phy.encrypt_frm_payload(&session.app_s_key)?;
+        }
+
+        session.f_cnt_up += 1;
+        self.ack_pending = false;
+        self.confirmed_uplink_pending = confirmed;
+        self.mac_commands.clear();
+
+        let mut up = self.get_uplink(phy)?;
+        let session = self.session.as_ref().unwrap();
+        up.phy_payload.set_uplink_data_mic(
+            MACVersion::LoRaWAN1_0,
+            0,
+            up.dr,
+            up.channel as u8,
+            &session.nwk_s_key,
+            &session.nwk_s_key,
+        )?;
+
+        self.last_uplink = Some(up.clone());
+        Ok(up)
+    }
+
+    /// Returns the last uplink for retransmission, e.g. when a confirmed uplink was not
+    /// acknowledged. The retransmission uses the same frame-counter.
+    pub fn retransmit(&self) -> Result<Uplink> {
+        self.last_uplink
+            .clone()
+            .ok_or_else(|| anyhow!("No uplink to retransmit"))
+    }
+
+    /// Returns the RX1 and RX2 receive windows for the given uplink.
+    pub fn rx_windows(&self, up: &Uplink) -> Result<[RxWindow; 2]> {
+        let defaults = self.region.get_defaults();
+
+        let (rx1_delay, rx2_delay) = if up.phy_payload.mhdr.f_type == FType::JoinRequest {
+            (defaults.join_accept_delay1, defaults.join_accept_delay2)
+        } else {
+            let rx1_delay = Duration::from_secs(self.rx1_delay.max(1).into());
+            (rx1_delay, rx1_delay + Duration::from_secs(1))
+        };
+
+        Ok([
+            RxWindow {
+                delay: rx1_delay,
+                frequency: self
+                    .region
+                    .get_rx1_frequency_for_uplink_frequency(up.frequency)?,
+                dr: self
+                    .region
+                    .get_rx1_data_rate_index(up.dr, self.rx1_dr_offset.into())?,
+            },
+            RxWindow {
+                delay: rx2_delay,
+                frequency: self.rx2_frequency,
+                dr: self.rx2_dr,
+            },
+        ])
+    }
+
+    /// Handle the received downlink PHYPayload. Mac-commands are processed and their answers
+    /// are queued for the next uplink.
+    pub fn handle_downlink(&mut self, b: &[u8]) -> Result<Downlink> {
+        let phy = PhyPayload::from_slice(b)?;
+
+        match phy.mhdr.f_type {
+            FType::JoinAccept => self.handle_join_accept(phy),
+            FType::UnconfirmedDataDown | FType::ConfirmedDataDown => self.handle_data(phy),
+            _ => Err(anyhow!("Unexpected downlink f_type: {}", phy.mhdr.f_type)),
+        }
+    }
+
+    fn handle_join_accept(&mut self, mut phy: PhyPayload) -> Result<Downlink> {
+        let (join_eui, app_key) = match &self.config.activation {
+            Activation::Otaa { join_eui, app_key } => (*join_eui, *app_key),
+            Activation::Abp { .. } => {
+                return Err(anyhow!("Join-accept requires OTAA activation"));
+            }
+        };
+
+        if !self.pending_join {
+            return Err(anyhow!("No pending join-request"));
+        }
+
+        phy.decrypt_join_accept_payload(&app_key)?;
+        if !phy.validate_join_accept_mic(JoinType::Join, &join_eui, self.dev_nonce, &app_key)? {
+            return Err(anyhow!("Invalid MIC"));
+        }
+
+        let pl = match &phy.payload {
+            Payload::JoinAccept(v) => v,
+            _ => return Err(anyhow!("Expected JoinAcceptPayload")),
+        };
+
+        let nwk_s_key = keys::get_f_nwk_s_int_key(
+            false,
+            &app_key,
+            &pl.home_netid,
+            &join_eui,
+            pl.join_nonce,
+            self.dev_nonce,
+        )?;
+        let app_s_key = keys::get_app_s_key(
+            false,
+            &app_key,
+            &pl.home_netid,
+            &join_eui,
+            pl.join_nonce,
+            self.dev_nonce,
+        )?;
+
+        self.rx1_delay = pl.rx_delay;
+        self.rx1_dr_offset = pl.dl_settings.rx1_dr_offset;
+        self.rx2_dr = pl.dl_settings.rx2_dr;
+        self.rx2_frequency = self.region.get_defaults().rx2_frequency;
+
+        match &pl.cflist {
+            Some(CFList::Channels(channels)) => {
+                for f in channels.iter().filter(|f| **f != 0) {
+                    let data_rates = self
+                        .region
+                        .get_uplink_channel(0)
+                        .map(|c| c.data_rates)
+                        .unwrap_or_default();
+                    self.region.add_channel(*f, data_rates)?;
+                }
+                self.enabled_uplink_channel_indices = self.region.get_uplink_channel_indices();
+            }
+            Some(CFList::ChannelMask(masks)) => {
+                self.enabled_uplink_channel_indices = masks
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, mask)| {
+                        mask.into_iter()
+                            .enumerate()
+                            .filter(|(_, v)| *v)
+                            .map(move |(j, _)| i * 16 + j)
+                    })
+                    .collect();
+            }
+            None => {}
+        }
+
+        self.session = Some(Session {
+            dev_addr: pl.devaddr,
+            nwk_s_key,
+            app_s_key,
+            f_cnt_up: 0,
+            n_f_cnt_down: 0,
+        });
+        self.pending_join = false;
+        self.ack_pending = false;
+        self.confirmed_uplink_pending = false;
+        self.mac_commands.clear();
+
+        Ok(Downlink::JoinAccept {
+            dev_addr: pl.devaddr,
+        })
+    }
+
+    fn handle_data(&mut self, mut phy: PhyPayload) -> Result<Downlink> {
+        let session = self
+            .session
+            .as_ref()
+            .ok_or_else(|| anyhow!("Device is not activated"))?
+            .clone();
+
+        let f_cnt = if let Payload::MACPayload(pl) = &mut phy.payload {
+            if pl.fhdr.devaddr != session.dev_addr {
+                return Err(anyhow!("DevAddr does not match"));
+            }
+
+            // Restore the full 32 bit frame-counter from the 16 lsb.
+            let mut f_cnt = (session.n_f_cnt_down & 0xffff0000) | (pl.fhdr.f_cnt & 0xffff);
+            if f_cnt < session.n_f_cnt_down {
+                f_cnt += 1 << 16;
+            }
+            pl.fhdr.f_cnt = f_cnt;
+            f_cnt
+        } else {
+            return Err(anyhow!("Expected MACPayload"));
+        };
+
+        if !phy.validate_downlink_data_mic(MACVersion::LoRaWAN1_0, 0, &session.nwk_s_key)? {
+            return Err(anyhow!("Invalid MIC"));
+        }
+
+        phy.decode_f_opts_to_mac_commands()?;
+
+        let f_port = match &phy.payload {
+            Payload::MACPayload(pl) => pl.f_port,
+            _ => None,
+        };
+
+        if f_port == Some(0) {
+            phy.decrypt_frm_payload(&session.nwk_s_key)?;
+        } else {
+            phy.decrypt_frm_payload(&session.app_s_key)?;
+        }
+
+        let pl = match phy.payload {
+            Payload::MACPayload(v) => v,
+            _ => return Err(anyhow!("Expected MACPayload")),
+        };
+
+        let mut mac_commands: Vec<MACCommand> = pl.fhdr.f_opts.iter().cloned().collect();
+        let mut data = Vec::new();
+        match pl.frm_payload {
+            Some(FRMPayload::MACCommandSet(v)) => mac_commands.extend(v.iter().cloned()),
+            Some(FRMPayload::Raw(v)) => data = v,
+            _ => {}
+        }
+
+        if let Some(s) = self.session.as_mut() {
+            s.n_f_cnt_down = f_cnt + 1;
+        }
+
+        let confirmed = phy.mhdr.f_type == FType::ConfirmedDataDown;
+        self.ack_pending = confirmed;
+        if pl.fhdr.f_ctrl.ack {
+            self.confirmed_uplink_pending = false;
+        }
+
+        self.handle_mac_commands(&mac_commands)?;
+
+        Ok(Downlink::Data {
+            f_cnt,
+            f_port,
+            data,
+            confirmed,
+            ack: pl.fhdr.f_ctrl.ack,
+            f_pending: pl.fhdr.f_ctrl.f_pending,
+            mac_commands,
+        })
+    }
+
+    fn handle_mac_commands(&mut self, macs: &[MACCommand]) -> Result<()> {
+        // The device-initiated mac-commands have been answered by this downlink.
+        self.mac_commands
+            .retain(|m| !matches!(m, MACCommand::DeviceTimeReq | MACCommand::LinkCheckReq));
+
+        // LinkADRReq mac-commands must be handled as a block.
+        let link_adr_reqs: Vec<LinkADRReqPayload> = macs
+            .iter()
+            .filter_map(|m| match m {
+                MACCommand::LinkADRReq(pl) => Some(pl.clone()),
+                _ => None,
+            })
+            .collect();
+        if !link_adr_reqs.is_empty() {
+            let ans = self.handle_link_adr_reqs(&link_adr_reqs);
+            for _ in &link_adr_reqs {
+                self.mac_commands.push(MACCommand::LinkADRAns(ans.clone()));
+            }
+        }
+
+        for mac in macs {
+            match mac {
+                MACCommand::DevStatusReq => {
+                    self.mac_commands
+                        .push(MACCommand::DevStatusAns(DevStatusAnsPayload {
+                            battery: self.config.battery,
+                            margin: self.config.margin,
+                        }));
+                }
+                MACCommand::RxParamSetupReq(pl) => {
+                    let ans = RxParamSetupAnsPayload {
+                        channel_ack: pl.frequency != 0,
+                        rx2_dr_ack: self
+                            .region
+                            .get_data_rate(false, pl.dl_settings.rx2_dr)
+                            .is_ok(),
+                        rx1_dr_offset_ack: self
+                            .region
+                            .get_rx1_data_rate_index(self.dr, pl.dl_settings.rx1_dr_offset.into())
+                            .is_ok(),
+                    };
+
+                    if ans.channel_ack && ans.rx2_dr_ack && ans.rx1_dr_offset_ack {
+                        self.rx2_frequency = pl.frequency;
+                        self.rx2_dr = pl.dl_settings.rx2_dr;
+                        self.rx1_dr_offset = pl.dl_settings.rx1_dr_offset;
+                    }
+
+                    self.mac_commands.push(MACCommand::RxParamSetupAns(ans));
+                }
+                MACCommand::RxTimingSetupReq(pl) => {
+                    self.rx1_delay = pl.delay;
+                    self.mac_commands.push(MACCommand::RxTimingSetupAns);
+                }
+                MACCommand::NewChannelReq(pl) => {
+                    let ans = self.handle_new_channel_req(pl);
+                    self.mac_commands.push(MACCommand::NewChannelAns(ans));
+                }
+                MACCommand::DlChannelReq(pl) => {
+                    self.mac_commands
+                        .push(MACCommand::DlChannelAns(DlChannelAnsPayload {
+                            uplink_freq_exists: self
+                                .region
+                                .get_uplink_channel(pl.ch_index.into())
+                                .is_ok(),
+                            channel_freq_ok: pl.freq != 0,
+                        }));
+                }
+                MACCommand::DutyCycleReq(_) => {
+                    self.mac_commands.push(MACCommand::DutyCycleAns);
+                }
+                MACCommand::TxParamSetupReq(_) => {
+                    self.mac_commands.push(MACCommand::TxParamSetupAns);
+                }
+                MACCommand::ADRParamSetupReq(_) => {
+                    self.mac_commands.push(MACCommand::ADRParamSetupAns);
+                }
+                MACCommand::DeviceTimeAns(pl) => {
+                    self.device_time = Some(pl.time_since_gps_epoch);
+                }
+                MACCommand::LinkCheckAns(pl) => {
+                    self.link_check = Some(pl.clone());
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_link_adr_reqs(&mut self, pls: &[LinkADRReqPayload]) -> LinkADRAnsPayload {
+        // The last mac-command of the block contains the DR, TXPower and NbTrans.
+        let last = pls.last().unwrap();
+
+        let ch_mask = self
+            .region
+            .get_enabled_uplink_channel_indices_for_link_adr_payloads(
+                &self.enabled_uplink_channel_indices,
+                pls,
+            )
+            .ok()
+            .filter(|v| !v.is_empty());
+
+        let dr_ack = last.dr == 15 || self.region.get_data_rate(true, last.dr).is_ok();
+        let tx_power_ack = last.tx_power == 15
+            || self
+                .region
+                .get_tx_power_offset(last.tx_power.into())
+                .is_ok();
+
+        let ans = LinkADRAnsPayload {
+            ch_mask_ack: ch_mask.is_some(),
+            dr_ack,
+            tx_power_ack,
+        };
+
+        // The changes are only applied when all are acknowledged.
+        if let Some(ch_mask) = ch_mask
+            && dr_ack
+            && tx_power_ack
+        {
+            self.enabled_uplink_channel_indices = ch_mask;
+            self.next_channel = 0;
+
+            if last.dr != 15 {
+                self.dr = last.dr;
+            }
+            if last.tx_power != 15 {
+                self.tx_power = last.tx_power;
+            }
+            if last.redundancy.nb_rep != 0 {
+                self.nb_trans = last.redundancy.nb_rep;
+            }
+        }
+
+        ans
+    }
+
+    fn handle_new_channel_req(&mut self, pl: &NewChannelReqPayload) -> NewChannelAnsPayload {
+        let ch_index: usize = pl.ch_index.into();
+        let data_rates: Vec<u8> = (pl.min_dr..=pl.max_dr).collect();
+        let dr_range_ok = !data_rates.is_empty()
+            && data_rates
+                .iter()
+                .all(|dr| self.region.get_data_rate(true, *dr).is_ok());
+
+        // Only adding a channel at the next free index is supported.
+        let channel_freq_ok =
+            pl.freq != 0 && ch_index == self.region.get_uplink_channel_indices().len();
+
+        if dr_range_ok && channel_freq_ok && self.region.add_channel(pl.freq, data_rates).is_ok() {
+            self.enabled_uplink_channel_indices.push(ch_index);
+        }
+
+        NewChannelAnsPayload {
+            channel_freq_ok,
+            dr_range_ok,
+        }
+    }
+
+    // Returns the uplink for the given PhyPayload, using the next enabled uplink channel.
+    fn get_uplink(&mut self, phy_payload: PhyPayload) -> Result<Uplink> {
+        let channels: Vec<usize> = self
+            .enabled_uplink_channel_indices
+            .iter()
+            .filter(|i| {
+                self.region
+                    .get_uplink_channel(**i)
+                    .map(|c| c.data_rates.contains(&self.dr))
+                    .unwrap_or_default()
+            })
+            .cloned()
+            .collect();
+
+        if channels.is_empty() {
+            return Err(anyhow!(
+                "No enabled uplink channel available for dr: {}",
+                self.dr
+            ));
+        }
+
+        let channel = channels[self.next_channel % channels.len()];
+        self.next_channel = self.next_channel.wrapping_add(1);
+
+        Ok(Uplink {
+            phy_payload,
+            frequency: self.region.get_uplink_channel(channel)?.frequency,
+            dr: self.dr,
+            channel,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::str::FromStr;
+
+    use super::*;
+
+    fn get_device() -> Device {
+        Device::new(Config {
+            dev_eui: EUI64::from_str("0102030405060708").unwrap(),
+            activation: Activation::Otaa {
+                join_eui: EUI64::from_str("0807060504030201").unwrap(),
+                app_key: AES128Key::from_str("01020304050607080102030405060708").unwrap(),
+            },
+            region: CommonName::EU868,
+            dr: 0,
+            battery: 128,
+            margin: 10,
+        })
+        .unwrap()
+    }
+
+    fn get_join_accept(dev_nonce: u16) -> Vec<u8> {
+        let app_key = AES128Key::from_str("01020304050607080102030405060708").unwrap();
+        let join_eui = EUI64::from_str("0807060504030201").unwrap();
+
+        let mut phy = PhyPayload {
+            mhdr: MHDR {
+                f_type: FType::JoinAccept,
+                major: Major::LoRaWANR1,
+            },
+            payload: Payload::JoinAccept(JoinAcceptPayload {
+                join_nonce: 1,
+                home_netid: NetID::from_be_bytes([0, 0, 1]),
+                devaddr: DevAddr::from_be_bytes([1, 2, 3, 4]),
+                dl_settings: DLSettings {
+                    opt_neg: false,
+                    rx2_dr: 3,
+                    rx1_dr_offset: 1,
+                },
+                rx_delay: 2,
+                cflist: Some(CFList::Channels(CFListChannels::new([
+                    867100000, 867300000, 0, 0, 0,
+                ]))),
+            }),
+            mic: None,
+        };
+        phy.set_join_accept_mic(JoinType::Join, &join_eui, dev_nonce, &app_key)
+            .unwrap();
+        phy.encrypt_join_accept_payload(&app_key).unwrap();
+        phy.to_vec().unwrap()
+    }
+
+    fn get_nwk_s_key(dev_nonce: u16) -> AES128Key {
+        keys::get_f_nwk_s_int_key(
+            false,
+            &AES128Key::from_str("01020304050607080102030405060708").unwrap(),
+            &NetID::from_be_bytes([0, 0, 1]),
+            &EUI64::from_str("0807060504030201").unwrap(),
+            1,
+            dev_nonce,
+        )
+        .unwrap()
+    }
+
+    fn get_downlink(
+        nwk_s_key: &AES128Key,
+        f_cnt: u32,
+        confirmed: bool,
+        ack: bool,
+        macs: Vec<MACCommand>,
+    ) -> Vec<u8> {
+        let mut phy = PhyPayload {
+            mhdr: MHDR {
+                f_type: if confirmed {
+                    FType::ConfirmedDataDown
+                } else {
+                    FType::UnconfirmedDataDown
+                },
+                major: Major::LoRaWANR1,
+            },
+            payload: Payload::MACPayload(MACPayload {
+                fhdr: FHDR {
+                    devaddr: DevAddr::from_be_bytes([1, 2, 3, 4]),
+                    f_ctrl: FCtrl {
+                        ack,
+                        ..Default::default()
+                    },
+                    f_cnt,
+                    f_opts: MACCommandSet::new(macs),
+                },
+                f_port: None,
+                frm_payload: None,
+            }),
+            mic: None,
+        };
+        phy.set_downlink_data_mic(MACVersion::LoRaWAN1_0, 0, nwk_s_key)
+            .unwrap();
+        phy.to_vec().unwrap()
+    }
+
+    #[test]
+    fn test_otaa() {
+        let mut dev = get_device();
+        assert!(!dev.is_activated());
+        assert!(dev.uplink(1, &[1, 2, 3], false).is_err());
+
+        // Join-request.
+        let up = dev.join_request().unwrap();
+        assert_eq!(868100000, up.frequency);
+        assert_eq!(0, up.dr);
+        assert!(
+            up.phy_payload
+                .validate_join_request_mic(
+                    &AES128Key::from_str("01020304050607080102030405060708").unwrap()
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            [
+                RxWindow {
+                    delay: Duration::from_secs(5),
+                    frequency: 868100000,
+                    dr: 0,
+                },
+                RxWindow {
+                    delay: Duration::from_secs(6),
+                    frequency: 869525000,
+                    dr: 0,
+                },
+            ],
+            dev.rx_windows(&up).unwrap()
+        );
+
+        // Join-accept.
+        assert_eq!(
+            Downlink::JoinAccept {
+                dev_addr: DevAddr::from_be_bytes([1, 2, 3, 4]),
+            },
+            dev.handle_downlink(&get_join_accept(1)).unwrap()
+        );
+        assert!(dev.is_activated());
+        assert_eq!(
+            vec![0, 1, 2, 3, 4],
+            dev.get_enabled_uplink_channel_indices()
+        );
+
+        // Uplink.
+        let nwk_s_key = get_nwk_s_key(1);
+        let up = dev.uplink(10, &[1, 2, 3], true).unwrap();
+        assert_eq!(868300000, up.frequency);
+        assert_eq!(1, dev.get_f_cnt_up());
+        assert!(dev.is_confirmed_uplink_pending());
+        assert!(
+            up.phy_payload
+                .validate_uplink_data_mic(MACVersion::LoRaWAN1_0, 0, 0, 0, &nwk_s_key, &nwk_s_key)
+                .unwrap()
+        );
+        assert_eq!(
+            [
+                RxWindow {
+                    delay: Duration::from_secs(2),
+                    frequency: 868300000,
+                    dr: 0,
+                },
+                RxWindow {
+                    delay: Duration::from_secs(3),
+                    frequency: 869525000,
+                    dr: 3,
+                },
+            ],
+            dev.rx_windows(&up).unwrap()
+        );
+
+        // Retransmission uses the same frame.
+        assert_eq!(up, dev.retransmit().unwrap());
+
+        // Confirmed downlink with ack and mac-commands.
+        let dn = dev
+            .handle_downlink(&get_downlink(
+                &nwk_s_key,
+                0,
+                true,
+                true,
+                vec![
+                    MACCommand::DevStatusReq,
+                    MACCommand::LinkADRReq(LinkADRReqPayload {
+                        dr: 5,
+                        tx_power: 2,
+                        ch_mask: ChMask::new([
+                            true, true, true, false, false, false, false, false, false, false,
+                            false, false, false, false, false, false,
+                        ]),
+                        redundancy: Redundancy {
+                            ch_mask_cntl: 0,
+                            nb_rep: 2,
+                        },
+                    }),
+                ],
+            ))
+            .unwrap();
+        match dn {
+            Downlink::Data {
+                f_cnt,
+                confirmed,
+                ack,
+                mac_commands,
+                ..
+            } => {
+                assert_eq!(0, f_cnt);
+                assert!(confirmed);
+                assert!(ack);
+                assert_eq!(2, mac_commands.len());
+            }
+            _ => panic!("Expected Data"),
+        }
+        assert!(!dev.is_confirmed_uplink_pending());
+        assert_eq!(1, dev.get_n_f_cnt_down());
+        assert_eq!(5, dev.get_dr());
+        assert_eq!(2, dev.get_tx_power());
+        assert_eq!(2, dev.get_nb_trans());
+        assert_eq!(vec![0, 1, 2], dev.get_enabled_uplink_channel_indices());
+
+        // The next uplink acknowledges the downlink and contains the mac-command answers.
+        let up = dev.uplink(10, &[], false).unwrap();
+        assert_eq!(5, up.dr);
+        let mut phy = up.phy_payload.clone();
+        phy.decode_f_opts_to_mac_commands().unwrap();
+        match &phy.payload {
+            Payload::MACPayload(pl) => {
+                assert!(pl.fhdr.f_ctrl.ack);
+                assert_eq!(1, pl.fhdr.f_cnt);
+                assert_eq!(
+                    MACCommandSet::new(vec![
+                        MACCommand::LinkADRAns(LinkADRAnsPayload {
+                            ch_mask_ack: true,
+                            dr_ack: true,
+                            tx_power_ack: true,
+                        }),
+                        MACCommand::DevStatusAns(DevStatusAnsPayload {
+                            battery: 128,
+                            margin: 10,
+                        }),
+                    ]),
+                    pl.fhdr.f_opts
+                );
+            }
+            _ => panic!("Expected MACPayload"),
+        }
+
+        // Replayed downlink.
+        assert!(
+            dev.handle_downlink(&get_downlink(&nwk_s_key, 0, false, false, vec![]))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_abp() {
+        let nwk_s_key = AES128Key::from_bytes([1; 16]);
+        let mut dev = Device::new(Config {
+            dev_eui: EUI64::from_str("0102030405060708").unwrap(),
+            activation: Activation::Abp {
+                dev_addr: DevAddr::from_be_bytes([1, 2, 3, 4]),
+                nwk_s_key,
+                app_s_key: AES128Key::from_bytes([2; 16]),
+            },
+            region: CommonName::EU868,
+            dr: 3,
+            battery: 255,
+            margin: 0,
+        })
+        .unwrap();
+
+        assert!(dev.is_activated());
+        assert!(dev.join_request().is_err());
+
+        dev.queue_mac_command(MACCommand::DeviceTimeReq);
+        let up = dev.uplink(1, &[1], false).unwrap();
+        assert_eq!(3, up.dr);
+        let mut phy = up.phy_payload.clone();
+        phy.decode_f_opts_to_mac_commands().unwrap();
+        match &phy.payload {
+            Payload::MACPayload(pl) => {
+                assert_eq!(
+                    MACCommandSet::new(vec![MACCommand::DeviceTimeReq]),
+                    pl.fhdr.f_opts
+                );
+            }
+            _ => panic!("Expected MACPayload"),
+        }
+
+        dev.handle_downlink(&get_downlink(
+            &nwk_s_key,
+            10,
+            false,
+            false,
+            vec![MACCommand::DeviceTimeAns(DeviceTimeAnsPayload {
+                time_since_gps_epoch: Duration::from_secs(100),
+            })],
+        ))
+        .unwrap();
+        assert_eq!(Some(Duration::from_secs(100)), dev.get_device_time());
+        assert_eq!(11, dev.get_n_f_cnt_down());
+
+        // Downlink with invalid MIC.
+        assert!(
+            dev.handle_downlink(&get_downlink(
+                &AES128Key::from_bytes([3; 16]),
+                11,
+                false,
+                false,
+                vec![]
+            ))
+            .is_err()
+        );
+    }
+}
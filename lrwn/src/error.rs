@@ -20,9 +20,16 @@ pub enum Error {
     #[error("EUI64Prefix must be in the form 0000000000000000/0")]
     EUI64PrefixFormat,
 
-    #[error(transparent)]
-    FromHexError(#[from] hex::FromHexError),
+    // hex::FromHexError only implements std::error::Error when compiled with std.
+    #[error("{0}")]
+    FromHexError(hex::FromHexError),
 
     #[error(transparent)]
     Anyhow(#[from] anyhow::Error),
 }
+
+impl From<hex::FromHexError> for Error {
+    fn from(e: hex::FromHexError) -> Self {
+        Error::FromHexError(e)
+    }
+}
@@ -1,5 +1,5 @@
-use std::fmt;
-use std::str::FromStr;
+use core::fmt;
+use core::str::FromStr;
 
 use anyhow::{Context, Result};
 #[cfg(feature = "postgres")]
@@ -7,7 +7,7 @@ use diesel::pg::Pg;
 #[cfg(feature = "sqlite")]
 use diesel::sqlite::Sqlite;
 #[cfg(feature = "diesel")]
-use diesel::{backend::Backend, deserialize, serialize, sql_types::Binary};
+use diesel::{backend::Backend, deserialize, serialize, sql_types::Binary, sql_types::Text};
 #[cfg(feature = "serde")]
 use serde::{
     Deserialize, Deserializer, Serialize, Serializer,
@@ -15,6 +15,7 @@ use serde::{
 };
 
 use crate::Error;
+use crate::prelude::*;
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "diesel", derive(AsExpression, FromSqlRow))]
@@ -171,6 +172,8 @@ impl diesel::sql_types::SqlType for EUI64 {
 }
 
 #[derive(PartialEq, Eq, Copy, Clone, Default)]
+#[cfg_attr(feature = "diesel", derive(AsExpression, FromSqlRow))]
+#[cfg_attr(feature = "diesel", diesel(sql_type = diesel::sql_types::Text))]
 pub struct EUI64Prefix([u8; 8], u64);
 
 impl EUI64Prefix {
@@ -228,7 +231,9 @@ impl FromStr for EUI64Prefix {
         }
 
         let mut mask: [u8; 8] = [0; 8];
-        hex::decode_to_slice(parts[0], &mut mask).context("Decode EUI64Prefix")?;
+        hex::decode_to_slice(parts[0], &mut mask)
+            .map_err(Error::from)
+            .context("Decode EUI64Prefix")?;
 
         Ok(EUI64Prefix(mask, size))
     }
@@ -273,6 +278,37 @@ impl Visitor<'_> for EUI64PrefixVisitor {
     }
 }
 
+#[cfg(feature = "postgres")]
+impl deserialize::FromSql<Text, Pg> for EUI64Prefix {
+    fn from_sql(bytes: <Pg as Backend>::RawValue<'_>) -> deserialize::Result<Self> {
+        let value = <String as deserialize::FromSql<Text, Pg>>::from_sql(bytes)?;
+        Ok(EUI64Prefix::from_str(&value)?)
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl serialize::ToSql<Text, Pg> for EUI64Prefix {
+    fn to_sql<'b>(&'b self, out: &mut serialize::Output<'b, '_, Pg>) -> serialize::Result {
+        <String as serialize::ToSql<Text, Pg>>::to_sql(&self.to_string(), &mut out.reborrow())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl deserialize::FromSql<Text, Sqlite> for EUI64Prefix {
+    fn from_sql(bytes: <Sqlite as Backend>::RawValue<'_>) -> deserialize::Result<Self> {
+        let value = <String as deserialize::FromSql<Text, Sqlite>>::from_sql(bytes)?;
+        Ok(EUI64Prefix::from_str(&value)?)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl serialize::ToSql<Text, Sqlite> for EUI64Prefix {
+    fn to_sql<'b>(&'b self, out: &mut serialize::Output<'b, '_, Sqlite>) -> serialize::Result {
+        out.set_value(self.to_string());
+        Ok(serialize::IsNull::No)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4,6 +4,7 @@ use serde::Serialize;
 
 use super::devaddr::DevAddr;
 use super::maccommand::MACCommandSet;
+use crate::prelude::*;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
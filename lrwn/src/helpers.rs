@@ -1,5 +1,7 @@
 use anyhow::Result;
 
+use crate::prelude::*;
+
 pub fn encode_freq(freq: u32) -> Result<[u8; 3]> {
     let mut freq = freq;
     // Support LoRaWAN 2.4GHz, in which case the stepping is 200Hz:
@@ -38,3 +40,36 @@ pub fn decode_freq(b: &[u8]) -> Result<u32> {
 
     Ok(freq)
 }
+
+/// Cursor over a byte buffer, implementing the subset of std::io::Read which is needed
+/// for decoding (this is not available without std).
+pub struct Cursor {
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl Cursor {
+    pub fn new(buf: Vec<u8>) -> Self {
+        Cursor { buf, pos: 0 }
+    }
+
+    /// Read the exact number of bytes required to fill b.
+    pub fn read_exact(&mut self, b: &mut [u8]) -> Result<()> {
+        let end = self.pos + b.len();
+        if end > self.buf.len() {
+            return Err(anyhow!("failed to fill whole buffer"));
+        }
+
+        b.copy_from_slice(&self.buf[self.pos..end]);
+        self.pos = end;
+        Ok(())
+    }
+
+    /// Read all remaining bytes and append these to b.
+    pub fn read_to_end(&mut self, b: &mut Vec<u8>) -> Result<usize> {
+        let n = self.buf.len() - self.pos;
+        b.extend_from_slice(&self.buf[self.pos..]);
+        self.pos = self.buf.len();
+        Ok(n)
+    }
+}
@@ -1,3 +1,6 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 #[cfg(feature = "diesel")]
 #[macro_use]
 extern crate diesel;
@@ -16,6 +19,7 @@ pub use self::mhdr::*;
 pub use self::netid::*;
 pub use self::payload::*;
 pub use self::phy_payload::*;
+pub use self::phy_payload_ref::*;
 pub use self::relay::*;
 
 mod aes128;
@@ -23,6 +27,8 @@ mod aes128;
 pub mod applayer;
 mod cflist;
 mod devaddr;
+#[cfg(feature = "device")]
+pub mod device;
 mod dl_settings;
 mod error;
 mod eui64;
@@ -35,6 +41,8 @@ mod mhdr;
 mod netid;
 mod payload;
 mod phy_payload;
+mod phy_payload_ref;
+mod prelude;
 #[cfg(feature = "regions")]
 pub mod region;
 mod relay;
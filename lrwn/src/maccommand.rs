@@ -1,7 +1,6 @@
-use std::fmt;
-use std::io::{Cursor, Read};
-use std::ops::{Deref, DerefMut};
-use std::time::Duration;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use core::time::Duration;
 
 use anyhow::Result;
 #[cfg(feature = "serde")]
@@ -9,10 +8,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::cflist::ChMask;
 use crate::dl_settings::DLSettings;
-use crate::helpers::{decode_freq, encode_freq};
+use crate::helpers::{Cursor, decode_freq, encode_freq};
+use crate::prelude::*;
 
 pub trait PayloadCodec<Struct = Self> {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Struct>;
+    fn decode(cur: &mut Cursor) -> Result<Struct>;
     fn encode(&self) -> Result<Vec<u8>>;
 }
 
@@ -807,7 +807,7 @@ pub struct ResetIndPayload {
 }
 
 impl PayloadCodec for ResetIndPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -828,7 +828,7 @@ pub struct ResetConfPayload {
 }
 
 impl PayloadCodec for ResetConfPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -850,7 +850,7 @@ pub struct LinkCheckAnsPayload {
 }
 
 impl PayloadCodec for LinkCheckAnsPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 2];
         cur.read_exact(&mut b)?;
 
@@ -875,7 +875,7 @@ pub struct LinkADRReqPayload {
 }
 
 impl PayloadCodec for LinkADRReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 4];
         cur.read_exact(&mut b)?;
 
@@ -942,7 +942,7 @@ pub struct LinkADRAnsPayload {
 }
 
 impl PayloadCodec for LinkADRAnsPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -977,7 +977,7 @@ pub struct DutyCycleReqPayload {
 }
 
 impl PayloadCodec for DutyCycleReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -1003,7 +1003,7 @@ pub struct RxParamSetupReqPayload {
 }
 
 impl PayloadCodec for RxParamSetupReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 4];
         cur.read_exact(&mut b)?;
 
@@ -1030,7 +1030,7 @@ pub struct RxParamSetupAnsPayload {
 }
 
 impl PayloadCodec for RxParamSetupAnsPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -1064,7 +1064,7 @@ pub struct DevStatusAnsPayload {
 }
 
 impl PayloadCodec for DevStatusAnsPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 2];
         cur.read_exact(&mut b)?;
 
@@ -1108,7 +1108,7 @@ pub struct NewChannelReqPayload {
 }
 
 impl PayloadCodec for NewChannelReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 5];
         cur.read_exact(&mut b)?;
 
@@ -1138,7 +1138,7 @@ pub struct NewChannelAnsPayload {
 }
 
 impl PayloadCodec for NewChannelAnsPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -1167,7 +1167,7 @@ pub struct RxTimingSetupReqPayload {
 }
 
 impl PayloadCodec for RxTimingSetupReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
         Ok(RxTimingSetupReqPayload { delay: b[0] })
@@ -1191,7 +1191,7 @@ pub struct TxParamSetupReqPayload {
 }
 
 impl PayloadCodec for TxParamSetupReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -1239,7 +1239,7 @@ pub struct DlChannelReqPayload {
 }
 
 impl PayloadCodec for DlChannelReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 4];
         cur.read_exact(&mut b)?;
 
@@ -1265,7 +1265,7 @@ pub struct DlChannelAnsPayload {
 }
 
 impl PayloadCodec for DlChannelAnsPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -1296,7 +1296,7 @@ pub struct RekeyConfPayload {
 }
 
 impl PayloadCodec for RekeyConfPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -1317,7 +1317,7 @@ pub struct RekeyIndPayload {
 }
 
 impl PayloadCodec for RekeyIndPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -1338,7 +1338,7 @@ pub struct ADRParamSetupReqPayload {
 }
 
 impl PayloadCodec for ADRParamSetupReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -1386,7 +1386,7 @@ pub struct DeviceTimeAnsPayload {
 }
 
 impl PayloadCodec for DeviceTimeAnsPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 5];
         cur.read_exact(&mut b)?;
 
@@ -1421,7 +1421,7 @@ pub struct ForceRejoinReqPayload {
 }
 
 impl PayloadCodec for ForceRejoinReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 2];
         cur.read_exact(&mut b)?;
 
@@ -1462,7 +1462,7 @@ pub struct RejoinParamSetupReqPayload {
 }
 
 impl PayloadCodec for RejoinParamSetupReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -1491,7 +1491,7 @@ pub struct RejoinParamSetupAnsPayload {
 }
 
 impl PayloadCodec for RejoinParamSetupAnsPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -1516,7 +1516,7 @@ pub struct PingSlotInfoReqPayload {
 }
 
 impl PayloadCodec for PingSlotInfoReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -1542,7 +1542,7 @@ pub struct PingSlotChannelReqPayload {
 }
 
 impl PayloadCodec for PingSlotChannelReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 4];
         cur.read_exact(&mut b)?;
 
@@ -1568,7 +1568,7 @@ pub struct PingSlotChannelAnsPayload {
 }
 
 impl PayloadCodec for PingSlotChannelAnsPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -1597,7 +1597,7 @@ pub struct BeaconFreqReqPayload {
 }
 
 impl PayloadCodec for BeaconFreqReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 3];
         cur.read_exact(&mut b)?;
 
@@ -1618,7 +1618,7 @@ pub struct BeaconFreqAnsPayload {
 }
 
 impl PayloadCodec for BeaconFreqAnsPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -1643,7 +1643,7 @@ pub struct DeviceModeIndPayload {
 }
 
 impl PayloadCodec for DeviceModeIndPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -1664,7 +1664,7 @@ pub struct DeviceModeConfPayload {
 }
 
 impl PayloadCodec for DeviceModeConfPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -1739,7 +1739,7 @@ pub struct RelayConfReqPayload {
 }
 
 impl PayloadCodec for RelayConfReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 5];
         cur.read_exact(&mut b)?;
 
@@ -1769,7 +1769,7 @@ pub struct RelayConfAnsPayload {
 }
 
 impl PayloadCodec for RelayConfAnsPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -1952,7 +1952,7 @@ pub struct EndDeviceConfReqPayload {
 }
 
 impl PayloadCodec for EndDeviceConfReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 6];
         cur.read_exact(&mut b)?;
 
@@ -1982,7 +1982,7 @@ pub struct EndDeviceConfAnsPayload {
 }
 
 impl PayloadCodec for EndDeviceConfAnsPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -2052,7 +2052,7 @@ pub struct FilterListReqPayload {
 }
 
 impl PayloadCodec for FilterListReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = vec![0; 2];
         cur.read_exact(&mut b)?;
 
@@ -2103,7 +2103,7 @@ pub struct FilterListAnsPayload {
 }
 
 impl PayloadCodec for FilterListAnsPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -2169,7 +2169,7 @@ pub struct UpdateUplinkListReqPayload {
 }
 
 impl PayloadCodec for UpdateUplinkListReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 26];
         cur.read_exact(&mut b)?;
 
@@ -2243,7 +2243,7 @@ pub struct CtrlUplinkListReqPayload {
 }
 
 impl PayloadCodec for CtrlUplinkListReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 1];
         cur.read_exact(&mut b)?;
 
@@ -2265,7 +2265,7 @@ pub struct CtrlUplinkListAnsPayload {
 }
 
 impl PayloadCodec for CtrlUplinkListAnsPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 5];
         cur.read_exact(&mut b)?;
 
@@ -2381,7 +2381,7 @@ pub struct ConfigureFwdLimitReqPayload {
 }
 
 impl PayloadCodec for ConfigureFwdLimitReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 5];
         cur.read_exact(&mut b)?;
 
@@ -2438,7 +2438,7 @@ pub struct NotifyNewEndDeviceReqPayload {
 }
 
 impl PayloadCodec for NotifyNewEndDeviceReqPayload {
-    fn decode(cur: &mut Cursor<Vec<u8>>) -> Result<Self> {
+    fn decode(cur: &mut Cursor) -> Result<Self> {
         let mut b = [0; 6];
         cur.read_exact(&mut b)?;
 
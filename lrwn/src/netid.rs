@@ -1,7 +1,13 @@
-use std::fmt;
-use std::str::FromStr;
+use core::fmt;
+use core::str::FromStr;
 
 use anyhow::Result;
+#[cfg(feature = "postgres")]
+use diesel::pg::Pg;
+#[cfg(feature = "sqlite")]
+use diesel::sqlite::Sqlite;
+#[cfg(feature = "diesel")]
+use diesel::{backend::Backend, deserialize, serialize, sql_types::Binary};
 #[cfg(feature = "serde")]
 use serde::{
     de::{self, Visitor},
@@ -10,8 +16,11 @@ use serde::{
 
 use crate::Error;
 use crate::devaddr::DevAddrPrefix;
+use crate::prelude::*;
 
 #[derive(Default, PartialEq, Clone, Copy, Hash, Eq)]
+#[cfg_attr(feature = "diesel", derive(AsExpression, FromSqlRow))]
+#[cfg_attr(feature = "diesel", diesel(sql_type = diesel::sql_types::Binary))]
 pub struct NetID([u8; 3]);
 
 impl NetID {
@@ -176,6 +185,49 @@ impl Visitor<'_> for NetIdVisitor {
     }
 }
 
+#[cfg(feature = "postgres")]
+impl deserialize::FromSql<Binary, Pg> for NetID {
+    fn from_sql(bytes: <Pg as Backend>::RawValue<'_>) -> deserialize::Result<Self> {
+        let value = <Vec<u8> as deserialize::FromSql<Binary, Pg>>::from_sql(bytes)?;
+        if value.len() != 3 {
+            return Err("NetID type expects exactly 3 bytes".into());
+        }
+
+        let mut b: [u8; 3] = [0; 3];
+        b.copy_from_slice(&value);
+        Ok(NetID(b))
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl serialize::ToSql<Binary, Pg> for NetID {
+    fn to_sql<'b>(&'b self, out: &mut serialize::Output<'b, '_, Pg>) -> serialize::Result {
+        <[u8] as serialize::ToSql<Binary, Pg>>::to_sql(&self.0, &mut out.reborrow())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl deserialize::FromSql<Binary, Sqlite> for NetID {
+    fn from_sql(bytes: <Sqlite as Backend>::RawValue<'_>) -> deserialize::Result<Self> {
+        let value = <Vec<u8> as deserialize::FromSql<Binary, Sqlite>>::from_sql(bytes)?;
+        if value.len() != 3 {
+            return Err("NetID type expects exactly 3 bytes".into());
+        }
+
+        let mut b: [u8; 3] = [0; 3];
+        b.copy_from_slice(&value);
+        Ok(NetID(b))
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl serialize::ToSql<Binary, Sqlite> for NetID {
+    fn to_sql<'b>(&'b self, out: &mut serialize::Output<'b, '_, Sqlite>) -> serialize::Result {
+        out.set_value(self.to_vec());
+        Ok(serialize::IsNull::No)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -11,6 +11,7 @@ use crate::fhdr::FHDR;
 use crate::maccommand::MACCommandSet;
 use crate::mhdr::FType;
 use crate::netid::NetID;
+use crate::prelude::*;
 use crate::relay::{ForwardDownlinkReq, ForwardUplinkReq};
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -419,7 +420,7 @@ impl RejoinRequestType1Payload {
 
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
+    use core::str::FromStr;
 
     use super::super::cflist::CFListChannels;
     use super::super::maccommand::MACCommand;
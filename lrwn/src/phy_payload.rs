@@ -20,6 +20,7 @@ use super::{
     payload::{JoinAcceptPayload, JoinType},
 };
 use crate::LA_FPORT_RELAY;
+use crate::prelude::*;
 use crate::relay::{ForwardDownlinkReq, ForwardUplinkReq};
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
@@ -33,7 +34,7 @@ pub enum MACVersion {
 ///
 /// Join-request example:
 /// ```rust
-/// use std::str::FromStr;
+/// use core::str::FromStr;
 /// use lrwn::*;
 ///
 /// let app_key = AES128Key::from_str("0102030405060708090a0b0c0d0e0f10").unwrap();
@@ -64,7 +65,7 @@ pub enum MACVersion {
 ///
 /// LoRaWAN 1.0.x Join-accept example:
 /// ```rust
-/// use std::str::FromStr;
+/// use core::str::FromStr;
 /// use lrwn::*;
 ///
 /// let app_key = AES128Key::from_str("0102030405060708090a0b0c0d0e0f10").unwrap();
@@ -126,7 +127,7 @@ pub enum MACVersion {
 ///
 /// LoRaWAN 1.1.x Join-accept example:
 /// ```rust
-/// use std::str::FromStr;
+/// use core::str::FromStr;
 /// use lrwn::*;
 ///
 /// let app_key = AES128Key::from_str("0102030405060708090a0b0c0d0e0f10").unwrap();
@@ -188,7 +189,7 @@ pub enum MACVersion {
 ///
 /// LoRaWAN 1.0.x confirmed uplink example:
 /// ```rust
-/// use std::str::FromStr;
+/// use core::str::FromStr;
 /// use lrwn::*;
 ///
 /// let nwk_s_key = AES128Key::from_str("0102030405060708090a0b0c0d0e0f10").unwrap();
@@ -241,7 +242,7 @@ pub enum MACVersion {
 ///
 /// LoRaWAN 1.1.x downlink with encrypted f_opts example:
 /// ```rust
-/// use std::str::FromStr;
+/// use core::str::FromStr;
 /// use lrwn::*;
 ///
 /// let s_nwk_s_int_key = AES128Key::from_str("01010101010101010101010101010100").unwrap();
@@ -304,7 +305,7 @@ pub enum MACVersion {
 ///
 /// Proprietary example:
 /// ```rust
-/// use std::str::FromStr;
+/// use core::str::FromStr;
 /// use lrwn::*;
 ///
 /// let phy = PhyPayload {
@@ -325,7 +326,7 @@ pub enum MACVersion {
 ///
 /// LoRaWAN 1.0.x Relay ForwardUplinkReq example:
 /// ```rust
-/// use std::str::FromStr;
+/// use core::str::FromStr;
 /// use lrwn::*;
 ///
 /// // Payload from the end-device.
@@ -411,7 +412,7 @@ pub enum MACVersion {
 ///
 /// LoRaWAN 1.0.x Relay ForwardDownlinkReq example:
 /// ```rust
-/// use std::str::FromStr;
+/// use core::str::FromStr;
 /// use lrwn::*;
 ///
 /// // Payload for the end-device.
@@ -926,70 +927,22 @@ impl PhyPayload {
         s_nwk_s_int_key: &AES128Key,
     ) -> Result<[u8; 4]> {
         if let Payload::MACPayload(pl) = &self.payload {
-            // set to 0 if the uplink does not contain an ACK
-            let mut conf_f_cnt = conf_f_cnt;
-            if !pl.fhdr.f_ctrl.ack {
-                conf_f_cnt = 0;
-            }
-
-            // truncate to 16 lsb
-            let conf_f_cnt = (conf_f_cnt % (1 << 16)) as u16;
-
             let mut mic_bytes = Vec::new();
             mic_bytes.extend_from_slice(&self.mhdr.to_le_bytes());
             mic_bytes.extend_from_slice(&self.payload.to_vec()?);
 
-            let mut b0: [u8; 16] = [0; 16];
-            let mut b1: [u8; 16] = [0; 16];
-
-            b0[0] = 0x49;
-            b1[0] = 0x49;
-
-            // devaddr
-            let devaddr_b = pl.fhdr.devaddr.to_le_bytes();
-            b0[6..10].clone_from_slice(&devaddr_b);
-            b1[6..10].clone_from_slice(&devaddr_b);
-
-            // fcntup
-            b0[10..14].clone_from_slice(&pl.fhdr.f_cnt.to_le_bytes());
-            b1[10..14].clone_from_slice(&pl.fhdr.f_cnt.to_le_bytes());
-
-            // msg len
-            b0[15] = mic_bytes.len() as u8;
-            b1[15] = mic_bytes.len() as u8;
-
-            // remaining b1 fields
-            b1[1..3].clone_from_slice(&conf_f_cnt.to_le_bytes());
-            b1[3] = tx_dr;
-            b1[4] = tx_ch;
-
-            let mut mac = Cmac::<Aes128>::new_from_slice(&s_nwk_s_int_key.to_bytes()).unwrap();
-            mac.update(&b1);
-            mac.update(&mic_bytes);
-
-            let cmac_s = mac.finalize().into_bytes();
-            if cmac_s.len() < 4 {
-                return Err(anyhow!("cmac_s is less than 4 bytes"));
-            }
-
-            let mut mac = Cmac::<Aes128>::new_from_slice(&f_nwk_s_int_key.to_bytes()).unwrap();
-            mac.update(&b0);
-            mac.update(&mic_bytes);
-
-            let cmac_f = mac.finalize().into_bytes();
-            if cmac_f.len() < 4 {
-                return Err(anyhow!("cmac_f is less than 4 bytes"));
-            }
-
-            let mut mic: [u8; 4] = [0; 4];
-            if mac_version == MACVersion::LoRaWAN1_0 {
-                mic.clone_from_slice(&cmac_f[0..4]);
-                return Ok(mic);
-            } else {
-                mic[0..2].clone_from_slice(&cmac_s[0..2]);
-                mic[2..4].clone_from_slice(&cmac_f[0..2]);
-                return Ok(mic);
-            }
+            return uplink_data_mic(
+                mac_version,
+                &mic_bytes,
+                &pl.fhdr.devaddr,
+                pl.fhdr.f_cnt,
+                pl.fhdr.f_ctrl.ack,
+                conf_f_cnt,
+                tx_dr,
+                tx_ch,
+                f_nwk_s_int_key,
+                s_nwk_s_int_key,
+            );
         }
 
         Err(anyhow!("payload must be of type MACPayload"))
@@ -1003,41 +956,19 @@ impl PhyPayload {
         s_nwk_s_int_key: &AES128Key,
     ) -> Result<[u8; 4]> {
         if let Payload::MACPayload(pl) = &self.payload {
-            // set to 0 if the downlink does not contain an ack or in case of LoRaWAN 1.0
-            let mut conf_f_cnt = conf_f_cnt;
-            if mac_version == MACVersion::LoRaWAN1_0 || !pl.fhdr.f_ctrl.ack {
-                conf_f_cnt = 0;
-            }
-
-            // truncate to 16 lsb
-            let conf_f_cnt = (conf_f_cnt % (1 << 16)) as u16;
-
-            // mic bytes
             let mut mic_bytes = Vec::new();
             mic_bytes.extend_from_slice(&self.mhdr.to_le_bytes());
             mic_bytes.extend_from_slice(&self.payload.to_vec()?);
 
-            // b0
-            let mut b0: [u8; 16] = [0; 16];
-            b0[0] = 0x49;
-            b0[1..3].clone_from_slice(&conf_f_cnt.to_le_bytes());
-            b0[5] = 0x01;
-            b0[6..10].clone_from_slice(&pl.fhdr.devaddr.to_le_bytes());
-            b0[10..14].clone_from_slice(&pl.fhdr.f_cnt.to_le_bytes());
-            b0[15] = mic_bytes.len() as u8;
-
-            let mut mac = Cmac::<Aes128>::new_from_slice(&s_nwk_s_int_key.to_bytes()).unwrap();
-            mac.update(&b0);
-            mac.update(&mic_bytes);
-
-            let hash = mac.finalize().into_bytes();
-            if hash.len() < 4 {
-                return Err(anyhow!("hash is less than 4 bytes"));
-            }
-
-            let mut mic: [u8; 4] = [0; 4];
-            mic.clone_from_slice(&hash[0..4]);
-            return Ok(mic);
+            return downlink_data_mic(
+                mac_version,
+                &mic_bytes,
+                &pl.fhdr.devaddr,
+                pl.fhdr.f_cnt,
+                pl.fhdr.f_ctrl.ack,
+                conf_f_cnt,
+                s_nwk_s_int_key,
+            );
         }
 
         Err(anyhow!("payload must be of type MACPayload"))
@@ -1045,23 +976,11 @@ impl PhyPayload {
 
     #[cfg(feature = "crypto")]
     fn calculate_upink_join_mic(&self, key: &AES128Key) -> Result<[u8; 4]> {
-        // mic bytes
         let mut mic_bytes = Vec::new();
-
         mic_bytes.extend_from_slice(&self.mhdr.to_le_bytes());
         mic_bytes.extend_from_slice(&self.payload.to_vec()?);
 
-        let mut mac = Cmac::<Aes128>::new_from_slice(&key.to_bytes()).unwrap();
-        mac.update(&mic_bytes);
-
-        let hash = mac.finalize().into_bytes();
-        if hash.len() < 4 {
-            return Err(anyhow!("hash is less than 4 bytes"));
-        }
-
-        let mut mic: [u8; 4] = [0; 4];
-        mic.clone_from_slice(&hash[0..4]);
-        Ok(mic)
+        uplink_join_mic(&mic_bytes, key)
     }
 
     #[cfg(feature = "crypto")]
@@ -1110,6 +1029,144 @@ impl PhyPayload {
     }
 }
 
+/// Calculate the uplink data MIC over the given MHDR | FHDR | FPort | FRMPayload bytes.
+/// The f_cnt must be the full 32 bit frame-counter.
+#[cfg(feature = "crypto")]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn uplink_data_mic(
+    mac_version: MACVersion,
+    mic_bytes: &[u8],
+    devaddr: &DevAddr,
+    f_cnt: u32,
+    ack: bool,
+    conf_f_cnt: u32,
+    tx_dr: u8,
+    tx_ch: u8,
+    f_nwk_s_int_key: &AES128Key,
+    s_nwk_s_int_key: &AES128Key,
+) -> Result<[u8; 4]> {
+    // set to 0 if the uplink does not contain an ACK
+    let mut conf_f_cnt = conf_f_cnt;
+    if !ack {
+        conf_f_cnt = 0;
+    }
+
+    // truncate to 16 lsb
+    let conf_f_cnt = (conf_f_cnt % (1 << 16)) as u16;
+
+    let mut b0: [u8; 16] = [0; 16];
+    let mut b1: [u8; 16] = [0; 16];
+
+    b0[0] = 0x49;
+    b1[0] = 0x49;
+
+    // devaddr
+    let devaddr_b = devaddr.to_le_bytes();
+    b0[6..10].clone_from_slice(&devaddr_b);
+    b1[6..10].clone_from_slice(&devaddr_b);
+
+    // fcntup
+    b0[10..14].clone_from_slice(&f_cnt.to_le_bytes());
+    b1[10..14].clone_from_slice(&f_cnt.to_le_bytes());
+
+    // msg len
+    b0[15] = mic_bytes.len() as u8;
+    b1[15] = mic_bytes.len() as u8;
+
+    // remaining b1 fields
+    b1[1..3].clone_from_slice(&conf_f_cnt.to_le_bytes());
+    b1[3] = tx_dr;
+    b1[4] = tx_ch;
+
+    let mut mac = Cmac::<Aes128>::new_from_slice(&s_nwk_s_int_key.to_bytes()).unwrap();
+    mac.update(&b1);
+    mac.update(mic_bytes);
+
+    let cmac_s = mac.finalize().into_bytes();
+    if cmac_s.len() < 4 {
+        return Err(anyhow!("cmac_s is less than 4 bytes"));
+    }
+
+    let mut mac = Cmac::<Aes128>::new_from_slice(&f_nwk_s_int_key.to_bytes()).unwrap();
+    mac.update(&b0);
+    mac.update(mic_bytes);
+
+    let cmac_f = mac.finalize().into_bytes();
+    if cmac_f.len() < 4 {
+        return Err(anyhow!("cmac_f is less than 4 bytes"));
+    }
+
+    let mut mic: [u8; 4] = [0; 4];
+    if mac_version == MACVersion::LoRaWAN1_0 {
+        mic.clone_from_slice(&cmac_f[0..4]);
+    } else {
+        mic[0..2].clone_from_slice(&cmac_s[0..2]);
+        mic[2..4].clone_from_slice(&cmac_f[0..2]);
+    }
+
+    Ok(mic)
+}
+
+/// Calculate the downlink data MIC over the given MHDR | FHDR | FPort | FRMPayload bytes.
+/// The f_cnt must be the full 32 bit frame-counter.
+#[cfg(feature = "crypto")]
+pub(crate) fn downlink_data_mic(
+    mac_version: MACVersion,
+    mic_bytes: &[u8],
+    devaddr: &DevAddr,
+    f_cnt: u32,
+    ack: bool,
+    conf_f_cnt: u32,
+    s_nwk_s_int_key: &AES128Key,
+) -> Result<[u8; 4]> {
+    // set to 0 if the downlink does not contain an ack or in case of LoRaWAN 1.0
+    let mut conf_f_cnt = conf_f_cnt;
+    if mac_version == MACVersion::LoRaWAN1_0 || !ack {
+        conf_f_cnt = 0;
+    }
+
+    // truncate to 16 lsb
+    let conf_f_cnt = (conf_f_cnt % (1 << 16)) as u16;
+
+    // b0
+    let mut b0: [u8; 16] = [0; 16];
+    b0[0] = 0x49;
+    b0[1..3].clone_from_slice(&conf_f_cnt.to_le_bytes());
+    b0[5] = 0x01;
+    b0[6..10].clone_from_slice(&devaddr.to_le_bytes());
+    b0[10..14].clone_from_slice(&f_cnt.to_le_bytes());
+    b0[15] = mic_bytes.len() as u8;
+
+    let mut mac = Cmac::<Aes128>::new_from_slice(&s_nwk_s_int_key.to_bytes()).unwrap();
+    mac.update(&b0);
+    mac.update(mic_bytes);
+
+    let hash = mac.finalize().into_bytes();
+    if hash.len() < 4 {
+        return Err(anyhow!("hash is less than 4 bytes"));
+    }
+
+    let mut mic: [u8; 4] = [0; 4];
+    mic.clone_from_slice(&hash[0..4]);
+    Ok(mic)
+}
+
+/// Calculate the join-request or rejoin-request MIC over the given MHDR | Payload bytes.
+#[cfg(feature = "crypto")]
+pub(crate) fn uplink_join_mic(mic_bytes: &[u8], key: &AES128Key) -> Result<[u8; 4]> {
+    let mut mac = Cmac::<Aes128>::new_from_slice(&key.to_bytes()).unwrap();
+    mac.update(mic_bytes);
+
+    let hash = mac.finalize().into_bytes();
+    if hash.len() < 4 {
+        return Err(anyhow!("hash is less than 4 bytes"));
+    }
+
+    let mut mic: [u8; 4] = [0; 4];
+    mic.clone_from_slice(&hash[0..4]);
+    Ok(mic)
+}
+
 /// Encrypt f_opts mac-command data.
 /// For uplink:
 ///   Set the a_fcnt_down to false and use the f_cnt_up as f_cnt.
@@ -1243,7 +1300,7 @@ fn decode_frm_payload(pl: &mut MACPayload, uplink: bool, f_port: u8, b: Vec<u8>)
 
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
+    use core::str::FromStr;
 
     use super::super::eui64::EUI64;
     use super::super::mhdr::Major;
@@ -0,0 +1,524 @@
+use anyhow::Result;
+
+use super::devaddr::DevAddr;
+use super::eui64::EUI64;
+use super::fhdr::FCtrl;
+use super::mhdr::{FType, MHDR};
+use super::phy_payload::PhyPayload;
+#[cfg(feature = "crypto")]
+use super::{
+    aes128::AES128Key,
+    phy_payload::{MACVersion, downlink_data_mic, uplink_data_mic, uplink_join_mic},
+};
+
+/// PhyPayloadRef is a borrowed view on an encoded PhyPayload.
+///
+/// Unlike PhyPayload, it does not decode (and allocate) the payload on creation. The
+/// from_slice function only validates that the frame is structurally valid, the fields are
+/// decoded on access. This makes it suitable for hot paths in which only a few fields are
+/// needed, or in which the MIC must be validated against multiple keys or frame-counters.
+///
+/// Example:
+///
+/// ```rust
+/// use lrwn::*;
+///
+/// let bytes = vec![
+///     0x40, 0x04, 0x03, 0x02, 0x01, 0x80, 0x01, 0x00, 0x01, 0xa6, 0x94, 0x64, 0x26, 0x15, 0xd6,
+///     0xc3, 0xb5, 0x82,
+/// ];
+/// let phy = PhyPayloadRef::from_slice(&bytes).unwrap();
+///
+/// assert_eq!(FType::UnconfirmedDataUp, phy.mhdr().f_type);
+/// assert_eq!(Some(DevAddr::from_be_bytes([0x01, 0x02, 0x03, 0x04])), phy.dev_addr());
+/// assert_eq!(Some(1), phy.f_cnt());
+/// assert_eq!(Some(1), phy.f_port());
+/// assert_eq!(Some(&[0xa6, 0x94, 0x64, 0x26, 0x15][..]), phy.frm_payload());
+/// assert_eq!(Some([0xd6, 0xc3, 0xb5, 0x82]), phy.mic());
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PhyPayloadRef<'a> {
+    b: &'a [u8],
+    mhdr: MHDR,
+}
+
+impl<'a> PhyPayloadRef<'a> {
+    pub fn from_slice(b: &'a [u8]) -> Result<Self> {
+        let b_len = b.len();
+
+        // We need 1 byte to decode the mhdr.
+        if b_len == 0 {
+            return Err(anyhow!("at least 1 byte required to decode PhyPayload"));
+        }
+
+        let mhdr = MHDR::from_le_bytes([b[0]])?;
+
+        if mhdr.f_type == FType::Proprietary {
+            return Ok(PhyPayloadRef { b, mhdr });
+        }
+
+        // Validate the minimum required bytes for not running into slicing errors.
+        if b_len < 5 {
+            return Err(anyhow!(
+                "at least 5 bytes are required to decode PhyPayload"
+            ));
+        }
+
+        match mhdr.f_type {
+            FType::UnconfirmedDataUp
+            | FType::UnconfirmedDataDown
+            | FType::ConfirmedDataUp
+            | FType::ConfirmedDataDown => {
+                // MHDR | DevAddr | FCtrl | FCnt | MIC
+                if b_len < 12 {
+                    return Err(anyhow!(
+                        "at least 12 bytes are required to decode a data PhyPayload"
+                    ));
+                }
+
+                let f_opts_len = (b[5] & 0x0f) as usize;
+                if b_len < 12 + f_opts_len {
+                    return Err(anyhow!("not enough bytes to decode FHDR"));
+                }
+            }
+            FType::JoinRequest => {
+                if b_len != 23 {
+                    return Err(anyhow!(
+                        "23 bytes are expected for a JoinRequest PhyPayload"
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        Ok(PhyPayloadRef { b, mhdr })
+    }
+
+    /// Returns the underlying bytes.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.b
+    }
+
+    /// Decodes the borrowed bytes into a PhyPayload.
+    pub fn to_phy_payload(&self) -> Result<PhyPayload> {
+        PhyPayload::from_slice(self.b)
+    }
+
+    pub fn mhdr(&self) -> &MHDR {
+        &self.mhdr
+    }
+
+    /// Returns the payload bytes (the bytes between the MHDR and MIC).
+    pub fn payload(&self) -> &'a [u8] {
+        if self.mhdr.f_type == FType::Proprietary {
+            &self.b[1..]
+        } else {
+            &self.b[1..self.b.len() - 4]
+        }
+    }
+
+    /// Returns the MIC. This returns None in case of the proprietary message-type.
+    pub fn mic(&self) -> Option<[u8; 4]> {
+        if self.mhdr.f_type == FType::Proprietary {
+            return None;
+        }
+
+        let mut mic: [u8; 4] = [0; 4];
+        mic.copy_from_slice(&self.b[self.b.len() - 4..]);
+        Some(mic)
+    }
+
+    /// Returns the DevAddr in case of a data frame.
+    pub fn dev_addr(&self) -> Option<DevAddr> {
+        let pl = self.mac_payload()?;
+        let mut devaddr: [u8; 4] = [0; 4];
+        devaddr.copy_from_slice(&pl[0..4]);
+        Some(DevAddr::from_le_bytes(devaddr))
+    }
+
+    /// Returns the JoinEUI in case of a join-request.
+    pub fn join_eui(&self) -> Option<EUI64> {
+        let pl = self.join_request_payload()?;
+        let mut join_eui: [u8; 8] = [0; 8];
+        join_eui.copy_from_slice(&pl[0..8]);
+        Some(EUI64::from_le_bytes(join_eui))
+    }
+
+    /// Returns the DevEUI in case of a join-request.
+    pub fn dev_eui(&self) -> Option<EUI64> {
+        let pl = self.join_request_payload()?;
+        let mut dev_eui: [u8; 8] = [0; 8];
+        dev_eui.copy_from_slice(&pl[8..16]);
+        Some(EUI64::from_le_bytes(dev_eui))
+    }
+
+    /// Returns the FCtrl in case of a data frame.
+    pub fn f_ctrl(&self) -> Option<FCtrl> {
+        let pl = self.mac_payload()?;
+        Some(FCtrl::from_le_bytes([pl[4]]))
+    }
+
+    /// Returns the FCnt in case of a data frame. Note that only the 16 lsb are transmitted.
+    pub fn f_cnt(&self) -> Option<u32> {
+        let pl = self.mac_payload()?;
+        Some(u16::from_le_bytes([pl[5], pl[6]]).into())
+    }
+
+    /// Returns the (encrypted in case of LoRaWAN 1.1) FOpts bytes in case of a data frame.
+    pub fn f_opts(&self) -> Option<&'a [u8]> {
+        let pl = self.mac_payload()?;
+        Some(&pl[7..self.fhdr_len()?])
+    }
+
+    /// Returns the FPort in case of a data frame containing a FPort.
+    pub fn f_port(&self) -> Option<u8> {
+        let pl = self.mac_payload()?;
+        pl.get(self.fhdr_len()?).cloned()
+    }
+
+    /// Returns the (encrypted) FRMPayload bytes in case of a data frame containing a
+    /// FRMPayload.
+    pub fn frm_payload(&self) -> Option<&'a [u8]> {
+        let pl = self.mac_payload()?;
+        let fhdr_len = self.fhdr_len()?;
+
+        if pl.len() > fhdr_len + 1 {
+            Some(&pl[fhdr_len + 1..])
+        } else {
+            None
+        }
+    }
+
+    /// Validate the MIC of an uplink data frame.
+    /// As only the 16 lsb of the frame-counter are transmitted over the air, the full 32 bit
+    /// frame-counter must be given.
+    /// The conf_f_cnt, tx_dr, tx_ch and s_nwk_s_int_key are only required for LoRaWAN 1.1 and can
+    /// be left blank for LoRaWAN 1.0.
+    #[cfg(feature = "crypto")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn validate_uplink_data_mic(
+        &self,
+        mac_version: MACVersion,
+        f_cnt: u32,
+        conf_f_cnt: u32,
+        tx_dr: u8,
+        tx_ch: u8,
+        f_nwk_s_int_key: &AES128Key,
+        s_nwk_s_int_key: &AES128Key,
+    ) -> Result<bool> {
+        let (dev_addr, f_ctrl, mic) = self.data_mic_fields()?;
+
+        Ok(mic
+            == uplink_data_mic(
+                mac_version,
+                &self.b[..self.b.len() - 4],
+                &dev_addr,
+                f_cnt,
+                f_ctrl.ack,
+                conf_f_cnt,
+                tx_dr,
+                tx_ch,
+                f_nwk_s_int_key,
+                s_nwk_s_int_key,
+            )?)
+    }
+
+    /// Validate the cmacF part of the uplink data MIC (LoRaWAN 1.1 only).
+    /// As only the 16 lsb of the frame-counter are transmitted over the air, the full 32 bit
+    /// frame-counter must be given.
+    #[cfg(feature = "crypto")]
+    pub fn validate_uplink_data_micf(
+        &self,
+        f_cnt: u32,
+        f_nwk_s_int_key: &AES128Key,
+    ) -> Result<bool> {
+        let (dev_addr, f_ctrl, mic) = self.data_mic_fields()?;
+
+        // We are only interested in mic[2:] (cmacF bytes), therefore there is no
+        // need to pass the correct confFCnt, txDR, txCh and sNwkSIntKey parameters.
+        let calculated = uplink_data_mic(
+            MACVersion::LoRaWAN1_1,
+            &self.b[..self.b.len() - 4],
+            &dev_addr,
+            f_cnt,
+            f_ctrl.ack,
+            0,
+            0,
+            0,
+            f_nwk_s_int_key,
+            f_nwk_s_int_key,
+        )?;
+
+        Ok(mic[2..] == calculated[2..])
+    }
+
+    /// Validate the MIC of a downlink data frame.
+    /// The conf_f_cnt is only required for LoRaWAN 1.1 and can be left blank for LoRaWAN 1.0.
+    #[cfg(feature = "crypto")]
+    pub fn validate_downlink_data_mic(
+        &self,
+        mac_version: MACVersion,
+        f_cnt: u32,
+        conf_f_cnt: u32,
+        s_nwk_s_int_key: &AES128Key,
+    ) -> Result<bool> {
+        let (dev_addr, f_ctrl, mic) = self.data_mic_fields()?;
+
+        Ok(mic
+            == downlink_data_mic(
+                mac_version,
+                &self.b[..self.b.len() - 4],
+                &dev_addr,
+                f_cnt,
+                f_ctrl.ack,
+                conf_f_cnt,
+                s_nwk_s_int_key,
+            )?)
+    }
+
+    /// Validate the join-request MIC.
+    #[cfg(feature = "crypto")]
+    pub fn validate_join_request_mic(&self, key: &AES128Key) -> Result<bool> {
+        if self.mhdr.f_type != FType::JoinRequest {
+            return Err(anyhow!("payload must be of type JoinRequestPayload"));
+        }
+
+        match self.mic() {
+            Some(mic) => Ok(mic == uplink_join_mic(&self.b[..self.b.len() - 4], key)?),
+            None => Ok(false),
+        }
+    }
+
+    // Returns the MACPayload bytes in case of a data frame.
+    fn mac_payload(&self) -> Option<&'a [u8]> {
+        match self.mhdr.f_type {
+            FType::UnconfirmedDataUp
+            | FType::UnconfirmedDataDown
+            | FType::ConfirmedDataUp
+            | FType::ConfirmedDataDown => Some(self.payload()),
+            _ => None,
+        }
+    }
+
+    // Returns the JoinRequestPayload bytes in case of a join-request.
+    fn join_request_payload(&self) -> Option<&'a [u8]> {
+        match self.mhdr.f_type {
+            FType::JoinRequest => Some(self.payload()),
+            _ => None,
+        }
+    }
+
+    // Returns the length of the FHDR (DevAddr | FCtrl | FCnt | FOpts).
+    fn fhdr_len(&self) -> Option<usize> {
+        Some(7 + self.f_ctrl()?.f_opts_len as usize)
+    }
+
+    #[cfg(feature = "crypto")]
+    fn data_mic_fields(&self) -> Result<(DevAddr, FCtrl, [u8; 4])> {
+        match (self.dev_addr(), self.f_ctrl(), self.mic()) {
+            (Some(dev_addr), Some(f_ctrl), Some(mic)) => Ok((dev_addr, f_ctrl, mic)),
+            _ => Err(anyhow!("payload must be of type MACPayload")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prelude::*;
+    use crate::{FHDR, FRMPayload, MACPayload, Major, Payload};
+
+    fn data_up(f_cnt: u32, f_opts: Vec<u8>, f_port: Option<u8>, data: Vec<u8>) -> PhyPayload {
+        PhyPayload {
+            mhdr: MHDR {
+                f_type: FType::ConfirmedDataUp,
+                major: Major::LoRaWANR1,
+            },
+            payload: Payload::MACPayload(MACPayload {
+                fhdr: FHDR {
+                    devaddr: DevAddr::from_be_bytes([0x01, 0x02, 0x03, 0x04]),
+                    f_ctrl: FCtrl {
+                        adr: true,
+                        ack: true,
+                        ..Default::default()
+                    },
+                    f_cnt,
+                    f_opts: crate::MACCommandSet::new(vec![crate::MACCommand::Raw(f_opts)]),
+                },
+                f_port,
+                frm_payload: if data.is_empty() {
+                    None
+                } else {
+                    Some(FRMPayload::Raw(data))
+                },
+            }),
+            mic: Some([0x01, 0x02, 0x03, 0x04]),
+        }
+    }
+
+    #[test]
+    fn test_from_slice() {
+        assert!(PhyPayloadRef::from_slice(&[]).is_err());
+
+        // Proprietary.
+        let phy = PhyPayloadRef::from_slice(&[0xe0, 0x01, 0x02]).unwrap();
+        assert_eq!(FType::Proprietary, phy.mhdr().f_type);
+        assert_eq!(&[0x01, 0x02], phy.payload());
+        assert_eq!(None, phy.mic());
+        assert_eq!(None, phy.dev_addr());
+        assert_eq!(None, phy.join_eui());
+
+        // Data frame without FOpts and FPort.
+        let b = data_up(10, vec![], None, vec![]).to_vec().unwrap();
+        let phy = PhyPayloadRef::from_slice(&b).unwrap();
+        assert_eq!(FType::ConfirmedDataUp, phy.mhdr().f_type);
+        assert_eq!(
+            Some(DevAddr::from_be_bytes([0x01, 0x02, 0x03, 0x04])),
+            phy.dev_addr()
+        );
+        assert_eq!(Some(10), phy.f_cnt());
+        assert_eq!(Some(&[][..]), phy.f_opts());
+        assert_eq!(None, phy.f_port());
+        assert_eq!(None, phy.frm_payload());
+        assert_eq!(Some([0x01, 0x02, 0x03, 0x04]), phy.mic());
+
+        // Data frame with FOpts, FPort and FRMPayload.
+        let b = data_up(65537, vec![0x02], Some(10), vec![0x01, 0x02, 0x03])
+            .to_vec()
+            .unwrap();
+        let phy = PhyPayloadRef::from_slice(&b).unwrap();
+        let f_ctrl = phy.f_ctrl().unwrap();
+        assert!(f_ctrl.adr);
+        assert!(f_ctrl.ack);
+        assert_eq!(1, f_ctrl.f_opts_len);
+        assert_eq!(Some(1), phy.f_cnt());
+        assert_eq!(Some(&[0x02][..]), phy.f_opts());
+        assert_eq!(Some(10), phy.f_port());
+        assert_eq!(Some(&[0x01, 0x02, 0x03][..]), phy.frm_payload());
+        assert_eq!(
+            PhyPayload::from_slice(&b).unwrap(),
+            phy.to_phy_payload().unwrap()
+        );
+
+        // FOptsLen exceeds the available bytes.
+        let mut b = data_up(10, vec![], None, vec![]).to_vec().unwrap();
+        b[5] |= 0x02;
+        assert!(PhyPayloadRef::from_slice(&b).is_err());
+
+        // Too short.
+        assert!(PhyPayloadRef::from_slice(&[0x40, 0x01, 0x02, 0x03, 0x04]).is_err());
+    }
+
+    #[test]
+    fn test_validate_uplink_data_mic() {
+        let f_nwk_s_int_key = AES128Key::from_bytes([1; 16]);
+        let s_nwk_s_int_key = AES128Key::from_bytes([2; 16]);
+
+        for mac_version in [MACVersion::LoRaWAN1_0, MACVersion::LoRaWAN1_1] {
+            let mut phy = data_up(65537, vec![], Some(10), vec![0x01, 0x02, 0x03]);
+            phy.set_uplink_data_mic(mac_version, 5, 3, 2, &f_nwk_s_int_key, &s_nwk_s_int_key)
+                .unwrap();
+            let b = phy.to_vec().unwrap();
+
+            let phy_ref = PhyPayloadRef::from_slice(&b).unwrap();
+            assert!(
+                phy_ref
+                    .validate_uplink_data_mic(
+                        mac_version,
+                        65537,
+                        5,
+                        3,
+                        2,
+                        &f_nwk_s_int_key,
+                        &s_nwk_s_int_key
+                    )
+                    .unwrap()
+            );
+            if mac_version == MACVersion::LoRaWAN1_1 {
+                assert!(
+                    phy_ref
+                        .validate_uplink_data_micf(65537, &f_nwk_s_int_key)
+                        .unwrap()
+                );
+            }
+
+            // Only the 16 lsb of the frame-counter are transmitted.
+            assert!(
+                !phy_ref
+                    .validate_uplink_data_mic(
+                        mac_version,
+                        1,
+                        5,
+                        3,
+                        2,
+                        &f_nwk_s_int_key,
+                        &s_nwk_s_int_key
+                    )
+                    .unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_downlink_data_mic() {
+        let s_nwk_s_int_key = AES128Key::from_bytes([2; 16]);
+
+        let mut phy = data_up(12, vec![], Some(10), vec![0x01]);
+        phy.mhdr.f_type = FType::UnconfirmedDataDown;
+        phy.set_downlink_data_mic(MACVersion::LoRaWAN1_1, 3, &s_nwk_s_int_key)
+            .unwrap();
+        let b = phy.to_vec().unwrap();
+
+        let phy_ref = PhyPayloadRef::from_slice(&b).unwrap();
+        assert!(
+            phy_ref
+                .validate_downlink_data_mic(MACVersion::LoRaWAN1_1, 12, 3, &s_nwk_s_int_key)
+                .unwrap()
+        );
+        assert!(
+            !phy_ref
+                .validate_downlink_data_mic(MACVersion::LoRaWAN1_1, 12, 4, &s_nwk_s_int_key)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_validate_join_request_mic() {
+        let key = AES128Key::from_bytes([3; 16]);
+
+        let mut phy = PhyPayload {
+            mhdr: MHDR {
+                f_type: FType::JoinRequest,
+                major: Major::LoRaWANR1,
+            },
+            payload: Payload::JoinRequest(crate::JoinRequestPayload {
+                join_eui: crate::EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8]),
+                dev_eui: crate::EUI64::from_be_bytes([8, 7, 6, 5, 4, 3, 2, 1]),
+                dev_nonce: 258,
+            }),
+            mic: None,
+        };
+        phy.set_join_request_mic(&key).unwrap();
+        let b = phy.to_vec().unwrap();
+
+        let phy_ref = PhyPayloadRef::from_slice(&b).unwrap();
+        assert!(phy_ref.validate_join_request_mic(&key).unwrap());
+        assert!(
+            !phy_ref
+                .validate_join_request_mic(&AES128Key::from_bytes([4; 16]))
+                .unwrap()
+        );
+        assert_eq!(None, phy_ref.dev_addr());
+        assert_eq!(
+            Some(crate::EUI64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8])),
+            phy_ref.join_eui()
+        );
+        assert_eq!(
+            Some(crate::EUI64::from_be_bytes([8, 7, 6, 5, 4, 3, 2, 1])),
+            phy_ref.dev_eui()
+        );
+
+        // Invalid join-request length.
+        assert!(PhyPayloadRef::from_slice(&b[..22]).is_err());
+    }
+}
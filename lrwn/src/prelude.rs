@@ -0,0 +1,8 @@
+// Items which are part of the std prelude, but must be imported explicitly from
+// alloc when compiling without the std feature.
+pub use alloc::boxed::Box;
+#[cfg(feature = "regions")]
+pub use alloc::string::String;
+pub use alloc::string::ToString;
+pub use alloc::vec;
+pub use alloc::vec::Vec;
@@ -1,11 +1,13 @@
-use std::time::Duration;
+use core::time::Duration;
 
 use anyhow::Result;
 
 use super::{
     Channel, CommonName, DataRate, DataRateModulation, Defaults, FskDataRate, LinkADRReqPayload,
-    LoraDataRate, MacVersion, MaxPayloadSize, Region, RegionBaseConfig, Revision,
+    LoraDataRate, LrFhssDataRate, MacVersion, MaxPayloadSize, Region, RegionBaseConfig, Revision,
+    params,
 };
+use crate::prelude::*;
 use crate::{CFList, DevAddr};
 
 pub struct Configuration {
@@ -125,6 +127,50 @@ impl Configuration {
                             modulation: DataRateModulation::Fsk(FskDataRate { bitrate: 50000 }),
                         },
                     ),
+                    (
+                        8,
+                        DataRate {
+                            uplink: true,
+                            downlink: false,
+                            modulation: DataRateModulation::LrFhss(LrFhssDataRate {
+                                coding_rate: "2/6".to_string(),
+                                occupied_channel_width: 137000,
+                            }),
+                        },
+                    ),
+                    (
+                        9,
+                        DataRate {
+                            uplink: true,
+                            downlink: false,
+                            modulation: DataRateModulation::LrFhss(LrFhssDataRate {
+                                coding_rate: "4/6".to_string(),
+                                occupied_channel_width: 137000,
+                            }),
+                        },
+                    ),
+                    (
+                        10,
+                        DataRate {
+                            uplink: true,
+                            downlink: false,
+                            modulation: DataRateModulation::LrFhss(LrFhssDataRate {
+                                coding_rate: "2/6".to_string(),
+                                occupied_channel_width: 336000,
+                            }),
+                        },
+                    ),
+                    (
+                        11,
+                        DataRate {
+                            uplink: true,
+                            downlink: false,
+                            modulation: DataRateModulation::LrFhss(LrFhssDataRate {
+                                coding_rate: "4/6".to_string(),
+                                occupied_channel_width: 336000,
+                            }),
+                        },
+                    ),
                     (
                         12,
                         DataRate {
@@ -150,724 +196,11 @@ impl Configuration {
                         },
                     ),
                 ],
-                max_dl_payload_size_per_dr: match repeater_compatible {
-                    true => match dwell_time_400ms {
-                        // repeater + dwell time
-                        true => [
-                            (
-                                MacVersion::LORAWAN_1_0_2,
-                                [(
-                                    Revision::Latest, // B
-                                    [
-                                        (0, MaxPayloadSize { m: 0, n: 0 }),
-                                        (1, MaxPayloadSize { m: 0, n: 0 }),
-                                        (2, MaxPayloadSize { m: 19, n: 11 }),
-                                        (3, MaxPayloadSize { m: 61, n: 53 }),
-                                        (4, MaxPayloadSize { m: 133, n: 125 }),
-                                        (5, MaxPayloadSize { m: 250, n: 242 }),
-                                        (6, MaxPayloadSize { m: 250, n: 242 }),
-                                        (7, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                )]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            ),
-                            (
-                                MacVersion::LORAWAN_1_0_3,
-                                [(
-                                    Revision::Latest, // A
-                                    [
-                                        (0, MaxPayloadSize { m: 0, n: 0 }),
-                                        (1, MaxPayloadSize { m: 0, n: 0 }),
-                                        (2, MaxPayloadSize { m: 19, n: 11 }),
-                                        (3, MaxPayloadSize { m: 61, n: 53 }),
-                                        (4, MaxPayloadSize { m: 133, n: 125 }),
-                                        (5, MaxPayloadSize { m: 250, n: 242 }),
-                                        (6, MaxPayloadSize { m: 250, n: 242 }),
-                                        (7, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                )]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            ),
-                            (
-                                MacVersion::LORAWAN_1_1_0,
-                                [(
-                                    Revision::Latest, // A
-                                    [
-                                        (0, MaxPayloadSize { m: 0, n: 0 }),
-                                        (1, MaxPayloadSize { m: 0, n: 0 }),
-                                        (2, MaxPayloadSize { m: 19, n: 11 }),
-                                        (3, MaxPayloadSize { m: 61, n: 53 }),
-                                        (4, MaxPayloadSize { m: 133, n: 125 }),
-                                        (5, MaxPayloadSize { m: 250, n: 242 }),
-                                        (6, MaxPayloadSize { m: 250, n: 242 }),
-                                        (7, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                )]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            ),
-                            (
-                                MacVersion::Latest,
-                                [
-                                    (
-                                        Revision::RP002_1_0_0, // RP002-1.0.0
-                                        [
-                                            (0, MaxPayloadSize { m: 0, n: 0 }),
-                                            (1, MaxPayloadSize { m: 0, n: 0 }),
-                                            (2, MaxPayloadSize { m: 19, n: 11 }),
-                                            (3, MaxPayloadSize { m: 61, n: 53 }),
-                                            (4, MaxPayloadSize { m: 133, n: 125 }),
-                                            (5, MaxPayloadSize { m: 230, n: 222 }),
-                                            (6, MaxPayloadSize { m: 230, n: 222 }),
-                                            (7, MaxPayloadSize { m: 230, n: 222 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::RP002_1_0_1, // RP002-1.0.1
-                                        [
-                                            (0, MaxPayloadSize { m: 0, n: 0 }),
-                                            (1, MaxPayloadSize { m: 0, n: 0 }),
-                                            (2, MaxPayloadSize { m: 19, n: 11 }),
-                                            (3, MaxPayloadSize { m: 61, n: 53 }),
-                                            (4, MaxPayloadSize { m: 133, n: 125 }),
-                                            (5, MaxPayloadSize { m: 230, n: 222 }),
-                                            (6, MaxPayloadSize { m: 230, n: 222 }),
-                                            (7, MaxPayloadSize { m: 230, n: 222 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::RP002_1_0_2, // RP002-1.0.2
-                                        [
-                                            (0, MaxPayloadSize { m: 0, n: 0 }),
-                                            (1, MaxPayloadSize { m: 0, n: 0 }),
-                                            (2, MaxPayloadSize { m: 19, n: 11 }),
-                                            (3, MaxPayloadSize { m: 61, n: 53 }),
-                                            (4, MaxPayloadSize { m: 133, n: 125 }),
-                                            (5, MaxPayloadSize { m: 230, n: 222 }),
-                                            (6, MaxPayloadSize { m: 230, n: 222 }),
-                                            (7, MaxPayloadSize { m: 230, n: 222 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::RP002_1_0_3,
-                                        [
-                                            (0, MaxPayloadSize { m: 0, n: 0 }),
-                                            (1, MaxPayloadSize { m: 0, n: 0 }),
-                                            (2, MaxPayloadSize { m: 19, n: 11 }),
-                                            (3, MaxPayloadSize { m: 61, n: 53 }),
-                                            (4, MaxPayloadSize { m: 133, n: 125 }),
-                                            (5, MaxPayloadSize { m: 230, n: 222 }),
-                                            (6, MaxPayloadSize { m: 230, n: 222 }),
-                                            (7, MaxPayloadSize { m: 230, n: 222 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::RP002_1_0_4,
-                                        [
-                                            (0, MaxPayloadSize { m: 0, n: 0 }),
-                                            (1, MaxPayloadSize { m: 0, n: 0 }),
-                                            (2, MaxPayloadSize { m: 19, n: 11 }),
-                                            (3, MaxPayloadSize { m: 61, n: 53 }),
-                                            (4, MaxPayloadSize { m: 133, n: 125 }),
-                                            (5, MaxPayloadSize { m: 230, n: 222 }),
-                                            (6, MaxPayloadSize { m: 230, n: 222 }),
-                                            (7, MaxPayloadSize { m: 230, n: 222 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::Latest, // RP002-1.0.5
-                                        [
-                                            (0, MaxPayloadSize { m: 0, n: 0 }),
-                                            (1, MaxPayloadSize { m: 0, n: 0 }),
-                                            (2, MaxPayloadSize { m: 19, n: 11 }),
-                                            (3, MaxPayloadSize { m: 61, n: 53 }),
-                                            (4, MaxPayloadSize { m: 133, n: 125 }),
-                                            (5, MaxPayloadSize { m: 230, n: 222 }),
-                                            (6, MaxPayloadSize { m: 230, n: 222 }),
-                                            (7, MaxPayloadSize { m: 230, n: 222 }),
-                                            (12, MaxPayloadSize { m: 230, n: 222 }),
-                                            (13, MaxPayloadSize { m: 230, n: 222 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            ),
-                        ]
-                        .iter()
-                        .cloned()
-                        .collect(),
-                        // repeater + no dwell time
-                        false => [
-                            (
-                                MacVersion::LORAWAN_1_0_2,
-                                [(
-                                    Revision::Latest, // B
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 230, n: 222 }),
-                                        (5, MaxPayloadSize { m: 230, n: 222 }),
-                                        (6, MaxPayloadSize { m: 230, n: 222 }),
-                                        (7, MaxPayloadSize { m: 230, n: 222 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                )]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            ),
-                            (
-                                MacVersion::LORAWAN_1_0_3,
-                                [(
-                                    Revision::Latest, // A
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 230, n: 222 }),
-                                        (5, MaxPayloadSize { m: 230, n: 222 }),
-                                        (6, MaxPayloadSize { m: 230, n: 222 }),
-                                        (7, MaxPayloadSize { m: 230, n: 222 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                )]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            ),
-                            (
-                                MacVersion::LORAWAN_1_1_0,
-                                [(
-                                    Revision::Latest, // A
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 230, n: 222 }),
-                                        (5, MaxPayloadSize { m: 230, n: 222 }),
-                                        (6, MaxPayloadSize { m: 230, n: 222 }),
-                                        (7, MaxPayloadSize { m: 230, n: 222 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                )]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            ),
-                            (
-                                MacVersion::Latest,
-                                [
-                                    (
-                                        Revision::RP002_1_0_0, // RP002-1.0.0
-                                        [
-                                            (0, MaxPayloadSize { m: 59, n: 51 }),
-                                            (1, MaxPayloadSize { m: 59, n: 51 }),
-                                            (2, MaxPayloadSize { m: 59, n: 51 }),
-                                            (3, MaxPayloadSize { m: 123, n: 115 }),
-                                            (4, MaxPayloadSize { m: 230, n: 222 }),
-                                            (5, MaxPayloadSize { m: 230, n: 222 }),
-                                            (6, MaxPayloadSize { m: 230, n: 222 }),
-                                            (7, MaxPayloadSize { m: 230, n: 222 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::RP002_1_0_1, // RP002-1.0.1
-                                        [
-                                            (0, MaxPayloadSize { m: 59, n: 51 }),
-                                            (1, MaxPayloadSize { m: 59, n: 51 }),
-                                            (2, MaxPayloadSize { m: 123, n: 115 }),
-                                            (3, MaxPayloadSize { m: 123, n: 115 }),
-                                            (4, MaxPayloadSize { m: 230, n: 222 }),
-                                            (5, MaxPayloadSize { m: 230, n: 222 }),
-                                            (6, MaxPayloadSize { m: 230, n: 222 }),
-                                            (7, MaxPayloadSize { m: 230, n: 222 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::RP002_1_0_2, // RP002-1.0.2
-                                        [
-                                            (0, MaxPayloadSize { m: 59, n: 51 }),
-                                            (1, MaxPayloadSize { m: 59, n: 51 }),
-                                            (2, MaxPayloadSize { m: 123, n: 115 }),
-                                            (3, MaxPayloadSize { m: 123, n: 115 }),
-                                            (4, MaxPayloadSize { m: 230, n: 222 }),
-                                            (5, MaxPayloadSize { m: 230, n: 222 }),
-                                            (6, MaxPayloadSize { m: 230, n: 222 }),
-                                            (7, MaxPayloadSize { m: 230, n: 222 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::RP002_1_0_3,
-                                        [
-                                            (0, MaxPayloadSize { m: 59, n: 51 }),
-                                            (1, MaxPayloadSize { m: 59, n: 51 }),
-                                            (2, MaxPayloadSize { m: 123, n: 115 }),
-                                            (3, MaxPayloadSize { m: 123, n: 115 }),
-                                            (4, MaxPayloadSize { m: 230, n: 222 }),
-                                            (5, MaxPayloadSize { m: 230, n: 222 }),
-                                            (6, MaxPayloadSize { m: 230, n: 222 }),
-                                            (7, MaxPayloadSize { m: 230, n: 222 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::RP002_1_0_4,
-                                        [
-                                            (0, MaxPayloadSize { m: 59, n: 51 }),
-                                            (1, MaxPayloadSize { m: 59, n: 51 }),
-                                            (2, MaxPayloadSize { m: 123, n: 115 }),
-                                            (3, MaxPayloadSize { m: 123, n: 115 }),
-                                            (4, MaxPayloadSize { m: 230, n: 222 }),
-                                            (5, MaxPayloadSize { m: 230, n: 222 }),
-                                            (6, MaxPayloadSize { m: 230, n: 222 }),
-                                            (7, MaxPayloadSize { m: 230, n: 222 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::Latest, // RP002-1.0.5
-                                        [
-                                            (0, MaxPayloadSize { m: 59, n: 51 }),
-                                            (1, MaxPayloadSize { m: 59, n: 51 }),
-                                            (2, MaxPayloadSize { m: 123, n: 115 }),
-                                            (3, MaxPayloadSize { m: 123, n: 115 }),
-                                            (4, MaxPayloadSize { m: 230, n: 222 }),
-                                            (5, MaxPayloadSize { m: 230, n: 222 }),
-                                            (6, MaxPayloadSize { m: 230, n: 222 }),
-                                            (7, MaxPayloadSize { m: 230, n: 222 }),
-                                            (12, MaxPayloadSize { m: 230, n: 222 }),
-                                            (13, MaxPayloadSize { m: 230, n: 222 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            ),
-                        ]
-                        .iter()
-                        .cloned()
-                        .collect(),
-                    },
-                    false => match dwell_time_400ms {
-                        // no repeater + dwell time
-                        true => [
-                            (
-                                MacVersion::LORAWAN_1_0_2,
-                                [(
-                                    Revision::Latest, // B
-                                    [
-                                        (0, MaxPayloadSize { m: 0, n: 0 }),
-                                        (1, MaxPayloadSize { m: 0, n: 0 }),
-                                        (2, MaxPayloadSize { m: 19, n: 11 }),
-                                        (3, MaxPayloadSize { m: 61, n: 53 }),
-                                        (4, MaxPayloadSize { m: 133, n: 125 }),
-                                        (5, MaxPayloadSize { m: 250, n: 242 }),
-                                        (6, MaxPayloadSize { m: 250, n: 242 }),
-                                        (7, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                )]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            ),
-                            (
-                                MacVersion::LORAWAN_1_0_3,
-                                [(
-                                    Revision::Latest, // A
-                                    [
-                                        (0, MaxPayloadSize { m: 0, n: 0 }),
-                                        (1, MaxPayloadSize { m: 0, n: 0 }),
-                                        (2, MaxPayloadSize { m: 19, n: 11 }),
-                                        (3, MaxPayloadSize { m: 61, n: 53 }),
-                                        (4, MaxPayloadSize { m: 133, n: 125 }),
-                                        (5, MaxPayloadSize { m: 250, n: 242 }),
-                                        (6, MaxPayloadSize { m: 250, n: 242 }),
-                                        (7, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                )]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            ),
-                            (
-                                MacVersion::LORAWAN_1_1_0,
-                                [(
-                                    Revision::Latest, // A
-                                    [
-                                        (0, MaxPayloadSize { m: 0, n: 0 }),
-                                        (1, MaxPayloadSize { m: 0, n: 0 }),
-                                        (2, MaxPayloadSize { m: 19, n: 11 }),
-                                        (3, MaxPayloadSize { m: 61, n: 53 }),
-                                        (4, MaxPayloadSize { m: 133, n: 125 }),
-                                        (5, MaxPayloadSize { m: 250, n: 242 }),
-                                        (6, MaxPayloadSize { m: 250, n: 242 }),
-                                        (7, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                )]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            ),
-                            (
-                                MacVersion::Latest,
-                                [
-                                    (
-                                        Revision::RP002_1_0_0, // RP002-1.0.0
-                                        [
-                                            (0, MaxPayloadSize { m: 0, n: 0 }),
-                                            (1, MaxPayloadSize { m: 0, n: 0 }),
-                                            (2, MaxPayloadSize { m: 19, n: 11 }),
-                                            (3, MaxPayloadSize { m: 61, n: 53 }),
-                                            (4, MaxPayloadSize { m: 133, n: 125 }),
-                                            (5, MaxPayloadSize { m: 250, n: 242 }),
-                                            (6, MaxPayloadSize { m: 250, n: 242 }),
-                                            (7, MaxPayloadSize { m: 250, n: 242 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::RP002_1_0_1, // RP002-1.0.1
-                                        [
-                                            (0, MaxPayloadSize { m: 0, n: 0 }),
-                                            (1, MaxPayloadSize { m: 0, n: 0 }),
-                                            (2, MaxPayloadSize { m: 19, n: 11 }),
-                                            (3, MaxPayloadSize { m: 61, n: 53 }),
-                                            (4, MaxPayloadSize { m: 133, n: 125 }),
-                                            (5, MaxPayloadSize { m: 250, n: 242 }),
-                                            (6, MaxPayloadSize { m: 250, n: 242 }),
-                                            (7, MaxPayloadSize { m: 250, n: 242 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::RP002_1_0_2, // RP002-1.0.2
-                                        [
-                                            (0, MaxPayloadSize { m: 0, n: 0 }),
-                                            (1, MaxPayloadSize { m: 0, n: 0 }),
-                                            (2, MaxPayloadSize { m: 19, n: 11 }),
-                                            (3, MaxPayloadSize { m: 61, n: 53 }),
-                                            (4, MaxPayloadSize { m: 133, n: 125 }),
-                                            (5, MaxPayloadSize { m: 250, n: 242 }),
-                                            (6, MaxPayloadSize { m: 250, n: 242 }),
-                                            (7, MaxPayloadSize { m: 250, n: 242 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::RP002_1_0_3,
-                                        [
-                                            (0, MaxPayloadSize { m: 0, n: 0 }),
-                                            (1, MaxPayloadSize { m: 0, n: 0 }),
-                                            (2, MaxPayloadSize { m: 19, n: 11 }),
-                                            (3, MaxPayloadSize { m: 61, n: 53 }),
-                                            (4, MaxPayloadSize { m: 133, n: 125 }),
-                                            (5, MaxPayloadSize { m: 250, n: 242 }),
-                                            (6, MaxPayloadSize { m: 250, n: 242 }),
-                                            (7, MaxPayloadSize { m: 250, n: 242 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::RP002_1_0_4,
-                                        [
-                                            (0, MaxPayloadSize { m: 0, n: 0 }),
-                                            (1, MaxPayloadSize { m: 0, n: 0 }),
-                                            (2, MaxPayloadSize { m: 19, n: 11 }),
-                                            (3, MaxPayloadSize { m: 61, n: 53 }),
-                                            (4, MaxPayloadSize { m: 133, n: 125 }),
-                                            (5, MaxPayloadSize { m: 250, n: 242 }),
-                                            (6, MaxPayloadSize { m: 250, n: 242 }),
-                                            (7, MaxPayloadSize { m: 250, n: 242 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::Latest, // RP002-1.0.5
-                                        [
-                                            (0, MaxPayloadSize { m: 0, n: 0 }),
-                                            (1, MaxPayloadSize { m: 0, n: 0 }),
-                                            (2, MaxPayloadSize { m: 19, n: 11 }),
-                                            (3, MaxPayloadSize { m: 61, n: 53 }),
-                                            (4, MaxPayloadSize { m: 133, n: 125 }),
-                                            (5, MaxPayloadSize { m: 250, n: 242 }),
-                                            (6, MaxPayloadSize { m: 250, n: 242 }),
-                                            (7, MaxPayloadSize { m: 250, n: 242 }),
-                                            (12, MaxPayloadSize { m: 250, n: 242 }),
-                                            (13, MaxPayloadSize { m: 250, n: 242 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            ),
-                        ]
-                        .iter()
-                        .cloned()
-                        .collect(),
-                        // no repeater + no dwell time
-                        false => [
-                            (
-                                MacVersion::LORAWAN_1_0_2,
-                                [(
-                                    Revision::Latest, // B
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 250, n: 242 }),
-                                        (5, MaxPayloadSize { m: 250, n: 242 }),
-                                        (6, MaxPayloadSize { m: 250, n: 242 }),
-                                        (7, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                )]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            ),
-                            (
-                                MacVersion::LORAWAN_1_0_3,
-                                [(
-                                    Revision::Latest, // A
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 250, n: 242 }),
-                                        (5, MaxPayloadSize { m: 250, n: 242 }),
-                                        (6, MaxPayloadSize { m: 250, n: 242 }),
-                                        (7, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                )]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            ),
-                            (
-                                MacVersion::LORAWAN_1_1_0,
-                                [(
-                                    Revision::Latest, // A
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 250, n: 242 }),
-                                        (5, MaxPayloadSize { m: 250, n: 242 }),
-                                        (6, MaxPayloadSize { m: 250, n: 242 }),
-                                        (7, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                )]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            ),
-                            (
-                                MacVersion::Latest,
-                                [
-                                    (
-                                        Revision::RP002_1_0_0, // RP002-1.0.0
-                                        [
-                                            (0, MaxPayloadSize { m: 59, n: 51 }),
-                                            (1, MaxPayloadSize { m: 59, n: 51 }),
-                                            (2, MaxPayloadSize { m: 59, n: 51 }),
-                                            (3, MaxPayloadSize { m: 123, n: 115 }),
-                                            (4, MaxPayloadSize { m: 250, n: 242 }),
-                                            (5, MaxPayloadSize { m: 250, n: 242 }),
-                                            (6, MaxPayloadSize { m: 250, n: 242 }),
-                                            (7, MaxPayloadSize { m: 250, n: 242 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::RP002_1_0_1, // RP002-1.0.1
-                                        [
-                                            (0, MaxPayloadSize { m: 59, n: 51 }),
-                                            (1, MaxPayloadSize { m: 59, n: 51 }),
-                                            (2, MaxPayloadSize { m: 123, n: 115 }),
-                                            (3, MaxPayloadSize { m: 123, n: 115 }),
-                                            (4, MaxPayloadSize { m: 250, n: 242 }),
-                                            (5, MaxPayloadSize { m: 250, n: 242 }),
-                                            (6, MaxPayloadSize { m: 250, n: 242 }),
-                                            (7, MaxPayloadSize { m: 250, n: 242 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::RP002_1_0_2, // RP002-1.0.2
-                                        [
-                                            (0, MaxPayloadSize { m: 59, n: 51 }),
-                                            (1, MaxPayloadSize { m: 59, n: 51 }),
-                                            (2, MaxPayloadSize { m: 123, n: 115 }),
-                                            (3, MaxPayloadSize { m: 123, n: 115 }),
-                                            (4, MaxPayloadSize { m: 250, n: 242 }),
-                                            (5, MaxPayloadSize { m: 250, n: 242 }),
-                                            (6, MaxPayloadSize { m: 250, n: 242 }),
-                                            (7, MaxPayloadSize { m: 250, n: 242 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::RP002_1_0_3,
-                                        [
-                                            (0, MaxPayloadSize { m: 59, n: 51 }),
-                                            (1, MaxPayloadSize { m: 59, n: 51 }),
-                                            (2, MaxPayloadSize { m: 123, n: 115 }),
-                                            (3, MaxPayloadSize { m: 123, n: 115 }),
-                                            (4, MaxPayloadSize { m: 250, n: 242 }),
-                                            (5, MaxPayloadSize { m: 250, n: 242 }),
-                                            (6, MaxPayloadSize { m: 250, n: 242 }),
-                                            (7, MaxPayloadSize { m: 250, n: 242 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::RP002_1_0_4,
-                                        [
-                                            (0, MaxPayloadSize { m: 59, n: 51 }),
-                                            (1, MaxPayloadSize { m: 59, n: 51 }),
-                                            (2, MaxPayloadSize { m: 123, n: 115 }),
-                                            (3, MaxPayloadSize { m: 123, n: 115 }),
-                                            (4, MaxPayloadSize { m: 250, n: 242 }),
-                                            (5, MaxPayloadSize { m: 250, n: 242 }),
-                                            (6, MaxPayloadSize { m: 250, n: 242 }),
-                                            (7, MaxPayloadSize { m: 250, n: 242 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                    (
-                                        Revision::Latest, // RP002-1.0.5
-                                        [
-                                            (0, MaxPayloadSize { m: 59, n: 51 }),
-                                            (1, MaxPayloadSize { m: 59, n: 51 }),
-                                            (2, MaxPayloadSize { m: 123, n: 115 }),
-                                            (3, MaxPayloadSize { m: 123, n: 115 }),
-                                            (4, MaxPayloadSize { m: 250, n: 242 }),
-                                            (5, MaxPayloadSize { m: 250, n: 242 }),
-                                            (6, MaxPayloadSize { m: 250, n: 242 }),
-                                            (7, MaxPayloadSize { m: 250, n: 242 }),
-                                            (12, MaxPayloadSize { m: 250, n: 242 }),
-                                            (13, MaxPayloadSize { m: 250, n: 242 }),
-                                        ]
-                                        .iter()
-                                        .cloned()
-                                        .collect(),
-                                    ),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            ),
-                        ]
-                        .iter()
-                        .cloned()
-                        .collect(),
-                    },
-                },
+                max_dl_payload_size_per_dr: params::get_max_payload_size_table(
+                    cn,
+                    repeater_compatible,
+                    dwell_time_400ms,
+                ),
                 rx1_data_rate_table: match dwell_time_400ms {
                     false => [
                         (0, vec![0, 0, 0, 0, 0, 0, 1, 2]),
@@ -878,6 +211,10 @@ impl Configuration {
                         (5, vec![5, 4, 3, 2, 1, 0, 6, 7]),
                         (6, vec![6, 5, 4, 3, 2, 1, 7, 7]),
                         (7, vec![7, 6, 5, 4, 3, 2, 7, 7]),
+                        (8, vec![1, 0, 0, 0, 0, 0, 2, 3]),
+                        (9, vec![2, 1, 0, 0, 0, 0, 3, 4]),
+                        (10, vec![1, 0, 0, 0, 0, 0, 2, 3]),
+                        (11, vec![2, 1, 0, 0, 0, 0, 3, 4]),
                         (12, vec![12, 5, 4, 3, 2, 1, 13, 13]),
                         (13, vec![13, 12, 5, 4, 3, 2, 13, 13]),
                     ],
@@ -890,6 +227,10 @@ impl Configuration {
                         (5, vec![5, 4, 3, 2, 2, 2, 6, 7]),
                         (6, vec![6, 5, 4, 3, 2, 2, 7, 7]),
                         (7, vec![7, 6, 5, 4, 3, 2, 7, 7]),
+                        (8, vec![2, 2, 2, 2, 2, 2, 2, 3]),
+                        (9, vec![2, 2, 2, 2, 2, 2, 3, 4]),
+                        (10, vec![2, 2, 2, 2, 2, 2, 2, 3]),
+                        (11, vec![2, 2, 2, 2, 2, 2, 3, 4]),
                         (12, vec![12, 5, 4, 3, 2, 2, 13, 13]),
                         (13, vec![13, 12, 5, 4, 3, 2, 13, 13]),
                     ],
@@ -977,10 +318,6 @@ impl Region for Configuration {
         }
     }
 
-    fn implements_tx_param_setup(&self, _mac_version: MacVersion) -> bool {
-        true
-    }
-
     fn get_rx1_data_rate_index(&self, uplink_dr: u8, rx1_dr_offset: usize) -> Result<u8> {
         self.base.get_rx1_data_rate_index(uplink_dr, rx1_dr_offset)
     }
@@ -1336,6 +673,11 @@ pub mod test {
             923400000 - 1800000,
             c.get_downlink_channel(1).unwrap().frequency
         );
+
+        // AS923-2 was introduced by RP002-1.0.1.
+        assert!(c.implements_tx_param_setup(MacVersion::LORAWAN_1_0_4, Revision::RP002_1_0_4));
+        // Older revisions fall back to the MAC version rule.
+        assert!(c.implements_tx_param_setup(MacVersion::LORAWAN_1_0_2, Revision::B));
     }
 
     #[test]
@@ -1397,4 +739,48 @@ pub mod test {
             c.get_downlink_channel(1).unwrap().frequency
         );
     }
+
+    #[test]
+    fn test_lr_fhss() {
+        for dwell_time_400ms in [false, true] {
+            let c = Configuration::new(CommonName::AS923, false, dwell_time_400ms);
+
+            // Data-rate index.
+            let tests = vec![
+                ("2/6", 137000, 8),
+                ("4/6", 137000, 9),
+                ("2/6", 336000, 10),
+                ("4/6", 336000, 11),
+                ("1/3", 136719, 8),
+                ("2/3", 335938, 11),
+            ];
+            for (coding_rate, occupied_channel_width, dr) in tests {
+                let modulation = DataRateModulation::LrFhss(LrFhssDataRate {
+                    coding_rate: coding_rate.to_string(),
+                    occupied_channel_width,
+                });
+                assert_eq!(dr, c.get_data_rate_index(true, &modulation).unwrap());
+                assert!(c.get_data_rate_index(false, &modulation).is_err());
+            }
+
+            // RX1 data-rate.
+            assert_eq!(
+                c.get_rx1_data_rate_index(1, 0).unwrap(),
+                c.get_rx1_data_rate_index(8, 0).unwrap()
+            );
+            assert_eq!(
+                c.get_rx1_data_rate_index(2, 0).unwrap(),
+                c.get_rx1_data_rate_index(11, 0).unwrap()
+            );
+
+            // Max payload sizes.
+            for (dr, m, n) in [(8, 58, 50), (9, 123, 115), (10, 58, 50), (11, 123, 115)] {
+                let pl = c
+                    .get_max_dl_payload_size(MacVersion::LORAWAN_1_0_4, Revision::RP002_1_0_4, dr)
+                    .unwrap();
+                assert_eq!(m, pl.m);
+                assert_eq!(n, pl.n);
+            }
+        }
+    }
 }
@@ -1,11 +1,12 @@
-use std::time::Duration;
+use core::time::Duration;
 
 use anyhow::Result;
 
 use super::{
     Channel, CommonName, DataRate, DataRateModulation, Defaults, LinkADRReqPayload, LoraDataRate,
-    LrFhssDataRate, MacVersion, MaxPayloadSize, Region, RegionBaseConfig, Revision,
+    LrFhssDataRate, MacVersion, MaxPayloadSize, Region, RegionBaseConfig, Revision, params,
 };
+use crate::prelude::*;
 use crate::{CFList, ChMask, DevAddr, Redundancy};
 
 pub struct Configuration {
@@ -240,344 +241,11 @@ impl Configuration {
                         },
                     ),
                 ],
-                max_dl_payload_size_per_dr: match repeater_compatible {
-                    // repeater compatible
-                    true => [
-                        (
-                            MacVersion::LORAWAN_1_0_1,
-                            [(
-                                Revision::Latest,
-                                [
-                                    (8, MaxPayloadSize { m: 41, n: 33 }),
-                                    (9, MaxPayloadSize { m: 117, n: 109 }),
-                                    (10, MaxPayloadSize { m: 230, n: 222 }),
-                                    (11, MaxPayloadSize { m: 230, n: 222 }),
-                                    (12, MaxPayloadSize { m: 230, n: 222 }),
-                                    (13, MaxPayloadSize { m: 230, n: 222 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_0_2,
-                            [
-                                (
-                                    Revision::A,
-                                    [
-                                        (8, MaxPayloadSize { m: 41, n: 33 }),
-                                        (9, MaxPayloadSize { m: 117, n: 109 }),
-                                        (10, MaxPayloadSize { m: 230, n: 222 }),
-                                        (11, MaxPayloadSize { m: 230, n: 222 }),
-                                        (12, MaxPayloadSize { m: 230, n: 222 }),
-                                        (13, MaxPayloadSize { m: 230, n: 222 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::Latest, // B
-                                    [
-                                        (8, MaxPayloadSize { m: 41, n: 33 }),
-                                        (9, MaxPayloadSize { m: 117, n: 109 }),
-                                        (10, MaxPayloadSize { m: 230, n: 222 }),
-                                        (11, MaxPayloadSize { m: 230, n: 222 }),
-                                        (12, MaxPayloadSize { m: 230, n: 222 }),
-                                        (13, MaxPayloadSize { m: 230, n: 222 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                            ]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_0_3,
-                            [(
-                                Revision::Latest, // A
-                                [
-                                    (8, MaxPayloadSize { m: 41, n: 33 }),
-                                    (9, MaxPayloadSize { m: 117, n: 109 }),
-                                    (10, MaxPayloadSize { m: 230, n: 222 }),
-                                    (11, MaxPayloadSize { m: 230, n: 222 }),
-                                    (12, MaxPayloadSize { m: 230, n: 222 }),
-                                    (13, MaxPayloadSize { m: 230, n: 222 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_1_0,
-                            [(
-                                Revision::Latest, // A & B
-                                [
-                                    (8, MaxPayloadSize { m: 41, n: 33 }),
-                                    (9, MaxPayloadSize { m: 117, n: 109 }),
-                                    (10, MaxPayloadSize { m: 230, n: 222 }),
-                                    (11, MaxPayloadSize { m: 230, n: 222 }),
-                                    (12, MaxPayloadSize { m: 230, n: 222 }),
-                                    (13, MaxPayloadSize { m: 230, n: 222 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::Latest,
-                            [
-                                (
-                                    Revision::RP002_1_0_0,
-                                    [
-                                        (8, MaxPayloadSize { m: 41, n: 33 }),
-                                        (9, MaxPayloadSize { m: 117, n: 109 }),
-                                        (10, MaxPayloadSize { m: 230, n: 222 }),
-                                        (11, MaxPayloadSize { m: 230, n: 222 }),
-                                        (12, MaxPayloadSize { m: 230, n: 222 }),
-                                        (13, MaxPayloadSize { m: 230, n: 222 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::RP002_1_0_1,
-                                    [
-                                        (8, MaxPayloadSize { m: 41, n: 33 }),
-                                        (9, MaxPayloadSize { m: 117, n: 109 }),
-                                        (10, MaxPayloadSize { m: 230, n: 222 }),
-                                        (11, MaxPayloadSize { m: 230, n: 222 }),
-                                        (12, MaxPayloadSize { m: 230, n: 222 }),
-                                        (13, MaxPayloadSize { m: 230, n: 222 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::RP002_1_0_2,
-                                    [
-                                        (8, MaxPayloadSize { m: 61, n: 53 }),
-                                        (9, MaxPayloadSize { m: 137, n: 129 }),
-                                        (10, MaxPayloadSize { m: 230, n: 222 }),
-                                        (11, MaxPayloadSize { m: 230, n: 222 }),
-                                        (12, MaxPayloadSize { m: 230, n: 222 }),
-                                        (13, MaxPayloadSize { m: 230, n: 222 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::RP002_1_0_3,
-                                    [
-                                        (8, MaxPayloadSize { m: 61, n: 53 }),
-                                        (9, MaxPayloadSize { m: 137, n: 129 }),
-                                        (10, MaxPayloadSize { m: 230, n: 222 }),
-                                        (11, MaxPayloadSize { m: 230, n: 222 }),
-                                        (12, MaxPayloadSize { m: 230, n: 222 }),
-                                        (13, MaxPayloadSize { m: 230, n: 222 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::RP002_1_0_4,
-                                    [
-                                        (8, MaxPayloadSize { m: 61, n: 53 }),
-                                        (9, MaxPayloadSize { m: 137, n: 129 }),
-                                        (10, MaxPayloadSize { m: 230, n: 222 }),
-                                        (11, MaxPayloadSize { m: 230, n: 222 }),
-                                        (12, MaxPayloadSize { m: 230, n: 222 }),
-                                        (13, MaxPayloadSize { m: 230, n: 222 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::Latest, // RP002-1.0.5
-                                    [
-                                        (0, MaxPayloadSize { m: 230, n: 222 }),
-                                        (8, MaxPayloadSize { m: 61, n: 53 }),
-                                        (9, MaxPayloadSize { m: 137, n: 129 }),
-                                        (10, MaxPayloadSize { m: 230, n: 222 }),
-                                        (11, MaxPayloadSize { m: 230, n: 222 }),
-                                        (12, MaxPayloadSize { m: 230, n: 222 }),
-                                        (13, MaxPayloadSize { m: 230, n: 222 }),
-                                        (14, MaxPayloadSize { m: 230, n: 222 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                            ]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                    ]
-                    .iter()
-                    .cloned()
-                    .collect(),
-                    // no repeater
-                    false => [
-                        (
-                            MacVersion::LORAWAN_1_0_3, // LoRaWAN < 1.0.3 + < LoRaWAN 1.1.0B does not have dwell-time
-                            [(
-                                Revision::Latest, // A
-                                [
-                                    (8, MaxPayloadSize { m: 61, n: 53 }),
-                                    (9, MaxPayloadSize { m: 137, n: 129 }),
-                                    (10, MaxPayloadSize { m: 250, n: 242 }),
-                                    (11, MaxPayloadSize { m: 250, n: 242 }),
-                                    (12, MaxPayloadSize { m: 250, n: 242 }),
-                                    (13, MaxPayloadSize { m: 250, n: 242 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_1_0,
-                            [(
-                                Revision::Latest, // B
-                                [
-                                    (8, MaxPayloadSize { m: 61, n: 53 }),
-                                    (9, MaxPayloadSize { m: 137, n: 129 }),
-                                    (10, MaxPayloadSize { m: 250, n: 242 }),
-                                    (11, MaxPayloadSize { m: 250, n: 242 }),
-                                    (12, MaxPayloadSize { m: 250, n: 242 }),
-                                    (13, MaxPayloadSize { m: 250, n: 242 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::Latest,
-                            [
-                                (
-                                    Revision::RP002_1_0_0,
-                                    [
-                                        (8, MaxPayloadSize { m: 61, n: 53 }),
-                                        (9, MaxPayloadSize { m: 137, n: 129 }),
-                                        (10, MaxPayloadSize { m: 250, n: 222 }),
-                                        (11, MaxPayloadSize { m: 250, n: 250 }),
-                                        (12, MaxPayloadSize { m: 250, n: 250 }),
-                                        (13, MaxPayloadSize { m: 250, n: 250 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::RP002_1_0_1,
-                                    [
-                                        (8, MaxPayloadSize { m: 61, n: 53 }),
-                                        (9, MaxPayloadSize { m: 137, n: 129 }),
-                                        (10, MaxPayloadSize { m: 250, n: 242 }),
-                                        (11, MaxPayloadSize { m: 250, n: 242 }),
-                                        (12, MaxPayloadSize { m: 250, n: 242 }),
-                                        (13, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::RP002_1_0_2,
-                                    [
-                                        (8, MaxPayloadSize { m: 61, n: 53 }),
-                                        (9, MaxPayloadSize { m: 137, n: 129 }),
-                                        (10, MaxPayloadSize { m: 250, n: 242 }),
-                                        (11, MaxPayloadSize { m: 250, n: 242 }),
-                                        (12, MaxPayloadSize { m: 250, n: 242 }),
-                                        (13, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::RP002_1_0_3,
-                                    [
-                                        (8, MaxPayloadSize { m: 61, n: 53 }),
-                                        (9, MaxPayloadSize { m: 137, n: 129 }),
-                                        (10, MaxPayloadSize { m: 250, n: 242 }),
-                                        (11, MaxPayloadSize { m: 250, n: 242 }),
-                                        (12, MaxPayloadSize { m: 250, n: 242 }),
-                                        (13, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::RP002_1_0_4,
-                                    [
-                                        (8, MaxPayloadSize { m: 61, n: 53 }),
-                                        (9, MaxPayloadSize { m: 137, n: 129 }),
-                                        (10, MaxPayloadSize { m: 250, n: 242 }),
-                                        (11, MaxPayloadSize { m: 250, n: 242 }),
-                                        (12, MaxPayloadSize { m: 250, n: 242 }),
-                                        (13, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::Latest, // RP002-1.0.5
-                                    [
-                                        (0, MaxPayloadSize { m: 250, n: 242 }),
-                                        (8, MaxPayloadSize { m: 61, n: 53 }),
-                                        (9, MaxPayloadSize { m: 137, n: 129 }),
-                                        (10, MaxPayloadSize { m: 250, n: 242 }),
-                                        (11, MaxPayloadSize { m: 250, n: 242 }),
-                                        (12, MaxPayloadSize { m: 250, n: 242 }),
-                                        (13, MaxPayloadSize { m: 250, n: 242 }),
-                                        (14, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                            ]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                    ]
-                    .iter()
-                    .cloned()
-                    .collect(),
-                },
+                max_dl_payload_size_per_dr: params::get_max_payload_size_table(
+                    CommonName::AU915,
+                    repeater_compatible,
+                    false,
+                ),
                 rx1_data_rate_table: [
                     (0, vec![8, 8, 8, 8, 8, 8]),
                     (1, vec![9, 8, 8, 8, 8, 8]),
@@ -693,10 +361,6 @@ impl Region for Configuration {
         }
     }
 
-    fn implements_tx_param_setup(&self, mac_version: MacVersion) -> bool {
-        !(mac_version == MacVersion::LORAWAN_1_0_1 || mac_version == MacVersion::LORAWAN_1_0_2)
-    }
-
     fn get_data_rate_index(&self, uplink: bool, modulation: &DataRateModulation) -> Result<u8> {
         self.base.get_data_rate_index(uplink, modulation)
     }
@@ -1219,4 +883,19 @@ pub mod test {
             assert_eq!(tst.expected_uplink_channels, channels);
         }
     }
+
+    #[test]
+    fn test_lr_fhss() {
+        let c = Configuration::new(false, false);
+
+        let tests = vec![("1/3", 1523438, 7)];
+        for (coding_rate, occupied_channel_width, dr) in tests {
+            let modulation = DataRateModulation::LrFhss(LrFhssDataRate {
+                coding_rate: coding_rate.to_string(),
+                occupied_channel_width,
+            });
+            assert_eq!(dr, c.get_data_rate_index(true, &modulation).unwrap());
+            assert!(c.get_data_rate_index(false, &modulation).is_err());
+        }
+    }
 }
@@ -1,11 +1,12 @@
-use std::time::Duration;
+use core::time::Duration;
 
 use anyhow::Result;
 
 use super::{
     Channel, CommonName, DataRate, DataRateModulation, Defaults, FskDataRate, LinkADRReqPayload,
-    LoraDataRate, MacVersion, MaxPayloadSize, Region, RegionBaseConfig, Revision,
+    LoraDataRate, MacVersion, MaxPayloadSize, Region, RegionBaseConfig, Revision, params,
 };
+use crate::prelude::*;
 use crate::{CFList, DevAddr};
 
 pub struct Configuration {
@@ -113,270 +114,11 @@ impl Configuration {
                         },
                     ),
                 ],
-                max_dl_payload_size_per_dr: match repeater_compatible {
-                    true => [
-                        (
-                            MacVersion::LORAWAN_1_0_1,
-                            [(
-                                Revision::Latest,
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 230, n: 222 }),
-                                    (5, MaxPayloadSize { m: 230, n: 222 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_0_2,
-                            [(
-                                Revision::Latest, // A & B
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 230, n: 222 }),
-                                    (5, MaxPayloadSize { m: 230, n: 222 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_0_3,
-                            [(
-                                Revision::Latest, // A
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 230, n: 222 }),
-                                    (5, MaxPayloadSize { m: 230, n: 222 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_1_0,
-                            [(
-                                Revision::Latest, // A & B
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 230, n: 222 }),
-                                    (5, MaxPayloadSize { m: 230, n: 222 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::Latest,
-                            [
-                                (
-                                    Revision::RP002_1_0_0,
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 230, n: 222 }),
-                                        (5, MaxPayloadSize { m: 230, n: 222 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::RP002_1_0_1,
-                                    [
-                                        (0, MaxPayloadSize { m: 0, n: 0 }),
-                                        (1, MaxPayloadSize { m: 31, n: 23 }),
-                                        (2, MaxPayloadSize { m: 94, n: 86 }),
-                                        (3, MaxPayloadSize { m: 172, n: 164 }),
-                                        (4, MaxPayloadSize { m: 230, n: 222 }),
-                                        (5, MaxPayloadSize { m: 230, n: 222 }),
-                                        (6, MaxPayloadSize { m: 230, n: 222 }),
-                                        (7, MaxPayloadSize { m: 230, n: 222 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::Latest, // RP002-1.0.2, PR002-1.0.3
-                                    [
-                                        (0, MaxPayloadSize { m: 0, n: 0 }),
-                                        (1, MaxPayloadSize { m: 31, n: 23 }),
-                                        (2, MaxPayloadSize { m: 94, n: 86 }),
-                                        (3, MaxPayloadSize { m: 192, n: 184 }),
-                                        (4, MaxPayloadSize { m: 230, n: 222 }),
-                                        (5, MaxPayloadSize { m: 230, n: 222 }),
-                                        (6, MaxPayloadSize { m: 230, n: 222 }),
-                                        (7, MaxPayloadSize { m: 230, n: 222 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                            ]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                    ]
-                    .iter()
-                    .cloned()
-                    .collect(),
-                    false => [
-                        (
-                            MacVersion::LORAWAN_1_0_1,
-                            [(
-                                Revision::Latest,
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 230, n: 222 }),
-                                    (5, MaxPayloadSize { m: 230, n: 222 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_0_2,
-                            [(
-                                Revision::Latest, // A & B
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 250, n: 242 }),
-                                    (5, MaxPayloadSize { m: 250, n: 242 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_0_3,
-                            [(
-                                Revision::Latest, // A
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 250, n: 242 }),
-                                    (5, MaxPayloadSize { m: 250, n: 242 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_1_0,
-                            [(
-                                Revision::Latest, // A & B
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 250, n: 242 }),
-                                    (5, MaxPayloadSize { m: 250, n: 242 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::Latest,
-                            [
-                                (
-                                    Revision::RP002_1_0_0,
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 250, n: 242 }),
-                                        (5, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::Latest, // PR002-1.0.1, RP002-1.0.2, PR002-1.0.3
-                                    [
-                                        (0, MaxPayloadSize { m: 0, n: 0 }),
-                                        (1, MaxPayloadSize { m: 31, n: 23 }),
-                                        (2, MaxPayloadSize { m: 94, n: 86 }),
-                                        (3, MaxPayloadSize { m: 192, n: 184 }),
-                                        (4, MaxPayloadSize { m: 250, n: 242 }),
-                                        (5, MaxPayloadSize { m: 250, n: 242 }),
-                                        (6, MaxPayloadSize { m: 250, n: 242 }),
-                                        (7, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                            ]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                    ]
-                    .iter()
-                    .cloned()
-                    .collect(),
-                },
+                max_dl_payload_size_per_dr: params::get_max_payload_size_table(
+                    CommonName::CN470,
+                    repeater_compatible,
+                    false,
+                ),
                 rx1_data_rate_table: [
                     (0, vec![0, 0, 0, 0, 0, 0]),
                     (1, vec![1, 0, 0, 0, 0, 0]),
@@ -474,10 +216,6 @@ impl Region for Configuration {
         }
     }
 
-    fn implements_tx_param_setup(&self, _mac_version: MacVersion) -> bool {
-        false
-    }
-
     fn get_data_rate_index(&self, uplink: bool, modulation: &DataRateModulation) -> Result<u8> {
         self.base.get_data_rate_index(uplink, modulation)
     }
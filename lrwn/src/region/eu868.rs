@@ -1,11 +1,13 @@
-use std::time::Duration;
+use core::time::Duration;
 
 use anyhow::Result;
 
 use super::{
     Channel, CommonName, DataRate, DataRateModulation, Defaults, FskDataRate, LinkADRReqPayload,
     LoraDataRate, LrFhssDataRate, MacVersion, MaxPayloadSize, Region, RegionBaseConfig, Revision,
+    params,
 };
+use crate::prelude::*;
 use crate::{CFList, DevAddr};
 
 pub struct Configuration {
@@ -181,482 +183,11 @@ impl Configuration {
                         },
                     ),
                 ],
-                max_dl_payload_size_per_dr: match repeater_compatible {
-                    true => [
-                        (
-                            MacVersion::LORAWAN_1_0_0,
-                            [(
-                                Revision::Latest,
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 230, n: 222 }),
-                                    (5, MaxPayloadSize { m: 230, n: 222 }),
-                                    (6, MaxPayloadSize { m: 230, n: 222 }),
-                                    (7, MaxPayloadSize { m: 230, n: 222 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_0_1,
-                            [(
-                                Revision::Latest,
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 230, n: 222 }),
-                                    (5, MaxPayloadSize { m: 230, n: 222 }),
-                                    (6, MaxPayloadSize { m: 230, n: 222 }),
-                                    (7, MaxPayloadSize { m: 230, n: 222 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_0_2,
-                            [(
-                                Revision::Latest, // A & B
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 230, n: 222 }),
-                                    (5, MaxPayloadSize { m: 230, n: 222 }),
-                                    (6, MaxPayloadSize { m: 230, n: 222 }),
-                                    (7, MaxPayloadSize { m: 230, n: 222 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_0_3,
-                            [(
-                                Revision::Latest, // A
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 230, n: 222 }),
-                                    (5, MaxPayloadSize { m: 230, n: 222 }),
-                                    (6, MaxPayloadSize { m: 230, n: 222 }),
-                                    (7, MaxPayloadSize { m: 230, n: 222 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_1_0,
-                            [(
-                                Revision::Latest, // A & B
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 230, n: 222 }),
-                                    (5, MaxPayloadSize { m: 230, n: 222 }),
-                                    (6, MaxPayloadSize { m: 230, n: 222 }),
-                                    (7, MaxPayloadSize { m: 230, n: 222 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::Latest,
-                            [
-                                (
-                                    Revision::RP002_1_0_0,
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 230, n: 222 }),
-                                        (5, MaxPayloadSize { m: 230, n: 222 }),
-                                        (6, MaxPayloadSize { m: 230, n: 222 }),
-                                        (7, MaxPayloadSize { m: 230, n: 222 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::RP002_1_0_1,
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 230, n: 222 }),
-                                        (5, MaxPayloadSize { m: 230, n: 222 }),
-                                        (6, MaxPayloadSize { m: 230, n: 222 }),
-                                        (7, MaxPayloadSize { m: 230, n: 222 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::RP002_1_0_2,
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 230, n: 222 }),
-                                        (5, MaxPayloadSize { m: 230, n: 222 }),
-                                        (6, MaxPayloadSize { m: 230, n: 222 }),
-                                        (7, MaxPayloadSize { m: 230, n: 222 }),
-                                        (8, MaxPayloadSize { m: 58, n: 50 }),
-                                        (9, MaxPayloadSize { m: 123, n: 115 }),
-                                        (10, MaxPayloadSize { m: 58, n: 50 }),
-                                        (11, MaxPayloadSize { m: 123, n: 115 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::RP002_1_0_3,
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 230, n: 222 }),
-                                        (5, MaxPayloadSize { m: 230, n: 222 }),
-                                        (6, MaxPayloadSize { m: 230, n: 222 }),
-                                        (7, MaxPayloadSize { m: 230, n: 222 }),
-                                        (8, MaxPayloadSize { m: 58, n: 50 }),
-                                        (9, MaxPayloadSize { m: 123, n: 115 }),
-                                        (10, MaxPayloadSize { m: 58, n: 50 }),
-                                        (11, MaxPayloadSize { m: 123, n: 115 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::RP002_1_0_4,
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 230, n: 222 }),
-                                        (5, MaxPayloadSize { m: 230, n: 222 }),
-                                        (6, MaxPayloadSize { m: 230, n: 222 }),
-                                        (7, MaxPayloadSize { m: 230, n: 222 }),
-                                        (8, MaxPayloadSize { m: 58, n: 50 }),
-                                        (9, MaxPayloadSize { m: 123, n: 115 }),
-                                        (10, MaxPayloadSize { m: 58, n: 50 }),
-                                        (11, MaxPayloadSize { m: 123, n: 115 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::Latest, // RP002_1_0_5
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 230, n: 222 }),
-                                        (5, MaxPayloadSize { m: 230, n: 222 }),
-                                        (6, MaxPayloadSize { m: 230, n: 222 }),
-                                        (7, MaxPayloadSize { m: 230, n: 222 }),
-                                        (8, MaxPayloadSize { m: 58, n: 50 }),
-                                        (9, MaxPayloadSize { m: 123, n: 115 }),
-                                        (10, MaxPayloadSize { m: 58, n: 50 }),
-                                        (11, MaxPayloadSize { m: 123, n: 115 }),
-                                        (12, MaxPayloadSize { m: 230, n: 222 }),
-                                        (13, MaxPayloadSize { m: 230, n: 222 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                            ]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                    ]
-                    .iter()
-                    .cloned()
-                    .collect(),
-                    false => [
-                        (
-                            MacVersion::LORAWAN_1_0_0,
-                            [(
-                                Revision::Latest,
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 250, n: 242 }),
-                                    (5, MaxPayloadSize { m: 250, n: 242 }),
-                                    (6, MaxPayloadSize { m: 250, n: 242 }),
-                                    (7, MaxPayloadSize { m: 250, n: 242 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_0_1,
-                            [(
-                                Revision::Latest,
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 250, n: 242 }),
-                                    (5, MaxPayloadSize { m: 250, n: 242 }),
-                                    (6, MaxPayloadSize { m: 250, n: 242 }),
-                                    (7, MaxPayloadSize { m: 250, n: 242 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_0_2,
-                            [(
-                                Revision::Latest, // A & B
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 250, n: 242 }),
-                                    (5, MaxPayloadSize { m: 250, n: 242 }),
-                                    (6, MaxPayloadSize { m: 250, n: 242 }),
-                                    (7, MaxPayloadSize { m: 250, n: 242 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_0_3,
-                            [(
-                                Revision::Latest, // A
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 250, n: 242 }),
-                                    (5, MaxPayloadSize { m: 250, n: 242 }),
-                                    (6, MaxPayloadSize { m: 250, n: 242 }),
-                                    (7, MaxPayloadSize { m: 250, n: 242 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::LORAWAN_1_1_0,
-                            [(
-                                Revision::Latest, // A & B
-                                [
-                                    (0, MaxPayloadSize { m: 59, n: 51 }),
-                                    (1, MaxPayloadSize { m: 59, n: 51 }),
-                                    (2, MaxPayloadSize { m: 59, n: 51 }),
-                                    (3, MaxPayloadSize { m: 123, n: 115 }),
-                                    (4, MaxPayloadSize { m: 250, n: 242 }),
-                                    (5, MaxPayloadSize { m: 250, n: 242 }),
-                                    (6, MaxPayloadSize { m: 250, n: 242 }),
-                                    (7, MaxPayloadSize { m: 250, n: 242 }),
-                                ]
-                                .iter()
-                                .cloned()
-                                .collect(),
-                            )]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                        (
-                            MacVersion::Latest,
-                            [
-                                (
-                                    Revision::RP002_1_0_0,
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 250, n: 242 }),
-                                        (5, MaxPayloadSize { m: 250, n: 242 }),
-                                        (6, MaxPayloadSize { m: 250, n: 242 }),
-                                        (7, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::RP002_1_0_1,
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 250, n: 242 }),
-                                        (5, MaxPayloadSize { m: 250, n: 242 }),
-                                        (6, MaxPayloadSize { m: 250, n: 242 }),
-                                        (7, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::RP002_1_0_2,
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 250, n: 222 }),
-                                        (5, MaxPayloadSize { m: 250, n: 242 }),
-                                        (6, MaxPayloadSize { m: 250, n: 242 }),
-                                        (7, MaxPayloadSize { m: 250, n: 242 }),
-                                        (8, MaxPayloadSize { m: 58, n: 50 }),
-                                        (9, MaxPayloadSize { m: 123, n: 115 }),
-                                        (10, MaxPayloadSize { m: 58, n: 50 }),
-                                        (11, MaxPayloadSize { m: 123, n: 115 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::RP002_1_0_3,
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 250, n: 222 }),
-                                        (5, MaxPayloadSize { m: 250, n: 242 }),
-                                        (6, MaxPayloadSize { m: 250, n: 242 }),
-                                        (7, MaxPayloadSize { m: 250, n: 242 }),
-                                        (8, MaxPayloadSize { m: 58, n: 50 }),
-                                        (9, MaxPayloadSize { m: 123, n: 115 }),
-                                        (10, MaxPayloadSize { m: 58, n: 50 }),
-                                        (11, MaxPayloadSize { m: 123, n: 115 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::RP002_1_0_4,
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 250, n: 222 }),
-                                        (5, MaxPayloadSize { m: 250, n: 242 }),
-                                        (6, MaxPayloadSize { m: 250, n: 242 }),
-                                        (7, MaxPayloadSize { m: 250, n: 242 }),
-                                        (8, MaxPayloadSize { m: 58, n: 50 }),
-                                        (9, MaxPayloadSize { m: 123, n: 115 }),
-                                        (10, MaxPayloadSize { m: 58, n: 50 }),
-                                        (11, MaxPayloadSize { m: 123, n: 115 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                                (
-                                    Revision::Latest, // RP002_1_0_5
-                                    [
-                                        (0, MaxPayloadSize { m: 59, n: 51 }),
-                                        (1, MaxPayloadSize { m: 59, n: 51 }),
-                                        (2, MaxPayloadSize { m: 59, n: 51 }),
-                                        (3, MaxPayloadSize { m: 123, n: 115 }),
-                                        (4, MaxPayloadSize { m: 250, n: 222 }),
-                                        (5, MaxPayloadSize { m: 250, n: 242 }),
-                                        (6, MaxPayloadSize { m: 250, n: 242 }),
-                                        (7, MaxPayloadSize { m: 250, n: 242 }),
-                                        (8, MaxPayloadSize { m: 58, n: 50 }),
-                                        (9, MaxPayloadSize { m: 123, n: 115 }),
-                                        (10, MaxPayloadSize { m: 58, n: 50 }),
-                                        (11, MaxPayloadSize { m: 123, n: 115 }),
-                                        (12, MaxPayloadSize { m: 250, n: 242 }),
-                                        (13, MaxPayloadSize { m: 250, n: 242 }),
-                                    ]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                                ),
-                            ]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        ),
-                    ]
-                    .iter()
-                    .cloned()
-                    .collect(),
-                },
+                max_dl_payload_size_per_dr: params::get_max_payload_size_table(
+                    CommonName::EU868,
+                    repeater_compatible,
+                    false,
+                ),
                 rx1_data_rate_table: [
                     (0, vec![0, 0, 0, 0, 0, 0]),
                     (1, vec![1, 0, 0, 0, 0, 0]),
@@ -765,10 +296,6 @@ impl Region for Configuration {
         }
     }
 
-    fn implements_tx_param_setup(&self, _mac_version: MacVersion) -> bool {
-        false
-    }
-
     fn get_data_rate_index(&self, uplink: bool, modulation: &DataRateModulation) -> Result<u8> {
         self.base.get_data_rate_index(uplink, modulation)
     }
@@ -1211,4 +738,38 @@ mod tests {
             c.get_cf_list(MacVersion::LORAWAN_1_0_4).unwrap()
         );
     }
+
+    #[test]
+    fn lr_fhss() {
+        let c = Configuration::new(false);
+
+        // Gateways may report the reduced coding-rate and the exact OCW.
+        let tests = vec![
+            ("1/3", 136719, 8),
+            ("2/3", 136719, 9),
+            ("1/3", 335938, 10),
+            ("2/3", 335938, 11),
+        ];
+        for (coding_rate, occupied_channel_width, dr) in tests {
+            let modulation = DataRateModulation::LrFhss(LrFhssDataRate {
+                coding_rate: coding_rate.to_string(),
+                occupied_channel_width,
+            });
+            assert_eq!(dr, c.get_data_rate_index(true, &modulation).unwrap());
+        }
+
+        let modulation = DataRateModulation::LrFhss(LrFhssDataRate {
+            coding_rate: "2/6".to_string(),
+            occupied_channel_width: 1523000,
+        });
+        assert!(c.get_data_rate_index(true, &modulation).is_err());
+
+        for (dr, m, n) in [(8, 58, 50), (9, 123, 115), (10, 58, 50), (11, 123, 115)] {
+            let pl = c
+                .get_max_dl_payload_size(MacVersion::LORAWAN_1_0_4, Revision::RP002_1_0_4, dr)
+                .unwrap();
+            assert_eq!(m, pl.m);
+            assert_eq!(n, pl.n);
+        }
+    }
 }
@@ -1,11 +1,12 @@
-use std::time::Duration;
+use core::time::Duration;
 
 use anyhow::Result;
 
 use super::{
     Channel, CommonName, DataRate, DataRateModulation, Defaults, LinkADRReqPayload, LoraDataRate,
-    MacVersion, MaxPayloadSize, Region, RegionBaseConfig, Revision,
+    MacVersion, MaxPayloadSize, Region, RegionBaseConfig, Revision, params,
 };
+use crate::prelude::*;
 use crate::{CFList, DevAddr};
 
 pub struct Configuration {
@@ -117,58 +118,11 @@ impl Configuration {
                         },
                     ),
                 ],
-                max_dl_payload_size_per_dr: match repeater_compatible {
-                    true => [(
-                        MacVersion::Latest,
-                        [(
-                            Revision::Latest,
-                            [
-                                (0, MaxPayloadSize { m: 59, n: 51 }),
-                                (1, MaxPayloadSize { m: 123, n: 115 }),
-                                (2, MaxPayloadSize { m: 228, n: 220 }),
-                                (3, MaxPayloadSize { m: 228, n: 220 }),
-                                (4, MaxPayloadSize { m: 228, n: 220 }),
-                                (5, MaxPayloadSize { m: 228, n: 220 }),
-                                (6, MaxPayloadSize { m: 228, n: 220 }),
-                                (7, MaxPayloadSize { m: 228, n: 220 }),
-                            ]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        )]
-                        .iter()
-                        .cloned()
-                        .collect(),
-                    )]
-                    .iter()
-                    .cloned()
-                    .collect(),
-                    false => [(
-                        MacVersion::Latest,
-                        [(
-                            Revision::Latest,
-                            [
-                                (0, MaxPayloadSize { m: 59, n: 51 }),
-                                (1, MaxPayloadSize { m: 123, n: 115 }),
-                                (2, MaxPayloadSize { m: 248, n: 240 }),
-                                (3, MaxPayloadSize { m: 248, n: 240 }),
-                                (4, MaxPayloadSize { m: 248, n: 240 }),
-                                (5, MaxPayloadSize { m: 248, n: 240 }),
-                                (6, MaxPayloadSize { m: 248, n: 240 }),
-                                (7, MaxPayloadSize { m: 248, n: 240 }),
-                            ]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                        )]
-                        .iter()
-                        .cloned()
-                        .collect(),
-                    )]
-                    .iter()
-                    .cloned()
-                    .collect(),
-                },
+                max_dl_payload_size_per_dr: params::get_max_payload_size_table(
+                    CommonName::ISM2400,
+                    repeater_compatible,
+                    false,
+                ),
                 rx1_data_rate_table: [
                     (0, vec![0, 0, 0, 0, 0, 0]),
                     (1, vec![1, 0, 0, 0, 0, 0]),
@@ -274,10 +228,6 @@ impl Region for Configuration {
         }
     }
 
-    fn implements_tx_param_setup(&self, _mac_version: MacVersion) -> bool {
-        true
-    }
-
     fn get_data_rate_index(&self, uplink: bool, modulation: &DataRateModulation) -> Result<u8> {
         self.base.get_data_rate_index(uplink, modulation)
     }
@@ -1,7 +1,7 @@
-use std::collections::{HashMap, HashSet};
-use std::fmt;
-use std::str::FromStr;
-use std::time::Duration;
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
 
 use anyhow::{Context, Result};
 #[cfg(feature = "sqlite")]
@@ -15,6 +15,7 @@ use diesel::{
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Deserializer, Serialize, de};
 
+use crate::prelude::*;
 use crate::{
     CFList, CFListChannelMasks, CFListChannels, ChMask, DevAddr, LinkADRReqPayload, Redundancy,
 };
@@ -28,9 +29,14 @@ pub mod eu868;
 pub mod in865;
 pub mod ism2400;
 pub mod kr920;
+mod params;
 pub mod ru864;
 pub mod us915;
 
+pub use params::{
+    MaxPayloadSizeTable, RegionalParameters, get_max_payload_size_table, get_regional_parameters,
+};
+
 #[allow(non_camel_case_types)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
@@ -62,7 +68,7 @@ impl fmt::Display for CommonName {
 impl FromStr for CommonName {
     type Err = anyhow::Error;
 
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
         Ok(match s {
             "EU868" => CommonName::EU868,
             "US915" => CommonName::US915,
@@ -133,11 +139,10 @@ impl serialize::ToSql<Text, Sqlite> for CommonName {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "diesel", derive(AsExpression, FromSqlRow))]
 #[cfg_attr(feature = "diesel", diesel(sql_type = diesel::sql_types::Text))]
 pub enum Revision {
-    Latest,
     A,
     B,
     RP002_1_0_0,
@@ -146,6 +151,7 @@ pub enum Revision {
     RP002_1_0_3,
     RP002_1_0_4,
     RP002_1_0_5,
+    Latest,
 }
 
 impl Revision {
@@ -178,7 +184,7 @@ impl fmt::Debug for Revision {
 impl FromStr for Revision {
     type Err = anyhow::Error;
 
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
         Ok(match s {
             "A" => Revision::A,
             "B" => Revision::B,
@@ -243,17 +249,17 @@ impl serialize::ToSql<Text, Sqlite> for Revision {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "diesel", derive(AsExpression, FromSqlRow))]
 #[cfg_attr(feature = "diesel", diesel(sql_type = diesel::sql_types::Text))]
 pub enum MacVersion {
-    Latest,
     LORAWAN_1_0_0,
     LORAWAN_1_0_1,
     LORAWAN_1_0_2,
     LORAWAN_1_0_3,
     LORAWAN_1_0_4,
     LORAWAN_1_1_0,
+    Latest,
 }
 
 impl MacVersion {
@@ -284,7 +290,7 @@ impl fmt::Debug for MacVersion {
 impl FromStr for MacVersion {
     type Err = anyhow::Error;
 
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
         Ok(match s {
             "1.0.0" | "1.0" => MacVersion::LORAWAN_1_0_0,
             "1.0.1" => MacVersion::LORAWAN_1_0_1,
@@ -378,6 +384,39 @@ pub struct LrFhssDataRate {
     pub occupied_channel_width: u32,
 }
 
+impl LrFhssDataRate {
+    /// Returns true if both data-rates describe the same LR-FHSS data-rate.
+    ///
+    /// The coding-rate is compared in its reduced form (e.g. 1/3 equals 2/6) and the
+    /// occupied channel width may differ by the hopping grid rounding (e.g. gateways
+    /// may report 136719 Hz instead of 137000 Hz).
+    pub fn matches(&self, other: &LrFhssDataRate) -> bool {
+        const OCW_TOLERANCE: u32 = 1000;
+
+        lr_fhss_coding_rate(&self.coding_rate) == lr_fhss_coding_rate(&other.coding_rate)
+            && self
+                .occupied_channel_width
+                .abs_diff(other.occupied_channel_width)
+                <= OCW_TOLERANCE
+    }
+}
+
+fn lr_fhss_coding_rate(cr: &str) -> Option<(u32, u32)> {
+    let (a, b) = cr.split_once('/')?;
+    let (a, b): (u32, u32) = (a.trim().parse().ok()?, b.trim().parse().ok()?);
+    if a == 0 || b == 0 {
+        return None;
+    }
+
+    let mut x = a;
+    let mut y = b;
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+
+    Some((a / x, b / x))
+}
+
 pub struct Defaults {
     pub rx2_frequency: u32,
     pub rx2_dr: u8,
@@ -533,8 +572,31 @@ pub trait Region {
     /// Returns the defaults.
     fn get_defaults(&self) -> Defaults;
 
-    /// Returns if the device supports the TxParamSetup mac-command.
-    fn implements_tx_param_setup(&self, mac_version: MacVersion) -> bool;
+    /// Returns the regional parameters for the given MAC version and regional-parameters
+    /// revision.
+    fn get_regional_parameters(
+        &self,
+        mac_version: MacVersion,
+        reg_params_revision: Revision,
+    ) -> Result<RegionalParameters> {
+        get_regional_parameters(self.get_name(), mac_version, reg_params_revision)
+    }
+
+    /// Returns if the device supports the TxParamSetup mac-command, given its MAC version
+    /// and regional-parameters revision.
+    ///
+    /// In case the region is not defined by the given revision (e.g. AS923-2 and revision
+    /// A), this falls back to the MAC version rule of the latest revision.
+    fn implements_tx_param_setup(
+        &self,
+        mac_version: MacVersion,
+        reg_params_revision: Revision,
+    ) -> bool {
+        self.get_regional_parameters(mac_version, reg_params_revision)
+            .or_else(|_| self.get_regional_parameters(mac_version, Revision::Latest))
+            .map(|p| p.tx_param_setup)
+            .unwrap_or_default()
+    }
 }
 
 struct RegionBaseConfig {
@@ -542,8 +604,8 @@ struct RegionBaseConfig {
     cf_list_min_dr: u8,
     cf_list_max_dr: u8,
     data_rates: Vec<(u8, DataRate)>,
-    max_dl_payload_size_per_dr: HashMap<MacVersion, HashMap<Revision, HashMap<u8, MaxPayloadSize>>>,
-    rx1_data_rate_table: HashMap<u8, Vec<u8>>,
+    max_dl_payload_size_per_dr: MaxPayloadSizeTable,
+    rx1_data_rate_table: BTreeMap<u8, Vec<u8>>,
     tx_power_offsets: Vec<isize>,
     uplink_channels: Vec<Channel>,
     downlink_channels: Vec<Channel>,
@@ -559,6 +621,13 @@ impl RegionBaseConfig {
             if modulation == &dr.modulation {
                 return Ok(*i);
             }
+
+            if let (DataRateModulation::LrFhss(a), DataRateModulation::LrFhss(b)) =
+                (modulation, &dr.modulation)
+                && a.matches(b)
+            {
+                return Ok(*i);
+            }
         }
 
         Err(anyhow!("Unknown data-rate: {:?}", modulation))
@@ -787,7 +856,7 @@ impl RegionBaseConfig {
     }
 
     fn get_enabled_uplink_data_rates(&self) -> Vec<u8> {
-        let mut out: HashSet<u8> = HashSet::new();
+        let mut out: BTreeSet<u8> = BTreeSet::new();
         for uc in &self.uplink_channels {
             out.extend(uc.data_rates.iter());
         }
@@ -860,8 +929,8 @@ impl RegionBaseConfig {
         device_enabled_channels: &[usize],
     ) -> Vec<LinkADRReqPayload> {
         let enabled_channels = self.get_enabled_uplink_channel_indices();
-        let device_set: HashSet<usize> = device_enabled_channels.iter().cloned().collect();
-        let enabled_set: HashSet<usize> = enabled_channels.iter().cloned().collect();
+        let device_set: BTreeSet<usize> = device_enabled_channels.iter().cloned().collect();
+        let enabled_set: BTreeSet<usize> = enabled_channels.iter().cloned().collect();
 
         // Get the diff between desided and actual channels.
         // This returns the channels that must be activated and / or de-activated
@@ -1009,3 +1078,54 @@ pub fn get(
         CommonName::US915 => Box::new(us915::Configuration::new(repeater_compatible)),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_lr_fhss_uplink_only() {
+        let tests = vec![
+            (CommonName::EU868, vec![8, 9, 10, 11]),
+            (CommonName::US915, vec![5, 6]),
+            (CommonName::CN779, vec![]),
+            (CommonName::EU433, vec![]),
+            (CommonName::AU915, vec![7]),
+            (CommonName::CN470, vec![]),
+            (CommonName::AS923, vec![8, 9, 10, 11]),
+            (CommonName::AS923_2, vec![8, 9, 10, 11]),
+            (CommonName::AS923_3, vec![8, 9, 10, 11]),
+            (CommonName::AS923_4, vec![8, 9, 10, 11]),
+            (CommonName::KR920, vec![]),
+            (CommonName::IN865, vec![]),
+            (CommonName::RU864, vec![]),
+            (CommonName::ISM2400, vec![]),
+        ];
+
+        for (common_name, expected_drs) in tests {
+            for dwell_time_400ms in [false, true] {
+                let c = get(common_name, false, dwell_time_400ms);
+
+                let drs: Vec<u8> = (0..=15)
+                    .filter(|dr| {
+                        matches!(
+                            c.get_data_rate(true, *dr),
+                            Ok(DataRateModulation::LrFhss(_))
+                        )
+                    })
+                    .collect();
+                assert_eq!(expected_drs, drs, "{}", common_name);
+
+                // LR-FHSS is uplink only.
+                for dr in drs {
+                    assert!(
+                        c.get_data_rate(false, dr).is_err(),
+                        "{} DR{}",
+                        common_name,
+                        dr
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use alloc::collections::BTreeMap;
+
+use anyhow::Result;
+
+use super::{CommonName, MacVersion, MaxPayloadSize, Revision};
+
+mod max_payload_size;
+
+/// Max. downlink payload-size per LoRaWAN MAC version, Regional Parameters revision and
+/// data-rate.
+pub type MaxPayloadSizeTable =
+    BTreeMap<MacVersion, BTreeMap<Revision, BTreeMap<u8, MaxPayloadSize>>>;
+
+/// Regional parameters as defined by the LoRaWAN Regional Parameters specification.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegionalParameters {
+    /// Lowest allowed frequency (Hz).
+    pub frequency_min: u32,
+    /// Highest allowed frequency (Hz).
+    pub frequency_max: u32,
+    /// Default max. EIRP (dBm).
+    pub max_eirp: f32,
+    /// Number of default uplink channels.
+    pub default_channels: usize,
+    /// Max. number of uplink channels (including the default channels).
+    pub max_channels: usize,
+    /// Additional channels can be defined (e.g. using the CFList or NewChannelReq).
+    pub user_channels: bool,
+    /// The TxParamSetupReq mac-command is implemented.
+    pub tx_param_setup: bool,
+}
+
+impl RegionalParameters {
+    /// Returns true if the channel (center frequency and bandwidth) is within the
+    /// allowed frequency range.
+    pub fn frequency_in_range(&self, frequency: u32, bandwidth: u32) -> bool {
+        let half_bw = bandwidth / 2;
+        frequency >= self.frequency_min + half_bw && frequency + half_bw <= self.frequency_max
+    }
+}
+
+/// Returns the regional parameters for the given region, LoRaWAN MAC version and
+/// Regional Parameters revision.
+///
+/// Regions that were only introduced by a later Regional Parameters revision return
+/// an error for the earlier revisions.
+pub fn get_regional_parameters(
+    common_name: CommonName,
+    mac_version: MacVersion,
+    revision: Revision,
+) -> Result<RegionalParameters> {
+    let min_revision = match common_name {
+        CommonName::AS923_2 | CommonName::AS923_3 => Some(Revision::RP002_1_0_1),
+        CommonName::AS923_4 => Some(Revision::RP002_1_0_2),
+        _ => None,
+    };
+
+    if let Some(min_revision) = min_revision
+        && revision < min_revision
+    {
+        return Err(anyhow!(
+            "{} is not defined by Regional Parameters revision {}",
+            common_name,
+            revision
+        ));
+    }
+
+    Ok(match common_name {
+        CommonName::EU868 => RegionalParameters {
+            frequency_min: 863000000,
+            frequency_max: 870000000,
+            max_eirp: 16.0,
+            default_channels: 3,
+            max_channels: 16,
+            user_channels: true,
+            tx_param_setup: false,
+        },
+        CommonName::US915 => RegionalParameters {
+            frequency_min: 902000000,
+            frequency_max: 928000000,
+            max_eirp: 30.0,
+            default_channels: 72,
+            max_channels: 72,
+            user_channels: false,
+            tx_param_setup: false,
+        },
+        CommonName::CN779 => RegionalParameters {
+            frequency_min: 779000000,
+            frequency_max: 787000000,
+            max_eirp: 12.15,
+            default_channels: 3,
+            max_channels: 16,
+            user_channels: true,
+            tx_param_setup: false,
+        },
+        CommonName::EU433 => RegionalParameters {
+            frequency_min: 433050000,
+            frequency_max: 434790000,
+            max_eirp: 12.15,
+            default_channels: 3,
+            max_channels: 16,
+            user_channels: true,
+            tx_param_setup: false,
+        },
+        CommonName::AU915 => RegionalParameters {
+            frequency_min: 915000000,
+            frequency_max: 928000000,
+            max_eirp: 30.0,
+            default_channels: 72,
+            max_channels: 72,
+            user_channels: false,
+            // TxParamSetupReq was introduced for AU915 by the LoRaWAN 1.0.2rB
+            // Regional Parameters.
+            tx_param_setup: !(mac_version == MacVersion::LORAWAN_1_0_1
+                || mac_version == MacVersion::LORAWAN_1_0_2),
+        },
+        CommonName::CN470 => RegionalParameters {
+            frequency_min: 470000000,
+            frequency_max: 510000000,
+            max_eirp: 19.15,
+            default_channels: 96,
+            max_channels: 96,
+            user_channels: false,
+            tx_param_setup: false,
+        },
+        CommonName::AS923 => RegionalParameters {
+            frequency_min: 915000000,
+            frequency_max: 928000000,
+            max_eirp: 16.0,
+            default_channels: 2,
+            max_channels: 16,
+            user_channels: true,
+            tx_param_setup: true,
+        },
+        CommonName::AS923_2 => RegionalParameters {
+            frequency_min: 920000000,
+            frequency_max: 923000000,
+            max_eirp: 16.0,
+            default_channels: 2,
+            max_channels: 16,
+            user_channels: true,
+            tx_param_setup: true,
+        },
+        CommonName::AS923_3 => RegionalParameters {
+            frequency_min: 915000000,
+            frequency_max: 921000000,
+            max_eirp: 16.0,
+            default_channels: 2,
+            max_channels: 16,
+            user_channels: true,
+            tx_param_setup: true,
+        },
+        CommonName::AS923_4 => RegionalParameters {
+            frequency_min: 917000000,
+            frequency_max: 920000000,
+            max_eirp: 16.0,
+            default_channels: 2,
+            max_channels: 16,
+            user_channels: true,
+            tx_param_setup: true,
+        },
+        CommonName::KR920 => RegionalParameters {
+            frequency_min: 920900000,
+            frequency_max: 923300000,
+            max_eirp: 14.0,
+            default_channels: 3,
+            max_channels: 16,
+            user_channels: true,
+            tx_param_setup: false,
+        },
+        CommonName::IN865 => RegionalParameters {
+            frequency_min: 865000000,
+            frequency_max: 867000000,
+            max_eirp: 30.0,
+            default_channels: 3,
+            max_channels: 16,
+            user_channels: true,
+            tx_param_setup: false,
+        },
+        CommonName::RU864 => RegionalParameters {
+            frequency_min: 864000000,
+            frequency_max: 870000000,
+            max_eirp: 16.0,
+            default_channels: 2,
+            max_channels: 16,
+            user_channels: true,
+            tx_param_setup: false,
+        },
+        CommonName::ISM2400 => RegionalParameters {
+            frequency_min: 2400000000,
+            frequency_max: 2500000000,
+            max_eirp: 10.0,
+            default_channels: 3,
+            max_channels: 16,
+            user_channels: true,
+            tx_param_setup: true,
+        },
+    })
+}
+
+/// Returns the max. downlink payload-size table for the given region. The table
+/// depends on the repeater compatibility and, for the AS923 regions, on the downlink
+/// dwell-time.
+pub fn get_max_payload_size_table(
+    common_name: CommonName,
+    repeater_compatible: bool,
+    dwell_time_400ms: bool,
+) -> MaxPayloadSizeTable {
+    match common_name {
+        CommonName::EU868 => max_payload_size::eu868(repeater_compatible),
+        CommonName::US915 => max_payload_size::us915(repeater_compatible),
+        CommonName::CN779 => max_payload_size::cn779(repeater_compatible),
+        CommonName::EU433 => max_payload_size::eu433(repeater_compatible),
+        CommonName::AU915 => max_payload_size::au915(repeater_compatible),
+        CommonName::CN470 => max_payload_size::cn470(repeater_compatible),
+        CommonName::AS923 | CommonName::AS923_2 | CommonName::AS923_3 | CommonName::AS923_4 => {
+            max_payload_size::as923(repeater_compatible, dwell_time_400ms)
+        }
+        CommonName::KR920 => max_payload_size::kr920(repeater_compatible),
+        CommonName::IN865 => max_payload_size::in865(repeater_compatible),
+        CommonName::RU864 => max_payload_size::ru864(repeater_compatible),
+        CommonName::ISM2400 => max_payload_size::ism2400(repeater_compatible),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_regional_parameters() {
+        let p = get_regional_parameters(
+            CommonName::EU868,
+            MacVersion::LORAWAN_1_0_4,
+            Revision::RP002_1_0_4,
+        )
+        .unwrap();
+        assert_eq!(863000000, p.frequency_min);
+        assert_eq!(870000000, p.frequency_max);
+        assert_eq!(16.0, p.max_eirp);
+        assert!(p.frequency_in_range(868100000, 125000));
+        assert!(!p.frequency_in_range(862950000, 125000));
+        assert!(!p.frequency_in_range(869950000, 125000));
+
+        // TxParamSetup depends on the MAC version for AU915.
+        assert!(
+            !get_regional_parameters(CommonName::AU915, MacVersion::LORAWAN_1_0_2, Revision::B)
+                .unwrap()
+                .tx_param_setup
+        );
+        assert!(
+            get_regional_parameters(CommonName::AU915, MacVersion::LORAWAN_1_0_3, Revision::A)
+                .unwrap()
+                .tx_param_setup
+        );
+
+        // AS923-4 was introduced by RP002-1.0.2.
+        assert!(
+            get_regional_parameters(
+                CommonName::AS923_4,
+                MacVersion::LORAWAN_1_0_4,
+                Revision::RP002_1_0_1
+            )
+            .is_err()
+        );
+        assert!(
+            get_regional_parameters(
+                CommonName::AS923_4,
+                MacVersion::LORAWAN_1_0_4,
+                Revision::RP002_1_0_2
+            )
+            .is_ok()
+        );
+        assert!(
+            get_regional_parameters(CommonName::AS923_4, MacVersion::Latest, Revision::Latest)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_get_max_payload_size_table() {
+        let get = |t: &MaxPayloadSizeTable, dr: u8| {
+            let pl = &t[&MacVersion::Latest][&Revision::RP002_1_0_4][&dr];
+            (pl.m, pl.n)
+        };
+
+        let t = get_max_payload_size_table(CommonName::EU868, false, false);
+        assert_eq!((59, 51), get(&t, 0));
+        assert_eq!((250, 242), get(&t, 5));
+
+        // The AS923 table depends on the dwell-time.
+        let t = get_max_payload_size_table(CommonName::AS923, false, false);
+        assert_eq!((59, 51), get(&t, 0));
+        let t = get_max_payload_size_table(CommonName::AS923_2, false, true);
+        assert_eq!((0, 0), get(&t, 0));
+    }
+}
@@ -0,0 +1,3979 @@
+use super::MaxPayloadSizeTable;
+use crate::region::{MacVersion, MaxPayloadSize, Revision};
+
+pub fn as923(repeater_compatible: bool, dwell_time_400ms: bool) -> MaxPayloadSizeTable {
+    match repeater_compatible {
+        true => match dwell_time_400ms {
+            // repeater + dwell time
+            true => [
+                (
+                    MacVersion::LORAWAN_1_0_2,
+                    [(
+                        Revision::Latest, // B
+                        [
+                            (0, MaxPayloadSize { m: 0, n: 0 }),
+                            (1, MaxPayloadSize { m: 0, n: 0 }),
+                            (2, MaxPayloadSize { m: 19, n: 11 }),
+                            (3, MaxPayloadSize { m: 61, n: 53 }),
+                            (4, MaxPayloadSize { m: 133, n: 125 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    )]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                ),
+                (
+                    MacVersion::LORAWAN_1_0_3,
+                    [(
+                        Revision::Latest, // A
+                        [
+                            (0, MaxPayloadSize { m: 0, n: 0 }),
+                            (1, MaxPayloadSize { m: 0, n: 0 }),
+                            (2, MaxPayloadSize { m: 19, n: 11 }),
+                            (3, MaxPayloadSize { m: 61, n: 53 }),
+                            (4, MaxPayloadSize { m: 133, n: 125 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    )]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                ),
+                (
+                    MacVersion::LORAWAN_1_1_0,
+                    [(
+                        Revision::Latest, // A
+                        [
+                            (0, MaxPayloadSize { m: 0, n: 0 }),
+                            (1, MaxPayloadSize { m: 0, n: 0 }),
+                            (2, MaxPayloadSize { m: 19, n: 11 }),
+                            (3, MaxPayloadSize { m: 61, n: 53 }),
+                            (4, MaxPayloadSize { m: 133, n: 125 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    )]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                ),
+                (
+                    MacVersion::Latest,
+                    [
+                        (
+                            Revision::RP002_1_0_0, // RP002-1.0.0
+                            [
+                                (0, MaxPayloadSize { m: 0, n: 0 }),
+                                (1, MaxPayloadSize { m: 0, n: 0 }),
+                                (2, MaxPayloadSize { m: 19, n: 11 }),
+                                (3, MaxPayloadSize { m: 61, n: 53 }),
+                                (4, MaxPayloadSize { m: 133, n: 125 }),
+                                (5, MaxPayloadSize { m: 230, n: 222 }),
+                                (6, MaxPayloadSize { m: 230, n: 222 }),
+                                (7, MaxPayloadSize { m: 230, n: 222 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::RP002_1_0_1, // RP002-1.0.1
+                            [
+                                (0, MaxPayloadSize { m: 0, n: 0 }),
+                                (1, MaxPayloadSize { m: 0, n: 0 }),
+                                (2, MaxPayloadSize { m: 19, n: 11 }),
+                                (3, MaxPayloadSize { m: 61, n: 53 }),
+                                (4, MaxPayloadSize { m: 133, n: 125 }),
+                                (5, MaxPayloadSize { m: 230, n: 222 }),
+                                (6, MaxPayloadSize { m: 230, n: 222 }),
+                                (7, MaxPayloadSize { m: 230, n: 222 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::RP002_1_0_2, // RP002-1.0.2
+                            [
+                                (0, MaxPayloadSize { m: 0, n: 0 }),
+                                (1, MaxPayloadSize { m: 0, n: 0 }),
+                                (2, MaxPayloadSize { m: 19, n: 11 }),
+                                (3, MaxPayloadSize { m: 61, n: 53 }),
+                                (4, MaxPayloadSize { m: 133, n: 125 }),
+                                (5, MaxPayloadSize { m: 230, n: 222 }),
+                                (6, MaxPayloadSize { m: 230, n: 222 }),
+                                (7, MaxPayloadSize { m: 230, n: 222 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::RP002_1_0_3,
+                            [
+                                (0, MaxPayloadSize { m: 0, n: 0 }),
+                                (1, MaxPayloadSize { m: 0, n: 0 }),
+                                (2, MaxPayloadSize { m: 19, n: 11 }),
+                                (3, MaxPayloadSize { m: 61, n: 53 }),
+                                (4, MaxPayloadSize { m: 133, n: 125 }),
+                                (5, MaxPayloadSize { m: 230, n: 222 }),
+                                (6, MaxPayloadSize { m: 230, n: 222 }),
+                                (7, MaxPayloadSize { m: 230, n: 222 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::RP002_1_0_4,
+                            [
+                                (0, MaxPayloadSize { m: 0, n: 0 }),
+                                (1, MaxPayloadSize { m: 0, n: 0 }),
+                                (2, MaxPayloadSize { m: 19, n: 11 }),
+                                (3, MaxPayloadSize { m: 61, n: 53 }),
+                                (4, MaxPayloadSize { m: 133, n: 125 }),
+                                (5, MaxPayloadSize { m: 230, n: 222 }),
+                                (6, MaxPayloadSize { m: 230, n: 222 }),
+                                (7, MaxPayloadSize { m: 230, n: 222 }),
+                                (8, MaxPayloadSize { m: 58, n: 50 }),
+                                (9, MaxPayloadSize { m: 123, n: 115 }),
+                                (10, MaxPayloadSize { m: 58, n: 50 }),
+                                (11, MaxPayloadSize { m: 123, n: 115 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::Latest, // RP002-1.0.5
+                            [
+                                (0, MaxPayloadSize { m: 0, n: 0 }),
+                                (1, MaxPayloadSize { m: 0, n: 0 }),
+                                (2, MaxPayloadSize { m: 19, n: 11 }),
+                                (3, MaxPayloadSize { m: 61, n: 53 }),
+                                (4, MaxPayloadSize { m: 133, n: 125 }),
+                                (5, MaxPayloadSize { m: 230, n: 222 }),
+                                (6, MaxPayloadSize { m: 230, n: 222 }),
+                                (7, MaxPayloadSize { m: 230, n: 222 }),
+                                (8, MaxPayloadSize { m: 58, n: 50 }),
+                                (9, MaxPayloadSize { m: 123, n: 115 }),
+                                (10, MaxPayloadSize { m: 58, n: 50 }),
+                                (11, MaxPayloadSize { m: 123, n: 115 }),
+                                (12, MaxPayloadSize { m: 230, n: 222 }),
+                                (13, MaxPayloadSize { m: 230, n: 222 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                ),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+            // repeater + no dwell time
+            false => [
+                (
+                    MacVersion::LORAWAN_1_0_2,
+                    [(
+                        Revision::Latest, // B
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    )]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                ),
+                (
+                    MacVersion::LORAWAN_1_0_3,
+                    [(
+                        Revision::Latest, // A
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    )]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                ),
+                (
+                    MacVersion::LORAWAN_1_1_0,
+                    [(
+                        Revision::Latest, // A
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    )]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                ),
+                (
+                    MacVersion::Latest,
+                    [
+                        (
+                            Revision::RP002_1_0_0, // RP002-1.0.0
+                            [
+                                (0, MaxPayloadSize { m: 59, n: 51 }),
+                                (1, MaxPayloadSize { m: 59, n: 51 }),
+                                (2, MaxPayloadSize { m: 59, n: 51 }),
+                                (3, MaxPayloadSize { m: 123, n: 115 }),
+                                (4, MaxPayloadSize { m: 230, n: 222 }),
+                                (5, MaxPayloadSize { m: 230, n: 222 }),
+                                (6, MaxPayloadSize { m: 230, n: 222 }),
+                                (7, MaxPayloadSize { m: 230, n: 222 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::RP002_1_0_1, // RP002-1.0.1
+                            [
+                                (0, MaxPayloadSize { m: 59, n: 51 }),
+                                (1, MaxPayloadSize { m: 59, n: 51 }),
+                                (2, MaxPayloadSize { m: 123, n: 115 }),
+                                (3, MaxPayloadSize { m: 123, n: 115 }),
+                                (4, MaxPayloadSize { m: 230, n: 222 }),
+                                (5, MaxPayloadSize { m: 230, n: 222 }),
+                                (6, MaxPayloadSize { m: 230, n: 222 }),
+                                (7, MaxPayloadSize { m: 230, n: 222 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::RP002_1_0_2, // RP002-1.0.2
+                            [
+                                (0, MaxPayloadSize { m: 59, n: 51 }),
+                                (1, MaxPayloadSize { m: 59, n: 51 }),
+                                (2, MaxPayloadSize { m: 123, n: 115 }),
+                                (3, MaxPayloadSize { m: 123, n: 115 }),
+                                (4, MaxPayloadSize { m: 230, n: 222 }),
+                                (5, MaxPayloadSize { m: 230, n: 222 }),
+                                (6, MaxPayloadSize { m: 230, n: 222 }),
+                                (7, MaxPayloadSize { m: 230, n: 222 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::RP002_1_0_3,
+                            [
+                                (0, MaxPayloadSize { m: 59, n: 51 }),
+                                (1, MaxPayloadSize { m: 59, n: 51 }),
+                                (2, MaxPayloadSize { m: 123, n: 115 }),
+                                (3, MaxPayloadSize { m: 123, n: 115 }),
+                                (4, MaxPayloadSize { m: 230, n: 222 }),
+                                (5, MaxPayloadSize { m: 230, n: 222 }),
+                                (6, MaxPayloadSize { m: 230, n: 222 }),
+                                (7, MaxPayloadSize { m: 230, n: 222 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::RP002_1_0_4,
+                            [
+                                (0, MaxPayloadSize { m: 59, n: 51 }),
+                                (1, MaxPayloadSize { m: 59, n: 51 }),
+                                (2, MaxPayloadSize { m: 123, n: 115 }),
+                                (3, MaxPayloadSize { m: 123, n: 115 }),
+                                (4, MaxPayloadSize { m: 230, n: 222 }),
+                                (5, MaxPayloadSize { m: 230, n: 222 }),
+                                (6, MaxPayloadSize { m: 230, n: 222 }),
+                                (7, MaxPayloadSize { m: 230, n: 222 }),
+                                (8, MaxPayloadSize { m: 58, n: 50 }),
+                                (9, MaxPayloadSize { m: 123, n: 115 }),
+                                (10, MaxPayloadSize { m: 58, n: 50 }),
+                                (11, MaxPayloadSize { m: 123, n: 115 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::Latest, // RP002-1.0.5
+                            [
+                                (0, MaxPayloadSize { m: 59, n: 51 }),
+                                (1, MaxPayloadSize { m: 59, n: 51 }),
+                                (2, MaxPayloadSize { m: 123, n: 115 }),
+                                (3, MaxPayloadSize { m: 123, n: 115 }),
+                                (4, MaxPayloadSize { m: 230, n: 222 }),
+                                (5, MaxPayloadSize { m: 230, n: 222 }),
+                                (6, MaxPayloadSize { m: 230, n: 222 }),
+                                (7, MaxPayloadSize { m: 230, n: 222 }),
+                                (8, MaxPayloadSize { m: 58, n: 50 }),
+                                (9, MaxPayloadSize { m: 123, n: 115 }),
+                                (10, MaxPayloadSize { m: 58, n: 50 }),
+                                (11, MaxPayloadSize { m: 123, n: 115 }),
+                                (12, MaxPayloadSize { m: 230, n: 222 }),
+                                (13, MaxPayloadSize { m: 230, n: 222 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                ),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        },
+        false => match dwell_time_400ms {
+            // no repeater + dwell time
+            true => [
+                (
+                    MacVersion::LORAWAN_1_0_2,
+                    [(
+                        Revision::Latest, // B
+                        [
+                            (0, MaxPayloadSize { m: 0, n: 0 }),
+                            (1, MaxPayloadSize { m: 0, n: 0 }),
+                            (2, MaxPayloadSize { m: 19, n: 11 }),
+                            (3, MaxPayloadSize { m: 61, n: 53 }),
+                            (4, MaxPayloadSize { m: 133, n: 125 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    )]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                ),
+                (
+                    MacVersion::LORAWAN_1_0_3,
+                    [(
+                        Revision::Latest, // A
+                        [
+                            (0, MaxPayloadSize { m: 0, n: 0 }),
+                            (1, MaxPayloadSize { m: 0, n: 0 }),
+                            (2, MaxPayloadSize { m: 19, n: 11 }),
+                            (3, MaxPayloadSize { m: 61, n: 53 }),
+                            (4, MaxPayloadSize { m: 133, n: 125 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    )]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                ),
+                (
+                    MacVersion::LORAWAN_1_1_0,
+                    [(
+                        Revision::Latest, // A
+                        [
+                            (0, MaxPayloadSize { m: 0, n: 0 }),
+                            (1, MaxPayloadSize { m: 0, n: 0 }),
+                            (2, MaxPayloadSize { m: 19, n: 11 }),
+                            (3, MaxPayloadSize { m: 61, n: 53 }),
+                            (4, MaxPayloadSize { m: 133, n: 125 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    )]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                ),
+                (
+                    MacVersion::Latest,
+                    [
+                        (
+                            Revision::RP002_1_0_0, // RP002-1.0.0
+                            [
+                                (0, MaxPayloadSize { m: 0, n: 0 }),
+                                (1, MaxPayloadSize { m: 0, n: 0 }),
+                                (2, MaxPayloadSize { m: 19, n: 11 }),
+                                (3, MaxPayloadSize { m: 61, n: 53 }),
+                                (4, MaxPayloadSize { m: 133, n: 125 }),
+                                (5, MaxPayloadSize { m: 250, n: 242 }),
+                                (6, MaxPayloadSize { m: 250, n: 242 }),
+                                (7, MaxPayloadSize { m: 250, n: 242 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::RP002_1_0_1, // RP002-1.0.1
+                            [
+                                (0, MaxPayloadSize { m: 0, n: 0 }),
+                                (1, MaxPayloadSize { m: 0, n: 0 }),
+                                (2, MaxPayloadSize { m: 19, n: 11 }),
+                                (3, MaxPayloadSize { m: 61, n: 53 }),
+                                (4, MaxPayloadSize { m: 133, n: 125 }),
+                                (5, MaxPayloadSize { m: 250, n: 242 }),
+                                (6, MaxPayloadSize { m: 250, n: 242 }),
+                                (7, MaxPayloadSize { m: 250, n: 242 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::RP002_1_0_2, // RP002-1.0.2
+                            [
+                                (0, MaxPayloadSize { m: 0, n: 0 }),
+                                (1, MaxPayloadSize { m: 0, n: 0 }),
+                                (2, MaxPayloadSize { m: 19, n: 11 }),
+                                (3, MaxPayloadSize { m: 61, n: 53 }),
+                                (4, MaxPayloadSize { m: 133, n: 125 }),
+                                (5, MaxPayloadSize { m: 250, n: 242 }),
+                                (6, MaxPayloadSize { m: 250, n: 242 }),
+                                (7, MaxPayloadSize { m: 250, n: 242 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::RP002_1_0_3,
+                            [
+                                (0, MaxPayloadSize { m: 0, n: 0 }),
+                                (1, MaxPayloadSize { m: 0, n: 0 }),
+                                (2, MaxPayloadSize { m: 19, n: 11 }),
+                                (3, MaxPayloadSize { m: 61, n: 53 }),
+                                (4, MaxPayloadSize { m: 133, n: 125 }),
+                                (5, MaxPayloadSize { m: 250, n: 242 }),
+                                (6, MaxPayloadSize { m: 250, n: 242 }),
+                                (7, MaxPayloadSize { m: 250, n: 242 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::RP002_1_0_4,
+                            [
+                                (0, MaxPayloadSize { m: 0, n: 0 }),
+                                (1, MaxPayloadSize { m: 0, n: 0 }),
+                                (2, MaxPayloadSize { m: 19, n: 11 }),
+                                (3, MaxPayloadSize { m: 61, n: 53 }),
+                                (4, MaxPayloadSize { m: 133, n: 125 }),
+                                (5, MaxPayloadSize { m: 250, n: 242 }),
+                                (6, MaxPayloadSize { m: 250, n: 242 }),
+                                (7, MaxPayloadSize { m: 250, n: 242 }),
+                                (8, MaxPayloadSize { m: 58, n: 50 }),
+                                (9, MaxPayloadSize { m: 123, n: 115 }),
+                                (10, MaxPayloadSize { m: 58, n: 50 }),
+                                (11, MaxPayloadSize { m: 123, n: 115 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::Latest, // RP002-1.0.5
+                            [
+                                (0, MaxPayloadSize { m: 0, n: 0 }),
+                                (1, MaxPayloadSize { m: 0, n: 0 }),
+                                (2, MaxPayloadSize { m: 19, n: 11 }),
+                                (3, MaxPayloadSize { m: 61, n: 53 }),
+                                (4, MaxPayloadSize { m: 133, n: 125 }),
+                                (5, MaxPayloadSize { m: 250, n: 242 }),
+                                (6, MaxPayloadSize { m: 250, n: 242 }),
+                                (7, MaxPayloadSize { m: 250, n: 242 }),
+                                (8, MaxPayloadSize { m: 58, n: 50 }),
+                                (9, MaxPayloadSize { m: 123, n: 115 }),
+                                (10, MaxPayloadSize { m: 58, n: 50 }),
+                                (11, MaxPayloadSize { m: 123, n: 115 }),
+                                (12, MaxPayloadSize { m: 250, n: 242 }),
+                                (13, MaxPayloadSize { m: 250, n: 242 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                ),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+            // no repeater + no dwell time
+            false => [
+                (
+                    MacVersion::LORAWAN_1_0_2,
+                    [(
+                        Revision::Latest, // B
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    )]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                ),
+                (
+                    MacVersion::LORAWAN_1_0_3,
+                    [(
+                        Revision::Latest, // A
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    )]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                ),
+                (
+                    MacVersion::LORAWAN_1_1_0,
+                    [(
+                        Revision::Latest, // A
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    )]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                ),
+                (
+                    MacVersion::Latest,
+                    [
+                        (
+                            Revision::RP002_1_0_0, // RP002-1.0.0
+                            [
+                                (0, MaxPayloadSize { m: 59, n: 51 }),
+                                (1, MaxPayloadSize { m: 59, n: 51 }),
+                                (2, MaxPayloadSize { m: 59, n: 51 }),
+                                (3, MaxPayloadSize { m: 123, n: 115 }),
+                                (4, MaxPayloadSize { m: 250, n: 242 }),
+                                (5, MaxPayloadSize { m: 250, n: 242 }),
+                                (6, MaxPayloadSize { m: 250, n: 242 }),
+                                (7, MaxPayloadSize { m: 250, n: 242 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::RP002_1_0_1, // RP002-1.0.1
+                            [
+                                (0, MaxPayloadSize { m: 59, n: 51 }),
+                                (1, MaxPayloadSize { m: 59, n: 51 }),
+                                (2, MaxPayloadSize { m: 123, n: 115 }),
+                                (3, MaxPayloadSize { m: 123, n: 115 }),
+                                (4, MaxPayloadSize { m: 250, n: 242 }),
+                                (5, MaxPayloadSize { m: 250, n: 242 }),
+                                (6, MaxPayloadSize { m: 250, n: 242 }),
+                                (7, MaxPayloadSize { m: 250, n: 242 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::RP002_1_0_2, // RP002-1.0.2
+                            [
+                                (0, MaxPayloadSize { m: 59, n: 51 }),
+                                (1, MaxPayloadSize { m: 59, n: 51 }),
+                                (2, MaxPayloadSize { m: 123, n: 115 }),
+                                (3, MaxPayloadSize { m: 123, n: 115 }),
+                                (4, MaxPayloadSize { m: 250, n: 242 }),
+                                (5, MaxPayloadSize { m: 250, n: 242 }),
+                                (6, MaxPayloadSize { m: 250, n: 242 }),
+                                (7, MaxPayloadSize { m: 250, n: 242 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::RP002_1_0_3,
+                            [
+                                (0, MaxPayloadSize { m: 59, n: 51 }),
+                                (1, MaxPayloadSize { m: 59, n: 51 }),
+                                (2, MaxPayloadSize { m: 123, n: 115 }),
+                                (3, MaxPayloadSize { m: 123, n: 115 }),
+                                (4, MaxPayloadSize { m: 250, n: 242 }),
+                                (5, MaxPayloadSize { m: 250, n: 242 }),
+                                (6, MaxPayloadSize { m: 250, n: 242 }),
+                                (7, MaxPayloadSize { m: 250, n: 242 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::RP002_1_0_4,
+                            [
+                                (0, MaxPayloadSize { m: 59, n: 51 }),
+                                (1, MaxPayloadSize { m: 59, n: 51 }),
+                                (2, MaxPayloadSize { m: 123, n: 115 }),
+                                (3, MaxPayloadSize { m: 123, n: 115 }),
+                                (4, MaxPayloadSize { m: 250, n: 242 }),
+                                (5, MaxPayloadSize { m: 250, n: 242 }),
+                                (6, MaxPayloadSize { m: 250, n: 242 }),
+                                (7, MaxPayloadSize { m: 250, n: 242 }),
+                                (8, MaxPayloadSize { m: 58, n: 50 }),
+                                (9, MaxPayloadSize { m: 123, n: 115 }),
+                                (10, MaxPayloadSize { m: 58, n: 50 }),
+                                (11, MaxPayloadSize { m: 123, n: 115 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        (
+                            Revision::Latest, // RP002-1.0.5
+                            [
+                                (0, MaxPayloadSize { m: 59, n: 51 }),
+                                (1, MaxPayloadSize { m: 59, n: 51 }),
+                                (2, MaxPayloadSize { m: 123, n: 115 }),
+                                (3, MaxPayloadSize { m: 123, n: 115 }),
+                                (4, MaxPayloadSize { m: 250, n: 242 }),
+                                (5, MaxPayloadSize { m: 250, n: 242 }),
+                                (6, MaxPayloadSize { m: 250, n: 242 }),
+                                (7, MaxPayloadSize { m: 250, n: 242 }),
+                                (8, MaxPayloadSize { m: 58, n: 50 }),
+                                (9, MaxPayloadSize { m: 123, n: 115 }),
+                                (10, MaxPayloadSize { m: 58, n: 50 }),
+                                (11, MaxPayloadSize { m: 123, n: 115 }),
+                                (12, MaxPayloadSize { m: 250, n: 242 }),
+                                (13, MaxPayloadSize { m: 250, n: 242 }),
+                            ]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                ),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        },
+    }
+}
+
+pub fn au915(repeater_compatible: bool) -> MaxPayloadSizeTable {
+    match repeater_compatible {
+        // repeater compatible
+        true => [
+            (
+                MacVersion::LORAWAN_1_0_1,
+                [(
+                    Revision::Latest,
+                    [
+                        (8, MaxPayloadSize { m: 41, n: 33 }),
+                        (9, MaxPayloadSize { m: 117, n: 109 }),
+                        (10, MaxPayloadSize { m: 230, n: 222 }),
+                        (11, MaxPayloadSize { m: 230, n: 222 }),
+                        (12, MaxPayloadSize { m: 230, n: 222 }),
+                        (13, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_2,
+                [
+                    (
+                        Revision::A,
+                        [
+                            (8, MaxPayloadSize { m: 41, n: 33 }),
+                            (9, MaxPayloadSize { m: 117, n: 109 }),
+                            (10, MaxPayloadSize { m: 230, n: 222 }),
+                            (11, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::Latest, // B
+                        [
+                            (8, MaxPayloadSize { m: 41, n: 33 }),
+                            (9, MaxPayloadSize { m: 117, n: 109 }),
+                            (10, MaxPayloadSize { m: 230, n: 222 }),
+                            (11, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_3,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (8, MaxPayloadSize { m: 41, n: 33 }),
+                        (9, MaxPayloadSize { m: 117, n: 109 }),
+                        (10, MaxPayloadSize { m: 230, n: 222 }),
+                        (11, MaxPayloadSize { m: 230, n: 222 }),
+                        (12, MaxPayloadSize { m: 230, n: 222 }),
+                        (13, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_1_0,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (8, MaxPayloadSize { m: 41, n: 33 }),
+                        (9, MaxPayloadSize { m: 117, n: 109 }),
+                        (10, MaxPayloadSize { m: 230, n: 222 }),
+                        (11, MaxPayloadSize { m: 230, n: 222 }),
+                        (12, MaxPayloadSize { m: 230, n: 222 }),
+                        (13, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [
+                    (
+                        Revision::RP002_1_0_0,
+                        [
+                            (8, MaxPayloadSize { m: 41, n: 33 }),
+                            (9, MaxPayloadSize { m: 117, n: 109 }),
+                            (10, MaxPayloadSize { m: 230, n: 222 }),
+                            (11, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_1,
+                        [
+                            (8, MaxPayloadSize { m: 41, n: 33 }),
+                            (9, MaxPayloadSize { m: 117, n: 109 }),
+                            (10, MaxPayloadSize { m: 230, n: 222 }),
+                            (11, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_2,
+                        [
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 230, n: 222 }),
+                            (11, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_3,
+                        [
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 230, n: 222 }),
+                            (11, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_4,
+                        [
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 230, n: 222 }),
+                            (11, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::Latest, // RP002-1.0.5
+                        [
+                            (0, MaxPayloadSize { m: 230, n: 222 }),
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 230, n: 222 }),
+                            (11, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                            (14, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+        // no repeater
+        false => [
+            (
+                MacVersion::LORAWAN_1_0_3, // LoRaWAN < 1.0.3 + < LoRaWAN 1.1.0B does not have dwell-time
+                [(
+                    Revision::Latest, // A
+                    [
+                        (8, MaxPayloadSize { m: 61, n: 53 }),
+                        (9, MaxPayloadSize { m: 137, n: 129 }),
+                        (10, MaxPayloadSize { m: 250, n: 242 }),
+                        (11, MaxPayloadSize { m: 250, n: 242 }),
+                        (12, MaxPayloadSize { m: 250, n: 242 }),
+                        (13, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_1_0,
+                [(
+                    Revision::Latest, // B
+                    [
+                        (8, MaxPayloadSize { m: 61, n: 53 }),
+                        (9, MaxPayloadSize { m: 137, n: 129 }),
+                        (10, MaxPayloadSize { m: 250, n: 242 }),
+                        (11, MaxPayloadSize { m: 250, n: 242 }),
+                        (12, MaxPayloadSize { m: 250, n: 242 }),
+                        (13, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [
+                    (
+                        Revision::RP002_1_0_0,
+                        [
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 250, n: 222 }),
+                            (11, MaxPayloadSize { m: 250, n: 250 }),
+                            (12, MaxPayloadSize { m: 250, n: 250 }),
+                            (13, MaxPayloadSize { m: 250, n: 250 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_1,
+                        [
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 250, n: 242 }),
+                            (11, MaxPayloadSize { m: 250, n: 242 }),
+                            (12, MaxPayloadSize { m: 250, n: 242 }),
+                            (13, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_2,
+                        [
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 250, n: 242 }),
+                            (11, MaxPayloadSize { m: 250, n: 242 }),
+                            (12, MaxPayloadSize { m: 250, n: 242 }),
+                            (13, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_3,
+                        [
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 250, n: 242 }),
+                            (11, MaxPayloadSize { m: 250, n: 242 }),
+                            (12, MaxPayloadSize { m: 250, n: 242 }),
+                            (13, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_4,
+                        [
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 250, n: 242 }),
+                            (11, MaxPayloadSize { m: 250, n: 242 }),
+                            (12, MaxPayloadSize { m: 250, n: 242 }),
+                            (13, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::Latest, // RP002-1.0.5
+                        [
+                            (0, MaxPayloadSize { m: 250, n: 242 }),
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 250, n: 242 }),
+                            (11, MaxPayloadSize { m: 250, n: 242 }),
+                            (12, MaxPayloadSize { m: 250, n: 242 }),
+                            (13, MaxPayloadSize { m: 250, n: 242 }),
+                            (14, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+    }
+}
+
+pub fn cn470(repeater_compatible: bool) -> MaxPayloadSizeTable {
+    match repeater_compatible {
+        true => [
+            (
+                MacVersion::LORAWAN_1_0_1,
+                [(
+                    Revision::Latest,
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_2,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_3,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_1_0,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [
+                    (
+                        Revision::RP002_1_0_0,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_1,
+                        [
+                            (0, MaxPayloadSize { m: 0, n: 0 }),
+                            (1, MaxPayloadSize { m: 31, n: 23 }),
+                            (2, MaxPayloadSize { m: 94, n: 86 }),
+                            (3, MaxPayloadSize { m: 172, n: 164 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::Latest, // RP002-1.0.2, PR002-1.0.3
+                        [
+                            (0, MaxPayloadSize { m: 0, n: 0 }),
+                            (1, MaxPayloadSize { m: 31, n: 23 }),
+                            (2, MaxPayloadSize { m: 94, n: 86 }),
+                            (3, MaxPayloadSize { m: 192, n: 184 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+        false => [
+            (
+                MacVersion::LORAWAN_1_0_1,
+                [(
+                    Revision::Latest,
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_2,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_3,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_1_0,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [
+                    (
+                        Revision::RP002_1_0_0,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::Latest, // PR002-1.0.1, RP002-1.0.2, PR002-1.0.3
+                        [
+                            (0, MaxPayloadSize { m: 0, n: 0 }),
+                            (1, MaxPayloadSize { m: 31, n: 23 }),
+                            (2, MaxPayloadSize { m: 94, n: 86 }),
+                            (3, MaxPayloadSize { m: 192, n: 184 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+    }
+}
+
+pub fn cn779(repeater_compatible: bool) -> MaxPayloadSizeTable {
+    match repeater_compatible {
+        true => [
+            (
+                MacVersion::LORAWAN_1_0_0,
+                [(
+                    Revision::Latest,
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_1,
+                [(
+                    Revision::Latest,
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_2,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_3,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [(
+                    Revision::Latest, // RP002-1.0.0, RP002-1.0.2, RP002-1.0.3
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 230, n: 222 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+        false => [
+            (
+                MacVersion::LORAWAN_1_0_0,
+                [(
+                    Revision::Latest,
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_1,
+                [(
+                    Revision::Latest,
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_2,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_3,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [(
+                    Revision::Latest, // RP002-1.0.0, RP002-1.0.2, RP002-1.0.3
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+    }
+}
+
+pub fn eu433(repeater_compatible: bool) -> MaxPayloadSizeTable {
+    match repeater_compatible {
+        true => [
+            (
+                MacVersion::LORAWAN_1_0_0,
+                [(
+                    Revision::Latest,
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 230, n: 222 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_1,
+                [(
+                    Revision::Latest,
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 230, n: 222 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_2,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 230, n: 222 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_3,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 230, n: 222 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_1_0,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 230, n: 222 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [
+                    (
+                        Revision::RP002_1_0_0,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_1,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_2,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_3,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_4,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::Latest, // RP002-1.0.5
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+        false => [
+            (
+                MacVersion::LORAWAN_1_0_0,
+                [(
+                    Revision::Latest,
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_1,
+                [(
+                    Revision::Latest,
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_2,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_3,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_1_0,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [
+                    (
+                        Revision::RP002_1_0_0,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_1,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_2,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_3,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_4,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::Latest, // RP002-1.0.5
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                            (12, MaxPayloadSize { m: 250, n: 242 }),
+                            (13, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+    }
+}
+
+pub fn eu868(repeater_compatible: bool) -> MaxPayloadSizeTable {
+    match repeater_compatible {
+        true => [
+            (
+                MacVersion::LORAWAN_1_0_0,
+                [(
+                    Revision::Latest,
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 230, n: 222 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_1,
+                [(
+                    Revision::Latest,
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 230, n: 222 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_2,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 230, n: 222 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_3,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 230, n: 222 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_1_0,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 230, n: 222 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [
+                    (
+                        Revision::RP002_1_0_0,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_1,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_2,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                            (8, MaxPayloadSize { m: 58, n: 50 }),
+                            (9, MaxPayloadSize { m: 123, n: 115 }),
+                            (10, MaxPayloadSize { m: 58, n: 50 }),
+                            (11, MaxPayloadSize { m: 123, n: 115 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_3,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                            (8, MaxPayloadSize { m: 58, n: 50 }),
+                            (9, MaxPayloadSize { m: 123, n: 115 }),
+                            (10, MaxPayloadSize { m: 58, n: 50 }),
+                            (11, MaxPayloadSize { m: 123, n: 115 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_4,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                            (8, MaxPayloadSize { m: 58, n: 50 }),
+                            (9, MaxPayloadSize { m: 123, n: 115 }),
+                            (10, MaxPayloadSize { m: 58, n: 50 }),
+                            (11, MaxPayloadSize { m: 123, n: 115 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::Latest, // RP002_1_0_5
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                            (8, MaxPayloadSize { m: 58, n: 50 }),
+                            (9, MaxPayloadSize { m: 123, n: 115 }),
+                            (10, MaxPayloadSize { m: 58, n: 50 }),
+                            (11, MaxPayloadSize { m: 123, n: 115 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+        false => [
+            (
+                MacVersion::LORAWAN_1_0_0,
+                [(
+                    Revision::Latest,
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_1,
+                [(
+                    Revision::Latest,
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_2,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_3,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_1_0,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [
+                    (
+                        Revision::RP002_1_0_0,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_1,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_2,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 222 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                            (8, MaxPayloadSize { m: 58, n: 50 }),
+                            (9, MaxPayloadSize { m: 123, n: 115 }),
+                            (10, MaxPayloadSize { m: 58, n: 50 }),
+                            (11, MaxPayloadSize { m: 123, n: 115 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_3,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 222 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                            (8, MaxPayloadSize { m: 58, n: 50 }),
+                            (9, MaxPayloadSize { m: 123, n: 115 }),
+                            (10, MaxPayloadSize { m: 58, n: 50 }),
+                            (11, MaxPayloadSize { m: 123, n: 115 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_4,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 222 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                            (8, MaxPayloadSize { m: 58, n: 50 }),
+                            (9, MaxPayloadSize { m: 123, n: 115 }),
+                            (10, MaxPayloadSize { m: 58, n: 50 }),
+                            (11, MaxPayloadSize { m: 123, n: 115 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::Latest, // RP002_1_0_5
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 222 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                            (8, MaxPayloadSize { m: 58, n: 50 }),
+                            (9, MaxPayloadSize { m: 123, n: 115 }),
+                            (10, MaxPayloadSize { m: 58, n: 50 }),
+                            (11, MaxPayloadSize { m: 123, n: 115 }),
+                            (12, MaxPayloadSize { m: 250, n: 242 }),
+                            (13, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+    }
+}
+
+pub fn in865(repeater_compatible: bool) -> MaxPayloadSizeTable {
+    match repeater_compatible {
+        true => [
+            (
+                MacVersion::LORAWAN_1_0_2,
+                [(
+                    Revision::Latest, // B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 230, n: 222 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_3,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 230, n: 222 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_1_0,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 230, n: 222 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [
+                    (
+                        Revision::RP002_1_0_0,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_1,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_2,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_3,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_4,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::Latest, // RP002-1.0.5
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+        false => [
+            (
+                MacVersion::LORAWAN_1_0_2,
+                [(
+                    Revision::Latest, // B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_3,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_1_0,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [
+                    (
+                        Revision::RP002_1_0_0,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_1,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_2,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_3,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_4,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::Latest, // RP002-1.0.5
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                            (12, MaxPayloadSize { m: 250, n: 242 }),
+                            (13, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+    }
+}
+
+pub fn ism2400(repeater_compatible: bool) -> MaxPayloadSizeTable {
+    match repeater_compatible {
+        true => [(
+            MacVersion::Latest,
+            [(
+                Revision::Latest,
+                [
+                    (0, MaxPayloadSize { m: 59, n: 51 }),
+                    (1, MaxPayloadSize { m: 123, n: 115 }),
+                    (2, MaxPayloadSize { m: 228, n: 220 }),
+                    (3, MaxPayloadSize { m: 228, n: 220 }),
+                    (4, MaxPayloadSize { m: 228, n: 220 }),
+                    (5, MaxPayloadSize { m: 228, n: 220 }),
+                    (6, MaxPayloadSize { m: 228, n: 220 }),
+                    (7, MaxPayloadSize { m: 228, n: 220 }),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            )]
+            .iter()
+            .cloned()
+            .collect(),
+        )]
+        .iter()
+        .cloned()
+        .collect(),
+        false => [(
+            MacVersion::Latest,
+            [(
+                Revision::Latest,
+                [
+                    (0, MaxPayloadSize { m: 59, n: 51 }),
+                    (1, MaxPayloadSize { m: 123, n: 115 }),
+                    (2, MaxPayloadSize { m: 248, n: 240 }),
+                    (3, MaxPayloadSize { m: 248, n: 240 }),
+                    (4, MaxPayloadSize { m: 248, n: 240 }),
+                    (5, MaxPayloadSize { m: 248, n: 240 }),
+                    (6, MaxPayloadSize { m: 248, n: 240 }),
+                    (7, MaxPayloadSize { m: 248, n: 240 }),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            )]
+            .iter()
+            .cloned()
+            .collect(),
+        )]
+        .iter()
+        .cloned()
+        .collect(),
+    }
+}
+
+pub fn kr920(repeater_compatible: bool) -> MaxPayloadSizeTable {
+    match repeater_compatible {
+        true => [
+            (
+                MacVersion::LORAWAN_1_0_2,
+                [(
+                    Revision::Latest, // B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_3,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_1_0,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [
+                    (
+                        Revision::RP002_1_0_0,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_1,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_2,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_3,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_4,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::Latest, // RP002-1.0.5
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+        false => [
+            (
+                MacVersion::LORAWAN_1_0_2,
+                [(
+                    Revision::Latest, // B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_3,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_1_0,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [
+                    (
+                        Revision::RP002_1_0_0,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_1,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_2,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_3,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_4,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::Latest, // RP002-1.0.5
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (12, MaxPayloadSize { m: 250, n: 242 }),
+                            (13, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+    }
+}
+
+pub fn ru864(repeater_compatible: bool) -> MaxPayloadSizeTable {
+    match repeater_compatible {
+        true => [
+            (
+                MacVersion::LORAWAN_1_0_3,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 230, n: 222 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_1_0, // B
+                [(
+                    Revision::Latest,
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 230, n: 222 }),
+                        (5, MaxPayloadSize { m: 230, n: 222 }),
+                        (6, MaxPayloadSize { m: 230, n: 222 }),
+                        (7, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [
+                    (
+                        Revision::RP002_1_0_0,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_1,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_2,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_3,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_4,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::Latest, // RP002-1.0.5
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 230, n: 222 }),
+                            (5, MaxPayloadSize { m: 230, n: 222 }),
+                            (6, MaxPayloadSize { m: 230, n: 222 }),
+                            (7, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+        false => [
+            (
+                MacVersion::LORAWAN_1_0_3,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_1_0, // B
+                [(
+                    Revision::Latest,
+                    [
+                        (0, MaxPayloadSize { m: 59, n: 51 }),
+                        (1, MaxPayloadSize { m: 59, n: 51 }),
+                        (2, MaxPayloadSize { m: 59, n: 51 }),
+                        (3, MaxPayloadSize { m: 123, n: 115 }),
+                        (4, MaxPayloadSize { m: 250, n: 242 }),
+                        (5, MaxPayloadSize { m: 250, n: 242 }),
+                        (6, MaxPayloadSize { m: 250, n: 242 }),
+                        (7, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [
+                    (
+                        Revision::RP002_1_0_0,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_1,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_2,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_3,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_4,
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::Latest, // RP002-1.0.5
+                        [
+                            (0, MaxPayloadSize { m: 59, n: 51 }),
+                            (1, MaxPayloadSize { m: 59, n: 51 }),
+                            (2, MaxPayloadSize { m: 59, n: 51 }),
+                            (3, MaxPayloadSize { m: 123, n: 115 }),
+                            (4, MaxPayloadSize { m: 250, n: 242 }),
+                            (5, MaxPayloadSize { m: 250, n: 242 }),
+                            (6, MaxPayloadSize { m: 250, n: 242 }),
+                            (7, MaxPayloadSize { m: 250, n: 242 }),
+                            (12, MaxPayloadSize { m: 250, n: 242 }),
+                            (13, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+    }
+}
+
+pub fn us915(repeater_compatible: bool) -> MaxPayloadSizeTable {
+    match repeater_compatible {
+        true => [
+            (
+                MacVersion::LORAWAN_1_0_0,
+                [(
+                    Revision::Latest,
+                    [
+                        (8, MaxPayloadSize { m: 41, n: 33 }),
+                        (9, MaxPayloadSize { m: 117, n: 109 }),
+                        (10, MaxPayloadSize { m: 230, n: 222 }),
+                        (11, MaxPayloadSize { m: 230, n: 222 }),
+                        (12, MaxPayloadSize { m: 230, n: 222 }),
+                        (13, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_1,
+                [(
+                    Revision::Latest,
+                    [
+                        (8, MaxPayloadSize { m: 41, n: 33 }),
+                        (9, MaxPayloadSize { m: 117, n: 109 }),
+                        (10, MaxPayloadSize { m: 230, n: 222 }),
+                        (11, MaxPayloadSize { m: 230, n: 222 }),
+                        (12, MaxPayloadSize { m: 230, n: 222 }),
+                        (13, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_2,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (8, MaxPayloadSize { m: 41, n: 33 }),
+                        (9, MaxPayloadSize { m: 117, n: 109 }),
+                        (10, MaxPayloadSize { m: 230, n: 222 }),
+                        (11, MaxPayloadSize { m: 230, n: 222 }),
+                        (12, MaxPayloadSize { m: 230, n: 222 }),
+                        (13, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_3,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (8, MaxPayloadSize { m: 41, n: 33 }),
+                        (9, MaxPayloadSize { m: 117, n: 109 }),
+                        (10, MaxPayloadSize { m: 230, n: 222 }),
+                        (11, MaxPayloadSize { m: 230, n: 222 }),
+                        (12, MaxPayloadSize { m: 230, n: 222 }),
+                        (13, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_1_0,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (8, MaxPayloadSize { m: 41, n: 33 }),
+                        (9, MaxPayloadSize { m: 117, n: 109 }),
+                        (10, MaxPayloadSize { m: 230, n: 222 }),
+                        (11, MaxPayloadSize { m: 230, n: 222 }),
+                        (12, MaxPayloadSize { m: 230, n: 222 }),
+                        (13, MaxPayloadSize { m: 230, n: 222 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [
+                    (
+                        Revision::RP002_1_0_0,
+                        [
+                            (8, MaxPayloadSize { m: 41, n: 33 }),
+                            (9, MaxPayloadSize { m: 117, n: 109 }),
+                            (10, MaxPayloadSize { m: 230, n: 222 }),
+                            (11, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_1,
+                        [
+                            (8, MaxPayloadSize { m: 41, n: 33 }),
+                            (9, MaxPayloadSize { m: 117, n: 109 }),
+                            (10, MaxPayloadSize { m: 230, n: 222 }),
+                            (11, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_2,
+                        [
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 230, n: 222 }),
+                            (11, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_3,
+                        [
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 230, n: 222 }),
+                            (11, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_4,
+                        [
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 230, n: 222 }),
+                            (11, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::Latest, // RP002-1.0.5
+                        [
+                            (0, MaxPayloadSize { m: 230, n: 222 }),
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 230, n: 222 }),
+                            (11, MaxPayloadSize { m: 230, n: 222 }),
+                            (12, MaxPayloadSize { m: 230, n: 222 }),
+                            (13, MaxPayloadSize { m: 230, n: 222 }),
+                            (14, MaxPayloadSize { m: 230, n: 222 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+        false => [
+            (
+                MacVersion::LORAWAN_1_0_0,
+                [(
+                    Revision::Latest,
+                    [
+                        (8, MaxPayloadSize { m: 61, n: 53 }),
+                        (9, MaxPayloadSize { m: 137, n: 129 }),
+                        (10, MaxPayloadSize { m: 250, n: 242 }),
+                        (11, MaxPayloadSize { m: 250, n: 242 }),
+                        (12, MaxPayloadSize { m: 250, n: 242 }),
+                        (13, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_1,
+                [(
+                    Revision::Latest,
+                    [
+                        (8, MaxPayloadSize { m: 61, n: 53 }),
+                        (9, MaxPayloadSize { m: 137, n: 129 }),
+                        (10, MaxPayloadSize { m: 250, n: 242 }),
+                        (11, MaxPayloadSize { m: 250, n: 242 }),
+                        (12, MaxPayloadSize { m: 250, n: 242 }),
+                        (13, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_2, // A & B
+                [(
+                    Revision::Latest,
+                    [
+                        (8, MaxPayloadSize { m: 61, n: 53 }),
+                        (9, MaxPayloadSize { m: 137, n: 129 }),
+                        (10, MaxPayloadSize { m: 250, n: 242 }),
+                        (11, MaxPayloadSize { m: 250, n: 242 }),
+                        (12, MaxPayloadSize { m: 250, n: 242 }),
+                        (13, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_0_3,
+                [(
+                    Revision::Latest, // A
+                    [
+                        (8, MaxPayloadSize { m: 61, n: 53 }),
+                        (9, MaxPayloadSize { m: 137, n: 129 }),
+                        (10, MaxPayloadSize { m: 250, n: 242 }),
+                        (11, MaxPayloadSize { m: 250, n: 242 }),
+                        (12, MaxPayloadSize { m: 250, n: 242 }),
+                        (13, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::LORAWAN_1_1_0,
+                [(
+                    Revision::Latest, // A & B
+                    [
+                        (8, MaxPayloadSize { m: 61, n: 53 }),
+                        (9, MaxPayloadSize { m: 137, n: 129 }),
+                        (10, MaxPayloadSize { m: 250, n: 242 }),
+                        (11, MaxPayloadSize { m: 250, n: 242 }),
+                        (12, MaxPayloadSize { m: 250, n: 242 }),
+                        (13, MaxPayloadSize { m: 250, n: 242 }),
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect(),
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            (
+                MacVersion::Latest,
+                [
+                    (
+                        Revision::RP002_1_0_0,
+                        [
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 250, n: 242 }),
+                            (11, MaxPayloadSize { m: 250, n: 242 }),
+                            (12, MaxPayloadSize { m: 250, n: 242 }),
+                            (13, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_1,
+                        [
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 250, n: 242 }),
+                            (11, MaxPayloadSize { m: 250, n: 242 }),
+                            (12, MaxPayloadSize { m: 250, n: 242 }),
+                            (13, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_2,
+                        [
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 250, n: 242 }),
+                            (11, MaxPayloadSize { m: 250, n: 242 }),
+                            (12, MaxPayloadSize { m: 250, n: 242 }),
+                            (13, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_3,
+                        [
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 250, n: 242 }),
+                            (11, MaxPayloadSize { m: 250, n: 242 }),
+                            (12, MaxPayloadSize { m: 250, n: 242 }),
+                            (13, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::RP002_1_0_4,
+                        [
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 250, n: 242 }),
+                            (11, MaxPayloadSize { m: 250, n: 242 }),
+                            (12, MaxPayloadSize { m: 250, n: 242 }),
+                            (13, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                    (
+                        Revision::Latest, // RP002-1.0.5
+                        [
+                            (0, MaxPayloadSize { m: 250, n: 242 }),
+                            (8, MaxPayloadSize { m: 61, n: 53 }),
+                            (9, MaxPayloadSize { m: 137, n: 129 }),
+                            (10, MaxPayloadSize { m: 250, n: 242 }),
+                            (11, MaxPayloadSize { m: 250, n: 242 }),
+                            (12, MaxPayloadSize { m: 250, n: 242 }),
+                            (13, MaxPayloadSize { m: 250, n: 242 }),
+                            (14, MaxPayloadSize { m: 250, n: 242 }),
+                        ]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+    }
+}
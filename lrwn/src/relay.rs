@@ -4,6 +4,7 @@ use serde::Serialize;
 
 use crate::helpers::{decode_freq, encode_freq};
 use crate::phy_payload::PhyPayload;
+use crate::prelude::*;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
@@ -25,6 +25,7 @@ function TenantForm(props: IProps) {
     tenant.setMaxDeviceCount(v.maxDeviceCount);
     tenant.setPrivateGatewaysUp(v.privateGatewaysUp);
     tenant.setPrivateGatewaysDown(v.privateGatewaysDown);
+    tenant.setNetId(v.netId);
 
     // tags
     for (const elm of v.tagsMap) {
@@ -166,6 +167,19 @@ function TenantForm(props: IProps) {
               subset of the network available DevAddr pool.
             </p>
           </Card>
+          <Form.Item
+            label="NetID"
+            name="netId"
+            tooltip="Optional NetID to bind the tenant to. This must be the network NetID or one of the secondary NetIDs. If not set, the network NetID will be used."
+            rules={[
+              {
+                pattern: new RegExp(/^[A-Fa-f0-9]{6}$/),
+                message: "Please enter a valid NetID",
+              },
+            ]}
+          >
+            <Input className="input-code" maxLength={6} placeholder="000000" disabled={props.disabled} />
+          </Form.Item>
           <Form.List name="devAddrPrefixesList">
             {(fields, { add, remove }) => (
               <>